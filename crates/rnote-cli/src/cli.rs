@@ -1,16 +1,18 @@
 // Imports
-use crate::{export, import, test, thumbnail};
+use crate::{export, import, inspect, merge, test, thumbnail, upgrade};
 use anyhow::Context;
 use clap::Parser;
 use rnote_compose::SplitOrder;
 use rnote_engine::SelectionCollision;
 use rnote_engine::engine::export::{
-    DocExportFormat, DocPagesExportFormat, DocPagesExportPrefs, SelectionExportFormat,
-    SelectionExportPrefs,
+    DocExportFormat, DocPagesExportFormat, DocPagesExportPrefs, DocReplayExportFormat,
+    DocReplayExportPrefs, PageSelection, SelectionExportFormat, SelectionExportPrefs,
 };
-use rnote_engine::engine::import::XoppImportPrefs;
+use rnote_engine::engine::import::{DocMergeMode, XoppImportPrefs};
+use rnote_engine::fileformats::rnoteformat::CompressionMethod;
 use smol::fs::File;
 use smol::io::{AsyncReadExt, AsyncWriteExt};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
@@ -33,7 +35,7 @@ pub(crate) enum Command {
         rnote_files: Vec<PathBuf>,
     },
     /// Imports the specified input file and saves it as a rnote save file.{n}
-    /// Currently only `.xopp` files can be imported.
+    /// `.pdf` and `.xopp` files can be imported.
     Import {
         /// The rnote save file.
         rnote_file: PathBuf,
@@ -43,13 +45,59 @@ pub(crate) enum Command {
         /// When importing a .xopp file, the import dpi can be specified.
         #[arg(long, default_value_t = XoppImportPrefs::default().dpi)]
         xopp_dpi: f64,
+        /// When importing a .pdf file, the pages that get imported in the given order, for example "3, 1-2, 7-".
+        /// All pages are imported if not set.
+        #[arg(long)]
+        pdf_pages: Option<PageSelection>,
+    },
+    /// Upgrades the specified rnote files in place to the current format version.{n}
+    /// The files are validated and repaired, orphaned data is removed. Files are only written when something
+    /// changed.
+    Upgrade {
+        /// The rnote files.
+        #[arg(required = true)]
+        rnote_files: Vec<PathBuf>,
+        /// Recompress the files with the given compression method. Keeps the current method by default.
+        #[arg(long)]
+        compression: Option<CompressionMethod>,
+        /// Recompress the files with the given compression level.
+        #[arg(long)]
+        compression_level: Option<i32>,
+        /// Only report what would be changed, without writing the files.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        dry_run: bool,
+    },
+    /// Prints information about the specified rnote files as JSON.{n}
+    /// Includes the format version, compression, document layout, page count, stroke counts per type and layer
+    /// and the embedded attachments.
+    Inspect {
+        /// The rnote files.
+        #[arg(required = true)]
+        rnote_files: Vec<PathBuf>,
+    },
+    /// Merges the specified rnote files into a single rnote save file.{n}
+    /// The files are merged in the given order, the document settings of the first file are kept.
+    Merge {
+        /// The rnote files that are merged.
+        #[arg(required = true, num_args = 2..)]
+        rnote_files: Vec<PathBuf>,
+        /// The merged rnote save file.
+        #[arg(short = 'o', long)]
+        output_file: PathBuf,
+        /// Whether the documents are appended page-wise or stacked on layers.
+        #[arg(short = 'm', long, default_value = "pages")]
+        mode: DocMergeMode,
+        /// The action that will be performed if the output file already exists.
+        #[arg(long, default_value = "ask")]
+        on_conflict: OnConflict,
     },
     /// Exports the Rnote file(s) and saves it/them in the desired format.{n}
     /// See sub-commands for usage.
     Export {
         #[command(subcommand)]
         export_command: ExportCommand,
-        /// The rnote save file.
+        /// The rnote save file(s).{n}
+        /// Directories are searched recursively for rnote files and glob patterns like "notes/*.rnote" are expanded.
         #[arg(global = true)]
         rnote_files: Vec<PathBuf>,
         /// The action that will be performed if the to be exported file(s) already exist(s).
@@ -68,14 +116,37 @@ pub(crate) enum Command {
         /// Opens output folder when using "doc-pages" sub-command.
         #[arg(long, action = clap::ArgAction::SetTrue, global = true)]
         open: bool,
+        /// The number of files that are exported in parallel. Defaults to the available parallelism.
+        #[arg(short = 'j', long, global = true)]
+        jobs: Option<usize>,
+        /// The directory the exported files are written to when using "--output-format", or the directory the pages
+        /// get exported to when using the "doc-pages" sub-command.{n}
+        /// "{dir}" is replaced with the directory and "{stem}" with the file stem of the rnote file.{n}
+        /// The directory is created if it does not exist yet.
+        #[arg(long, global = true)]
+        output_dir_template: Option<String>,
     },
-    /// Generate rnote thumbail from a given file
+    /// Generate rnote thumbail from a given file.{n}
+    /// Renders the first page by default. The image format is recognized from the file extension of the output
+    /// path.
     Thumbnail {
         /// Input rnote file
         rnote_file: PathBuf,
-        /// Size of the thumbnail in bits
+        /// The maximum width and height of the thumbnail in pixels.
         #[arg(short, long, default_value_t = 256)]
         size: u32,
+        /// The page that is rendered, starting at 1.
+        #[arg(short, long, default_value_t = 1)]
+        page: usize,
+        /// Render a rectangular area of the document instead of a page.
+        #[arg(
+            long,
+            num_args = 4,
+            value_names = ["X", "Y", "WIDTH", "HEIGHT"],
+            allow_negative_numbers = true,
+            conflicts_with = "page"
+        )]
+        region: Option<Vec<f64>>,
         /// Output path of the thumbnail
         output: PathBuf,
     },
@@ -114,16 +185,29 @@ pub(crate) enum ExportCommand {
         /// pages.
         #[arg(long, default_value_t = Default::default())]
         page_order: SplitOrder,
+        /// The pages that get exported, for example "1-3, 7, 12-". All pages are exported if not set.
+        #[arg(long)]
+        pages: Option<PageSelection>,
     },
     /// Export each page of the document(s) individually.{n}
-    /// Both "--output-dir" and "--output-format" need to be set.
+    /// Either "--output-dir" or "--output-dir-template" and "--output-format" need to be set.
     DocPages {
-        /// The directory the pages get exported to.
+        /// The directory the pages get exported to. It is created if it does not exist yet.{n}
+        /// "{dir}" is replaced with the directory and "{stem}" with the file stem of the rnote file.
         #[arg(short = 'o', long)]
-        output_dir: PathBuf,
+        output_dir: Option<PathBuf>,
         /// The file name stem when naming the to be exported pages files.
         #[arg(short = 's', long)]
         output_file_stem: Option<String>,
+        /// The naming pattern of the page files, without the extension.{n}
+        /// "{stem}" is replaced with the file stem, "{page}" with the page number and "{pages}" with the total
+        /// amount of pages.
+        #[arg(
+            long,
+            default_value = DocPagesExportPrefs::PAGE_FILES_NAMING_PATTERN_DEFAULT,
+            value_parser = parse_page_files_naming_pattern
+        )]
+        file_name_pattern: String,
         /// The export output format.
         #[arg(short = 'f', long)]
         export_format: DocPagesExportFormat,
@@ -131,12 +215,36 @@ pub(crate) enum ExportCommand {
         /// pages.
         #[arg(long, default_value_t = Default::default())]
         page_order: SplitOrder,
+        /// The pages that get exported, for example "1-3, 7, 12-". All pages are exported if not set.
+        #[arg(long)]
+        pages: Option<PageSelection>,
         /// The bitmap scale-factor in relation to the actual size on the document.
         #[arg(long, default_value_t = DocPagesExportPrefs::default().bitmap_scalefactor)]
         bitmap_scalefactor: f64,
+        /// The bitmap DPI. Takes precedence over "--bitmap-scalefactor".
+        #[arg(long)]
+        bitmap_dpi: Option<f64>,
+        /// The maximum width and height of the generated image(s) in pixels. Larger images get downscaled to fit,
+        /// or split into tiles when using "--bitmap-tiling".
+        #[arg(long, default_value_t = DocPagesExportPrefs::default().bitmap_max_dimension)]
+        bitmap_max_dimension: u32,
+        /// Split pages exceeding the maximum size into tiles instead of downscaling them.{n}
+        /// The tiles are written together with a JSON manifest describing their arrangement.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        bitmap_tiling: bool,
+        /// The overlap between neighbouring tiles in pixels.
+        #[arg(long, default_value_t = DocPagesExportPrefs::default().bitmap_tile_overlap)]
+        bitmap_tile_overlap: u32,
+        /// Leave the background color transparent when Png or Tiff is used as export format.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        transparent_background: bool,
         /// The quality of the generated image(s) when Jpeg is used as export format.
         #[arg(long, default_value_t = DocPagesExportPrefs::default().jpeg_quality)]
         jpeg_quality: u8,
+        /// Export all pages into a single multi-page file named after the file stem when Tiff is used as export
+        /// format.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        tiff_multipage: bool,
     },
     /// Export a selection in a document.{n}
     /// When using "--output-file", only a single input file can be specified.{n}
@@ -155,6 +263,24 @@ pub(crate) enum ExportCommand {
         /// The bitmap scale-factor in relation to the actual size on the document.
         #[arg(long, default_value_t = SelectionExportPrefs::default().bitmap_scalefactor, global = true)]
         bitmap_scalefactor: f64,
+        /// The bitmap DPI. Takes precedence over "--bitmap-scalefactor".
+        #[arg(long, global = true)]
+        bitmap_dpi: Option<f64>,
+        /// The maximum width and height of the generated image in pixels. Larger images get downscaled to fit,
+        /// or split into tiles when using "--bitmap-tiling".
+        #[arg(long, default_value_t = SelectionExportPrefs::default().bitmap_max_dimension, global = true)]
+        bitmap_max_dimension: u32,
+        /// Split a selection exceeding the maximum size into tiles instead of downscaling it.{n}
+        /// The tiles are written next to the output file together with a JSON manifest describing their
+        /// arrangement.
+        #[arg(long, action = clap::ArgAction::SetTrue, global = true)]
+        bitmap_tiling: bool,
+        /// The overlap between neighbouring tiles in pixels.
+        #[arg(long, default_value_t = SelectionExportPrefs::default().bitmap_tile_overlap, global = true)]
+        bitmap_tile_overlap: u32,
+        /// Leave the background color transparent when Png is used as export format.
+        #[arg(long, action = clap::ArgAction::SetTrue, global = true)]
+        transparent_background: bool,
         /// The quality of the generated image(s) when Jpeg is used as export format.
         #[arg(long, default_value_t = SelectionExportPrefs::default().jpeg_quality, global = true)]
         jpeg_quality: u8,
@@ -162,6 +288,30 @@ pub(crate) enum ExportCommand {
         #[arg(long, default_value_t = SelectionExportPrefs::default().margin, global = true)]
         margin: f64,
     },
+    /// Export a replay of the document being drawn, stroke by stroke in the order they were created.{n}
+    /// When using "--output-file", only a single input file can be specified.{n}
+    /// When using "--output-format", the file name and path of the rnote file is used with the extension changed.{n}
+    /// "--output-file and "--output-format" are mutually exclusive and specifying one of them is required.
+    Replay {
+        #[command(flatten)]
+        file_args: FileArgs<DocReplayExportFormat>,
+        /// The amount of strokes that get added with every frame.{n}
+        /// Gets increased when the replay would otherwise exceed the maximum amount of frames.
+        #[arg(long, default_value_t = DocReplayExportPrefs::default().strokes_per_frame)]
+        strokes_per_frame: usize,
+        /// The duration every frame is shown, in milliseconds.
+        #[arg(long, default_value_t = DocReplayExportPrefs::default().frame_duration_ms)]
+        frame_duration: u32,
+        /// The duration the last frame is shown before the replay restarts, in milliseconds.
+        #[arg(long, default_value_t = DocReplayExportPrefs::default().final_frame_duration_ms)]
+        final_frame_duration: u32,
+        /// The bitmap scale-factor in relation to the actual size on the document.
+        #[arg(long, default_value_t = DocReplayExportPrefs::default().bitmap_scalefactor)]
+        bitmap_scalefactor: f64,
+        /// The maximum width and height of the frames in pixels. Larger frames get downscaled to fit.
+        #[arg(long, default_value_t = DocReplayExportPrefs::default().bitmap_max_dimension)]
+        bitmap_max_dimension: u32,
+    },
 }
 
 #[derive(clap::Subcommand, Debug, Clone, Copy)]
@@ -225,11 +375,36 @@ pub(crate) async fn run() -> anyhow::Result<()> {
             rnote_file,
             input_file,
             xopp_dpi,
+            pdf_pages,
         } => {
             println!("Importing..");
-            import::run_import(&rnote_file, &input_file, xopp_dpi).await?;
+            import::run_import(&rnote_file, &input_file, xopp_dpi, pdf_pages).await?;
             println!("Import finished!");
         }
+        Command::Upgrade {
+            rnote_files,
+            compression,
+            compression_level,
+            dry_run,
+        } => {
+            println!("Upgrading..");
+            upgrade::run_upgrade(&rnote_files, compression, compression_level, dry_run).await?;
+            println!("Upgrade finished!");
+        }
+        Command::Inspect { rnote_files } => {
+            // Only the JSON is printed, so that the output can be piped into other tools
+            inspect::run_inspect(&rnote_files).await?;
+        }
+        Command::Merge {
+            rnote_files,
+            output_file,
+            mode,
+            on_conflict,
+        } => {
+            println!("Merging..");
+            merge::run_merge(&rnote_files, &output_file, mode, on_conflict).await?;
+            println!("Merge finished!");
+        }
         Command::Export {
             rnote_files,
             no_background,
@@ -237,6 +412,8 @@ pub(crate) async fn run() -> anyhow::Result<()> {
             optimize_printing,
             on_conflict,
             open,
+            jobs,
+            output_dir_template,
             export_command,
         } => {
             println!("Exporting..");
@@ -247,6 +424,8 @@ pub(crate) async fn run() -> anyhow::Result<()> {
                 optimize_printing,
                 on_conflict,
                 open,
+                jobs,
+                output_dir_template,
                 export_command,
             )
             .await?;
@@ -255,10 +434,19 @@ pub(crate) async fn run() -> anyhow::Result<()> {
         Command::Thumbnail {
             rnote_file,
             size,
+            page,
+            region,
             output,
         } => {
             println!("Thumbnail...");
-            thumbnail::run_thumbnail(rnote_file, size, output).await?;
+            let area = match region.as_deref() {
+                Some(&[x, y, width, height]) => thumbnail::ThumbnailArea::Region {
+                    mins: na::vector![x, y],
+                    size: na::vector![width, height],
+                },
+                _ => thumbnail::ThumbnailArea::Page(page),
+            };
+            thumbnail::run_thumbnail(rnote_file, size, area, output).await?;
         }
     }
 
@@ -272,6 +460,81 @@ pub(crate) fn new_progressbar(message: String) -> indicatif::ProgressBar {
     pb
 }
 
+pub(crate) fn new_batch_progressbar(len: u64) -> indicatif::ProgressBar {
+    let pb = indicatif::ProgressBar::new(len).with_style(
+        indicatif::ProgressStyle::with_template("{spinner} [{bar:40}] {pos}/{len} {wide_msg}")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    pb.set_draw_target(indicatif::ProgressDrawTarget::stdout());
+    pb.enable_steady_tick(Duration::from_millis(8));
+    pb
+}
+
+/// Prints the message above the progressbar, or directly to stdout when the progressbar is hidden.
+pub(crate) fn progressbar_println(pb: &indicatif::ProgressBar, msg: impl AsRef<str>) {
+    if pb.is_hidden() {
+        println!("{}", msg.as_ref());
+    } else {
+        pb.println(msg);
+    }
+}
+
+/// Expands the given input paths to the rnote files they refer to.
+///
+/// Directories are searched recursively for files with the "rnote" extension and glob patterns are expanded.
+/// All other paths are passed through as-is, they are validated when they are processed.
+pub(crate) fn collect_rnote_files(inputs: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut rnote_files = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        let input_str = input.to_string_lossy();
+        if input.is_dir() {
+            collect_rnote_files_in_dir(input, &mut rnote_files)?;
+        } else if !input.exists() && input_str.contains(['*', '?', '[']) {
+            let matches = glob::glob(&input_str)
+                .with_context(|| format!("Invalid glob pattern \"{input_str}\"."))?
+                .collect::<Result<Vec<PathBuf>, glob::GlobError>>()?;
+            if matches.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "No files are matching the glob pattern \"{input_str}\"."
+                ));
+            }
+            rnote_files.extend(matches.into_iter().filter(|p| p.is_file()));
+        } else {
+            rnote_files.push(input.clone());
+        }
+    }
+    // Files might be matched multiple times by overlapping inputs
+    let mut seen = HashSet::new();
+    rnote_files.retain(|f| seen.insert(f.clone()));
+    Ok(rnote_files)
+}
+
+fn collect_rnote_files_in_dir(dir: &Path, rnote_files: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    let mut entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory \"{}\".", dir.display()))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<PathBuf>, _>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect_rnote_files_in_dir(&path, rnote_files)?;
+        } else if path.extension().is_some_and(|ext| ext == "rnote") {
+            rnote_files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn parse_page_files_naming_pattern(pattern: &str) -> Result<String, String> {
+    if !DocPagesExportPrefs::page_files_naming_pattern_valid(pattern) {
+        return Err(String::from(
+            "the pattern must contain \"{page}\", else all page files overwrite each other",
+        ));
+    }
+    Ok(pattern.to_string())
+}
+
 pub(crate) async fn read_bytes_from_file(file_path: impl AsRef<Path>) -> anyhow::Result<Vec<u8>> {
     let mut bytes = vec![];
     let mut fh = File::open(file_path).await?;
@@ -289,6 +552,33 @@ pub(crate) async fn create_overwrite_file_w_bytes(
     Ok(())
 }
 
+/// Replaces the file with the bytes, without leaving it truncated or partially written if writing fails.
+///
+/// The bytes are written to a temporary file next to it first, which is then renamed over the original.
+pub(crate) async fn replace_file_w_bytes(
+    file_path: impl AsRef<Path>,
+    bytes: &[u8],
+) -> anyhow::Result<()> {
+    let file_path = file_path.as_ref();
+    let Some(file_name) = file_path.file_name() else {
+        return Err(anyhow::anyhow!(
+            "Failed to get filename from \"{}\".",
+            file_path.display()
+        ));
+    };
+    let tmp_file_path = file_path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+    let res = async {
+        create_overwrite_file_w_bytes(&tmp_file_path, bytes).await?;
+        smol::fs::rename(&tmp_file_path, file_path).await?;
+        anyhow::Ok(())
+    }
+    .await;
+    if res.is_err() {
+        let _ = smol::fs::remove_file(&tmp_file_path).await;
+    }
+    res.with_context(|| format!("Failed to replace file \"{}\".", file_path.display()))
+}
+
 pub(crate) fn open_file_default_app(file_path: impl AsRef<Path>) -> anyhow::Result<()> {
     open::that_detached(file_path.as_ref()).with_context(|| {
         format!(
@@ -2,14 +2,17 @@
 use crate::cli::{self, OnConflict};
 use crate::validators;
 use anyhow::Context;
+use futures::StreamExt;
 use p2d::bounding_volume::Aabb;
 use rnote_compose::SplitOrder;
 use rnote_engine::engine::export::{
     DocExportFormat, DocExportPrefs, DocPagesExportFormat, DocPagesExportPrefs,
-    SelectionExportFormat, SelectionExportPrefs,
+    DocReplayExportFormat, DocReplayExportPrefs, PageSelection, SelectionExportFormat,
+    SelectionExportPrefs, TiledBitmap,
 };
 use rnote_engine::engine::{EngineConfigShared, EngineSnapshot};
 use rnote_engine::{Engine, SelectionCollision};
+use std::cell::Cell;
 use std::io::{self, IsTerminal};
 use std::path::{Path, PathBuf};
 
@@ -21,8 +24,11 @@ pub(crate) async fn run_export(
     optimize_printing: bool,
     on_conflict: OnConflict,
     open: bool,
+    jobs: Option<usize>,
+    output_dir_template: Option<String>,
     export_command: cli::ExportCommand,
 ) -> anyhow::Result<()> {
+    let rnote_files = cli::collect_rnote_files(&rnote_files)?;
     if rnote_files.is_empty() {
         return Err(anyhow::anyhow!(
             "There must be at least one rnote file specified for exporting."
@@ -30,20 +36,34 @@ pub(crate) async fn run_export(
     }
 
     let config = EngineConfigShared::default();
-    let mut engine = Engine::default();
-    let _ = engine.install_config(&config, None);
-    let mut on_conflict_overwrite = None;
+    let on_conflict_overwrite = Cell::new(None);
     let output_file = match &export_command {
         cli::ExportCommand::Doc { file_args, .. } => file_args.output_file.as_ref(),
         cli::ExportCommand::Selection { file_args, .. } => file_args.output_file.as_ref(),
+        cli::ExportCommand::Replay { file_args, .. } => file_args.output_file.as_ref(),
         cli::ExportCommand::DocPages {
-            output_file_stem, ..
+            output_dir,
+            output_file_stem,
+            ..
         } => {
             if rnote_files.len() > 1 && output_file_stem.is_some() {
                 return Err(anyhow::anyhow!(
                     "The option \"--file-stem\" cannot be used when exporting multiple rnote files."
                 ));
             }
+            match (output_dir, &output_dir_template) {
+                (Some(_), Some(_)) => {
+                    return Err(anyhow::anyhow!(
+                        "The options \"--output-dir\" and \"--output-dir-template\" are mutually exclusive."
+                    ));
+                }
+                (None, None) => {
+                    return Err(anyhow::anyhow!(
+                        "Either \"--output-dir\" or \"--output-dir-template\" is required."
+                    ));
+                }
+                _ => {}
+            }
             None
         }
     };
@@ -58,6 +78,11 @@ pub(crate) async fn run_export(
     )?;
 
     match output_file {
+        Some(_) if output_dir_template.is_some() => {
+            return Err(anyhow::anyhow!(
+                "The option \"--output-dir-template\" cannot be used together with \"--output-file\"."
+            ));
+        }
         Some(output_file) => {
             let Some(rnote_file) = rnote_files.first() else {
                 return Err(anyhow::anyhow!(
@@ -69,7 +94,7 @@ pub(crate) async fn run_export(
             let output_file = get_output_file_path(
                 output_file,
                 on_conflict,
-                &mut on_conflict_overwrite,
+                &on_conflict_overwrite,
                 &export_command,
             )?;
             if rnote_files.len() > 1 {
@@ -84,13 +109,15 @@ pub(crate) async fn run_export(
                 "Exporting \"{rnote_file_disp}\" to: \"{output_file_disp}\"."
             ));
 
+            let mut engine = Engine::default();
+            let _ = engine.install_config(&config, None);
             if let Err(e) = export_to_file(
                 &mut engine,
                 rnote_file,
                 output_file,
                 &export_command,
                 on_conflict,
-                &mut on_conflict_overwrite,
+                &on_conflict_overwrite,
                 open,
             )
             .await
@@ -115,21 +142,61 @@ pub(crate) async fn run_export(
         None => {
             let exporting_doc_pages = matches!(export_command, cli::ExportCommand::DocPages { .. });
             let output_ext = file_ext_from_export_command(&config, &export_command);
-            let output_files = rnote_files
-                .iter()
-                .map(|file| {
-                    let mut output = file.clone();
-                    output.set_extension(&output_ext);
-                    output
-                })
-                .collect::<Vec<PathBuf>>();
-
-            for (rnote_file, output_file) in rnote_files.iter().zip(output_files.iter()) {
-                validators::file_has_ext(rnote_file, "rnote")?;
+            let jobs = jobs
+                .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
+                .unwrap_or(1)
+                .max(1);
+
+            // All file conflicts are resolved before the parallel exports start, so that the prompts are not
+            // interleaved. The names of page and tile files are only known after rendering, so when exporting them
+            // the behavior for all conflicts is chosen up-front.
+            let on_conflict = if exporting_doc_pages || exports_tiles(&config, &export_command) {
+                batch_on_conflict(on_conflict, &on_conflict_overwrite)?
+            } else {
+                on_conflict
+            };
+            let mut exports = Vec::with_capacity(rnote_files.len());
+            let mut failed = Vec::new();
+            for rnote_file in rnote_files.iter() {
+                if let Err(e) = validators::file_has_ext(rnote_file, "rnote") {
+                    println!("{e}");
+                    failed.push(rnote_file.clone());
+                    continue;
+                }
+                if let cli::ExportCommand::DocPages { output_dir, .. } = &export_command {
+                    // The pages are exported into the output directory, which is passed on as output file
+                    let template = output_dir_template.clone().unwrap_or_else(|| {
+                        output_dir
+                            .as_ref()
+                            .map(|dir| dir.to_string_lossy().to_string())
+                            .unwrap_or_default()
+                    });
+                    exports.push((
+                        rnote_file.clone(),
+                        expand_output_dir_template(&template, rnote_file),
+                    ));
+                    continue;
+                }
+                let mut initial_output_file = match &output_dir_template {
+                    Some(template) => {
+                        let output_dir = expand_output_dir_template(template, rnote_file);
+                        if let Err(e) = smol::fs::create_dir_all(&output_dir).await {
+                            println!(
+                                "Failed to create output directory \"{}\", Err: {e:?}",
+                                output_dir.display()
+                            );
+                            failed.push(rnote_file.clone());
+                            continue;
+                        }
+                        output_dir.join(rnote_file.file_name().unwrap_or_default())
+                    }
+                    None => rnote_file.clone(),
+                };
+                initial_output_file.set_extension(&output_ext);
                 let output_file = match get_output_file_path(
-                    output_file,
+                    &initial_output_file,
                     on_conflict,
-                    &mut on_conflict_overwrite,
+                    &on_conflict_overwrite,
                     &export_command,
                 ) {
                     Ok(file) => file,
@@ -138,48 +205,68 @@ pub(crate) async fn run_export(
                         continue;
                     }
                 };
-                let rnote_file_disp = rnote_file.display().to_string();
-                let output_file_disp = output_file.display().to_string();
-                let progressbar_msg = match exporting_doc_pages {
-                    true => format!("Exporting \"{rnote_file_disp}\"."),
-                    false => format!("Exporting \"{rnote_file_disp}\" to: \"{output_file_disp}\"."),
-                };
-                let progressbar = cli::new_progressbar(progressbar_msg);
+                exports.push((rnote_file.clone(), output_file));
+            }
 
-                if let Err(e) = export_to_file(
-                    &mut engine,
-                    &rnote_file,
-                    output_file,
-                    &export_command,
-                    on_conflict,
-                    &mut on_conflict_overwrite,
-                    open,
-                )
-                .await
-                {
-                    let abandon_msg = match exporting_doc_pages {
-                        true => format!("Export \"{rnote_file_disp}\" failed, Err: {e:?}"),
-                        false => format!(
-                            "Export \"{rnote_file_disp}\" to: \"{output_file_disp}\" failed, Err: {e:?}"
-                        ),
-                    };
-                    if progressbar.is_hidden() {
-                        println!("{abandon_msg}")
-                    }
-                    progressbar.abandon_with_message(abandon_msg);
-                    return Err(e);
-                } else {
-                    let finish_msg = match exporting_doc_pages {
-                        false => format!(
+            let progressbar = cli::new_batch_progressbar(exports.len() as u64);
+            let export_futures = exports.into_iter().map(|(rnote_file, output_file)| {
+                let config = &config;
+                let export_command = &export_command;
+                let on_conflict_overwrite = &on_conflict_overwrite;
+                let progressbar = &progressbar;
+                async move {
+                    let rnote_file_disp = rnote_file.display().to_string();
+                    let output_file_disp = output_file.display().to_string();
+                    progressbar.set_message(format!("Exporting \"{rnote_file_disp}\"."));
+
+                    // Every export needs its own engine, the config is shared
+                    let mut engine = Engine::default();
+                    let _ = engine.install_config(config, None);
+                    let res = export_to_file(
+                        &mut engine,
+                        &rnote_file,
+                        &output_file,
+                        export_command,
+                        on_conflict,
+                        on_conflict_overwrite,
+                        open,
+                    )
+                    .await;
+                    let msg = match (&res, exporting_doc_pages) {
+                        (Ok(()), true) => format!("Export \"{rnote_file_disp}\" succeeded."),
+                        (Ok(()), false) => format!(
                             "Export \"{rnote_file_disp}\" to: \"{output_file_disp}\" succeeded."
                         ),
-                        true => format!("Export \"{rnote_file_disp}\" succeeded."),
+                        (Err(e), true) => format!("Export \"{rnote_file_disp}\" failed, Err: {e:?}"),
+                        (Err(e), false) => format!(
+                            "Export \"{rnote_file_disp}\" to: \"{output_file_disp}\" failed, Err: {e:?}"
+                        ),
                     };
-                    if progressbar.is_hidden() {
-                        println!("{finish_msg}")
-                    }
-                    progressbar.finish_with_message(finish_msg);
+                    cli::progressbar_println(progressbar, msg);
+                    progressbar.inc(1);
+                    res.err().map(|_| rnote_file)
                 }
+            });
+            failed.extend(
+                futures::stream::iter(export_futures)
+                    .buffer_unordered(jobs)
+                    .filter_map(|failed_file| async move { failed_file })
+                    .collect::<Vec<PathBuf>>()
+                    .await,
+            );
+            progressbar.finish_and_clear();
+
+            if !failed.is_empty() {
+                let failed_list = failed
+                    .iter()
+                    .map(|f| format!("  \"{}\"", f.display()))
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                return Err(anyhow::anyhow!(
+                    "Exporting {} of {} file(s) failed:\n{failed_list}",
+                    failed.len(),
+                    rnote_files.len()
+                ));
             }
         }
     }
@@ -199,6 +286,7 @@ fn apply_export_prefs(
         cli::ExportCommand::Doc {
             file_args,
             page_order,
+            pages,
         } => {
             config.write().export_prefs.doc_export_prefs = create_doc_export_prefs_from_args(
                 output_file,
@@ -207,13 +295,21 @@ fn apply_export_prefs(
                 no_pattern,
                 optimize_printing,
                 *page_order,
+                pages.clone().unwrap_or_default(),
             )?;
         }
         cli::ExportCommand::DocPages {
             export_format: output_format,
             page_order,
+            pages,
             bitmap_scalefactor,
+            bitmap_dpi,
+            bitmap_max_dimension,
+            bitmap_tiling,
+            bitmap_tile_overlap,
+            transparent_background,
             jpeg_quality,
+            tiff_multipage,
             ..
         } => {
             config.write().export_prefs.doc_pages_export_prefs =
@@ -223,13 +319,25 @@ fn apply_export_prefs(
                     no_pattern,
                     optimize_printing,
                     *page_order,
+                    pages.clone().unwrap_or_default(),
                     *bitmap_scalefactor,
+                    *bitmap_dpi,
+                    *bitmap_max_dimension,
+                    *bitmap_tiling,
+                    *bitmap_tile_overlap,
+                    *transparent_background,
                     *jpeg_quality,
+                    *tiff_multipage,
                 )?;
         }
         cli::ExportCommand::Selection {
             file_args,
             bitmap_scalefactor,
+            bitmap_dpi,
+            bitmap_max_dimension,
+            bitmap_tiling,
+            bitmap_tile_overlap,
+            transparent_background,
             jpeg_quality,
             margin,
             ..
@@ -242,10 +350,37 @@ fn apply_export_prefs(
                     no_pattern,
                     optimize_printing,
                     *bitmap_scalefactor,
+                    *bitmap_dpi,
+                    *bitmap_max_dimension,
+                    *bitmap_tiling,
+                    *bitmap_tile_overlap,
+                    *transparent_background,
                     *jpeg_quality,
                     *margin,
                 )?;
         }
+        cli::ExportCommand::Replay {
+            file_args,
+            strokes_per_frame,
+            frame_duration,
+            final_frame_duration,
+            bitmap_scalefactor,
+            bitmap_max_dimension,
+        } => {
+            config.write().export_prefs.doc_replay_export_prefs =
+                create_doc_replay_export_prefs_from_args(
+                    output_file,
+                    file_args.output_format,
+                    no_background,
+                    no_pattern,
+                    optimize_printing,
+                    *strokes_per_frame,
+                    *frame_duration,
+                    *final_frame_duration,
+                    *bitmap_scalefactor,
+                    *bitmap_max_dimension,
+                )?;
+        }
     }
     Ok(())
 }
@@ -273,6 +408,12 @@ fn file_ext_from_export_command(
             .selection_export_prefs
             .export_format
             .file_ext(),
+        cli::ExportCommand::Replay { .. } => config
+            .read()
+            .export_prefs
+            .doc_replay_export_prefs
+            .export_format
+            .file_ext(),
     }
 }
 
@@ -283,6 +424,7 @@ pub(crate) fn create_doc_export_prefs_from_args(
     no_pattern: bool,
     optimize_printing: bool,
     page_order: SplitOrder,
+    page_selection: PageSelection,
 ) -> anyhow::Result<DocExportPrefs> {
     let format = match (output_file, output_format) {
         (Some(file), None) => match file.as_ref().extension().and_then(|ext| ext.to_str()) {
@@ -314,7 +456,10 @@ pub(crate) fn create_doc_export_prefs_from_args(
         with_background: !no_background,
         with_pattern: !no_pattern,
         optimize_printing,
+        invert_colors: false,
         page_order,
+        page_selection,
+        pdf_annotations: false,
     };
 
     Ok(prefs)
@@ -331,14 +476,22 @@ fn doc_export_format_from_ext_str(format: &str) -> anyhow::Result<DocExportForma
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn create_doc_pages_export_prefs_from_args(
     export_format: DocPagesExportFormat,
     no_background: bool,
     no_pattern: bool,
     optimize_printing: bool,
     page_order: SplitOrder,
+    page_selection: PageSelection,
     bitmap_scalefactor: f64,
+    bitmap_dpi: Option<f64>,
+    bitmap_max_dimension: u32,
+    bitmap_tiling: bool,
+    bitmap_tile_overlap: u32,
+    transparent_background: bool,
     jpeg_quality: u8,
+    tiff_multipage: bool,
 ) -> anyhow::Result<DocPagesExportPrefs> {
     Ok(DocPagesExportPrefs {
         export_format,
@@ -348,6 +501,14 @@ pub(crate) fn create_doc_pages_export_prefs_from_args(
         page_order,
         bitmap_scalefactor,
         jpeg_quality,
+        bitmap_dpi,
+        transparent_background,
+        bitmap_max_dimension,
+        bitmap_tiling,
+        bitmap_tile_overlap,
+        page_selection,
+        tiff_multipage,
+        ..Default::default()
     })
 }
 
@@ -359,6 +520,11 @@ pub(crate) fn create_selection_export_prefs_from_args(
     no_pattern: bool,
     optimize_printing: bool,
     bitmap_scalefactor: f64,
+    bitmap_dpi: Option<f64>,
+    bitmap_max_dimension: u32,
+    bitmap_tiling: bool,
+    bitmap_tile_overlap: u32,
+    transparent_background: bool,
     jpeg_quality: u8,
     margin: f64,
 ) -> anyhow::Result<SelectionExportPrefs> {
@@ -395,6 +561,11 @@ pub(crate) fn create_selection_export_prefs_from_args(
         bitmap_scalefactor,
         jpeg_quality,
         margin,
+        bitmap_dpi,
+        transparent_background,
+        bitmap_max_dimension,
+        bitmap_tiling,
+        bitmap_tile_overlap,
     };
 
     Ok(prefs)
@@ -405,16 +576,142 @@ fn get_selection_export_format(format: &str) -> anyhow::Result<SelectionExportFo
         "svg" => Ok(SelectionExportFormat::Svg),
         "png" => Ok(SelectionExportFormat::Png),
         "jpg" | "jpeg" => Ok(SelectionExportFormat::Jpeg),
+        "emf" => Ok(SelectionExportFormat::Emf),
+        "wmf" => Err(anyhow::anyhow!(
+            "Exporting selection as Wmf is not supported, export it as Emf instead."
+        )),
         ext => Err(anyhow::anyhow!(
             "Exporting selection to format with extension \"{ext}\" is not supported."
         )),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_doc_replay_export_prefs_from_args(
+    output_file: Option<impl AsRef<Path>>,
+    output_format: Option<DocReplayExportFormat>,
+    no_background: bool,
+    no_pattern: bool,
+    optimize_printing: bool,
+    strokes_per_frame: usize,
+    frame_duration_ms: u32,
+    final_frame_duration_ms: u32,
+    bitmap_scalefactor: f64,
+    bitmap_max_dimension: u32,
+) -> anyhow::Result<DocReplayExportPrefs> {
+    let format = match (output_file, output_format) {
+        (Some(file), None) => match file.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some(extension) => doc_replay_export_format_from_ext_str(extension)?,
+            None => {
+                return Err(anyhow::anyhow!(
+                    "The output file \"{}\" needs to have a supported extension to determine its file type.",
+                    file.as_ref().display()
+                ));
+            }
+        },
+        (None, Some(out_format)) => out_format,
+        // should be unreachable because the arguments are exclusive (clap conflicts_with)
+        (Some(_), Some(_)) => {
+            return Err(anyhow::anyhow!(
+                "\"--output-file\" and \"--output-format\" are mutually exclusive."
+            ));
+        }
+        // should be unreachable because either --output-file or --output-format is required
+        (None, None) => {
+            return Err(anyhow::anyhow!(
+                "Either \"--output-file\" or \"--output-format\" is required."
+            ));
+        }
+    };
+
+    Ok(DocReplayExportPrefs {
+        export_format: format,
+        with_background: !no_background,
+        with_pattern: !no_pattern,
+        optimize_printing,
+        strokes_per_frame,
+        frame_duration_ms,
+        final_frame_duration_ms,
+        bitmap_scalefactor,
+        bitmap_max_dimension,
+    })
+}
+
+fn doc_replay_export_format_from_ext_str(format: &str) -> anyhow::Result<DocReplayExportFormat> {
+    match format {
+        "gif" => Ok(DocReplayExportFormat::Gif),
+        ext => Err(anyhow::anyhow!(
+            "Exporting document replay to format with extension \"{ext}\" is not supported."
+        )),
+    }
+}
+
+/// Expands the placeholders of an output directory template.
+///
+/// "{dir}" is replaced with the directory and "{stem}" with the file stem of the rnote file.
+pub(crate) fn expand_output_dir_template(template: &str, rnote_file: &Path) -> PathBuf {
+    let dir = rnote_file
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let stem = rnote_file
+        .file_stem()
+        .map(|s| s.to_string_lossy())
+        .unwrap_or_default();
+    PathBuf::from(
+        template
+            .replace("{dir}", &dir.to_string_lossy())
+            .replace("{stem}", &stem),
+    )
+}
+
+/// Whether the export writes tiles, of which the file names are only known after rendering.
+fn exports_tiles(config: &EngineConfigShared, export_command: &cli::ExportCommand) -> bool {
+    let export_prefs = &config.read().export_prefs;
+    match export_command {
+        cli::ExportCommand::DocPages { .. } => export_prefs.doc_pages_export_prefs.exports_tiles(),
+        cli::ExportCommand::Selection { .. } => export_prefs.selection_export_prefs.exports_tiles(),
+        cli::ExportCommand::Doc { .. } | cli::ExportCommand::Replay { .. } => false,
+    }
+}
+
+/// Choose the behavior for all file conflicts of a batch export, prompting the user once if needed.
+fn batch_on_conflict(
+    on_conflict: OnConflict,
+    on_conflict_overwrite: &Cell<Option<OnConflict>>,
+) -> anyhow::Result<OnConflict> {
+    if let Some(o) = on_conflict_overwrite.get() {
+        return Ok(o);
+    }
+    if !matches!(on_conflict, OnConflict::Ask) {
+        return Ok(on_conflict);
+    }
+    if !io::stdout().is_terminal() {
+        return Err(anyhow::anyhow!(
+            "Files might already exist and terminal is not interactive. Option \"--on-conflict\" needs to be supplied."
+        ));
+    }
+    let options = &[OnConflict::Overwrite, OnConflict::Skip, OnConflict::Suffix];
+    match dialoguer::Select::new()
+        .with_prompt("The names of the exported files are only known during the export. If files already exist:")
+        .items(options)
+        .default(0)
+        .interact()
+    {
+        Ok(c) => {
+            on_conflict_overwrite.set(Some(options[c]));
+            Ok(options[c])
+        }
+        Err(e) => Err(anyhow::anyhow!(
+            "Failed to show select prompt, retry or select the behavior with\"--on-conflict\", Err: {e:?}"
+        )),
+    }
+}
+
 pub(crate) fn get_output_file_path(
     initial_output_file: &Path,
     on_conflict: OnConflict,
-    on_conflict_overwrite: &mut Option<OnConflict>,
+    on_conflict_overwrite: &Cell<Option<OnConflict>>,
     export_command: &cli::ExportCommand,
 ) -> anyhow::Result<PathBuf> {
     match export_command {
@@ -435,13 +732,13 @@ pub(crate) fn get_output_file_path(
 pub(crate) fn file_conflict_prompt_action(
     output_file: &Path,
     mut on_conflict: OnConflict,
-    on_conflict_overwrite: &mut Option<OnConflict>,
+    on_conflict_overwrite: &Cell<Option<OnConflict>>,
 ) -> anyhow::Result<Option<PathBuf>> {
     if !output_file.exists() {
         return Ok(None);
     }
-    match on_conflict_overwrite {
-        Some(o) => on_conflict = *o,
+    match on_conflict_overwrite.get() {
+        Some(o) => on_conflict = o,
         None => {
             let options = &[
                 OnConflict::Ask,
@@ -487,15 +784,15 @@ pub(crate) fn file_conflict_prompt_action(
         }
         OnConflict::AlwaysOverwrite => {
             on_conflict = OnConflict::Overwrite;
-            *on_conflict_overwrite = Some(on_conflict);
+            on_conflict_overwrite.set(Some(on_conflict));
         }
         OnConflict::AlwaysSkip => {
             on_conflict = OnConflict::Skip;
-            *on_conflict_overwrite = Some(on_conflict);
+            on_conflict_overwrite.set(Some(on_conflict));
         }
         OnConflict::AlwaysSuffix => {
             on_conflict = OnConflict::Suffix;
-            *on_conflict_overwrite = Some(on_conflict);
+            on_conflict_overwrite.set(Some(on_conflict));
         }
         OnConflict::Overwrite | OnConflict::Skip | OnConflict::Suffix => (),
     }
@@ -532,13 +829,16 @@ pub(crate) fn file_conflict_prompt_action(
     }
 }
 
+/// Export the rnote file to the output file.
+///
+/// When exporting the document pages, the output file is the directory the page files are written to.
 pub(crate) async fn export_to_file(
     engine: &mut Engine,
     rnote_file: impl AsRef<Path>,
     output_file: impl AsRef<Path>,
     export_command: &cli::ExportCommand,
     on_conflict: OnConflict,
-    on_conflict_overwrite: &mut Option<OnConflict>,
+    on_conflict_overwrite: &Cell<Option<OnConflict>>,
     open: bool,
 ) -> anyhow::Result<()> {
     let rnote_bytes = cli::read_bytes_from_file(&rnote_file).await?;
@@ -552,6 +852,51 @@ pub(crate) async fn export_to_file(
             ..
         } => {
             select_strokes_for_selection_args(engine, selection, *selection_collision);
+            if engine
+                .config
+                .read()
+                .export_prefs
+                .selection_export_prefs
+                .exports_tiles()
+            {
+                let tiled_bitmap = engine
+                    .export_selection_as_bitmap_tiles(None)
+                    .await??
+                    .context("Exporting selection failed, no strokes selected.")?;
+                let output_file = output_file.as_ref();
+                if !tiled_bitmap.is_tiled() {
+                    // Fits into the maximum size, written to the output file as usual
+                    for tile in tiled_bitmap.tiles {
+                        cli::create_overwrite_file_w_bytes(output_file, &tile.bytes).await?;
+                    }
+                    if open {
+                        cli::open_file_default_app(output_file)?;
+                    }
+                    return Ok(());
+                }
+                let output_dir = output_file.parent().unwrap_or(Path::new("."));
+                let file_stem = output_file
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let file_ext = output_file
+                    .extension()
+                    .map(|ext| ext.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                write_tiled_bitmap_files(
+                    tiled_bitmap,
+                    output_dir,
+                    &file_stem,
+                    &file_ext,
+                    on_conflict,
+                    on_conflict_overwrite,
+                )
+                .await?;
+                if open {
+                    cli::open_file_default_app(output_dir)?;
+                }
+                return Ok(());
+            }
             let export_bytes = engine
                 .export_selection(None)
                 .await??
@@ -578,17 +923,44 @@ pub(crate) async fn export_to_file(
                 cli::open_file_default_app(output_file)?;
             }
         }
+        cli::ExportCommand::Replay { .. } => {
+            let export_bytes = engine.export_doc_replay(None).await??;
+            cli::create_overwrite_file_w_bytes(&output_file, &export_bytes).await?;
+            if open {
+                cli::open_file_default_app(output_file)?;
+            }
+        }
         cli::ExportCommand::DocPages {
-            output_dir,
             output_file_stem,
+            file_name_pattern,
             export_format: output_format,
+            page_order,
+            pages,
+            tiff_multipage,
             ..
         } => {
+            // The pages are exported into the output directory, passed as output file
+            let output_dir = output_file.as_ref();
+            if !output_dir.exists() {
+                smol::fs::create_dir_all(output_dir)
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Failed to create output directory \"{}\".",
+                            output_dir.display()
+                        )
+                    })?;
+            }
             validators::path_is_dir(output_dir)?;
-            // The output file cannot be set with this subcommand
-            drop(output_file);
 
-            let pages_export_bytes = engine.export_doc_pages(None).await??;
+            let pages_amount = engine.pages_bounds_w_content(*page_order).len();
+            let page_numbers = pages
+                .clone()
+                .unwrap_or_default()
+                .selected_indices(pages_amount)
+                .into_iter()
+                .map(|i| i + 1)
+                .collect::<Vec<usize>>();
             let out_ext = output_format.file_ext();
             let output_file_stem = match output_file_stem {
                 Some(o) => o.clone(),
@@ -602,13 +974,66 @@ pub(crate) async fn export_to_file(
                     }
                 },
             };
-            let pages_amount = pages_export_bytes.len();
-            for (page_i, bytes) in pages_export_bytes.into_iter().enumerate() {
+            if *tiff_multipage && *output_format == DocPagesExportFormat::Tiff {
+                let mut output_file = output_dir.join(format!("{output_file_stem}.{out_ext}"));
+                if let Some(new_out) =
+                    file_conflict_prompt_action(&output_file, on_conflict, on_conflict_overwrite)?
+                {
+                    output_file = new_out;
+                }
+                let export_bytes = engine
+                    .export_doc_pages_as_multipage_tiff_bytes(None)
+                    .await??;
+                cli::create_overwrite_file_w_bytes(&output_file, &export_bytes).await?;
+                if open {
+                    cli::open_file_default_app(output_file)?;
+                }
+                return Ok(());
+            }
+            if engine
+                .config
+                .read()
+                .export_prefs
+                .doc_pages_export_prefs
+                .exports_tiles()
+            {
+                let tiled_pages = engine.export_doc_pages_as_bitmap_tiles(None).await??;
+                for (page_number, tiled_page) in page_numbers.into_iter().zip(tiled_pages) {
+                    let page_file_stem = rnote_engine::utils::doc_pages_files_names_w_pattern(
+                        file_name_pattern,
+                        &output_file_stem,
+                        page_number,
+                        pages_amount,
+                    );
+                    write_tiled_bitmap_files(
+                        tiled_page,
+                        output_dir,
+                        &page_file_stem,
+                        &out_ext,
+                        on_conflict,
+                        on_conflict_overwrite,
+                    )
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Failed to export page {page_number} of document \"{}\".",
+                            rnote_file.as_ref().display()
+                        )
+                    })?;
+                }
+                if open {
+                    cli::open_file_default_app(output_dir)?;
+                }
+                return Ok(());
+            }
+            let pages_export_bytes = engine.export_doc_pages(None).await??;
+            for (page_number, bytes) in page_numbers.into_iter().zip(pages_export_bytes) {
                 let output_file = doc_page_determine_output_file(
-                    page_i,
+                    page_number,
                     pages_amount,
                     output_dir,
                     &out_ext,
+                    file_name_pattern,
                     &output_file_stem,
                     on_conflict,
                     on_conflict_overwrite,
@@ -616,7 +1041,7 @@ pub(crate) async fn export_to_file(
                 cli::create_overwrite_file_w_bytes(&output_file, &bytes)
                     .await
                     .context(format!(
-                        "Failed to export page {page_i} of document \"{}\".",
+                        "Failed to export page {page_number} of document \"{}\".",
                         rnote_file.as_ref().display()
                     ))?
             }
@@ -651,21 +1076,48 @@ fn select_strokes_for_selection_args(
     };
 }
 
+#[allow(clippy::too_many_arguments)]
+/// Writes the files of the tiled bitmap into the output directory.
+///
+/// See [TiledBitmap::into_files].
+async fn write_tiled_bitmap_files(
+    tiled_bitmap: TiledBitmap,
+    output_dir: &Path,
+    file_stem: &str,
+    file_ext: &str,
+    on_conflict: OnConflict,
+    on_conflict_overwrite: &Cell<Option<OnConflict>>,
+) -> anyhow::Result<()> {
+    for (file_name, bytes) in tiled_bitmap.into_files(file_stem, file_ext)? {
+        let mut out = output_dir.join(file_name);
+        if let Some(new_out) =
+            file_conflict_prompt_action(&out, on_conflict, on_conflict_overwrite)?
+        {
+            out = new_out;
+        }
+        cli::create_overwrite_file_w_bytes(&out, &bytes).await?;
+    }
+    Ok(())
+}
+
 fn doc_page_determine_output_file(
-    mut page_i: usize,
+    page_number: usize,
     pages_amount: usize,
     output_dir: &Path,
     out_ext: &str,
+    file_name_pattern: &str,
     output_file_stem: &str,
     on_conflict: OnConflict,
-    on_conflict_overwrite: &mut Option<OnConflict>,
+    on_conflict_overwrite: &Cell<Option<OnConflict>>,
 ) -> anyhow::Result<PathBuf> {
-    // user facing number is one-indexed
-    page_i += 1;
-    let leading_zeros = pages_amount.to_string().len();
     let mut out = output_dir.join(format!(
-        "{output_file_stem} - page {number}.{out_ext}",
-        number = format_args!("{page_i:0fill$}", fill = leading_zeros)
+        "{}.{out_ext}",
+        rnote_engine::utils::doc_pages_files_names_w_pattern(
+            file_name_pattern,
+            output_file_stem,
+            page_number,
+            pages_amount,
+        )
     ));
     if let Some(new_out) =
         file_conflict_prompt_action(out.as_ref(), on_conflict, on_conflict_overwrite)?
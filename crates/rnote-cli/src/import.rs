@@ -1,6 +1,7 @@
 // Imports
 use crate::{cli, validators};
 use rnote_engine::Engine;
+use rnote_engine::engine::export::PageSelection;
 use rnote_engine::engine::{EngineConfigShared, EngineSnapshot};
 use std::path::Path;
 
@@ -8,6 +9,7 @@ pub(crate) async fn run_import(
     rnote_file: &Path,
     input_file: &Path,
     xopp_dpi: f64,
+    pdf_pages: Option<PageSelection>,
 ) -> anyhow::Result<()> {
     validators::file_has_ext(rnote_file, "rnote")?;
     // Xopp files don't require file extensions
@@ -25,7 +27,7 @@ pub(crate) async fn run_import(
         "Importing \"{input_file_disp}\" to: \"{rnote_file_disp}\""
     ));
 
-    if let Err(e) = import_file(&mut engine, &config, input_file, rnote_file).await {
+    if let Err(e) = import_file(&mut engine, &config, input_file, rnote_file, pdf_pages).await {
         let abandon_msg =
             format!("Import \"{input_file_disp}\" to \"{rnote_file_disp}\" failed, Err: {e:?}");
         if progressbar.is_hidden() {
@@ -54,6 +56,7 @@ pub(crate) async fn import_file(
     config: &EngineConfigShared,
     input_file: &Path,
     rnote_file: &Path,
+    pdf_pages: Option<PageSelection>,
 ) -> anyhow::Result<()> {
     let Some(rnote_file_name) = rnote_file
         .file_name()
@@ -62,9 +65,39 @@ pub(crate) async fn import_file(
         return Err(anyhow::anyhow!("Failed to get filename from rnote_file"));
     };
     let input_bytes = cli::read_bytes_from_file(&input_file).await?;
-    let xopp_import_prefs = config.read().import_prefs.xopp_import_prefs;
-    let snapshot = EngineSnapshot::load_from_xopp_bytes(input_bytes, xopp_import_prefs).await?;
-    let _ = engine.load_snapshot(snapshot);
+    if input_file.extension().is_some_and(|ext| ext == "pdf") {
+        // The new document is adjusted to the format of the Pdf pages
+        config.write().import_prefs.pdf_import_prefs.adjust_document = true;
+        let page_indices = pdf_pages
+            .map(|pages| {
+                anyhow::Ok(
+                    pages.indices_in_order(Engine::pdf_page_count(input_bytes.clone(), None)?),
+                )
+            })
+            .transpose()?;
+        if page_indices
+            .as_ref()
+            .is_some_and(|indices| indices.is_empty())
+        {
+            return Err(anyhow::anyhow!(
+                "No pages of the Pdf are selected for import"
+            ));
+        }
+        let pdf_bytes = input_bytes.clone();
+        let strokes = engine
+            .generate_pdf_pages_from_bytes(
+                input_bytes,
+                na::Vector2::zeros(),
+                page_indices.clone(),
+                None,
+            )
+            .await??;
+        let _ = engine.import_generated_pdf_pages(strokes, true, pdf_bytes, page_indices);
+    } else {
+        let xopp_import_prefs = config.read().import_prefs.xopp_import_prefs;
+        let snapshot = EngineSnapshot::load_from_xopp_bytes(input_bytes, xopp_import_prefs).await?;
+        let _ = engine.load_snapshot(snapshot);
+    }
     let rnote_bytes = engine.save_as_rnote_bytes(rnote_file_name).await??;
     cli::create_overwrite_file_w_bytes(&rnote_file, &rnote_bytes).await?;
 
@@ -0,0 +1,121 @@
+// Imports
+use crate::{cli, validators};
+use anyhow::Context;
+use rnote_compose::SplitOrder;
+use rnote_engine::Engine;
+use rnote_engine::engine::EngineSnapshot;
+use rnote_engine::fileformats::rnoteformat::RnoteFileHeader;
+use rnote_engine::fileformats::rnoteformat::encryption;
+use rnote_engine::store::chrono_comp::StrokeLayer;
+use rnote_engine::strokes::Stroke;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Prints information about the rnote files as a JSON array to stdout, one object for each file.
+pub(crate) async fn run_inspect(rnote_files: &[PathBuf]) -> anyhow::Result<()> {
+    let mut infos = Vec::with_capacity(rnote_files.len());
+    for rnote_file in rnote_files.iter() {
+        validators::file_has_ext(rnote_file, "rnote")?;
+        infos.push(
+            inspect_file(rnote_file)
+                .await
+                .with_context(|| format!("Inspecting file \"{}\" failed", rnote_file.display()))?,
+        );
+    }
+    println!("{}", serde_json::to_string_pretty(&infos)?);
+    Ok(())
+}
+
+pub(crate) async fn inspect_file(rnote_file: &Path) -> anyhow::Result<serde_json::Value> {
+    let rnote_bytes = cli::read_bytes_from_file(rnote_file).await?;
+    let file_size = rnote_bytes.len();
+    if encryption::is_encrypted(&rnote_bytes) {
+        // Everything else needs the password
+        return Ok(json!({
+            "file": rnote_file.display().to_string(),
+            "file_size": file_size,
+            "encrypted": true,
+        }));
+    }
+    let header = RnoteFileHeader::from_bytes(&rnote_bytes)?;
+    let snapshot = EngineSnapshot::load_from_rnote_bytes(rnote_bytes).await?;
+
+    let mut strokes_per_type = BTreeMap::<String, usize>::new();
+    let mut strokes_per_layer = BTreeMap::<String, usize>::new();
+    for (key, stroke) in snapshot.stroke_components.iter() {
+        let stroke_type = match stroke.as_ref() {
+            Stroke::BrushStroke(_) => "brushstroke",
+            Stroke::ShapeStroke(_) => "shapestroke",
+            Stroke::TextStroke(_) => "textstroke",
+            Stroke::VectorImage(_) => "vectorimage",
+            Stroke::BitmapImage(_) => "bitmapimage",
+            Stroke::SprayStroke(_) => "spraystroke",
+            Stroke::StampStroke(_) => "stampstroke",
+            Stroke::TapeStroke(_) => "tapestroke",
+            Stroke::SmudgeStroke(_) => "smudgestroke",
+            Stroke::Opaque(opaque) => opaque.stroke_type(),
+        };
+        *strokes_per_type.entry(stroke_type.to_string()).or_default() += 1;
+        let layer = snapshot
+            .chrono_components
+            .get(key)
+            .map(|chrono_comp| chrono_comp.layer)
+            .unwrap_or_else(|| stroke.extract_default_layer());
+        let layer = match layer {
+            StrokeLayer::UserLayer(n) => format!("user_layer_{n}"),
+            StrokeLayer::Highlighter => String::from("highlighter"),
+            StrokeLayer::Image => String::from("image"),
+            StrokeLayer::Document => String::from("document"),
+            StrokeLayer::Tape => String::from("tape"),
+        };
+        *strokes_per_layer.entry(layer).or_default() += 1;
+    }
+    let attachments = snapshot
+        .document
+        .attachments
+        .list()
+        .into_iter()
+        .map(|attachment| {
+            json!({
+                "name": attachment.name,
+                "mime_type": attachment.mime_type,
+                "size": attachment.size,
+            })
+        })
+        .collect::<Vec<serde_json::Value>>();
+    let strokes_amount = snapshot.stroke_components.len();
+
+    let mut engine = Engine::default();
+    let _ = engine.load_snapshot(snapshot);
+    let document = &engine.document;
+    let format = &document.config.format;
+
+    Ok(json!({
+        "file": rnote_file.display().to_string(),
+        "file_size": file_size,
+        "encrypted": false,
+        "format_version": header.version.to_string(),
+        "compression": header.compression,
+        "document": {
+            "layout": document.config.layout,
+            "x": document.x,
+            "y": document.y,
+            "width": document.width,
+            "height": document.height,
+            "format": {
+                "width": format.width(),
+                "height": format.height(),
+                "dpi": format.dpi(),
+            },
+        },
+        "pages": engine.pages_bounds_w_content(SplitOrder::default()).len(),
+        "strokes": {
+            "total": strokes_amount,
+            "per_type": strokes_per_type,
+            "per_layer": strokes_per_layer,
+        },
+        "layers": engine.layers(),
+        "attachments": attachments,
+    }))
+}
@@ -6,8 +6,11 @@
 pub(crate) mod cli;
 pub(crate) mod export;
 pub(crate) mod import;
+pub(crate) mod inspect;
+pub(crate) mod merge;
 pub(crate) mod test;
 pub(crate) mod thumbnail;
+pub(crate) mod upgrade;
 pub(crate) mod validators;
 
 // Renames
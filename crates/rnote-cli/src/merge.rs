@@ -0,0 +1,91 @@
+// Imports
+use crate::cli::{self, OnConflict};
+use crate::{export, validators};
+use anyhow::Context;
+use rnote_engine::Engine;
+use rnote_engine::engine::EngineSnapshot;
+use rnote_engine::engine::import::DocMergeMode;
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+
+pub(crate) async fn run_merge(
+    rnote_files: &[PathBuf],
+    output_file: &Path,
+    mode: DocMergeMode,
+    on_conflict: OnConflict,
+) -> anyhow::Result<()> {
+    if rnote_files.len() < 2 {
+        return Err(anyhow::anyhow!(
+            "At least two rnote files are required for merging."
+        ));
+    }
+    for rnote_file in rnote_files.iter() {
+        validators::file_has_ext(rnote_file, "rnote")?;
+    }
+    if output_file.extension().is_none_or(|ext| ext != "rnote") {
+        return Err(anyhow::anyhow!(
+            "Expected output file with extension \"rnote\", file \"{}\".",
+            output_file.display()
+        ));
+    }
+    let output_file = export::file_conflict_prompt_action(
+        output_file,
+        on_conflict,
+        &Cell::new(None::<OnConflict>),
+    )?
+    .unwrap_or_else(|| output_file.to_path_buf());
+
+    let output_file_disp = output_file.display().to_string();
+    let progressbar = cli::new_progressbar(format!(
+        "Merging {} files into \"{output_file_disp}\"",
+        rnote_files.len()
+    ));
+
+    if let Err(e) = merge_files(rnote_files, &output_file, mode).await {
+        let abandon_msg = format!("Merging into \"{output_file_disp}\" failed, Err: {e:?}");
+        if progressbar.is_hidden() {
+            println!("{abandon_msg}");
+        }
+        progressbar.abandon_with_message(abandon_msg);
+        return Err(e);
+    } else {
+        let finish_msg = format!("Merging into \"{output_file_disp}\" succeeded");
+        if progressbar.is_hidden() {
+            println!("{finish_msg}");
+        }
+        progressbar.finish_with_message(finish_msg);
+    }
+
+    Ok(())
+}
+
+/// Merges the files in the given order. The document configuration of the first file is used.
+pub(crate) async fn merge_files(
+    rnote_files: &[PathBuf],
+    output_file: &Path,
+    mode: DocMergeMode,
+) -> anyhow::Result<()> {
+    let Some(output_file_name) = output_file
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+    else {
+        return Err(anyhow::anyhow!("Failed to get filename from output file"));
+    };
+    let mut engine = Engine::default();
+
+    for (i, rnote_file) in rnote_files.iter().enumerate() {
+        let rnote_bytes = cli::read_bytes_from_file(rnote_file).await?;
+        let snapshot = EngineSnapshot::load_from_rnote_bytes(rnote_bytes)
+            .await
+            .with_context(|| format!("Failed to load rnote file \"{}\"", rnote_file.display()))?;
+        if i == 0 {
+            let _ = engine.load_snapshot(snapshot);
+        } else {
+            let _ = engine.merge_snapshot(snapshot, mode);
+        }
+    }
+
+    let rnote_bytes = engine.save_as_rnote_bytes(output_file_name).await??;
+    cli::create_overwrite_file_w_bytes(output_file, &rnote_bytes).await?;
+    Ok(())
+}
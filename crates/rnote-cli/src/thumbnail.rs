@@ -2,18 +2,30 @@
 // Author: ayykamp <kamp@ayyy.dev>
 
 use anyhow::Context;
-use image::DynamicImage;
-use image::imageops::FilterType;
+use p2d::bounding_volume::Aabb;
+use rnote_compose::SplitOrder;
 use rnote_engine::Engine;
 use rnote_engine::engine::EngineSnapshot;
-use rnote_engine::engine::export::{SelectionExportFormat, SelectionExportPrefs};
 use std::fs::File;
 use std::io::Read;
 use std::path::PathBuf;
 
+/// The area of the document that is rendered as thumbnail.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ThumbnailArea {
+    /// The page with the given number, starting at 1.
+    Page(usize),
+    /// A rectangular area in document coordinates.
+    Region {
+        mins: na::Vector2<f64>,
+        size: na::Vector2<f64>,
+    },
+}
+
 pub(crate) async fn run_thumbnail(
     rnote_file: PathBuf,
     output_size: u32,
+    area: ThumbnailArea,
     output: PathBuf,
 ) -> anyhow::Result<()> {
     let mut engine = Engine::default();
@@ -23,40 +35,38 @@ pub(crate) async fn run_thumbnail(
     fh.read_to_end(&mut rnote_file_bytes)?;
     let engine_snapshot = EngineSnapshot::load_from_rnote_bytes(rnote_file_bytes).await?;
 
-    // We dont care about the return values of these functions
+    // We dont care about the return value of this function
     let _ = engine.load_snapshot(engine_snapshot);
-    let _ = engine.select_all_strokes();
 
-    let prefs = SelectionExportPrefs {
-        export_format: SelectionExportFormat::Png,
-        ..Default::default()
+    let content = match area {
+        ThumbnailArea::Page(page) => {
+            let mut pages_content = engine.extract_pages_content(SplitOrder::default());
+            let pages_amount = pages_content.len();
+            if page == 0 || page > pages_amount {
+                return Err(anyhow::anyhow!(
+                    "Page {page} does not exist, the document has {pages_amount} page(s)."
+                ));
+            }
+            pages_content.swap_remove(page - 1)
+        }
+        ThumbnailArea::Region { mins, size } => {
+            if size[0] <= 0.0 || size[1] <= 0.0 {
+                return Err(anyhow::anyhow!(
+                    "The width and height of the region must be positive."
+                ));
+            }
+            engine.extract_region_content(Aabb::new(mins.into(), (mins + size).into()))
+        }
     };
-    let export_bytes = engine
-        .export_selection(Some(prefs))
-        .await??
-        .context("Exporting selection failed, no strokes selected.")?;
-
-    let mut image = image::load_from_memory(&export_bytes)?;
-    let (width, height) = (image.width(), image.height());
-
-    if std::cmp::max(width, height) > output_size {
-        let ratio = if width >= height {
-            // Landscape
-            width as f64 / output_size as f64
-        } else {
-            // Portrait
-            height as f64 / output_size as f64
-        };
-        let nwidth = width as f64 / ratio;
-        let nheight = height as f64 / ratio;
-        image = DynamicImage::from(image::imageops::resize(
-            &image,
-            nwidth as u32,
-            nheight as u32,
-            FilterType::Nearest,
-        ));
-    }
+    let svg = content
+        .gen_svg(true, true, false, 0.0)?
+        .context("Generating Svg for the thumbnail failed, returned None.")?;
 
-    image.save(output)?;
+    // Rendered directly at the thumbnail size, instead of scaling down a larger image
+    let image_scale = f64::from(output_size) / svg.bounds.extents().max();
+    svg.gen_image(image_scale)?
+        .into_imgbuf()?
+        .save(&output)
+        .with_context(|| format!("Saving thumbnail to \"{}\" failed.", output.display()))?;
     Ok(())
 }
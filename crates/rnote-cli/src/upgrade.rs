@@ -0,0 +1,152 @@
+// Imports
+use crate::{cli, validators};
+use anyhow::Context;
+use rnote_engine::Engine;
+use rnote_engine::engine::EngineSnapshot;
+use rnote_engine::fileformats::rnoteformat::{
+    CompressionMethod, RnoteFile, RnoteFileHeader, encryption,
+};
+use std::path::{Path, PathBuf};
+
+pub(crate) async fn run_upgrade(
+    rnote_files: &[PathBuf],
+    compression: Option<CompressionMethod>,
+    compression_level: Option<i32>,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    for rnote_file in rnote_files.iter() {
+        validators::file_has_ext(rnote_file, "rnote")?;
+        let file_disp = rnote_file.display().to_string();
+        let progressbar = cli::new_progressbar(format!("Upgrading file \"{file_disp}\""));
+
+        match upgrade_file(rnote_file, compression, compression_level, dry_run).await {
+            Err(e) => {
+                let abandon_msg = format!("Upgrading file \"{file_disp}\" failed, Err: {e:?}");
+                if progressbar.is_hidden() {
+                    println!("{abandon_msg}");
+                }
+                progressbar.abandon_with_message(abandon_msg);
+                return Err(e);
+            }
+            Ok(changes) => {
+                let finish_msg = if changes.is_empty() {
+                    format!("File \"{file_disp}\" is up to date")
+                } else if dry_run {
+                    format!(
+                        "File \"{file_disp}\" would be changed (dry run): {}",
+                        changes.join(", ")
+                    )
+                } else {
+                    format!("Upgraded file \"{file_disp}\": {}", changes.join(", "))
+                };
+                if progressbar.is_hidden() {
+                    println!("{finish_msg}");
+                }
+                progressbar.finish_with_message(finish_msg);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Upgrades the file in place, returning the descriptions of the changes.
+///
+/// The file is only written when something changed and `dry_run` is not set. It is replaced in a single step, so an
+/// interrupted upgrade leaves the original file intact.
+pub(crate) async fn upgrade_file(
+    rnote_file: &Path,
+    compression: Option<CompressionMethod>,
+    compression_level: Option<i32>,
+    dry_run: bool,
+) -> anyhow::Result<Vec<String>> {
+    let Some(file_name) = rnote_file
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+    else {
+        return Err(anyhow::anyhow!("Failed to get filename from rnote file"));
+    };
+    let rnote_bytes = cli::read_bytes_from_file(rnote_file).await?;
+    if encryption::is_encrypted(&rnote_bytes) {
+        return Err(anyhow::anyhow!(
+            "The file is encrypted, upgrading encrypted files is not supported."
+        ));
+    }
+    let header = RnoteFileHeader::from_bytes(&rnote_bytes)?;
+    let current_version = semver::Version::parse(RnoteFile::SEMVER)?;
+    if header.version > current_version {
+        return Err(anyhow::anyhow!(
+            "The file was saved with the newer version {}, upgrading it would lose data.",
+            header.version
+        ));
+    }
+    let mut snapshot = EngineSnapshot::load_from_rnote_bytes(rnote_bytes)
+        .await
+        .context("Validating the file failed")?;
+    let mut changes = Vec::new();
+
+    if header.version < current_version {
+        changes.push(format!(
+            "migrated format version {} to {current_version}",
+            header.version
+        ));
+    }
+    let report = snapshot.repair();
+    if report.removed_invalid_strokes > 0 {
+        changes.push(format!(
+            "removed {} stroke(s) with invalid geometry",
+            report.removed_invalid_strokes
+        ));
+    }
+    if report.removed_orphaned_chrono_components > 0 {
+        changes.push(format!(
+            "removed {} orphaned chrono component(s)",
+            report.removed_orphaned_chrono_components
+        ));
+    }
+    if report.added_missing_chrono_components > 0 {
+        changes.push(format!(
+            "added {} missing chrono component(s)",
+            report.added_missing_chrono_components
+        ));
+    }
+    if report.advanced_chrono_counter {
+        changes.push(String::from("advanced the chrono counter"));
+    }
+
+    let mut engine = Engine::default();
+    let _ = engine.load_snapshot(snapshot);
+    {
+        let mut config = engine.config.write();
+        config.compression_prefs.method = compression.unwrap_or(header.compression);
+        if let Some(level) = compression_level {
+            config.compression_prefs.set_level(level);
+        }
+        let compression_prefs = config.compression_prefs;
+        if compression_prefs.method != header.compression {
+            changes.push(format!(
+                "recompressed from {} to {}",
+                compression_name(header.compression),
+                compression_name(compression_prefs.method)
+            ));
+        } else if compression_level.is_some() {
+            changes.push(format!(
+                "recompressed with level {}",
+                compression_prefs.level()
+            ));
+        }
+    }
+
+    if !changes.is_empty() && !dry_run {
+        let rnote_bytes = engine.save_as_rnote_bytes(file_name).await??;
+        cli::replace_file_w_bytes(rnote_file, &rnote_bytes).await?;
+    }
+    Ok(changes)
+}
+
+fn compression_name(method: CompressionMethod) -> &'static str {
+    match method {
+        CompressionMethod::Gzip => "gzip",
+        CompressionMethod::Zstd => "zstd",
+    }
+}
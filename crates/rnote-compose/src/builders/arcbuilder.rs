@@ -0,0 +1,172 @@
+// Imports
+use super::buildable::{Buildable, BuilderCreator, BuilderProgress};
+use crate::constraints::ConstraintRatio;
+use crate::eventresult::EventPropagation;
+use crate::ext::AabbExt;
+use crate::penevent::{PenEvent, PenState};
+use crate::penpath::Element;
+use crate::shapes::{Arc, Line};
+use crate::style::{Composer, indicators};
+use crate::{Constraints, EventResult};
+use crate::{Shape, Style};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use std::time::Instant;
+
+#[derive(Debug, Clone)]
+enum ArcBuilderState {
+    End {
+        start: na::Vector2<f64>,
+        end: na::Vector2<f64>,
+    },
+    EndFinished {
+        start: na::Vector2<f64>,
+        end: na::Vector2<f64>,
+    },
+    Through {
+        start: na::Vector2<f64>,
+        end: na::Vector2<f64>,
+        through: na::Vector2<f64>,
+    },
+}
+
+/// Builder for an arc through three points.
+///
+/// First the start and end points are set, then the point the arc passes through.
+#[derive(Debug, Clone)]
+pub struct ArcBuilder {
+    state: ArcBuilderState,
+}
+
+impl BuilderCreator for ArcBuilder {
+    fn start(element: Element, _now: Instant) -> Self {
+        Self {
+            state: ArcBuilderState::End {
+                start: element.pos,
+                end: element.pos,
+            },
+        }
+    }
+}
+
+impl Buildable for ArcBuilder {
+    type Emit = Shape;
+
+    fn handle_event(
+        &mut self,
+        event: PenEvent,
+        _now: Instant,
+        mut constraints: Constraints,
+    ) -> EventResult<BuilderProgress<Self::Emit>> {
+        // we always want to allow horizontal and vertical constraints while building an arc
+        constraints.ratios.insert(ConstraintRatio::Horizontal);
+        constraints.ratios.insert(ConstraintRatio::Vertical);
+
+        let progress = match (&mut self.state, event) {
+            (ArcBuilderState::End { start, end }, PenEvent::Down { element, .. }) => {
+                *end = constraints.constrain(element.pos - *start) + *start;
+                BuilderProgress::InProgress
+            }
+            (ArcBuilderState::End { start, .. }, PenEvent::Up { element, .. }) => {
+                self.state = ArcBuilderState::EndFinished {
+                    start: *start,
+                    end: constraints.constrain(element.pos - *start) + *start,
+                };
+                BuilderProgress::InProgress
+            }
+            (ArcBuilderState::End { .. }, ..) => BuilderProgress::InProgress,
+            (ArcBuilderState::EndFinished { start, end }, PenEvent::Down { element, .. }) => {
+                self.state = ArcBuilderState::Through {
+                    start: *start,
+                    end: *end,
+                    through: element.pos,
+                };
+                BuilderProgress::InProgress
+            }
+            (ArcBuilderState::EndFinished { .. }, ..) => BuilderProgress::InProgress,
+            (ArcBuilderState::Through { through, .. }, PenEvent::Down { element, .. }) => {
+                *through = element.pos;
+                BuilderProgress::InProgress
+            }
+            (ArcBuilderState::Through { start, end, .. }, PenEvent::Up { .. }) => {
+                let (start, end) = (*start, *end);
+                // degenerates to a line when the points are collinear
+                let shape = self
+                    .state_as_arc()
+                    .map(Shape::Arc)
+                    .unwrap_or_else(|| Shape::Line(Line::new(start, end)));
+                BuilderProgress::Finished(vec![shape])
+            }
+            (ArcBuilderState::Through { .. }, ..) => BuilderProgress::InProgress,
+        };
+
+        EventResult {
+            handled: true,
+            propagate: EventPropagation::Stop,
+            progress,
+        }
+    }
+
+    fn bounds(&self, style: &Style, zoom: f64) -> Option<Aabb> {
+        let stroke_width = style.stroke_width();
+
+        match &self.state {
+            ArcBuilderState::End { start, end } | ArcBuilderState::EndFinished { start, end } => {
+                Some(
+                    Aabb::new_positive((*start).into(), (*end).into())
+                        .loosened(stroke_width.max(indicators::POS_INDICATOR_RADIUS) / zoom),
+                )
+            }
+            ArcBuilderState::Through {
+                start,
+                end,
+                through,
+            } => {
+                let mut aabb = Aabb::new_positive((*start).into(), (*end).into());
+                aabb.take_point((*through).into());
+                if let Some(arc) = self.state_as_arc() {
+                    aabb.merge(&arc.composed_bounds(style));
+                }
+
+                Some(aabb.loosened(stroke_width.max(indicators::POS_INDICATOR_RADIUS) / zoom))
+            }
+        }
+    }
+
+    fn draw_styled(&self, cx: &mut piet_cairo::CairoRenderContext, style: &Style, zoom: f64) {
+        match &self.state {
+            ArcBuilderState::End { start, end } | ArcBuilderState::EndFinished { start, end } => {
+                indicators::draw_vec_indicator(cx, PenState::Down, *start, *end, zoom);
+                indicators::draw_pos_indicator(cx, PenState::Up, *start, zoom);
+                indicators::draw_pos_indicator(cx, PenState::Down, *end, zoom);
+            }
+            ArcBuilderState::Through {
+                start,
+                end,
+                through,
+            } => {
+                match self.state_as_arc() {
+                    Some(arc) => arc.draw_composed(cx, style),
+                    None => Line::new(*start, *end).draw_composed(cx, style),
+                }
+
+                indicators::draw_pos_indicator(cx, PenState::Up, *start, zoom);
+                indicators::draw_pos_indicator(cx, PenState::Up, *end, zoom);
+                indicators::draw_pos_indicator(cx, PenState::Down, *through, zoom);
+            }
+        }
+    }
+}
+
+impl ArcBuilder {
+    /// The current state as an arc, if the through point is already set and the points are not collinear.
+    pub fn state_as_arc(&self) -> Option<Arc> {
+        match &self.state {
+            ArcBuilderState::Through {
+                start,
+                end,
+                through,
+            } => Arc::from_three_points(*start, *through, *end),
+            _ => None,
+        }
+    }
+}
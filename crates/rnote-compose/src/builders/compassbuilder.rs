@@ -0,0 +1,215 @@
+// Imports
+use super::buildable::{Buildable, BuilderCreator, BuilderProgress};
+use crate::constraints::ConstraintRatio;
+use crate::eventresult::EventPropagation;
+use crate::ext::Vector2Ext;
+use crate::penevent::{PenEvent, PenState};
+use crate::penpath::Element;
+use crate::shapes::Arc;
+use crate::style::{Composer, indicators};
+use crate::transform::Transform;
+use crate::{Constraints, EventResult};
+use crate::{Shape, Style};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::RenderContext;
+use std::f64::consts::{PI, TAU};
+use std::time::Instant;
+
+#[derive(Debug, Clone)]
+enum CompassBuilderState {
+    /// The center is pinned, the radius follows the pen.
+    Radius {
+        center: na::Vector2<f64>,
+        current: na::Vector2<f64>,
+    },
+    /// The radius is set, waiting for the pen to start the arc.
+    Pinned {
+        center: na::Vector2<f64>,
+        radius: f64,
+    },
+    /// The arc is swept around the center, following the angle of the pen.
+    Sweeping {
+        center: na::Vector2<f64>,
+        radius: f64,
+        start_angle: f64,
+        sweep_angle: f64,
+        /// The angle of the pen at the last event, to accumulate the sweep across the discontinuity at PI.
+        last_angle: f64,
+    },
+}
+
+/// Builder for arcs with a pinned center, like a compass.
+///
+/// First the center is pinned and the radius is set by dragging, then the arc is drawn by sweeping the pen around
+/// the center. The arc can sweep up to a full circle in both directions.
+#[derive(Debug, Clone)]
+pub struct CompassBuilder {
+    state: CompassBuilderState,
+}
+
+impl BuilderCreator for CompassBuilder {
+    fn start(element: Element, _now: Instant) -> Self {
+        Self {
+            state: CompassBuilderState::Radius {
+                center: element.pos,
+                current: element.pos,
+            },
+        }
+    }
+}
+
+impl Buildable for CompassBuilder {
+    type Emit = Shape;
+
+    fn handle_event(
+        &mut self,
+        event: PenEvent,
+        _now: Instant,
+        mut constraints: Constraints,
+    ) -> EventResult<BuilderProgress<Self::Emit>> {
+        // we always want to allow horizontal and vertical constraints while setting the radius
+        constraints.ratios.insert(ConstraintRatio::Horizontal);
+        constraints.ratios.insert(ConstraintRatio::Vertical);
+
+        let progress = match (&mut self.state, event) {
+            (CompassBuilderState::Radius { center, current }, PenEvent::Down { element, .. }) => {
+                *current = constraints.constrain(element.pos - *center) + *center;
+                BuilderProgress::InProgress
+            }
+            (CompassBuilderState::Radius { center, .. }, PenEvent::Up { element, .. }) => {
+                let radius = constraints.constrain(element.pos - *center).magnitude();
+                self.state = CompassBuilderState::Pinned {
+                    center: *center,
+                    radius,
+                };
+                BuilderProgress::InProgress
+            }
+            (CompassBuilderState::Radius { .. }, ..) => BuilderProgress::InProgress,
+            (CompassBuilderState::Pinned { center, radius }, PenEvent::Down { element, .. }) => {
+                let angle = angle_around(*center, element.pos);
+                self.state = CompassBuilderState::Sweeping {
+                    center: *center,
+                    radius: *radius,
+                    start_angle: angle,
+                    sweep_angle: 0.0,
+                    last_angle: angle,
+                };
+                BuilderProgress::InProgress
+            }
+            (CompassBuilderState::Pinned { .. }, ..) => BuilderProgress::InProgress,
+            (
+                CompassBuilderState::Sweeping {
+                    center,
+                    sweep_angle,
+                    last_angle,
+                    ..
+                },
+                PenEvent::Down { element, .. },
+            ) => {
+                let angle = angle_around(*center, element.pos);
+                // The shortest rotation from the last angle
+                let delta = (angle - *last_angle + PI).rem_euclid(TAU) - PI;
+                *sweep_angle = (*sweep_angle + delta).clamp(-TAU, TAU);
+                *last_angle = angle;
+                BuilderProgress::InProgress
+            }
+            (CompassBuilderState::Sweeping { .. }, PenEvent::Up { .. }) => {
+                match self.state_as_arc() {
+                    Some(arc) => BuilderProgress::Finished(vec![Shape::Arc(arc)]),
+                    None => BuilderProgress::Finished(vec![]),
+                }
+            }
+            (CompassBuilderState::Sweeping { .. }, ..) => BuilderProgress::InProgress,
+        };
+
+        EventResult {
+            handled: true,
+            propagate: EventPropagation::Stop,
+            progress,
+        }
+    }
+
+    fn bounds(&self, style: &Style, zoom: f64) -> Option<Aabb> {
+        let (center, radius) = self.center_and_radius();
+        // The guide circle always encloses the arc
+        Some(
+            Aabb::from_half_extents(center.into(), na::Vector2::repeat(radius)).loosened(
+                style
+                    .stroke_width()
+                    .max(indicators::POS_INDICATOR_RADIUS.max(Self::GUIDE_LINE_WIDTH) / zoom),
+            ),
+        )
+    }
+
+    fn draw_styled(&self, cx: &mut piet_cairo::CairoRenderContext, style: &Style, zoom: f64) {
+        let (center, radius) = self.center_and_radius();
+        cx.stroke_styled(
+            kurbo::Circle::new(center.to_kurbo_point(), radius),
+            &Self::GUIDE_COLOR,
+            Self::GUIDE_LINE_WIDTH / zoom,
+            &piet::StrokeStyle::new().dash_pattern(&[6.0 / zoom, 4.0 / zoom]),
+        );
+
+        match &self.state {
+            CompassBuilderState::Radius { center, current } => {
+                indicators::draw_vec_indicator(cx, PenState::Down, *center, *current, zoom);
+                indicators::draw_pos_indicator(cx, PenState::Down, *current, zoom);
+            }
+            CompassBuilderState::Pinned { .. } => {}
+            CompassBuilderState::Sweeping {
+                start_angle,
+                sweep_angle,
+                ..
+            } => {
+                if let Some(arc) = self.state_as_arc() {
+                    arc.draw_composed(cx, style);
+                }
+                let end_angle = start_angle + sweep_angle;
+                let end = center + na::vector![end_angle.cos(), end_angle.sin()] * radius;
+                indicators::draw_vec_indicator(cx, PenState::Down, center, end, zoom);
+                indicators::draw_pos_indicator(cx, PenState::Down, end, zoom);
+            }
+        }
+        indicators::draw_pos_indicator(cx, PenState::Up, center, zoom);
+    }
+}
+
+impl CompassBuilder {
+    const GUIDE_COLOR: piet::Color = crate::color::GNOME_BLUES[3].with_a8(160);
+    const GUIDE_LINE_WIDTH: f64 = 1.0;
+
+    /// The current state as an arc, once the pen sweeps around the center.
+    pub fn state_as_arc(&self) -> Option<Arc> {
+        match &self.state {
+            CompassBuilderState::Sweeping {
+                center,
+                radius,
+                start_angle,
+                sweep_angle,
+                ..
+            } if *radius > 0.0 && *sweep_angle != 0.0 => Some(Arc {
+                radius: *radius,
+                start_angle: *start_angle,
+                sweep_angle: *sweep_angle,
+                transform: Transform::new_w_isometry(na::Isometry2::new(*center, 0.0)),
+            }),
+            _ => None,
+        }
+    }
+
+    fn center_and_radius(&self) -> (na::Vector2<f64>, f64) {
+        match &self.state {
+            CompassBuilderState::Radius { center, current } => {
+                (*center, (*current - *center).magnitude())
+            }
+            CompassBuilderState::Pinned { center, radius }
+            | CompassBuilderState::Sweeping { center, radius, .. } => (*center, *radius),
+        }
+    }
+}
+
+/// The angle of the position around the center.
+fn angle_around(center: na::Vector2<f64>, pos: na::Vector2<f64>) -> f64 {
+    let offset = pos - center;
+    offset[1].atan2(offset[0])
+}
@@ -1,7 +1,9 @@
 // Modules
+mod arcbuilder;
 mod arrowbuilder;
 /// Buildable trait.
 pub mod buildable;
+mod compassbuilder;
 mod coordsystem2dbuilder;
 mod coordsystem3dbuilder;
 mod cubbezbuilder;
@@ -11,15 +13,22 @@ mod gridbuilder;
 mod linebuilder;
 mod penpathcurvedbuilder;
 mod penpathmodeledbuilder;
+mod penpathpredictedbuilder;
 mod penpathsimplebuilder;
 mod polygonbuilder;
 mod polylinebuilder;
 mod quadbezbuilder;
 mod quadrantcoordsystem2dbuilder;
 mod rectanglebuilder;
+mod regularpolygonbuilder;
+mod roundedrectanglebuilder;
+mod splinebuilder;
+mod starbuilder;
 
 // Re-exports
+pub use arcbuilder::ArcBuilder;
 pub use arrowbuilder::ArrowBuilder;
+pub use compassbuilder::CompassBuilder;
 pub use coordsystem2dbuilder::CoordSystem2DBuilder;
 pub use coordsystem3dbuilder::CoordSystem3DBuilder;
 pub use cubbezbuilder::CubBezBuilder;
@@ -28,13 +37,18 @@ pub use fociellipsebuilder::FociEllipseBuilder;
 pub use gridbuilder::GridBuilder;
 pub use linebuilder::LineBuilder;
 pub use penpathcurvedbuilder::PenPathCurvedBuilder;
-pub use penpathmodeledbuilder::PenPathModeledBuilder;
+pub use penpathmodeledbuilder::{InputSmoothingConfig, PenPathModeledBuilder};
+pub use penpathpredictedbuilder::PenPathPredictedBuilder;
 pub use penpathsimplebuilder::PenPathSimpleBuilder;
 pub use polygonbuilder::PolygonBuilder;
 pub use polylinebuilder::PolylineBuilder;
 pub use quadbezbuilder::QuadBezBuilder;
 pub use quadrantcoordsystem2dbuilder::QuadrantCoordSystem2DBuilder;
 pub use rectanglebuilder::RectangleBuilder;
+pub use regularpolygonbuilder::RegularPolygonBuilder;
+pub use roundedrectanglebuilder::RoundedRectangleBuilder;
+pub use splinebuilder::SplineBuilder;
+pub use starbuilder::StarBuilder;
 
 // Imports
 use anyhow::Context;
@@ -93,6 +107,24 @@ pub enum ShapeBuilderType {
     /// A polygon builder
     #[serde(rename = "polygon")]
     Polygon,
+    /// A rounded rectangle builder
+    #[serde(rename = "rounded_rectangle")]
+    RoundedRectangle,
+    /// A regular polygon builder
+    #[serde(rename = "regular_polygon")]
+    RegularPolygon,
+    /// A star builder
+    #[serde(rename = "star")]
+    Star,
+    /// An arc through three points builder
+    #[serde(rename = "arc")]
+    Arc,
+    /// A spline builder, for smooth curves through points
+    #[serde(rename = "spline")]
+    Spline,
+    /// An arc around a pinned center builder, like a compass
+    #[serde(rename = "compass")]
+    Compass,
 }
 
 impl ShapeBuilderType {
@@ -112,6 +144,12 @@ impl ShapeBuilderType {
             "shapebuilder-cubbez-symbolic" => Some(Self::CubBez),
             "shapebuilder-polyline-symbolic" => Some(Self::Polyline),
             "shapebuilder-polygon-symbolic" => Some(Self::Polygon),
+            "shapebuilder-roundedrectangle-symbolic" => Some(Self::RoundedRectangle),
+            "shapebuilder-regularpolygon-symbolic" => Some(Self::RegularPolygon),
+            "shapebuilder-star-symbolic" => Some(Self::Star),
+            "shapebuilder-arc-symbolic" => Some(Self::Arc),
+            "shapebuilder-spline-symbolic" => Some(Self::Spline),
+            "shapebuilder-compass-symbolic" => Some(Self::Compass),
             _ => None,
         }
     }
@@ -134,6 +172,12 @@ impl ShapeBuilderType {
             Self::CubBez => String::from("shapebuilder-cubbez-symbolic"),
             Self::Polyline => String::from("shapebuilder-polyline-symbolic"),
             Self::Polygon => String::from("shapebuilder-polygon-symbolic"),
+            Self::RoundedRectangle => String::from("shapebuilder-roundedrectangle-symbolic"),
+            Self::RegularPolygon => String::from("shapebuilder-regularpolygon-symbolic"),
+            Self::Star => String::from("shapebuilder-star-symbolic"),
+            Self::Arc => String::from("shapebuilder-arc-symbolic"),
+            Self::Spline => String::from("shapebuilder-spline-symbolic"),
+            Self::Compass => String::from("shapebuilder-compass-symbolic"),
         }
     }
 }
@@ -78,7 +78,9 @@ impl Buildable for PenPathCurvedBuilder {
             }
             (_, PenEvent::Proximity { .. })
             | (_, PenEvent::KeyPressed { .. })
-            | (_, PenEvent::Text { .. }) => BuilderProgress::InProgress,
+            | (_, PenEvent::Text { .. })
+            | (_, PenEvent::LongPress { .. })
+            | (_, PenEvent::DoubleTap { .. }) => BuilderProgress::InProgress,
             (_, PenEvent::Cancel) => {
                 self.reset();
 
@@ -13,9 +13,51 @@ use ink_stroke_modeler_rs::{
 use once_cell::sync::Lazy;
 use p2d::bounding_volume::Aabb;
 use piet::RenderContext;
+use serde::{Deserialize, Serialize};
 use std::time::Instant;
 use tracing::{debug, error};
 
+/// The input smoothing of the modeled pen path builder.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "input_smoothing_config")]
+pub struct InputSmoothingConfig {
+    /// How strongly the high frequency wobble of the input is smoothed, between 0.0 and 1.0.
+    #[serde(rename = "smoothing_strength")]
+    pub smoothing_strength: f64,
+    /// How far in seconds the path is predicted ahead of the last input.
+    #[serde(rename = "prediction_window")]
+    pub prediction_window: f64,
+    /// The minimum distance between input samples. Closer samples are skipped.
+    #[serde(rename = "min_sample_distance")]
+    pub min_sample_distance: f64,
+}
+
+impl Default for InputSmoothingConfig {
+    fn default() -> Self {
+        Self {
+            smoothing_strength: 0.5,
+            prediction_window: 0.2,
+            min_sample_distance: 0.0,
+        }
+    }
+}
+
+impl InputSmoothingConfig {
+    /// The wobble smoother timeout in seconds at full smoothing strength.
+    const WOBBLE_SMOOTHER_TIMEOUT_MAX: f64 = 0.08;
+    /// The wobble smoother timeout must be positive.
+    const WOBBLE_SMOOTHER_TIMEOUT_MIN: f64 = 0.001;
+
+    fn modeler_params(&self) -> ModelerParams {
+        ModelerParams {
+            wobble_smoother_timeout: (self.smoothing_strength.clamp(0.0, 1.0)
+                * Self::WOBBLE_SMOOTHER_TIMEOUT_MAX)
+                .max(Self::WOBBLE_SMOOTHER_TIMEOUT_MIN),
+            ..*MODELER_PARAMS
+        }
+    }
+}
+
 /// Pen path modeled builder.
 pub struct PenPathModeledBuilder {
     /// Buffered elements, which are filled up by new pen events and used to build path segments.
@@ -27,6 +69,7 @@ pub struct PenPathModeledBuilder {
     last_element: Element,
     last_element_time: Instant,
     stroke_modeler: StrokeModeler,
+    input_smoothing: InputSmoothingConfig,
 }
 
 impl std::fmt::Debug for PenPathModeledBuilder {
@@ -38,25 +81,14 @@ impl std::fmt::Debug for PenPathModeledBuilder {
             .field("last_element", &self.last_element)
             .field("last_element_time", &self.last_element_time)
             .field("stroke_modeler", &"{.. no debug impl ..}")
+            .field("input_smoothing", &self.input_smoothing)
             .finish()
     }
 }
 
 impl BuilderCreator for PenPathModeledBuilder {
     fn start(element: Element, now: Instant) -> Self {
-        let mut builder = Self {
-            buffer: vec![],
-            prediction_start: element,
-            prediction_buffer: vec![],
-            start_time: now,
-            last_element: element,
-            last_element_time: now,
-            stroke_modeler: StrokeModeler::default(),
-        };
-
-        builder.restart(element, now);
-
-        builder
+        Self::start_w_input_smoothing(element, now, InputSmoothingConfig::default())
     }
 }
 
@@ -86,9 +118,11 @@ impl Buildable for PenPathModeledBuilder {
 
                 BuilderProgress::Finished(segments)
             }
-            PenEvent::Proximity { .. } | PenEvent::KeyPressed { .. } | PenEvent::Text { .. } => {
-                BuilderProgress::InProgress
-            }
+            PenEvent::Proximity { .. }
+            | PenEvent::KeyPressed { .. }
+            | PenEvent::Text { .. }
+            | PenEvent::LongPress { .. }
+            | PenEvent::DoubleTap { .. } => BuilderProgress::InProgress,
             PenEvent::Cancel => BuilderProgress::Finished(vec![]),
         };
 
@@ -139,6 +173,28 @@ static MODELER_PARAMS: Lazy<ModelerParams> = Lazy::new(|| ModelerParams {
 });
 
 impl PenPathModeledBuilder {
+    /// Start the builder with the given input smoothing.
+    pub fn start_w_input_smoothing(
+        element: Element,
+        now: Instant,
+        input_smoothing: InputSmoothingConfig,
+    ) -> Self {
+        let mut builder = Self {
+            buffer: vec![],
+            prediction_start: element,
+            prediction_buffer: vec![],
+            start_time: now,
+            last_element: element,
+            last_element_time: now,
+            stroke_modeler: StrokeModeler::default(),
+            input_smoothing,
+        };
+
+        builder.restart(element, now);
+
+        builder
+    }
+
     fn try_build_segments(&mut self) -> Option<Vec<Segment>> {
         if self.buffer.is_empty() {
             return None;
@@ -165,6 +221,14 @@ impl PenPathModeledBuilder {
         event_type: ModelerInputEventType,
         now: Instant,
     ) {
+        // skip samples that are too close to the previous one, except the last
+        if event_type == ModelerInputEventType::Move
+            && (element.pos - self.last_element.pos).magnitude()
+                < self.input_smoothing.min_sample_distance
+        {
+            return;
+        }
+
         let modeler_input = ModelerInput {
             event_type,
             pos: (element.pos[0], element.pos[1]),
@@ -176,7 +240,8 @@ impl PenPathModeledBuilder {
             Ok(results) => self.buffer.extend(results.into_iter().map(|r| {
                 let (x, y) = r.pos;
                 let pressure = r.pressure;
-                Element::new(na::vector![x, y], pressure)
+                // The modeler does not model the tilt, so the latest one is used
+                Element::new(na::vector![x, y], pressure).with_tilt_angle(element.tilt_angle)
             })),
             Err(e) => {
                 match e {
@@ -209,6 +274,7 @@ impl PenPathModeledBuilder {
                 };
             }
         }
+        self.last_element = element;
         self.last_element_time = now;
 
         // The prediction start is the last buffer element (which will get drained)
@@ -220,13 +286,17 @@ impl PenPathModeledBuilder {
         if event_type == ModelerInputEventType::Up {
             self.prediction_buffer.clear();
         } else {
+            let prediction_end = now.duration_since(self.start_time).as_secs_f64()
+                + self.input_smoothing.prediction_window.max(0.0);
             self.prediction_buffer = match self.stroke_modeler.predict() {
                 Ok(results) => results
                     .into_iter()
+                    .take_while(|r| r.time <= prediction_end)
                     .map(|r| {
                         let (x, y) = r.pos;
                         let pressure = r.pressure;
                         Element::new(na::vector![x, y], pressure)
+                            .with_tilt_angle(element.tilt_angle)
                     })
                     .collect::<Vec<Element>>(),
                 Err(e) => {
@@ -243,7 +313,10 @@ impl PenPathModeledBuilder {
         self.start_time = now;
         self.last_element_time = now;
         self.last_element = element;
-        if let Err(e) = self.stroke_modeler.reset_w_params(*MODELER_PARAMS) {
+        if let Err(e) = self
+            .stroke_modeler
+            .reset_w_params(self.input_smoothing.modeler_params())
+        {
             error!("Resetting stroke modeler failed while restarting, Err: {e:?}");
             return;
         }
@@ -258,7 +331,7 @@ impl PenPathModeledBuilder {
                 self.buffer.extend(results.into_iter().map(|r| {
                     let (x, y) = r.pos;
                     let pressure = r.pressure;
-                    Element::new(na::vector![x, y], pressure)
+                    Element::new(na::vector![x, y], pressure).with_tilt_angle(element.tilt_angle)
                 }));
             }
             Err(e) => {
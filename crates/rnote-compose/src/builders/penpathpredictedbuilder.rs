@@ -0,0 +1,145 @@
+// Imports
+use super::buildable::{Buildable, BuilderCreator, BuilderProgress};
+use crate::PenEvent;
+use crate::penpath::{Element, Segment};
+use crate::style::Composer;
+use crate::{Constraints, EventResult};
+use crate::{PenPath, Style};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::RenderContext;
+use std::time::Instant;
+
+#[derive(Debug, Clone)]
+/// Pen path predicted builder
+///
+/// Wraps a pen path builder that receives the real pen events, and additionally accepts elements that are predicted
+/// ahead of the real input. The predicted elements form provisional segments that continue the path from the last
+/// real element. They are never emitted, but retracted and replaced by the segments built from the real samples
+/// once those arrive.
+pub struct PenPathPredictedBuilder<B>
+where
+    B: Buildable<Emit = Segment>,
+{
+    /// The builder that builds the segments from the real elements.
+    inner: B,
+    /// The last real element.
+    last_element: Element,
+    /// The predicted elements continuing from the last real element.
+    predicted: Vec<Element>,
+}
+
+impl<B> BuilderCreator for PenPathPredictedBuilder<B>
+where
+    B: Buildable<Emit = Segment> + BuilderCreator,
+{
+    fn start(element: Element, now: Instant) -> Self {
+        Self {
+            inner: B::start(element, now),
+            last_element: element,
+            predicted: vec![],
+        }
+    }
+}
+
+impl<B> Buildable for PenPathPredictedBuilder<B>
+where
+    B: Buildable<Emit = Segment>,
+{
+    type Emit = Segment;
+
+    fn handle_event(
+        &mut self,
+        event: PenEvent,
+        now: Instant,
+        constraints: Constraints,
+    ) -> EventResult<BuilderProgress<Self::Emit>> {
+        match &event {
+            PenEvent::Down { element, .. } | PenEvent::Up { element, .. } => {
+                // the real sample supersedes the prediction
+                self.last_element = *element;
+                self.predicted.clear();
+            }
+            PenEvent::Cancel => {
+                self.predicted.clear();
+            }
+            PenEvent::Proximity { .. }
+            | PenEvent::KeyPressed { .. }
+            | PenEvent::Text { .. }
+            | PenEvent::LongPress { .. }
+            | PenEvent::DoubleTap { .. } => {}
+        }
+
+        self.inner.handle_event(event, now, constraints)
+    }
+
+    fn bounds(&self, style: &Style, zoom: f64) -> Option<Aabb> {
+        let inner_bounds = self.inner.bounds(style, zoom);
+        let provisional_bounds = self
+            .provisional_path()
+            .map(|provisional_path| provisional_path.composed_bounds(style));
+
+        match (inner_bounds, provisional_bounds) {
+            (Some(inner_bounds), Some(provisional_bounds)) => {
+                Some(inner_bounds.merged(&provisional_bounds))
+            }
+            (inner_bounds, provisional_bounds) => inner_bounds.or(provisional_bounds),
+        }
+    }
+
+    fn draw_styled(&self, cx: &mut piet_cairo::CairoRenderContext, style: &Style, zoom: f64) {
+        self.inner.draw_styled(cx, style, zoom);
+
+        cx.save().unwrap();
+
+        if let Some(provisional_path) = self.provisional_path() {
+            provisional_path.draw_composed(cx, style);
+        }
+
+        cx.restore().unwrap();
+    }
+}
+
+impl<B> PenPathPredictedBuilder<B>
+where
+    B: Buildable<Emit = Segment>,
+{
+    /// Replace the predicted elements, retracting the provisional segments of the previous prediction.
+    ///
+    /// The elements are expected to continue from the last real element.
+    pub fn set_predicted(&mut self, predicted: impl IntoIterator<Item = Element>) {
+        self.predicted = predicted.into_iter().collect();
+    }
+
+    /// Retract the provisional segments of the current prediction.
+    pub fn retract_predicted(&mut self) {
+        self.predicted.clear();
+    }
+
+    /// The predicted elements.
+    pub fn predicted(&self) -> &[Element] {
+        &self.predicted
+    }
+
+    /// The provisional segments from the last real element through the predicted elements.
+    pub fn provisional_segments(&self) -> Vec<Segment> {
+        self.predicted
+            .iter()
+            .map(|&end| Segment::LineTo { end })
+            .collect()
+    }
+
+    /// The inner builder that builds the segments from the real elements.
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    fn provisional_path(&self) -> Option<PenPath> {
+        if self.predicted.is_empty() {
+            return None;
+        }
+        Some(PenPath::new_w_segments(
+            self.last_element,
+            self.provisional_segments(),
+        ))
+    }
+}
@@ -49,9 +49,11 @@ impl Buildable for PenPathSimpleBuilder {
 
                 BuilderProgress::Finished(segments)
             }
-            PenEvent::Proximity { .. } | PenEvent::KeyPressed { .. } | PenEvent::Text { .. } => {
-                BuilderProgress::InProgress
-            }
+            PenEvent::Proximity { .. }
+            | PenEvent::KeyPressed { .. }
+            | PenEvent::Text { .. }
+            | PenEvent::LongPress { .. }
+            | PenEvent::DoubleTap { .. } => BuilderProgress::InProgress,
             PenEvent::Cancel => {
                 self.reset();
                 BuilderProgress::Finished(vec![])
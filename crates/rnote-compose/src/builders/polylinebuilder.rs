@@ -65,7 +65,9 @@ impl Buildable for PolylineBuilder {
                 self.pen_state = PenState::Down;
                 self.pen_pos = element.pos;
                 let last_pos = self.path.last().copied().unwrap_or(self.start);
-                self.current = constraints.constrain(element.pos - last_pos) + last_pos;
+                self.current = constraints
+                    .constrain_w_last_segment(element.pos - last_pos, self.last_segment())
+                    + last_pos;
                 BuilderProgress::InProgress
             }
             PenEvent::Up { element, .. } => {
@@ -91,7 +93,9 @@ impl Buildable for PolylineBuilder {
                 }
                 _ => BuilderProgress::InProgress,
             },
-            PenEvent::Text { .. } => BuilderProgress::InProgress,
+            PenEvent::Text { .. } | PenEvent::LongPress { .. } | PenEvent::DoubleTap { .. } => {
+                BuilderProgress::InProgress
+            }
             PenEvent::Cancel => {
                 self.pen_state = PenState::Up;
                 self.finish = false;
@@ -157,4 +161,16 @@ impl PolylineBuilder {
         (pos - self.path.last().copied().unwrap_or(self.start)).magnitude()
             < Self::FINISH_THRESHOLD_DIST
     }
+
+    /// The direction of the last added segment, if there is one.
+    fn last_segment(&self) -> Option<na::Vector2<f64>> {
+        let last = *self.path.last()?;
+        let prev = self
+            .path
+            .len()
+            .checked_sub(2)
+            .map(|i| self.path[i])
+            .unwrap_or(self.start);
+        Some(last - prev)
+    }
 }
@@ -0,0 +1,87 @@
+// Imports
+use super::buildable::{Buildable, BuilderCreator, BuilderProgress};
+use crate::eventresult::EventPropagation;
+use crate::penevent::{PenEvent, PenState};
+use crate::penpath::Element;
+use crate::shapes::RegularPolygon;
+use crate::style::{Composer, indicators};
+use crate::{Constraints, EventResult};
+use crate::{Shape, Style};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::RenderContext;
+use std::time::Instant;
+
+/// Regular polygon builder.
+#[derive(Debug, Clone)]
+pub struct RegularPolygonBuilder {
+    /// Center position.
+    center: na::Vector2<f64>,
+    /// Current position, the first vertex.
+    current: na::Vector2<f64>,
+}
+
+impl BuilderCreator for RegularPolygonBuilder {
+    fn start(element: Element, _now: Instant) -> Self {
+        Self {
+            center: element.pos,
+            current: element.pos,
+        }
+    }
+}
+
+impl Buildable for RegularPolygonBuilder {
+    type Emit = Shape;
+
+    fn handle_event(
+        &mut self,
+        event: PenEvent,
+        _now: Instant,
+        constraints: Constraints,
+    ) -> EventResult<BuilderProgress<Self::Emit>> {
+        let progress = match event {
+            PenEvent::Down { element, .. } => {
+                self.current = constraints.constrain(element.pos - self.center) + self.center;
+                BuilderProgress::InProgress
+            }
+            PenEvent::Up { .. } => BuilderProgress::Finished(vec![Shape::RegularPolygon(
+                self.state_as_regular_polygon(),
+            )]),
+            _ => BuilderProgress::InProgress,
+        };
+
+        EventResult {
+            handled: true,
+            propagate: EventPropagation::Stop,
+            progress,
+        }
+    }
+
+    fn bounds(&self, style: &Style, zoom: f64) -> Option<Aabb> {
+        Some(
+            self.state_as_regular_polygon()
+                .composed_bounds(style)
+                .loosened(indicators::POS_INDICATOR_RADIUS / zoom),
+        )
+    }
+
+    fn draw_styled(&self, cx: &mut piet_cairo::CairoRenderContext, style: &Style, zoom: f64) {
+        cx.save().unwrap();
+        let shape = self.state_as_regular_polygon();
+        shape.draw_composed(cx, style);
+
+        indicators::draw_pos_indicator(cx, PenState::Up, self.center, zoom);
+        indicators::draw_pos_indicator(cx, PenState::Down, self.current, zoom);
+        cx.restore().unwrap();
+    }
+}
+
+impl RegularPolygonBuilder {
+    /// The current state as a regular polygon.
+    pub fn state_as_regular_polygon(&self) -> RegularPolygon {
+        RegularPolygon::from_center_and_vertex(
+            self.center,
+            self.current,
+            RegularPolygon::N_SIDES_DEFAULT,
+        )
+    }
+}
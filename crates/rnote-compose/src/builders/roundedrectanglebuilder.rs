@@ -0,0 +1,92 @@
+// Imports
+use super::buildable::{Buildable, BuilderCreator, BuilderProgress};
+use crate::eventresult::EventPropagation;
+use crate::penevent::{PenEvent, PenState};
+use crate::penpath::Element;
+use crate::shapes::RoundedRectangle;
+use crate::style::{Composer, indicators};
+use crate::{Constraints, EventResult};
+use crate::{Shape, Style};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::RenderContext;
+use std::time::Instant;
+
+/// Rounded rectangle builder.
+#[derive(Debug, Clone)]
+pub struct RoundedRectangleBuilder {
+    /// Start position.
+    start: na::Vector2<f64>,
+    /// Current position.
+    current: na::Vector2<f64>,
+}
+
+impl BuilderCreator for RoundedRectangleBuilder {
+    fn start(element: Element, _now: Instant) -> Self {
+        Self {
+            start: element.pos,
+            current: element.pos,
+        }
+    }
+}
+
+impl Buildable for RoundedRectangleBuilder {
+    type Emit = Shape;
+
+    fn handle_event(
+        &mut self,
+        event: PenEvent,
+        _now: Instant,
+        constraints: Constraints,
+    ) -> EventResult<BuilderProgress<Self::Emit>> {
+        let progress = match event {
+            PenEvent::Down { element, .. } => {
+                self.current = constraints.constrain(element.pos - self.start) + self.start;
+                BuilderProgress::InProgress
+            }
+            PenEvent::Up { .. } => BuilderProgress::Finished(vec![Shape::RoundedRectangle(
+                self.state_as_rounded_rect(),
+            )]),
+            _ => BuilderProgress::InProgress,
+        };
+
+        EventResult {
+            handled: true,
+            propagate: EventPropagation::Stop,
+            progress,
+        }
+    }
+
+    fn bounds(&self, style: &Style, zoom: f64) -> Option<Aabb> {
+        Some(
+            self.state_as_rounded_rect()
+                .composed_bounds(style)
+                .loosened(indicators::POS_INDICATOR_RADIUS / zoom),
+        )
+    }
+
+    fn draw_styled(&self, cx: &mut piet_cairo::CairoRenderContext, style: &Style, zoom: f64) {
+        cx.save().unwrap();
+        let rounded_rect = self.state_as_rounded_rect();
+        rounded_rect.draw_composed(cx, style);
+
+        indicators::draw_pos_indicator(cx, PenState::Up, self.start, zoom);
+        indicators::draw_pos_indicator(cx, PenState::Down, self.current, zoom);
+        cx.restore().unwrap();
+    }
+}
+
+impl RoundedRectangleBuilder {
+    /// The corner radius relative to the shorter side.
+    pub const CORNER_RADIUS_RATIO: f64 = 0.2;
+
+    /// The current state as a rounded rectangle.
+    pub fn state_as_rounded_rect(&self) -> RoundedRectangle {
+        let extents = (self.current - self.start).abs();
+
+        RoundedRectangle::from_corners(
+            self.start,
+            self.current,
+            extents.min() * Self::CORNER_RADIUS_RATIO,
+        )
+    }
+}
@@ -0,0 +1,176 @@
+// Imports
+use super::buildable::{Buildable, BuilderCreator, BuilderProgress};
+use crate::constraints::ConstraintRatio;
+use crate::eventresult::EventPropagation;
+use crate::penevent::{KeyboardKey, PenEvent, PenState};
+use crate::penpath::Element;
+use crate::shapes::Spline;
+use crate::style::{Composer, indicators};
+use crate::{Constraints, EventResult};
+use crate::{Shape, Style};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::RenderContext;
+use std::time::Instant;
+
+/// Spline builder, for smooth curves through points like drawn with a french curve.
+#[derive(Debug, Clone)]
+pub struct SplineBuilder {
+    /// Start position.
+    start: na::Vector2<f64>,
+    /// Position of the next/current point.
+    current: na::Vector2<f64>,
+    /// The points after the start.
+    path: Vec<na::Vector2<f64>>,
+    /// Pen state.
+    pen_state: PenState,
+    /// Pen position.
+    pen_pos: na::Vector2<f64>,
+    /// Finish the spline on the next `PenEvent::Up`.
+    finish: bool,
+}
+
+impl BuilderCreator for SplineBuilder {
+    fn start(element: Element, _now: Instant) -> Self {
+        Self {
+            start: element.pos,
+            current: element.pos,
+            path: Vec::new(),
+            pen_state: PenState::Down,
+            pen_pos: element.pos,
+            finish: false,
+        }
+    }
+}
+
+impl Buildable for SplineBuilder {
+    type Emit = Shape;
+
+    fn handle_event(
+        &mut self,
+        event: PenEvent,
+        _now: Instant,
+        mut constraints: Constraints,
+    ) -> EventResult<BuilderProgress<Self::Emit>> {
+        // we always want to allow horizontal and vertical constraints while building a spline
+        constraints.ratios.insert(ConstraintRatio::Horizontal);
+        constraints.ratios.insert(ConstraintRatio::Vertical);
+
+        let progress = match event {
+            PenEvent::Down { element, .. } => {
+                if (self.pen_state == PenState::Up || self.pen_state == PenState::Proximity)
+                    && self.pos_in_finish(element.pos)
+                {
+                    self.finish = true;
+                }
+                self.pen_state = PenState::Down;
+                self.pen_pos = element.pos;
+                let last_pos = self.path.last().copied().unwrap_or(self.start);
+                self.current = constraints
+                    .constrain_w_last_segment(element.pos - last_pos, self.last_segment())
+                    + last_pos;
+                BuilderProgress::InProgress
+            }
+            PenEvent::Up { element, .. } => {
+                if self.finish {
+                    BuilderProgress::Finished(vec![Shape::Spline(self.state_as_spline())])
+                } else {
+                    if self.pen_state == PenState::Down {
+                        self.path.push(self.current);
+                    }
+                    self.pen_state = PenState::Up;
+                    self.pen_pos = element.pos;
+                    BuilderProgress::InProgress
+                }
+            }
+            PenEvent::Proximity { element, .. } => {
+                self.pen_state = PenState::Proximity;
+                self.pen_pos = element.pos;
+                BuilderProgress::InProgress
+            }
+            PenEvent::KeyPressed { keyboard_key, .. } => match keyboard_key {
+                KeyboardKey::Escape | KeyboardKey::CarriageReturn | KeyboardKey::Linefeed => {
+                    BuilderProgress::Finished(vec![Shape::Spline(self.state_as_spline())])
+                }
+                _ => BuilderProgress::InProgress,
+            },
+            PenEvent::Text { .. } | PenEvent::LongPress { .. } | PenEvent::DoubleTap { .. } => {
+                BuilderProgress::InProgress
+            }
+            PenEvent::Cancel => {
+                self.pen_state = PenState::Up;
+                self.finish = false;
+                BuilderProgress::Finished(vec![])
+            }
+        };
+
+        EventResult {
+            handled: true,
+            propagate: EventPropagation::Stop,
+            progress,
+        }
+    }
+
+    fn bounds(&self, style: &Style, zoom: f64) -> Option<Aabb> {
+        let mut spline = self.state_as_spline();
+        if !self.finish {
+            spline.path.push(self.current);
+        }
+        Some(
+            spline
+                .composed_bounds(style)
+                .loosened(indicators::POS_INDICATOR_RADIUS / zoom),
+        )
+    }
+
+    fn draw_styled(&self, cx: &mut piet_cairo::CairoRenderContext, style: &Style, zoom: f64) {
+        cx.save().unwrap();
+
+        let mut spline = self.state_as_spline();
+        if !self.finish {
+            spline.path.push(self.current);
+        }
+
+        spline.draw_composed(cx, style);
+        indicators::draw_pos_indicator(cx, PenState::Up, self.start, zoom);
+        if !self.finish {
+            if self.pos_in_finish(self.pen_pos)
+                && (self.pen_state == PenState::Up || self.pen_state == PenState::Proximity)
+            {
+                indicators::draw_finish_indicator(cx, self.pen_state, self.current, zoom);
+            } else {
+                indicators::draw_pos_indicator(cx, self.pen_state, self.current, zoom);
+            }
+        }
+
+        cx.restore().unwrap();
+    }
+}
+
+impl SplineBuilder {
+    const FINISH_THRESHOLD_DIST: f64 = 8.0;
+
+    /// The current state as a spline.
+    pub fn state_as_spline(&self) -> Spline {
+        Spline {
+            start: self.start,
+            path: self.path.clone(),
+        }
+    }
+
+    fn pos_in_finish(&self, pos: na::Vector2<f64>) -> bool {
+        (pos - self.path.last().copied().unwrap_or(self.start)).magnitude()
+            < Self::FINISH_THRESHOLD_DIST
+    }
+
+    /// The direction of the last added segment, if there is one.
+    fn last_segment(&self) -> Option<na::Vector2<f64>> {
+        let last = *self.path.last()?;
+        let prev = self
+            .path
+            .len()
+            .checked_sub(2)
+            .map(|i| self.path[i])
+            .unwrap_or(self.start);
+        Some(last - prev)
+    }
+}
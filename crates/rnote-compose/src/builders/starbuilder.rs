@@ -0,0 +1,88 @@
+// Imports
+use super::buildable::{Buildable, BuilderCreator, BuilderProgress};
+use crate::eventresult::EventPropagation;
+use crate::penevent::{PenEvent, PenState};
+use crate::penpath::Element;
+use crate::shapes::Star;
+use crate::style::{Composer, indicators};
+use crate::{Constraints, EventResult};
+use crate::{Shape, Style};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::RenderContext;
+use std::time::Instant;
+
+/// Star builder.
+#[derive(Debug, Clone)]
+pub struct StarBuilder {
+    /// Center position.
+    center: na::Vector2<f64>,
+    /// Current position, the first tip.
+    current: na::Vector2<f64>,
+}
+
+impl BuilderCreator for StarBuilder {
+    fn start(element: Element, _now: Instant) -> Self {
+        Self {
+            center: element.pos,
+            current: element.pos,
+        }
+    }
+}
+
+impl Buildable for StarBuilder {
+    type Emit = Shape;
+
+    fn handle_event(
+        &mut self,
+        event: PenEvent,
+        _now: Instant,
+        constraints: Constraints,
+    ) -> EventResult<BuilderProgress<Self::Emit>> {
+        let progress = match event {
+            PenEvent::Down { element, .. } => {
+                self.current = constraints.constrain(element.pos - self.center) + self.center;
+                BuilderProgress::InProgress
+            }
+            PenEvent::Up { .. } => {
+                BuilderProgress::Finished(vec![Shape::Star(self.state_as_star())])
+            }
+            _ => BuilderProgress::InProgress,
+        };
+
+        EventResult {
+            handled: true,
+            propagate: EventPropagation::Stop,
+            progress,
+        }
+    }
+
+    fn bounds(&self, style: &Style, zoom: f64) -> Option<Aabb> {
+        Some(
+            self.state_as_star()
+                .composed_bounds(style)
+                .loosened(indicators::POS_INDICATOR_RADIUS / zoom),
+        )
+    }
+
+    fn draw_styled(&self, cx: &mut piet_cairo::CairoRenderContext, style: &Style, zoom: f64) {
+        cx.save().unwrap();
+        let shape = self.state_as_star();
+        shape.draw_composed(cx, style);
+
+        indicators::draw_pos_indicator(cx, PenState::Up, self.center, zoom);
+        indicators::draw_pos_indicator(cx, PenState::Down, self.current, zoom);
+        cx.restore().unwrap();
+    }
+}
+
+impl StarBuilder {
+    /// The current state as a star.
+    pub fn state_as_star(&self) -> Star {
+        Star::from_center_and_tip(
+            self.center,
+            self.current,
+            Star::N_POINTS_DEFAULT,
+            Star::INNER_RADIUS_RATIO_DEFAULT,
+        )
+    }
+}
@@ -123,6 +123,18 @@ impl Color {
             && approx::relative_eq!(self.a as f32, other.a as f32)
     }
 
+    /// The distance to the other color, ranging [0.0 - 1.0].
+    ///
+    /// Zero for equal colors, one between transparent black and opaque white.
+    pub fn distance(&self, other: &Self) -> f64 {
+        (((self.r - other.r).powi(2)
+            + (self.g - other.g).powi(2)
+            + (self.b - other.b).powi(2)
+            + (self.a - other.a).powi(2))
+            / 4.0)
+            .sqrt()
+    }
+
     /// The luma value, ranging [0.0 - 1.0].
     ///
     /// see: <https://en.wikipedia.org/wiki/Luma_(video)>
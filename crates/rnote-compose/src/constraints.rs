@@ -12,17 +12,61 @@ pub struct Constraints {
     /// stores the constraint ratios
     #[serde(rename = "ratios")]
     pub ratios: HashSet<ConstraintRatio>,
+    /// The angle snapping
+    #[serde(rename = "angle_snap")]
+    pub angle_snap: AngleSnap,
+    /// Whether positions snap onto the points of existing geometry
+    #[serde(rename = "object_snap")]
+    pub object_snap: bool,
+    /// The candidate points of existing geometry that positions snap onto
+    #[serde(skip)]
+    pub snap_candidates: Vec<na::Vector2<f64>>,
+    /// The distance within positions snap onto the candidate points
+    #[serde(skip)]
+    pub snap_distance: f64,
 }
 
 impl Constraints {
+    /// The default distance within positions snap onto the candidate points, in surface coordinates.
+    pub const OBJECT_SNAP_DISTANCE: f64 = 10.0;
+
+    /// Snap the absolute position onto the nearest candidate point within the snap distance.
+    ///
+    /// Returns None when object snapping is disabled or no candidate is near enough.
+    pub fn snap_to_objects(&self, pos: na::Vector2<f64>) -> Option<na::Vector2<f64>> {
+        if !self.object_snap {
+            return None;
+        }
+        self.snap_candidates
+            .iter()
+            .map(|candidate| ((candidate - pos).norm(), *candidate))
+            .filter(|(dist, _)| *dist <= self.snap_distance)
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, candidate)| candidate)
+    }
+
     /// Constrain the coordinates of a vector by the current stored constraint ratios
     pub fn constrain(&self, pos: na::Vector2<f64>) -> na::Vector2<f64> {
+        self.constrain_w_last_segment(pos, None)
+    }
+
+    /// Constrain the coordinates of a vector by the current stored constraint ratios and the angle snapping.
+    ///
+    /// The direction of the last segment is used as the reference of the angle snapping
+    /// when it snaps relative to the last segment.
+    pub fn constrain_w_last_segment(
+        &self,
+        pos: na::Vector2<f64>,
+        last_segment: Option<na::Vector2<f64>>,
+    ) -> na::Vector2<f64> {
         if !self.enabled {
             return pos;
         }
         self.ratios
             .iter()
-            .map(|ratio| ((ratio.constrain(pos) - pos).norm(), ratio.constrain(pos)))
+            .map(|ratio| ratio.constrain(pos))
+            .chain(self.angle_snap.constrain(pos, last_segment))
+            .map(|constrained| ((constrained - pos).norm(), constrained))
             .reduce(|(acc_dist, acc_posi), (dist, posi)| {
                 if dist <= acc_dist {
                     (dist, posi)
@@ -35,6 +79,67 @@ impl Constraints {
     }
 }
 
+/// Snaps the angle of a vector to multiples of an increment.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "angle_snap")]
+pub struct AngleSnap {
+    /// Whether angle snapping is enabled
+    #[serde(rename = "enabled")]
+    pub enabled: bool,
+    /// The increment in radians
+    #[serde(rename = "increment")]
+    pub increment: f64,
+    /// Whether the angles are relative to the direction of the last segment instead of the horizontal axis
+    #[serde(rename = "relative_to_last_segment")]
+    pub relative_to_last_segment: bool,
+}
+
+impl Default for AngleSnap {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            increment: 15.0_f64.to_radians(),
+            relative_to_last_segment: false,
+        }
+    }
+}
+
+impl AngleSnap {
+    /// The minimum increment.
+    pub const INCREMENT_MIN: f64 = 0.1 * std::f64::consts::PI / 180.0;
+    /// The maximum increment.
+    pub const INCREMENT_MAX: f64 = std::f64::consts::PI;
+
+    /// Constrain the vector onto the nearest direction that is a multiple of the increment.
+    ///
+    /// Returns None when angle snapping is disabled or the vector has no direction.
+    pub fn constrain(
+        &self,
+        pos: na::Vector2<f64>,
+        last_segment: Option<na::Vector2<f64>>,
+    ) -> Option<na::Vector2<f64>> {
+        if !self.enabled || pos.norm() == 0.0 {
+            return None;
+        }
+        let reference_angle = if self.relative_to_last_segment {
+            last_segment
+                .filter(|last_segment| last_segment.norm() > 0.0)
+                .map(|last_segment| last_segment[1].atan2(last_segment[0]))
+                .unwrap_or(0.0)
+        } else {
+            0.0
+        };
+        let increment = self
+            .increment
+            .clamp(Self::INCREMENT_MIN, Self::INCREMENT_MAX);
+        let angle = pos[1].atan2(pos[0]) - reference_angle;
+        let snapped_angle = (angle / increment).round() * increment + reference_angle;
+        let direction = na::vector![snapped_angle.cos(), snapped_angle.sin()];
+
+        Some(direction * pos.dot(&direction))
+    }
+}
+
 /// A constraint ratio.
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename = "constraint_ratio")]
@@ -172,6 +172,8 @@ where
         split_size: na::Vector2<f64>,
         split_order: SplitOrder,
     ) -> Vec<Self>;
+    /// The parts of the Aabb that are not covered by the other, as up to four non-overlapping boxes.
+    fn subtract(&self, other: &Self) -> Vec<Self>;
     /// Converts a Aabb to a kurbo Rectangle
     fn to_kurbo_rect(&self) -> kurbo::Rect;
     /// Converts a kurbo Rectangle to Aabb
@@ -434,6 +436,28 @@ impl AabbExt for Aabb {
         split_aabbs
     }
 
+    fn subtract(&self, other: &Self) -> Vec<Self> {
+        let Some(intersection) = self.intersection(other) else {
+            return vec![*self];
+        };
+        [
+            // top and bottom span the entire width, left and right the height of the intersection
+            Aabb::new(self.mins, na::point![self.maxs[0], intersection.mins[1]]),
+            Aabb::new(na::point![self.mins[0], intersection.maxs[1]], self.maxs),
+            Aabb::new(
+                na::point![self.mins[0], intersection.mins[1]],
+                na::point![intersection.mins[0], intersection.maxs[1]],
+            ),
+            Aabb::new(
+                na::point![intersection.maxs[0], intersection.mins[1]],
+                na::point![self.maxs[0], intersection.maxs[1]],
+            ),
+        ]
+        .into_iter()
+        .filter(|part| part.extents()[0] > 0.0 && part.extents()[1] > 0.0)
+        .collect()
+    }
+
     fn to_kurbo_rect(&self) -> kurbo::Rect {
         kurbo::Rect::from_points(
             self.mins.coords.to_kurbo_point(),
@@ -0,0 +1,159 @@
+// Imports
+use crate::PenEvent;
+use crate::penpath::Element;
+use anyhow::Context;
+use num_derive::{FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Serialize};
+
+/// How the tilt of the stylus is mapped onto the elements.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, FromPrimitive, ToPrimitive,
+)]
+#[serde(rename = "tilt_mapping")]
+pub enum TiltMapping {
+    /// The tilt is ignored.
+    #[default]
+    #[serde(rename = "none")]
+    None,
+    /// A tilted stylus increases the pressure and with it the stroke width, like the side of a pencil.
+    #[serde(rename = "width")]
+    Width,
+}
+
+impl TryFrom<u32> for TiltMapping {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value)
+            .with_context(|| format!("TiltMapping try_from::<u32>() for value {value} failed"))
+    }
+}
+
+/// Transforms applied onto the input elements before they are handled by the pens and builders.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "input_transform")]
+pub struct InputTransform {
+    /// The input pressure which is mapped to no pressure. Lower input pressures are clamped.
+    #[serde(rename = "pressure_min", with = "crate::serialize::f64_dp3")]
+    pub pressure_min: f64,
+    /// The input pressure which is mapped to full pressure. Higher input pressures are clamped.
+    #[serde(rename = "pressure_max", with = "crate::serialize::f64_dp3")]
+    pub pressure_max: f64,
+    /// The gamma of the pressure curve. Values below 1.0 make the pressure rise faster, values above 1.0 slower.
+    #[serde(rename = "pressure_gamma", with = "crate::serialize::f64_dp3")]
+    pub pressure_gamma: f64,
+    /// The tilt mapping.
+    #[serde(rename = "tilt_mapping")]
+    pub tilt_mapping: TiltMapping,
+    /// How strongly the tilt is mapped, between 0.0 and 1.0.
+    #[serde(rename = "tilt_strength", with = "crate::serialize::f64_dp3")]
+    pub tilt_strength: f64,
+    /// Position changes below this distance are damped, to filter out jitter of the input. Disabled when 0.0.
+    #[serde(rename = "jitter_threshold", with = "crate::serialize::f64_dp3")]
+    pub jitter_threshold: f64,
+}
+
+impl Default for InputTransform {
+    fn default() -> Self {
+        Self {
+            pressure_min: 0.0,
+            pressure_max: 1.0,
+            pressure_gamma: 1.0,
+            tilt_mapping: TiltMapping::default(),
+            tilt_strength: 0.5,
+            jitter_threshold: 0.0,
+        }
+    }
+}
+
+impl InputTransform {
+    /// The minimum pressure gamma.
+    pub const PRESSURE_GAMMA_MIN: f64 = 0.1;
+    /// The maximum pressure gamma.
+    pub const PRESSURE_GAMMA_MAX: f64 = 10.0;
+    /// The maximum tilt in degrees, reached when the stylus lies flat.
+    pub const TILT_MAX: f64 = 90.0;
+
+    /// Map the input pressure through the calibrated range and the gamma curve.
+    pub fn map_pressure(&self, pressure: f64) -> f64 {
+        let range = self.pressure_max - self.pressure_min;
+        let normalized = if range > 0.0 {
+            ((pressure - self.pressure_min) / range).clamp(0.0, 1.0)
+        } else {
+            pressure.clamp(0.0, 1.0)
+        };
+        normalized.powf(
+            self.pressure_gamma
+                .clamp(Self::PRESSURE_GAMMA_MIN, Self::PRESSURE_GAMMA_MAX),
+        )
+    }
+
+    /// Map the tilt onto the pressure, according to the tilt mapping.
+    ///
+    /// The tilt is given in degrees along the x- and y-axis.
+    pub fn map_tilt(&self, pressure: f64, tilt: na::Vector2<f64>) -> f64 {
+        match self.tilt_mapping {
+            TiltMapping::None => pressure,
+            TiltMapping::Width => {
+                let amount = tilt_amount(tilt) * self.tilt_strength.clamp(0.0, 1.0);
+                (pressure + (1.0 - pressure) * amount).clamp(0.0, 1.0)
+            }
+        }
+    }
+
+    /// Transform the element with the pressure and tilt mappings, and record its tilt angle.
+    ///
+    /// The tilt is only available for stylus input.
+    pub fn transform_element(&self, element: Element, tilt: Option<na::Vector2<f64>>) -> Element {
+        let mut pressure = self.map_pressure(element.pressure);
+        if let Some(tilt) = tilt {
+            pressure = self.map_tilt(pressure, tilt);
+        }
+        Element::new(element.pos, pressure).with_tilt_angle(tilt.and_then(tilt_angle))
+    }
+}
+
+/// The amount the stylus is tilted, between 0.0 when held upright and 1.0 when it lies flat.
+///
+/// The tilt is given in degrees along the x- and y-axis.
+pub fn tilt_amount(tilt: na::Vector2<f64>) -> f64 {
+    (tilt.magnitude() / InputTransform::TILT_MAX).clamp(0.0, 1.0)
+}
+
+/// The angle in radians of the direction the stylus is tilted towards, or None when it is held upright.
+///
+/// The tilt is given in degrees along the x- and y-axis.
+pub fn tilt_angle(tilt: na::Vector2<f64>) -> Option<f64> {
+    (tilt.magnitude() > 0.0).then(|| tilt[1].atan2(tilt[0]))
+}
+
+/// Filters out the jitter of the positions of consecutive pen down events.
+///
+/// Movements below the threshold are damped, the larger the movement the less it is damped.
+#[derive(Debug, Clone, Default)]
+pub struct JitterFilter {
+    prev: Option<na::Vector2<f64>>,
+}
+
+impl JitterFilter {
+    /// Filter the element position of the event.
+    ///
+    /// Only down events are filtered, the other events that end or interrupt a press reset the filter.
+    pub fn filter(&mut self, mut event: PenEvent, threshold: f64) -> PenEvent {
+        match &mut event {
+            PenEvent::Down { element, .. } if threshold > 0.0 => {
+                if let Some(prev) = self.prev {
+                    let offset = element.pos - prev;
+                    let dist = offset.magnitude();
+                    if dist < threshold {
+                        element.pos = prev + offset * (dist / threshold);
+                    }
+                }
+                self.prev = Some(element.pos);
+            }
+            PenEvent::Down { .. } | PenEvent::KeyPressed { .. } | PenEvent::Text { .. } => {}
+            _ => self.prev = None,
+        }
+        event
+    }
+}
@@ -15,10 +15,18 @@ pub mod constraints;
 pub mod eventresult;
 /// module for extension traits for foreign types
 pub mod ext;
+/// transforms of the pen input
+pub mod inputtransform;
+/// path intersection and boolean operations
+pub mod pathops;
 /// module for pen events
 pub mod penevent;
 /// module for pen paths
 pub mod penpath;
+/// recognition of shapes in freehand paths
+pub mod recognition;
+/// detection of regions enclosed by boundaries
+pub mod regions;
 /// utilities for serializing / deserializing
 pub mod serialize;
 /// module for shapes
@@ -0,0 +1,399 @@
+// Imports
+use crate::PenPath;
+use crate::ext::Vector2Ext;
+use geo::{BooleanOps, Contains};
+use kurbo::{ParamCurve, ParamCurveArclen, Shape};
+
+/// The tolerance used when flattening curves for the path operations.
+pub const FLATTEN_TOLERANCE: f64 = 0.1;
+
+/// An intersection of a pen path with another path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathIntersection {
+    /// The position of the intersection.
+    pub pos: na::Vector2<f64>,
+    /// The arc-length position of the intersection on the pen path.
+    pub length: f64,
+}
+
+/// The intersections of the pen path with the other path, ordered by their arc-length position on the pen path.
+///
+/// The other path is flattened, the curves of the pen path are intersected exactly.
+pub fn pen_path_intersections(pen_path: &PenPath, other: &kurbo::BezPath) -> Vec<PathIntersection> {
+    let other_lines = flattened_lines(other);
+    let mut intersections = vec![];
+    let mut length_before = 0.0;
+    let mut prev = pen_path.start.pos;
+
+    for segment in pen_path.segments.iter() {
+        let seg = segment.to_kurbo_seg(prev);
+
+        for line in other_lines.iter() {
+            intersections.extend(seg.intersect_line(*line).into_iter().map(|intersection| {
+                PathIntersection {
+                    pos: na::Vector2::from_kurbo_point(seg.eval(intersection.segment_t)),
+                    length: length_before
+                        + seg
+                            .subsegment(0.0..intersection.segment_t)
+                            .arclen(PenPath::ARCLEN_ACCURACY),
+                }
+            }));
+        }
+
+        length_before += seg.arclen(PenPath::ARCLEN_ACCURACY);
+        prev = segment.end().pos;
+    }
+
+    intersections.sort_unstable_by(|a, b| a.length.total_cmp(&b.length));
+    // intersections at the joints of the other path's lines are found twice
+    intersections.dedup_by(|a, b| (a.length - b.length).abs() < PenPath::ARCLEN_ACCURACY);
+    intersections
+}
+
+/// The intersection points of two paths.
+///
+/// Both paths are flattened.
+pub fn path_intersections(a: &kurbo::BezPath, b: &kurbo::BezPath) -> Vec<na::Vector2<f64>> {
+    let b_lines = flattened_lines(b);
+
+    flattened_lines(a)
+        .into_iter()
+        .flat_map(|a_line| {
+            b_lines.iter().flat_map(move |b_line| {
+                kurbo::PathSeg::Line(a_line)
+                    .intersect_line(*b_line)
+                    .into_iter()
+                    .map(move |intersection| {
+                        na::Vector2::from_kurbo_point(a_line.eval(intersection.segment_t))
+                    })
+            })
+        })
+        .collect()
+}
+
+/// Split the pen path at its intersections with the region outline, into the parts inside and the parts outside
+/// of the region.
+///
+/// Returns the inside and the outside parts.
+pub fn split_pen_path_by_region(
+    pen_path: &PenPath,
+    region: &kurbo::BezPath,
+) -> (Vec<PenPath>, Vec<PenPath>) {
+    let mut inside = vec![];
+    let mut outside = vec![];
+    let mut rest = pen_path.clone();
+    let mut split_length = 0.0;
+
+    for intersection in pen_path_intersections(pen_path, region) {
+        let (part, remaining) = rest.split_at_length(intersection.length - split_length);
+        split_length = intersection.length;
+        rest = remaining;
+        classify_part(part, region, &mut inside, &mut outside);
+    }
+    classify_part(rest, region, &mut inside, &mut outside);
+
+    (inside, outside)
+}
+
+/// The union of two closed paths. Subpaths are treated as closed.
+pub fn union(a: &kurbo::BezPath, b: &kurbo::BezPath) -> kurbo::BezPath {
+    multi_polygon_to_bezpath(&to_multi_polygon(a).union(&to_multi_polygon(b)))
+}
+
+/// The difference of two closed paths, the area of `a` that is not covered by `b`. Subpaths are treated as closed.
+pub fn difference(a: &kurbo::BezPath, b: &kurbo::BezPath) -> kurbo::BezPath {
+    multi_polygon_to_bezpath(&to_multi_polygon(a).difference(&to_multi_polygon(b)))
+}
+
+/// The intersection of two closed paths, the area covered by both. Subpaths are treated as closed.
+pub fn intersection(a: &kurbo::BezPath, b: &kurbo::BezPath) -> kurbo::BezPath {
+    multi_polygon_to_bezpath(&to_multi_polygon(a).intersection(&to_multi_polygon(b)))
+}
+
+fn classify_part(
+    part: PenPath,
+    region: &kurbo::BezPath,
+    inside: &mut Vec<PenPath>,
+    outside: &mut Vec<PenPath>,
+) {
+    let length = part.length();
+    if length <= PenPath::ARCLEN_ACCURACY {
+        return;
+    }
+    let mid = part.sample_at_length(length * 0.5).pos.to_kurbo_point();
+    if region.contains(mid) {
+        inside.push(part);
+    } else {
+        outside.push(part);
+    }
+}
+
+fn flattened_lines(path: &kurbo::BezPath) -> Vec<kurbo::Line> {
+    let mut lines = vec![];
+    let mut start = kurbo::Point::ZERO;
+    let mut prev = kurbo::Point::ZERO;
+
+    kurbo::flatten(path.iter(), FLATTEN_TOLERANCE, |el| match el {
+        kurbo::PathEl::MoveTo(point) => {
+            start = point;
+            prev = point;
+        }
+        kurbo::PathEl::LineTo(next) => {
+            lines.push(kurbo::Line::new(prev, next));
+            prev = next;
+        }
+        kurbo::PathEl::ClosePath => {
+            if prev != start {
+                lines.push(kurbo::Line::new(prev, start));
+            }
+            prev = start;
+        }
+        _ => {}
+    });
+
+    lines
+}
+
+/// Convert the flattened subpaths to polygons.
+///
+/// Following the even-odd rule, subpaths enclosed by an odd amount of others are holes in the innermost subpath that
+/// encloses them, like the inside of an "O". The remaining subpaths are united.
+fn to_multi_polygon(path: &kurbo::BezPath) -> geo::MultiPolygon<f64> {
+    let mut rings: Vec<Vec<geo::Coord<f64>>> = vec![];
+
+    kurbo::flatten(path.iter(), FLATTEN_TOLERANCE, |el| match el {
+        kurbo::PathEl::MoveTo(point) => rings.push(vec![geo::Coord {
+            x: point.x,
+            y: point.y,
+        }]),
+        kurbo::PathEl::LineTo(point) => {
+            if let Some(ring) = rings.last_mut() {
+                ring.push(geo::Coord {
+                    x: point.x,
+                    y: point.y,
+                });
+            }
+        }
+        _ => {}
+    });
+
+    let rings = rings
+        .into_iter()
+        .filter(|ring| ring.len() >= 3)
+        .map(|ring| geo::Polygon::new(geo::LineString::new(ring), vec![]))
+        .collect::<Vec<geo::Polygon<f64>>>();
+    // The indices of the rings that enclose each ring
+    let parents = rings
+        .iter()
+        .enumerate()
+        .map(|(i, ring)| {
+            let point = geo::Point::from(ring.exterior().0[0]);
+            (0..rings.len())
+                .filter(|&j| j != i && rings[j].contains(&point))
+                .collect::<Vec<usize>>()
+        })
+        .collect::<Vec<Vec<usize>>>();
+
+    let mut polygons = (0..rings.len())
+        .filter(|&i| parents[i].len() % 2 == 0)
+        .map(|i| (i, vec![]))
+        .collect::<Vec<(usize, Vec<geo::LineString<f64>>)>>();
+    for i in (0..rings.len()).filter(|&i| parents[i].len() % 2 == 1) {
+        // The innermost parent is the one that is enclosed by the most others
+        if let Some(parent) = parents[i].iter().copied().max_by_key(|&j| parents[j].len())
+            && let Some((_, interiors)) = polygons.iter_mut().find(|(j, _)| *j == parent)
+        {
+            interiors.push(rings[i].exterior().clone());
+        }
+    }
+
+    polygons
+        .into_iter()
+        .map(|(i, interiors)| geo::Polygon::new(rings[i].exterior().clone(), interiors))
+        .fold(geo::MultiPolygon::new(vec![]), |acc, polygon| {
+            acc.union(&polygon)
+        })
+}
+
+fn multi_polygon_to_bezpath(multi_polygon: &geo::MultiPolygon<f64>) -> kurbo::BezPath {
+    let mut bezpath = kurbo::BezPath::new();
+
+    for polygon in multi_polygon.iter() {
+        for ring in std::iter::once(polygon.exterior()).chain(polygon.interiors().iter()) {
+            let mut coords = ring.coords();
+            let Some(first) = coords.next() else {
+                continue;
+            };
+            bezpath.move_to((first.x, first.y));
+            for coord in coords {
+                bezpath.line_to((coord.x, coord.y));
+            }
+            bezpath.close_path();
+        }
+    }
+
+    bezpath
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::penpath::{Element, Segment};
+
+    fn rect(x0: f64, y0: f64, x1: f64, y1: f64) -> kurbo::BezPath {
+        kurbo::Rect::new(x0, y0, x1, y1).to_path(FLATTEN_TOLERANCE)
+    }
+
+    fn line_path(start: na::Vector2<f64>, end: na::Vector2<f64>) -> PenPath {
+        PenPath::new_w_segments(
+            Element::new(start, 0.5),
+            [Segment::LineTo {
+                end: Element::new(end, 0.5),
+            }],
+        )
+    }
+
+    #[test]
+    fn boolean_ops_overlapping() {
+        let a = rect(0.0, 0.0, 2.0, 2.0);
+        let b = rect(1.0, 1.0, 3.0, 3.0);
+
+        approx::assert_relative_eq!(union(&a, &b).area().abs(), 7.0, epsilon = 1e-6);
+        approx::assert_relative_eq!(difference(&a, &b).area().abs(), 3.0, epsilon = 1e-6);
+        approx::assert_relative_eq!(intersection(&a, &b).area().abs(), 1.0, epsilon = 1e-6);
+        let intersection_bounds = intersection(&a, &b).bounding_box();
+        approx::assert_relative_eq!(intersection_bounds.x0, 1.0, epsilon = 1e-6);
+        approx::assert_relative_eq!(intersection_bounds.y0, 1.0, epsilon = 1e-6);
+        approx::assert_relative_eq!(intersection_bounds.x1, 2.0, epsilon = 1e-6);
+        approx::assert_relative_eq!(intersection_bounds.y1, 2.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn boolean_ops_disjoint() {
+        let a = rect(0.0, 0.0, 2.0, 2.0);
+        let b = rect(3.0, 0.0, 5.0, 2.0);
+
+        approx::assert_relative_eq!(union(&a, &b).area().abs(), 8.0, epsilon = 1e-6);
+        approx::assert_relative_eq!(difference(&a, &b).area().abs(), 4.0, epsilon = 1e-6);
+        assert!(intersection(&a, &b).elements().is_empty());
+    }
+
+    #[test]
+    fn boolean_ops_containment() {
+        let outer = rect(0.0, 0.0, 4.0, 4.0);
+        let inner = rect(1.0, 1.0, 2.0, 2.0);
+
+        approx::assert_relative_eq!(union(&outer, &inner).area().abs(), 16.0, epsilon = 1e-6);
+        // the inner rectangle is cut out as a hole
+        let difference_path = difference(&outer, &inner);
+        approx::assert_relative_eq!(difference_path.area().abs(), 15.0, epsilon = 1e-6);
+        assert!(!difference_path.contains(kurbo::Point::new(1.5, 1.5)));
+        assert!(difference_path.contains(kurbo::Point::new(3.0, 3.0)));
+        assert!(difference(&inner, &outer).elements().is_empty());
+        approx::assert_relative_eq!(
+            intersection(&outer, &inner).area().abs(),
+            1.0,
+            epsilon = 1e-6
+        );
+
+        // subpaths enclosed by others are holes
+        let mut nested = outer.clone();
+        nested.extend(inner.iter());
+        approx::assert_relative_eq!(
+            intersection(&nested, &outer).area().abs(),
+            15.0,
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn boolean_ops_ring() {
+        // a ring with an island inside its hole
+        let mut ring = rect(0.0, 0.0, 10.0, 10.0);
+        ring.extend(rect(3.0, 3.0, 7.0, 7.0).iter());
+        ring.extend(rect(4.5, 4.5, 5.5, 5.5).iter());
+        approx::assert_relative_eq!(union(&ring, &ring).area().abs(), 85.0, epsilon = 1e-6);
+
+        // a band through the ring, the island and the hole
+        let band = rect(-1.0, 4.0, 11.0, 6.0);
+        let difference_path = difference(&ring, &band);
+        approx::assert_relative_eq!(
+            difference_path.area().abs(),
+            85.0 - 12.0 - 1.0,
+            epsilon = 1e-6
+        );
+        assert!(difference_path.contains(kurbo::Point::new(1.0, 1.0)));
+        assert!(!difference_path.contains(kurbo::Point::new(1.0, 5.0)));
+        assert!(!difference_path.contains(kurbo::Point::new(5.0, 3.5)));
+        approx::assert_relative_eq!(
+            intersection(&ring, &band).area().abs(),
+            12.0 + 1.0,
+            epsilon = 1e-6
+        );
+
+        // nothing of the ring is inside its hole, apart from the island
+        approx::assert_relative_eq!(
+            intersection(&ring, &rect(3.5, 3.5, 6.5, 6.5)).area().abs(),
+            1.0,
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn intersections() {
+        let region = rect(1.0, 0.0, 3.0, 2.0);
+
+        let points = path_intersections(&rect(0.0, 0.5, 4.0, 1.5), &region);
+        assert_eq!(points.len(), 4);
+        assert!(path_intersections(&rect(5.0, 0.0, 6.0, 1.0), &region).is_empty());
+
+        let intersections = pen_path_intersections(
+            &line_path(na::vector![0.0, 1.0], na::vector![4.0, 1.0]),
+            &region,
+        );
+        assert_eq!(intersections.len(), 2);
+        approx::assert_relative_eq!(intersections[0].pos, na::vector![1.0, 1.0], epsilon = 1e-6);
+        approx::assert_relative_eq!(intersections[0].length, 1.0, epsilon = 1e-6);
+        approx::assert_relative_eq!(intersections[1].pos, na::vector![3.0, 1.0], epsilon = 1e-6);
+        approx::assert_relative_eq!(intersections[1].length, 3.0, epsilon = 1e-6);
+
+        assert!(
+            pen_path_intersections(
+                &line_path(na::vector![0.0, 5.0], na::vector![4.0, 5.0]),
+                &region
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn split_by_region() {
+        let region = rect(1.0, 0.0, 3.0, 2.0);
+
+        let (inside, outside) = split_pen_path_by_region(
+            &line_path(na::vector![0.0, 1.0], na::vector![4.0, 1.0]),
+            &region,
+        );
+        assert_eq!(inside.len(), 1);
+        approx::assert_relative_eq!(inside[0].length(), 2.0, epsilon = 1e-6);
+        assert_eq!(outside.len(), 2);
+        approx::assert_relative_eq!(outside[0].length(), 1.0, epsilon = 1e-6);
+        approx::assert_relative_eq!(outside[1].length(), 1.0, epsilon = 1e-6);
+
+        // disjoint
+        let (inside, outside) = split_pen_path_by_region(
+            &line_path(na::vector![0.0, 5.0], na::vector![4.0, 5.0]),
+            &region,
+        );
+        assert!(inside.is_empty());
+        assert_eq!(outside.len(), 1);
+
+        // contained
+        let (inside, outside) = split_pen_path_by_region(
+            &line_path(na::vector![1.5, 1.0], na::vector![2.5, 1.0]),
+            &region,
+        );
+        assert_eq!(inside.len(), 1);
+        assert!(outside.is_empty());
+    }
+}
@@ -2,6 +2,7 @@
 use crate::penpath::Element;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::time::Instant;
 
 /// A Pen Event.
 ///
@@ -39,6 +40,22 @@ pub enum PenEvent {
         /// Modifier keys pressed during the event.
         modifier_keys: HashSet<ModifierKey>,
     },
+    /// A long-press event, synthesized by the [PenGestureDetector] when the pen is pressed down without moving.
+    ///
+    /// Is emitted after the down event that exceeded the long-press duration, at most once per press.
+    LongPress {
+        /// The element where the pen was pressed down.
+        element: Element,
+        /// Modifier keys pressed during the event.
+        modifier_keys: HashSet<ModifierKey>,
+    },
+    /// A double-tap event, synthesized by the [PenGestureDetector] after the up event of the second of two taps.
+    DoubleTap {
+        /// The element of the second tap.
+        element: Element,
+        /// Modifier keys pressed during the event.
+        modifier_keys: HashSet<ModifierKey>,
+    },
     /// Text input event.
     Text {
         /// The committed text.
@@ -186,3 +203,135 @@ pub enum PenProgress {
     /// Pen is finished.
     Finished,
 }
+
+/// The thresholds for synthesizing long-press and double-tap events.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "pen_gesture_thresholds")]
+pub struct PenGestureThresholds {
+    /// The duration in seconds the pen needs to be pressed down for a long-press.
+    /// Shorter presses are taps.
+    #[serde(rename = "long_press_duration", with = "crate::serialize::f64_dp3")]
+    pub long_press_duration: f64,
+    /// The maximum interval in seconds between two taps of a double-tap.
+    #[serde(rename = "double_tap_interval", with = "crate::serialize::f64_dp3")]
+    pub double_tap_interval: f64,
+    /// The distance the pen may move while pressed down, and between the two taps of a double-tap.
+    #[serde(rename = "movement_tolerance", with = "crate::serialize::f64_dp3")]
+    pub movement_tolerance: f64,
+}
+
+impl Default for PenGestureThresholds {
+    fn default() -> Self {
+        Self {
+            long_press_duration: 0.5,
+            double_tap_interval: 0.3,
+            movement_tolerance: 8.0,
+        }
+    }
+}
+
+/// An input preprocessing stage that synthesizes long-press and double-tap events from the pen event stream.
+#[derive(Debug, Clone, Default)]
+pub struct PenGestureDetector {
+    press: Option<GesturePress>,
+    last_tap: Option<(Element, Instant)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GesturePress {
+    start: Element,
+    start_time: Instant,
+    /// Set when the press can't be a long-press or tap anymore.
+    invalidated: bool,
+}
+
+impl PenGestureDetector {
+    /// Process the event, returning the event itself followed by the synthesized events.
+    pub fn process(
+        &mut self,
+        event: PenEvent,
+        now: Instant,
+        thresholds: &PenGestureThresholds,
+    ) -> Vec<PenEvent> {
+        let synthesized = match &event {
+            PenEvent::Down {
+                element,
+                modifier_keys,
+            } => {
+                let press = self.press.get_or_insert(GesturePress {
+                    start: *element,
+                    start_time: now,
+                    invalidated: false,
+                });
+
+                if !press.invalidated
+                    && (element.pos - press.start.pos).magnitude() > thresholds.movement_tolerance
+                {
+                    press.invalidated = true;
+                }
+                if !press.invalidated
+                    && now.duration_since(press.start_time).as_secs_f64()
+                        >= thresholds.long_press_duration
+                {
+                    // a long-press is neither a tap
+                    press.invalidated = true;
+                    self.last_tap = None;
+                    Some(PenEvent::LongPress {
+                        element: press.start,
+                        modifier_keys: modifier_keys.clone(),
+                    })
+                } else {
+                    None
+                }
+            }
+            PenEvent::Up {
+                element,
+                modifier_keys,
+            } => self.press.take().and_then(|press| {
+                let is_tap = !press.invalidated
+                    && (element.pos - press.start.pos).magnitude() <= thresholds.movement_tolerance
+                    && now.duration_since(press.start_time).as_secs_f64()
+                        < thresholds.long_press_duration;
+                if !is_tap {
+                    self.last_tap = None;
+                    return None;
+                }
+
+                match self.last_tap.take() {
+                    Some((last_element, last_time))
+                        if now.duration_since(last_time).as_secs_f64()
+                            <= thresholds.double_tap_interval
+                            && (element.pos - last_element.pos).magnitude()
+                                <= thresholds.movement_tolerance =>
+                    {
+                        Some(PenEvent::DoubleTap {
+                            element: *element,
+                            modifier_keys: modifier_keys.clone(),
+                        })
+                    }
+                    _ => {
+                        self.last_tap = Some((*element, now));
+                        None
+                    }
+                }
+            }),
+            PenEvent::Cancel => {
+                self.reset();
+                None
+            }
+            PenEvent::Proximity { .. }
+            | PenEvent::KeyPressed { .. }
+            | PenEvent::Text { .. }
+            | PenEvent::LongPress { .. }
+            | PenEvent::DoubleTap { .. } => None,
+        };
+
+        std::iter::once(event).chain(synthesized).collect()
+    }
+
+    /// Reset the state, discarding the current press and the last tap.
+    pub fn reset(&mut self) {
+        self.press = None;
+        self.last_tap = None;
+    }
+}
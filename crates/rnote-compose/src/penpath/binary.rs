@@ -0,0 +1,399 @@
+// Imports
+use super::{Element, PenPath, Segment};
+use anyhow::Context;
+use base64::Engine;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::cell::Cell;
+
+/// The version of the binary encoding.
+const VERSION: u8 = 1;
+/// Flag that is set when the path has timestamps.
+const FLAG_TIMESTAMPS: u8 = 1 << 0;
+const TAG_LINE_TO: u8 = 0;
+const TAG_QUADBEZ_TO: u8 = 1;
+const TAG_CUBBEZ_TO: u8 = 2;
+/// The minimum encoded length of a segment, a line-to segment.
+const SEGMENT_LEN_MIN: usize = 1 + 8 + 2;
+
+thread_local! {
+    /// Whether pen paths are serialized in their binary encoding on the current thread.
+    static BINARY_ENCODING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Serialize all pen paths in their compact binary encoding while the closure runs on the current thread.
+///
+/// Deserializing a [PenPath] accepts both the regular and the binary encoding.
+pub fn with_binary_encoding<T>(f: impl FnOnce() -> T) -> T {
+    /// Restores the previous state, also when the closure panics.
+    struct Reset(bool);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            BINARY_ENCODING.set(self.0);
+        }
+    }
+
+    let _reset = Reset(BINARY_ENCODING.replace(true));
+    f()
+}
+
+/// Whether pen paths are currently serialized in their binary encoding, see [with_binary_encoding].
+pub(super) fn binary_encoding_enabled() -> bool {
+    BINARY_ENCODING.get()
+}
+
+impl PenPath {
+    /// Encode the path in a compact binary encoding.
+    ///
+    /// The start position is stored exactly, the following positions as single precision deltas to the previous
+    /// segment end and the pressures quantized to 16 bit. The deltas are taken from the decoded positions, so the
+    /// rounding errors don't accumulate along the path.
+    pub fn to_binary(&self) -> Vec<u8> {
+        let timestamps = self.timestamps();
+        let mut bytes =
+            Vec::with_capacity(2 + 16 + 2 + 4 + self.segments.len() * (SEGMENT_LEN_MIN + 4));
+
+        bytes.push(VERSION);
+        bytes.push(if timestamps.is_some() {
+            FLAG_TIMESTAMPS
+        } else {
+            0
+        });
+        bytes.extend(self.start.pos[0].to_le_bytes());
+        bytes.extend(self.start.pos[1].to_le_bytes());
+        write_pressure(&mut bytes, self.start.pressure);
+        bytes.extend((self.segments.len() as u32).to_le_bytes());
+
+        let mut prev = self.start.pos;
+        for segment in self.segments.iter() {
+            match segment {
+                Segment::LineTo { end } => {
+                    bytes.push(TAG_LINE_TO);
+                    prev = write_delta(&mut bytes, prev, end.pos);
+                    write_pressure(&mut bytes, end.pressure);
+                }
+                Segment::QuadBezTo { cp, end } => {
+                    bytes.push(TAG_QUADBEZ_TO);
+                    write_delta(&mut bytes, prev, *cp);
+                    prev = write_delta(&mut bytes, prev, end.pos);
+                    write_pressure(&mut bytes, end.pressure);
+                }
+                Segment::CubBezTo { cp1, cp2, end } => {
+                    bytes.push(TAG_CUBBEZ_TO);
+                    write_delta(&mut bytes, prev, *cp1);
+                    write_delta(&mut bytes, prev, *cp2);
+                    prev = write_delta(&mut bytes, prev, end.pos);
+                    write_pressure(&mut bytes, end.pressure);
+                }
+            }
+        }
+
+        if let Some(timestamps) = timestamps {
+            for timestamp in timestamps {
+                bytes.extend((*timestamp as f32).to_le_bytes());
+            }
+        }
+
+        bytes
+    }
+
+    /// Decode the path from its binary encoding, see [PenPath::to_binary].
+    pub fn from_binary(bytes: &[u8]) -> anyhow::Result<Self> {
+        let mut reader = Reader { bytes };
+
+        let version = reader.read_u8()?;
+        if version != VERSION {
+            return Err(anyhow::anyhow!(
+                "unsupported pen path binary encoding version {version}"
+            ));
+        }
+        let flags = reader.read_u8()?;
+        let start = Element::new(
+            na::vector![reader.read_f64()?, reader.read_f64()?],
+            reader.read_pressure()?,
+        );
+        let n_segments = reader.read_u32()? as usize;
+
+        // don't trust the count for the allocation, the bytes might be corrupted
+        let mut segments = Vec::with_capacity(n_segments.min(reader.bytes.len() / SEGMENT_LEN_MIN));
+        let mut prev = start.pos;
+        for _ in 0..n_segments {
+            let segment = match reader.read_u8()? {
+                TAG_LINE_TO => {
+                    prev += reader.read_delta()?;
+                    Segment::LineTo {
+                        end: Element::new(prev, reader.read_pressure()?),
+                    }
+                }
+                TAG_QUADBEZ_TO => {
+                    let cp = prev + reader.read_delta()?;
+                    prev += reader.read_delta()?;
+                    Segment::QuadBezTo {
+                        cp,
+                        end: Element::new(prev, reader.read_pressure()?),
+                    }
+                }
+                TAG_CUBBEZ_TO => {
+                    let cp1 = prev + reader.read_delta()?;
+                    let cp2 = prev + reader.read_delta()?;
+                    prev += reader.read_delta()?;
+                    Segment::CubBezTo {
+                        cp1,
+                        cp2,
+                        end: Element::new(prev, reader.read_pressure()?),
+                    }
+                }
+                tag => return Err(anyhow::anyhow!("invalid pen path segment tag {tag}")),
+            };
+            segments.push(segment);
+        }
+
+        let timestamps = if flags & FLAG_TIMESTAMPS != 0 {
+            Some(
+                (0..n_segments)
+                    .map(|_| reader.read_f32().map(f64::from))
+                    .collect::<anyhow::Result<Vec<f64>>>()?,
+            )
+        } else {
+            None
+        };
+
+        Ok(Self {
+            start,
+            segments,
+            timestamps,
+        })
+    }
+}
+
+/// Serialize the pen path in its binary encoding, base64 encoded in the field `binary`.
+pub(super) fn serialize<S>(pen_path: &PenPath, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut state = serializer.serialize_struct("pen_path", 1)?;
+    state.serialize_field("binary", &Base64(&pen_path.to_binary()))?;
+    state.end()
+}
+
+/// Bytes that are serialized base64 encoded, written straight into the serializer.
+struct Base64<'a>(&'a [u8]);
+
+impl Serialize for Base64<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&base64::display::Base64Display::new(
+            self.0,
+            &base64::engine::general_purpose::STANDARD,
+        ))
+    }
+}
+
+/// Decode the base64 encoded binary form of the pen path.
+pub(super) fn decode_base64(binary: &str) -> anyhow::Result<PenPath> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(binary.as_bytes())
+        .context("decoding base64 failed")?;
+    PenPath::from_binary(&bytes)
+}
+
+/// Write the delta between the positions and return the position as it is decoded.
+fn write_delta(
+    bytes: &mut Vec<u8>,
+    prev: na::Vector2<f64>,
+    pos: na::Vector2<f64>,
+) -> na::Vector2<f64> {
+    let delta = pos - prev;
+    let (dx, dy) = (delta[0] as f32, delta[1] as f32);
+    bytes.extend(dx.to_le_bytes());
+    bytes.extend(dy.to_le_bytes());
+    prev + na::vector![f64::from(dx), f64::from(dy)]
+}
+
+fn write_pressure(bytes: &mut Vec<u8>, pressure: f64) {
+    let quantized = (pressure.clamp(0.0, 1.0) * f64::from(u16::MAX)).round() as u16;
+    bytes.extend(quantized.to_le_bytes());
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> anyhow::Result<[u8; N]> {
+        let (taken, rest) = self
+            .bytes
+            .split_first_chunk::<N>()
+            .context("unexpected end of pen path binary encoding")?;
+        self.bytes = rest;
+        Ok(*taken)
+    }
+
+    fn read_u8(&mut self) -> anyhow::Result<u8> {
+        Ok(u8::from_le_bytes(self.take()?))
+    }
+
+    fn read_u32(&mut self) -> anyhow::Result<u32> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    fn read_f32(&mut self) -> anyhow::Result<f32> {
+        Ok(f32::from_le_bytes(self.take()?))
+    }
+
+    fn read_f64(&mut self) -> anyhow::Result<f64> {
+        Ok(f64::from_le_bytes(self.take()?))
+    }
+
+    fn read_pressure(&mut self) -> anyhow::Result<f64> {
+        Ok(f64::from(u16::from_le_bytes(self.take()?)) / f64::from(u16::MAX))
+    }
+
+    fn read_delta(&mut self) -> anyhow::Result<na::Vector2<f64>> {
+        Ok(na::vector![
+            f64::from(self.read_f32()?),
+            f64::from(self.read_f32()?)
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The maximum error of the positions, stored as single precision deltas.
+    const POS_TOLERANCE: f64 = 1e-4;
+    /// The maximum error of the pressures, half of the quantization step.
+    const PRESSURE_TOLERANCE: f64 = 0.5 / u16::MAX as f64;
+    /// The maximum error of the timestamps, stored in single precision.
+    const TIMESTAMP_TOLERANCE: f64 = 1e-6;
+
+    fn pen_path() -> PenPath {
+        let mut pen_path = PenPath::new(Element::new(na::vector![1234.567, -987.654], 0.3));
+        pen_path.extend_timed(
+            [
+                Segment::LineTo {
+                    end: Element::new(na::vector![1240.123, -980.5], 0.45),
+                },
+                Segment::QuadBezTo {
+                    cp: na::vector![1245.0, -990.25],
+                    end: Element::new(na::vector![1250.75, -975.125], 1.0),
+                },
+            ],
+            0.5,
+        );
+        pen_path.extend_timed(
+            [Segment::CubBezTo {
+                cp1: na::vector![1255.3, -970.7],
+                cp2: na::vector![1260.9, -985.1],
+                end: Element::new(na::vector![1270.0, -960.0], 0.0),
+            }],
+            1.25,
+        );
+        pen_path
+    }
+
+    /// The control points and the end element of the segment.
+    fn segment_points(segment: &Segment) -> (Vec<na::Vector2<f64>>, Element) {
+        match segment {
+            Segment::LineTo { end } => (vec![], *end),
+            Segment::QuadBezTo { cp, end } => (vec![*cp], *end),
+            Segment::CubBezTo { cp1, cp2, end } => (vec![*cp1, *cp2], *end),
+        }
+    }
+
+    fn assert_element_eq(a: Element, b: Element) {
+        approx::assert_abs_diff_eq!(a.pos, b.pos, epsilon = POS_TOLERANCE);
+        approx::assert_abs_diff_eq!(a.pressure, b.pressure, epsilon = PRESSURE_TOLERANCE);
+    }
+
+    fn assert_pen_path_eq(a: &PenPath, b: &PenPath) {
+        assert_element_eq(a.start, b.start);
+        assert_eq!(a.segments.len(), b.segments.len());
+        for (a_segment, b_segment) in a.segments.iter().zip(b.segments.iter()) {
+            assert_eq!(
+                std::mem::discriminant(a_segment),
+                std::mem::discriminant(b_segment)
+            );
+            let ((a_cps, a_end), (b_cps, b_end)) =
+                (segment_points(a_segment), segment_points(b_segment));
+            for (a_cp, b_cp) in a_cps.into_iter().zip(b_cps) {
+                approx::assert_abs_diff_eq!(a_cp, b_cp, epsilon = POS_TOLERANCE);
+            }
+            assert_element_eq(a_end, b_end);
+        }
+        match (a.timestamps(), b.timestamps()) {
+            (Some(a_timestamps), Some(b_timestamps)) => {
+                assert_eq!(a_timestamps.len(), b_timestamps.len());
+                for (a_timestamp, b_timestamp) in a_timestamps.iter().zip(b_timestamps) {
+                    approx::assert_abs_diff_eq!(
+                        a_timestamp,
+                        b_timestamp,
+                        epsilon = TIMESTAMP_TOLERANCE
+                    );
+                }
+            }
+            (None, None) => {}
+            _ => panic!("only one of the pen paths has timestamps"),
+        }
+    }
+
+    #[test]
+    fn binary_roundtrip() {
+        let pen_path = pen_path();
+        assert_pen_path_eq(
+            &PenPath::from_binary(&pen_path.to_binary()).unwrap(),
+            &pen_path,
+        );
+
+        let without_timestamps = PenPath::new_w_segments(pen_path.start, pen_path.segments.clone());
+        let decoded = PenPath::from_binary(&without_timestamps.to_binary()).unwrap();
+        assert!(decoded.timestamps().is_none());
+        assert_pen_path_eq(&decoded, &without_timestamps);
+
+        // the rounding errors of the deltas don't accumulate along the path
+        let long = PenPath::new_w_segments(
+            Element::new(na::vector![5000.0, 5000.0], 0.5),
+            (1..=10_000).map(|i| Segment::LineTo {
+                end: Element::new(
+                    na::vector![
+                        5000.0 + f64::from(i) * 0.1,
+                        5000.0 + (f64::from(i) * 0.3).sin()
+                    ],
+                    0.5,
+                ),
+            }),
+        );
+        assert_pen_path_eq(&PenPath::from_binary(&long.to_binary()).unwrap(), &long);
+    }
+
+    #[test]
+    fn binary_invalid() {
+        let bytes = pen_path().to_binary();
+        assert!(PenPath::from_binary(&bytes[..bytes.len() - 1]).is_err());
+        assert!(PenPath::from_binary(&[]).is_err());
+
+        let mut unsupported_version = bytes.clone();
+        unsupported_version[0] = VERSION + 1;
+        assert!(PenPath::from_binary(&unsupported_version).is_err());
+    }
+
+    #[test]
+    fn serialize_binary() {
+        let pen_path = pen_path();
+
+        let binary = with_binary_encoding(|| serde_json::to_value(&pen_path)).unwrap();
+        assert!(binary.get("binary").is_some());
+        assert!(binary.get("start").is_none());
+        assert_pen_path_eq(&serde_json::from_value(binary).unwrap(), &pen_path);
+
+        // the regular form is serialized again outside of the closure
+        let regular = serde_json::to_value(&pen_path).unwrap();
+        assert!(regular.get("binary").is_none());
+        assert!(regular.get("start").is_some());
+        assert_pen_path_eq(&serde_json::from_value(regular).unwrap(), &pen_path);
+    }
+}
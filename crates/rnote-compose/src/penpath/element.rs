@@ -1,5 +1,5 @@
 // Imports
-use crate::transform::Transformable;
+use crate::transform::{Projective, Transformable, Warpable};
 use p2d::bounding_volume::Aabb;
 use serde::{Deserialize, Serialize};
 
@@ -13,6 +13,13 @@ pub struct Element {
     #[serde(rename = "pressure", with = "crate::serialize::f64_dp3")]
     /// The pen pressure. The valid range is [0.0, 1.0].
     pub pressure: f64,
+    #[serde(
+        rename = "tilt_angle",
+        with = "crate::serialize::option_f64_dp3",
+        skip_serializing_if = "Option::is_none"
+    )]
+    /// The angle in radians of the direction the stylus is tilted towards. None when not available.
+    pub tilt_angle: Option<f64>,
 }
 
 impl Default for Element {
@@ -30,6 +37,7 @@ impl Transformable for Element {
         let mut isometry = na::Isometry2::identity();
         isometry.append_rotation_wrt_point_mut(&na::UnitComplex::new(angle), &center);
         self.pos = isometry.transform_point(&self.pos.into()).coords;
+        self.rotate_tilt_angle(angle);
     }
 
     fn scale(&mut self, scale: na::Vector2<f64>) {
@@ -37,6 +45,12 @@ impl Transformable for Element {
     }
 }
 
+impl Warpable for Element {
+    fn warp(&mut self, projective: &Projective) {
+        self.pos = projective.transform_point(self.pos);
+    }
+}
+
 impl Element {
     /// The default fallback pen pressure, when it could not be retrieved from the input.
     pub const PRESSURE_DEFAULT: f64 = 0.5;
@@ -46,6 +60,20 @@ impl Element {
         Self {
             pos,
             pressure: pressure.clamp(0.0, 1.0),
+            tilt_angle: None,
+        }
+    }
+
+    /// The element with the given tilt angle.
+    pub fn with_tilt_angle(mut self, tilt_angle: Option<f64>) -> Self {
+        self.tilt_angle = tilt_angle;
+        self
+    }
+
+    /// Rotates the tilt angle together with the element.
+    pub(crate) fn rotate_tilt_angle(&mut self, angle: f64) {
+        if let Some(tilt_angle) = self.tilt_angle.as_mut() {
+            *tilt_angle += angle;
         }
     }
 
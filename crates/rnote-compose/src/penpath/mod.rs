@@ -1,29 +1,85 @@
 // Modules
+mod binary;
 mod element;
 mod segment;
 
 // Re-exports
+pub use binary::with_binary_encoding;
 pub use element::Element;
 pub use segment::Segment;
 
 // Imports
 use crate::ext::{KurboShapeExt, Vector2Ext};
 use crate::shapes::{CubicBezier, Line, QuadraticBezier, Shapeable};
-use crate::transform::Transformable;
-use kurbo::Shape;
+use crate::transform::{Projective, Transformable, Warpable};
+use kurbo::{ParamCurve, ParamCurveArclen, ParamCurveDeriv, Shape};
 use p2d::bounding_volume::{Aabb, BoundingVolume};
+use serde::ser::SerializeStruct;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename = "pen_path")]
+#[derive(Debug, Clone)]
 /// a pen path, consisting of segments of pen input elements
 pub struct PenPath {
     /// The path start
-    #[serde(rename = "start")]
     pub start: Element,
     /// The segments
-    #[serde(rename = "segments")]
     pub segments: Vec<Segment>,
+    /// The optional timestamps of the segment ends, in seconds since the start of the path.
+    ///
+    /// Only valid when there is a timestamp for every segment, see [PenPath::timestamps].
+    timestamps: Option<Vec<f64>>,
+}
+
+impl Serialize for PenPath {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if binary::binary_encoding_enabled() {
+            return binary::serialize(self, serializer);
+        }
+        let mut state = serializer.serialize_struct("pen_path", 3)?;
+        state.serialize_field("start", &self.start)?;
+        state.serialize_field("segments", &self.segments)?;
+        state.serialize_field("timestamps", &self.timestamps)?;
+        state.end()
+    }
+}
+
+/// The precursor of a pen path when deserializing. Either the regular fields or the compact binary encoding,
+/// see [with_binary_encoding].
+#[derive(Debug, Deserialize)]
+#[serde(rename = "pen_path")]
+struct PenPathPrecursor {
+    #[serde(rename = "start", default)]
+    start: Option<Element>,
+    #[serde(rename = "segments", default)]
+    segments: Vec<Segment>,
+    #[serde(rename = "timestamps", default)]
+    timestamps: Option<Vec<f64>>,
+    #[serde(rename = "binary", default)]
+    binary: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for PenPath {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let precursor = PenPathPrecursor::deserialize(deserializer)?;
+
+        if let Some(binary) = precursor.binary {
+            return binary::decode_base64(&binary).map_err(serde::de::Error::custom);
+        }
+        let start = precursor
+            .start
+            .ok_or_else(|| serde::de::Error::missing_field("start"))?;
+        Ok(Self {
+            start,
+            segments: precursor.segments,
+            timestamps: precursor.timestamps,
+        })
+    }
 }
 
 impl Shapeable for PenPath {
@@ -100,12 +156,26 @@ impl Transformable for PenPath {
     }
 }
 
+impl Warpable for PenPath {
+    fn warp(&mut self, projective: &Projective) {
+        // Projective transforms only map the end and control points of curves exactly,
+        // so long curves are resampled first to follow the perspective along their length
+        self.resample_curves(Self::WARP_RESAMPLE_LENGTH);
+
+        self.start.warp(projective);
+        self.segments.iter_mut().for_each(|segment| {
+            segment.warp(projective);
+        });
+    }
+}
+
 impl PenPath {
     /// A new pen path
     pub fn new(start: Element) -> Self {
         Self {
             start,
             segments: Vec::default(),
+            timestamps: None,
         }
     }
 
@@ -114,6 +184,7 @@ impl PenPath {
         Self {
             start,
             segments: segments.into_iter().collect(),
+            timestamps: None,
         }
     }
 
@@ -139,7 +210,43 @@ impl PenPath {
             .map(|el| Segment::LineTo { end: el })
             .collect::<Vec<Segment>>();
 
-        Some(Self { start, segments })
+        Some(Self {
+            start,
+            segments,
+            timestamps: None,
+        })
+    }
+
+    /// The timestamps of the segment ends in seconds since the start of the path, if every segment has one.
+    pub fn timestamps(&self) -> Option<&[f64]> {
+        self.timestamps
+            .as_deref()
+            .filter(|timestamps| timestamps.len() == self.segments.len())
+    }
+
+    /// The duration of drawing the path in seconds, if it has timestamps.
+    pub fn duration(&self) -> Option<f64> {
+        self.timestamps()
+            .map(|timestamps| timestamps.last().copied().unwrap_or(0.0))
+    }
+
+    /// Extend the path with segments that were captured at the given time, in seconds since the start of the path.
+    ///
+    /// The segments are spread evenly between the previous timestamp and the time, as segments are usually emitted
+    /// in batches. Timestamps are only recorded when the path is empty or already has timestamps.
+    pub fn extend_timed(&mut self, segments: impl IntoIterator<Item = Segment>, time: f64) {
+        let n_before = self.segments.len();
+        if self.timestamps().is_none() {
+            self.timestamps = (n_before == 0).then(Vec::new);
+        }
+        self.segments.extend(segments);
+        let n_new = self.segments.len() - n_before;
+
+        if let Some(timestamps) = &mut self.timestamps {
+            let prev = timestamps.last().copied().unwrap_or(0.0);
+            let time = time.max(prev);
+            timestamps.extend((1..=n_new).map(|i| prev + (time - prev) * i as f64 / n_new as f64));
+        }
     }
 
     /// Checks whether bounds collide with the path. If it does, it returns the indices of the colliding segments
@@ -262,9 +369,368 @@ impl PenPath {
     }
 }
 
+/// Arc-length parameterization.
+impl PenPath {
+    /// The accuracy of the arc-length calculations.
+    pub const ARCLEN_ACCURACY: f64 = 0.01;
+    /// The maximum length of curve segments when warping, longer ones are resampled.
+    const WARP_RESAMPLE_LENGTH: f64 = 8.0;
+
+    /// The total length of the path.
+    pub fn length(&self) -> f64 {
+        self.segment_lengths().into_iter().sum()
+    }
+
+    /// The lengths of the segments.
+    pub fn segment_lengths(&self) -> Vec<f64> {
+        self.kurbo_segs()
+            .map(|(_, _, seg)| seg.arclen(Self::ARCLEN_ACCURACY))
+            .collect()
+    }
+
+    /// Sample the element at the given arc-length position, with the pressure interpolated between the segment
+    /// elements.
+    ///
+    /// The length is clamped to the length of the path.
+    pub fn sample_at_length(&self, length: f64) -> Element {
+        match self.locate_length(length) {
+            Some((i, t)) => {
+                let (start, segment, seg) = self.kurbo_segs().nth(i).unwrap();
+                Self::sample_seg(start, segment, seg, t)
+            }
+            None => self.start,
+        }
+    }
+
+    /// The unit direction of the path at the given arc-length position.
+    ///
+    /// Returns None when the path has no direction at the position, for example if it consists of a single element.
+    pub fn direction_at_length(&self, length: f64) -> Option<na::Vector2<f64>> {
+        let (i, t) = self.locate_length(length)?;
+        let (_, _, seg) = self.kurbo_segs().nth(i)?;
+        let deriv = match seg {
+            kurbo::PathSeg::Line(line) => line.deriv().eval(t),
+            kurbo::PathSeg::Quad(quad) => quad.deriv().eval(t),
+            kurbo::PathSeg::Cubic(cubic) => cubic.deriv().eval(t),
+        };
+        let direction = na::vector![deriv.x, deriv.y];
+        let norm = direction.norm();
+        (norm > 0.0).then_some(direction / norm)
+    }
+
+    /// Sample elements evenly spaced by the given arc-length spacing, including the start and the end of the path.
+    pub fn sample_evenly(&self, spacing: f64) -> Vec<Element> {
+        let mut samples = vec![self.start];
+        if spacing <= 0.0 {
+            return samples;
+        }
+        // The arc-length position of the next sample, relative to the start of the current segment
+        let mut next = spacing;
+        let mut last = None;
+
+        for (start, segment, seg) in self.kurbo_segs() {
+            let seg_length = seg.arclen(Self::ARCLEN_ACCURACY);
+            while next <= seg_length {
+                let t = if seg_length > 0.0 {
+                    seg.inv_arclen(next, Self::ARCLEN_ACCURACY)
+                } else {
+                    0.0
+                };
+                samples.push(Self::sample_seg(start, segment, seg, t.clamp(0.0, 1.0)));
+                next += spacing;
+            }
+            next -= seg_length;
+            last = Some((start, segment, seg));
+        }
+        // The end is sampled too, unless the last sample already is at it
+        if let Some((start, segment, seg)) = last
+            && spacing - next > Self::ARCLEN_ACCURACY
+        {
+            samples.push(Self::sample_seg(start, segment, seg, 1.0));
+        }
+        samples
+    }
+
+    /// Split the path at the given arc-length position into the path before and the path after it.
+    ///
+    /// The length is clamped to the length of the path.
+    pub fn split_at_length(&self, length: f64) -> (PenPath, PenPath) {
+        let Some((i, t)) = self.locate_length(length) else {
+            return (self.clone(), PenPath::new(self.start));
+        };
+        let (_, _, seg) = self.kurbo_segs().nth(i).unwrap();
+        let split = self.sample_at_length(length);
+        let end = self.segments[i].end();
+
+        let mut first = PenPath::new_w_segments(self.start, self.segments[..i].iter().copied());
+        first.segments.push(Segment::from_kurbo_seg(
+            seg.subsegment(0.0..t),
+            split.pressure,
+        ));
+
+        let mut second = PenPath::new(split);
+        second.segments.push(Segment::from_kurbo_seg(
+            seg.subsegment(t..1.0),
+            end.pressure,
+        ));
+        second
+            .segments
+            .extend(self.segments[i + 1..].iter().copied());
+
+        if let Some(timestamps) = self.timestamps() {
+            let prev = i.checked_sub(1).map(|i| timestamps[i]).unwrap_or(0.0);
+            let split_time = prev + (timestamps[i] - prev) * t;
+            first.timestamps = Some(
+                timestamps[..i]
+                    .iter()
+                    .copied()
+                    .chain(std::iter::once(split_time))
+                    .collect(),
+            );
+            second.timestamps = Some(
+                timestamps[i..]
+                    .iter()
+                    .map(|timestamp| timestamp - split_time)
+                    .collect(),
+            );
+        }
+
+        (first, second)
+    }
+
+    /// Split curve segments that are longer than the given length into multiple shorter segments.
+    ///
+    /// The pressures and timestamps of the new segments are interpolated.
+    fn resample_curves(&mut self, max_length: f64) {
+        let timestamps = self.timestamps().map(|timestamps| timestamps.to_vec());
+        let mut segments = Vec::with_capacity(self.segments.len());
+        let mut resampled_timestamps = timestamps.as_ref().map(|_| Vec::new());
+
+        for (i, (start, segment, seg)) in self.kurbo_segs().enumerate() {
+            let end = segment.end();
+            let n_pieces = match seg {
+                kurbo::PathSeg::Line(_) => 1,
+                _ => (seg.arclen(Self::ARCLEN_ACCURACY) / max_length)
+                    .ceil()
+                    .max(1.0) as usize,
+            };
+
+            if n_pieces == 1 {
+                segments.push(*segment);
+            } else {
+                segments.extend((0..n_pieces).map(|j| {
+                    let (t0, t1) = (j as f64 / n_pieces as f64, (j + 1) as f64 / n_pieces as f64);
+                    let piece = Segment::from_kurbo_seg(
+                        seg.subsegment(t0..t1),
+                        start.pressure + (end.pressure - start.pressure) * t1,
+                    );
+                    if j == n_pieces - 1 {
+                        piece.with_end(end)
+                    } else {
+                        piece
+                    }
+                }));
+            }
+
+            if let (Some(timestamps), Some(resampled)) = (&timestamps, &mut resampled_timestamps) {
+                let prev = i.checked_sub(1).map(|i| timestamps[i]).unwrap_or(0.0);
+                resampled.extend(
+                    (1..=n_pieces)
+                        .map(|j| prev + (timestamps[i] - prev) * j as f64 / n_pieces as f64),
+                );
+            }
+        }
+
+        self.segments = segments;
+        self.timestamps = resampled_timestamps;
+    }
+
+    /// Locate the arc-length position as the segment index and the curve parameter inside the segment.
+    ///
+    /// Returns None when the path has no segments.
+    fn locate_length(&self, length: f64) -> Option<(usize, f64)> {
+        let mut remaining = length.max(0.0);
+        let mut last = None;
+
+        for (i, (_, _, seg)) in self.kurbo_segs().enumerate() {
+            let seg_length = seg.arclen(Self::ARCLEN_ACCURACY);
+            if remaining <= seg_length {
+                let t = if seg_length > 0.0 {
+                    seg.inv_arclen(remaining, Self::ARCLEN_ACCURACY)
+                } else {
+                    0.0
+                };
+                return Some((i, t.clamp(0.0, 1.0)));
+            }
+            remaining -= seg_length;
+            last = Some((i, 1.0));
+        }
+
+        last
+    }
+
+    /// Sample the element at the curve parameter of the segment, with the pressure interpolated between the segment
+    /// elements.
+    fn sample_seg(start: Element, segment: &Segment, seg: kurbo::PathSeg, t: f64) -> Element {
+        let end = segment.end();
+        Element::new(
+            na::Vector2::from_kurbo_point(seg.eval(t)),
+            start.pressure + (end.pressure - start.pressure) * t,
+        )
+    }
+
+    /// The segments with their start element, converted to [kurbo::PathSeg].
+    fn kurbo_segs(&self) -> impl Iterator<Item = (Element, &Segment, kurbo::PathSeg)> + '_ {
+        self.segments.iter().scan(self.start, |prev, segment| {
+            let start = *prev;
+            *prev = segment.end();
+            Some((start, segment, segment.to_kurbo_seg(start.pos)))
+        })
+    }
+}
+
+/// Precise erasing.
+impl PenPath {
+    /// The maximum amount of samples inside a single segment when searching for the erased parts.
+    const ERASE_SAMPLES_MAX: usize = 1000;
+    /// The amount of bisection steps to locate the boundaries of the erased parts.
+    const ERASE_BISECTION_STEPS: usize = 12;
+
+    /// Erase the parts of the path closer than `radius` to the line from `from` to `to`, the path of the eraser.
+    ///
+    /// The segments are cut exactly where they enter and leave the erased area, and the pressure and tilt of
+    /// the elements at the cuts are interpolated between the segment elements.
+    ///
+    /// Returns the remaining sub-paths in order, or None when the path is not touched by the eraser.
+    pub fn erase_along(
+        &self,
+        from: na::Vector2<f64>,
+        to: na::Vector2<f64>,
+        radius: f64,
+    ) -> Option<Vec<PenPath>> {
+        let erased = |pos: na::Vector2<f64>| distance_to_line(pos, from, to) < radius;
+
+        if self.segments.is_empty() {
+            return erased(self.start.pos).then(Vec::new);
+        }
+
+        // Samples must be closer than the width of the erased area, to not skip over it
+        let sample_spacing = (radius * 0.5).max(Self::ARCLEN_ACCURACY);
+        let mut touched = false;
+        let mut remaining = vec![];
+        let mut current: Option<PenPath> = None;
+
+        for (start, segment, seg) in self.kurbo_segs() {
+            let end = segment.end();
+            let n_samples = ((seg.arclen(Self::ARCLEN_ACCURACY) / sample_spacing).ceil() as usize)
+                .clamp(1, Self::ERASE_SAMPLES_MAX);
+            let erased_at = |t: f64| erased(na::Vector2::from_kurbo_point(seg.eval(t)));
+
+            // The parameter ranges of the segment that are kept
+            let mut kept = vec![];
+            let mut kept_start = (!erased_at(0.0)).then_some(0.0);
+            let mut prev_t = 0.0;
+            for i in 1..=n_samples {
+                let t = i as f64 / n_samples as f64;
+                match (kept_start, erased_at(t)) {
+                    (Some(start_t), true) => {
+                        kept.push(start_t..bisect(prev_t, t, erased_at));
+                        kept_start = None;
+                    }
+                    (None, false) => {
+                        kept_start = Some(bisect(t, prev_t, erased_at));
+                    }
+                    _ => {}
+                }
+                prev_t = t;
+            }
+            if let Some(start_t) = kept_start {
+                kept.push(start_t..1.0);
+            }
+            if !matches!(kept.as_slice(), [range] if range.start == 0.0 && range.end == 1.0) {
+                touched = true;
+            }
+
+            for range in kept.into_iter().filter(|range| range.end > range.start) {
+                let range_start =
+                    interpolate_element(start, end, range.start, seg.eval(range.start));
+                let range_end = interpolate_element(start, end, range.end, seg.eval(range.end));
+                let subsegment =
+                    Segment::from_kurbo_seg(seg.subsegment(range.clone()), range_end.pressure)
+                        .with_end(range_end);
+
+                match current.as_mut() {
+                    Some(path) if range.start == 0.0 => path.segments.push(subsegment),
+                    _ => {
+                        remaining.extend(current.take());
+                        current = Some(PenPath::new_w_segments(range_start, [subsegment]));
+                    }
+                }
+                if range.end < 1.0 {
+                    remaining.extend(current.take());
+                }
+            }
+        }
+        remaining.extend(current.take());
+
+        touched.then_some(remaining)
+    }
+}
+
+/// Bisect between the parameter `kept` which is not erased and `erased` which is, returning the last parameter
+/// that is not erased.
+fn bisect(mut kept: f64, mut erased: f64, erased_at: impl Fn(f64) -> bool) -> f64 {
+    for _ in 0..PenPath::ERASE_BISECTION_STEPS {
+        let mid = (kept + erased) * 0.5;
+        if erased_at(mid) {
+            erased = mid;
+        } else {
+            kept = mid;
+        }
+    }
+    kept
+}
+
+/// The distance of the position to the line from `start` to `end`.
+fn distance_to_line(pos: na::Vector2<f64>, start: na::Vector2<f64>, end: na::Vector2<f64>) -> f64 {
+    let direction = end - start;
+    let length_squared = direction.norm_squared();
+    if length_squared <= 0.0 {
+        return (pos - start).magnitude();
+    }
+    let t = ((pos - start).dot(&direction) / length_squared).clamp(0.0, 1.0);
+    (pos - (start + direction * t)).magnitude()
+}
+
+/// The element at the parameter `t` and position `pos` of the segment between the two elements.
+///
+/// The pressure is interpolated, the tilt angle is taken from the closer element.
+fn interpolate_element(start: Element, end: Element, t: f64, pos: kurbo::Point) -> Element {
+    if t <= 0.0 {
+        return start;
+    }
+    if t >= 1.0 {
+        return end;
+    }
+    Element::new(
+        na::Vector2::from_kurbo_point(pos),
+        start.pressure + (end.pressure - start.pressure) * t,
+    )
+    .with_tilt_angle(if t < 0.5 {
+        start.tilt_angle
+    } else {
+        end.tilt_angle
+    })
+}
+
 impl Extend<Segment> for PenPath {
     fn extend<T: IntoIterator<Item = Segment>>(&mut self, iter: T) {
+        let n_before = self.segments.len();
         self.segments.extend(iter);
+        // the new segments don't have timestamps
+        if self.segments.len() != n_before {
+            self.timestamps = None;
+        }
     }
 }
 
@@ -283,3 +749,251 @@ pub(crate) fn no_subsegments_for_segment_len(len: f64) -> i32 {
         MAX_SUBSEGMENT_ELEMENTS
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A line from (0, 0) to (3, 4) with length 5, followed by a line to (3, 10) with length 6.
+    fn two_lines() -> PenPath {
+        PenPath::new_w_segments(
+            Element::new(na::vector![0.0, 0.0], 0.0),
+            [
+                Segment::LineTo {
+                    end: Element::new(na::vector![3.0, 4.0], 1.0),
+                },
+                Segment::LineTo {
+                    end: Element::new(na::vector![3.0, 10.0], 0.5),
+                },
+            ],
+        )
+    }
+
+    #[test]
+    fn length() {
+        let path = two_lines();
+        let segment_lengths = path.segment_lengths();
+        assert_eq!(segment_lengths.len(), 2);
+        approx::assert_relative_eq!(segment_lengths[0], 5.0);
+        approx::assert_relative_eq!(segment_lengths[1], 6.0);
+        approx::assert_relative_eq!(path.length(), 11.0);
+
+        // a straight cubic curve with evenly spaced control points
+        let cubic = PenPath::new_w_segments(
+            Element::new(na::vector![0.0, 0.0], 0.5),
+            [Segment::CubBezTo {
+                cp1: na::vector![1.0, 0.0],
+                cp2: na::vector![2.0, 0.0],
+                end: Element::new(na::vector![3.0, 0.0], 0.5),
+            }],
+        );
+        approx::assert_abs_diff_eq!(cubic.length(), 3.0, epsilon = PenPath::ARCLEN_ACCURACY);
+        approx::assert_relative_eq!(PenPath::new(Element::default()).length(), 0.0);
+    }
+
+    #[test]
+    fn sample_at_length() {
+        let path = two_lines();
+
+        let sample = path.sample_at_length(2.5);
+        approx::assert_relative_eq!(sample.pos, na::vector![1.5, 2.0], epsilon = 1e-6);
+        approx::assert_relative_eq!(sample.pressure, 0.5, epsilon = 1e-6);
+
+        // the boundary between the segments is the end of the first segment
+        let sample = path.sample_at_length(5.0);
+        approx::assert_relative_eq!(sample.pos, na::vector![3.0, 4.0], epsilon = 1e-6);
+        approx::assert_relative_eq!(sample.pressure, 1.0, epsilon = 1e-6);
+
+        let sample = path.sample_at_length(8.0);
+        approx::assert_relative_eq!(sample.pos, na::vector![3.0, 7.0], epsilon = 1e-6);
+        approx::assert_relative_eq!(sample.pressure, 0.75, epsilon = 1e-6);
+
+        // clamped to the path
+        approx::assert_relative_eq!(path.sample_at_length(-1.0).pos, na::vector![0.0, 0.0]);
+        approx::assert_relative_eq!(
+            path.sample_at_length(20.0).pos,
+            na::vector![3.0, 10.0],
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn direction_at_length() {
+        let path = two_lines();
+
+        approx::assert_relative_eq!(
+            path.direction_at_length(2.5).unwrap(),
+            na::vector![0.6, 0.8],
+            epsilon = 1e-6
+        );
+        approx::assert_relative_eq!(
+            path.direction_at_length(8.0).unwrap(),
+            na::vector![0.0, 1.0],
+            epsilon = 1e-6
+        );
+        assert!(
+            PenPath::new(Element::default())
+                .direction_at_length(0.0)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn sample_evenly() {
+        let path = two_lines();
+
+        let samples = path.sample_evenly(5.0);
+        assert_eq!(samples.len(), 4);
+        for (sample, pos) in samples.iter().zip([
+            na::vector![0.0, 0.0],
+            na::vector![3.0, 4.0],
+            na::vector![3.0, 9.0],
+            na::vector![3.0, 10.0],
+        ]) {
+            approx::assert_relative_eq!(sample.pos, pos, epsilon = 1e-6);
+        }
+
+        // the end is not sampled twice when the spacing divides the length
+        assert_eq!(path.sample_evenly(5.5).len(), 3);
+        assert_eq!(path.sample_evenly(0.0).len(), 1);
+
+        // the samples match the ones at their arc-length positions
+        let samples = path.sample_evenly(0.7);
+        assert_eq!(samples.len(), 17);
+        for (i, sample) in samples[..16].iter().enumerate() {
+            let expected = path.sample_at_length(i as f64 * 0.7);
+            approx::assert_relative_eq!(sample.pos, expected.pos, epsilon = 1e-6);
+            approx::assert_relative_eq!(sample.pressure, expected.pressure, epsilon = 1e-6);
+        }
+        approx::assert_relative_eq!(samples[16].pos, na::vector![3.0, 10.0], epsilon = 1e-6);
+    }
+
+    #[test]
+    fn split_at_length() {
+        let path = two_lines();
+
+        let (first, second) = path.split_at_length(8.0);
+        assert_eq!(first.segments.len(), 2);
+        assert_eq!(second.segments.len(), 1);
+        approx::assert_relative_eq!(first.length(), 8.0, epsilon = 1e-6);
+        approx::assert_relative_eq!(second.length(), 3.0, epsilon = 1e-6);
+        approx::assert_relative_eq!(
+            first.segments[1].end().pos,
+            na::vector![3.0, 7.0],
+            epsilon = 1e-6
+        );
+        approx::assert_relative_eq!(second.start.pos, na::vector![3.0, 7.0], epsilon = 1e-6);
+        approx::assert_relative_eq!(second.start.pressure, 0.75, epsilon = 1e-6);
+        approx::assert_relative_eq!(second.segments[0].end().pos, na::vector![3.0, 10.0]);
+
+        // splitting at a segment boundary keeps the lengths of both segments
+        let (first, second) = path.split_at_length(5.0);
+        approx::assert_relative_eq!(first.length(), 5.0, epsilon = 1e-6);
+        approx::assert_relative_eq!(second.length(), 6.0, epsilon = 1e-6);
+        approx::assert_relative_eq!(second.start.pos, na::vector![3.0, 4.0], epsilon = 1e-6);
+    }
+
+    /// A line from (0, 0) to (10, 0) with increasing pressure, followed by a line to (20, 0).
+    fn horizontal_lines() -> PenPath {
+        PenPath::new_w_segments(
+            Element::new(na::vector![0.0, 0.0], 0.0),
+            [
+                Segment::LineTo {
+                    end: Element::new(na::vector![10.0, 0.0], 1.0),
+                },
+                Segment::LineTo {
+                    end: Element::new(na::vector![20.0, 0.0], 1.0),
+                },
+            ],
+        )
+    }
+
+    /// The tolerance of the positions where the path is cut.
+    const CUT_TOLERANCE: f64 = 1e-3;
+
+    fn assert_pos_eq(pos: na::Vector2<f64>, expected: na::Vector2<f64>) {
+        approx::assert_abs_diff_eq!(pos, expected, epsilon = CUT_TOLERANCE);
+    }
+
+    fn end_pos(path: &PenPath) -> na::Vector2<f64> {
+        path.segments.last().unwrap().end().pos
+    }
+
+    #[test]
+    fn erase_along_middle() {
+        let remaining = horizontal_lines()
+            .erase_along(na::vector![5.0, -5.0], na::vector![5.0, 5.0], 1.0)
+            .unwrap();
+
+        assert_eq!(remaining.len(), 2);
+        assert_pos_eq(remaining[0].start.pos, na::vector![0.0, 0.0]);
+        assert_pos_eq(end_pos(&remaining[0]), na::vector![4.0, 0.0]);
+        assert_pos_eq(remaining[1].start.pos, na::vector![6.0, 0.0]);
+        assert_pos_eq(end_pos(&remaining[1]), na::vector![20.0, 0.0]);
+        assert_eq!(remaining[1].segments.len(), 2);
+        // the pressures at the cuts are interpolated
+        approx::assert_abs_diff_eq!(
+            remaining[0].segments[0].end().pressure,
+            0.4,
+            epsilon = CUT_TOLERANCE
+        );
+        approx::assert_abs_diff_eq!(remaining[1].start.pressure, 0.6, epsilon = CUT_TOLERANCE);
+
+        // cutting at the boundary between the segments
+        let remaining = horizontal_lines()
+            .erase_along(na::vector![10.0, -5.0], na::vector![10.0, 5.0], 1.0)
+            .unwrap();
+        assert_eq!(remaining.len(), 2);
+        assert_pos_eq(end_pos(&remaining[0]), na::vector![9.0, 0.0]);
+        assert_pos_eq(remaining[1].start.pos, na::vector![11.0, 0.0]);
+    }
+
+    #[test]
+    fn erase_along_start_end() {
+        let remaining = horizontal_lines()
+            .erase_along(na::vector![0.0, -5.0], na::vector![0.0, 5.0], 1.0)
+            .unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_pos_eq(remaining[0].start.pos, na::vector![1.0, 0.0]);
+        assert_pos_eq(end_pos(&remaining[0]), na::vector![20.0, 0.0]);
+
+        let remaining = horizontal_lines()
+            .erase_along(na::vector![20.0, -5.0], na::vector![20.0, 5.0], 1.0)
+            .unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_pos_eq(remaining[0].start.pos, na::vector![0.0, 0.0]);
+        assert_pos_eq(end_pos(&remaining[0]), na::vector![19.0, 0.0]);
+    }
+
+    #[test]
+    fn erase_along_complete() {
+        let remaining = horizontal_lines()
+            .erase_along(na::vector![-5.0, 0.0], na::vector![25.0, 0.0], 1.0)
+            .unwrap();
+        assert!(remaining.is_empty());
+
+        let single = PenPath::new(Element::new(na::vector![0.0, 0.0], 0.5));
+        assert!(
+            single
+                .erase_along(na::vector![0.0, -5.0], na::vector![0.0, 5.0], 1.0)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn erase_along_miss() {
+        assert!(
+            horizontal_lines()
+                .erase_along(na::vector![0.0, 5.0], na::vector![20.0, 5.0], 1.0)
+                .is_none()
+        );
+
+        let single = PenPath::new(Element::new(na::vector![0.0, 0.0], 0.5));
+        assert!(
+            single
+                .erase_along(na::vector![5.0, -5.0], na::vector![5.0, 5.0], 1.0)
+                .is_none()
+        );
+    }
+}
@@ -1,6 +1,7 @@
 // Imports
 use super::Element;
-use crate::transform::Transformable;
+use crate::ext::Vector2Ext;
+use crate::transform::{Projective, Transformable, Warpable};
 use serde::{Deserialize, Serialize};
 
 /// A single segment, usually of a pen path.
@@ -63,16 +64,16 @@ impl Transformable for Segment {
 
         match self {
             Self::LineTo { end } => {
-                end.pos = isometry.transform_point(&end.pos.into()).coords;
+                end.rotate(angle, center);
             }
             Self::QuadBezTo { cp, end } => {
                 *cp = isometry.transform_point(&(*cp).into()).coords;
-                end.pos = isometry.transform_point(&end.pos.into()).coords;
+                end.rotate(angle, center);
             }
             Self::CubBezTo { cp1, cp2, end } => {
                 *cp1 = isometry.transform_point(&(*cp1).into()).coords;
                 *cp2 = isometry.transform_point(&(*cp2).into()).coords;
-                end.pos = isometry.transform_point(&end.pos.into()).coords;
+                end.rotate(angle, center);
             }
         }
     }
@@ -95,6 +96,25 @@ impl Transformable for Segment {
     }
 }
 
+impl Warpable for Segment {
+    fn warp(&mut self, projective: &Projective) {
+        match self {
+            Self::LineTo { end } => {
+                end.warp(projective);
+            }
+            Self::QuadBezTo { cp, end } => {
+                *cp = projective.transform_point(*cp);
+                end.warp(projective);
+            }
+            Self::CubBezTo { cp1, cp2, end } => {
+                *cp1 = projective.transform_point(*cp1);
+                *cp2 = projective.transform_point(*cp2);
+                end.warp(projective);
+            }
+        }
+    }
+}
+
 impl Segment {
     /// The end element of a segment.
     ///
@@ -106,4 +126,52 @@ impl Segment {
             Segment::CubBezTo { end, .. } => *end,
         }
     }
+
+    /// The segment with the end element replaced.
+    pub fn with_end(self, end: Element) -> Self {
+        match self {
+            Segment::LineTo { .. } => Segment::LineTo { end },
+            Segment::QuadBezTo { cp, .. } => Segment::QuadBezTo { cp, end },
+            Segment::CubBezTo { cp1, cp2, .. } => Segment::CubBezTo { cp1, cp2, end },
+        }
+    }
+
+    /// Convert to a [kurbo::PathSeg], starting at the given position.
+    pub fn to_kurbo_seg(&self, start: na::Vector2<f64>) -> kurbo::PathSeg {
+        match self {
+            Segment::LineTo { end } => kurbo::PathSeg::Line(kurbo::Line::new(
+                start.to_kurbo_point(),
+                end.pos.to_kurbo_point(),
+            )),
+            Segment::QuadBezTo { cp, end } => kurbo::PathSeg::Quad(kurbo::QuadBez::new(
+                start.to_kurbo_point(),
+                cp.to_kurbo_point(),
+                end.pos.to_kurbo_point(),
+            )),
+            Segment::CubBezTo { cp1, cp2, end } => kurbo::PathSeg::Cubic(kurbo::CubicBez::new(
+                start.to_kurbo_point(),
+                cp1.to_kurbo_point(),
+                cp2.to_kurbo_point(),
+                end.pos.to_kurbo_point(),
+            )),
+        }
+    }
+
+    /// Create from a [kurbo::PathSeg], ending with the given pressure. The start of the path segment is discarded.
+    pub fn from_kurbo_seg(seg: kurbo::PathSeg, end_pressure: f64) -> Self {
+        match seg {
+            kurbo::PathSeg::Line(line) => Segment::LineTo {
+                end: Element::new(na::Vector2::from_kurbo_point(line.p1), end_pressure),
+            },
+            kurbo::PathSeg::Quad(quad) => Segment::QuadBezTo {
+                cp: na::Vector2::from_kurbo_point(quad.p1),
+                end: Element::new(na::Vector2::from_kurbo_point(quad.p2), end_pressure),
+            },
+            kurbo::PathSeg::Cubic(cubic) => Segment::CubBezTo {
+                cp1: na::Vector2::from_kurbo_point(cubic.p1),
+                cp2: na::Vector2::from_kurbo_point(cubic.p2),
+                end: Element::new(na::Vector2::from_kurbo_point(cubic.p3), end_pressure),
+            },
+        }
+    }
 }
@@ -0,0 +1,531 @@
+// Imports
+use crate::PenPath;
+use crate::shapes::{Arrow, Ellipse, Line, Polygon, Rectangle, Shape};
+use crate::transform::Transform;
+use std::f64::consts::PI;
+
+/// Recognitions with a lower confidence should be discarded.
+pub const CONFIDENCE_MIN: f64 = 0.6;
+
+/// Paths shorter than this length are never recognized.
+const LENGTH_MIN: f64 = 8.0;
+/// The tolerance of the simplification into corners, relative to the diagonal of the bounds of the path.
+const SIMPLIFY_TOLERANCE: f64 = 0.06;
+/// Paths whose ends are closer than this fraction of their length are closed.
+const CLOSED_GAP_FACTOR: f64 = 0.2;
+/// Corners that turn less than this angle are merged into a straight edge.
+const CORNER_ANGLE_MIN: f64 = PI / 6.0;
+/// The mean deviation of the points from a line, relative to its length, at which the confidence drops to zero.
+const LINE_TOLERANCE: f64 = 0.04;
+/// The mean deviation of the points from the outline of a polygon, relative to the diagonal of its bounds,
+/// at which the confidence drops to zero.
+const POLYGON_TOLERANCE: f64 = 0.05;
+/// The mean deviation of the normalized radius of the points from the ellipse at which the confidence drops to zero.
+const ELLIPSE_TOLERANCE: f64 = 0.12;
+/// The maximum length of the arrow head, relative to the length of the shaft.
+const ARROW_HEAD_FACTOR_MAX: f64 = 0.6;
+
+/// A shape recognized in a freehand path.
+#[derive(Debug, Clone)]
+pub struct Recognition {
+    /// The recognized shape.
+    pub shape: Shape,
+    /// How well the path fits the shape, between 0.0 and 1.0.
+    pub confidence: f64,
+}
+
+/// Recognize a line, rectangle, ellipse, triangle or arrow in the path.
+///
+/// Open paths are matched against lines and arrows, closed paths against rectangles, ellipses and triangles.
+/// Returns the shape that fits best, or None when the path does not resemble any of them.
+pub fn recognize(path: &PenPath) -> Option<Recognition> {
+    let points = std::iter::once(path.start.pos)
+        .chain(path.segments.iter().map(|seg| seg.end().pos))
+        .collect::<Vec<na::Vector2<f64>>>();
+    let length = polyline_length(&points);
+    if points.len() < 2 || length < LENGTH_MIN {
+        return None;
+    }
+    let (mins, maxs) = points_bounds(&points);
+    let diagonal = (maxs - mins).magnitude();
+    let gap = (points[points.len() - 1] - points[0]).magnitude();
+
+    let candidates = if gap < length * CLOSED_GAP_FACTOR {
+        let corners = closed_corners(&points, diagonal * SIMPLIFY_TOLERANCE);
+        [
+            recognize_ellipse(&points),
+            recognize_rectangle(&points, &corners, diagonal),
+            recognize_triangle(&points, &corners, diagonal),
+        ]
+    } else {
+        [
+            recognize_line(&points),
+            recognize_arrow(&points, diagonal),
+            None,
+        ]
+    };
+
+    candidates
+        .into_iter()
+        .flatten()
+        .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+}
+
+fn recognize_line(points: &[na::Vector2<f64>]) -> Option<Recognition> {
+    let start = points[0];
+    let end = points[points.len() - 1];
+    let chord = (end - start).magnitude();
+    if chord <= 0.0 {
+        return None;
+    }
+    let deviation = points
+        .iter()
+        .map(|p| distance_to_segment(*p, start, end))
+        .sum::<f64>()
+        / points.len() as f64;
+
+    Some(Recognition {
+        shape: Shape::Line(Line { start, end }),
+        confidence: confidence(deviation / chord, LINE_TOLERANCE),
+    })
+}
+
+/// Recognizes arrows drawn in one stroke, the shaft first and then the head at its end.
+fn recognize_arrow(points: &[na::Vector2<f64>], diagonal: f64) -> Option<Recognition> {
+    let indices = simplify(points, diagonal * SIMPLIFY_TOLERANCE);
+    if indices.len() < 3 {
+        return None;
+    }
+    let start = points[indices[0]];
+    let tip = points[indices[1]];
+    let shaft = tip - start;
+    let shaft_len = shaft.magnitude();
+    if shaft_len <= 0.0 {
+        return None;
+    }
+    let direction = shaft / shaft_len;
+
+    // The head stays close to the tip and has at least one barb pointing backwards
+    let head = &indices[2..];
+    if head
+        .iter()
+        .any(|i| (points[*i] - tip).magnitude() > shaft_len * ARROW_HEAD_FACTOR_MAX)
+    {
+        return None;
+    }
+    let has_barb = head.iter().any(|i| {
+        let offset = points[*i] - tip;
+        offset.dot(&direction) < 0.0 && offset.perp(&direction).abs() > shaft_len * 0.05
+    });
+    if !has_barb {
+        return None;
+    }
+
+    let shaft_points = &points[indices[0]..=indices[1]];
+    let deviation = shaft_points
+        .iter()
+        .map(|p| distance_to_segment(*p, start, tip))
+        .sum::<f64>()
+        / shaft_points.len() as f64;
+
+    Some(Recognition {
+        shape: Shape::Arrow(Arrow { start, tip }),
+        confidence: confidence(deviation / shaft_len, LINE_TOLERANCE),
+    })
+}
+
+fn recognize_ellipse(points: &[na::Vector2<f64>]) -> Option<Recognition> {
+    // The principal axes of the points are the axes of the ellipse
+    let centroid = points.iter().sum::<na::Vector2<f64>>() / points.len() as f64;
+    let covariance = points
+        .iter()
+        .map(|p| {
+            let d = p - centroid;
+            d * d.transpose()
+        })
+        .sum::<na::Matrix2<f64>>();
+    let angle = 0.5 * (2.0 * covariance[(0, 1)]).atan2(covariance[(0, 0)] - covariance[(1, 1)]);
+
+    let rotation = na::Rotation2::new(-angle);
+    let local = points
+        .iter()
+        .map(|p| rotation * (p - centroid))
+        .collect::<Vec<na::Vector2<f64>>>();
+    let (mins, maxs) = points_bounds(&local);
+    let radii = (maxs - mins) * 0.5;
+    if radii[0] <= 0.0 || radii[1] <= 0.0 {
+        return None;
+    }
+    let local_center = (mins + maxs) * 0.5;
+    let deviation = local
+        .iter()
+        .map(|p| ((p - local_center).component_div(&radii).magnitude() - 1.0).abs())
+        .sum::<f64>()
+        / local.len() as f64;
+    let center = centroid + rotation.inverse() * local_center;
+
+    Some(Recognition {
+        shape: Shape::Ellipse(Ellipse {
+            radii,
+            transform: Transform::new_w_isometry(na::Isometry2::new(center, angle)),
+        }),
+        confidence: confidence(deviation, ELLIPSE_TOLERANCE),
+    })
+}
+
+fn recognize_rectangle(
+    points: &[na::Vector2<f64>],
+    corners: &[na::Vector2<f64>],
+    diagonal: f64,
+) -> Option<Recognition> {
+    if corners.len() != 4 || diagonal <= 0.0 {
+        return None;
+    }
+    // The mean direction of the edges, folded into a quarter turn
+    let (sin, cos) = (0..4)
+        .map(|i| {
+            let edge = corners[(i + 1) % 4] - corners[i];
+            let angle = edge[1].atan2(edge[0]) * 4.0;
+            (angle.sin(), angle.cos())
+        })
+        .fold((0.0, 0.0), |acc, (sin, cos)| (acc.0 + sin, acc.1 + cos));
+    let angle = sin.atan2(cos) / 4.0;
+
+    let rotation = na::Rotation2::new(-angle);
+    let local = corners
+        .iter()
+        .map(|p| rotation * *p)
+        .collect::<Vec<na::Vector2<f64>>>();
+    let (mins, maxs) = points_bounds(&local);
+    let half_extents = (maxs - mins) * 0.5;
+    let center = rotation.inverse() * ((mins + maxs) * 0.5);
+    let rectangle = Rectangle {
+        cuboid: p2d::shape::Cuboid::new(half_extents),
+        transform: Transform::new_w_isometry(na::Isometry2::new(center, angle)),
+    };
+
+    let outline = rectangle_corners(center, half_extents, angle);
+    Some(Recognition {
+        shape: Shape::Rectangle(rectangle),
+        confidence: confidence(
+            mean_distance_to_ring(points, &outline) / diagonal,
+            POLYGON_TOLERANCE,
+        ),
+    })
+}
+
+fn recognize_triangle(
+    points: &[na::Vector2<f64>],
+    corners: &[na::Vector2<f64>],
+    diagonal: f64,
+) -> Option<Recognition> {
+    if corners.len() != 3 || diagonal <= 0.0 {
+        return None;
+    }
+
+    Some(Recognition {
+        shape: Shape::Polygon(Polygon {
+            start: corners[0],
+            path: corners[1..].to_vec(),
+        }),
+        confidence: confidence(
+            mean_distance_to_ring(points, corners) / diagonal,
+            POLYGON_TOLERANCE,
+        ),
+    })
+}
+
+/// The confidence for the error, dropping linearly to zero at the tolerance.
+fn confidence(error: f64, tolerance: f64) -> f64 {
+    (1.0 - error / tolerance).clamp(0.0, 1.0)
+}
+
+/// The corners of a closed path, with the corners that barely turn merged into straight edges.
+fn closed_corners(points: &[na::Vector2<f64>], tolerance: f64) -> Vec<na::Vector2<f64>> {
+    // The chord of a closed path is too short to simplify against, so it is split at the point farthest from the start
+    let split = (0..points.len())
+        .max_by(|a, b| {
+            (points[*a] - points[0])
+                .magnitude()
+                .total_cmp(&(points[*b] - points[0]).magnitude())
+        })
+        .unwrap_or_default();
+    let mut corners = simplify(&points[..=split], tolerance)
+        .into_iter()
+        .chain(
+            simplify(&points[split..], tolerance)
+                .into_iter()
+                .skip(1)
+                .map(|i| i + split),
+        )
+        .map(|i| points[i])
+        .collect::<Vec<na::Vector2<f64>>>();
+    // The end closes back to the start
+    corners.pop();
+
+    loop {
+        let n = corners.len();
+        if n < 3 {
+            return corners;
+        }
+        let flattest = (0..n)
+            .map(|i| {
+                let prev = corners[(i + n - 1) % n];
+                let next = corners[(i + 1) % n];
+                (i, turning_angle(prev, corners[i], next))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        match flattest {
+            Some((i, angle)) if angle < CORNER_ANGLE_MIN => {
+                corners.remove(i);
+            }
+            _ => return corners,
+        }
+    }
+}
+
+/// The absolute angle the path turns at the corner.
+fn turning_angle(prev: na::Vector2<f64>, corner: na::Vector2<f64>, next: na::Vector2<f64>) -> f64 {
+    let a = corner - prev;
+    let b = next - corner;
+    if a.magnitude() <= 0.0 || b.magnitude() <= 0.0 {
+        return 0.0;
+    }
+    a.perp(&b).atan2(a.dot(&b)).abs()
+}
+
+/// Simplify the polyline with the Ramer-Douglas-Peucker algorithm.
+///
+/// Returns the indices of the kept points, including the first and the last one.
+fn simplify(points: &[na::Vector2<f64>], tolerance: f64) -> Vec<usize> {
+    fn simplify_range(
+        points: &[na::Vector2<f64>],
+        first: usize,
+        last: usize,
+        tolerance: f64,
+        kept: &mut Vec<usize>,
+    ) {
+        let farthest = (first + 1..last)
+            .map(|i| {
+                (
+                    i,
+                    distance_to_segment(points[i], points[first], points[last]),
+                )
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((i, distance)) = farthest
+            && distance > tolerance
+        {
+            simplify_range(points, first, i, tolerance, kept);
+            kept.push(i);
+            simplify_range(points, i, last, tolerance, kept);
+        }
+    }
+
+    if points.len() < 2 {
+        return (0..points.len()).collect();
+    }
+    let last = points.len() - 1;
+    let mut kept = vec![0];
+    simplify_range(points, 0, last, tolerance, &mut kept);
+    kept.push(last);
+    kept
+}
+
+fn distance_to_segment(p: na::Vector2<f64>, a: na::Vector2<f64>, b: na::Vector2<f64>) -> f64 {
+    let ab = b - a;
+    let len_squared = ab.magnitude_squared();
+    if len_squared <= 0.0 {
+        return (p - a).magnitude();
+    }
+    let t = ((p - a).dot(&ab) / len_squared).clamp(0.0, 1.0);
+    (p - (a + ab * t)).magnitude()
+}
+
+/// The mean distance of the points to the closed polygon through the corners.
+fn mean_distance_to_ring(points: &[na::Vector2<f64>], corners: &[na::Vector2<f64>]) -> f64 {
+    let n = corners.len();
+    points
+        .iter()
+        .map(|p| {
+            (0..n)
+                .map(|i| distance_to_segment(*p, corners[i], corners[(i + 1) % n]))
+                .fold(f64::INFINITY, f64::min)
+        })
+        .sum::<f64>()
+        / points.len() as f64
+}
+
+fn rectangle_corners(
+    center: na::Vector2<f64>,
+    half_extents: na::Vector2<f64>,
+    angle: f64,
+) -> [na::Vector2<f64>; 4] {
+    let rotation = na::Rotation2::new(angle);
+    [
+        na::vector![-1.0, -1.0],
+        na::vector![1.0, -1.0],
+        na::vector![1.0, 1.0],
+        na::vector![-1.0, 1.0],
+    ]
+    .map(|corner| center + rotation * corner.component_mul(&half_extents))
+}
+
+fn polyline_length(points: &[na::Vector2<f64>]) -> f64 {
+    points.windows(2).map(|w| (w[1] - w[0]).magnitude()).sum()
+}
+
+fn points_bounds(points: &[na::Vector2<f64>]) -> (na::Vector2<f64>, na::Vector2<f64>) {
+    points.iter().fold(
+        (
+            na::Vector2::repeat(f64::INFINITY),
+            na::Vector2::repeat(f64::NEG_INFINITY),
+        ),
+        |(mins, maxs), p| (mins.inf(p), maxs.sup(p)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::penpath::Element;
+    use approx::assert_abs_diff_eq;
+
+    /// A freehand path through the points, with a slight wobble like a real stroke.
+    fn freehand(points: impl IntoIterator<Item = na::Vector2<f64>>) -> PenPath {
+        PenPath::try_from_elements(points.into_iter().enumerate().map(|(i, p)| {
+            let wobble = (i as f64 * 1.3).sin() * 0.5;
+            Element::new(p + na::vector![wobble, -wobble], 0.5)
+        }))
+        .unwrap()
+    }
+
+    /// Points sampled evenly along the edges between the corners.
+    fn along_edges(corners: &[na::Vector2<f64>], closed: bool) -> Vec<na::Vector2<f64>> {
+        let n_edges = if closed {
+            corners.len()
+        } else {
+            corners.len() - 1
+        };
+        let mut points = (0..n_edges)
+            .flat_map(|i| {
+                let (a, b) = (corners[i], corners[(i + 1) % corners.len()]);
+                (0..20).map(move |j| a.lerp(&b, j as f64 / 20.0))
+            })
+            .collect::<Vec<na::Vector2<f64>>>();
+        points.push(if closed {
+            corners[0]
+        } else {
+            corners[corners.len() - 1]
+        });
+        points
+    }
+
+    fn rotated_rectangle(
+        center: na::Vector2<f64>,
+        half_extents: na::Vector2<f64>,
+        angle: f64,
+    ) -> PenPath {
+        freehand(along_edges(
+            &rectangle_corners(center, half_extents, angle),
+            true,
+        ))
+    }
+
+    fn ellipse(center: na::Vector2<f64>, radii: na::Vector2<f64>, angle: f64) -> PenPath {
+        let rotation = na::Rotation2::new(angle);
+        freehand((0..=64).map(|i| {
+            let t = i as f64 / 64.0 * 2.0 * PI;
+            center + rotation * na::vector![radii[0] * t.cos(), radii[1] * t.sin()]
+        }))
+    }
+
+    fn recognized(path: &PenPath) -> Shape {
+        let recognition = recognize(path).expect("no shape recognized");
+        assert!(
+            recognition.confidence >= CONFIDENCE_MIN,
+            "confidence {} too low for {:?}",
+            recognition.confidence,
+            recognition.shape
+        );
+        recognition.shape
+    }
+
+    fn not_recognized(path: &PenPath) -> bool {
+        recognize(path).is_none_or(|recognition| recognition.confidence < CONFIDENCE_MIN)
+    }
+
+    #[test]
+    fn recognize_line() {
+        let path = freehand(along_edges(
+            &[na::vector![10.0, 20.0], na::vector![110.0, 70.0]],
+            false,
+        ));
+        let Shape::Line(line) = recognized(&path) else {
+            panic!("not recognized as line");
+        };
+        assert_abs_diff_eq!(line.start, na::vector![10.0, 20.0], epsilon = 1.0);
+        assert_abs_diff_eq!(line.end, na::vector![110.0, 70.0], epsilon = 1.0);
+    }
+
+    #[test]
+    fn recognize_rectangle() {
+        for angle in [0.0, PI / 6.0] {
+            let path = rotated_rectangle(na::vector![50.0, 40.0], na::vector![50.0, 30.0], angle);
+            let Shape::Rectangle(rectangle) = recognized(&path) else {
+                panic!("not recognized as rectangle at angle {angle}");
+            };
+            assert_abs_diff_eq!(
+                rectangle.cuboid.half_extents,
+                na::vector![50.0, 30.0],
+                epsilon = 1.5
+            );
+            assert_abs_diff_eq!(
+                rectangle.transform.translation_part(),
+                na::vector![50.0, 40.0],
+                epsilon = 1.5
+            );
+        }
+    }
+
+    #[test]
+    fn recognize_ellipse() {
+        for angle in [0.0, 0.4] {
+            let path = ellipse(na::vector![100.0, 50.0], na::vector![60.0, 30.0], angle);
+            let Shape::Ellipse(ellipse) = recognized(&path) else {
+                panic!("not recognized as ellipse at angle {angle}");
+            };
+            assert_abs_diff_eq!(ellipse.radii, na::vector![60.0, 30.0], epsilon = 1.5);
+            assert_abs_diff_eq!(
+                ellipse.transform.translation_part(),
+                na::vector![100.0, 50.0],
+                epsilon = 1.5
+            );
+        }
+        // A circle is not mistaken for a rectangle
+        let path = ellipse(na::vector![0.0, 0.0], na::vector![40.0, 40.0], 0.0);
+        assert!(matches!(recognized(&path), Shape::Ellipse(_)));
+    }
+
+    #[test]
+    fn recognize_rejects() {
+        // Too short
+        assert!(recognize(&freehand([na::vector![0.0, 0.0], na::vector![3.0, 0.0]])).is_none());
+        // An open arc is not a line
+        assert!(not_recognized(&freehand((0..=32).map(|i| {
+            let t = i as f64 / 32.0 * PI;
+            na::vector![50.0 * t.cos(), 50.0 * t.sin()]
+        }))));
+        // A zigzag scribble
+        assert!(not_recognized(&freehand((0..=12).map(|i| na::vector![
+            i as f64 * 10.0,
+            if i % 2 == 0 { 0.0 } else { 40.0 }
+        ]))));
+        // A closed blob with a dent is neither an ellipse nor a polygon
+        assert!(not_recognized(&freehand((0..=64).map(|i| {
+            let t = i as f64 / 64.0 * 2.0 * PI;
+            let r = 40.0 + 25.0 * (3.0 * t).cos().powi(9);
+            na::vector![r * t.cos(), r * t.sin()]
+        }))));
+    }
+}
@@ -0,0 +1,288 @@
+// Imports
+use crate::ext::Vector2Ext;
+use crate::pathops::FLATTEN_TOLERANCE;
+use crate::shapes::Polygon;
+use p2d::bounding_volume::Aabb;
+use std::collections::VecDeque;
+
+/// The maximum amount of grid cells along one axis when detecting regions.
+pub const GRID_CELLS_MAX: usize = 1024;
+
+/// A line that encloses regions, together with other boundaries.
+#[derive(Debug, Clone)]
+pub struct Boundary {
+    /// The path of the line.
+    pub path: kurbo::BezPath,
+    /// The width of the line.
+    pub width: f64,
+}
+
+/// Detect the region around the position that is enclosed by the boundaries.
+///
+/// The boundaries are rasterized into a grid with the given cell size covering the bounds, then the region is flood
+/// filled starting at the position. The cell size is increased when the grid would exceed [GRID_CELLS_MAX] cells along
+/// one axis.
+///
+/// Returns the outline of the region, or None when the position lies on a boundary or the region is not enclosed
+/// inside the bounds. Holes in the region are not part of the outline.
+pub fn enclosed_region(
+    boundaries: &[Boundary],
+    pos: na::Vector2<f64>,
+    bounds: Aabb,
+    cell_size: f64,
+) -> Option<Polygon> {
+    let mut grid = RegionGrid::new(bounds, cell_size)?;
+    for boundary in boundaries {
+        grid.rasterize_boundary(boundary);
+    }
+
+    let start = grid.cell_at(pos)?;
+    if grid.cells[grid.index(start)] != Cell::Empty || !grid.flood_fill(start) {
+        return None;
+    }
+
+    // Removes the staircase pattern of the grid cells
+    let outline = simplify_ring(grid.trace_outline(), grid.cell_size);
+    let (start, path) = outline.split_first()?;
+    if path.len() < 2 {
+        return None;
+    }
+
+    Some(Polygon {
+        start: *start,
+        path: path.to_vec(),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    Boundary,
+    Region,
+}
+
+#[derive(Debug)]
+struct RegionGrid {
+    origin: na::Vector2<f64>,
+    cell_size: f64,
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl RegionGrid {
+    fn new(bounds: Aabb, cell_size: f64) -> Option<Self> {
+        let extents = bounds.extents();
+        if cell_size <= 0.0 || extents[0] <= 0.0 || extents[1] <= 0.0 {
+            return None;
+        }
+        let cell_size = cell_size.max(extents.max() / GRID_CELLS_MAX as f64);
+        let width = (extents[0] / cell_size).ceil() as usize;
+        let height = (extents[1] / cell_size).ceil() as usize;
+
+        Some(Self {
+            origin: bounds.mins.coords,
+            cell_size,
+            width,
+            height,
+            cells: vec![Cell::Empty; width * height],
+        })
+    }
+
+    fn index(&self, (x, y): (usize, usize)) -> usize {
+        y * self.width + x
+    }
+
+    fn cell_at(&self, pos: na::Vector2<f64>) -> Option<(usize, usize)> {
+        let rel = (pos - self.origin) / self.cell_size;
+        if rel[0] < 0.0 || rel[1] < 0.0 {
+            return None;
+        }
+        let (x, y) = (rel[0] as usize, rel[1] as usize);
+        (x < self.width && y < self.height).then_some((x, y))
+    }
+
+    /// Whether the cell is part of the region. Cells outside of the grid are not.
+    fn is_region(&self, (x, y): (isize, isize)) -> bool {
+        x >= 0
+            && y >= 0
+            && (x as usize) < self.width
+            && (y as usize) < self.height
+            && self.cells[self.index((x as usize, y as usize))] == Cell::Region
+    }
+
+    /// Mark the cells covered by the boundary line.
+    fn rasterize_boundary(&mut self, boundary: &Boundary) {
+        // At least the cells the line passes through are marked, so that the region can't leak through the boundary
+        let radius = (boundary.width * 0.5).max(self.cell_size * 0.75);
+        let step = self.cell_size * 0.25;
+        let mut start = kurbo::Point::ZERO;
+        let mut prev = kurbo::Point::ZERO;
+
+        kurbo::flatten(boundary.path.iter(), FLATTEN_TOLERANCE, |el| {
+            let next = match el {
+                kurbo::PathEl::MoveTo(point) => {
+                    start = point;
+                    prev = point;
+                    self.mark_disk(point, radius);
+                    return;
+                }
+                kurbo::PathEl::LineTo(point) => point,
+                kurbo::PathEl::ClosePath => start,
+                _ => return,
+            };
+            let n_steps = (prev.distance(next) / step).ceil().max(1.0) as usize;
+            for i in 1..=n_steps {
+                self.mark_disk(prev.lerp(next, i as f64 / n_steps as f64), radius);
+            }
+            prev = next;
+        });
+    }
+
+    fn mark_disk(&mut self, pos: kurbo::Point, radius: f64) {
+        let center = (na::Vector2::from_kurbo_point(pos) - self.origin) / self.cell_size;
+        let radius = radius / self.cell_size;
+        let x_range = (center[0] - radius).floor().max(0.0) as usize
+            ..=((center[0] + radius).ceil() as usize).min(self.width - 1);
+        let y_range = (center[1] - radius).floor().max(0.0) as usize
+            ..=((center[1] + radius).ceil() as usize).min(self.height - 1);
+
+        for y in y_range {
+            for x in x_range.clone() {
+                let cell_center = na::vector![x as f64 + 0.5, y as f64 + 0.5];
+                if (cell_center - center).magnitude() <= radius {
+                    let i = self.index((x, y));
+                    self.cells[i] = Cell::Boundary;
+                }
+            }
+        }
+    }
+
+    /// Flood fill the empty cells connected to the start cell.
+    ///
+    /// Returns false when the region reaches the border of the grid, meaning it is not enclosed.
+    fn flood_fill(&mut self, start: (usize, usize)) -> bool {
+        let i = self.index(start);
+        self.cells[i] = Cell::Region;
+        let mut queue = VecDeque::from([start]);
+
+        while let Some((x, y)) = queue.pop_front() {
+            if x == 0 || y == 0 || x == self.width - 1 || y == self.height - 1 {
+                return false;
+            }
+            for neighbour in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+                let i = self.index(neighbour);
+                if self.cells[i] == Cell::Empty {
+                    self.cells[i] = Cell::Region;
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Trace the outer outline of the region along the edges of its cells, clockwise.
+    ///
+    /// Returns the corners of the outline.
+    fn trace_outline(&self) -> Vec<na::Vector2<f64>> {
+        // The top left corner of the first cell is always a corner of the outer outline
+        let Some(first) = self.cells.iter().position(|cell| *cell == Cell::Region) else {
+            return vec![];
+        };
+        let start = ((first % self.width) as isize, (first / self.width) as isize);
+        let mut vertex = start;
+        let mut dir: (isize, isize) = (1, 0);
+        let mut outline = vec![];
+
+        // Guards against a malformed region
+        for _ in 0..2 * (self.width + 1) * (self.height + 1) {
+            vertex = (vertex.0 + dir.0, vertex.1 + dir.1);
+
+            // The cells in front of the vertex, on the left and on the right side of the direction.
+            // The region is kept on the right side.
+            let (front_left, front_right) = match dir {
+                (1, 0) => ((vertex.0, vertex.1 - 1), (vertex.0, vertex.1)),
+                (0, 1) => ((vertex.0, vertex.1), (vertex.0 - 1, vertex.1)),
+                (-1, 0) => ((vertex.0 - 1, vertex.1), (vertex.0 - 1, vertex.1 - 1)),
+                _ => ((vertex.0 - 1, vertex.1 - 1), (vertex.0, vertex.1 - 1)),
+            };
+            let new_dir = if !self.is_region(front_right) {
+                (-dir.1, dir.0)
+            } else if self.is_region(front_left) {
+                (dir.1, -dir.0)
+            } else {
+                dir
+            };
+
+            if new_dir != dir {
+                outline.push(
+                    self.origin + na::vector![vertex.0 as f64, vertex.1 as f64] * self.cell_size,
+                );
+            }
+            dir = new_dir;
+
+            if vertex == start {
+                break;
+            }
+        }
+
+        outline
+    }
+}
+
+/// Simplify the closed outline with the Ramer-Douglas-Peucker algorithm.
+fn simplify_ring(ring: Vec<na::Vector2<f64>>, epsilon: f64) -> Vec<na::Vector2<f64>> {
+    if ring.len() < 4 {
+        return ring;
+    }
+    // The ring is split into two polylines at the point farthest from the first one
+    let farthest = (1..ring.len())
+        .max_by(|&a, &b| {
+            (ring[a] - ring[0])
+                .magnitude()
+                .total_cmp(&(ring[b] - ring[0]).magnitude())
+        })
+        .unwrap_or(ring.len() / 2);
+    let mut second_half = ring[farthest..].to_vec();
+    second_half.push(ring[0]);
+
+    let mut simplified = simplify_polyline(&ring[..=farthest], epsilon);
+    simplified.pop();
+    let second_half = simplify_polyline(&second_half, epsilon);
+    simplified.extend_from_slice(&second_half[..second_half.len() - 1]);
+    simplified
+}
+
+fn simplify_polyline(points: &[na::Vector2<f64>], epsilon: f64) -> Vec<na::Vector2<f64>> {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let (i_max, dist_max) = points[1..points.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(i, point)| (i + 1, distance_to_line_segment(*point, first, last)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or((0, 0.0));
+
+    if dist_max > epsilon {
+        let mut simplified = simplify_polyline(&points[..=i_max], epsilon);
+        simplified.pop();
+        simplified.extend(simplify_polyline(&points[i_max..], epsilon));
+        simplified
+    } else {
+        vec![first, last]
+    }
+}
+
+fn distance_to_line_segment(
+    point: na::Vector2<f64>,
+    start: na::Vector2<f64>,
+    end: na::Vector2<f64>,
+) -> f64 {
+    let line = end - start;
+    let length_squared = line.norm_squared();
+    if length_squared == 0.0 {
+        return (point - start).magnitude();
+    }
+    let t = ((point - start).dot(&line) / length_squared).clamp(0.0, 1.0);
+    (point - (start + line * t)).magnitude()
+}
@@ -17,6 +17,23 @@ pub mod f64_dp3 {
     }
 }
 
+/// (De)Serialize an optional [f64] rounded to 3 decimal places
+pub mod option_f64_dp3 {
+    use serde::{Deserialize, Serialize};
+    use serde::{Deserializer, Serializer};
+
+    /// Serialize an optional [f64] rounded to 3 decimal places
+    pub fn serialize<S: Serializer>(v: &Option<f64>, s: S) -> Result<S::Ok, S::Error> {
+        const D: f64 = (10_u32.pow(3)) as f64;
+        v.map(|v| (v * D).round() / D).serialize(s)
+    }
+
+    /// Deserialize an optional [f64]
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<f64>, D::Error> {
+        Option::<f64>::deserialize(d)
+    }
+}
+
 /// (De)Serialize a [`na::Vector2<f64>`] rounded to 3 decimal places
 pub mod na_vector2_f64_dp3 {
     use serde::{Deserialize, Serialize};
@@ -0,0 +1,171 @@
+// Imports
+use super::{Line, Shapeable};
+use crate::Transform;
+use crate::ext::Vector2Ext;
+use crate::transform::Transformable;
+use kurbo::Shape;
+use p2d::bounding_volume::Aabb;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename = "arc")]
+/// A circular arc.
+pub struct Arc {
+    /// The radius.
+    #[serde(rename = "radius", with = "crate::serialize::f64_dp3")]
+    pub radius: f64,
+    /// The angle of the start point in radians.
+    #[serde(rename = "start_angle")]
+    pub start_angle: f64,
+    /// The sweep angle in radians, positive in the direction of increasing angles.
+    #[serde(rename = "sweep_angle")]
+    pub sweep_angle: f64,
+    /// The transform of the center of the arc.
+    #[serde(rename = "transform")]
+    pub transform: Transform,
+}
+
+impl Default for Arc {
+    fn default() -> Self {
+        Self {
+            radius: 0.0,
+            start_angle: 0.0,
+            sweep_angle: 0.0,
+            transform: Transform::default(),
+        }
+    }
+}
+
+impl Transformable for Arc {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.transform.append_translation_mut(offset);
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        self.transform.append_rotation_wrt_point_mut(angle, center)
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.transform.append_scale_mut(scale);
+    }
+}
+
+impl Shapeable for Arc {
+    fn bounds(&self) -> Aabb {
+        let rect = self.outline_path().bounding_box();
+        Aabb::new(na::point![rect.x0, rect.y0], na::point![rect.x1, rect.y1])
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        self.approx_with_lines()
+            .into_iter()
+            .flat_map(|line| line.hitboxes())
+            .collect()
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        let arc = kurbo::Arc::new(
+            kurbo::Point::ZERO,
+            kurbo::Vec2::new(self.radius, self.radius),
+            self.start_angle,
+            self.sweep_angle,
+            0.0,
+        );
+
+        self.transform.to_kurbo() * arc.to_path(0.1)
+    }
+}
+
+impl Arc {
+    /// Construct the arc that starts at `start`, passes through `through` and ends at `end`.
+    ///
+    /// Returns None when the points are collinear or coincide, then there is no circle through them.
+    pub fn from_three_points(
+        start: na::Vector2<f64>,
+        through: na::Vector2<f64>,
+        end: na::Vector2<f64>,
+    ) -> Option<Self> {
+        let center = circumcenter(start, through, end)?;
+        let angle_of = |p: na::Vector2<f64>| (p[1] - center[1]).atan2(p[0] - center[0]);
+        let start_angle = angle_of(start);
+
+        let sweep_positive = (angle_of(end) - start_angle).rem_euclid(std::f64::consts::TAU);
+        let through_positive = (angle_of(through) - start_angle).rem_euclid(std::f64::consts::TAU);
+        // sweep in the direction that passes the through point
+        let sweep_angle = if through_positive <= sweep_positive {
+            sweep_positive
+        } else {
+            sweep_positive - std::f64::consts::TAU
+        };
+
+        Some(Self {
+            radius: (start - center).magnitude(),
+            start_angle,
+            sweep_angle,
+            transform: Transform::new_w_isometry(na::Isometry2::new(center, 0.0)),
+        })
+    }
+
+    /// The start point.
+    pub fn start(&self) -> na::Vector2<f64> {
+        self.point_at_angle(self.start_angle)
+    }
+
+    /// The end point.
+    pub fn end(&self) -> na::Vector2<f64> {
+        self.point_at_angle(self.start_angle + self.sweep_angle)
+    }
+
+    /// The center of the circle the arc lies on.
+    pub fn center(&self) -> na::Vector2<f64> {
+        self.transform.translation_part()
+    }
+
+    /// Approximate with lines.
+    pub fn approx_with_lines(&self) -> Vec<Line> {
+        let mut lines = Vec::new();
+        let mut prev = kurbo::Point::ZERO;
+
+        kurbo::flatten(self.outline_path(), 0.25, |el| match el {
+            kurbo::PathEl::MoveTo(point) => prev = point,
+            kurbo::PathEl::LineTo(next) => {
+                lines.push(Line {
+                    start: na::Vector2::from_kurbo_point(prev),
+                    end: na::Vector2::from_kurbo_point(next),
+                });
+                prev = next
+            }
+            _ => {}
+        });
+
+        lines
+    }
+
+    fn point_at_angle(&self, angle: f64) -> na::Vector2<f64> {
+        self.transform
+            .transform_point(na::point![
+                self.radius * angle.cos(),
+                self.radius * angle.sin()
+            ])
+            .coords
+    }
+}
+
+/// The center of the circle through the three points, or None when they are collinear.
+fn circumcenter(
+    a: na::Vector2<f64>,
+    b: na::Vector2<f64>,
+    c: na::Vector2<f64>,
+) -> Option<na::Vector2<f64>> {
+    let (ab, ac) = (b - a, c - a);
+    let cross = ab.perp(&ac);
+    // relative to the lengths, so the check does not depend on the scale of the points
+    if cross.abs() <= f64::EPSILON.sqrt() * ab.magnitude() * ac.magnitude() {
+        return None;
+    }
+    let (ab_sq, ac_sq) = (ab.magnitude_squared(), ac.magnitude_squared());
+    let offset =
+        na::vector![ac[1] * ab_sq - ab[1] * ac_sq, ab[0] * ac_sq - ac[0] * ab_sq] / (2.0 * cross);
+
+    Some(a + offset)
+}
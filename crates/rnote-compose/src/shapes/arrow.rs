@@ -2,7 +2,7 @@
 use super::Line;
 use crate::ext::Vector2Ext;
 use crate::shapes::Shapeable;
-use crate::transform::Transformable;
+use crate::transform::{Projective, Transformable, Warpable};
 use kurbo::{PathEl, Shape};
 use na::Rotation2;
 use p2d::bounding_volume::Aabb;
@@ -58,6 +58,13 @@ impl Transformable for Arrow {
     }
 }
 
+impl Warpable for Arrow {
+    fn warp(&mut self, projective: &Projective) {
+        self.start = projective.transform_point(self.start);
+        self.tip = projective.transform_point(self.tip);
+    }
+}
+
 impl Shapeable for Arrow {
     fn bounds(&self) -> Aabb {
         self.internal_compute_bounds(None)
@@ -3,7 +3,7 @@ use super::line::Line;
 use super::quadbez::QuadraticBezier;
 use crate::ext::{KurboShapeExt, Vector2Ext};
 use crate::shapes::Shapeable;
-use crate::transform::Transformable;
+use crate::transform::{Projective, Transformable, Warpable};
 use kurbo::Shape;
 use p2d::bounding_volume::Aabb;
 use serde::{Deserialize, Serialize};
@@ -52,6 +52,15 @@ impl Transformable for CubicBezier {
     }
 }
 
+impl Warpable for CubicBezier {
+    fn warp(&mut self, projective: &Projective) {
+        self.start = projective.transform_point(self.start);
+        self.cp1 = projective.transform_point(self.cp1);
+        self.cp2 = projective.transform_point(self.cp2);
+        self.end = projective.transform_point(self.end);
+    }
+}
+
 impl Shapeable for CubicBezier {
     fn bounds(&self) -> p2d::bounding_volume::Aabb {
         self.outline_path().bounding_box().bounds_to_p2d_aabb()
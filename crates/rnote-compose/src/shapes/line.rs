@@ -3,7 +3,7 @@ use crate::Transform;
 use crate::ext::{AabbExt, Vector2Ext};
 use crate::shapes::Rectangle;
 use crate::shapes::Shapeable;
-use crate::transform::Transformable;
+use crate::transform::{Projective, Transformable, Warpable};
 use kurbo::Shape;
 use p2d::bounding_volume::Aabb;
 use serde::{Deserialize, Serialize};
@@ -40,6 +40,13 @@ impl Transformable for Line {
     }
 }
 
+impl Warpable for Line {
+    fn warp(&mut self, projective: &Projective) {
+        self.start = projective.transform_point(self.start);
+        self.end = projective.transform_point(self.end);
+    }
+}
+
 impl Shapeable for Line {
     fn bounds(&self) -> Aabb {
         AabbExt::new_positive(self.start.into(), self.end.into())
@@ -1,4 +1,6 @@
 // Modules
+/// Arc
+pub mod arc;
 /// Arrow
 pub mod arrow;
 /// Cubic-bezier curve
@@ -15,12 +17,21 @@ pub mod polyline;
 pub mod quadbez;
 /// Rectangle
 pub mod rectangle;
+/// Regular polygon
+pub mod regularpolygon;
+/// Rounded rectangle
+pub mod roundedrectangle;
 /// Shape
 pub mod shape;
 /// Shapeable
 pub mod shapeable;
+/// Spline
+pub mod spline;
+/// Star
+pub mod star;
 
 // Re-exports
+pub use arc::Arc;
 pub use arrow::Arrow;
 pub use cubbez::CubicBezier;
 pub use ellipse::Ellipse;
@@ -29,8 +40,12 @@ pub use polygon::Polygon;
 pub use polyline::Polyline;
 pub use quadbez::QuadraticBezier;
 pub use rectangle::Rectangle;
+pub use regularpolygon::RegularPolygon;
+pub use roundedrectangle::RoundedRectangle;
 pub use shape::Shape;
 pub use shapeable::Shapeable;
+pub use spline::Spline;
+pub use star::Star;
 
 /// Calculate the number hitbox elems for the given length ( e.g. length of a line, curve, etc.).
 fn hitbox_elems_for_shape_len(len: f64) -> i32 {
@@ -1,7 +1,7 @@
 // Imports
 use super::{Line, Shapeable};
 use crate::ext::{AabbExt, Vector2Ext};
-use crate::transform::Transformable;
+use crate::transform::{Projective, Transformable, Warpable};
 use p2d::bounding_volume::Aabb;
 use serde::{Deserialize, Serialize};
 
@@ -43,6 +43,15 @@ impl Transformable for Polygon {
     }
 }
 
+impl Warpable for Polygon {
+    fn warp(&mut self, projective: &Projective) {
+        self.start = projective.transform_point(self.start);
+        self.path.iter_mut().for_each(|p| {
+            *p = projective.transform_point(*p);
+        });
+    }
+}
+
 impl Shapeable for Polygon {
     fn bounds(&self) -> Aabb {
         let mut bounds = Aabb::new(self.start.into(), self.start.into());
@@ -3,7 +3,7 @@ use super::CubicBezier;
 use super::line::Line;
 use crate::ext::{KurboShapeExt, Vector2Ext};
 use crate::shapes::Shapeable;
-use crate::transform::Transformable;
+use crate::transform::{Projective, Transformable, Warpable};
 use kurbo::Shape;
 use p2d::bounding_volume::Aabb;
 use serde::{Deserialize, Serialize};
@@ -46,6 +46,14 @@ impl Transformable for QuadraticBezier {
     }
 }
 
+impl Warpable for QuadraticBezier {
+    fn warp(&mut self, projective: &Projective) {
+        self.start = projective.transform_point(self.start);
+        self.cp = projective.transform_point(self.cp);
+        self.end = projective.transform_point(self.end);
+    }
+}
+
 impl Shapeable for QuadraticBezier {
     fn bounds(&self) -> p2d::bounding_volume::Aabb {
         self.outline_path().bounding_box().bounds_to_p2d_aabb()
@@ -0,0 +1,107 @@
+// Imports
+use super::{Polygon, Shapeable};
+use crate::Transform;
+use crate::transform::Transformable;
+use p2d::bounding_volume::Aabb;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "regular_polygon")]
+/// A regular polygon.
+pub struct RegularPolygon {
+    /// The radius of the circumscribed circle.
+    #[serde(rename = "radius")]
+    pub radius: f64,
+    /// The number of sides.
+    #[serde(rename = "n_sides")]
+    pub n_sides: u32,
+    /// The transform of the center of the polygon. Untransformed, the first vertex points upwards.
+    #[serde(rename = "transform")]
+    pub transform: Transform,
+}
+
+impl Default for RegularPolygon {
+    fn default() -> Self {
+        Self {
+            radius: 0.0,
+            n_sides: Self::N_SIDES_DEFAULT,
+            transform: Transform::default(),
+        }
+    }
+}
+
+impl Transformable for RegularPolygon {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.transform.append_translation_mut(offset);
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        self.transform.append_rotation_wrt_point_mut(angle, center)
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.transform.append_scale_mut(scale);
+    }
+}
+
+impl Shapeable for RegularPolygon {
+    fn bounds(&self) -> Aabb {
+        self.to_polygon().bounds()
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        self.to_polygon().hitboxes()
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        self.to_polygon().outline_path()
+    }
+}
+
+impl RegularPolygon {
+    /// The minimum number of sides.
+    pub const N_SIDES_MIN: u32 = 3;
+    /// The default number of sides.
+    pub const N_SIDES_DEFAULT: u32 = 6;
+
+    /// A new regular polygon around the center, with its first vertex at the given position.
+    pub fn from_center_and_vertex(
+        center: na::Vector2<f64>,
+        vertex: na::Vector2<f64>,
+        n_sides: u32,
+    ) -> Self {
+        let v = vertex - center;
+        let angle = v[1].atan2(v[0]) + std::f64::consts::FRAC_PI_2;
+
+        Self {
+            radius: v.magnitude(),
+            n_sides,
+            transform: Transform::new_w_isometry(na::Isometry2::new(center, angle)),
+        }
+    }
+
+    /// The vertices.
+    pub fn vertices(&self) -> Vec<na::Vector2<f64>> {
+        let n_sides = self.n_sides.max(Self::N_SIDES_MIN);
+        (0..n_sides)
+            .map(|i| {
+                let angle = std::f64::consts::TAU * f64::from(i) / f64::from(n_sides)
+                    - std::f64::consts::FRAC_PI_2;
+                self.transform
+                    .transform_point(na::point![
+                        self.radius * angle.cos(),
+                        self.radius * angle.sin()
+                    ])
+                    .coords
+            })
+            .collect()
+    }
+
+    /// Convert to a polygon with the same vertices.
+    pub fn to_polygon(&self) -> Polygon {
+        let mut vertices = self.vertices().into_iter();
+        let mut polygon = Polygon::new(vertices.next().unwrap_or_else(na::Vector2::zeros));
+        polygon.extend(vertices);
+        polygon
+    }
+}
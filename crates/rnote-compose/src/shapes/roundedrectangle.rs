@@ -0,0 +1,146 @@
+// Imports
+use super::Line;
+use crate::Transform;
+use crate::shapes::Shapeable;
+use crate::transform::Transformable;
+use kurbo::Shape;
+use p2d::bounding_volume::Aabb;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename = "rounded_rectangle")]
+/// A rectangle with rounded corners.
+pub struct RoundedRectangle {
+    #[serde(rename = "cuboid", with = "crate::serialize::p2d_cuboid_dp3")]
+    /// The cuboid, specifies the extents.
+    pub cuboid: p2d::shape::Cuboid,
+    #[serde(rename = "radii")]
+    /// The corner radii, clockwise starting from the top left corner.
+    pub radii: [f64; 4],
+    #[serde(rename = "transform")]
+    /// The transform of the center of the cuboid.
+    pub transform: Transform,
+}
+
+impl Default for RoundedRectangle {
+    fn default() -> Self {
+        Self {
+            cuboid: p2d::shape::Cuboid::new(na::Vector2::zeros()),
+            radii: [0.0; 4],
+            transform: Transform::default(),
+        }
+    }
+}
+
+impl Shapeable for RoundedRectangle {
+    fn bounds(&self) -> Aabb {
+        let center = self.transform.affine * na::point![0.0, 0.0];
+        // using a vector to ignore the translation
+        let half_extents = na::Vector2::from_homogeneous(
+            self.transform.affine.into_inner().abs()
+                * self.cuboid.half_extents.abs().to_homogeneous(),
+        )
+        .unwrap()
+        .abs();
+
+        Aabb::from_half_extents(center, half_extents)
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        self.approx_with_lines()
+            .into_iter()
+            .flat_map(|line| line.hitboxes())
+            .collect()
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        let half_extents = self.cuboid.half_extents.abs();
+        // the radii can't exceed the half of the shorter side
+        let max_radius = half_extents.min();
+        let [top_left, top_right, bottom_right, bottom_left] =
+            self.radii.map(|radius| radius.clamp(0.0, max_radius));
+
+        let rounded_rect = kurbo::RoundedRect::from_rect(
+            kurbo::Rect::new(
+                -half_extents[0],
+                -half_extents[1],
+                half_extents[0],
+                half_extents[1],
+            ),
+            kurbo::RoundedRectRadii::new(top_left, top_right, bottom_right, bottom_left),
+        );
+
+        self.transform.to_kurbo() * rounded_rect.to_path(0.25)
+    }
+}
+
+impl Transformable for RoundedRectangle {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.transform.append_translation_mut(offset);
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        self.transform.append_rotation_wrt_point_mut(angle, center)
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.transform.append_scale_mut(scale);
+    }
+}
+
+impl RoundedRectangle {
+    /// Construct from corners across from each other, with the same radius for all corners.
+    pub fn from_corners(first: na::Vector2<f64>, second: na::Vector2<f64>, radius: f64) -> Self {
+        let half_extents = (second - first).abs() * 0.5;
+        let center = first + (second - first) * 0.5;
+
+        let cuboid = p2d::shape::Cuboid::new(half_extents);
+        let transform = Transform::new_w_isometry(na::Isometry2::new(center, 0.0));
+
+        Self {
+            cuboid,
+            radii: [radius; 4],
+            transform,
+        }
+    }
+
+    /// The corners of the bounding rectangle, clockwise starting from the top left corner.
+    pub fn corners(&self) -> [na::Vector2<f64>; 4] {
+        let half_extents = self.cuboid.half_extents;
+        [
+            na::point![-half_extents[0], -half_extents[1]],
+            na::point![half_extents[0], -half_extents[1]],
+            na::point![half_extents[0], half_extents[1]],
+            na::point![-half_extents[0], half_extents[1]],
+        ]
+        .map(|corner| self.transform.transform_point(corner).coords)
+    }
+
+    /// Approximate with lines.
+    pub fn approx_with_lines(&self) -> Vec<Line> {
+        let mut lines = Vec::new();
+        let mut start = kurbo::Point::new(0.0, 0.0);
+        let mut prev = kurbo::Point::new(0.0, 0.0);
+
+        kurbo::flatten(self.outline_path(), 0.25, |el| match el {
+            kurbo::PathEl::MoveTo(point) => {
+                start = point;
+                prev = point;
+            }
+            kurbo::PathEl::LineTo(next) => {
+                lines.push(Line {
+                    start: na::vector![prev.x, prev.y],
+                    end: na::vector![next.x, next.y],
+                });
+                prev = next
+            }
+            kurbo::PathEl::ClosePath => lines.push(Line {
+                start: na::vector![prev.x, prev.y],
+                end: na::vector![start.x, start.y],
+            }),
+            _ => {}
+        });
+
+        lines
+    }
+}
@@ -1,8 +1,10 @@
 // Imports
 use super::{
-    Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle, Shapeable,
+    Arc, Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle,
+    RegularPolygon, RoundedRectangle, Shapeable, Spline, Star,
 };
-use crate::transform::Transformable;
+use crate::Transform;
+use crate::transform::{Projective, Transformable, Warpable};
 use p2d::bounding_volume::Aabb;
 use serde::{Deserialize, Serialize};
 
@@ -34,6 +36,21 @@ pub enum Shape {
     /// A polygon shape.
     #[serde(rename = "polygon")]
     Polygon(Polygon),
+    /// A rectangle shape with rounded corners.
+    #[serde(rename = "rounded_rect")]
+    RoundedRectangle(RoundedRectangle),
+    /// A regular polygon shape.
+    #[serde(rename = "regular_polygon")]
+    RegularPolygon(RegularPolygon),
+    /// A star shape.
+    #[serde(rename = "star")]
+    Star(Star),
+    /// A circular arc shape.
+    #[serde(rename = "arc")]
+    Arc(Arc),
+    /// A smooth curve shape through points.
+    #[serde(rename = "spline")]
+    Spline(Spline),
 }
 
 impl Default for Shape {
@@ -69,6 +86,21 @@ impl Transformable for Shape {
             Self::Polygon(polygon) => {
                 polygon.translate(offset);
             }
+            Self::RoundedRectangle(rounded_rectangle) => {
+                rounded_rectangle.translate(offset);
+            }
+            Self::RegularPolygon(regular_polygon) => {
+                regular_polygon.translate(offset);
+            }
+            Self::Star(star) => {
+                star.translate(offset);
+            }
+            Self::Arc(arc) => {
+                arc.translate(offset);
+            }
+            Self::Spline(spline) => {
+                spline.translate(offset);
+            }
         }
     }
 
@@ -98,6 +130,21 @@ impl Transformable for Shape {
             Self::Polygon(polygon) => {
                 polygon.rotate(angle, center);
             }
+            Self::RoundedRectangle(rounded_rectangle) => {
+                rounded_rectangle.rotate(angle, center);
+            }
+            Self::RegularPolygon(regular_polygon) => {
+                regular_polygon.rotate(angle, center);
+            }
+            Self::Star(star) => {
+                star.rotate(angle, center);
+            }
+            Self::Arc(arc) => {
+                arc.rotate(angle, center);
+            }
+            Self::Spline(spline) => {
+                spline.rotate(angle, center);
+            }
         }
     }
 
@@ -127,6 +174,65 @@ impl Transformable for Shape {
             Self::Polygon(polygon) => {
                 polygon.scale(scale);
             }
+            Self::RoundedRectangle(rounded_rectangle) => {
+                rounded_rectangle.scale(scale);
+            }
+            Self::RegularPolygon(regular_polygon) => {
+                regular_polygon.scale(scale);
+            }
+            Self::Star(star) => {
+                star.scale(scale);
+            }
+            Self::Arc(arc) => {
+                arc.scale(scale);
+            }
+            Self::Spline(spline) => {
+                spline.scale(scale);
+            }
+        }
+    }
+}
+
+impl Warpable for Shape {
+    fn warp(&mut self, projective: &Projective) {
+        match self {
+            Self::Line(line) => {
+                line.warp(projective);
+            }
+            Self::Arrow(arrow) => {
+                arrow.warp(projective);
+            }
+            Self::QuadraticBezier(quadbez) => {
+                quadbez.warp(projective);
+            }
+            Self::CubicBezier(cubbez) => {
+                cubbez.warp(projective);
+            }
+            Self::Polyline(polyline) => {
+                polyline.warp(projective);
+            }
+            Self::Polygon(polygon) => {
+                polygon.warp(projective);
+            }
+            Self::Spline(spline) => {
+                spline.warp(projective);
+            }
+            Self::Rectangle(_)
+            | Self::Ellipse(_)
+            | Self::RoundedRectangle(_)
+            | Self::RegularPolygon(_)
+            | Self::Star(_)
+            | Self::Arc(_) => {
+                if let Some(affine) = projective.to_affine() {
+                    // Shapes defined by a transform stay the same kind of shape when the warp is only a skew
+                    if let Some(transform) = self.transform_mut() {
+                        transform.affine = affine * transform.affine;
+                    }
+                } else {
+                    *self = self.to_flattened();
+                    self.warp(projective);
+                }
+            }
         }
     }
 }
@@ -142,6 +248,11 @@ impl Shapeable for Shape {
             Self::CubicBezier(cubbez) => cubbez.bounds(),
             Self::Polyline(polyline) => polyline.bounds(),
             Self::Polygon(polygon) => polygon.bounds(),
+            Self::RoundedRectangle(rounded_rectangle) => rounded_rectangle.bounds(),
+            Self::RegularPolygon(regular_polygon) => regular_polygon.bounds(),
+            Self::Star(star) => star.bounds(),
+            Self::Arc(arc) => arc.bounds(),
+            Self::Spline(spline) => spline.bounds(),
         }
     }
 
@@ -155,6 +266,11 @@ impl Shapeable for Shape {
             Self::CubicBezier(cubbez) => cubbez.hitboxes(),
             Self::Polyline(polyline) => polyline.hitboxes(),
             Self::Polygon(polygon) => polygon.hitboxes(),
+            Self::RoundedRectangle(rounded_rectangle) => rounded_rectangle.hitboxes(),
+            Self::RegularPolygon(regular_polygon) => regular_polygon.hitboxes(),
+            Self::Star(star) => star.hitboxes(),
+            Self::Arc(arc) => arc.hitboxes(),
+            Self::Spline(spline) => spline.hitboxes(),
         }
     }
 
@@ -168,6 +284,136 @@ impl Shapeable for Shape {
             Self::CubicBezier(cubbez) => cubbez.outline_path(),
             Self::Polyline(polyline) => polyline.outline_path(),
             Self::Polygon(polygon) => polygon.outline_path(),
+            Self::RoundedRectangle(rounded_rectangle) => rounded_rectangle.outline_path(),
+            Self::RegularPolygon(regular_polygon) => regular_polygon.outline_path(),
+            Self::Star(star) => star.outline_path(),
+            Self::Arc(arc) => arc.outline_path(),
+            Self::Spline(spline) => spline.outline_path(),
+        }
+    }
+}
+
+impl Shape {
+    /// The tolerance when flattening curved outlines for warping.
+    const FLATTEN_TOLERANCE: f64 = 0.1;
+
+    /// The points that new shapes can snap onto: vertices, endpoints and the midpoints of straight segments.
+    pub fn snap_points(&self) -> Vec<na::Vector2<f64>> {
+        fn with_segment_midpoints(
+            points: &[na::Vector2<f64>],
+            closed: bool,
+        ) -> Vec<na::Vector2<f64>> {
+            let mut snap_points = points.to_vec();
+            snap_points.extend(points.windows(2).map(|w| (w[0] + w[1]) * 0.5));
+            if closed && let (Some(first), Some(last)) = (points.first(), points.last()) {
+                snap_points.push((first + last) * 0.5);
+            }
+            snap_points
+        }
+
+        match self {
+            Self::Line(line) => with_segment_midpoints(&[line.start, line.end], false),
+            Self::Arrow(arrow) => with_segment_midpoints(&[arrow.start, arrow.tip], false),
+            Self::Rectangle(rectangle) => {
+                let corners = rectangle
+                    .outline_lines()
+                    .iter()
+                    .map(|line| line.start)
+                    .collect::<Vec<na::Vector2<f64>>>();
+                let mut snap_points = with_segment_midpoints(&corners, true);
+                snap_points.push(rectangle.transform.translation_part());
+                snap_points
+            }
+            Self::Ellipse(ellipse) => [
+                na::point![0.0, 0.0],
+                na::point![ellipse.radii[0], 0.0],
+                na::point![-ellipse.radii[0], 0.0],
+                na::point![0.0, ellipse.radii[1]],
+                na::point![0.0, -ellipse.radii[1]],
+            ]
+            .into_iter()
+            .map(|point| ellipse.transform.transform_point(point).coords)
+            .collect(),
+            Self::QuadraticBezier(quadbez) => vec![quadbez.start, quadbez.end],
+            Self::CubicBezier(cubbez) => vec![cubbez.start, cubbez.end],
+            Self::Polyline(polyline) => with_segment_midpoints(
+                &std::iter::once(polyline.start)
+                    .chain(polyline.path.iter().copied())
+                    .collect::<Vec<na::Vector2<f64>>>(),
+                false,
+            ),
+            Self::Polygon(polygon) => with_segment_midpoints(
+                &std::iter::once(polygon.start)
+                    .chain(polygon.path.iter().copied())
+                    .collect::<Vec<na::Vector2<f64>>>(),
+                true,
+            ),
+            Self::RoundedRectangle(rounded_rectangle) => {
+                let corners = rounded_rectangle.corners();
+                // the corners are rounded off, so only the midpoints of the sides are on the outline
+                let mut snap_points = (0..corners.len())
+                    .map(|i| (corners[i] + corners[(i + 1) % corners.len()]) * 0.5)
+                    .collect::<Vec<na::Vector2<f64>>>();
+                snap_points.push(rounded_rectangle.transform.translation_part());
+                snap_points
+            }
+            Self::RegularPolygon(regular_polygon) => {
+                let mut snap_points = with_segment_midpoints(&regular_polygon.vertices(), true);
+                snap_points.push(regular_polygon.transform.translation_part());
+                snap_points
+            }
+            Self::Star(star) => {
+                let mut snap_points = star.vertices();
+                snap_points.push(star.transform.translation_part());
+                snap_points
+            }
+            Self::Arc(arc) => vec![arc.start(), arc.end(), arc.center()],
+            Self::Spline(spline) => spline.points(),
+        }
+    }
+
+    /// The transform of shapes that are defined by one.
+    fn transform_mut(&mut self) -> Option<&mut Transform> {
+        match self {
+            Self::Rectangle(rectangle) => Some(&mut rectangle.transform),
+            Self::Ellipse(ellipse) => Some(&mut ellipse.transform),
+            Self::RoundedRectangle(rounded_rectangle) => Some(&mut rounded_rectangle.transform),
+            Self::RegularPolygon(regular_polygon) => Some(&mut regular_polygon.transform),
+            Self::Star(star) => Some(&mut star.transform),
+            Self::Arc(arc) => Some(&mut arc.transform),
+            Self::Line(_)
+            | Self::Arrow(_)
+            | Self::QuadraticBezier(_)
+            | Self::CubicBezier(_)
+            | Self::Polyline(_)
+            | Self::Polygon(_)
+            | Self::Spline(_) => None,
+        }
+    }
+
+    /// The outline flattened into a polygon, or a polyline when the outline is open.
+    fn to_flattened(&self) -> Self {
+        let mut points = vec![];
+        let mut closed = false;
+        self.outline_path()
+            .flatten(Self::FLATTEN_TOLERANCE, |el| match el {
+                kurbo::PathEl::MoveTo(p) | kurbo::PathEl::LineTo(p) => {
+                    points.push(na::vector![p.x, p.y]);
+                }
+                kurbo::PathEl::ClosePath => closed = true,
+                // flattening only emits lines
+                kurbo::PathEl::QuadTo(..) | kurbo::PathEl::CurveTo(..) => {}
+            });
+        if closed && points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+        let start = points.first().copied().unwrap_or_default();
+        let path = points.into_iter().skip(1).collect();
+
+        if closed {
+            Self::Polygon(Polygon { start, path })
+        } else {
+            Self::Polyline(Polyline { start, path })
         }
     }
 }
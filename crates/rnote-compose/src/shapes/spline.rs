@@ -0,0 +1,126 @@
+// Imports
+use super::{CubicBezier, Shapeable};
+use crate::ext::Vector2Ext;
+use crate::transform::{Projective, Transformable, Warpable};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "spline")]
+/// A smooth curve through points, like drawn with a french curve.
+///
+/// Composed of cubic bezier segments that join with a continuous tangent.
+pub struct Spline {
+    /// The spline start
+    #[serde(rename = "start")]
+    pub start: na::Vector2<f64>,
+    /// The points the spline passes through after the start
+    #[serde(rename = "path")]
+    pub path: Vec<na::Vector2<f64>>,
+}
+
+impl Transformable for Spline {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.start += offset;
+        for p in &mut self.path {
+            *p += offset;
+        }
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        let mut isometry = na::Isometry2::identity();
+        isometry.append_rotation_wrt_point_mut(&na::UnitComplex::new(angle), &center);
+
+        self.start = isometry.transform_point(&self.start.into()).coords;
+        for p in &mut self.path {
+            *p = isometry.transform_point(&(*p).into()).coords;
+        }
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.start = self.start.component_mul(&scale);
+        for p in &mut self.path {
+            *p = p.component_mul(&scale);
+        }
+    }
+}
+
+impl Warpable for Spline {
+    fn warp(&mut self, projective: &Projective) {
+        self.start = projective.transform_point(self.start);
+        self.path.iter_mut().for_each(|p| {
+            *p = projective.transform_point(*p);
+        });
+    }
+}
+
+impl Shapeable for Spline {
+    fn bounds(&self) -> Aabb {
+        self.to_cubbezs().into_iter().fold(
+            Aabb::new(self.start.into(), self.start.into()),
+            |acc, cubbez| acc.merged(&cubbez.bounds()),
+        )
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        let mut hitboxes = vec![Aabb::new(self.start.into(), self.start.into())];
+        hitboxes.extend(
+            self.to_cubbezs()
+                .into_iter()
+                .flat_map(|cubbez| cubbez.hitboxes()),
+        );
+        hitboxes
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        let mut bezpath = kurbo::BezPath::new();
+        bezpath.move_to(self.start.to_kurbo_point());
+        for cubbez in self.to_cubbezs() {
+            bezpath.curve_to(
+                cubbez.cp1.to_kurbo_point(),
+                cubbez.cp2.to_kurbo_point(),
+                cubbez.end.to_kurbo_point(),
+            );
+        }
+        bezpath
+    }
+}
+
+impl Spline {
+    /// A new spline
+    pub fn new(start: na::Vector2<f64>) -> Self {
+        Self {
+            start,
+            path: Vec::new(),
+        }
+    }
+
+    /// The points the spline passes through, including the start.
+    pub fn points(&self) -> Vec<na::Vector2<f64>> {
+        std::iter::once(self.start)
+            .chain(self.path.iter().copied())
+            .collect()
+    }
+
+    /// Convert to cubic bezier segments with the catmull-rom spline algorithm.
+    ///
+    /// The tangents at the joints are continuous, the tangents at the ends point towards the neighbouring points.
+    /// Segments without a length are skipped.
+    pub fn to_cubbezs(&self) -> Vec<CubicBezier> {
+        let points = self.points();
+        (1..points.len())
+            .filter_map(|i| {
+                // the end points are duplicated to have a neighbour for the tangents
+                let first = points[i.saturating_sub(2)];
+                let forth = points[(i + 1).min(points.len() - 1)];
+                CubicBezier::new_w_catmull_rom(first, points[i - 1], points[i], forth)
+            })
+            .collect()
+    }
+}
+
+impl Extend<na::Vector2<f64>> for Spline {
+    fn extend<T: IntoIterator<Item = na::Vector2<f64>>>(&mut self, iter: T) {
+        self.path.extend(iter);
+    }
+}
@@ -0,0 +1,118 @@
+// Imports
+use super::{Polygon, Shapeable};
+use crate::Transform;
+use crate::transform::Transformable;
+use p2d::bounding_volume::Aabb;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "star")]
+/// A star.
+pub struct Star {
+    /// The radius of the tips.
+    #[serde(rename = "outer_radius")]
+    pub outer_radius: f64,
+    /// The radius of the inner vertices between the tips.
+    #[serde(rename = "inner_radius")]
+    pub inner_radius: f64,
+    /// The number of tips.
+    #[serde(rename = "n_points")]
+    pub n_points: u32,
+    /// The transform of the center of the star. Untransformed, the first tip points upwards.
+    #[serde(rename = "transform")]
+    pub transform: Transform,
+}
+
+impl Default for Star {
+    fn default() -> Self {
+        Self {
+            outer_radius: 0.0,
+            inner_radius: 0.0,
+            n_points: Self::N_POINTS_DEFAULT,
+            transform: Transform::default(),
+        }
+    }
+}
+
+impl Transformable for Star {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.transform.append_translation_mut(offset);
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        self.transform.append_rotation_wrt_point_mut(angle, center)
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.transform.append_scale_mut(scale);
+    }
+}
+
+impl Shapeable for Star {
+    fn bounds(&self) -> Aabb {
+        self.to_polygon().bounds()
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        self.to_polygon().hitboxes()
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        self.to_polygon().outline_path()
+    }
+}
+
+impl Star {
+    /// The minimum number of tips.
+    pub const N_POINTS_MIN: u32 = 3;
+    /// The default number of tips.
+    pub const N_POINTS_DEFAULT: u32 = 5;
+    /// The default ratio of the inner to the outer radius.
+    pub const INNER_RADIUS_RATIO_DEFAULT: f64 = 0.5;
+
+    /// A new star around the center, with its first tip at the given position.
+    pub fn from_center_and_tip(
+        center: na::Vector2<f64>,
+        tip: na::Vector2<f64>,
+        n_points: u32,
+        inner_radius_ratio: f64,
+    ) -> Self {
+        let v = tip - center;
+        let angle = v[1].atan2(v[0]) + std::f64::consts::FRAC_PI_2;
+        let outer_radius = v.magnitude();
+
+        Self {
+            outer_radius,
+            inner_radius: outer_radius * inner_radius_ratio,
+            n_points,
+            transform: Transform::new_w_isometry(na::Isometry2::new(center, angle)),
+        }
+    }
+
+    /// The vertices, alternating between the tips and the inner vertices.
+    pub fn vertices(&self) -> Vec<na::Vector2<f64>> {
+        let n_vertices = self.n_points.max(Self::N_POINTS_MIN) * 2;
+        (0..n_vertices)
+            .map(|i| {
+                let radius = if i % 2 == 0 {
+                    self.outer_radius
+                } else {
+                    self.inner_radius
+                };
+                let angle = std::f64::consts::TAU * f64::from(i) / f64::from(n_vertices)
+                    - std::f64::consts::FRAC_PI_2;
+                self.transform
+                    .transform_point(na::point![radius * angle.cos(), radius * angle.sin()])
+                    .coords
+            })
+            .collect()
+    }
+
+    /// Convert to a polygon with the same vertices.
+    pub fn to_polygon(&self) -> Polygon {
+        let mut vertices = self.vertices().into_iter();
+        let mut polygon = Polygon::new(vertices.next().unwrap_or_else(na::Vector2::zeros));
+        polygon.extend(vertices);
+        polygon
+    }
+}
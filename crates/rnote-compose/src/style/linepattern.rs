@@ -0,0 +1,245 @@
+// Imports
+use anyhow::Context;
+use kurbo::Vec2;
+use num_derive::{FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Serialize};
+
+/// A line pattern, rendered procedurally along the outline of a shape instead of a plain line.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, FromPrimitive, ToPrimitive,
+)]
+#[serde(rename = "line_pattern")]
+pub enum LinePattern {
+    /// No pattern, a plain line
+    #[default]
+    #[serde(rename = "none")]
+    None,
+    /// A zigzag line
+    #[serde(rename = "zigzag")]
+    Zigzag,
+    /// A sine wave line
+    #[serde(rename = "wave")]
+    Wave,
+    /// Two parallel lines
+    #[serde(rename = "double")]
+    Double,
+    /// Two parallel lines, connected by perpendicular ties
+    #[serde(rename = "train_track")]
+    TrainTrack,
+}
+
+impl TryFrom<u32> for LinePattern {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value)
+            .with_context(|| format!("LinePattern try_from::<u32>() for value {value} failed"))
+    }
+}
+
+impl LinePattern {
+    /// The tolerance used when flattening the outline.
+    const FLATTEN_TOLERANCE: f64 = 0.1;
+    /// The amplitude of the zigzag and wave patterns, relative to the stroke width.
+    const AMPLITUDE_TO_WIDTH_RATIO: f64 = 1.5;
+    /// The period of the zigzag and wave patterns, relative to the stroke width.
+    const PERIOD_TO_WIDTH_RATIO: f64 = 6.0;
+    /// The distance between the lines of the double and train-track patterns, relative to the stroke width.
+    const GAP_TO_WIDTH_RATIO: f64 = 3.0;
+    /// The distance between the ties of the train-track pattern, relative to the stroke width.
+    const TIE_SPACING_TO_WIDTH_RATIO: f64 = 4.0;
+    /// The number of samples of one wave period.
+    const WAVE_SAMPLES_PER_PERIOD: usize = 16;
+    /// The line width the patterns are stroked with, relative to the stroke width.
+    const LINE_WIDTH_RATIO: f64 = 0.5;
+
+    /// Whether the pattern is a plain line.
+    pub fn is_none(&self) -> bool {
+        *self == Self::None
+    }
+
+    /// The half of the extent of the patterned line perpendicular to the outline.
+    pub fn half_extent(&self, stroke_width: f64) -> f64 {
+        let line_width = stroke_width * Self::LINE_WIDTH_RATIO;
+        match self {
+            Self::None => stroke_width * 0.5,
+            Self::Zigzag | Self::Wave => {
+                stroke_width * Self::AMPLITUDE_TO_WIDTH_RATIO + line_width * 0.5
+            }
+            // the ties overhang the rails by one line width
+            Self::Double | Self::TrainTrack => {
+                stroke_width * Self::GAP_TO_WIDTH_RATIO * 0.5 + line_width * 1.5
+            }
+        }
+    }
+
+    /// Generate the patterned path along the outline and the line width it should be stroked with.
+    ///
+    /// Returns None for [LinePattern::None] and strokes without a width, then the outline should be stroked directly.
+    pub fn apply(
+        &self,
+        outline: &kurbo::BezPath,
+        stroke_width: f64,
+    ) -> Option<(kurbo::BezPath, f64)> {
+        if self.is_none() || stroke_width <= 0.0 {
+            return None;
+        }
+        let line_width = stroke_width * Self::LINE_WIDTH_RATIO;
+        let amplitude = stroke_width * Self::AMPLITUDE_TO_WIDTH_RATIO;
+        let period = stroke_width * Self::PERIOD_TO_WIDTH_RATIO;
+        let half_gap = stroke_width * Self::GAP_TO_WIDTH_RATIO * 0.5;
+        let mut path = kurbo::BezPath::new();
+
+        for polyline in flattened_polylines(outline, Self::FLATTEN_TOLERANCE) {
+            if polyline.length() <= 0.0 {
+                continue;
+            }
+            match self {
+                Self::None => {}
+                Self::Zigzag => {
+                    let n_halves = (polyline.length() / (period * 0.5)).round().max(1.0) as usize;
+                    // the zigzag starts and ends on the outline
+                    append_offset_polyline(&mut path, &polyline, n_halves, false, |i, _| match i {
+                        0 => 0.0,
+                        i if i == n_halves => 0.0,
+                        i if i % 2 == 1 => amplitude,
+                        _ => -amplitude,
+                    });
+                }
+                Self::Wave => {
+                    let n_periods = (polyline.length() / period).round().max(1.0) as usize;
+                    let wave_period = polyline.length() / n_periods as f64;
+                    append_offset_polyline(
+                        &mut path,
+                        &polyline,
+                        n_periods * Self::WAVE_SAMPLES_PER_PERIOD,
+                        false,
+                        |_, s| amplitude * (std::f64::consts::TAU * s / wave_period).sin(),
+                    );
+                }
+                Self::Double | Self::TrainTrack => {
+                    // the rails are sampled with a step of the line width
+                    let n_steps = (polyline.length() / line_width).ceil().max(1.0) as usize;
+                    append_offset_polyline(&mut path, &polyline, n_steps, true, |_, _| half_gap);
+                    append_offset_polyline(&mut path, &polyline, n_steps, true, |_, _| -half_gap);
+                    if *self == Self::Double {
+                        continue;
+                    }
+
+                    let tie_spacing = stroke_width * Self::TIE_SPACING_TO_WIDTH_RATIO;
+                    let n_ties = (polyline.length() / tie_spacing).floor() as usize;
+                    // the ties are centered on the polyline
+                    let offset = (polyline.length() - n_ties as f64 * tie_spacing) * 0.5;
+                    let tie_half_length = half_gap + line_width;
+                    for i in 0..=n_ties {
+                        let (pos, normal) = polyline.sample(offset + i as f64 * tie_spacing);
+                        path.move_to(pos + normal * tie_half_length);
+                        path.line_to(pos - normal * tie_half_length);
+                    }
+                }
+            }
+        }
+
+        Some((path, line_width))
+    }
+}
+
+/// A flattened subpath of the outline.
+#[derive(Debug, Clone)]
+struct FlattenedPolyline {
+    points: Vec<kurbo::Point>,
+    /// The arc-length position of each point.
+    lengths: Vec<f64>,
+    closed: bool,
+}
+
+impl FlattenedPolyline {
+    fn new(start: kurbo::Point) -> Self {
+        Self {
+            points: vec![start],
+            lengths: vec![0.0],
+            closed: false,
+        }
+    }
+
+    fn push(&mut self, point: kurbo::Point) {
+        let prev = *self.points.last().unwrap();
+        let length = self.length() + (point - prev).hypot();
+        // skip duplicate points, they don't have a direction
+        if (point - prev).hypot() > 0.0 {
+            self.points.push(point);
+            self.lengths.push(length);
+        }
+    }
+
+    fn length(&self) -> f64 {
+        *self.lengths.last().unwrap()
+    }
+
+    /// The position and the unit normal at the arc-length position.
+    fn sample(&self, s: f64) -> (kurbo::Point, Vec2) {
+        let s = s.clamp(0.0, self.length());
+        let i = self
+            .lengths
+            .partition_point(|length| *length < s)
+            .clamp(1, self.points.len() - 1);
+        let (start, end) = (self.points[i - 1], self.points[i]);
+        let seg_length = self.lengths[i] - self.lengths[i - 1];
+        let t = if seg_length > 0.0 {
+            (s - self.lengths[i - 1]) / seg_length
+        } else {
+            0.0
+        };
+        let dir = (end - start).normalize();
+
+        (start.lerp(end, t), Vec2::new(-dir.y, dir.x))
+    }
+}
+
+fn flattened_polylines(outline: &kurbo::BezPath, tolerance: f64) -> Vec<FlattenedPolyline> {
+    let mut polylines: Vec<FlattenedPolyline> = vec![];
+
+    kurbo::flatten(outline.iter(), tolerance, |el| match el {
+        kurbo::PathEl::MoveTo(point) => polylines.push(FlattenedPolyline::new(point)),
+        kurbo::PathEl::LineTo(point) => {
+            if let Some(polyline) = polylines.last_mut() {
+                polyline.push(point);
+            }
+        }
+        kurbo::PathEl::ClosePath => {
+            if let Some(polyline) = polylines.last_mut() {
+                let start = polyline.points[0];
+                polyline.push(start);
+                polyline.closed = true;
+            }
+        }
+        _ => {}
+    });
+
+    polylines
+}
+
+/// Append the polyline offset along its normal, sampled in evenly spaced steps.
+///
+/// The offset is given by the sample index and its arc-length position.
+fn append_offset_polyline(
+    path: &mut kurbo::BezPath,
+    polyline: &FlattenedPolyline,
+    n_steps: usize,
+    close: bool,
+    offset: impl Fn(usize, f64) -> f64,
+) {
+    for i in 0..=n_steps {
+        let s = polyline.length() * i as f64 / n_steps as f64;
+        let (pos, normal) = polyline.sample(s);
+        let point = pos + normal * offset(i, s);
+        if i == 0 {
+            path.move_to(point);
+        } else {
+            path.line_to(point);
+        }
+    }
+    if close && polyline.closed {
+        path.close_path();
+    }
+}
@@ -3,6 +3,8 @@
 mod composer;
 /// Draw indicators
 pub mod indicators;
+/// Line patterns
+mod linepattern;
 /// The rough module for rough styles
 pub mod rough;
 /// The smooth module for smooth styles
@@ -17,11 +19,13 @@ use self::textured::TexturedOptions;
 
 // Imports
 use crate::shapes::{
-    Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle,
+    Arc, Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle,
+    RegularPolygon, RoundedRectangle, Spline, Star,
 };
 use crate::{Color, PenPath, Shape};
 use anyhow::Context;
 pub use composer::Composer;
+pub use linepattern::LinePattern;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,7 +72,7 @@ impl Style {
     pub fn bounds_margin(&self) -> f64 {
         match self {
             Style::Smooth(options) => options.stroke_width,
-            Style::Rough(options) => options.stroke_width + RoughOptions::ROUGH_BOUNDS_MARGIN,
+            Style::Rough(options) => options.stroke_width * 0.5 + options.bounds_margin(),
             Style::Textured(options) => options.stroke_width,
         }
     }
@@ -263,6 +267,96 @@ impl Composer<Style> for Polygon {
     }
 }
 
+impl Composer<Style> for RoundedRectangle {
+    fn composed_bounds(&self, options: &Style) -> p2d::bounding_volume::Aabb {
+        match options {
+            Style::Smooth(options) => self.composed_bounds(options),
+            Style::Rough(options) => self.composed_bounds(options),
+            Style::Textured(_options) => unimplemented!(),
+        }
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &Style) {
+        match options {
+            Style::Smooth(options) => self.draw_composed(cx, options),
+            Style::Rough(options) => self.draw_composed(cx, options),
+            Style::Textured(_options) => unimplemented!(),
+        }
+    }
+}
+
+impl Composer<Style> for RegularPolygon {
+    fn composed_bounds(&self, options: &Style) -> p2d::bounding_volume::Aabb {
+        match options {
+            Style::Smooth(options) => self.composed_bounds(options),
+            Style::Rough(options) => self.composed_bounds(options),
+            Style::Textured(_options) => unimplemented!(),
+        }
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &Style) {
+        match options {
+            Style::Smooth(options) => self.draw_composed(cx, options),
+            Style::Rough(options) => self.draw_composed(cx, options),
+            Style::Textured(_options) => unimplemented!(),
+        }
+    }
+}
+
+impl Composer<Style> for Star {
+    fn composed_bounds(&self, options: &Style) -> p2d::bounding_volume::Aabb {
+        match options {
+            Style::Smooth(options) => self.composed_bounds(options),
+            Style::Rough(options) => self.composed_bounds(options),
+            Style::Textured(_options) => unimplemented!(),
+        }
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &Style) {
+        match options {
+            Style::Smooth(options) => self.draw_composed(cx, options),
+            Style::Rough(options) => self.draw_composed(cx, options),
+            Style::Textured(_options) => unimplemented!(),
+        }
+    }
+}
+
+impl Composer<Style> for Arc {
+    fn composed_bounds(&self, options: &Style) -> p2d::bounding_volume::Aabb {
+        match options {
+            Style::Smooth(options) => self.composed_bounds(options),
+            Style::Rough(options) => self.composed_bounds(options),
+            Style::Textured(_options) => unimplemented!(),
+        }
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &Style) {
+        match options {
+            Style::Smooth(options) => self.draw_composed(cx, options),
+            Style::Rough(options) => self.draw_composed(cx, options),
+            Style::Textured(_options) => unimplemented!(),
+        }
+    }
+}
+
+impl Composer<Style> for Spline {
+    fn composed_bounds(&self, options: &Style) -> p2d::bounding_volume::Aabb {
+        match options {
+            Style::Smooth(options) => self.composed_bounds(options),
+            Style::Rough(options) => self.composed_bounds(options),
+            Style::Textured(_options) => unimplemented!(),
+        }
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &Style) {
+        match options {
+            Style::Smooth(options) => self.draw_composed(cx, options),
+            Style::Rough(options) => self.draw_composed(cx, options),
+            Style::Textured(_options) => unimplemented!(),
+        }
+    }
+}
+
 impl Composer<Style> for PenPath {
     fn composed_bounds(&self, options: &Style) -> p2d::bounding_volume::Aabb {
         match options {
@@ -292,6 +386,13 @@ impl Composer<Style> for Shape {
             Shape::CubicBezier(cubic_bezier) => cubic_bezier.composed_bounds(options),
             Shape::Polyline(polyline) => polyline.composed_bounds(options),
             Shape::Polygon(polygon) => polygon.composed_bounds(options),
+            Shape::RoundedRectangle(rounded_rectangle) => {
+                rounded_rectangle.composed_bounds(options)
+            }
+            Shape::RegularPolygon(regular_polygon) => regular_polygon.composed_bounds(options),
+            Shape::Star(star) => star.composed_bounds(options),
+            Shape::Arc(arc) => arc.composed_bounds(options),
+            Shape::Spline(spline) => spline.composed_bounds(options),
         }
     }
 
@@ -305,6 +406,13 @@ impl Composer<Style> for Shape {
             Shape::CubicBezier(cubic_bezier) => cubic_bezier.draw_composed(cx, options),
             Shape::Polyline(polyline) => polyline.draw_composed(cx, options),
             Shape::Polygon(polygon) => polygon.draw_composed(cx, options),
+            Shape::RoundedRectangle(rounded_rectangle) => {
+                rounded_rectangle.draw_composed(cx, options)
+            }
+            Shape::RegularPolygon(regular_polygon) => regular_polygon.draw_composed(cx, options),
+            Shape::Star(star) => star.draw_composed(cx, options),
+            Shape::Arc(arc) => arc.draw_composed(cx, options),
+            Shape::Spline(spline) => spline.draw_composed(cx, options),
         }
     }
 }
@@ -315,6 +423,8 @@ impl Composer<Style> for Shape {
     Clone,
     Copy,
     Default,
+    PartialEq,
+    Eq,
     Serialize,
     Deserialize,
     num_derive::FromPrimitive,
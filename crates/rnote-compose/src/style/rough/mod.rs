@@ -10,7 +10,8 @@ use super::Composer;
 use crate::Color;
 use crate::ext::Vector2Ext;
 use crate::shapes::{
-    Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle, Shapeable,
+    Arc, Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle,
+    RegularPolygon, RoundedRectangle, Shapeable, Spline, Star,
 };
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use roughr::Point2D;
@@ -21,7 +22,10 @@ fn generate_roughr_options(options: &RoughOptions) -> roughr::core::Options {
     roughr_options
         .stroke_width(options.stroke_width as f32)
         .hachure_angle(options.hachure_angle.to_degrees() as f32)
-        .fill_style(options.fill_style.into());
+        .fill_style(options.fill_style.into())
+        .roughness(options.roughness.clamp(0.0, RoughOptions::ROUGHNESS_MAX) as f32)
+        .bowing(options.bowing.clamp(0.0, RoughOptions::BOWING_MAX) as f32)
+        .disable_multi_stroke(!options.multi_stroke);
 
     if let Some(seed) = options.seed {
         roughr_options.seed(seed);
@@ -46,8 +50,7 @@ fn generate_roughr_options(options: &RoughOptions) -> roughr::core::Options {
 
 impl Composer<RoughOptions> for Line {
     fn composed_bounds(&self, options: &RoughOptions) -> p2d::bounding_volume::Aabb {
-        self.bounds()
-            .loosened(options.stroke_width * 0.5 + RoughOptions::ROUGH_BOUNDS_MARGIN)
+        self.bounds().loosened(options.bounds_margin())
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &RoughOptions) {
@@ -69,7 +72,7 @@ impl Composer<RoughOptions> for Line {
 impl Composer<RoughOptions> for Arrow {
     fn composed_bounds(&self, options: &RoughOptions) -> p2d::bounding_volume::Aabb {
         self.internal_compute_bounds(Some(options.stroke_width))
-            .loosened(options.stroke_width * 0.5 + RoughOptions::ROUGH_BOUNDS_MARGIN)
+            .loosened(options.bounds_margin())
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &RoughOptions) {
@@ -111,8 +114,7 @@ impl Composer<RoughOptions> for Arrow {
 
 impl Composer<RoughOptions> for Rectangle {
     fn composed_bounds(&self, options: &RoughOptions) -> p2d::bounding_volume::Aabb {
-        self.bounds()
-            .loosened(options.stroke_width * 0.5 + RoughOptions::ROUGH_BOUNDS_MARGIN)
+        self.bounds().loosened(options.bounds_margin())
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &RoughOptions) {
@@ -137,8 +139,7 @@ impl Composer<RoughOptions> for Rectangle {
 
 impl Composer<RoughOptions> for Ellipse {
     fn composed_bounds(&self, options: &RoughOptions) -> p2d::bounding_volume::Aabb {
-        self.bounds()
-            .loosened(options.stroke_width * 0.5 + RoughOptions::ROUGH_BOUNDS_MARGIN)
+        self.bounds().loosened(options.bounds_margin())
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &RoughOptions) {
@@ -158,8 +159,7 @@ impl Composer<RoughOptions> for Ellipse {
 
 impl Composer<RoughOptions> for QuadraticBezier {
     fn composed_bounds(&self, options: &RoughOptions) -> p2d::bounding_volume::Aabb {
-        self.bounds()
-            .loosened(options.stroke_width * 0.5 + RoughOptions::ROUGH_BOUNDS_MARGIN)
+        self.bounds().loosened(options.bounds_margin())
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &RoughOptions) {
@@ -180,8 +180,7 @@ impl Composer<RoughOptions> for QuadraticBezier {
 
 impl Composer<RoughOptions> for CubicBezier {
     fn composed_bounds(&self, options: &RoughOptions) -> p2d::bounding_volume::Aabb {
-        self.bounds()
-            .loosened(options.stroke_width * 0.5 + RoughOptions::ROUGH_BOUNDS_MARGIN)
+        self.bounds().loosened(options.bounds_margin())
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &RoughOptions) {
@@ -203,8 +202,7 @@ impl Composer<RoughOptions> for CubicBezier {
 
 impl Composer<RoughOptions> for Polyline {
     fn composed_bounds(&self, options: &RoughOptions) -> Aabb {
-        self.bounds()
-            .loosened(options.stroke_width * 0.5 + RoughOptions::ROUGH_BOUNDS_MARGIN)
+        self.bounds().loosened(options.bounds_margin())
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &RoughOptions) {
@@ -228,8 +226,7 @@ impl Composer<RoughOptions> for Polyline {
 
 impl Composer<RoughOptions> for Polygon {
     fn composed_bounds(&self, options: &RoughOptions) -> Aabb {
-        self.bounds()
-            .loosened(options.stroke_width * 0.5 + RoughOptions::ROUGH_BOUNDS_MARGIN)
+        self.bounds().loosened(options.bounds_margin())
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &RoughOptions) {
@@ -251,6 +248,81 @@ impl Composer<RoughOptions> for Polygon {
     }
 }
 
+impl Composer<RoughOptions> for RoundedRectangle {
+    fn composed_bounds(&self, options: &RoughOptions) -> Aabb {
+        self.bounds().loosened(options.bounds_margin())
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &RoughOptions) {
+        // the rounded corners are approximated by the flattened outline
+        let points: Vec<roughr::Point2D<_, _>> = self
+            .approx_with_lines()
+            .into_iter()
+            .map(|line| roughr::Point2D::new(line.start[0] as f32, line.start[1] as f32))
+            .collect();
+
+        let drawable =
+            rough_piet::KurboGenerator::new(generate_roughr_options(options)).polygon(&points);
+
+        drawable.draw(cx);
+    }
+}
+
+impl Composer<RoughOptions> for RegularPolygon {
+    fn composed_bounds(&self, options: &RoughOptions) -> Aabb {
+        self.to_polygon().composed_bounds(options)
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &RoughOptions) {
+        self.to_polygon().draw_composed(cx, options)
+    }
+}
+
+impl Composer<RoughOptions> for Star {
+    fn composed_bounds(&self, options: &RoughOptions) -> Aabb {
+        self.to_polygon().composed_bounds(options)
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &RoughOptions) {
+        self.to_polygon().draw_composed(cx, options)
+    }
+}
+
+impl Composer<RoughOptions> for Arc {
+    fn composed_bounds(&self, options: &RoughOptions) -> Aabb {
+        self.bounds().loosened(options.bounds_margin())
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &RoughOptions) {
+        // the arc is approximated by the flattened outline
+        let lines = self.approx_with_lines();
+        let points: Vec<roughr::Point2D<_, _>> = lines
+            .first()
+            .map(|line| line.start)
+            .into_iter()
+            .chain(lines.iter().map(|line| line.end))
+            .map(|p| roughr::Point2D::new(p[0] as f32, p[1] as f32))
+            .collect();
+
+        let drawable = rough_piet::KurboGenerator::new(generate_roughr_options(options))
+            .linear_path(&points, false);
+
+        drawable.draw(cx);
+    }
+}
+
+impl Composer<RoughOptions> for Spline {
+    fn composed_bounds(&self, options: &RoughOptions) -> Aabb {
+        self.bounds().loosened(options.bounds_margin())
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &RoughOptions) {
+        for cubbez in self.to_cubbezs() {
+            cubbez.draw_composed(cx, options);
+        }
+    }
+}
+
 impl Composer<RoughOptions> for crate::Shape {
     fn composed_bounds(&self, options: &RoughOptions) -> Aabb {
         match self {
@@ -262,6 +334,15 @@ impl Composer<RoughOptions> for crate::Shape {
             crate::Shape::CubicBezier(cubbez) => cubbez.composed_bounds(options),
             crate::Shape::Polyline(polyline) => polyline.composed_bounds(options),
             crate::Shape::Polygon(polygon) => polygon.composed_bounds(options),
+            crate::Shape::RoundedRectangle(rounded_rectangle) => {
+                rounded_rectangle.composed_bounds(options)
+            }
+            crate::Shape::RegularPolygon(regular_polygon) => {
+                regular_polygon.composed_bounds(options)
+            }
+            crate::Shape::Star(star) => star.composed_bounds(options),
+            crate::Shape::Arc(arc) => arc.composed_bounds(options),
+            crate::Shape::Spline(spline) => spline.composed_bounds(options),
         }
     }
 
@@ -275,6 +356,15 @@ impl Composer<RoughOptions> for crate::Shape {
             crate::Shape::CubicBezier(cubbez) => cubbez.draw_composed(cx, options),
             crate::Shape::Polyline(polyline) => polyline.draw_composed(cx, options),
             crate::Shape::Polygon(polygon) => polygon.draw_composed(cx, options),
+            crate::Shape::RoundedRectangle(rounded_rectangle) => {
+                rounded_rectangle.draw_composed(cx, options)
+            }
+            crate::Shape::RegularPolygon(regular_polygon) => {
+                regular_polygon.draw_composed(cx, options)
+            }
+            crate::Shape::Star(star) => star.draw_composed(cx, options),
+            crate::Shape::Arc(arc) => arc.draw_composed(cx, options),
+            crate::Shape::Spline(spline) => spline.draw_composed(cx, options),
         }
     }
 }
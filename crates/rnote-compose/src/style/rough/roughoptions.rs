@@ -22,6 +22,15 @@ pub struct RoughOptions {
     /// Hachure angle (in radians).
     #[serde(rename = "hachure_angle", with = "crate::serialize::f64_dp3")]
     pub hachure_angle: f64,
+    /// How much the lines wobble and deviate from the geometry. 0.0 draws the exact geometry.
+    #[serde(rename = "roughness", with = "crate::serialize::f64_dp3")]
+    pub roughness: f64,
+    /// How much lines bow outwards in their middle.
+    #[serde(rename = "bowing", with = "crate::serialize::f64_dp3")]
+    pub bowing: f64,
+    /// Whether lines are drawn in two slightly offset passes, like sketched by hand.
+    #[serde(rename = "multi_stroke")]
+    pub multi_stroke: bool,
     /// An optional seed to generate reproducible shapes.
     #[serde(rename = "seed")]
     pub seed: Option<u64>,
@@ -36,15 +45,30 @@ impl Default for RoughOptions {
             fill_style: FillStyle::Hachure,
             // Default hachure angle (in rad). is -41 degrees
             hachure_angle: -0.715585,
+            roughness: 1.0,
+            bowing: 1.0,
+            multi_stroke: true,
             seed: None,
         }
     }
 }
 
 impl RoughOptions {
-    /// The margin for the bounds of composed rough shapes.
-    // TODO: make this not a fixed value, but dependent on the shape size, roughness, etc.
+    /// The margin for the bounds of composed rough shapes, at a roughness of 1.0.
+    // TODO: make this also dependent on the shape size
     pub const ROUGH_BOUNDS_MARGIN: f64 = 20.0;
+    /// The maximum roughness.
+    pub const ROUGHNESS_MAX: f64 = 10.0;
+    /// The maximum bowing.
+    pub const BOWING_MAX: f64 = 10.0;
+
+    /// The margin the bounds of composed shapes are loosened by.
+    ///
+    /// Grows with the roughness, as the lines deviate further from the geometry.
+    pub fn bounds_margin(&self) -> f64 {
+        self.stroke_width * 0.5
+            + Self::ROUGH_BOUNDS_MARGIN * self.roughness.clamp(1.0, Self::ROUGHNESS_MAX)
+    }
 
     /// Advance the seed, if it is set to `Some()`.
     pub fn advance_seed(&mut self) {
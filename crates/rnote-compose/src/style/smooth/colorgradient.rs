@@ -0,0 +1,136 @@
+// Imports
+use crate::Color;
+use serde::{Deserialize, Serialize};
+
+/// A color stop of a gradient.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "color_stop")]
+pub struct ColorStop {
+    /// The offset of the stop, ranging [0.0, 1.0].
+    #[serde(rename = "offset", with = "crate::serialize::f64_dp3")]
+    pub offset: f64,
+    /// The color at the offset.
+    #[serde(rename = "color")]
+    pub color: Color,
+}
+
+impl Default for ColorStop {
+    fn default() -> Self {
+        Self {
+            offset: 0.0,
+            color: Color::BLACK,
+        }
+    }
+}
+
+impl ColorStop {
+    /// A new color stop.
+    pub fn new(offset: f64, color: Color) -> Self {
+        Self {
+            offset: offset.clamp(0.0, 1.0),
+            color,
+        }
+    }
+}
+
+/// A color gradient with two or more color stops, interpolated linearly between the stops.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename = "color_gradient")]
+pub struct ColorGradient {
+    #[serde(rename = "stops")]
+    stops: Vec<ColorStop>,
+}
+
+impl Default for ColorGradient {
+    fn default() -> Self {
+        Self {
+            stops: vec![
+                ColorStop::new(0.0, Color::BLACK),
+                ColorStop::new(1.0, Color::BLACK),
+            ],
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ColorGradient {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename = "color_gradient")]
+        struct ColorGradientPrecursor {
+            #[serde(rename = "stops")]
+            stops: Vec<ColorStop>,
+        }
+
+        let precursor = ColorGradientPrecursor::deserialize(deserializer)?;
+        Self::new(precursor.stops).ok_or_else(|| {
+            serde::de::Error::custom("color gradient needs at least two color stops")
+        })
+    }
+}
+
+impl ColorGradient {
+    /// The minimum number of color stops.
+    pub const N_STOPS_MIN: usize = 2;
+
+    /// A new gradient from the color stops, which are ordered by their offset.
+    ///
+    /// Returns None when less than two stops are given.
+    pub fn new(stops: impl IntoIterator<Item = ColorStop>) -> Option<Self> {
+        let mut stops = stops
+            .into_iter()
+            .map(|stop| ColorStop::new(stop.offset, stop.color))
+            .collect::<Vec<ColorStop>>();
+        if stops.len() < Self::N_STOPS_MIN {
+            return None;
+        }
+        stops.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+        Some(Self { stops })
+    }
+
+    /// A gradient from the start to the end color.
+    pub fn new_two_colors(start: Color, end: Color) -> Self {
+        Self {
+            stops: vec![ColorStop::new(0.0, start), ColorStop::new(1.0, end)],
+        }
+    }
+
+    /// The color stops, ordered by their offset.
+    pub fn stops(&self) -> &[ColorStop] {
+        &self.stops
+    }
+
+    /// The interpolated color at the offset. Offsets outside of the stops get the color of the nearest stop.
+    pub fn color_at(&self, offset: f64) -> Color {
+        let first = self.stops[0];
+        let last = self.stops[self.stops.len() - 1];
+        if offset <= first.offset {
+            return first.color;
+        }
+        if offset >= last.offset {
+            return last.color;
+        }
+
+        self.stops
+            .windows(2)
+            .find(|w| offset <= w[1].offset)
+            .map(|w| {
+                let (a, b) = (w[0], w[1]);
+                let span = b.offset - a.offset;
+                let t = if span > 0.0 {
+                    (offset - a.offset) / span
+                } else {
+                    1.0
+                };
+                Color::new(
+                    a.color.r + (b.color.r - a.color.r) * t,
+                    a.color.g + (b.color.g - a.color.g) * t,
+                    a.color.b + (b.color.b - a.color.b) * t,
+                    a.color.a + (b.color.a - a.color.a) * t,
+                )
+            })
+            .unwrap_or(last.color)
+    }
+}
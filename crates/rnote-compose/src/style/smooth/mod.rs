@@ -1,23 +1,26 @@
 // Modules
+mod colorgradient;
 mod smoothoptions;
 
 // Re-exports
-pub use smoothoptions::{LineCap, LineStyle, SmoothOptions};
+pub use colorgradient::{ColorGradient, ColorStop};
+pub use smoothoptions::{LineCap, LineStyle, PenTip, SmoothOptions};
 
 // Imports
 use super::Composer;
-use crate::PenPath;
 use crate::ext::Vector2Ext;
-use crate::penpath::{self, Segment};
+use crate::penpath::{self, Element, Segment};
 use crate::shapes::{
-    Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle, Shapeable,
+    Arc, Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle,
+    RegularPolygon, RoundedRectangle, Shapeable, Spline, Star,
 };
+use crate::{Color, PenPath};
 use kurbo::Shape;
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 
 impl Composer<SmoothOptions> for Line {
     fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
-        self.bounds().loosened(options.stroke_width * 0.5)
+        self.bounds().loosened(options.stroke_half_extent())
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
@@ -25,13 +28,7 @@ impl Composer<SmoothOptions> for Line {
         let line = self.outline_path();
 
         if let Some(stroke_color) = options.stroke_color {
-            let stroke_brush = cx.solid_brush(stroke_color.into());
-            cx.stroke_styled(
-                line,
-                &stroke_brush,
-                options.stroke_width,
-                &options.piet_stroke_style,
-            );
+            stroke_outline(cx, &line, stroke_color, &options.piet_stroke_style, options);
         }
         cx.restore().unwrap();
     }
@@ -62,7 +59,7 @@ impl Composer<SmoothOptions> for Arrow {
 
 impl Composer<SmoothOptions> for Rectangle {
     fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
-        self.bounds().loosened(options.stroke_width * 0.5)
+        self.bounds().loosened(options.stroke_half_extent())
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
@@ -75,12 +72,39 @@ impl Composer<SmoothOptions> for Rectangle {
         }
 
         if let Some(stroke_color) = options.stroke_color {
-            let stroke_brush = cx.solid_brush(stroke_color.into());
-            cx.stroke_styled(
-                shape,
-                &stroke_brush,
-                options.stroke_width,
+            stroke_outline(
+                cx,
+                &shape,
+                stroke_color,
+                &options.piet_stroke_style,
+                options,
+            );
+        }
+        cx.restore().unwrap();
+    }
+}
+
+impl Composer<SmoothOptions> for RoundedRectangle {
+    fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
+        self.bounds().loosened(options.stroke_half_extent())
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
+        cx.save().unwrap();
+        let shape = self.outline_path();
+
+        if let Some(fill_color) = options.fill_color {
+            let fill_brush = cx.solid_brush(fill_color.into());
+            cx.fill(shape.clone(), &fill_brush);
+        }
+
+        if let Some(stroke_color) = options.stroke_color {
+            stroke_outline(
+                cx,
+                &shape,
+                stroke_color,
                 &options.piet_stroke_style,
+                options,
             );
         }
         cx.restore().unwrap();
@@ -89,7 +113,7 @@ impl Composer<SmoothOptions> for Rectangle {
 
 impl Composer<SmoothOptions> for Ellipse {
     fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
-        self.bounds().loosened(options.stroke_width * 0.5)
+        self.bounds().loosened(options.stroke_half_extent())
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
@@ -102,12 +126,12 @@ impl Composer<SmoothOptions> for Ellipse {
         }
 
         if let Some(stroke_color) = options.stroke_color {
-            let stroke_brush = cx.solid_brush(stroke_color.into());
-            cx.stroke_styled(
-                ellipse,
-                &stroke_brush,
-                options.stroke_width,
+            stroke_outline(
+                cx,
+                &ellipse,
+                stroke_color,
                 &options.piet_stroke_style,
+                options,
             );
         }
         cx.restore().unwrap();
@@ -116,7 +140,7 @@ impl Composer<SmoothOptions> for Ellipse {
 
 impl Composer<SmoothOptions> for QuadraticBezier {
     fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
-        self.bounds().loosened(options.stroke_width * 0.5)
+        self.bounds().loosened(options.stroke_half_extent())
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
@@ -129,12 +153,12 @@ impl Composer<SmoothOptions> for QuadraticBezier {
         }
 
         if let Some(stroke_color) = options.stroke_color {
-            let stroke_brush = cx.solid_brush(stroke_color.into());
-            cx.stroke_styled(
-                quadbez,
-                &stroke_brush,
-                options.stroke_width,
+            stroke_outline(
+                cx,
+                &quadbez,
+                stroke_color,
                 &options.piet_stroke_style,
+                options,
             );
         }
         cx.restore().unwrap();
@@ -143,7 +167,7 @@ impl Composer<SmoothOptions> for QuadraticBezier {
 
 impl Composer<SmoothOptions> for CubicBezier {
     fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
-        self.bounds().loosened(options.stroke_width * 0.5)
+        self.bounds().loosened(options.stroke_half_extent())
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
@@ -156,12 +180,12 @@ impl Composer<SmoothOptions> for CubicBezier {
         }
 
         if let Some(stroke_color) = options.stroke_color {
-            let stroke_brush = cx.solid_brush(stroke_color.into());
-            cx.stroke_styled(
-                cubbez,
-                &stroke_brush,
-                options.stroke_width,
+            stroke_outline(
+                cx,
+                &cubbez,
+                stroke_color,
                 &options.piet_stroke_style,
+                options,
             );
         }
         cx.restore().unwrap();
@@ -170,7 +194,7 @@ impl Composer<SmoothOptions> for CubicBezier {
 
 impl Composer<SmoothOptions> for Polyline {
     fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
-        self.bounds().loosened(options.stroke_width * 0.5)
+        self.bounds().loosened(options.stroke_half_extent())
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
@@ -192,19 +216,14 @@ impl Composer<SmoothOptions> for Polyline {
                 .clone()
                 .line_cap(piet::LineCap::Butt)
                 .line_join(piet::LineJoin::Bevel);
-            cx.stroke_styled(
-                self.outline_path(),
-                &Into::<piet::Color>::into(color),
-                options.stroke_width,
-                &style,
-            );
+            stroke_outline(cx, &self.outline_path(), color, &style, options);
         }
     }
 }
 
 impl Composer<SmoothOptions> for Polygon {
     fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
-        self.bounds().loosened(options.stroke_width * 0.5)
+        self.bounds().loosened(options.stroke_half_extent())
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
@@ -231,12 +250,7 @@ impl Composer<SmoothOptions> for Polygon {
                 .line_cap(piet::LineCap::Butt)
                 .line_join(piet::LineJoin::Bevel);
 
-            cx.stroke_styled(
-                &outline_path,
-                &Into::<piet::Color>::into(color),
-                options.stroke_width,
-                &style,
-            );
+            stroke_outline(cx, &outline_path, color, &style, options);
         }
     }
 }
@@ -250,11 +264,138 @@ impl Composer<SmoothOptions> for PenPath {
         let Some(color) = options.stroke_color else {
             return;
         };
-        let mut single_pos = true;
-        let mut prev = self.start;
+        if options.line_style != LineStyle::Solid {
+            self.draw_dashed(cx, options, 0.0);
+            return;
+        }
+
+        cx.save().unwrap();
+
+        let outlines = self.composed_outlines(options);
+        let colors = self.composed_outline_colors(color, options.stroke_gradient.as_ref());
+        for (bez_path, color) in outlines.into_iter().zip(colors) {
+            cx.fill(bez_path, &Into::<piet::Color>::into(color));
+        }
+
+        cx.restore().unwrap();
+    }
+}
+
+impl PenPath {
+    /// Draw the path with the dash pattern of a dashed or dotted line style.
+    ///
+    /// The dash pattern can't be applied to the composed outlines, so the path is stroked along its center with the
+    /// stroke width instead, without the pressure. `dash_offset` is the position of the path start in the dash pattern,
+    /// so that the pattern continues when a path is drawn in parts.
+    pub fn draw_dashed(
+        &self,
+        cx: &mut impl piet::RenderContext,
+        options: &SmoothOptions,
+        dash_offset: f64,
+    ) {
+        let Some(color) = options.stroke_color else {
+            return;
+        };
+        let mut stroke_style = options.piet_stroke_style.clone();
+        stroke_style.set_line_join(piet::LineJoin::Round);
+        stroke_style.set_dash_offset(dash_offset);
 
         cx.save().unwrap();
 
+        match options.stroke_gradient.as_ref() {
+            None => {
+                cx.stroke_styled(
+                    self.outline_path(),
+                    &Into::<piet::Color>::into(color),
+                    options.stroke_width,
+                    &stroke_style,
+                );
+            }
+            Some(gradient) => {
+                // Stroked piecewise with the gradient color at the midpoint of each segment
+                let segment_lengths = self.segment_lengths();
+                let total_length = segment_lengths.iter().sum::<f64>();
+                let mut length_before = 0.0;
+                let mut prev = self.start.pos;
+
+                for (seg, length) in self.segments.iter().zip(segment_lengths) {
+                    let offset = if total_length > 0.0 {
+                        (length_before + length * 0.5) / total_length
+                    } else {
+                        0.0
+                    };
+                    stroke_style.set_dash_offset(dash_offset + length_before);
+                    cx.stroke_styled(
+                        seg.to_kurbo_seg(prev),
+                        &Into::<piet::Color>::into(gradient.color_at(offset)),
+                        options.stroke_width,
+                        &stroke_style,
+                    );
+                    length_before += length;
+                    prev = seg.end().pos;
+                }
+            }
+        }
+
+        cx.restore().unwrap();
+    }
+
+    /// The fill colors of the outlines returned by [PenPath::composed_outlines], in the same order.
+    ///
+    /// Without a gradient all outlines have the stroke color. With a gradient each outline gets the gradient color
+    /// at the arc-length position of its segment's midpoint, so the gradient is rendered piecewise.
+    pub fn composed_outline_colors(
+        &self,
+        stroke_color: Color,
+        gradient: Option<&ColorGradient>,
+    ) -> Vec<Color> {
+        let Some(gradient) = gradient else {
+            return vec![stroke_color; self.segments.len().max(1)];
+        };
+        if self
+            .segments
+            .iter()
+            .all(|seg| seg.end().pos == self.start.pos)
+        {
+            return vec![gradient.color_at(0.0)];
+        }
+
+        let segment_lengths = self.segment_lengths();
+        let total_length = segment_lengths.iter().sum::<f64>();
+        let mut length_before = 0.0;
+
+        self.segments
+            .iter()
+            .zip(segment_lengths)
+            .filter_map(|(seg, length)| {
+                let mid = length_before + length * 0.5;
+                length_before += length;
+                // skipped the same way as in composed_outlines()
+                if seg.end().pos == self.start.pos {
+                    return None;
+                }
+                let offset = if total_length > 0.0 {
+                    mid / total_length
+                } else {
+                    0.0
+                };
+                Some(gradient.color_at(offset))
+            })
+            .collect()
+    }
+
+    /// The outlines of the path composed with the smooth style, one for each segment.
+    ///
+    /// The outlines must be drawn with only a fill in the stroke color,
+    /// or in the colors returned by [PenPath::composed_outline_colors].
+    pub fn composed_outlines(&self, options: &SmoothOptions) -> Vec<kurbo::BezPath> {
+        if options.pen_tip == PenTip::Chisel {
+            return self.composed_chisel_outlines(options);
+        }
+        let mut single_pos = true;
+        let mut prev = self.start;
+        let mut outlines = Vec::with_capacity(self.segments.len());
+
         for seg in self.segments.iter() {
             if seg.end().pos == self.start.pos {
                 continue;
@@ -266,12 +407,8 @@ impl Composer<SmoothOptions> for PenPath {
                 match seg {
                     Segment::LineTo { end } => {
                         let (width_start, width_end) = (
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, prev.pressure),
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, end.pressure),
+                            options.pressure_width(prev.pressure),
+                            options.pressure_width(end.pressure),
                         );
 
                         let bez_path = compose_lines_variable_width(
@@ -289,12 +426,8 @@ impl Composer<SmoothOptions> for PenPath {
                     }
                     Segment::QuadBezTo { cp, end } => {
                         let (width_start, width_end) = (
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, prev.pressure),
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, end.pressure),
+                            options.pressure_width(prev.pressure),
+                            options.pressure_width(end.pressure),
                         );
 
                         let quadbez = QuadraticBezier {
@@ -315,12 +448,8 @@ impl Composer<SmoothOptions> for PenPath {
                     }
                     Segment::CubBezTo { cp1, cp2, end } => {
                         let (width_start, width_end) = (
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, prev.pressure),
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, end.pressure),
+                            options.pressure_width(prev.pressure),
+                            options.pressure_width(end.pressure),
                         );
 
                         let cubbez = CubicBezier {
@@ -343,24 +472,157 @@ impl Composer<SmoothOptions> for PenPath {
                 }
             };
 
-            // Outlines for debugging
-            //let stroke_brush = cx.solid_brush(piet::Color::RED);
-            //cx.stroke(bez_path.clone(), &stroke_brush, 0.2);
-
-            cx.fill(bez_path, &Into::<piet::Color>::into(color));
+            outlines.push(bez_path);
         }
 
         // Single element/position strokes need special treatment to be rendered
         if single_pos {
-            let start_width = options
-                .pressure_curve
-                .apply(options.stroke_width, self.start.pressure);
-            cx.fill(
-                kurbo::Circle::new(self.start.pos.to_kurbo_point(), start_width * 0.5),
-                &Into::<piet::Color>::into(color),
+            let start_width = options.pressure_width(self.start.pressure);
+            outlines.push(
+                kurbo::Circle::new(self.start.pos.to_kurbo_point(), start_width * 0.5)
+                    .into_path(0.1),
+            );
+        }
+
+        outlines
+    }
+}
+
+impl PenPath {
+    /// The outlines of the path composed with a chisel tip, one for each segment.
+    ///
+    /// The nib is swept along the path, turning with the tilt angles of the elements.
+    fn composed_chisel_outlines(&self, options: &SmoothOptions) -> Vec<kurbo::BezPath> {
+        let mut single_pos = true;
+        let mut prev = self.start;
+        let mut outlines = Vec::with_capacity(self.segments.len());
+
+        for seg in self.segments.iter() {
+            if seg.end().pos == self.start.pos {
+                continue;
+            } else {
+                single_pos = false;
+            }
+
+            let lines = match seg {
+                Segment::LineTo { end } => vec![Line {
+                    start: prev.pos,
+                    end: end.pos,
+                }],
+                Segment::QuadBezTo { cp, end } => {
+                    let quadbez = QuadraticBezier {
+                        start: prev.pos,
+                        cp: *cp,
+                        end: end.pos,
+                    };
+                    let n_splits = penpath::no_subsegments_for_segment_len(
+                        quadbez.outline_path().perimeter(0.25),
+                    )
+                    .max(2);
+                    quadbez.approx_with_lines(n_splits)
+                }
+                Segment::CubBezTo { cp1, cp2, end } => {
+                    let cubbez = CubicBezier {
+                        start: prev.pos,
+                        cp1: *cp1,
+                        cp2: *cp2,
+                        end: end.pos,
+                    };
+                    let n_splits = penpath::no_subsegments_for_segment_len(
+                        cubbez.outline_path().perimeter(0.25),
+                    )
+                    .max(2);
+                    cubbez.approx_with_lines(n_splits)
+                }
+            };
+            let end = seg.end();
+            outlines.push(compose_lines_chisel(&lines, &prev, &end, options));
+            prev = end;
+        }
+
+        // Single element/position strokes only leave the imprint of the nib
+        if single_pos {
+            let mut bez_path = kurbo::BezPath::new();
+            append_convex_hull(
+                &mut bez_path,
+                chisel_nib_corners(
+                    self.start.pos,
+                    chisel_nib_angle(&self.start),
+                    options.pressure_width(self.start.pressure),
+                )
+                .to_vec(),
             );
+            outlines.push(bez_path);
+        }
+
+        outlines
+    }
+}
+
+impl Composer<SmoothOptions> for RegularPolygon {
+    fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
+        self.to_polygon().composed_bounds(options)
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
+        self.to_polygon().draw_composed(cx, options)
+    }
+}
+
+impl Composer<SmoothOptions> for Star {
+    fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
+        self.to_polygon().composed_bounds(options)
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
+        self.to_polygon().draw_composed(cx, options)
+    }
+}
+
+impl Composer<SmoothOptions> for Arc {
+    fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
+        self.bounds().loosened(options.stroke_half_extent())
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
+        cx.save().unwrap();
+        let arc = self.outline_path();
+
+        if let Some(fill_color) = options.fill_color {
+            let fill_brush = cx.solid_brush(fill_color.into());
+            cx.fill(&arc, &fill_brush);
         }
 
+        if let Some(stroke_color) = options.stroke_color {
+            stroke_outline(cx, &arc, stroke_color, &options.piet_stroke_style, options);
+        }
+        cx.restore().unwrap();
+    }
+}
+
+impl Composer<SmoothOptions> for Spline {
+    fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
+        self.bounds().loosened(options.stroke_half_extent())
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
+        cx.save().unwrap();
+        let spline = self.outline_path();
+
+        if let Some(fill_color) = options.fill_color {
+            let fill_brush = cx.solid_brush(fill_color.into());
+            cx.fill(&spline, &fill_brush);
+        }
+
+        if let Some(stroke_color) = options.stroke_color {
+            stroke_outline(
+                cx,
+                &spline,
+                stroke_color,
+                &options.piet_stroke_style,
+                options,
+            );
+        }
         cx.restore().unwrap();
     }
 }
@@ -376,6 +638,15 @@ impl Composer<SmoothOptions> for crate::Shape {
             crate::Shape::CubicBezier(cubbez) => cubbez.composed_bounds(options),
             crate::Shape::Polyline(polyline) => polyline.composed_bounds(options),
             crate::Shape::Polygon(polygon) => polygon.composed_bounds(options),
+            crate::Shape::RoundedRectangle(rounded_rectangle) => {
+                rounded_rectangle.composed_bounds(options)
+            }
+            crate::Shape::RegularPolygon(regular_polygon) => {
+                regular_polygon.composed_bounds(options)
+            }
+            crate::Shape::Star(star) => star.composed_bounds(options),
+            crate::Shape::Arc(arc) => arc.composed_bounds(options),
+            crate::Shape::Spline(spline) => spline.composed_bounds(options),
         }
     }
 
@@ -389,6 +660,15 @@ impl Composer<SmoothOptions> for crate::Shape {
             crate::Shape::CubicBezier(cubbez) => cubbez.draw_composed(cx, options),
             crate::Shape::Polyline(polyline) => polyline.draw_composed(cx, options),
             crate::Shape::Polygon(polygon) => polygon.draw_composed(cx, options),
+            crate::Shape::RoundedRectangle(rounded_rectangle) => {
+                rounded_rectangle.draw_composed(cx, options)
+            }
+            crate::Shape::RegularPolygon(regular_polygon) => {
+                regular_polygon.draw_composed(cx, options)
+            }
+            crate::Shape::Star(star) => star.draw_composed(cx, options),
+            crate::Shape::Arc(arc) => arc.draw_composed(cx, options),
+            crate::Shape::Spline(spline) => spline.draw_composed(cx, options),
         }
     }
 }
@@ -486,3 +766,127 @@ fn compose_lines_variable_width(
 
     bez_path
 }
+
+/// The nib angle of chisel tips when the tilt of the stylus is not available.
+const CHISEL_NIB_ANGLE_DEFAULT: f64 = -std::f64::consts::FRAC_PI_4;
+/// The thickness of the chisel nib in relation to its width.
+const CHISEL_NIB_THICKNESS_RATIO: f64 = 0.15;
+
+/// The angle of the chisel nib at the element. The nib lies across the direction the stylus is tilted towards.
+fn chisel_nib_angle(element: &Element) -> f64 {
+    element
+        .tilt_angle
+        .map(|tilt_angle| tilt_angle + std::f64::consts::FRAC_PI_2)
+        .unwrap_or(CHISEL_NIB_ANGLE_DEFAULT)
+}
+
+/// Interpolates between two nib angles the shorter way. The nib looks the same after half a turn.
+fn lerp_nib_angle(start: f64, end: f64, t: f64) -> f64 {
+    use std::f64::consts::{FRAC_PI_2, PI};
+    let delta = (end - start + FRAC_PI_2).rem_euclid(PI) - FRAC_PI_2;
+    start + delta * t
+}
+
+/// The corners of the chisel nib at the position.
+fn chisel_nib_corners(pos: na::Vector2<f64>, angle: f64, width: f64) -> [na::Vector2<f64>; 4] {
+    let along = na::vector![angle.cos(), angle.sin()] * width * 0.5;
+    let across = na::vector![-angle.sin(), angle.cos()] * width * CHISEL_NIB_THICKNESS_RATIO * 0.5;
+    [
+        pos + along + across,
+        pos - along + across,
+        pos - along - across,
+        pos + along - across,
+    ]
+}
+
+/// Composes lines with a chisel tip by sweeping the nib along them. Must be drawn with only a fill.
+fn compose_lines_chisel(
+    lines: &[Line],
+    start: &Element,
+    end: &Element,
+    options: &SmoothOptions,
+) -> kurbo::BezPath {
+    let (start_width, end_width) = (
+        options.pressure_width(start.pressure),
+        options.pressure_width(end.pressure),
+    );
+    let (start_angle, end_angle) = (chisel_nib_angle(start), chisel_nib_angle(end));
+    let n_lines = lines.len() as f64;
+    let mut bez_path = kurbo::BezPath::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        let (t_start, t_end) = (i as f64 / n_lines, (i + 1) as f64 / n_lines);
+        let corners = chisel_nib_corners(
+            line.start,
+            lerp_nib_angle(start_angle, end_angle, t_start),
+            start_width + (end_width - start_width) * t_start,
+        )
+        .into_iter()
+        .chain(chisel_nib_corners(
+            line.end,
+            lerp_nib_angle(start_angle, end_angle, t_end),
+            start_width + (end_width - start_width) * t_end,
+        ))
+        .collect::<Vec<na::Vector2<f64>>>();
+        append_convex_hull(&mut bez_path, corners);
+    }
+
+    bez_path
+}
+
+/// Appends the convex hull of the points as closed subpath.
+///
+/// All hulls have the same winding direction, so overlapping hulls are filled as their union.
+fn append_convex_hull(bez_path: &mut kurbo::BezPath, mut points: Vec<na::Vector2<f64>>) {
+    fn cross(o: na::Vector2<f64>, a: na::Vector2<f64>, b: na::Vector2<f64>) -> f64 {
+        (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+    }
+
+    // Andrew's monotone chain
+    points.sort_by(|a, b| a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1])));
+    let mut hull: Vec<na::Vector2<f64>> = Vec::with_capacity(points.len() * 2);
+    for pass in [points.clone(), points.into_iter().rev().collect()] {
+        let pass_start = hull.len();
+        for p in pass {
+            while hull.len() >= pass_start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        // The last point is the first of the next pass
+        hull.pop();
+    }
+    if hull.len() < 3 {
+        return;
+    }
+
+    bez_path.move_to(hull[0].to_kurbo_point());
+    for p in hull[1..].iter() {
+        bez_path.line_to(p.to_kurbo_point());
+    }
+    bez_path.close_path();
+}
+
+/// Stroke the outline of a shape with the stroke style, or with the line pattern of the options when one is set.
+fn stroke_outline(
+    cx: &mut impl piet::RenderContext,
+    outline: &kurbo::BezPath,
+    color: Color,
+    stroke_style: &piet::StrokeStyle,
+    options: &SmoothOptions,
+) {
+    let color = Into::<piet::Color>::into(color);
+
+    match options.line_pattern.apply(outline, options.stroke_width) {
+        Some((pattern_path, line_width)) => {
+            // the pattern replaces the dashes of the line style
+            let pattern_style = piet::StrokeStyle::new()
+                .line_cap(piet::LineCap::Round)
+                .line_join(piet::LineJoin::Round);
+            cx.stroke_styled(pattern_path, &color, line_width, &pattern_style);
+        }
+        None => cx.stroke_styled(outline, &color, options.stroke_width, stroke_style),
+    }
+}
@@ -1,6 +1,7 @@
 // Imports
+use super::ColorGradient;
 use crate::Color;
-use crate::style::PressureCurve;
+use crate::style::{LinePattern, PressureCurve};
 use anyhow::Context;
 use num_derive::{FromPrimitive, ToPrimitive};
 use serde::{Deserialize, Serialize};
@@ -19,18 +20,33 @@ pub struct SmoothOptions {
     /// Stroke color. When set to None, the stroke outline is not drawn.
     #[serde(rename = "stroke_color")]
     pub stroke_color: Option<Color>,
+    /// Stroke color gradient. When set, the stroke color of pen paths is interpolated along their length with it.
+    #[serde(rename = "stroke_gradient")]
+    pub stroke_gradient: Option<ColorGradient>,
     /// Fill color. When set to None, the fill is not drawn.
     #[serde(rename = "fill_color")]
     pub fill_color: Option<Color>,
     /// Pressure curve.
     #[serde(rename = "pressure_curve")]
     pub pressure_curve: PressureCurve,
+    /// The ratio of the stroke width that remains at no pressure, between 0.0 and 1.0.
+    #[serde(
+        rename = "pressure_width_min_ratio",
+        with = "crate::serialize::f64_dp3"
+    )]
+    pub pressure_width_min_ratio: f64,
+    /// The tip pen paths are composed with.
+    #[serde(rename = "pen_tip")]
+    pub pen_tip: PenTip,
     /// Line style.
     #[serde(rename = "line_style")]
     pub line_style: LineStyle,
     /// Line cap.
     #[serde(rename = "line_cap")]
     pub line_cap: LineCap,
+    /// Line pattern, rendered along the outlines of shapes instead of a plain line.
+    #[serde(rename = "line_pattern")]
+    pub line_pattern: LinePattern,
     /// The inner piet::StrokeStyle, computed using the stroke_width, line_style, and line_cap.
     #[serde(skip)]
     pub piet_stroke_style: piet::StrokeStyle,
@@ -44,10 +60,14 @@ impl Default for SmoothOptions {
         Self {
             stroke_width,
             stroke_color: Some(Color::BLACK),
+            stroke_gradient: None,
             fill_color: None,
             pressure_curve: PressureCurve::default(),
+            pressure_width_min_ratio: 0.0,
+            pen_tip: PenTip::default(),
             line_style,
             line_cap,
+            line_pattern: LinePattern::default(),
             piet_stroke_style: Self::compute_piet_stroke_style(stroke_width, line_style, line_cap),
         }
     }
@@ -83,6 +103,20 @@ impl SmoothOptions {
         stroke_style
     }
 
+    /// The width at the given pressure, with the pressure curve and the minimum width ratio applied.
+    ///
+    /// Expects pressure to be between range [0.0 - 1.0].
+    pub fn pressure_width(&self, pressure: f64) -> f64 {
+        let min_ratio = self.pressure_width_min_ratio.clamp(0.0, 1.0);
+        self.stroke_width * min_ratio
+            + self.pressure_curve.apply(self.stroke_width, pressure) * (1.0 - min_ratio)
+    }
+
+    /// The half of the extent of the stroked outline of shapes, including the line pattern.
+    pub fn stroke_half_extent(&self) -> f64 {
+        self.line_pattern.half_extent(self.stroke_width)
+    }
+
     /// Updates the inner piet::Strokestyle
     pub fn update_piet_stroke_style(&mut self) {
         self.piet_stroke_style =
@@ -122,14 +156,25 @@ impl<'de> Deserialize<'de> for SmoothOptions {
             pub stroke_width: f64,
             #[serde(rename = "stroke_color")]
             pub stroke_color: Option<Color>,
+            #[serde(rename = "stroke_gradient")]
+            pub stroke_gradient: Option<ColorGradient>,
             #[serde(rename = "fill_color")]
             pub fill_color: Option<Color>,
             #[serde(rename = "pressure_curve")]
             pub pressure_curve: PressureCurve,
+            #[serde(
+                rename = "pressure_width_min_ratio",
+                with = "crate::serialize::f64_dp3"
+            )]
+            pub pressure_width_min_ratio: f64,
+            #[serde(rename = "pen_tip")]
+            pub pen_tip: PenTip,
             #[serde(rename = "line_style")]
             pub line_style: LineStyle,
             #[serde(rename = "line_cap")]
             pub line_cap: LineCap,
+            #[serde(rename = "line_pattern")]
+            pub line_pattern: LinePattern,
         }
 
         impl From<SmoothOptions> for SmoothOptionsPrecursor {
@@ -137,10 +182,14 @@ impl<'de> Deserialize<'de> for SmoothOptions {
                 Self {
                     stroke_width: value.stroke_width,
                     stroke_color: value.stroke_color,
+                    stroke_gradient: value.stroke_gradient,
                     fill_color: value.fill_color,
                     pressure_curve: value.pressure_curve,
+                    pressure_width_min_ratio: value.pressure_width_min_ratio,
+                    pen_tip: value.pen_tip,
                     line_style: value.line_style,
                     line_cap: value.line_cap,
+                    line_pattern: value.line_pattern,
                 }
             }
         }
@@ -156,10 +205,14 @@ impl<'de> Deserialize<'de> for SmoothOptions {
         Ok(SmoothOptions {
             stroke_width: precursor.stroke_width,
             stroke_color: precursor.stroke_color,
+            stroke_gradient: precursor.stroke_gradient,
             fill_color: precursor.fill_color,
             pressure_curve: precursor.pressure_curve,
+            pressure_width_min_ratio: precursor.pressure_width_min_ratio,
+            pen_tip: precursor.pen_tip,
             line_style: precursor.line_style,
             line_cap: precursor.line_cap,
+            line_pattern: precursor.line_pattern,
             piet_stroke_style: Self::compute_piet_stroke_style(
                 precursor.stroke_width,
                 precursor.line_style,
@@ -169,6 +222,30 @@ impl<'de> Deserialize<'de> for SmoothOptions {
     }
 }
 
+/// The tip pen paths are composed with.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, FromPrimitive, ToPrimitive,
+)]
+#[serde(rename = "pen_tip")]
+pub enum PenTip {
+    /// Round tip, the width is the same in every direction
+    #[default]
+    #[serde(rename = "round")]
+    Round,
+    /// Flat chisel tip oriented along the tilt of the stylus, wide across the nib and thin along it
+    #[serde(rename = "chisel")]
+    Chisel,
+}
+
+impl TryFrom<u32> for PenTip {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value)
+            .with_context(|| format!("PenTip try_from::<u32>() for value {value} failed"))
+    }
+}
+
 /// Line cap present at the start and end of a line
 #[derive(
     Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, FromPrimitive, ToPrimitive,
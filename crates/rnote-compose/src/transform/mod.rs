@@ -1,8 +1,12 @@
 // Modules
+mod projective;
 mod transformable;
+mod warpable;
 
 // Re-exports
+pub use projective::{Projective, bounds_corners, quad_is_convex};
 pub use transformable::Transformable;
+pub use warpable::Warpable;
 
 // Imports
 use crate::ext::{AabbExt, Affine2Ext};
@@ -0,0 +1,131 @@
+// Imports
+use p2d::bounding_volume::Aabb;
+
+/// A projective transformation (homography).
+///
+/// Maps straight lines to straight lines, but in contrast to affine transforms parallel lines can converge.
+/// Used to warp content into perspective.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Projective {
+    /// The homogeneous transform matrix.
+    pub matrix: na::Matrix3<f64>,
+}
+
+impl Default for Projective {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Projective {
+    /// The tolerance for the perspective part of the matrix, below which the transform is treated as affine.
+    const AFFINE_TOLERANCE: f64 = 1e-12;
+
+    /// The identity transform.
+    pub fn identity() -> Self {
+        Self {
+            matrix: na::Matrix3::identity(),
+        }
+    }
+
+    /// The transform that maps the four corners of a quadrilateral onto the four corners of another.
+    ///
+    /// Returns None when either quadrilateral is degenerate.
+    pub fn from_quad_to_quad(
+        from: [na::Vector2<f64>; 4],
+        to: [na::Vector2<f64>; 4],
+    ) -> Option<Self> {
+        let mut a = na::SMatrix::<f64, 8, 8>::zeros();
+        let mut b = na::SVector::<f64, 8>::zeros();
+
+        for (i, (p, q)) in from.iter().zip(to.iter()).enumerate() {
+            let (x, y, u, v) = (p[0], p[1], q[0], q[1]);
+            let (row_u, row_v) = (i * 2, i * 2 + 1);
+            a[(row_u, 0)] = x;
+            a[(row_u, 1)] = y;
+            a[(row_u, 2)] = 1.0;
+            a[(row_u, 6)] = -u * x;
+            a[(row_u, 7)] = -u * y;
+            a[(row_v, 3)] = x;
+            a[(row_v, 4)] = y;
+            a[(row_v, 5)] = 1.0;
+            a[(row_v, 6)] = -v * x;
+            a[(row_v, 7)] = -v * y;
+            b[row_u] = u;
+            b[row_v] = v;
+        }
+
+        let h = a.lu().solve(&b)?;
+        let matrix = na::Matrix3::new(h[0], h[1], h[2], h[3], h[4], h[5], h[6], h[7], 1.0);
+        if !matrix.iter().all(|v| v.is_finite()) {
+            return None;
+        }
+        Some(Self { matrix })
+    }
+
+    /// The transform that maps the corners of the bounds onto the quadrilateral.
+    ///
+    /// The corners are expected in the order top-left, top-right, bottom-right, bottom-left.
+    pub fn from_bounds_to_quad(bounds: Aabb, to: [na::Vector2<f64>; 4]) -> Option<Self> {
+        Self::from_quad_to_quad(bounds_corners(bounds), to)
+    }
+
+    /// The inverse transform, if it exists.
+    pub fn inverse(&self) -> Option<Self> {
+        self.matrix.try_inverse().map(|matrix| Self { matrix })
+    }
+
+    /// The transform that first applies this transform, then the other.
+    pub fn then(&self, other: &Self) -> Self {
+        Self {
+            matrix: other.matrix * self.matrix,
+        }
+    }
+
+    /// The affine transform if the transform does not have a perspective part.
+    pub fn to_affine(&self) -> Option<na::Affine2<f64>> {
+        let w = self.matrix[(2, 2)];
+        if w.abs() <= Self::AFFINE_TOLERANCE
+            || (self.matrix[(2, 0)] / w).abs() > Self::AFFINE_TOLERANCE
+            || (self.matrix[(2, 1)] / w).abs() > Self::AFFINE_TOLERANCE
+        {
+            return None;
+        }
+        let mut matrix = self.matrix / w;
+        matrix[(2, 0)] = 0.0;
+        matrix[(2, 1)] = 0.0;
+        matrix[(2, 2)] = 1.0;
+        Some(na::Affine2::from_matrix_unchecked(matrix))
+    }
+
+    /// Transform a point.
+    ///
+    /// Points on the horizon of the transform are returned unchanged.
+    pub fn transform_point(&self, point: na::Vector2<f64>) -> na::Vector2<f64> {
+        let transformed = self.matrix * point.push(1.0);
+        if transformed[2].abs() <= f64::EPSILON {
+            return point;
+        }
+        transformed.xy() / transformed[2]
+    }
+}
+
+/// The corners of the bounds, in the order top-left, top-right, bottom-right, bottom-left.
+pub fn bounds_corners(bounds: Aabb) -> [na::Vector2<f64>; 4] {
+    [
+        bounds.mins.coords,
+        na::vector![bounds.maxs[0], bounds.mins[1]],
+        bounds.maxs.coords,
+        na::vector![bounds.mins[0], bounds.maxs[1]],
+    ]
+}
+
+/// Whether the quadrilateral is strictly convex, which is required for a warp without folding.
+pub fn quad_is_convex(quad: [na::Vector2<f64>; 4]) -> bool {
+    let turns = [0, 1, 2, 3].map(|i| {
+        let a = quad[(i + 1) % 4] - quad[i];
+        let b = quad[(i + 2) % 4] - quad[(i + 1) % 4];
+        a.perp(&b)
+    });
+    turns.iter().all(|t| *t > 0.0) || turns.iter().all(|t| *t < 0.0)
+}
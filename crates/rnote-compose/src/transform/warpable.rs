@@ -0,0 +1,11 @@
+// Imports
+use super::Projective;
+
+/// Trait for types that can be warped by a projective transform.
+///
+/// In contrast to [Transformable](super::Transformable), warping might change the representation,
+/// for example by resampling curves so that they follow the perspective.
+pub trait Warpable {
+    /// Warp by the given projective transform.
+    fn warp(&mut self, projective: &Projective);
+}
@@ -0,0 +1,90 @@
+//! Benchmarks of the render path over recorded documents.
+//!
+//! Run with `cargo bench -p rnote-engine`.
+
+// Imports
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rnote_compose::shapes::Shapeable;
+use rnote_engine::Engine;
+use rnote_engine::engine::EngineSnapshot;
+use rnote_engine::engine::rendering::RenderQuality;
+use rnote_engine::strokes::Content;
+use std::hint::black_box;
+use std::path::PathBuf;
+
+/// The recorded documents the benchmarks run over, relative to the repository root.
+const DOCUMENTS: &[&str] = &[
+    "misc/file-tests/v0-9-0-test.rnote",
+    "misc/screenshots-pages/lecture_note_1.rnote",
+    "misc/screenshots-pages/overview.rnote",
+];
+
+fn document_path(document: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .join(document)
+}
+
+fn load_snapshot(document: &str) -> EngineSnapshot {
+    let bytes = std::fs::read(document_path(document)).expect("reading document failed");
+    futures::executor::block_on(EngineSnapshot::load_from_rnote_bytes(bytes))
+        .expect("loading document failed")
+}
+
+fn load_documents(c: &mut Criterion) {
+    let mut group = c.benchmark_group("load");
+    group.sample_size(10);
+
+    for document in DOCUMENTS {
+        let bytes = std::fs::read(document_path(document)).expect("reading document failed");
+        group.bench_with_input(BenchmarkId::from_parameter(document), &bytes, |b, bytes| {
+            b.iter(|| {
+                let snapshot = futures::executor::block_on(EngineSnapshot::load_from_rnote_bytes(
+                    bytes.clone(),
+                ))
+                .unwrap();
+                let mut engine = Engine::default();
+                let _ = engine.load_snapshot(snapshot);
+                black_box(engine)
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn gen_stroke_images(c: &mut Criterion) {
+    let mut group = c.benchmark_group("gen_stroke_images");
+    group.sample_size(10);
+
+    for document in DOCUMENTS {
+        let snapshot = load_snapshot(document);
+
+        for image_scale in [0.25, 1.0, 4.0] {
+            group.bench_with_input(
+                BenchmarkId::new(*document, image_scale),
+                &image_scale,
+                |b, &image_scale| {
+                    b.iter(|| {
+                        for stroke in snapshot.stroke_components.values() {
+                            black_box(
+                                stroke
+                                    .gen_images(
+                                        stroke.bounds(),
+                                        image_scale,
+                                        RenderQuality::default(),
+                                    )
+                                    .unwrap(),
+                            );
+                        }
+                    })
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, load_documents, gen_stroke_images);
+criterion_main!(benches);
@@ -0,0 +1,122 @@
+// Imports
+use serde::{Deserialize, Serialize};
+
+/// A file embedded into the document, for example an audio memo or the original of an imported image.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "attachment")]
+pub struct Attachment {
+    /// The file name, unique within the document.
+    #[serde(rename = "name")]
+    pub name: String,
+    /// The mime type of the data.
+    #[serde(rename = "mime_type")]
+    pub mime_type: String,
+    /// The file data.
+    ///
+    /// Is (de)serialized with base64 encoding.
+    #[serde(rename = "data", with = "crate::utils::glib_bytes_base64")]
+    pub data: glib::Bytes,
+}
+
+impl Default for Attachment {
+    fn default() -> Self {
+        Self {
+            name: String::default(),
+            mime_type: String::from("application/octet-stream"),
+            data: glib::Bytes::from_static(&[]),
+        }
+    }
+}
+
+/// Information about an attachment, without its data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttachmentInfo {
+    /// The file name.
+    pub name: String,
+    /// The mime type.
+    pub mime_type: String,
+    /// The size of the data in bytes.
+    pub size: usize,
+}
+
+/// The attachments embedded into a document.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "attachments")]
+pub struct Attachments {
+    #[serde(rename = "entries")]
+    entries: Vec<Attachment>,
+}
+
+impl Attachments {
+    /// Adds an attachment and returns the name it is stored under.
+    ///
+    /// If the name is already taken, a numbered suffix is inserted before the file extension.
+    pub fn add(&mut self, name: &str, mime_type: String, data: glib::Bytes) -> String {
+        let name = self.unique_name(name);
+        self.entries.push(Attachment {
+            name: name.clone(),
+            mime_type,
+            data,
+        });
+        name
+    }
+
+    /// Removes the attachment with the given name and returns it.
+    pub fn remove(&mut self, name: &str) -> Option<Attachment> {
+        let i = self.entries.iter().position(|a| a.name == name)?;
+        Some(self.entries.remove(i))
+    }
+
+    /// The attachment with the given name.
+    pub fn get(&self, name: &str) -> Option<&Attachment> {
+        self.entries.iter().find(|a| a.name == name)
+    }
+
+    /// Lists all attachments in the order they were added.
+    pub fn list(&self) -> Vec<AttachmentInfo> {
+        self.entries
+            .iter()
+            .map(|a| AttachmentInfo {
+                name: a.name.clone(),
+                mime_type: a.mime_type.clone(),
+                size: a.data.len(),
+            })
+            .collect()
+    }
+
+    /// The amount of attachments.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The total size of the data of all attachments in bytes.
+    pub fn total_size(&self) -> usize {
+        self.entries.iter().map(|a| a.data.len()).sum()
+    }
+
+    fn unique_name(&self, name: &str) -> String {
+        let name = if name.trim().is_empty() {
+            "attachment"
+        } else {
+            name.trim()
+        };
+        if self.get(name).is_none() {
+            return name.to_string();
+        }
+        let (stem, ext) = match name.rsplit_once('.') {
+            Some((stem, ext)) if !stem.is_empty() => (stem, Some(ext)),
+            _ => (name, None),
+        };
+        (2..)
+            .map(|i| match ext {
+                Some(ext) => format!("{stem} ({i}).{ext}"),
+                None => format!("{stem} ({i})"),
+            })
+            .find(|candidate| self.get(candidate).is_none())
+            .unwrap()
+    }
+}
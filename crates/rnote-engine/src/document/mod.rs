@@ -1,23 +1,28 @@
 // Modules
+pub mod attachments;
 pub mod background;
 pub mod config;
 pub mod format;
 pub mod layout;
+pub mod pdfunderlay;
 
 // Re-exports
+pub use attachments::Attachments;
 pub use background::Background;
 pub use config::DocumentConfig;
 pub use format::Format;
 pub use layout::Layout;
+pub use pdfunderlay::PdfUnderlay;
 
 // Imports
-use crate::engine::EngineConfig;
 use crate::engine::snapshot::Snapshotable;
+use crate::engine::{EngineConfig, ExportPrefs};
 use crate::{Camera, StrokeStore, WidgetFlags};
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::ext::{AabbExt, Vector2Ext};
 use rnote_compose::{Color, SplitOrder};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default, rename = "document")]
@@ -32,6 +37,17 @@ pub struct Document {
     pub width: f64,
     #[serde(rename = "height", with = "rnote_compose::serialize::f64_dp3")]
     pub height: f64,
+    #[serde(rename = "attachments")]
+    pub attachments: Attachments,
+    /// The export preferences used when the document was last exported.
+    #[serde(rename = "export_prefs")]
+    pub export_prefs: Option<ExportPrefs>,
+    /// The folder the document was last exported into.
+    #[serde(rename = "export_dir")]
+    pub export_dir: Option<PathBuf>,
+    /// The Pdf the document was created over, if it is kept.
+    #[serde(rename = "pdf_underlay")]
+    pub pdf_underlay: Option<PdfUnderlay>,
 }
 
 impl Default for Document {
@@ -42,6 +58,10 @@ impl Default for Document {
             y: 0.0,
             width: Format::default().width(),
             height: Format::default().height(),
+            attachments: Attachments::default(),
+            export_prefs: None,
+            export_dir: None,
+            pdf_underlay: None,
         }
     }
 }
@@ -256,7 +276,7 @@ impl Document {
         );
 
         if include_content {
-            let keys = store.stroke_keys_as_rendered();
+            let keys = store.stroke_keys_unordered();
             let content_bounds = if let Some(content_bounds) = store.bounds_for_strokes(&keys) {
                 content_bounds
                     .extend_right_and_bottom_by(na::vector![padding_horizontal, padding_vertical])
@@ -301,7 +321,7 @@ impl Document {
             .merged(&viewport.extend_by(na::vector![padding_horizontal, padding_vertical]));
 
         if include_content {
-            let keys = store.stroke_keys_as_rendered();
+            let keys = store.stroke_keys_unordered();
             let content_bounds = if let Some(content_bounds) = store.bounds_for_strokes(&keys) {
                 content_bounds.extend_by(na::vector![padding_horizontal, padding_vertical])
             } else {
@@ -0,0 +1,34 @@
+// Imports
+use p2d::bounding_volume::Aabb;
+use serde::{Deserialize, Serialize};
+
+/// A page of the Pdf underlay.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "pdf_underlay_page")]
+pub struct PdfUnderlayPage {
+    /// The zero-based index of the page in the Pdf.
+    #[serde(rename = "page_index")]
+    pub page_index: usize,
+    /// The bounds of the imported page in the document.
+    #[serde(rename = "bounds")]
+    pub bounds: Aabb,
+}
+
+/// The Pdf a document was created over.
+///
+/// It is kept as attachment, so that the strokes can be exported as annotations onto the original Pdf.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "pdf_underlay")]
+pub struct PdfUnderlay {
+    /// The name of the attachment holding the Pdf data.
+    #[serde(rename = "attachment_name")]
+    pub attachment_name: String,
+    /// The imported pages.
+    #[serde(rename = "pages")]
+    pub pages: Vec<PdfUnderlayPage>,
+}
+
+impl PdfUnderlay {
+    /// The name the Pdf is attached under.
+    pub const ATTACHMENT_NAME: &str = "underlay.pdf";
+}
@@ -1,6 +1,11 @@
 // Imports
-use super::{ExportPrefs, ImportPrefs};
-use crate::pens::PensConfig;
+use super::rendering::{
+    PrerenderMargin, RenderBackend, RenderLimits, RenderQuality, RenderScheduling,
+};
+use super::{ExportPrefs, ExportPreset, ImportPrefs, StampLibrary};
+use crate::fileformats::rnoteformat::CompressionPrefs;
+use crate::pens::{PenPreset, PensConfig};
+use rnote_compose::inputtransform::InputTransform;
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
@@ -10,18 +15,42 @@ use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 pub struct EngineConfig {
     #[serde(rename = "pens_config")]
     pub pens_config: PensConfig,
+    #[serde(rename = "pen_presets")]
+    pub pen_presets: Vec<PenPreset>,
+    /// The stamps of the stamp brush.
+    #[serde(rename = "stamp_library")]
+    pub stamp_library: StampLibrary,
     #[serde(rename = "import_prefs")]
     pub import_prefs: ImportPrefs,
     #[serde(rename = "export_prefs")]
     pub export_prefs: ExportPrefs,
+    #[serde(rename = "export_presets")]
+    pub export_presets: Vec<ExportPreset>,
     #[serde(rename = "pen_sounds")]
     pub pen_sounds: bool,
     #[serde(rename = "optimize_epd")]
     pub optimize_epd: bool,
     #[serde(rename = "snap_positions")]
     pub snap_positions: bool,
+    #[serde(rename = "compression_prefs")]
+    pub compression_prefs: CompressionPrefs,
+    #[serde(rename = "render_backend")]
+    pub render_backend: RenderBackend,
+    #[serde(rename = "render_limits")]
+    pub render_limits: RenderLimits,
+    #[serde(rename = "prerender_margin")]
+    pub prerender_margin: PrerenderMargin,
+    #[serde(rename = "render_quality")]
+    pub render_quality: RenderQuality,
+    #[serde(rename = "render_scheduling")]
+    pub render_scheduling: RenderScheduling,
+    /// The calibration of the stylus input.
+    #[serde(rename = "input_transform")]
+    pub input_transform: InputTransform,
     #[serde(skip)]
     pub visual_debug: bool,
+    #[serde(skip)]
+    pub render_diagnostics: bool,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -51,11 +80,22 @@ impl EngineConfigShared {
     pub fn load_values(&self, config: EngineConfig) {
         let mut write = self.write();
         write.pens_config = config.pens_config;
+        write.pen_presets = config.pen_presets;
+        write.stamp_library = config.stamp_library;
         write.import_prefs = config.import_prefs;
         write.export_prefs = config.export_prefs;
+        write.export_presets = config.export_presets;
         write.pen_sounds = config.pen_sounds;
         write.optimize_epd = config.optimize_epd;
         write.snap_positions = config.snap_positions;
+        write.compression_prefs = config.compression_prefs;
+        write.render_backend = config.render_backend;
+        write.render_limits = config.render_limits;
+        write.prerender_margin = config.prerender_margin;
+        write.render_quality = config.render_quality;
+        write.render_scheduling = config.render_scheduling;
+        write.input_transform = config.input_transform;
         write.visual_debug = config.visual_debug;
+        write.render_diagnostics = config.render_diagnostics;
     }
 }
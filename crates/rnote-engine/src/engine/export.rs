@@ -1,15 +1,156 @@
 // Imports
 use super::{Engine, StrokeContent};
-use crate::fileformats::rnoteformat::RnoteFile;
+use crate::fileformats::emfformat::EmfFile;
+use crate::fileformats::pdfannotationformat::{self, Annotation, PageAnnotations};
+use crate::fileformats::rnoteformat::{RnoteFile, encryption};
 use crate::fileformats::{FileFormatSaver, xoppformat};
+use crate::image::ImageMemoryFormat;
+use crate::store::chrono_comp::StrokeLayer;
+use crate::strokes::Stroke;
+use crate::{Image, Svg, WidgetFlags};
 use anyhow::Context;
 use futures::channel::oneshot;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rayon::prelude::*;
 use rnote_compose::SplitOrder;
+use rnote_compose::shapes::Shapeable;
 use rnote_compose::transform::Transformable;
 use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
-use tracing::error;
+use tracing::{error, warn};
+use zeroize::Zeroizing;
+
+/// A selection of document pages, parsed from strings like "1-3, 7, 12-".
+///
+/// Page numbers are one-indexed and ranges are inclusive. Open-ended ranges extend to the last page.
+/// An empty selection contains all pages.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PageSelection {
+    ranges: Vec<(usize, Option<usize>)>,
+}
+
+impl FromStr for PageSelection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_page = |page: &str| -> anyhow::Result<usize> {
+            let page = page.trim().parse::<usize>().map_err(|e| {
+                anyhow::anyhow!("Parsing page number \"{page}\" failed, Err: {e:?}")
+            })?;
+            if page == 0 {
+                return Err(anyhow::anyhow!("Page numbers start at 1."));
+            }
+            Ok(page)
+        };
+        let mut ranges = Vec::new();
+        for part in s
+            .split([',', ';'])
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+        {
+            let range = match part.split_once(['-', '–']) {
+                Some((start, end)) => {
+                    let start = if start.trim().is_empty() {
+                        1
+                    } else {
+                        parse_page(start)?
+                    };
+                    let end = if end.trim().is_empty() {
+                        None
+                    } else {
+                        Some(parse_page(end)?)
+                    };
+                    if let Some(end) = end
+                        && end < start
+                    {
+                        return Err(anyhow::anyhow!(
+                            "Invalid page range \"{part}\", the end is before the start."
+                        ));
+                    }
+                    (start, end)
+                }
+                None => {
+                    let page = parse_page(part)?;
+                    (page, Some(page))
+                }
+            };
+            ranges.push(range);
+        }
+        Ok(Self { ranges })
+    }
+}
+
+impl TryFrom<String> for PageSelection {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::from_str(&value)
+    }
+}
+
+impl From<PageSelection> for String {
+    fn from(value: PageSelection) -> Self {
+        value.to_string()
+    }
+}
+
+impl std::fmt::Display for PageSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts = self
+            .ranges
+            .iter()
+            .map(|(start, end)| match end {
+                Some(end) if end == start => format!("{start}"),
+                Some(end) => format!("{start}-{end}"),
+                None => format!("{start}-"),
+            })
+            .collect::<Vec<String>>();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+impl PageSelection {
+    /// Whether all pages are selected.
+    pub fn is_all(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Whether the page with the given zero-based index is selected.
+    pub fn contains(&self, page_i: usize) -> bool {
+        let page = page_i + 1;
+        self.is_all()
+            || self
+                .ranges
+                .iter()
+                .any(|(start, end)| page >= *start && end.is_none_or(|end| page <= end))
+    }
+
+    /// The zero-based indices of the selected pages out of the given total amount of pages.
+    pub fn selected_indices(&self, n_pages: usize) -> Vec<usize> {
+        (0..n_pages).filter(|i| self.contains(*i)).collect()
+    }
+
+    /// The zero-based indices of the selected pages in the order they were specified, for example "3, 1-2" results
+    /// in `[2, 0, 1]`.
+    ///
+    /// Pages beyond the given total amount of pages are skipped.
+    pub fn indices_in_order(&self, n_pages: usize) -> Vec<usize> {
+        if self.is_all() {
+            return (0..n_pages).collect();
+        }
+        self.ranges
+            .iter()
+            .flat_map(|(start, end)| {
+                let end = end.unwrap_or(n_pages).min(n_pages);
+                (*start..=end).map(|page| page - 1)
+            })
+            .collect()
+    }
+}
 
 /// Document export format.
 #[derive(
@@ -67,7 +208,7 @@ impl DocExportFormat {
 }
 
 /// Document export preferences.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default, rename = "doc_export_prefs")]
 pub struct DocExportPrefs {
     /// Whether the background should be exported.
@@ -79,12 +220,22 @@ pub struct DocExportPrefs {
     /// Whether the background and stroke colors should be optimized for printing.
     #[serde(rename = "optimize_printing")]
     pub optimize_printing: bool,
+    /// Whether the brightness of the background and stroke colors is inverted. Only applies to Pdf exports.
+    #[serde(rename = "invert_colors")]
+    pub invert_colors: bool,
     /// The export format.
     #[serde(rename = "export_format")]
     pub export_format: DocExportFormat,
     /// The page order when documents with layouts that expand in horizontal and vertical directions are cut into pages.
     #[serde(rename = "page_order")]
     pub page_order: SplitOrder,
+    /// The pages that get exported.
+    #[serde(rename = "page_selection")]
+    pub page_selection: PageSelection,
+    /// Whether the strokes are written as annotations onto the Pdf the document was created over, instead of
+    /// exporting the rendered document. Only applies to Pdf exports of documents that have a Pdf underlay.
+    #[serde(rename = "pdf_annotations")]
+    pub pdf_annotations: bool,
 }
 
 impl Default for DocExportPrefs {
@@ -93,8 +244,11 @@ impl Default for DocExportPrefs {
             with_background: true,
             with_pattern: true,
             optimize_printing: false,
+            invert_colors: false,
             export_format: DocExportFormat::default(),
             page_order: SplitOrder::default(),
+            page_selection: PageSelection::default(),
+            pdf_annotations: false,
         }
     }
 }
@@ -126,6 +280,10 @@ pub enum DocPagesExportFormat {
     Png,
     #[serde(rename = "jpeg")]
     Jpeg,
+    #[serde(rename = "emf")]
+    Emf,
+    #[serde(rename = "tiff")]
+    Tiff,
 }
 
 impl Default for DocPagesExportFormat {
@@ -153,12 +311,24 @@ impl DocPagesExportFormat {
             Self::Svg => String::from("svg"),
             Self::Png => String::from("png"),
             Self::Jpeg => String::from("jpg"),
+            Self::Emf => String::from("emf"),
+            Self::Tiff => String::from("tiff"),
         }
     }
+
+    /// Whether the pages are exported as bitmap images.
+    pub fn is_bitmap(self) -> bool {
+        matches!(self, Self::Png | Self::Jpeg | Self::Tiff)
+    }
+
+    /// Whether the bitmap format supports a transparent background.
+    pub fn supports_transparency(self) -> bool {
+        matches!(self, Self::Png | Self::Tiff)
+    }
 }
 
 /// Document pages export preferences.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default, rename = "doc_pages_export_prefs")]
 pub struct DocPagesExportPrefs {
     /// Whether the background should be exported.
@@ -170,6 +340,9 @@ pub struct DocPagesExportPrefs {
     /// Whether the background and stroke colors should be optimized for printing.
     #[serde(rename = "optimize_printing")]
     pub optimize_printing: bool,
+    /// Whether the brightness of the background and stroke colors is inverted.
+    #[serde(rename = "invert_colors")]
+    pub invert_colors: bool,
     /// Export format
     #[serde(rename = "export_format")]
     pub export_format: DocPagesExportFormat,
@@ -182,10 +355,129 @@ pub struct DocPagesExportPrefs {
     /// Quality when exporting as Jpeg.
     #[serde(rename = "jpg_quality")]
     pub jpeg_quality: u8,
+    /// The bitmap DPI. When set, takes precedence over the bitmap scale-factor.
+    #[serde(rename = "bitmap_dpi")]
+    pub bitmap_dpi: Option<f64>,
+    /// Whether the background color is left transparent when exporting to a bitmap format that supports it.
+    ///
+    /// The background pattern is still drawn if enabled.
+    #[serde(rename = "transparent_background")]
+    pub transparent_background: bool,
+    /// The maximum width and height of exported bitmaps in pixels. Larger bitmaps get downscaled to fit, or split
+    /// into tiles when tiling is enabled.
+    #[serde(rename = "bitmap_max_dimension")]
+    pub bitmap_max_dimension: u32,
+    /// Whether bitmaps exceeding the maximum dimension are split into tiles instead of being downscaled.
+    #[serde(rename = "bitmap_tiling")]
+    pub bitmap_tiling: bool,
+    /// The overlap between neighbouring tiles in pixels.
+    #[serde(rename = "bitmap_tile_overlap")]
+    pub bitmap_tile_overlap: u32,
+    /// The pages that get exported.
+    #[serde(rename = "page_selection")]
+    pub page_selection: PageSelection,
+    /// The naming pattern for the page files, without the file extension.
+    ///
+    /// See [crate::utils::doc_pages_files_names_w_pattern] for the available placeholders.
+    #[serde(rename = "page_files_naming_pattern")]
+    pub page_files_naming_pattern: String,
+    /// Whether all pages are exported into a single multi-page file when exporting as Tiff.
+    #[serde(rename = "tiff_multipage")]
+    pub tiff_multipage: bool,
 }
 
 impl DocPagesExportPrefs {
+    pub const PAGE_FILES_NAMING_PATTERN_DEFAULT: &'static str = "{stem} - Page {page}";
+
     const MARGIN: f64 = 0.0;
+
+    /// Whether the naming pattern for the page files is valid.
+    ///
+    /// It must contain the `{page}` placeholder, else all page files would overwrite each other.
+    pub fn page_files_naming_pattern_valid(pattern: &str) -> bool {
+        pattern.contains("{page}")
+    }
+
+    /// The image scale used for the given export bounds, with the DPI and maximum dimension taken into account.
+    pub fn bitmap_image_scale(&self, doc_dpi: f64, bounds: Aabb) -> f64 {
+        bitmap_image_scale(
+            self.bitmap_scalefactor,
+            self.bitmap_dpi,
+            doc_dpi,
+            self.bitmap_max_dimension,
+            bounds,
+        )
+    }
+
+    /// Whether all pages are exported into a single file.
+    pub fn exports_single_file(&self) -> bool {
+        self.export_format == DocPagesExportFormat::Tiff && self.tiff_multipage
+    }
+
+    /// Whether the pages are exported as tiled bitmaps.
+    pub fn exports_tiles(&self) -> bool {
+        self.bitmap_tiling && self.export_format.is_bitmap() && !self.exports_single_file()
+    }
+
+    /// Encodes a bitmap image in the export format.
+    fn encode_image(&self, bitmap: Image, dpi: f64) -> anyhow::Result<Vec<u8>> {
+        let image_format = match self.export_format {
+            DocPagesExportFormat::Svg | DocPagesExportFormat::Emf => {
+                return Err(anyhow::anyhow!(
+                    "Extracting bitmap image format from doc pages export prefs failed, not set to a bitmap format."
+                ));
+            }
+            // encoded separately to include the resolution
+            DocPagesExportFormat::Png => return encode_png(bitmap, dpi),
+            DocPagesExportFormat::Tiff => return encode_tiff([Ok((bitmap, dpi))]),
+            DocPagesExportFormat::Jpeg => image::ImageFormat::Jpeg,
+        };
+        bitmap.into_encoded_bytes(image_format, Some(self.jpeg_quality))
+    }
+
+    /// Generates the Svg of a page.
+    fn gen_page_svg(&self, page_content: StrokeContent, page_no: usize) -> anyhow::Result<Svg> {
+        page_content
+            .gen_svg(
+                self.with_background,
+                self.with_pattern,
+                self.optimize_printing,
+                Self::MARGIN,
+            )?
+            .ok_or(anyhow::anyhow!(
+                "Generating Svg for page {page_no} failed, returned None."
+            ))
+    }
+
+    /// Generates the bitmap image of a page, together with its resolution in DPI.
+    fn gen_page_image(
+        &self,
+        page_content: StrokeContent,
+        page_no: usize,
+        doc_dpi: f64,
+    ) -> anyhow::Result<(Image, f64)> {
+        let page_svg = self.gen_page_svg(page_content, page_no)?;
+        let image_scale = self.bitmap_image_scale(doc_dpi, page_svg.bounds);
+        Ok((page_svg.gen_image(image_scale)?, image_scale * doc_dpi))
+    }
+
+    /// Generates the encoded bitmap of a page.
+    ///
+    /// Png pages are rendered in bands that are streamed into the encoder, to not allocate the entire bitmap.
+    fn gen_page_bitmap_bytes(
+        &self,
+        page_content: StrokeContent,
+        page_no: usize,
+        doc_dpi: f64,
+    ) -> anyhow::Result<Vec<u8>> {
+        if self.export_format == DocPagesExportFormat::Png {
+            let page_svg = self.gen_page_svg(page_content, page_no)?;
+            let image_scale = self.bitmap_image_scale(doc_dpi, page_svg.bounds);
+            return encode_png_streamed(&page_svg, image_scale, image_scale * doc_dpi);
+        }
+        let (page_image, dpi) = self.gen_page_image(page_content, page_no, doc_dpi)?;
+        self.encode_image(page_image, dpi)
+    }
 }
 
 impl Default for DocPagesExportPrefs {
@@ -194,10 +486,19 @@ impl Default for DocPagesExportPrefs {
             with_background: true,
             with_pattern: true,
             optimize_printing: false,
+            invert_colors: false,
             export_format: DocPagesExportFormat::default(),
             page_order: SplitOrder::default(),
             bitmap_scalefactor: 1.8,
             jpeg_quality: 85,
+            bitmap_dpi: None,
+            transparent_background: false,
+            bitmap_max_dimension: BITMAP_MAX_DIMENSION_DEFAULT,
+            bitmap_tiling: false,
+            bitmap_tile_overlap: BITMAP_TILE_OVERLAP_DEFAULT,
+            page_selection: PageSelection::default(),
+            page_files_naming_pattern: String::from(Self::PAGE_FILES_NAMING_PATTERN_DEFAULT),
+            tiff_multipage: false,
         }
     }
 }
@@ -225,6 +526,8 @@ pub enum SelectionExportFormat {
     Png,
     #[serde(rename = "jpeg")]
     Jpeg,
+    #[serde(rename = "emf")]
+    Emf,
 }
 
 impl Default for SelectionExportFormat {
@@ -239,6 +542,7 @@ impl SelectionExportFormat {
             SelectionExportFormat::Svg => String::from("svg"),
             SelectionExportFormat::Png => String::from("png"),
             SelectionExportFormat::Jpeg => String::from("jpg"),
+            SelectionExportFormat::Emf => String::from("emf"),
         }
     }
 }
@@ -256,7 +560,7 @@ impl TryFrom<u32> for SelectionExportFormat {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(default, rename = "selection_export_prefs")]
 pub struct SelectionExportPrefs {
     /// Whether the background should be exported.
@@ -280,6 +584,24 @@ pub struct SelectionExportPrefs {
     /// The margins of the export extending the bounds of the selection.
     #[serde(rename = "margin")]
     pub margin: f64,
+    /// The bitmap DPI. When set, takes precedence over the bitmap scale-factor.
+    #[serde(rename = "bitmap_dpi")]
+    pub bitmap_dpi: Option<f64>,
+    /// Whether the background color is left transparent when exporting to a bitmap format that supports it.
+    ///
+    /// The background pattern is still drawn if enabled.
+    #[serde(rename = "transparent_background")]
+    pub transparent_background: bool,
+    /// The maximum width and height of exported bitmaps in pixels. Larger bitmaps get downscaled to fit, or split
+    /// into tiles when tiling is enabled.
+    #[serde(rename = "bitmap_max_dimension")]
+    pub bitmap_max_dimension: u32,
+    /// Whether bitmaps exceeding the maximum dimension are split into tiles instead of being downscaled.
+    #[serde(rename = "bitmap_tiling")]
+    pub bitmap_tiling: bool,
+    /// The overlap between neighbouring tiles in pixels.
+    #[serde(rename = "bitmap_tile_overlap")]
+    pub bitmap_tile_overlap: u32,
 }
 
 impl Default for SelectionExportPrefs {
@@ -292,12 +614,662 @@ impl Default for SelectionExportPrefs {
             bitmap_scalefactor: 1.8,
             jpeg_quality: 85,
             margin: 12.0,
+            bitmap_dpi: None,
+            transparent_background: false,
+            bitmap_max_dimension: BITMAP_MAX_DIMENSION_DEFAULT,
+            bitmap_tiling: false,
+            bitmap_tile_overlap: BITMAP_TILE_OVERLAP_DEFAULT,
+        }
+    }
+}
+
+impl SelectionExportPrefs {
+    /// Whether the selection is exported as tiled bitmap.
+    pub fn exports_tiles(&self) -> bool {
+        self.bitmap_tiling
+            && matches!(
+                self.export_format,
+                SelectionExportFormat::Png | SelectionExportFormat::Jpeg
+            )
+    }
+
+    /// The image scale used for the given export bounds, with the DPI and maximum dimension taken into account.
+    pub fn bitmap_image_scale(&self, doc_dpi: f64, bounds: Aabb) -> f64 {
+        bitmap_image_scale(
+            self.bitmap_scalefactor,
+            self.bitmap_dpi,
+            doc_dpi,
+            self.bitmap_max_dimension,
+            bounds,
+        )
+    }
+}
+
+/// Document replay export format.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename = "doc_replay_export_format")]
+pub enum DocReplayExportFormat {
+    #[serde(rename = "gif")]
+    Gif,
+}
+
+impl Default for DocReplayExportFormat {
+    fn default() -> Self {
+        Self::Gif
+    }
+}
+
+impl TryFrom<u32> for DocReplayExportFormat {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!(
+                "DocReplayExportFormat try_from::<u32>() for value {} failed",
+                value
+            )
+        })
+    }
+}
+
+impl DocReplayExportFormat {
+    /// File extension for the format.
+    pub fn file_ext(self) -> String {
+        match self {
+            DocReplayExportFormat::Gif => String::from("gif"),
+        }
+    }
+}
+
+/// Document replay export preferences.
+///
+/// The replay is an animation of the document being drawn, stroke by stroke in the order they were created.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "doc_replay_export_prefs")]
+pub struct DocReplayExportPrefs {
+    /// Whether the background should be exported.
+    #[serde(rename = "with_background")]
+    pub with_background: bool,
+    /// Whether the background pattern should be exported.
+    #[serde(rename = "with_pattern")]
+    pub with_pattern: bool,
+    /// Whether the background and stroke colors should be optimized for printing.
+    #[serde(rename = "optimize_printing")]
+    pub optimize_printing: bool,
+    /// The export format.
+    #[serde(rename = "export_format")]
+    pub export_format: DocReplayExportFormat,
+    /// The amount of strokes that get added with every frame.
+    ///
+    /// Gets increased when the replay would otherwise exceed the maximum amount of frames.
+    #[serde(rename = "strokes_per_frame")]
+    pub strokes_per_frame: usize,
+    /// The duration every frame is shown, in milliseconds.
+    #[serde(rename = "frame_duration_ms")]
+    pub frame_duration_ms: u32,
+    /// The duration the last frame is shown before the replay restarts, in milliseconds.
+    #[serde(rename = "final_frame_duration_ms")]
+    pub final_frame_duration_ms: u32,
+    /// The bitmap scale-factor in relation to the actual size.
+    #[serde(rename = "bitmap_scalefactor")]
+    pub bitmap_scalefactor: f64,
+    /// The maximum width and height of the frames in pixels. Larger frames get downscaled to fit.
+    #[serde(rename = "bitmap_max_dimension")]
+    pub bitmap_max_dimension: u32,
+}
+
+impl Default for DocReplayExportPrefs {
+    fn default() -> Self {
+        Self {
+            with_background: true,
+            with_pattern: true,
+            optimize_printing: false,
+            export_format: DocReplayExportFormat::default(),
+            strokes_per_frame: 1,
+            frame_duration_ms: 50,
+            final_frame_duration_ms: 3000,
+            bitmap_scalefactor: 1.0,
+            bitmap_max_dimension: 1920,
+        }
+    }
+}
+
+impl DocReplayExportPrefs {
+    const MARGIN: f64 = 0.0;
+    /// The maximum amount of frames of a replay.
+    pub const MAX_FRAMES: usize = 1000;
+
+    /// The amount of strokes that get added with every frame, increased so that the replay of the given amount of
+    /// strokes does not exceed the maximum amount of frames.
+    pub fn effective_strokes_per_frame(&self, n_strokes: usize) -> usize {
+        self.strokes_per_frame
+            .max(1)
+            .max(n_strokes.div_ceil(Self::MAX_FRAMES))
+    }
+}
+
+/// The default maximum width and height of exported bitmaps in pixels.
+pub const BITMAP_MAX_DIMENSION_DEFAULT: u32 = 16384;
+
+/// Generate a Svg of the pages content, with the pages stacked vertically on top of each other.
+fn gen_stacked_pages_svg(
+    pages_content: &[StrokeContent],
+    doc_export_prefs: &DocExportPrefs,
+) -> anyhow::Result<Option<Svg>> {
+    let pages = pages_content
+        .iter()
+        .filter_map(|content| Some((content, content.bounds()?.loosened(DocExportPrefs::MARGIN))))
+        .collect::<Vec<(&StrokeContent, Aabb)>>();
+    if pages.is_empty() {
+        return Ok(None);
+    }
+    let width = pages
+        .iter()
+        .map(|(_, bounds)| bounds.extents()[0])
+        .fold(0.0, f64::max);
+    let height = pages.iter().map(|(_, bounds)| bounds.extents()[1]).sum();
+    let mut svg = Svg::gen_with_cairo(
+        |cairo_cx| {
+            let mut offset_y = 0.0;
+            for (content, bounds) in pages.iter() {
+                cairo_cx.save()?;
+                cairo_cx.translate(-bounds.mins[0], offset_y - bounds.mins[1]);
+                content.draw_to_cairo(
+                    cairo_cx,
+                    doc_export_prefs.with_background,
+                    doc_export_prefs.with_pattern,
+                    doc_export_prefs.optimize_printing,
+                    DocExportPrefs::MARGIN,
+                    1.0,
+                )?;
+                cairo_cx.restore()?;
+                offset_y += bounds.extents()[1];
+            }
+            Ok(())
+        },
+        Aabb::new(na::point![0.0, 0.0], na::point![width, height]),
+    )?;
+    if let Err(e) = svg.simplify() {
+        warn!("Simplifying Svg while generating the stacked pages Svg failed, Err: {e:?}");
+    }
+    Ok(Some(svg))
+}
+
+/// Calculate the image scale for a bitmap export.
+///
+/// If a DPI is given, it is used instead of the scale-factor, relative to the document DPI.
+/// The scale is then reduced so that the resulting bitmap does not exceed `max_dimension` in width or height.
+pub fn bitmap_image_scale(
+    scalefactor: f64,
+    dpi: Option<f64>,
+    doc_dpi: f64,
+    max_dimension: u32,
+    bounds: Aabb,
+) -> f64 {
+    let mut image_scale = match dpi {
+        Some(dpi) if dpi > 0.0 && doc_dpi > 0.0 => dpi / doc_dpi,
+        _ => scalefactor,
+    };
+    let max_extent = bounds.extents().max();
+    if max_dimension > 0 && max_extent > 0.0 && max_extent * image_scale > f64::from(max_dimension)
+    {
+        image_scale = f64::from(max_dimension) / max_extent;
+    }
+    image_scale
+}
+
+/// The default overlap between neighbouring tiles of tiled bitmap exports in pixels.
+pub const BITMAP_TILE_OVERLAP_DEFAULT: u32 = 64;
+
+/// A tile of a [TiledBitmap].
+#[derive(Debug, Clone)]
+pub struct BitmapTile {
+    /// The row of the tile, starting at 0.
+    pub row: u32,
+    /// The column of the tile, starting at 0.
+    pub column: u32,
+    /// The horizontal position of the tile in the entire bitmap in pixels.
+    pub x: u32,
+    /// The vertical position of the tile in the entire bitmap in pixels.
+    pub y: u32,
+    /// The width of the tile in pixels.
+    pub width: u32,
+    /// The height of the tile in pixels.
+    pub height: u32,
+    /// The encoded tile.
+    pub bytes: Vec<u8>,
+}
+
+/// A bitmap export that is split into tiles, so that it is not limited by the maximum bitmap dimension.
+///
+/// Bitmaps that fit into the maximum dimension consist of a single tile.
+#[derive(Debug, Clone)]
+pub struct TiledBitmap {
+    /// The width of the entire bitmap in pixels.
+    pub width: u32,
+    /// The height of the entire bitmap in pixels.
+    pub height: u32,
+    /// The overlap between neighbouring tiles in pixels.
+    pub overlap: u32,
+    /// The resolution in DPI.
+    pub dpi: f64,
+    /// The tiles, row by row.
+    pub tiles: Vec<BitmapTile>,
+}
+
+impl TiledBitmap {
+    /// Renders the Svg into tiles with a maximum width and height of `max_dimension` pixels.
+    ///
+    /// A `max_dimension` of zero disables the tiling.
+    fn render(
+        svg: &Svg,
+        image_scale: f64,
+        dpi: f64,
+        max_dimension: u32,
+        overlap: u32,
+        encode: impl Fn(Image) -> anyhow::Result<Vec<u8>> + Sync,
+    ) -> anyhow::Result<Self> {
+        let width = (svg.bounds.extents()[0] * image_scale).round() as u32;
+        let height = (svg.bounds.extents()[1] * image_scale).round() as u32;
+        let max_dimension = if max_dimension == 0 {
+            width.max(height).max(1)
+        } else {
+            max_dimension
+        };
+        // at least half of every tile is new content
+        let overlap = overlap.min(max_dimension / 2);
+        let tile_ranges = |len: u32| -> Vec<(u32, u32)> {
+            let mut ranges = vec![];
+            let mut start = 0;
+            loop {
+                let end = (start + max_dimension).min(len);
+                ranges.push((start, end - start));
+                if end >= len {
+                    break ranges;
+                }
+                start += max_dimension - overlap;
+            }
+        };
+        let columns = tile_ranges(width);
+        let rows = tile_ranges(height);
+
+        let tiles = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(row, &(y, tile_height))| {
+                columns
+                    .iter()
+                    .enumerate()
+                    .map(move |(column, &(x, tile_width))| {
+                        (row, column, x, y, tile_width, tile_height)
+                    })
+            })
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|(row, column, x, y, tile_width, tile_height)| {
+                let mins =
+                    svg.bounds.mins.coords + na::vector![f64::from(x), f64::from(y)] / image_scale;
+                let extents =
+                    na::vector![f64::from(tile_width), f64::from(tile_height)] / image_scale;
+                let tile_svg = Svg {
+                    svg_data: svg.svg_data.clone(),
+                    bounds: Aabb::new(mins.into(), (mins + extents).into()),
+                };
+                let bytes = encode(tile_svg.gen_image(image_scale)?)
+                    .with_context(|| format!("Encoding tile {row}-{column} failed."))?;
+                Ok(BitmapTile {
+                    row: row as u32,
+                    column: column as u32,
+                    x,
+                    y,
+                    width: tile_width,
+                    height: tile_height,
+                    bytes,
+                })
+            })
+            .collect::<anyhow::Result<Vec<BitmapTile>>>()?;
+
+        Ok(Self {
+            width,
+            height,
+            overlap,
+            dpi,
+            tiles,
+        })
+    }
+
+    /// Whether the bitmap is split into more than one tile.
+    pub fn is_tiled(&self) -> bool {
+        self.tiles.len() > 1
+    }
+
+    /// The file name of a tile with the given file stem.
+    pub fn tile_file_name(file_stem: &str, tile: &BitmapTile, file_ext: &str) -> String {
+        format!(
+            "{file_stem} - Tile {}-{}.{file_ext}",
+            tile.row + 1,
+            tile.column + 1
+        )
+    }
+
+    /// The files that get written for the bitmap, as pairs of file name and content.
+    ///
+    /// A bitmap with a single tile is written to a single file named by the file stem. Otherwise every tile is
+    /// written to its own file, together with a JSON manifest describing how the tiles are arranged.
+    pub fn into_files(
+        self,
+        file_stem: &str,
+        file_ext: &str,
+    ) -> anyhow::Result<Vec<(String, Vec<u8>)>> {
+        if !self.is_tiled() {
+            return Ok(self
+                .tiles
+                .into_iter()
+                .map(|tile| (format!("{file_stem}.{file_ext}"), tile.bytes))
+                .collect());
+        }
+        let manifest = serde_json::json!({
+            "width": self.width,
+            "height": self.height,
+            "overlap": self.overlap,
+            "dpi": self.dpi,
+            "tiles": self.tiles.iter().map(|tile| serde_json::json!({
+                "file": Self::tile_file_name(file_stem, tile, file_ext),
+                "row": tile.row,
+                "column": tile.column,
+                "x": tile.x,
+                "y": tile.y,
+                "width": tile.width,
+                "height": tile.height,
+            })).collect::<Vec<serde_json::Value>>(),
+        });
+        let mut files = Vec::with_capacity(self.tiles.len() + 1);
+        files.push((
+            format!("{file_stem} - Tiles.json"),
+            serde_json::to_vec_pretty(&manifest)?,
+        ));
+        for tile in self.tiles {
+            files.push((Self::tile_file_name(file_stem, &tile, file_ext), tile.bytes));
+        }
+        Ok(files)
+    }
+}
+
+/// The height in pixels of the bands that Png exports are rendered in.
+pub const BITMAP_STREAM_BAND_HEIGHT: u32 = 512;
+
+/// Creates a Png encoder for image data in the memory format, with the resolution set to the given DPI.
+///
+/// Data with float channels is written with 16 bits per channel to preserve the color depth.
+fn png_encoder<W: Write>(
+    w: W,
+    width: u32,
+    height: u32,
+    memory_format: ImageMemoryFormat,
+    dpi: f64,
+) -> png::Encoder<'static, W> {
+    const METERS_PER_INCH: f64 = 0.0254;
+
+    let mut encoder = png::Encoder::new(w, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(match memory_format {
+        ImageMemoryFormat::R32g32b32a32FloatPremultiplied => png::BitDepth::Sixteen,
+        ImageMemoryFormat::R8g8b8a8Premultiplied => png::BitDepth::Eight,
+    });
+    let pixels_per_meter = (dpi / METERS_PER_INCH).round() as u32;
+    encoder.set_pixel_dims(Some(png::PixelDimensions {
+        xppu: pixels_per_meter,
+        yppu: pixels_per_meter,
+        unit: png::Unit::Meter,
+    }));
+    encoder
+}
+
+/// Converts the image data into the layout expected by a Png encoder created with [png_encoder].
+fn png_data(image: &Image) -> Vec<u8> {
+    match image.memory_format {
+        ImageMemoryFormat::R32g32b32a32FloatPremultiplied => image
+            .data
+            .chunks_exact(4)
+            .flat_map(|channel| {
+                let value = f32::from_ne_bytes([channel[0], channel[1], channel[2], channel[3]]);
+                ((value.clamp(0.0, 1.0) * f32::from(u16::MAX)).round() as u16).to_be_bytes()
+            })
+            .collect::<Vec<u8>>(),
+        ImageMemoryFormat::R8g8b8a8Premultiplied => image.data.to_vec(),
+    }
+}
+
+/// Encodes the image as Png, with the resolution set to the given DPI.
+fn encode_png(image: Image, dpi: f64) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = vec![];
+    {
+        let mut writer = png_encoder(
+            &mut bytes,
+            image.pixel_width,
+            image.pixel_height,
+            image.memory_format,
+            dpi,
+        )
+        .write_header()
+        .context("Writing Png header failed.")?;
+        writer
+            .write_image_data(&png_data(&image))
+            .context("Writing Png image data failed.")?;
+        writer.finish().context("Finishing Png writer failed.")?;
+    }
+    Ok(bytes)
+}
+
+/// Renders the Svg in horizontal bands and streams them into a Png encoder, with the resolution set to the given DPI.
+///
+/// Only a single band is held in memory at a time, instead of the entire bitmap.
+fn encode_png_streamed(svg: &Svg, image_scale: f64, dpi: f64) -> anyhow::Result<Vec<u8>> {
+    let width = (svg.bounds.extents()[0] * image_scale).round() as u32;
+    let height = (svg.bounds.extents()[1] * image_scale).round() as u32;
+    let memory_format = ImageMemoryFormat::default();
+    let mut bytes = vec![];
+    {
+        let mut writer = png_encoder(&mut bytes, width, height, memory_format, dpi)
+            .write_header()
+            .context("Writing Png header failed.")?;
+        let mut stream_writer = writer
+            .stream_writer()
+            .context("Creating Png stream writer failed.")?;
+
+        let mut y = 0;
+        while y < height {
+            let band_height = BITMAP_STREAM_BAND_HEIGHT.min(height - y);
+            let mins = svg.bounds.mins.coords + na::vector![0.0, f64::from(y)] / image_scale;
+            let extents = na::vector![f64::from(width), f64::from(band_height)] / image_scale;
+            let band = svg
+                .gen_image_of_region(Aabb::new(mins.into(), (mins + extents).into()), image_scale)
+                .with_context(|| format!("Rendering band at row {y} failed."))?;
+            if band.pixel_width != width || band.pixel_height != band_height {
+                return Err(anyhow::anyhow!(
+                    "Rendered band at row {y} has dimensions ({}, {}), expected ({width}, {band_height}).",
+                    band.pixel_width,
+                    band.pixel_height
+                ));
+            }
+            stream_writer
+                .write_all(&png_data(&band))
+                .with_context(|| format!("Writing band at row {y} failed."))?;
+            y += band_height;
+        }
+
+        stream_writer
+            .finish()
+            .context("Finishing Png stream writer failed.")?;
+        writer.finish().context("Finishing Png writer failed.")?;
+    }
+    Ok(bytes)
+}
+
+/// Encodes the images as pages of a single Tiff, with the resolution of every page set to the given DPI.
+///
+/// The pages are pulled from the iterator one at a time and encoded right away, so only a single page bitmap is held
+/// in memory.
+fn encode_tiff(
+    pages: impl IntoIterator<Item = anyhow::Result<(Image, f64)>>,
+) -> anyhow::Result<Vec<u8>> {
+    // The resolution is stored as rational, the DPI is rounded to a hundredth
+    const RESOLUTION_DENOMINATOR: u32 = 100;
+
+    let mut bytes_buf = std::io::Cursor::new(Vec::new());
+    let mut encoder =
+        tiff::encoder::TiffEncoder::new(&mut bytes_buf).context("Creating Tiff encoder failed.")?;
+    for (i, page) in pages.into_iter().enumerate() {
+        let (image, dpi) = page?;
+        let (width, height) = (image.pixel_width, image.pixel_height);
+        let imgbuf = image
+            .into_imgbuf()
+            .context("Converting image to image::ImageBuffer failed.")?;
+        let resolution = tiff::encoder::Rational {
+            n: (dpi * f64::from(RESOLUTION_DENOMINATOR)).round() as u32,
+            d: RESOLUTION_DENOMINATOR,
+        };
+        let mut page = encoder
+            .new_image::<tiff::encoder::colortype::RGBA8>(width, height)
+            .with_context(|| format!("Creating Tiff page {i} failed."))?;
+        page.resolution(tiff::tags::ResolutionUnit::Inch, resolution);
+        page.write_data(imgbuf.as_raw())
+            .with_context(|| format!("Encoding Tiff page {i} failed."))?;
+    }
+    Ok(bytes_buf.into_inner())
+}
+
+/// How the pages are scaled onto the paper when printing.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "print_scaling")]
+pub enum PrintScaling {
+    /// Scale the pages to fit the printable area.
+    #[default]
+    #[serde(rename = "fit")]
+    Fit = 0,
+    /// Print the pages at their actual size, according to the document DPI.
+    #[serde(rename = "actual_size")]
+    ActualSize,
+    /// Print the pages at their actual size, scaled by a custom scale.
+    #[serde(rename = "custom")]
+    Custom,
+}
+
+impl TryFrom<u32> for PrintScaling {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!("PrintScaling try_from::<u32>() for value {value} failed")
+        })
+    }
+}
+
+/// Print preferences.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "print_prefs")]
+pub struct PrintPrefs {
+    /// Whether the background should be printed.
+    #[serde(rename = "with_background")]
+    pub with_background: bool,
+    /// Whether the background pattern should be printed.
+    #[serde(rename = "with_pattern")]
+    pub with_pattern: bool,
+    /// Whether the background and stroke colors should be optimized for printing.
+    #[serde(rename = "optimize_printing")]
+    pub optimize_printing: bool,
+    /// Whether the pages are converted to grayscale.
+    #[serde(rename = "grayscale")]
+    pub grayscale: bool,
+    /// The page order when documents with layouts that expand in horizontal and vertical directions are cut into pages.
+    #[serde(rename = "page_order")]
+    pub page_order: SplitOrder,
+    /// How the pages are scaled onto the paper.
+    #[serde(rename = "scaling")]
+    pub scaling: PrintScaling,
+    /// The scale in percent, when the scaling is [PrintScaling::Custom].
+    #[serde(rename = "custom_scale")]
+    pub custom_scale: f64,
+    /// Whether the pages are centered on the paper.
+    #[serde(rename = "center")]
+    pub center: bool,
+}
+
+impl Default for PrintPrefs {
+    fn default() -> Self {
+        Self {
+            with_background: true,
+            with_pattern: true,
+            optimize_printing: false,
+            grayscale: false,
+            page_order: SplitOrder::default(),
+            scaling: PrintScaling::default(),
+            custom_scale: 100.0,
+            center: true,
+        }
+    }
+}
+
+impl PrintPrefs {
+    /// The scale from document coordinates to the coordinates of the printable area.
+    ///
+    /// `printable_dpi` is the resolution of the printable area coordinates.
+    pub fn page_scale(
+        &self,
+        page_size: na::Vector2<f64>,
+        printable_size: na::Vector2<f64>,
+        printable_dpi: f64,
+        doc_dpi: f64,
+    ) -> f64 {
+        match self.scaling {
+            PrintScaling::Fit => {
+                (printable_size[0] / page_size[0]).min(printable_size[1] / page_size[1])
+            }
+            PrintScaling::ActualSize => printable_dpi / doc_dpi,
+            PrintScaling::Custom => printable_dpi / doc_dpi * self.custom_scale / 100.0,
+        }
+    }
+
+    /// The offset of the scaled page inside the printable area.
+    pub fn page_offset(
+        &self,
+        scaled_page_size: na::Vector2<f64>,
+        printable_size: na::Vector2<f64>,
+    ) -> na::Vector2<f64> {
+        if self.center {
+            (printable_size - scaled_page_size) * 0.5
+        } else {
+            na::Vector2::zeros()
         }
     }
 }
 
 /// Export preferences.
-#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(default, rename = "export_prefs")]
 pub struct ExportPrefs {
     /// Document export preferences.
@@ -309,6 +1281,24 @@ pub struct ExportPrefs {
     /// Selection export preferences.
     #[serde(rename = "selection_export_prefs")]
     pub selection_export_prefs: SelectionExportPrefs,
+    /// Document replay export preferences.
+    #[serde(rename = "doc_replay_export_prefs")]
+    pub doc_replay_export_prefs: DocReplayExportPrefs,
+    /// Print preferences.
+    #[serde(rename = "print_prefs")]
+    pub print_prefs: PrintPrefs,
+}
+
+/// Export preferences saved under a name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "export_preset")]
+pub struct ExportPreset {
+    /// The name of the preset.
+    #[serde(rename = "name")]
+    pub name: String,
+    /// The saved export preferences.
+    #[serde(rename = "export_prefs")]
+    pub export_prefs: ExportPrefs,
 }
 
 impl Engine {
@@ -316,18 +1306,18 @@ impl Engine {
     pub const STROKE_EXPORT_IMAGE_SCALE: f64 = 1.8;
 
     /// Save the current document as a .rnote file.
+    ///
+    /// The file is compressed according to the configured compression preferences.
     pub fn save_as_rnote_bytes(
         &self,
-        file_name: String,
+        _file_name: String,
     ) -> oneshot::Receiver<anyhow::Result<Vec<u8>>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
         let engine_snapshot = self.take_snapshot();
+        let compression_prefs = self.config.read().compression_prefs;
         rayon::spawn(move || {
             let result = || -> anyhow::Result<Vec<u8>> {
-                let rnote_file = RnoteFile {
-                    engine_snapshot: ijson::to_value(&engine_snapshot)?,
-                };
-                rnote_file.save_as_bytes(&file_name)
+                RnoteFile::save_engine_snapshot_as_bytes(&engine_snapshot, compression_prefs)
             };
             if oneshot_sender.send(result()).is_err() {
                 error!(
@@ -338,6 +1328,106 @@ impl Engine {
         oneshot_receiver
     }
 
+    /// Save the current document as a password encrypted .rnote file.
+    ///
+    /// See [encryption].
+    pub fn save_as_encrypted_rnote_bytes(
+        &self,
+        _file_name: String,
+        password: Zeroizing<String>,
+    ) -> oneshot::Receiver<anyhow::Result<Vec<u8>>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
+        let engine_snapshot = self.take_snapshot();
+        let compression_prefs = self.config.read().compression_prefs;
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<u8>> {
+                let bytes =
+                    RnoteFile::save_engine_snapshot_as_bytes(&engine_snapshot, compression_prefs)?;
+                encryption::encrypt(&bytes, &password)
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver failed while saving document as encrypted rnote bytes. Receiver already dropped."
+                );
+            }
+        });
+        oneshot_receiver
+    }
+
+    /// Apply the export preferences that were remembered for the document, if there are any.
+    pub fn apply_document_export_prefs(&self) {
+        if let Some(export_prefs) = self.document.export_prefs.clone() {
+            self.config.write().export_prefs = export_prefs;
+        }
+    }
+
+    /// Remember the current export preferences and the folder that was exported into for the document.
+    pub fn remember_document_export_prefs(&mut self, export_dir: Option<PathBuf>) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let export_prefs = self.config.read().export_prefs.clone();
+        let prefs_changed = self.document.export_prefs.as_ref() != Some(&export_prefs);
+        let dir_changed = export_dir.is_some() && self.document.export_dir != export_dir;
+        if prefs_changed {
+            self.document.export_prefs = Some(export_prefs);
+        }
+        if dir_changed {
+            self.document.export_dir = export_dir;
+        }
+        widget_flags.store_modified = prefs_changed || dir_changed;
+        widget_flags
+    }
+
+    /// The names of the saved export presets.
+    pub fn export_preset_names(&self) -> Vec<String> {
+        self.config
+            .read()
+            .export_presets
+            .iter()
+            .map(|preset| preset.name.clone())
+            .collect()
+    }
+
+    /// Save the current export preferences as preset, replacing an existing preset with the same name.
+    pub fn save_export_preset(&self, name: String) {
+        let mut config = self.config.write();
+        let export_prefs = config.export_prefs.clone();
+        match config
+            .export_presets
+            .iter_mut()
+            .find(|preset| preset.name == name)
+        {
+            Some(preset) => preset.export_prefs = export_prefs,
+            None => config
+                .export_presets
+                .push(ExportPreset { name, export_prefs }),
+        }
+    }
+
+    /// Apply the export preset with the given name.
+    ///
+    /// Returns false if no preset with the name exists.
+    pub fn apply_export_preset(&self, name: &str) -> bool {
+        let mut config = self.config.write();
+        let Some(export_prefs) = config
+            .export_presets
+            .iter()
+            .find(|preset| preset.name == name)
+            .map(|preset| preset.export_prefs.clone())
+        else {
+            return false;
+        };
+        config.export_prefs = export_prefs;
+        true
+    }
+
+    /// Remove the export preset with the given name.
+    pub fn remove_export_preset(&self, name: &str) {
+        self.config
+            .write()
+            .export_presets
+            .retain(|preset| preset.name != name);
+    }
+
     pub fn extract_document_content(&self) -> StrokeContent {
         StrokeContent::default()
             .with_strokes(
@@ -369,6 +1459,73 @@ impl Engine {
             .collect()
     }
 
+    /// Extract the content inside the given bounds, which are kept as the content bounds.
+    pub fn extract_region_content(&self, bounds: Aabb) -> StrokeContent {
+        StrokeContent::default()
+            .with_strokes(
+                self.store.get_strokes_arc(
+                    &self
+                        .store
+                        .stroke_keys_as_rendered_intersecting_bounds(bounds),
+                ),
+            )
+            .with_bounds(Some(bounds))
+            .with_background(Some(self.document.config.background))
+    }
+
+    /// Extract the content of the pages that are part of the page selection.
+    pub fn extract_selected_pages_content(
+        &self,
+        page_order: SplitOrder,
+        page_selection: &PageSelection,
+    ) -> Vec<StrokeContent> {
+        self.extract_pages_content(page_order)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, content)| page_selection.contains(i).then_some(content))
+            .collect()
+    }
+
+    /// Extract the content of the pages as they get exported with the document export preferences.
+    pub fn extract_doc_export_pages_content(
+        &self,
+        doc_export_prefs: &DocExportPrefs,
+    ) -> Vec<StrokeContent> {
+        let mut pages_content = self.extract_selected_pages_content(
+            doc_export_prefs.page_order,
+            &doc_export_prefs.page_selection,
+        );
+        if doc_export_prefs.invert_colors && doc_export_prefs.export_format == DocExportFormat::Pdf
+        {
+            for page_content in pages_content.iter_mut() {
+                page_content.invert_colors();
+            }
+        }
+        pages_content
+    }
+
+    /// Extract the content of the pages as they get exported with the document pages export preferences.
+    pub fn extract_doc_pages_export_content(
+        &self,
+        doc_pages_export_prefs: &DocPagesExportPrefs,
+    ) -> Vec<StrokeContent> {
+        let mut pages_content = self.extract_selected_pages_content(
+            doc_pages_export_prefs.page_order,
+            &doc_pages_export_prefs.page_selection,
+        );
+        let transparent_background = doc_pages_export_prefs.transparent_background
+            && doc_pages_export_prefs.export_format.supports_transparency();
+        for page_content in pages_content.iter_mut() {
+            if doc_pages_export_prefs.invert_colors {
+                page_content.invert_colors();
+            }
+            if transparent_background {
+                page_content.set_background_transparent();
+            }
+        }
+        pages_content
+    }
+
     pub fn extract_selection_content(&self) -> Option<StrokeContent> {
         let selection_keys = self.store.selection_keys_as_rendered();
         if selection_keys.is_empty() {
@@ -394,11 +1551,20 @@ impl Engine {
         title: String,
         doc_export_prefs_override: Option<DocExportPrefs>,
     ) -> oneshot::Receiver<Result<Vec<u8>, anyhow::Error>> {
-        let doc_export_prefs =
-            doc_export_prefs_override.unwrap_or(self.config.read().export_prefs.doc_export_prefs);
-
-        match doc_export_prefs.export_format {
+        let (export_format, pdf_annotations) = doc_export_prefs_override
+            .as_ref()
+            .map(|prefs| (prefs.export_format, prefs.pdf_annotations))
+            .unwrap_or_else(|| {
+                let config = self.config.read();
+                let prefs = &config.export_prefs.doc_export_prefs;
+                (prefs.export_format, prefs.pdf_annotations)
+            });
+
+        match export_format {
             DocExportFormat::Svg => self.export_doc_as_svg_bytes(doc_export_prefs_override),
+            DocExportFormat::Pdf if pdf_annotations && self.document.pdf_underlay.is_some() => {
+                self.export_doc_as_annotated_pdf_bytes()
+            }
             DocExportFormat::Pdf => self.export_doc_as_pdf_bytes(title, doc_export_prefs_override),
             DocExportFormat::Xopp => {
                 self.export_doc_as_xopp_bytes(title, doc_export_prefs_override)
@@ -412,19 +1578,21 @@ impl Engine {
         doc_export_prefs_override: Option<DocExportPrefs>,
     ) -> oneshot::Receiver<Result<Vec<u8>, anyhow::Error>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
-        let doc_export_prefs =
-            doc_export_prefs_override.unwrap_or(self.config.read().export_prefs.doc_export_prefs);
-        let doc_content = self.extract_document_content();
+        let doc_export_prefs = doc_export_prefs_override
+            .unwrap_or_else(|| self.config.read().export_prefs.doc_export_prefs.clone());
+        // A subset of the pages gets stacked without the pages in between
+        let pages_content = if doc_export_prefs.page_selection.is_all() {
+            vec![self.extract_document_content()]
+        } else {
+            self.extract_selected_pages_content(
+                doc_export_prefs.page_order,
+                &doc_export_prefs.page_selection,
+            )
+        };
 
         rayon::spawn(move || {
             let result = || -> anyhow::Result<Vec<u8>> {
-                let doc_svg = doc_content
-                    .gen_svg(
-                        doc_export_prefs.with_background,
-                        doc_export_prefs.with_pattern,
-                        doc_export_prefs.optimize_printing,
-                        DocExportPrefs::MARGIN,
-                    )?
+                let doc_svg = gen_stacked_pages_svg(&pages_content, &doc_export_prefs)?
                     .ok_or(anyhow::anyhow!("Generating doc svg failed, returned None."))?;
                 Ok(rnote_compose::utils::add_xml_header(
                     rnote_compose::utils::wrap_svg_root(
@@ -448,6 +1616,91 @@ impl Engine {
         oneshot_receiver
     }
 
+    /// Export the Pdf underlay of the doc with the strokes written onto it as annotations.
+    ///
+    /// Highlighter strokes become highlights, text strokes free text and brush and shape strokes ink annotations.
+    /// Other strokes, like images, are left out.
+    fn export_doc_as_annotated_pdf_bytes(&self) -> oneshot::Receiver<anyhow::Result<Vec<u8>>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
+        let underlay = self
+            .document
+            .pdf_underlay
+            .as_ref()
+            .and_then(|pdf_underlay| {
+                let pdf_bytes = self
+                    .document
+                    .attachments
+                    .get(&pdf_underlay.attachment_name)?
+                    .data
+                    .clone();
+                let pages = pdf_underlay
+                    .pages
+                    .iter()
+                    .map(|page| PageAnnotations {
+                        page_index: page.page_index,
+                        bounds: page.bounds,
+                        annotations: self.annotations_in_bounds(page.bounds),
+                    })
+                    .collect::<Vec<PageAnnotations>>();
+                Some((pdf_bytes, pages))
+            });
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<u8>> {
+                let (pdf_bytes, pages) = underlay.ok_or_else(|| {
+                    anyhow::anyhow!("The Pdf the document was created over is missing.")
+                })?;
+                pdfannotationformat::annotate_pdf(&pdf_bytes, &pages)
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver failed while exporting document as annotated Pdf. Receiver already dropped."
+                );
+            }
+        });
+        oneshot_receiver
+    }
+
+    /// The strokes intersecting the bounds as Pdf annotations.
+    fn annotations_in_bounds(&self, bounds: Aabb) -> Vec<Annotation> {
+        self.store
+            .stroke_keys_as_rendered_intersecting_bounds(bounds)
+            .into_iter()
+            .filter_map(|key| {
+                let layer = self.store.stroke_layer(key)?;
+                // the pages themselves are on the document layer
+                if layer == StrokeLayer::Document {
+                    return None;
+                }
+                match self.store.get_stroke_ref(key)? {
+                    Stroke::BrushStroke(brushstroke) if layer == StrokeLayer::Highlighter => {
+                        Some(Annotation::Highlight {
+                            bounds: brushstroke.bounds(),
+                            color: brushstroke.style.stroke_color()?,
+                        })
+                    }
+                    Stroke::BrushStroke(brushstroke) => Some(Annotation::ink_from_bezpath(
+                        &brushstroke.path.to_kurbo_flattened(0.1),
+                        brushstroke.style.stroke_width(),
+                        brushstroke.style.stroke_color()?,
+                    )),
+                    Stroke::ShapeStroke(shapestroke) => Some(Annotation::ink_from_bezpath(
+                        &shapestroke.shape.outline_path(),
+                        shapestroke.style.stroke_width(),
+                        shapestroke.style.stroke_color()?,
+                    )),
+                    Stroke::TextStroke(textstroke) => Some(Annotation::FreeText {
+                        bounds: textstroke.bounds(),
+                        text: textstroke.text.clone(),
+                        font_size: textstroke.text_style.font_size,
+                        color: textstroke.text_style.color,
+                    }),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
     /// Export the doc with the strokes as Pdf.
     fn export_doc_as_pdf_bytes(
         &self,
@@ -455,9 +1708,9 @@ impl Engine {
         doc_export_prefs_override: Option<DocExportPrefs>,
     ) -> oneshot::Receiver<anyhow::Result<Vec<u8>>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
-        let doc_export_prefs =
-            doc_export_prefs_override.unwrap_or(self.config.read().export_prefs.doc_export_prefs);
-        let pages_content = self.extract_pages_content(doc_export_prefs.page_order);
+        let doc_export_prefs = doc_export_prefs_override
+            .unwrap_or_else(|| self.config.read().export_prefs.doc_export_prefs.clone());
+        let pages_content = self.extract_doc_export_pages_content(&doc_export_prefs);
         let format_size = self.document.config.format.size();
 
         rayon::spawn(move || {
@@ -531,9 +1784,12 @@ impl Engine {
         doc_export_prefs_override: Option<DocExportPrefs>,
     ) -> oneshot::Receiver<Result<Vec<u8>, anyhow::Error>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
-        let doc_export_prefs =
-            doc_export_prefs_override.unwrap_or(self.config.read().export_prefs.doc_export_prefs);
-        let pages_content = self.extract_pages_content(doc_export_prefs.page_order);
+        let doc_export_prefs = doc_export_prefs_override
+            .unwrap_or_else(|| self.config.read().export_prefs.doc_export_prefs.clone());
+        let pages_content = self.extract_selected_pages_content(
+            doc_export_prefs.page_order,
+            &doc_export_prefs.page_selection,
+        );
         let document = self.document.clone();
 
         rayon::spawn(move || {
@@ -661,28 +1917,76 @@ impl Engine {
         &self,
         doc_pages_export_prefs_override: Option<DocPagesExportPrefs>,
     ) -> oneshot::Receiver<Result<Vec<Vec<u8>>, anyhow::Error>> {
-        let doc_pages_export_prefs = doc_pages_export_prefs_override
-            .unwrap_or(self.config.read().export_prefs.doc_pages_export_prefs);
-
-        match doc_pages_export_prefs.export_format {
+        let export_format = doc_pages_export_prefs_override
+            .as_ref()
+            .map(|prefs| prefs.export_format)
+            .unwrap_or(
+                self.config
+                    .read()
+                    .export_prefs
+                    .doc_pages_export_prefs
+                    .export_format,
+            );
+
+        match export_format {
             DocPagesExportFormat::Svg => {
                 self.export_doc_pages_as_svgs_bytes(doc_pages_export_prefs_override)
             }
-            DocPagesExportFormat::Png | DocPagesExportFormat::Jpeg => {
+            DocPagesExportFormat::Png | DocPagesExportFormat::Jpeg | DocPagesExportFormat::Tiff => {
                 self.export_doc_pages_as_bitmap_bytes(doc_pages_export_prefs_override)
             }
+            DocPagesExportFormat::Emf => {
+                self.export_doc_pages_as_emfs_bytes(doc_pages_export_prefs_override)
+            }
         }
     }
 
+    /// Generate a thumbnail of the first page of the document, fitting into `max_size` pixels in width and height.
+    pub fn generate_doc_thumbnail(
+        &self,
+        max_size: u32,
+    ) -> oneshot::Receiver<anyhow::Result<Image>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Image>>();
+        let first_page_content = self
+            .extract_pages_content(SplitOrder::default())
+            .into_iter()
+            .next();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Image> {
+                let page_svg = first_page_content
+                    .context("The document has no pages.")?
+                    .gen_svg(true, true, false, 0.0)?
+                    .context("Generating Svg for the thumbnail failed, returned None.")?;
+                // Rendered directly at the thumbnail size, instead of scaling down a larger image
+                let image_scale = f64::from(max_size) / page_svg.bounds.extents().max();
+                page_svg.gen_image(image_scale)
+            };
+
+            if oneshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver failed while generating the document thumbnail. Receiver already dropped."
+                );
+            }
+        });
+
+        oneshot_receiver
+    }
+
     /// Export the document as Svg.
     fn export_doc_pages_as_svgs_bytes(
         &self,
         doc_pages_export_prefs_override: Option<DocPagesExportPrefs>,
     ) -> oneshot::Receiver<Result<Vec<Vec<u8>>, anyhow::Error>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<Vec<u8>>>>();
-        let doc_pages_export_prefs = doc_pages_export_prefs_override
-            .unwrap_or(self.config.read().export_prefs.doc_pages_export_prefs);
-        let pages_content = self.extract_pages_content(doc_pages_export_prefs.page_order);
+        let doc_pages_export_prefs = doc_pages_export_prefs_override.unwrap_or_else(|| {
+            self.config
+                .read()
+                .export_prefs
+                .doc_pages_export_prefs
+                .clone()
+        });
+        let pages_content = self.extract_doc_pages_export_content(&doc_pages_export_prefs);
 
         rayon::spawn(move || {
             let result = || -> anyhow::Result<Vec<Vec<u8>>> {
@@ -724,6 +2028,53 @@ impl Engine {
         oneshot_receiver
     }
 
+    /// Export the document pages as Emf.
+    fn export_doc_pages_as_emfs_bytes(
+        &self,
+        doc_pages_export_prefs_override: Option<DocPagesExportPrefs>,
+    ) -> oneshot::Receiver<Result<Vec<Vec<u8>>, anyhow::Error>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<Vec<u8>>>>();
+        let doc_pages_export_prefs = doc_pages_export_prefs_override.unwrap_or_else(|| {
+            self.config
+                .read()
+                .export_prefs
+                .doc_pages_export_prefs
+                .clone()
+        });
+        let pages_content = self.extract_doc_pages_export_content(&doc_pages_export_prefs);
+        let doc_dpi = self.document.config.format.dpi();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<Vec<u8>>> {
+                pages_content
+                    .into_par_iter()
+                    .enumerate()
+                    .map(|(i, page_content)| {
+                        let page_svg = page_content
+                            .gen_svg(
+                                doc_pages_export_prefs.with_background,
+                                doc_pages_export_prefs.with_pattern,
+                                doc_pages_export_prefs.optimize_printing,
+                                DocPagesExportPrefs::MARGIN,
+                            )?
+                            .ok_or(anyhow::anyhow!(
+                                "Generating Svg for page {i} failed, returned None."
+                            ))?;
+                        EmfFile::from_svg(&page_svg, doc_dpi)?.save_as_bytes("")
+                    })
+                    .collect()
+            };
+
+            if oneshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver failed while exporting document pages as Emf bytes. Receiver already dropped."
+                );
+            }
+        });
+
+        oneshot_receiver
+    }
+
     /// Export the document pages as bitmap.
     ///
     /// Returns an error if the format pref is not set to a bitmap variant.
@@ -732,26 +2083,62 @@ impl Engine {
         doc_pages_export_prefs_override: Option<DocPagesExportPrefs>,
     ) -> oneshot::Receiver<Result<Vec<Vec<u8>>, anyhow::Error>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<Vec<u8>>>>();
-        let doc_pages_export_prefs = doc_pages_export_prefs_override
-            .unwrap_or(self.config.read().export_prefs.doc_pages_export_prefs);
-        let pages_contents = self.extract_pages_content(doc_pages_export_prefs.page_order);
+        let doc_pages_export_prefs = doc_pages_export_prefs_override.unwrap_or_else(|| {
+            self.config
+                .read()
+                .export_prefs
+                .doc_pages_export_prefs
+                .clone()
+        });
+        let pages_contents = self.extract_doc_pages_export_content(&doc_pages_export_prefs);
+        let doc_dpi = self.document.config.format.dpi();
 
         rayon::spawn(move || {
             let result = || -> Result<Vec<Vec<u8>>, anyhow::Error> {
-                let image_format = match doc_pages_export_prefs.export_format {
-                    DocPagesExportFormat::Svg => {
-                        return Err(anyhow::anyhow!(
-                            "Extracting bitmap image format from doc pages export prefs failed, not set to a bitmap format."
-                        ));
-                    }
-                    DocPagesExportFormat::Png => image::ImageFormat::Png,
-                    DocPagesExportFormat::Jpeg => image::ImageFormat::Jpeg,
-                };
                 pages_contents
                     .into_par_iter()
                     .enumerate()
                     .map(|(i, page_content)| {
-                        page_content
+                        doc_pages_export_prefs.gen_page_bitmap_bytes(page_content, i, doc_dpi)
+                    })
+                    .collect()
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver failed while exporting document pages as bitmap bytes. Receiver already dropped."
+                );
+            }
+        });
+
+        oneshot_receiver
+    }
+
+    /// Export the document pages as tiled bitmaps, one for every page.
+    ///
+    /// Instead of downscaling pages exceeding the maximum bitmap dimension, they are split into tiles.
+    pub fn export_doc_pages_as_bitmap_tiles(
+        &self,
+        doc_pages_export_prefs_override: Option<DocPagesExportPrefs>,
+    ) -> oneshot::Receiver<Result<Vec<TiledBitmap>, anyhow::Error>> {
+        let (oneshot_sender, oneshot_receiver) =
+            oneshot::channel::<anyhow::Result<Vec<TiledBitmap>>>();
+        let doc_pages_export_prefs = doc_pages_export_prefs_override.unwrap_or_else(|| {
+            self.config
+                .read()
+                .export_prefs
+                .doc_pages_export_prefs
+                .clone()
+        });
+        let pages_contents = self.extract_doc_pages_export_content(&doc_pages_export_prefs);
+        let doc_dpi = self.document.config.format.dpi();
+
+        rayon::spawn(move || {
+            let result = || -> Result<Vec<TiledBitmap>, anyhow::Error> {
+                pages_contents
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, page_content)| {
+                        let page_svg = page_content
                             .gen_svg(
                                 doc_pages_export_prefs.with_background,
                                 doc_pages_export_prefs.with_pattern,
@@ -760,18 +2147,198 @@ impl Engine {
                             )?
                             .ok_or(anyhow::anyhow!(
                                 "Generating Svg for page {i} failed, returned None."
-                            ))?
-                            .gen_image(doc_pages_export_prefs.bitmap_scalefactor)?
-                            .into_encoded_bytes(
-                                image_format,
-                                Some(doc_pages_export_prefs.jpeg_quality),
-                            )
+                            ))?;
+                        // The maximum dimension limits the tile size, not the scale
+                        let image_scale = bitmap_image_scale(
+                            doc_pages_export_prefs.bitmap_scalefactor,
+                            doc_pages_export_prefs.bitmap_dpi,
+                            doc_dpi,
+                            0,
+                            page_svg.bounds,
+                        );
+                        let dpi = image_scale * doc_dpi;
+                        TiledBitmap::render(
+                            &page_svg,
+                            image_scale,
+                            dpi,
+                            doc_pages_export_prefs.bitmap_max_dimension,
+                            doc_pages_export_prefs.bitmap_tile_overlap,
+                            |image| doc_pages_export_prefs.encode_image(image, dpi),
+                        )
                     })
                     .collect()
             };
             if oneshot_sender.send(result()).is_err() {
                 error!(
-                    "Sending result to receiver failed while exporting document pages as bitmap bytes. Receiver already dropped."
+                    "Sending result to receiver failed while exporting document pages as bitmap tiles. Receiver already dropped."
+                );
+            }
+        });
+
+        oneshot_receiver
+    }
+
+    /// Export the document pages into a single multi-page Tiff.
+    ///
+    /// All pages are rendered with the bitmap preferences of the doc pages export prefs. The pages are rendered and
+    /// encoded one after another, to not hold the bitmaps of all pages in memory at once.
+    pub fn export_doc_pages_as_multipage_tiff_bytes(
+        &self,
+        doc_pages_export_prefs_override: Option<DocPagesExportPrefs>,
+    ) -> oneshot::Receiver<Result<Vec<u8>, anyhow::Error>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
+        let doc_pages_export_prefs = doc_pages_export_prefs_override.unwrap_or_else(|| {
+            self.config
+                .read()
+                .export_prefs
+                .doc_pages_export_prefs
+                .clone()
+        });
+        let pages_contents = self.extract_doc_pages_export_content(&doc_pages_export_prefs);
+        let doc_dpi = self.document.config.format.dpi();
+
+        rayon::spawn(move || {
+            let result = || -> Result<Vec<u8>, anyhow::Error> {
+                encode_tiff(
+                    pages_contents
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, page_content)| {
+                            doc_pages_export_prefs.gen_page_image(page_content, i, doc_dpi)
+                        }),
+                )
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver failed while exporting document pages as multi-page Tiff bytes. Receiver already dropped."
+                );
+            }
+        });
+
+        oneshot_receiver
+    }
+
+    /// Export a replay of the document being drawn as an animation.
+    ///
+    /// Every frame adds the next strokes in the order they were created, while drawing them in the order they are
+    /// rendered.
+    pub fn export_doc_replay(
+        &self,
+        doc_replay_export_prefs_override: Option<DocReplayExportPrefs>,
+    ) -> oneshot::Receiver<Result<Vec<u8>, anyhow::Error>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
+        let doc_replay_export_prefs = doc_replay_export_prefs_override
+            .unwrap_or(self.config.read().export_prefs.doc_replay_export_prefs);
+        let content = self.extract_document_content();
+        // The strokes in the order they are rendered, together with their chronological time
+        let strokes_w_chrono_t = self
+            .store
+            .stroke_keys_as_rendered()
+            .into_iter()
+            .filter_map(|key| {
+                Some((
+                    self.store.get_strokes_arc(&[key]).pop()?,
+                    self.store.chrono_t(key)?,
+                ))
+            })
+            .collect::<Vec<(Arc<Stroke>, u32)>>();
+
+        rayon::spawn(move || {
+            let result = || -> Result<Vec<u8>, anyhow::Error> {
+                let n_strokes = strokes_w_chrono_t.len();
+                if n_strokes == 0 {
+                    return Err(anyhow::anyhow!(
+                        "Exporting document replay failed, document has no strokes."
+                    ));
+                }
+                let mut chrono_ts = strokes_w_chrono_t
+                    .iter()
+                    .map(|(_, t)| *t)
+                    .collect::<Vec<u32>>();
+                chrono_ts.sort_unstable();
+                let strokes_per_frame =
+                    doc_replay_export_prefs.effective_strokes_per_frame(n_strokes);
+                let frame_strokes_amounts = (strokes_per_frame..n_strokes)
+                    .step_by(strokes_per_frame)
+                    .chain(std::iter::once(n_strokes))
+                    .collect::<Vec<usize>>();
+                let n_frames = frame_strokes_amounts.len();
+
+                let gen_frame = |frame_i: usize, strokes_amount: usize| {
+                    // all strokes created up to and including the last stroke of this frame
+                    let last_chrono_t = chrono_ts[strokes_amount - 1];
+                    let strokes = strokes_w_chrono_t
+                        .iter()
+                        .filter(|(_, t)| *t <= last_chrono_t)
+                        .map(|(stroke, _)| Arc::clone(stroke))
+                        .collect::<Vec<Arc<Stroke>>>();
+                    let frame_svg = content
+                        .clone()
+                        .with_strokes(strokes)
+                        .gen_svg(
+                            doc_replay_export_prefs.with_background,
+                            doc_replay_export_prefs.with_pattern,
+                            doc_replay_export_prefs.optimize_printing,
+                            DocReplayExportPrefs::MARGIN,
+                        )?
+                        .ok_or(anyhow::anyhow!(
+                            "Generating Svg for replay frame {frame_i} failed, returned None."
+                        ))?;
+                    let image_scale = bitmap_image_scale(
+                        doc_replay_export_prefs.bitmap_scalefactor,
+                        None,
+                        1.0,
+                        doc_replay_export_prefs.bitmap_max_dimension,
+                        frame_svg.bounds,
+                    );
+                    let duration_ms = if frame_i + 1 == n_frames {
+                        doc_replay_export_prefs.final_frame_duration_ms
+                    } else {
+                        doc_replay_export_prefs.frame_duration_ms
+                    };
+                    anyhow::Ok(image::Frame::from_parts(
+                        frame_svg.gen_image(image_scale)?.into_imgbuf()?,
+                        0,
+                        0,
+                        image::Delay::from_numer_denom_ms(duration_ms, 1),
+                    ))
+                };
+                // Frames are rendered in parallel in batches and fed to the encoder right away,
+                // so that only a batch of frames is held in memory at a time
+                let frames_batch_size = rayon::current_num_threads().max(1);
+                let frames_w_index = frame_strokes_amounts
+                    .into_iter()
+                    .enumerate()
+                    .collect::<Vec<(usize, usize)>>();
+
+                match doc_replay_export_prefs.export_format {
+                    DocReplayExportFormat::Gif => {
+                        let mut bytes = Vec::new();
+                        {
+                            let mut encoder =
+                                image::codecs::gif::GifEncoder::new_with_speed(&mut bytes, 10);
+                            encoder.set_repeat(image::codecs::gif::Repeat::Infinite)?;
+                            for batch in frames_w_index.chunks(frames_batch_size) {
+                                let frames = batch
+                                    .par_iter()
+                                    .map(|&(frame_i, strokes_amount)| {
+                                        gen_frame(frame_i, strokes_amount)
+                                    })
+                                    .collect::<anyhow::Result<Vec<image::Frame>>>()?;
+                                for frame in frames {
+                                    encoder
+                                        .encode_frame(frame)
+                                        .context("Encoding replay frame as Gif failed.")?;
+                                }
+                            }
+                        }
+                        Ok(bytes)
+                    }
+                }
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver failed while exporting document replay. Receiver already dropped."
                 );
             }
         });
@@ -794,6 +2361,9 @@ impl Engine {
             SelectionExportFormat::Png | SelectionExportFormat::Jpeg => {
                 self.export_selection_as_bitmap_bytes(selection_export_prefs_override)
             }
+            SelectionExportFormat::Emf => {
+                self.export_selection_as_emf_bytes(selection_export_prefs_override)
+            }
         }
     }
 
@@ -846,6 +2416,45 @@ impl Engine {
         oneshot_receiver
     }
 
+    /// Exports the selection as Emf.
+    fn export_selection_as_emf_bytes(
+        &self,
+        selection_export_prefs_override: Option<SelectionExportPrefs>,
+    ) -> oneshot::Receiver<Result<Option<Vec<u8>>, anyhow::Error>> {
+        let (oneshot_sender, oneshot_receiver) =
+            oneshot::channel::<anyhow::Result<Option<Vec<u8>>>>();
+        let selection_export_prefs = selection_export_prefs_override
+            .unwrap_or(self.config.read().export_prefs.selection_export_prefs);
+        let content = self.extract_selection_content();
+        let doc_dpi = self.document.config.format.dpi();
+
+        rayon::spawn(move || {
+            let result = || -> Result<Option<Vec<u8>>, anyhow::Error> {
+                let Some(content) = content else {
+                    return Ok(None);
+                };
+                let Some(svg) = content.gen_svg(
+                    selection_export_prefs.with_background,
+                    selection_export_prefs.with_pattern,
+                    selection_export_prefs.optimize_printing,
+                    selection_export_prefs.margin,
+                )?
+                else {
+                    return Ok(None);
+                };
+
+                Ok(Some(EmfFile::from_svg(&svg, doc_dpi)?.save_as_bytes("")?))
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver failed while exporting selection as Emf bytes. Receiver already dropped."
+                );
+            }
+        });
+
+        oneshot_receiver
+    }
+
     /// Export the selection a bitmap bytes.
     ///
     /// Returns an error if the format pref is not set to a bitmap format
@@ -857,7 +2466,14 @@ impl Engine {
             oneshot::channel::<anyhow::Result<Option<Vec<u8>>>>();
         let selection_export_prefs = selection_export_prefs_override
             .unwrap_or(self.config.read().export_prefs.selection_export_prefs);
-        let content = self.extract_selection_content();
+        let mut content = self.extract_selection_content();
+        let doc_dpi = self.document.config.format.dpi();
+        if selection_export_prefs.transparent_background
+            && selection_export_prefs.export_format == SelectionExportFormat::Png
+            && let Some(content) = content.as_mut()
+        {
+            content.set_background_transparent();
+        }
 
         rayon::spawn(move || {
             let result = || -> Result<Option<Vec<u8>>, anyhow::Error> {
@@ -873,23 +2489,27 @@ impl Engine {
                 else {
                     return Ok(None);
                 };
-                let image_format = match selection_export_prefs.export_format {
-                    SelectionExportFormat::Svg => {
-                        return Err(anyhow::anyhow!(
+                let image_scale = selection_export_prefs.bitmap_image_scale(doc_dpi, svg.bounds);
+
+                match selection_export_prefs.export_format {
+                    SelectionExportFormat::Svg | SelectionExportFormat::Emf => {
+                        Err(anyhow::anyhow!(
                             "Extracting bitmap image format from doc pages export prefs failed, not set to a bitmap format."
-                        ));
+                        ))
                     }
-                    SelectionExportFormat::Png => image::ImageFormat::Png,
-                    SelectionExportFormat::Jpeg => image::ImageFormat::Jpeg,
-                };
-
-                Ok(Some(
-                    svg.gen_image(selection_export_prefs.bitmap_scalefactor)?
-                        .into_encoded_bytes(
-                            image_format,
+                    // rendered in bands that are streamed into the encoder, to not allocate the entire bitmap
+                    SelectionExportFormat::Png => Ok(Some(encode_png_streamed(
+                        &svg,
+                        image_scale,
+                        image_scale * doc_dpi,
+                    )?)),
+                    SelectionExportFormat::Jpeg => {
+                        Ok(Some(svg.gen_image(image_scale)?.into_encoded_bytes(
+                            image::ImageFormat::Jpeg,
                             Some(selection_export_prefs.jpeg_quality),
-                        )?,
-                ))
+                        )?))
+                    }
+                }
             };
             if oneshot_sender.send(result()).is_err() {
                 error!(
@@ -900,4 +2520,150 @@ impl Engine {
 
         oneshot_receiver
     }
+
+    /// Export the selection as tiled bitmap.
+    ///
+    /// Instead of downscaling a selection exceeding the maximum bitmap dimension, it is split into tiles.
+    pub fn export_selection_as_bitmap_tiles(
+        &self,
+        selection_export_prefs_override: Option<SelectionExportPrefs>,
+    ) -> oneshot::Receiver<Result<Option<TiledBitmap>, anyhow::Error>> {
+        let (oneshot_sender, oneshot_receiver) =
+            oneshot::channel::<anyhow::Result<Option<TiledBitmap>>>();
+        let selection_export_prefs = selection_export_prefs_override
+            .unwrap_or(self.config.read().export_prefs.selection_export_prefs);
+        let mut content = self.extract_selection_content();
+        let doc_dpi = self.document.config.format.dpi();
+        if selection_export_prefs.transparent_background
+            && selection_export_prefs.export_format == SelectionExportFormat::Png
+            && let Some(content) = content.as_mut()
+        {
+            content.set_background_transparent();
+        }
+
+        rayon::spawn(move || {
+            let result = || -> Result<Option<TiledBitmap>, anyhow::Error> {
+                let Some(content) = content else {
+                    return Ok(None);
+                };
+                let Some(svg) = content.gen_svg(
+                    selection_export_prefs.with_background,
+                    selection_export_prefs.with_pattern,
+                    selection_export_prefs.optimize_printing,
+                    selection_export_prefs.margin,
+                )?
+                else {
+                    return Ok(None);
+                };
+                let export_format = selection_export_prefs.export_format;
+                if matches!(
+                    export_format,
+                    SelectionExportFormat::Svg | SelectionExportFormat::Emf
+                ) {
+                    return Err(anyhow::anyhow!(
+                        "Extracting bitmap image format from selection export prefs failed, not set to a bitmap format."
+                    ));
+                }
+                // The maximum dimension limits the tile size, not the scale
+                let image_scale = bitmap_image_scale(
+                    selection_export_prefs.bitmap_scalefactor,
+                    selection_export_prefs.bitmap_dpi,
+                    doc_dpi,
+                    0,
+                    svg.bounds,
+                );
+                let dpi = image_scale * doc_dpi;
+
+                Ok(Some(TiledBitmap::render(
+                    &svg,
+                    image_scale,
+                    dpi,
+                    selection_export_prefs.bitmap_max_dimension,
+                    selection_export_prefs.bitmap_tile_overlap,
+                    |image| match export_format {
+                        // encoded separately to include the resolution
+                        SelectionExportFormat::Png => encode_png(image, dpi),
+                        _ => image.into_encoded_bytes(
+                            image::ImageFormat::Jpeg,
+                            Some(selection_export_prefs.jpeg_quality),
+                        ),
+                    },
+                )?))
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver failed while exporting selection as bitmap tiles. Receiver already dropped"
+                );
+            }
+        });
+
+        oneshot_receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Image, ImageMemoryFormat, PageSelection, encode_png, encode_tiff};
+    use std::str::FromStr;
+
+    fn test_image(width: u32, height: u32) -> Image {
+        Image {
+            data: glib::Bytes::from_owned(vec![255u8; (width * height * 4) as usize]),
+            rect: Default::default(),
+            pixel_width: width,
+            pixel_height: height,
+            memory_format: ImageMemoryFormat::R8g8b8a8Premultiplied,
+        }
+    }
+
+    #[test]
+    fn page_selection_parse() {
+        let selection = PageSelection::from_str("1-3, 7, 12-").unwrap();
+        assert_eq!(selection.selected_indices(14), vec![0, 1, 2, 6, 11, 12, 13]);
+        assert_eq!(selection.to_string(), "1-3, 7, 12-");
+        assert!(PageSelection::from_str("").unwrap().is_all());
+        assert!(PageSelection::from_str("0").is_err());
+        assert!(PageSelection::from_str("5-2").is_err());
+    }
+
+    #[test]
+    fn page_selection_indices_in_order() {
+        let selection = PageSelection::from_str("3, 1-2, 5-").unwrap();
+        assert_eq!(selection.indices_in_order(6), vec![2, 0, 1, 4, 5]);
+        assert_eq!(selection.indices_in_order(3), vec![2, 0, 1]);
+        assert_eq!(PageSelection::default().indices_in_order(2), vec![0, 1]);
+    }
+
+    #[test]
+    fn png_resolution() {
+        let bytes = encode_png(test_image(4, 3), 254.0).unwrap();
+        let reader = png::Decoder::new(std::io::Cursor::new(bytes))
+            .read_info()
+            .unwrap();
+        let info = reader.info();
+        assert_eq!((info.width, info.height), (4, 3));
+        let pixel_dims = info.pixel_dims.unwrap();
+        assert_eq!(pixel_dims.unit, png::Unit::Meter);
+        assert_eq!((pixel_dims.xppu, pixel_dims.yppu), (10000, 10000));
+    }
+
+    #[test]
+    fn tiff_pages() {
+        let bytes =
+            encode_tiff([Ok((test_image(4, 3), 96.0)), Ok((test_image(2, 5), 300.0))]).unwrap();
+        let mut decoder = tiff::decoder::Decoder::new(std::io::Cursor::new(bytes)).unwrap();
+        assert_eq!(decoder.dimensions().unwrap(), (4, 3));
+        assert!(decoder.more_images());
+        decoder.next_image().unwrap();
+        assert_eq!(decoder.dimensions().unwrap(), (2, 5));
+        assert!(!decoder.more_images());
+
+        assert!(
+            encode_tiff([
+                Ok((test_image(4, 3), 96.0)),
+                Err(anyhow::anyhow!("rendering failed")),
+            ])
+            .is_err()
+        );
+    }
 }
@@ -1,19 +1,28 @@
 // Imports
-use super::StrokeContent;
+use super::{EngineSnapshot, StrokeContent};
 use crate::document::Layout;
+use crate::document::pdfunderlay::{PdfUnderlay, PdfUnderlayPage};
 use crate::engine_view_mut;
+use crate::fileformats::htmlformat::HtmlRichText;
+use crate::fileformats::svgformat;
+use crate::fileformats::tableformat::TableText;
 use crate::pens::Pen;
 use crate::pens::PenStyle;
 use crate::store::StrokeKey;
 use crate::store::chrono_comp::StrokeLayer;
-use crate::strokes::{BitmapImage, Stroke, VectorImage};
+use crate::strokes::{BitmapImage, ShapeStroke, Stroke, TextStroke, VectorImage};
 use crate::strokes::{Resize, resize::ImageSizeOption, resize::calculate_resize_ratio};
-use crate::{Engine, WidgetFlags};
+use crate::{Engine, Image, WidgetFlags};
 use futures::channel::oneshot;
+use hayro::{hayro_interpret, hayro_syntax, vello_cpu};
+use p2d::bounding_volume::Aabb;
+use rnote_compose::Style;
 use rnote_compose::ext::Vector2Ext;
-use rnote_compose::shapes::Shapeable;
+use rnote_compose::shapes::{Line, Shape, Shapeable};
+use rnote_compose::style::smooth::SmoothOptions;
+use rnote_compose::transform::Transformable;
 use serde::{Deserialize, Serialize};
-use std::ops::Range;
+use std::sync::Arc;
 use std::time::Instant;
 use tracing::error;
 
@@ -96,6 +105,16 @@ pub struct PdfImportPrefs {
     /// Whether the document layout should be adjusted to the Pdf
     #[serde(rename = "adjust_document")]
     pub adjust_document: bool,
+    /// Whether handwriting is imported as brush strokes when importing as vector image.
+    ///
+    /// For Pdfs exported by note taking apps like GoodNotes or Notability, where handwriting is stored as stroked
+    /// paths.
+    #[serde(rename = "import_ink")]
+    pub import_ink: bool,
+    /// Whether the Pdf is embedded into the document as underlay, to be able to export the strokes as annotations onto
+    /// the original Pdf.
+    #[serde(rename = "keep_original")]
+    pub keep_original: bool,
 }
 
 impl Default for PdfImportPrefs {
@@ -106,10 +125,46 @@ impl Default for PdfImportPrefs {
             page_spacing: PdfImportPageSpacing::default(),
             bitmap_scalefactor: 1.8,
             adjust_document: false,
+            import_ink: false,
+            keep_original: false,
+        }
+    }
+}
+
+/// Bitmap image import preferences.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename = "bitmap_import_prefs")]
+pub struct BitmapImportPrefs {
+    /// Whether oversized images get downscaled on import.
+    #[serde(rename = "downscale")]
+    pub downscale: bool,
+    /// The maximum width and height of imported images, in pixels.
+    #[serde(rename = "max_dimension")]
+    pub max_dimension: u32,
+    /// The maximum amount of pixels of imported images, in megapixels.
+    ///
+    /// Images are stored uncompressed, so this limits the file size and memory usage to 4 bytes per pixel.
+    #[serde(rename = "max_megapixels")]
+    pub max_megapixels: f64,
+}
+
+impl Default for BitmapImportPrefs {
+    fn default() -> Self {
+        Self {
+            downscale: true,
+            max_dimension: 4096,
+            max_megapixels: 12.0,
         }
     }
 }
 
+impl BitmapImportPrefs {
+    /// The maximum amount of pixels of imported images.
+    pub fn max_pixels(&self) -> u64 {
+        (self.max_megapixels.max(0.01) * 1_000_000.0) as u64
+    }
+}
+
 /// Xournal++ `.xopp` file import preferences.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename = "xopp_import_prefs")]
@@ -135,9 +190,133 @@ pub struct ImportPrefs {
     /// Xournal++ `.xopp` file import preferences
     #[serde(rename = "xopp_import_prefs")]
     pub xopp_import_prefs: XoppImportPrefs,
+    /// Bitmap image import preferences
+    #[serde(rename = "bitmap_import_prefs")]
+    pub bitmap_import_prefs: BitmapImportPrefs,
+}
+
+/// How another document is merged into the current document.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename = "doc_merge_mode")]
+pub enum DocMergeMode {
+    /// Append the pages of the other document below the pages with content.
+    #[default]
+    #[serde(rename = "pages")]
+    Pages,
+    /// Stack the other document on top, with its user layers placed above the existing ones.
+    #[serde(rename = "layers")]
+    Layers,
+}
+
+impl TryFrom<u32> for DocMergeMode {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!("DocMergeMode try_from::<u32>() for value {} failed", value)
+        })
+    }
 }
 
 impl Engine {
+    /// Merge the content of another document into the current document.
+    ///
+    /// The document configuration, like the format and background, of the current document is kept. Attachments
+    /// of the other document are added, with their names made unique.
+    pub fn merge_snapshot(&mut self, snapshot: EngineSnapshot, mode: DocMergeMode) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let mut other = Engine::default();
+        let _ = other.load_snapshot(snapshot);
+
+        let offset = match mode {
+            DocMergeMode::Pages => {
+                let top = self
+                    .bounds_w_content_extended()
+                    .map(|bounds| bounds.maxs[1])
+                    .unwrap_or(self.document.y);
+                let other_top = other
+                    .bounds_w_content_extended()
+                    .map(|bounds| bounds.mins[1])
+                    .unwrap_or(other.document.y);
+                na::vector![self.document.x - other.document.x, top - other_top]
+            }
+            DocMergeMode::Layers => na::Vector2::zeros(),
+        };
+        let user_layers_offset = match mode {
+            DocMergeMode::Pages => 0,
+            DocMergeMode::Layers => {
+                let user_layers_offset = self.store.layers().len() as u32;
+                // The layers of the other document are stacked on top
+                for (i, layer) in other.store.layers().iter().enumerate() {
+                    let index = user_layers_offset + i as u32;
+                    self.store.insert_layer(index);
+                    self.store.set_layer_name(index, layer.name.clone());
+                    self.store.set_layer_visible(index, layer.visible);
+                    self.store.set_layer_locked(index, layer.locked);
+                }
+                user_layers_offset
+            }
+        };
+
+        let other_keys = other.store.stroke_keys_sorted_chrono();
+        let other_groups = other_keys
+            .iter()
+            .map(|&key| other.store.stroke_groups(key))
+            .collect::<Vec<Vec<u32>>>();
+        let inserted = other
+            .store
+            .get_strokes_arc(&other_keys)
+            .into_iter()
+            .zip(other_keys)
+            .map(|(stroke, key)| {
+                let mut stroke = Arc::unwrap_or_clone(stroke);
+                stroke.translate(offset);
+                let layer = other.store.stroke_layer(key).map(|layer| match layer {
+                    StrokeLayer::UserLayer(layer) => {
+                        StrokeLayer::UserLayer(layer + user_layers_offset)
+                    }
+                    layer => layer,
+                });
+                self.store.insert_stroke(stroke, layer)
+            })
+            .collect::<Vec<StrokeKey>>();
+        // The groups of the other document become new groups
+        self.store
+            .insert_groups(inserted.iter().copied().zip(other_groups));
+        for info in other.document.attachments.list() {
+            if let Some(attachment) = other.document.attachments.get(&info.name) {
+                self.document.attachments.add(
+                    &attachment.name,
+                    attachment.mime_type.clone(),
+                    attachment.data.clone(),
+                );
+            }
+        }
+
+        self.store.update_geometry_for_strokes(&inserted);
+        widget_flags |= self.doc_resize_to_fit_content()
+            | self.current_pen_update_state()
+            | self.update_rendering_current_viewport()
+            | self.store.record(Instant::now());
+        widget_flags.resize = true;
+        widget_flags.store_modified = true;
+        widget_flags.refresh_ui = true;
+        widget_flags.refresh_layers = true;
+        widget_flags
+    }
+
     /// Generate a vectorimage from the bytes.
     ///
     /// The bytes are expected to be from a valid UTF-8 encoded Svg string.
@@ -178,16 +357,68 @@ impl Engine {
         oneshot_receiver
     }
 
+    /// Generate editable strokes from the bytes of a Svg.
+    ///
+    /// The strokes are sized in the same way as a vector image generated from the bytes.
+    pub fn generate_strokes_from_svg_bytes(
+        &self,
+        pos: na::Vector2<f64>,
+        bytes: Vec<u8>,
+        respect_borders: bool,
+    ) -> oneshot::Receiver<anyhow::Result<Vec<(Stroke, Option<StrokeLayer>)>>> {
+        let (oneshot_sender, oneshot_receiver) =
+            oneshot::channel::<anyhow::Result<Vec<(Stroke, Option<StrokeLayer>)>>>();
+
+        let resize_struct = Resize {
+            width: self.document.config.format.width(),
+            height: self.document.config.format.height(),
+            layout_fixed_width: self.document.config.layout.is_fixed_width(),
+            max_viewpoint: Some(self.camera.viewport().maxs),
+            restrain_to_viewport: true,
+            respect_borders,
+        };
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<(Stroke, Option<StrokeLayer>)>> {
+                let svg_str = String::from_utf8(bytes)?;
+                let strokes = svgformat::strokes_from_svg(
+                    &svg_str,
+                    pos,
+                    ImageSizeOption::ResizeImage(resize_struct),
+                )?;
+                if strokes.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "Svg does not contain any convertible content."
+                    ));
+                }
+                Ok(strokes.into_iter().map(|stroke| (stroke, None)).collect())
+            };
+
+            if oneshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver while generating strokes from Svg bytes failed. Receiver already dropped."
+                );
+            }
+        });
+
+        oneshot_receiver
+    }
+
     /// Generate a bitmapimage for the bytes.
     ///
     /// The bytes are expected to be from a valid bitmap image (Png/Jpeg).
+    ///
+    /// Oversized images are downscaled according to the bitmap import prefs,
+    /// which can be overridden for this import with `import_prefs_override`.
     pub fn generate_bitmapimage_from_bytes(
         &self,
         pos: na::Vector2<f64>,
         bytes: Vec<u8>,
         respect_borders: bool,
+        import_prefs_override: Option<BitmapImportPrefs>,
     ) -> oneshot::Receiver<anyhow::Result<BitmapImage>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<BitmapImage>>();
+        let bitmap_import_prefs =
+            import_prefs_override.unwrap_or(self.config.read().import_prefs.bitmap_import_prefs);
 
         let resize_struct = Resize {
             width: self.document.config.format.width(),
@@ -199,11 +430,18 @@ impl Engine {
         };
         rayon::spawn(move || {
             let result = || -> anyhow::Result<BitmapImage> {
-                BitmapImage::from_image_bytes(
+                let mut bitmapimage = BitmapImage::from_image_bytes(
                     &bytes,
                     pos,
                     ImageSizeOption::ResizeImage(resize_struct),
-                )
+                )?;
+                if bitmap_import_prefs.downscale {
+                    bitmapimage.image = bitmapimage.image.downscale_to_fit(
+                        bitmap_import_prefs.max_dimension,
+                        bitmap_import_prefs.max_pixels(),
+                    )?;
+                }
+                Ok(bitmapimage)
             };
 
             if oneshot_sender.send(result()).is_err() {
@@ -216,9 +454,73 @@ impl Engine {
         oneshot_receiver
     }
 
+    /// The amount of pages of the Pdf.
+    pub fn pdf_page_count(bytes: Vec<u8>, password: Option<&str>) -> anyhow::Result<usize> {
+        let data = Arc::new(bytes);
+        let pdf = if let Some(password) = password {
+            hayro_syntax::Pdf::new_with_password(data, password)
+        } else {
+            hayro_syntax::Pdf::new(data)
+        }
+        .map_err(|err| anyhow::anyhow!("Creating Pdf instance failed, Err: {err:?}"))?;
+        Ok(pdf.pages().len())
+    }
+
+    /// Generate thumbnails of the pages of the Pdf, fitting into `max_size` pixels in width and height.
+    ///
+    /// Used to pick the pages that get imported.
+    pub fn generate_pdf_page_thumbnails(
+        &self,
+        bytes: Vec<u8>,
+        max_size: u32,
+        password: Option<String>,
+    ) -> oneshot::Receiver<anyhow::Result<Vec<Image>>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<Image>>>();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<Image>> {
+                let data = Arc::new(bytes);
+                let pdf = if let Some(password) = password {
+                    hayro_syntax::Pdf::new_with_password(data, &password)
+                } else {
+                    hayro_syntax::Pdf::new(data)
+                }
+                .map_err(|err| anyhow::anyhow!("Creating Pdf instance failed, Err: {err:?}"))?;
+                let interpreter_settings = hayro_interpret::InterpreterSettings::default();
+
+                pdf.pages()
+                    .iter()
+                    .map(|page| {
+                        let (width, height) = page.render_dimensions();
+                        let scale = f64::from(max_size) / f64::from(width.max(height)).max(1.0);
+                        let render_settings = hayro::RenderSettings {
+                            x_scale: scale as f32,
+                            y_scale: scale as f32,
+                            width: Some((f64::from(width) * scale).ceil().max(1.0) as u16),
+                            height: Some((f64::from(height) * scale).ceil().max(1.0) as u16),
+                            bg_color: vello_cpu::color::AlphaColor::WHITE,
+                        };
+                        let png_data = hayro::render(page, &interpreter_settings, &render_settings)
+                            .into_png()?;
+                        Image::try_from_encoded_bytes(&png_data)
+                    })
+                    .collect()
+            };
+
+            if oneshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver while generating Pdf page thumbnails failed. Receiver already dropped"
+                );
+            }
+        });
+
+        oneshot_receiver
+    }
+
     /// Generate image strokes for each page for the bytes.
     ///
-    /// The bytes are expected to be from a valid Pdf.
+    /// The bytes are expected to be from a valid Pdf. `page_indices` are the zero-based indices of the pages in the
+    /// order they get imported, all pages are imported if not set.
     ///
     /// Note: `insert_pos` does not have an effect when the `adjust_document` import pref is set true.
     #[allow(clippy::type_complexity)]
@@ -226,7 +528,7 @@ impl Engine {
         &self,
         bytes: Vec<u8>,
         insert_pos: na::Vector2<f64>,
-        page_range: Option<Range<usize>>,
+        page_indices: Option<Vec<usize>>,
         password: Option<String>,
     ) -> oneshot::Receiver<anyhow::Result<Vec<(Stroke, Option<StrokeLayer>)>>> {
         let (oneshot_sender, oneshot_receiver) =
@@ -253,7 +555,7 @@ impl Engine {
                             &bytes,
                             pdf_import_prefs,
                             insert_pos,
-                            page_range,
+                            page_indices,
                             &format,
                             password,
                         )?
@@ -262,12 +564,36 @@ impl Engine {
                         .collect::<Vec<(Stroke, Option<StrokeLayer>)>>();
                         Ok(bitmapimages)
                     }
+                    PdfImportPagesType::Vector if pdf_import_prefs.import_ink => {
+                        let strokes = VectorImage::from_pdf_bytes_w_ink(
+                            &bytes,
+                            pdf_import_prefs,
+                            insert_pos,
+                            page_indices,
+                            &format,
+                            password,
+                        )?
+                        .into_iter()
+                        .flat_map(|(vectorimage, ink)| {
+                            std::iter::once((
+                                Stroke::VectorImage(vectorimage),
+                                Some(StrokeLayer::Document),
+                            ))
+                            .chain(ink.into_iter().map(
+                                |(brushstroke, layer)| {
+                                    (Stroke::BrushStroke(brushstroke), Some(layer))
+                                },
+                            ))
+                        })
+                        .collect::<Vec<(Stroke, Option<StrokeLayer>)>>();
+                        Ok(strokes)
+                    }
                     PdfImportPagesType::Vector => {
                         let vectorimages = VectorImage::from_pdf_bytes(
                             &bytes,
                             pdf_import_prefs,
                             insert_pos,
-                            page_range,
+                            page_indices,
                             &format,
                             password,
                         )?
@@ -289,6 +615,60 @@ impl Engine {
         oneshot_receiver
     }
 
+    /// Import the strokes generated from the pages of a Pdf with [Self::generate_pdf_pages_from_bytes] into the store.
+    ///
+    /// `page_indices` must be the same that were used to generate the strokes. When the `keep_original` Pdf import pref
+    /// is set, the Pdf replaces the underlay of the document.
+    pub fn import_generated_pdf_pages(
+        &mut self,
+        strokes: Vec<(Stroke, Option<StrokeLayer>)>,
+        adjust_document: bool,
+        pdf_bytes: Vec<u8>,
+        page_indices: Option<Vec<usize>>,
+    ) -> WidgetFlags {
+        let keep_original = self
+            .config
+            .read()
+            .import_prefs
+            .pdf_import_prefs
+            .keep_original;
+        // every page is imported as one image on the document layer
+        let page_bounds = strokes
+            .iter()
+            .filter(|(stroke, layer)| {
+                matches!(stroke, Stroke::VectorImage(_) | Stroke::BitmapImage(_))
+                    && *layer == Some(StrokeLayer::Document)
+            })
+            .map(|(stroke, _)| stroke.bounds())
+            .collect::<Vec<Aabb>>();
+        let page_indices = page_indices.unwrap_or_else(|| (0..page_bounds.len()).collect());
+        let pages = page_indices
+            .into_iter()
+            .zip(page_bounds)
+            .map(|(page_index, bounds)| PdfUnderlayPage { page_index, bounds })
+            .collect::<Vec<PdfUnderlayPage>>();
+
+        let mut widget_flags = self.import_generated_content(strokes, adjust_document);
+        if keep_original && !pages.is_empty() {
+            if let Some(pdf_underlay) = self.document.pdf_underlay.take() {
+                self.document
+                    .attachments
+                    .remove(&pdf_underlay.attachment_name);
+            }
+            let attachment_name = self.document.attachments.add(
+                PdfUnderlay::ATTACHMENT_NAME,
+                String::from("application/pdf"),
+                glib::Bytes::from_owned(pdf_bytes),
+            );
+            self.document.pdf_underlay = Some(PdfUnderlay {
+                attachment_name,
+                pages,
+            });
+            widget_flags.store_modified = true;
+        }
+        widget_flags
+    }
+
     /// Import the generated strokes into the store.
     pub fn import_generated_content(
         &mut self,
@@ -303,7 +683,7 @@ impl Engine {
 
         // we need to always deselect all strokes. Even tough changing the pen style deselects too, it does only when
         // the pen is actually different.
-        let all_strokes = self.store.stroke_keys_as_rendered();
+        let all_strokes = self.store.stroke_keys_unordered();
         self.store.set_selected_keys(&all_strokes, false);
 
         if select {
@@ -322,7 +702,7 @@ impl Engine {
 
         let inserted = strokes
             .into_iter()
-            .map(|(stroke, layer)| self.store.insert_stroke(stroke, layer))
+            .map(|(stroke, layer)| self.store.insert_new_stroke(stroke, layer))
             .collect::<Vec<StrokeKey>>();
 
         // resize after the strokes are inserted, but before they are set selected
@@ -344,7 +724,7 @@ impl Engine {
         let mut widget_flags = WidgetFlags::default();
 
         // we need to always deselect all strokes. Even tough changing the pen style deselects too, but only when the pen is actually changed.
-        let all_strokes = self.store.stroke_keys_as_rendered();
+        let all_strokes = self.store.stroke_keys_unordered();
         self.store.set_selected_keys(&all_strokes, false);
 
         widget_flags |= self.change_pen_style(PenStyle::Typewriter);
@@ -358,6 +738,135 @@ impl Engine {
         widget_flags
     }
 
+    /// Insert text from a Html fragment, retaining its basic formatting.
+    pub fn insert_html_text(&mut self, html: &str, pos: Option<na::Vector2<f64>>) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let rich_text = HtmlRichText::from_html(
+            html,
+            self.config
+                .read()
+                .pens_config
+                .typewriter_config
+                .text_style
+                .font_size,
+        );
+        if rich_text.text.is_empty() {
+            return widget_flags;
+        }
+
+        // we need to always deselect all strokes. Even tough changing the pen style deselects too, but only when the pen is actually changed.
+        let all_strokes = self.store.stroke_keys_unordered();
+        self.store.set_selected_keys(&all_strokes, false);
+
+        widget_flags |= self.change_pen_style(PenStyle::Typewriter);
+
+        if let Pen::Typewriter(typewriter) = self.penholder.current_pen_mut() {
+            widget_flags |=
+                typewriter.insert_rich_text(rich_text, pos, &mut engine_view_mut!(self));
+        }
+
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// Insert tabular text as a table, made out of a text stroke for every cell and the grid lines.
+    ///
+    /// The column widths and row heights are sized to fit the cell with the largest text.
+    pub fn insert_table_text(&mut self, table: TableText, pos: na::Vector2<f64>) -> WidgetFlags {
+        const CELL_PADDING: f64 = 8.0;
+        const GRID_LINE_WIDTH: f64 = 1.5;
+
+        let columns_amount = table.columns_amount();
+        if columns_amount == 0 {
+            return WidgetFlags::default();
+        }
+        let mut text_style = self
+            .config
+            .read()
+            .pens_config
+            .typewriter_config
+            .text_style
+            .clone();
+        text_style.set_max_width(None);
+        text_style.ranged_text_attributes.clear();
+
+        let mut piet_text = piet_cairo::CairoText::new();
+        let mut column_widths = vec![0.0_f64; columns_amount];
+        let mut row_heights = vec![0.0_f64; table.rows.len()];
+        for (row_idx, row) in table.rows.iter().enumerate() {
+            for (column_idx, cell) in row.iter().enumerate() {
+                let size = text_style
+                    .untransformed_size(&mut piet_text, cell.clone())
+                    .unwrap_or_else(|| na::vector![0.0, text_style.font_size]);
+                column_widths[column_idx] = column_widths[column_idx].max(size[0]);
+                row_heights[row_idx] = row_heights[row_idx].max(size[1]);
+            }
+        }
+        let column_offsets = std::iter::once(0.0)
+            .chain(column_widths.iter().scan(0.0, |offset, width| {
+                *offset += width + 2.0 * CELL_PADDING;
+                Some(*offset)
+            }))
+            .collect::<Vec<f64>>();
+        let row_offsets = std::iter::once(0.0)
+            .chain(row_heights.iter().scan(0.0, |offset, height| {
+                *offset += height + 2.0 * CELL_PADDING;
+                Some(*offset)
+            }))
+            .collect::<Vec<f64>>();
+        let table_width = column_offsets[columns_amount];
+        let table_height = row_offsets[table.rows.len()];
+
+        let mut strokes = vec![];
+        for (row_idx, row) in table.rows.into_iter().enumerate() {
+            for (column_idx, cell) in row.into_iter().enumerate() {
+                if cell.is_empty() {
+                    continue;
+                }
+                let cell_pos = na::vector![
+                    column_offsets[column_idx] + CELL_PADDING,
+                    row_offsets[row_idx] + CELL_PADDING
+                ];
+                strokes.push(Stroke::TextStroke(TextStroke::new(
+                    cell,
+                    cell_pos,
+                    text_style.clone(),
+                )));
+            }
+        }
+
+        let mut grid_options = SmoothOptions {
+            stroke_width: GRID_LINE_WIDTH,
+            stroke_color: Some(text_style.color),
+            ..Default::default()
+        };
+        grid_options.update_piet_stroke_style();
+        let grid_style = Style::Smooth(grid_options);
+        let vertical_lines = column_offsets.iter().map(|&x| Line {
+            start: na::vector![x, 0.0],
+            end: na::vector![x, table_height],
+        });
+        let horizontal_lines = row_offsets.iter().map(|&y| Line {
+            start: na::vector![0.0, y],
+            end: na::vector![table_width, y],
+        });
+        strokes.extend(vertical_lines.chain(horizontal_lines).map(|line| {
+            Stroke::ShapeStroke(ShapeStroke::new(Shape::Line(line), grid_style.clone()))
+        }));
+
+        // The cells and the grid lines form a group, so that the table is selected and moved as one
+        let stroke_groups = vec![vec![0]; strokes.len()];
+        let content = StrokeContent::default()
+            .with_strokes(
+                strokes
+                    .into_iter()
+                    .map(std::sync::Arc::new)
+                    .collect::<Vec<_>>(),
+            )
+            .with_stroke_groups(stroke_groups);
+        self.insert_stroke_content(content, pos, ImageSizeOption::RespectOriginalSize)
+    }
+
     /// Insert the stroke content.
     ///
     /// The data usually comes from the clipboard, drag-and-drop, ..
@@ -371,7 +880,7 @@ impl Engine {
 
         // we need to always deselect all strokes
         // even though changing the pen style deselects too, but only when the pen is actually different.
-        let all_strokes = self.store.stroke_keys_as_rendered();
+        let all_strokes = self.store.stroke_keys_unordered();
         self.store.set_selected_keys(&all_strokes, false);
         widget_flags |= self.change_pen_style(PenStyle::Selector);
 
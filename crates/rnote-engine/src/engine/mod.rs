@@ -5,6 +5,7 @@ pub mod export;
 pub mod import;
 pub mod rendering;
 pub mod snapshot;
+pub mod stamplibrary;
 pub mod strokecontent;
 pub mod visual_debug;
 
@@ -12,18 +13,26 @@ pub mod visual_debug;
 pub use animation::Animation;
 pub use config::EngineConfig;
 pub use config::EngineConfigShared;
-pub use export::ExportPrefs;
+pub use export::{ExportPrefs, ExportPreset};
 pub use import::ImportPrefs;
+pub use rendering::{RenderDiagnostics, RenderMetrics, RenderProgress};
 pub use snapshot::EngineSnapshot;
+pub use stamplibrary::StampLibrary;
 pub use strokecontent::StrokeContent;
 
 // Imports
 use crate::Image;
 use crate::document::Layout;
+use crate::document::attachments::AttachmentInfo;
 use crate::pens::PenMode;
-use crate::pens::{Pen, PenStyle};
-use crate::store::StrokeKey;
+use crate::pens::pensconfig::toolsconfig::ToolStyle;
+use crate::pens::{Pen, PenPreset, PenStyle, Protractor, Ruler};
+use crate::store::chrono_comp::{Restack, StrokeLayer};
+use crate::store::display_list::DisplayList;
 use crate::store::render_comp::{self, RenderCompState};
+use crate::store::tile_cache::TileIndex;
+use crate::store::{Layer, StrokeKey};
+use crate::strokes::Stroke;
 use crate::strokes::content::GeneratedContentImages;
 use crate::strokes::textstroke::{TextAttribute, TextStyle};
 use crate::{AudioPlayer, SelectionCollision, WidgetFlags};
@@ -32,8 +41,10 @@ use futures::StreamExt;
 use futures::channel::mpsc::UnboundedReceiver;
 use futures::channel::{mpsc, oneshot};
 use p2d::bounding_volume::{Aabb, BoundingVolume};
+use rendering::RenderCounters;
 use rnote_compose::eventresult::EventPropagation;
 use rnote_compose::ext::AabbExt;
+use rnote_compose::inputtransform::InputTransform;
 use rnote_compose::penevent::{PenEvent, ShortcutKey};
 use rnote_compose::{Color, SplitOrder};
 use serde::{Deserialize, Serialize};
@@ -53,6 +64,8 @@ pub struct EngineView<'a> {
     pub camera: &'a Camera,
     pub audioplayer: &'a Option<AudioPlayer>,
     pub animation: &'a Animation,
+    pub ruler: &'a Ruler,
+    pub protractor: &'a Protractor,
 }
 
 /// Constructs an `EngineView` from an identifier containing an `Engine` instance.
@@ -67,6 +80,8 @@ macro_rules! engine_view {
             camera: &$engine.camera,
             audioplayer: &$engine.audioplayer,
             animation: &$engine.animation,
+            ruler: &$engine.ruler,
+            protractor: &$engine.protractor,
         }
     };
 }
@@ -81,6 +96,8 @@ pub struct EngineViewMut<'a> {
     pub camera: &'a mut Camera,
     pub audioplayer: &'a mut Option<AudioPlayer>,
     pub animation: &'a mut Animation,
+    pub ruler: &'a mut Ruler,
+    pub protractor: &'a mut Protractor,
 }
 
 /// Constructs an `EngineViewMut` from an identifier containing an `Engine` instance.
@@ -95,6 +112,8 @@ macro_rules! engine_view_mut {
             camera: &mut $engine.camera,
             audioplayer: &mut $engine.audioplayer,
             animation: &mut $engine.animation,
+            ruler: &mut $engine.ruler,
+            protractor: &mut $engine.protractor,
         }
     };
 }
@@ -110,6 +129,8 @@ impl EngineViewMut<'_> {
             camera: self.camera,
             audioplayer: self.audioplayer,
             animation: self.animation,
+            ruler: self.ruler,
+            protractor: self.protractor,
         }
     }
 }
@@ -129,6 +150,30 @@ pub enum EngineTask {
         /// The image scale-factor the render task was using while generating the images.
         image_scale: f64,
     },
+    /// Replace the rendering of the given stroke with low resolution preview images,
+    /// while the task keeps generating the images in full resolution.
+    UpdateStrokeWithPreviewImages {
+        /// The stroke key.
+        key: StrokeKey,
+        /// The generated preview images.
+        images: GeneratedContentImages,
+        /// The image scale-factor the render task was started with, not the lowered one of the preview images.
+        image_scale: f64,
+    },
+    /// Reports that generating the images of the given stroke failed.
+    ///
+    /// The stroke is flagged dirty again, so its rendering gets regenerated on the next update.
+    StrokeRenderingFailed {
+        /// The stroke key.
+        key: StrokeKey,
+    },
+    /// Reports the progress of a render task that generates the images of many strokes.
+    RenderProgress {
+        /// The amount of strokes that are rendered in full resolution.
+        done: usize,
+        /// The total amount of strokes the task renders.
+        total: usize,
+    },
     /// Appends the images to the rendering of the given stroke.
     ///
     /// The state of the render component should be set **before** spawning a thread, generating images and sending this task,
@@ -139,20 +184,43 @@ pub enum EngineTask {
         /// The generated images
         images: GeneratedContentImages,
     },
+    /// Replace the rendering of the content tile.
+    ///
+    /// The state of the tile should be set **before** spawning a thread, generating the image and sending this task.
+    UpdateTileWithImage {
+        /// The tile index.
+        index: TileIndex,
+        /// The generated image, None when no strokes intersect the tile.
+        image: Option<Image>,
+        /// The strokes that were rendered in the image.
+        strokes: Vec<(StrokeKey, Arc<Stroke>)>,
+        /// The display lists that were newly recorded for the strokes while rendering.
+        display_lists: Vec<(StrokeKey, Arc<DisplayList>)>,
+        /// The image scale-factor the render task was using while generating the image.
+        image_scale: f64,
+    },
     /// Requests that the typewriter cursor should be blinked/toggled
     BlinkTypewriterCursor,
     /// Change the permanent zoom to the given value
     Zoom(f64),
+    /// Indicates that the camera has stopped moving, so the rendering can be regenerated in full resolution.
+    MotionFinished,
     /// Indicates that the application is quitting. Sent to quit the handler which receives the tasks.
     Quit,
 }
 
 #[derive(Debug, Clone)]
-pub struct EngineTaskSender(mpsc::UnboundedSender<EngineTask>);
+pub struct EngineTaskSender {
+    tx: mpsc::UnboundedSender<EngineTask>,
+    /// The counters of the render path, updated by the tasks that hold the sender.
+    render_counters: Arc<RenderCounters>,
+}
 
 impl EngineTaskSender {
     pub fn send(&self, task: EngineTask) {
-        if let Err(e) = self.0.unbounded_send(task) {
+        self.render_counters.task_sent();
+        if let Err(e) = self.tx.unbounded_send(task) {
+            self.render_counters.task_handled();
             let err = format!("{e:?}");
             error!(
                 "Failed to send engine task {:?}, Err: {err}",
@@ -160,6 +228,10 @@ impl EngineTaskSender {
             );
         }
     }
+
+    pub(crate) fn render_counters(&self) -> &RenderCounters {
+        &self.render_counters
+    }
 }
 
 #[derive(Debug)]
@@ -190,6 +262,10 @@ pub struct Engine {
     audioplayer: Option<AudioPlayer>,
     #[serde(skip)]
     pub animation: Animation,
+    #[serde(skip)]
+    pub ruler: Ruler,
+    #[serde(skip)]
+    pub protractor: Protractor,
     // the task sender. Must not be modified, only cloned.
     #[serde(skip)]
     tasks_tx: EngineTaskSender,
@@ -198,6 +274,10 @@ pub struct Engine {
     // Background rendering
     #[serde(skip)]
     background_tile_image: Option<Image>,
+    // Kept to be reused when the camera is translated
+    #[cfg(feature = "ui")]
+    #[serde(skip)]
+    background_tile_texture: Option<gtk4::gdk::MemoryTexture>,
     #[cfg(feature = "ui")]
     #[serde(skip)]
     background_rendernodes: Vec<gtk4::gsk::RenderNode>,
@@ -206,7 +286,15 @@ pub struct Engine {
     origin_indicator_image: Option<Image>,
     #[cfg(feature = "ui")]
     #[serde(skip)]
+    origin_indicator_texture: Option<gtk4::gdk::MemoryTexture>,
+    #[cfg(feature = "ui")]
+    #[serde(skip)]
     origin_indicator_rendernode: Option<gtk4::gsk::RenderNode>,
+    // The viewport the content rendering was last updated for, to detect camera movement
+    #[serde(skip)]
+    rendered_viewport: Option<Aabb>,
+    #[serde(skip)]
+    motion_task_handle: Option<crate::tasks::OneOffTaskHandle>,
 }
 
 impl Default for Engine {
@@ -222,14 +310,25 @@ impl Default for Engine {
 
             audioplayer: None,
             animation: Animation::default(),
-            tasks_tx: EngineTaskSender(tasks_tx),
+            ruler: Ruler::default(),
+            protractor: Protractor::default(),
+            tasks_tx: EngineTaskSender {
+                tx: tasks_tx,
+                render_counters: Arc::new(RenderCounters::default()),
+            },
             tasks_rx: Some(EngineTaskReceiver(tasks_rx)),
             background_tile_image: None,
             #[cfg(feature = "ui")]
+            background_tile_texture: None,
+            #[cfg(feature = "ui")]
             background_rendernodes: Vec::default(),
             origin_indicator_image: None,
             #[cfg(feature = "ui")]
+            origin_indicator_texture: None,
+            #[cfg(feature = "ui")]
             origin_indicator_rendernode: None,
+            rendered_viewport: None,
+            motion_task_handle: None,
         }
     }
 }
@@ -266,6 +365,13 @@ impl Engine {
         self.tasks_rx.take()
     }
 
+    /// The calibration of the stylus input, applied onto the elements when retrieving the input.
+    ///
+    /// The jitter filter is applied by the penholder when handling the pen events.
+    pub fn input_transform(&self) -> InputTransform {
+        self.config.read().input_transform
+    }
+
     /// Whether pen sounds are enabled.
     pub fn pen_sounds(&self) -> bool {
         self.config.read().pen_sounds
@@ -297,6 +403,34 @@ impl Engine {
         }
     }
 
+    /// Whether the ruler is shown.
+    pub fn ruler_visible(&self) -> bool {
+        self.ruler.visible()
+    }
+
+    /// Show or hide the ruler. It appears in the center of the viewport.
+    pub fn set_ruler_visible(&mut self, visible: bool) -> WidgetFlags {
+        self.ruler
+            .set_visible(visible, self.camera.viewport().center().coords);
+        let mut widget_flags = WidgetFlags::default();
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// Whether the protractor is shown.
+    pub fn protractor_visible(&self) -> bool {
+        self.protractor.visible()
+    }
+
+    /// Show or hide the protractor. It appears in the center of the viewport.
+    pub fn set_protractor_visible(&mut self, visible: bool) -> WidgetFlags {
+        self.protractor
+            .set_visible(visible, self.camera.viewport().center().coords);
+        let mut widget_flags = WidgetFlags::default();
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
     pub fn optimize_epd(&self) -> bool {
         self.config.read().optimize_epd
     }
@@ -322,6 +456,8 @@ impl Engine {
             stroke_components: Arc::clone(&store_history_entry.stroke_components),
             chrono_components: Arc::clone(&store_history_entry.chrono_components),
             chrono_counter: store_history_entry.chrono_counter,
+            group_components: Arc::clone(&store_history_entry.group_components),
+            layers: Arc::clone(&store_history_entry.layers),
         }
     }
 
@@ -402,6 +538,7 @@ impl Engine {
     pub fn handle_engine_task(&mut self, task: EngineTask) -> (WidgetFlags, bool) {
         let mut widget_flags = WidgetFlags::default();
         let mut quit = false;
+        self.tasks_tx.render_counters().task_handled();
 
         match task {
             EngineTask::UpdateStrokeWithImages {
@@ -411,7 +548,10 @@ impl Engine {
             } => {
                 if let Some(state) = self.store.render_comp_state(key) {
                     match state {
-                        RenderCompState::Complete | RenderCompState::ForViewport(_) => {
+                        RenderCompState::Complete
+                        | RenderCompState::ForViewport(_)
+                        | RenderCompState::Paths
+                        | RenderCompState::Instanced => {
                             // The rendering was already regenerated in the meantime,
                             // so we just discard the render task result
                         }
@@ -428,17 +568,56 @@ impl Engine {
                             }
                             widget_flags.redraw = true;
                         }
-                        RenderCompState::Dirty => {
+                        RenderCompState::DirectDraw | RenderCompState::Dirty => {
                             // If the state was flagged dirty in the meantime,
                             // it is expected that retriggering rendering will be handled elsewhere
                         }
                     }
                 }
             }
+            EngineTask::UpdateStrokeWithPreviewImages {
+                key,
+                images,
+                image_scale,
+            } => {
+                if self.store.render_comp_state(key) == Some(RenderCompState::BusyRenderingInTask)
+                    && (self.camera.image_scale() - image_scale).abs()
+                        <= render_comp::RENDER_IMAGE_SCALE_TOLERANCE
+                {
+                    self.store
+                        .replace_rendering_with_preview_images(key, images);
+                    widget_flags.redraw = true;
+                }
+            }
+            EngineTask::StrokeRenderingFailed { key } => {
+                if self.store.render_comp_state(key) == Some(RenderCompState::BusyRenderingInTask) {
+                    self.store.set_rendering_dirty(key);
+                    widget_flags.redraw = true;
+                }
+            }
+            EngineTask::RenderProgress { done, total } => {
+                widget_flags.render_progress = Some(RenderProgress { done, total });
+            }
             EngineTask::AppendImagesToStroke { key, images } => {
                 self.store.append_rendering_images(key, images);
                 widget_flags.redraw = true;
             }
+            EngineTask::UpdateTileWithImage {
+                index,
+                image,
+                strokes,
+                display_lists,
+                image_scale,
+            } => {
+                self.store.replace_tile_rendering(
+                    index,
+                    image,
+                    strokes,
+                    display_lists,
+                    image_scale,
+                );
+                widget_flags.redraw = true;
+            }
             EngineTask::BlinkTypewriterCursor => {
                 if let Pen::Typewriter(typewriter) = self.penholder.current_pen_mut() {
                     typewriter.toggle_cursor_visibility();
@@ -454,6 +633,10 @@ impl Engine {
                     | self.background_rendering_regenerate()
                     | self.update_rendering_current_viewport();
             }
+            EngineTask::MotionFinished => {
+                self.store.set_in_motion(false);
+                widget_flags |= self.update_content_rendering_current_viewport();
+            }
             EngineTask::Quit => {
                 widget_flags |= self.set_active(false);
                 quit = true;
@@ -505,6 +688,63 @@ impl Engine {
             .change_pen_mode(pen_mode, &mut engine_view_mut!(self))
     }
 
+    /// Save the current pen as preset, replacing an existing preset with the same name.
+    ///
+    /// Returns false if the current pen has no stroke width that could be saved.
+    pub fn save_pen_preset(&self, name: String) -> bool {
+        let pen_style = self.current_pen_style_w_override();
+        let mut config = self.config.write();
+        let Some(stroke_width) = config.pens_config.stroke_width(pen_style) else {
+            return false;
+        };
+        let preset = PenPreset {
+            name,
+            pen_style,
+            stroke_width,
+            stroke_color: config.pens_config.stroke_color(pen_style),
+        };
+        match config
+            .pen_presets
+            .iter_mut()
+            .find(|existing| existing.name == preset.name)
+        {
+            Some(existing) => *existing = preset,
+            None => config.pen_presets.push(preset),
+        }
+        true
+    }
+
+    /// Apply the pen preset at the given index, selecting its pen style with the saved stroke width and color.
+    pub fn apply_pen_preset(&mut self, index: usize) -> WidgetFlags {
+        let Some(preset) = self.config.read().pen_presets.get(index).cloned() else {
+            return WidgetFlags::default();
+        };
+        {
+            let mut config = self.config.write();
+            config
+                .pens_config
+                .set_stroke_width(preset.pen_style, preset.stroke_width);
+            if let Some(stroke_color) = preset.stroke_color {
+                config
+                    .pens_config
+                    .set_stroke_color(preset.pen_style, stroke_color);
+            }
+        }
+        let mut widget_flags = self.change_pen_style(preset.pen_style)
+            | self.change_pen_style_override(None)
+            | self.reinstall_pen_current_style();
+        widget_flags.refresh_ui = true;
+        widget_flags
+    }
+
+    /// Remove the pen preset at the given index.
+    pub fn remove_pen_preset(&self, index: usize) {
+        let mut config = self.config.write();
+        if index < config.pen_presets.len() {
+            config.pen_presets.remove(index);
+        }
+    }
+
     /// Reinstall the pen in the current style.
     pub fn reinstall_pen_current_style(&mut self) -> WidgetFlags {
         self.penholder
@@ -528,7 +768,7 @@ impl Engine {
     /// Generate bounds for each page on the document which contains content.
     pub fn pages_bounds_w_content(&self, split_order: SplitOrder) -> Vec<Aabb> {
         let doc_bounds = self.document.bounds();
-        let keys = self.store.stroke_keys_as_rendered();
+        let keys = self.store.stroke_keys_unordered();
 
         let strokes_bounds = self.store.strokes_bounds(&keys);
 
@@ -581,7 +821,7 @@ impl Engine {
 
     pub fn set_scale_factor(&mut self, scale_factor: f64) -> WidgetFlags {
         self.store
-            .set_rendering_dirty_for_strokes(&self.store.stroke_keys_as_rendered());
+            .set_rendering_dirty_for_strokes(&self.store.stroke_keys_unordered());
         self.camera.set_scale_factor(scale_factor)
             | self.background_rendering_regenerate()
             | self.update_content_rendering_current_viewport()
@@ -621,6 +861,25 @@ impl Engine {
         self.camera_set_offset_expand(new_offset)
     }
 
+    /// Move the camera vertically by the amount of pages, positive amounts move down.
+    ///
+    /// The offset is aligned so that the page starts at the top of the viewport.
+    pub fn camera_move_pages(&mut self, amount: i32) -> WidgetFlags {
+        let zoom = self.camera.zoom();
+        let page_height = self.document.config.format.height();
+        if page_height <= 0.0 {
+            return WidgetFlags::default();
+        }
+        let offset = self.camera.offset();
+        // the page that is closest to the top of the viewport
+        let current_page = ((offset[1] / zoom + Document::SHADOW_WIDTH) / page_height).round();
+        let new_page = current_page + f64::from(amount);
+        self.camera_set_offset_expand(na::vector![
+            offset[0],
+            (new_page * page_height - Document::SHADOW_WIDTH) * zoom
+        ])
+    }
+
     /// Resize the doc when in autoexpanding layouts. called e.g. when finishing a new stroke.
     ///
     /// Background rendering then needs to be updated.
@@ -714,6 +973,15 @@ impl Engine {
         self.penholder.fetch_clipboard_content(&engine_view!(self))
     }
 
+    /// The content of the selected strokes, or None when nothing is selected.
+    pub fn selection_stroke_content(&self) -> Option<StrokeContent> {
+        let selection = self.store.selection_keys_as_rendered();
+        if selection.is_empty() {
+            return None;
+        }
+        Some(self.store.fetch_stroke_content(&selection))
+    }
+
     /// Cut clipboard content from the current pen.
     #[allow(clippy::type_complexity)]
     pub fn cut_clipboard_content(
@@ -732,10 +1000,52 @@ impl Engine {
         }
     }
 
+    /// Embeds a file into the document and returns the name it is stored under.
+    pub fn add_attachment(
+        &mut self,
+        name: &str,
+        mime_type: String,
+        data: glib::Bytes,
+    ) -> (String, WidgetFlags) {
+        let mut widget_flags = WidgetFlags::default();
+        let name = self.document.attachments.add(name, mime_type, data);
+        widget_flags.store_modified = true;
+        widget_flags.refresh_ui = true;
+        (name, widget_flags)
+    }
+
+    /// Lists the files embedded into the document.
+    pub fn list_attachments(&self) -> Vec<AttachmentInfo> {
+        self.document.attachments.list()
+    }
+
+    /// Extracts the data of the embedded file with the given name.
+    pub fn extract_attachment(&self, name: &str) -> Option<glib::Bytes> {
+        self.document
+            .attachments
+            .get(name)
+            .map(|attachment| attachment.data.clone())
+    }
+
+    /// Removes the embedded file with the given name.
+    pub fn remove_attachment(&mut self, name: &str) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if self.document.attachments.remove(name).is_some() {
+            widget_flags.store_modified = true;
+            widget_flags.refresh_ui = true;
+        }
+        widget_flags
+    }
+
     pub fn select_all_strokes(&mut self) -> WidgetFlags {
         let widget_flags = self.change_pen_style(PenStyle::Selector);
-        self.store
-            .set_selected_keys(&self.store.stroke_keys_as_rendered(), true);
+        self.store.set_selected_keys(
+            &self.store.expand_to_groups(
+                self.store
+                    .filter_unlocked(self.store.stroke_keys_as_rendered()),
+            ),
+            true,
+        );
         widget_flags
             | self.current_pen_update_state()
             | self.doc_resize_autoexpand()
@@ -765,7 +1075,12 @@ impl Engine {
                 .store
                 .stroke_keys_as_rendered_intersecting_bounds(bounds),
         };
-        self.store.set_selected_keys(&select, true);
+        self.store.set_selected_keys(
+            &self
+                .store
+                .expand_to_groups(self.store.filter_unlocked(select)),
+            true,
+        );
         self.doc_resize_autoexpand()
             | self.record(Instant::now())
             | self.update_rendering_current_viewport()
@@ -793,6 +1108,305 @@ impl Engine {
         self.store.selection_keys_unordered().is_empty()
     }
 
+    /// Whether a stroke is at the given position, including locked strokes.
+    pub fn stroke_at(&self, pos: na::Vector2<f64>) -> bool {
+        !self
+            .store
+            .stroke_hitboxes_contain_coord(self.camera.viewport(), pos)
+            .is_empty()
+    }
+
+    /// Select the topmost stroke at the given position, replacing the current selection.
+    ///
+    /// Keeps the selection when the stroke is already part of it. Unlike the selector this also selects locked
+    /// strokes, so that they can be unlocked. Strokes on locked layers are skipped, they are unlocked through
+    /// their layer. Returns whether a stroke is at the position.
+    pub fn select_stroke_at(&mut self, pos: na::Vector2<f64>) -> (bool, WidgetFlags) {
+        let Some(key) = self
+            .store
+            .stroke_hitboxes_contain_coord(self.camera.viewport(), pos)
+            .into_iter()
+            .rev()
+            .find(|&key| !self.store.on_locked_layer(key))
+        else {
+            return (false, WidgetFlags::default());
+        };
+        if self.store.selected(key).unwrap_or(false) {
+            return (true, WidgetFlags::default());
+        }
+        let widget_flags = self.change_pen_style(PenStyle::Selector);
+        self.store
+            .set_selected_keys(&self.store.selection_keys_unordered(), false);
+        self.store.set_selected(key, true);
+        (
+            true,
+            widget_flags
+                | self.current_pen_update_state()
+                | self.record(Instant::now())
+                | self.update_rendering_current_viewport(),
+        )
+    }
+
+    /// Add the strokes with the same type and color as one of the selected strokes to the selection.
+    pub fn select_similar_strokes(&mut self) -> WidgetFlags {
+        let similar_to = self
+            .store
+            .get_strokes_ref(&self.store.selection_keys_unordered())
+            .into_iter()
+            .map(|stroke| (std::mem::discriminant(stroke), stroke.stroke_color()))
+            .collect::<Vec<_>>();
+        if similar_to.is_empty() {
+            return WidgetFlags::default();
+        }
+        let similar = self
+            .store
+            .filter_unlocked(self.store.stroke_keys_as_rendered())
+            .into_iter()
+            .filter(|&key| {
+                self.store.get_stroke_ref(key).is_some_and(|stroke| {
+                    similar_to.contains(&(std::mem::discriminant(stroke), stroke.stroke_color()))
+                })
+            })
+            .collect::<Vec<StrokeKey>>();
+        self.store
+            .set_selected_keys(&self.store.expand_to_groups(similar), true);
+        self.current_pen_update_state()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport()
+    }
+
+    /// Select all strokes whose color is within the configured tolerance of the given color, replacing the
+    /// current selection.
+    pub fn select_strokes_with_color(&mut self, color: Color) -> WidgetFlags {
+        let widget_flags = self.change_pen_style(PenStyle::Selector);
+        let tolerance = self
+            .config
+            .read()
+            .pens_config
+            .selector_config
+            .color_tolerance;
+        self.store
+            .set_selected_keys(&self.store.selection_keys_unordered(), false);
+        self.store.set_selected_keys(
+            &self.store.expand_to_groups(
+                self.store.filter_unlocked(
+                    self.store
+                        .stroke_keys_as_rendered_with_color(color, tolerance),
+                ),
+            ),
+            true,
+        );
+        widget_flags
+            | self.current_pen_update_state()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport()
+    }
+
+    /// Move the selected strokes in front of the other strokes on their layer.
+    pub fn selection_bring_to_front(&mut self) -> WidgetFlags {
+        self.selection_restack(Restack::Front)
+    }
+
+    /// Move the selected strokes behind the other strokes on their layer.
+    pub fn selection_send_to_back(&mut self) -> WidgetFlags {
+        self.selection_restack(Restack::Back)
+    }
+
+    /// Move the selected strokes in front of the next stroke on their layer that overlaps them.
+    pub fn selection_raise(&mut self) -> WidgetFlags {
+        self.selection_restack(Restack::Raise)
+    }
+
+    /// Move the selected strokes behind the next stroke on their layer that overlaps them.
+    pub fn selection_lower(&mut self) -> WidgetFlags {
+        self.selection_restack(Restack::Lower)
+    }
+
+    fn selection_restack(&mut self, restack: Restack) -> WidgetFlags {
+        let selection = self.store.selection_keys_unordered();
+        if !self.store.restack_keys(&selection, restack) {
+            return WidgetFlags::default();
+        }
+        self.selection_layers_changed()
+    }
+
+    /// Move the selected strokes one layer up or down.
+    pub fn selection_move_layer(&mut self, up: bool) -> WidgetFlags {
+        let selection = self.store.selection_keys_unordered();
+        if up {
+            self.store.move_user_layers(&selection, |layer| layer + 1);
+        } else {
+            self.store
+                .move_user_layers(&selection, |layer| layer.saturating_sub(1));
+        }
+        self.selection_layers_changed()
+    }
+
+    /// Move the selected strokes onto the layer with the given index.
+    pub fn selection_move_to_layer(&mut self, index: u32) -> WidgetFlags {
+        let selection = self.store.selection_keys_unordered();
+        self.store.move_user_layers(&selection, |_| index);
+        self.selection_layers_changed()
+    }
+
+    fn selection_layers_changed(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        widget_flags.store_modified = true;
+        widget_flags | self.record(Instant::now()) | self.update_rendering_current_viewport()
+    }
+
+    /// The user layers of the document, ordered from bottom to top.
+    pub fn layers(&self) -> &[Layer] {
+        self.store.layers()
+    }
+
+    /// The index of the layer that new strokes are placed on.
+    pub fn active_layer(&self) -> u32 {
+        self.store.active_layer()
+    }
+
+    pub fn set_active_layer(&mut self, index: u32) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.store.set_active_layer(index);
+        widget_flags.refresh_layers = true;
+        widget_flags
+    }
+
+    /// Add a new layer above the active layer, which then becomes the active layer.
+    pub fn add_layer(&mut self) -> WidgetFlags {
+        let index = self.store.active_layer() + 1;
+        self.store.insert_layer(index);
+        self.store.set_active_layer(index);
+        self.layers_changed()
+    }
+
+    pub fn rename_layer(&mut self, index: u32, name: String) -> WidgetFlags {
+        if self
+            .store
+            .layers()
+            .get(index as usize)
+            .is_none_or(|layer| layer.name == name)
+        {
+            return WidgetFlags::default();
+        }
+        self.store.set_layer_name(index, name);
+        self.layers_changed()
+    }
+
+    /// Show or hide the layer with the given index.
+    pub fn set_layer_visible(&mut self, index: u32, visible: bool) -> WidgetFlags {
+        self.store.set_layer_visible(index, visible);
+        self.current_pen_update_state() | self.layers_changed()
+    }
+
+    /// Lock or unlock the layer with the given index.
+    pub fn set_layer_locked(&mut self, index: u32, locked: bool) -> WidgetFlags {
+        self.store.set_layer_locked(index, locked);
+        self.current_pen_update_state() | self.layers_changed()
+    }
+
+    /// Move the layer with the given index one position up or down.
+    pub fn move_layer(&mut self, index: u32, up: bool) -> WidgetFlags {
+        let to = if up {
+            index + 1
+        } else if let Some(to) = index.checked_sub(1) {
+            to
+        } else {
+            return WidgetFlags::default();
+        };
+        if to as usize >= self.store.layers().len() {
+            return WidgetFlags::default();
+        }
+        self.store.move_layer(index, to);
+        self.layers_changed()
+    }
+
+    /// Remove the layer with the given index together with its strokes.
+    ///
+    /// The last remaining layer can't be removed.
+    pub fn remove_layer(&mut self, index: u32) -> WidgetFlags {
+        if !self.store.remove_layer(index) {
+            return WidgetFlags::default();
+        }
+        self.current_pen_update_state() | self.doc_resize_autoexpand() | self.layers_changed()
+    }
+
+    fn layers_changed(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        widget_flags.store_modified = true;
+        widget_flags.refresh_layers = true;
+        widget_flags | self.record(Instant::now()) | self.update_rendering_current_viewport()
+    }
+
+    /// The stroke color of the bottommost selected stroke that has one.
+    pub fn selection_stroke_color(&self) -> Option<Color> {
+        self.store
+            .get_strokes_ref(&self.store.selection_keys_as_rendered())
+            .into_iter()
+            .find_map(|stroke| stroke.stroke_color())
+    }
+
+    /// Whether all selected strokes are locked.
+    pub fn selection_locked(&self) -> bool {
+        let selection = self.store.selection_keys_unordered();
+        !selection.is_empty()
+            && selection
+                .iter()
+                .all(|&key| self.store.locked(key).unwrap_or(false))
+    }
+
+    /// Lock or unlock the selected strokes. Locked strokes are deselected.
+    pub fn set_selection_locked(&mut self, locked: bool) -> WidgetFlags {
+        let selection = self.store.selection_keys_unordered();
+        if selection.is_empty() {
+            return WidgetFlags::default();
+        }
+        self.store.set_locked_keys(&selection, locked);
+        if locked {
+            self.store.set_selected_keys(&selection, false);
+        }
+        let mut widget_flags = WidgetFlags::default();
+        widget_flags.store_modified = true;
+        widget_flags
+            | self.current_pen_update_state()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport()
+    }
+
+    /// Whether any of the selected strokes is a member of a group.
+    pub fn selection_grouped(&self) -> bool {
+        self.store
+            .selection_keys_unordered()
+            .into_iter()
+            .any(|key| self.store.grouped(key))
+    }
+
+    /// Group the selected strokes, so that they are selected, transformed and deleted as a unit.
+    ///
+    /// Groups that are part of the selection are nested inside the new group.
+    pub fn selection_group(&mut self) -> WidgetFlags {
+        let selection = self.store.selection_keys_unordered();
+        if selection.len() < 2 {
+            return WidgetFlags::default();
+        }
+        self.store.group_keys(&selection);
+        let mut widget_flags = WidgetFlags::default();
+        widget_flags.store_modified = true;
+        widget_flags | self.record(Instant::now())
+    }
+
+    /// Dissolve the outermost groups of the selected strokes. The groups nested inside them are kept.
+    pub fn selection_ungroup(&mut self) -> WidgetFlags {
+        if !self.selection_grouped() {
+            return WidgetFlags::default();
+        }
+        self.store
+            .ungroup_keys(&self.store.selection_keys_unordered());
+        let mut widget_flags = WidgetFlags::default();
+        widget_flags.store_modified = true;
+        widget_flags | self.record(Instant::now())
+    }
+
     pub fn change_selection_stroke_colors(&mut self, stroke_color: Color) -> WidgetFlags {
         self.store
             .change_stroke_colors(&self.store.selection_keys_as_rendered(), stroke_color)
@@ -814,6 +1428,66 @@ impl Engine {
             | self.update_content_rendering_current_viewport()
     }
 
+    /// Copy the style of the topmost selected stroke into the config of the pen that creates this kind of stroke,
+    /// and switch to that pen.
+    pub fn selection_copy_style(&mut self) -> WidgetFlags {
+        let Some(pen_style) = self
+            .store
+            .selection_keys_as_rendered()
+            .last()
+            .and_then(|&key| {
+                let stroke = self.store.get_stroke_ref(key)?;
+                let layer = self.store.stroke_layer(key)?;
+                self.config
+                    .write()
+                    .pens_config
+                    .copy_style_from_stroke(stroke, layer)
+            })
+        else {
+            return WidgetFlags::default();
+        };
+        let mut widget_flags = WidgetFlags::default();
+        widget_flags.refresh_ui = true;
+        widget_flags | self.change_pen_style(pen_style)
+    }
+
+    /// Apply the styles of the pen configs onto the selected strokes.
+    ///
+    /// Brush strokes get the style of the brush, shapes the style of the shaper and text the style of the typewriter.
+    pub fn selection_paste_style(&mut self) -> WidgetFlags {
+        let pens_config = self.config.read().pens_config.clone();
+        let changed_keys = self
+            .store
+            .change_stroke_styles(&self.store.selection_keys_as_rendered(), |stroke| {
+                pens_config.apply_style_to_stroke(stroke)
+            });
+        if changed_keys.is_empty() {
+            return WidgetFlags::default();
+        }
+        // Marker strokes are drawn underneath the other strokes, so brush strokes change the layer with the style
+        let brush_layer = pens_config.brush_config.layer_for_current_options();
+        for &key in changed_keys.iter() {
+            if !matches!(self.store.get_stroke_ref(key), Some(Stroke::BrushStroke(_))) {
+                continue;
+            }
+            let layer = self.store.stroke_layer(key);
+            if brush_layer == StrokeLayer::Highlighter && layer != Some(StrokeLayer::Highlighter) {
+                self.store.set_stroke_layer(key, StrokeLayer::Highlighter);
+            } else if brush_layer != StrokeLayer::Highlighter
+                && layer == Some(StrokeLayer::Highlighter)
+            {
+                self.store.set_stroke_layer(key, StrokeLayer::default());
+            }
+        }
+        let mut widget_flags = WidgetFlags::default();
+        widget_flags.redraw = true;
+        widget_flags.store_modified = true;
+        widget_flags
+            | self.current_pen_update_state()
+            | self.record(Instant::now())
+            | self.update_content_rendering_current_viewport()
+    }
+
     pub fn text_selection_change_style<F>(&mut self, modify_func: F) -> WidgetFlags
     where
         F: FnOnce(&mut TextStyle),
@@ -900,4 +1574,14 @@ impl Engine {
         self.penholder
             .current_pen_style_w_override(&engine_view!(self))
     }
+
+    /// Whether the current pen only views the document without modifying it,
+    /// like the laser pointer or the tools that move the camera.
+    pub fn current_pen_is_view_only(&self) -> bool {
+        self.current_pen_style_w_override() == PenStyle::Tools
+            && matches!(
+                self.config.read().pens_config.tools_config.style,
+                ToolStyle::OffsetCamera | ToolStyle::Zoom | ToolStyle::Laser
+            )
+    }
 }
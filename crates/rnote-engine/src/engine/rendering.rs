@@ -1,11 +1,711 @@
 // Imports
 use crate::Image;
+use crate::engine::EngineTask;
+use crate::image::ImageMemoryFormat;
+use crate::store::StrokeKey;
+use crate::tasks::{OneOffTaskError, OneOffTaskHandle};
 use crate::{Engine, WidgetFlags};
 use p2d::bounding_volume::Aabb;
 use piet::RenderContext;
 use rnote_compose::color;
+use rnote_compose::penevent::PenProgress;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 use tracing::error;
 
+/// The backend that is used to render the strokes on the canvas.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "render_backend")]
+pub enum RenderBackend {
+    /// Strokes are rasterized with Cairo into fixed-size tiles, which only get regenerated where strokes have changed
+    /// or when the zoom changes.
+    #[serde(rename = "cairo")]
+    Cairo,
+    /// Strokes that support it are handed to the GPU renderer of the toolkit as filled paths, which are independent
+    /// of the viewport and zoom.
+    ///
+    /// All other strokes are still rasterized with Cairo.
+    #[serde(rename = "gpu_paths")]
+    GpuPaths,
+}
+
+impl Default for RenderBackend {
+    fn default() -> Self {
+        Self::Cairo
+    }
+}
+
+impl TryFrom<u32> for RenderBackend {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!("RenderBackend try_from::<u32>() for value {} failed", value)
+        })
+    }
+}
+
+/// Counters of the render path.
+///
+/// Shared with the render tasks through the [EngineTaskSender](super::EngineTaskSender), so they can be updated
+/// from the worker threads.
+#[derive(Debug, Default)]
+pub struct RenderCounters {
+    frames: AtomicU64,
+    strokes_drawn_last_frame: AtomicUsize,
+    rasterized_images: AtomicU64,
+    raster_nanos: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    pending_tasks: AtomicUsize,
+}
+
+impl RenderCounters {
+    pub(crate) fn record_frame(&self, strokes_drawn: usize) {
+        self.frames.fetch_add(1, Ordering::Relaxed);
+        self.strokes_drawn_last_frame
+            .store(strokes_drawn, Ordering::Relaxed);
+    }
+
+    /// Record the time it took to rasterize an image.
+    pub(crate) fn record_raster(&self, duration: Duration) {
+        self.rasterized_images.fetch_add(1, Ordering::Relaxed);
+        self.raster_nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Run the rasterization and record the time it took.
+    pub(crate) fn time_raster<T>(&self, raster: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = raster();
+        self.record_raster(start.elapsed());
+        result
+    }
+
+    /// Record whether an existing rendering could be reused (hit) or needed to be regenerated (miss).
+    pub(crate) fn record_cache_access(&self, hit: bool) {
+        if hit {
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.cache_misses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn task_sent(&self) {
+        self.pending_tasks.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn task_handled(&self) {
+        // saturating, a failed update leaves the value unchanged
+        let _ = self
+            .pending_tasks
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+    }
+
+    /// The current values of the counters.
+    pub fn metrics(&self) -> RenderMetrics {
+        RenderMetrics {
+            frames: self.frames.load(Ordering::Relaxed),
+            strokes_drawn_last_frame: self.strokes_drawn_last_frame.load(Ordering::Relaxed),
+            rasterized_images: self.rasterized_images.load(Ordering::Relaxed),
+            raster_time: Duration::from_nanos(self.raster_nanos.load(Ordering::Relaxed)),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            cache_misses: self.cache_misses.load(Ordering::Relaxed),
+            pending_tasks: self.pending_tasks.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Reset the accumulating counters. The amount of pending tasks is kept.
+    pub fn reset(&self) {
+        self.frames.store(0, Ordering::Relaxed);
+        self.strokes_drawn_last_frame.store(0, Ordering::Relaxed);
+        self.rasterized_images.store(0, Ordering::Relaxed);
+        self.raster_nanos.store(0, Ordering::Relaxed);
+        self.cache_hits.store(0, Ordering::Relaxed);
+        self.cache_misses.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Metrics of the render path, to make its performance measurable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderMetrics {
+    /// The amount of drawn frames.
+    pub frames: u64,
+    /// The amount of strokes that were drawn in the last frame.
+    pub strokes_drawn_last_frame: usize,
+    /// The amount of images that were rasterized.
+    pub rasterized_images: u64,
+    /// The total time that was spent rasterizing images, summed over all threads.
+    pub raster_time: Duration,
+    /// The amount of times an existing stroke or tile rendering could be reused.
+    pub cache_hits: u64,
+    /// The amount of times a stroke or tile rendering needed to be regenerated.
+    pub cache_misses: u64,
+    /// The amount of engine tasks that were sent but not yet handled.
+    pub pending_tasks: usize,
+}
+
+impl RenderMetrics {
+    /// The mean time it took to rasterize an image.
+    pub fn mean_raster_time(&self) -> Duration {
+        if self.rasterized_images == 0 {
+            Duration::ZERO
+        } else {
+            self.raster_time / self.rasterized_images as u32
+        }
+    }
+
+    /// The fraction of renderings that could be reused, between 0.0 and 1.0.
+    pub fn cache_hit_rate(&self) -> f64 {
+        let total = self.cache_hits + self.cache_misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.cache_hits as f64 / total as f64
+        }
+    }
+}
+
+/// A report of the memory that is used for rendering and the state of the render path.
+///
+/// Meant to be included in bug reports about excessive memory usage.
+#[derive(Debug, Clone, Default)]
+pub struct RenderDiagnostics {
+    /// The amount of strokes in the store.
+    pub n_strokes: usize,
+    /// The amount of strokes that currently hold images.
+    pub n_strokes_holding_images: usize,
+    /// The memory in bytes held by the images of the stroke renderings.
+    pub stroke_images_memory: usize,
+    /// The amount of content tiles that hold a rendering.
+    pub n_rendered_tiles: usize,
+    /// The memory in bytes held by the images of the content tiles.
+    pub tile_images_memory: usize,
+    /// The amount of cached stroke display lists.
+    pub n_display_lists: usize,
+    /// The amount of bitmap image textures that are shared between strokes.
+    pub n_instanced_textures: usize,
+    /// The configured budget in bytes for the rendering memory.
+    pub texture_memory_budget: usize,
+    /// The strokes holding the most image memory in bytes, in descending order.
+    pub largest_consumers: Vec<(StrokeKey, usize)>,
+    /// The metrics of the render path, including the pending task count.
+    pub metrics: RenderMetrics,
+}
+
+impl RenderDiagnostics {
+    /// The amount of largest consumers that are reported.
+    pub const N_LARGEST_CONSUMERS: usize = 5;
+
+    /// The total memory in bytes held by images of the rendering.
+    pub fn total_memory(&self) -> usize {
+        self.stroke_images_memory + self.tile_images_memory
+    }
+}
+
+impl std::fmt::Display for RenderDiagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const MIB: f64 = 1024.0 * 1024.0;
+
+        writeln!(
+            f,
+            "rendering memory: {:.1} / {:.1} MiB",
+            self.total_memory() as f64 / MIB,
+            self.texture_memory_budget as f64 / MIB
+        )?;
+        writeln!(
+            f,
+            "stroke images: {:.1} MiB ({} of {} strokes)",
+            self.stroke_images_memory as f64 / MIB,
+            self.n_strokes_holding_images,
+            self.n_strokes
+        )?;
+        writeln!(
+            f,
+            "tile images: {:.1} MiB ({} tiles)",
+            self.tile_images_memory as f64 / MIB,
+            self.n_rendered_tiles
+        )?;
+        writeln!(f, "display lists: {}", self.n_display_lists)?;
+        writeln!(f, "instanced textures: {}", self.n_instanced_textures)?;
+        writeln!(f, "pending tasks: {}", self.metrics.pending_tasks)?;
+        writeln!(
+            f,
+            "strokes last frame: {}",
+            self.metrics.strokes_drawn_last_frame
+        )?;
+        writeln!(
+            f,
+            "mean raster time: {:.2} ms",
+            self.metrics.mean_raster_time().as_secs_f64() * 1000.0
+        )?;
+        write!(
+            f,
+            "cache hit rate: {:.1} %",
+            self.metrics.cache_hit_rate() * 100.0
+        )?;
+        for (key, memory) in self.largest_consumers.iter() {
+            write!(f, "\n  {key:?}: {:.1} MiB", *memory as f64 / MIB)?;
+        }
+        Ok(())
+    }
+}
+
+/// The margin around the viewport in which the content is already rendered, as factor of the viewport extents.
+///
+/// For example: 1.0 means the viewport is extended by its own extents on all sides.
+/// A larger margin avoids pop-in when panning but consumes more memory.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PrerenderMargin(f64);
+
+impl Default for PrerenderMargin {
+    fn default() -> Self {
+        Self(crate::image::VIEWPORT_EXTENTS_MARGIN_FACTOR)
+    }
+}
+
+impl PrerenderMargin {
+    /// The minimum margin.
+    pub const MIN: f64 = 0.0;
+    /// The maximum margin.
+    pub const MAX: f64 = 2.0;
+
+    /// A new margin, clamped to the allowed range.
+    pub fn new(factor: f64) -> Self {
+        Self(factor.clamp(Self::MIN, Self::MAX))
+    }
+
+    /// The margin as factor of the viewport extents.
+    pub fn factor(&self) -> f64 {
+        self.0.clamp(Self::MIN, Self::MAX)
+    }
+
+    /// Extend the viewport by the margin.
+    pub fn extend_viewport(&self, viewport: Aabb) -> Aabb {
+        use rnote_compose::ext::AabbExt;
+
+        viewport.extend_by(viewport.extents() * self.factor())
+    }
+}
+
+/// The progress of rendering many strokes at once, e.g. after pasting or zooming.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RenderProgress {
+    /// The amount of strokes that are rendered in full resolution.
+    pub done: usize,
+    /// The total amount of strokes that are rendered.
+    pub total: usize,
+}
+
+impl RenderProgress {
+    /// The progress as fraction between 0.0 and 1.0.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.done as f64 / self.total as f64
+        }
+    }
+}
+
+/// Limits for the images that are generated to render the strokes.
+///
+/// Protects against allocating huge surfaces when zooming far into large strokes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "render_limits")]
+pub struct RenderLimits {
+    /// The maximum width and height in pixels of a generated stroke image.
+    ///
+    /// The image scale is lowered for images that would exceed it.
+    #[serde(rename = "max_image_dimension")]
+    pub max_image_dimension: u32,
+    /// The budget in MiB for the memory of all images held by the rendering.
+    ///
+    /// When newly generated images would exceed it, they are generated with a lowered image scale.
+    #[serde(rename = "texture_memory_budget_mib")]
+    pub texture_memory_budget_mib: u32,
+}
+
+impl Default for RenderLimits {
+    fn default() -> Self {
+        Self {
+            max_image_dimension: 8192,
+            texture_memory_budget_mib: 1024,
+        }
+    }
+}
+
+impl RenderLimits {
+    /// The lowest factor the image scale gets reduced by to stay within the memory budget.
+    const MIN_BUDGET_SCALE_FACTOR: f64 = 0.1;
+
+    /// The texture memory budget in bytes.
+    pub fn texture_memory_budget(&self) -> usize {
+        self.texture_memory_budget_mib as usize * 1024 * 1024
+    }
+
+    /// The image scale for generating an image of the given bounds, capped to not exceed the maximum image dimension.
+    pub fn capped_image_scale(&self, bounds: Aabb, image_scale: f64) -> f64 {
+        let max_extent = bounds.extents().max() * image_scale;
+        let max_image_dimension = f64::from(self.max_image_dimension.max(1));
+        if max_extent > max_image_dimension {
+            image_scale * max_image_dimension / max_extent
+        } else {
+            image_scale
+        }
+    }
+
+    /// The factor the image scale needs to be reduced by, so that generating images with the projected size
+    /// in bytes doesn't exceed the budget, given the memory that is already in use.
+    pub fn budget_scale_factor(&self, used: usize, projected: usize) -> f64 {
+        let available = self.texture_memory_budget().saturating_sub(used);
+        if projected <= available {
+            1.0
+        } else {
+            // the memory grows quadratically with the image scale
+            (available as f64 / projected as f64)
+                .sqrt()
+                .max(Self::MIN_BUDGET_SCALE_FACTOR)
+        }
+    }
+}
+
+/// The antialiasing of the generated images.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "antialias")]
+pub enum Antialias {
+    #[serde(rename = "none")]
+    None,
+    #[serde(rename = "fast")]
+    Fast,
+    #[serde(rename = "good")]
+    Good,
+    #[serde(rename = "best")]
+    Best,
+}
+
+impl Default for Antialias {
+    fn default() -> Self {
+        Self::Good
+    }
+}
+
+impl TryFrom<u32> for Antialias {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!("Antialias try_from::<u32>() for value {} failed", value)
+        })
+    }
+}
+
+impl From<Antialias> for cairo::Antialias {
+    fn from(value: Antialias) -> Self {
+        match value {
+            Antialias::None => cairo::Antialias::None,
+            Antialias::Fast => cairo::Antialias::Fast,
+            Antialias::Good => cairo::Antialias::Good,
+            Antialias::Best => cairo::Antialias::Best,
+        }
+    }
+}
+
+/// The color depth of the generated images.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "color_depth")]
+pub enum ColorDepth {
+    /// 8 bits per channel.
+    #[serde(rename = "standard")]
+    Standard,
+    /// Floating point channels, which avoid banding in gradients and translucent overlaps
+    /// and can be presented on wide-gamut and high dynamic range displays.
+    #[serde(rename = "high")]
+    High,
+}
+
+impl Default for ColorDepth {
+    fn default() -> Self {
+        Self::Standard
+    }
+}
+
+impl TryFrom<u32> for ColorDepth {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!("ColorDepth try_from::<u32>() for value {} failed", value)
+        })
+    }
+}
+
+/// The quality of the generated images, to trade fidelity for speed on low-end devices.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "render_quality")]
+pub struct RenderQuality {
+    /// The antialiasing of the generated images.
+    #[serde(rename = "antialias")]
+    pub antialias: Antialias,
+    /// The maximum distance in pixels between curves and the lines they are flattened to when rasterized.
+    #[serde(rename = "flattening_tolerance")]
+    pub flattening_tolerance: f64,
+    /// Whether the images that are generated while the camera is moving have a reduced resolution.
+    ///
+    /// They are regenerated in full resolution once the camera stops moving.
+    #[serde(rename = "reduce_in_motion")]
+    pub reduce_in_motion: bool,
+    /// The color depth of the generated images.
+    ///
+    /// A high color depth quadruples the memory of the images.
+    #[serde(rename = "color_depth")]
+    pub color_depth: ColorDepth,
+}
+
+impl Default for RenderQuality {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl RenderQuality {
+    pub const DEFAULT: Self = Self {
+        antialias: Antialias::Good,
+        flattening_tolerance: 0.1,
+        reduce_in_motion: false,
+        color_depth: ColorDepth::Standard,
+    };
+    /// The quality of images that are not only displayed, like exports and the images that are stored in strokes.
+    ///
+    /// Unaffected by the configured render quality.
+    pub const FULL: Self = Self::DEFAULT;
+    pub const FLATTENING_TOLERANCE_MIN: f64 = 0.01;
+    pub const FLATTENING_TOLERANCE_MAX: f64 = 2.0;
+    /// The factor applied to the image scale of images generated while the camera is moving.
+    pub const IN_MOTION_IMAGE_SCALE_FACTOR: f64 = 0.5;
+    /// The time after the last camera movement until the camera is considered to have stopped.
+    pub const MOTION_TIMEOUT: Duration = Duration::from_millis(250);
+
+    /// The flattening tolerance, clamped to the valid range.
+    pub fn flattening_tolerance(&self) -> f64 {
+        self.flattening_tolerance.clamp(
+            Self::FLATTENING_TOLERANCE_MIN,
+            Self::FLATTENING_TOLERANCE_MAX,
+        )
+    }
+}
+
+/// The projected size in bytes of an image with the given bounds, image scale and color depth.
+pub(crate) fn projected_image_size(
+    bounds: Aabb,
+    image_scale: f64,
+    color_depth: ColorDepth,
+) -> usize {
+    let bytes_per_pixel = ImageMemoryFormat::from(color_depth).bytes_per_pixel();
+    let extents = bounds.extents() * image_scale;
+    (extents[0].ceil().max(0.0) * extents[1].ceil().max(0.0) * f64::from(bytes_per_pixel)) as usize
+}
+
+/// The priority of the background render threads.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "render_thread_priority")]
+pub enum RenderThreadPriority {
+    #[serde(rename = "normal")]
+    Normal,
+    /// Lowered below the priority of the thread handling the input, where supported.
+    #[serde(rename = "low")]
+    Low,
+}
+
+impl Default for RenderThreadPriority {
+    fn default() -> Self {
+        Self::Low
+    }
+}
+
+impl TryFrom<u32> for RenderThreadPriority {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!(
+                "RenderThreadPriority try_from::<u32>() for value {} failed",
+                value
+            )
+        })
+    }
+}
+
+/// Controls the background render threads and how handling their results is paced against the input handling,
+/// so that heavy background regeneration doesn't delay pen samples.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "render_scheduling")]
+pub struct RenderScheduling {
+    /// The amount of background render threads.
+    ///
+    /// When zero, one less than the available parallelism is used, which leaves a core for the input handling.
+    #[serde(rename = "render_threads")]
+    pub render_threads: usize,
+    /// The priority of the background render threads.
+    #[serde(rename = "thread_priority")]
+    pub thread_priority: RenderThreadPriority,
+    /// The time in milliseconds that render results are handled while the pen is in progress,
+    /// before yielding to the input handling.
+    #[serde(rename = "frame_budget_ms")]
+    pub frame_budget_ms: f64,
+}
+
+impl Default for RenderScheduling {
+    fn default() -> Self {
+        Self {
+            render_threads: 0,
+            thread_priority: RenderThreadPriority::default(),
+            frame_budget_ms: Self::FRAME_BUDGET_MS_DEFAULT,
+        }
+    }
+}
+
+impl RenderScheduling {
+    pub const FRAME_BUDGET_MS_DEFAULT: f64 = 4.0;
+    /// The niceness of render threads with a low priority.
+    #[cfg(target_os = "linux")]
+    const LOW_PRIORITY_NICENESS: i32 = 10;
+
+    /// The amount of background render threads, resolving zero to one less than the available parallelism.
+    pub fn n_render_threads(&self) -> usize {
+        if self.render_threads > 0 {
+            self.render_threads
+        } else {
+            std::thread::available_parallelism()
+                .map(|n| n.get().saturating_sub(1))
+                .unwrap_or(1)
+                .max(1)
+        }
+    }
+
+    /// The time that render results are handled while the pen is in progress, before yielding to the input handling.
+    pub fn frame_budget(&self) -> Duration {
+        Duration::from_secs_f64(self.frame_budget_ms.max(0.0) / 1000.0)
+    }
+
+    /// Whether the render thread pool needs to be rebuilt when switching to the other scheduling.
+    fn pool_differs(&self, other: &Self) -> bool {
+        self.n_render_threads() != other.n_render_threads()
+            || self.thread_priority != other.thread_priority
+    }
+
+    fn build_pool(&self) -> anyhow::Result<rayon::ThreadPool> {
+        let thread_priority = self.thread_priority;
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(self.n_render_threads())
+            .thread_name(|i| format!("rnote-render-{i}"))
+            .start_handler(move |_| set_current_thread_priority(thread_priority))
+            .build()
+            .map_err(|e| anyhow::anyhow!("Building render thread pool failed, Err: {e:?}"))
+    }
+}
+
+/// The pool of the background render threads, together with the scheduling it was built for.
+static RENDER_POOL: RwLock<Option<(RenderScheduling, Arc<rayon::ThreadPool>)>> = RwLock::new(None);
+
+/// Rebuild the render thread pool when the scheduling requires it.
+///
+/// Tasks that are already running finish on the previous pool.
+pub(crate) fn set_render_scheduling(render_scheduling: RenderScheduling) {
+    let mut render_pool = RENDER_POOL.write().unwrap();
+    if let Some((current, _)) = render_pool.as_mut()
+        && !current.pool_differs(&render_scheduling)
+    {
+        *current = render_scheduling;
+        return;
+    }
+    match render_scheduling.build_pool() {
+        Ok(pool) => *render_pool = Some((render_scheduling, Arc::new(pool))),
+        Err(e) => error!("Applying render scheduling failed, Err: {e:?}"),
+    }
+}
+
+/// Spawn a render task on the background render threads.
+///
+/// Parallel iterators inside the task run on the render threads as well.
+/// Falls back to the global pool when no render scheduling was applied yet.
+pub(crate) fn spawn_render<F>(task: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    let pool = RENDER_POOL
+        .read()
+        .unwrap()
+        .as_ref()
+        .map(|(_, pool)| Arc::clone(pool));
+    match pool {
+        Some(pool) => pool.spawn(task),
+        None => rayon::spawn(task),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_current_thread_priority(thread_priority: RenderThreadPriority) {
+    let niceness = match thread_priority {
+        RenderThreadPriority::Normal => return,
+        RenderThreadPriority::Low => RenderScheduling::LOW_PRIORITY_NICENESS,
+    };
+    // On Linux the niceness applies to the calling thread only
+    // SAFETY: setpriority has no memory safety preconditions
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, niceness) } != 0 {
+        error!(
+            "Lowering the priority of render thread failed, Err: {:?}",
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_current_thread_priority(_thread_priority: RenderThreadPriority) {}
+
 impl Engine {
     /// Update the background rendering for the current viewport.
     ///
@@ -20,19 +720,31 @@ impl Engine {
             use rnote_compose::SplitOrder;
             use rnote_compose::ext::AabbExt;
 
-            let viewport = self.camera.viewport();
+            // the background is prerendered in the margin as well, so that it doesn't pop in while panning
+            let viewport = self
+                .config
+                .read()
+                .prerender_margin
+                .extend_viewport(self.camera.viewport());
             let mut rendernodes: Vec<gsk::RenderNode> = vec![];
 
             if let Some(image) = &self.background_tile_image {
-                // Only create the texture once, it is expensive
-                let new_texture = match image.to_memtexture() {
-                    Ok(t) => t,
-                    Err(e) => {
-                        error!(
-                            "Failed to generate memory-texture of background tile image, Err: {e:?}"
-                        );
-                        return widget_flags;
-                    }
+                // Only create the texture once, it is expensive.
+                // It is kept and reused for as long as the tile image stays the same, e.g. when the camera is translated.
+                let new_texture = match &self.background_tile_texture {
+                    Some(texture) => texture.clone(),
+                    None => match image.to_memtexture() {
+                        Ok(t) => {
+                            self.background_tile_texture = Some(t.clone());
+                            t
+                        }
+                        Err(e) => {
+                            error!(
+                                "Failed to generate memory-texture of background tile image, Err: {e:?}"
+                            );
+                            return widget_flags;
+                        }
+                    },
                 };
 
                 for split_bounds in viewport.split_extended_origin_aligned(
@@ -61,14 +773,20 @@ impl Engine {
 
             if let Some(image) = &self.origin_indicator_image {
                 // Only create the texture once, it is expensive
-                let new_texture = match image.to_memtexture() {
-                    Ok(t) => t,
-                    Err(e) => {
-                        error!(
-                            "Failed to generate memory-texture of origin indicator image, Err: {e:?}"
-                        );
-                        return widget_flags;
-                    }
+                let new_texture = match &self.origin_indicator_texture {
+                    Some(texture) => texture.clone(),
+                    None => match image.to_memtexture() {
+                        Ok(t) => {
+                            self.origin_indicator_texture = Some(t.clone());
+                            t
+                        }
+                        Err(e) => {
+                            error!(
+                                "Failed to generate memory-texture of origin indicator image, Err: {e:?}"
+                            );
+                            return widget_flags;
+                        }
+                    },
                 };
 
                 self.origin_indicator_rendernode = Some(
@@ -91,16 +809,130 @@ impl Engine {
     /// Update the content rendering for the current viewport.
     pub fn update_content_rendering_current_viewport(&mut self) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
+        let (render_backend, render_limits, prerender_margin, render_quality, render_scheduling) = {
+            let config = self.config.read();
+            (
+                config.render_backend,
+                config.render_limits,
+                config.prerender_margin,
+                config.render_quality,
+                config.render_scheduling,
+            )
+        };
+        set_render_scheduling(render_scheduling);
+        self.store.set_prerender_margin(prerender_margin);
+        if self.store.render_limits() != render_limits {
+            self.store.set_render_limits(render_limits);
+        }
+        if self.store.render_quality() != render_quality {
+            self.store.set_render_quality(render_quality);
+        }
+        let viewport = self.camera.viewport();
+        // the camera was translated when only the position of the viewport changed
+        let translated = self.rendered_viewport.is_some_and(|rendered_viewport| {
+            rendered_viewport.mins != viewport.mins
+                && rendered_viewport.extents() == viewport.extents()
+        });
+        self.rendered_viewport = Some(viewport);
+        if render_quality.reduce_in_motion && translated {
+            self.store.set_in_motion(true);
+            self.reset_motion_timeout();
+        }
+        let backend_changed = self.store.render_backend() != render_backend;
+        if backend_changed {
+            self.store.set_render_backend(render_backend);
+        }
         self.store.regenerate_rendering_in_viewport_threaded(
             self.engine_tasks_tx(),
-            false,
-            self.camera.viewport(),
+            backend_changed,
+            viewport,
             self.camera.image_scale(),
         );
         widget_flags.redraw = true;
         widget_flags
     }
 
+    /// (Re)start the timeout after which the camera is considered to have stopped moving.
+    fn reset_motion_timeout(&mut self) {
+        let tasks_tx = self.engine_tasks_tx();
+        let motion_task = move || {
+            tasks_tx.send(EngineTask::MotionFinished);
+        };
+        if let Some(handle) = self.motion_task_handle.as_mut() {
+            match handle.replace_task(motion_task.clone()) {
+                Ok(()) => return,
+                Err(OneOffTaskError::TimeoutReached) => {}
+                Err(e) => {
+                    error!("Could not replace task for one off motion task, Err: {e:?}");
+                }
+            }
+        }
+        self.motion_task_handle = Some(OneOffTaskHandle::new(
+            motion_task,
+            RenderQuality::MOTION_TIMEOUT,
+        ));
+    }
+
+    /// Whether the handling of engine tasks should yield to the input handling,
+    /// given how long tasks were handled since it last yielded.
+    ///
+    /// Only paced while the pen is in progress, so that render results don't delay pen samples.
+    pub fn yield_to_input(&self, handling_duration: Duration) -> bool {
+        self.penholder.current_pen_progress() == PenProgress::InProgress
+            && handling_duration >= self.config.read().render_scheduling.frame_budget()
+    }
+
+    /// The current metrics of the render path.
+    pub fn render_metrics(&self) -> RenderMetrics {
+        self.tasks_tx.render_counters().metrics()
+    }
+
+    /// A report of the memory that is used for rendering and the state of the render path.
+    pub fn render_diagnostics(&self) -> RenderDiagnostics {
+        let mut stroke_images_memory_usage = self.store.stroke_images_memory_usage();
+        let stroke_images_memory = stroke_images_memory_usage
+            .iter()
+            .map(|(_, memory)| memory)
+            .sum();
+        let n_strokes_holding_images = stroke_images_memory_usage.len();
+        stroke_images_memory_usage.sort_unstable_by(|(_, a), (_, b)| b.cmp(a));
+        stroke_images_memory_usage.truncate(RenderDiagnostics::N_LARGEST_CONSUMERS);
+
+        RenderDiagnostics {
+            n_strokes: self.store.stroke_components.len(),
+            n_strokes_holding_images,
+            stroke_images_memory,
+            n_rendered_tiles: self.store.n_rendered_tiles(),
+            tile_images_memory: self.store.tile_images().map(|image| image.data.len()).sum(),
+            n_display_lists: self.store.n_display_lists(),
+            n_instanced_textures: self.store.n_instanced_textures(),
+            texture_memory_budget: self.store.render_limits().texture_memory_budget(),
+            largest_consumers: stroke_images_memory_usage,
+            metrics: self.render_metrics(),
+        }
+    }
+
+    /// Reset the metrics of the render path, e.g. before measuring a specific operation.
+    pub fn reset_render_metrics(&self) {
+        self.tasks_tx.render_counters().reset();
+    }
+
+    /// The margin around the viewport in which the content is already rendered.
+    pub fn prerender_margin(&self) -> PrerenderMargin {
+        self.config.read().prerender_margin
+    }
+
+    /// Regenerate the content tiles in the current viewport where strokes have changed.
+    ///
+    /// Cheap when nothing has changed, so it is expected to be called before every redraw.
+    pub fn update_content_tiles_current_viewport(&mut self) {
+        self.store.regenerate_tiles_in_viewport_threaded(
+            self.engine_tasks_tx(),
+            self.camera.viewport(),
+            self.camera.image_scale(),
+        );
+    }
+
     /// Update the content and background rendering for the current viewport.
     ///
     /// If the background pattern or zoom has changed, the background pattern needs to be regenerated first.
@@ -117,6 +949,8 @@ impl Engine {
         self.origin_indicator_image.take();
         #[cfg(feature = "ui")]
         {
+            self.background_tile_texture.take();
+            self.origin_indicator_texture.take();
             self.background_rendernodes.clear();
             self.origin_indicator_rendernode.take();
         }
@@ -133,6 +967,8 @@ impl Engine {
         match self.document.config.background.gen_tile_image(image_scale) {
             Ok(image) => {
                 self.background_tile_image = Some(image);
+                #[cfg(feature = "ui")]
+                self.background_tile_texture.take();
             }
             Err(e) => {
                 error!("Regenerating background tile image failed, Err: {e:?}");
@@ -143,6 +979,8 @@ impl Engine {
         match gen_origin_indicator_image(scale_factor) {
             Ok(image) => {
                 self.origin_indicator_image = Some(image);
+                #[cfg(feature = "ui")]
+                self.origin_indicator_texture.take();
             }
             Err(e) => {
                 error!("Regenerating origin indicator image failed, Err: {e:?}");
@@ -178,8 +1016,10 @@ impl Engine {
         self.draw_background_to_gtk_snapshot(snapshot)?;
         self.draw_format_borders_to_gtk_snapshot(snapshot)?;
         self.draw_origin_indicator_to_gtk_snapshot(snapshot)?;
-        self.store
+        let strokes_drawn = self
+            .store
             .draw_strokes_to_gtk_snapshot(snapshot, doc_bounds, viewport);
+        self.tasks_tx.render_counters().record_frame(strokes_drawn);
         snapshot.restore();
         /*
                let cairo_cx = snapshot.append_cairo(&graphene::Rect::from_p2d_aabb(surface_bounds));
@@ -204,6 +1044,14 @@ impl Engine {
             visual_debug::draw_statistics_to_gtk_snapshot(snapshot, self, surface_bounds)?;
         }
 
+        if self.config.read().render_diagnostics {
+            visual_debug::draw_render_diagnostics_to_gtk_snapshot(
+                snapshot,
+                &self.render_diagnostics(),
+                surface_bounds,
+            )?;
+        }
+
         Ok(())
     }
 
@@ -379,5 +1227,6 @@ fn gen_origin_indicator_image(scale_factor: f64) -> anyhow::Result<Image> {
         },
         bounds,
         scale_factor,
+        RenderQuality::FULL,
     )
 }
@@ -1,16 +1,19 @@
 // Imports
 use crate::document::background;
 use crate::engine::import::XoppImportPrefs;
+use crate::fileformats::rnoteformat::encryption;
 use crate::fileformats::{FileFormatLoader, rnoteformat, xoppformat};
-use crate::store::{ChronoComponent, StrokeKey};
-use crate::strokes::Stroke;
+use crate::store::{ChronoComponent, GroupComponent, Layer, StrokeKey};
+use crate::strokes::{Content, OpaqueStroke, Stroke};
 use crate::{Camera, Document, Engine};
 use anyhow::Context;
 use futures::channel::oneshot;
+use rnote_compose::shapes::Shapeable;
 use serde::{Deserialize, Serialize};
 use slotmap::{SecondaryMap, SlotMap};
 use std::sync::Arc;
 use tracing::error;
+use zeroize::Zeroizing;
 
 /// Trait for types which hold configuration needed for engine snapshots
 pub trait Snapshotable {
@@ -31,6 +34,10 @@ pub struct EngineSnapshot {
     pub chrono_components: Arc<SecondaryMap<StrokeKey, Arc<ChronoComponent>>>,
     #[serde(rename = "chrono_counter")]
     pub chrono_counter: u32,
+    #[serde(rename = "group_components")]
+    pub group_components: Arc<SecondaryMap<StrokeKey, Arc<GroupComponent>>>,
+    #[serde(rename = "layers")]
+    pub layers: Arc<Vec<Layer>>,
 }
 
 impl Default for EngineSnapshot {
@@ -41,11 +48,134 @@ impl Default for EngineSnapshot {
             stroke_components: Arc::new(SlotMap::with_key()),
             chrono_components: Arc::new(SecondaryMap::new()),
             chrono_counter: 0,
+            group_components: Arc::new(SecondaryMap::new()),
+            layers: Arc::new(vec![Layer::default()]),
         }
     }
 }
 
+/// What was changed when repairing a snapshot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SnapshotRepairReport {
+    /// Strokes removed because their geometry is invalid.
+    pub removed_invalid_strokes: usize,
+    /// Chrono components removed because their stroke does not exist.
+    pub removed_orphaned_chrono_components: usize,
+    /// Chrono components added for strokes which had none.
+    pub added_missing_chrono_components: usize,
+    /// Whether the chrono counter was behind the chrono components and was advanced.
+    pub advanced_chrono_counter: bool,
+}
+
+impl SnapshotRepairReport {
+    /// Whether nothing needed to be repaired.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 impl EngineSnapshot {
+    /// Validates the structure of the snapshot and repairs it where possible.
+    ///
+    /// Removes strokes with non-finite bounds (except opaque strokes) and chrono components without strokes, adds missing chrono
+    /// components and advances the chrono counter past the existing components.
+    pub fn repair(&mut self) -> SnapshotRepairReport {
+        let mut report = SnapshotRepairReport::default();
+        let stroke_components = Arc::make_mut(&mut self.stroke_components);
+        let chrono_components = Arc::make_mut(&mut self.chrono_components);
+
+        let invalid_keys = stroke_components
+            .iter_mut()
+            // Opaque strokes are kept unchanged, their bounds are only estimated
+            .filter(|(_, stroke)| !matches!(stroke.as_ref(), Stroke::Opaque(_)))
+            .filter_map(|(key, stroke)| {
+                Arc::make_mut(stroke).update_geometry();
+                let bounds = stroke.bounds();
+                (!bounds.mins.coords.iter().all(|c| c.is_finite())
+                    || !bounds.maxs.coords.iter().all(|c| c.is_finite()))
+                .then_some(key)
+            })
+            .collect::<Vec<StrokeKey>>();
+        for key in invalid_keys {
+            stroke_components.remove(key);
+            report.removed_invalid_strokes += 1;
+        }
+
+        let chrono_components_len = chrono_components.len();
+        chrono_components.retain(|key, _| stroke_components.contains_key(key));
+        report.removed_orphaned_chrono_components = chrono_components_len - chrono_components.len();
+
+        let max_t = chrono_components
+            .values()
+            .map(|chrono_comp| chrono_comp.t())
+            .max()
+            .unwrap_or(0);
+        if self.chrono_counter < max_t {
+            self.chrono_counter = max_t;
+            report.advanced_chrono_counter = true;
+        }
+        for (key, stroke) in stroke_components.iter() {
+            if !chrono_components.contains_key(key) {
+                self.chrono_counter += 1;
+                chrono_components.insert(
+                    key,
+                    Arc::new(ChronoComponent::new(
+                        self.chrono_counter,
+                        stroke.extract_default_layer(),
+                    )),
+                );
+                report.added_missing_chrono_components += 1;
+            }
+        }
+        report
+    }
+
+    /// Deserializes the snapshot of the rnote file, saved with the given version.
+    fn from_rnote_file(
+        rnote_file: &rnoteformat::RnoteFile,
+        version: &semver::Version,
+    ) -> anyhow::Result<Self> {
+        let mut snapshot: Self = ijson::from_value(&rnote_file.engine_snapshot)?;
+        if *version > semver::Version::parse(rnoteformat::RnoteFile::SEMVER)? {
+            snapshot.preserve_unknown_stroke_fields(&rnote_file.engine_snapshot)?;
+        }
+        Ok(snapshot)
+    }
+
+    /// Converts the strokes which have fields that are unknown to this version to opaque strokes,
+    /// so that the fields are written back unchanged when saving.
+    ///
+    /// Only needed for files saved by newer versions, older fields that are ignored on purpose would be detected as
+    /// well.
+    fn preserve_unknown_stroke_fields(
+        &mut self,
+        engine_snapshot: &ijson::IValue,
+    ) -> anyhow::Result<()> {
+        let Some(stroke_components_data) = engine_snapshot.get("stroke_components") else {
+            return Ok(());
+        };
+        // Deserializing the slots again results in the same keys
+        let strokes_data =
+            ijson::from_value::<SlotMap<StrokeKey, serde_json::Value>>(stroke_components_data)?;
+        let stroke_components = Arc::make_mut(&mut self.stroke_components);
+
+        for (key, data) in strokes_data {
+            let Some(stroke) = stroke_components.get_mut(key) else {
+                continue;
+            };
+            if matches!(stroke.as_ref(), Stroke::Opaque(_)) {
+                continue;
+            }
+            if has_unknown_fields(&data, &serde_json::to_value(stroke.as_ref())?) {
+                *stroke = Arc::new(Stroke::Opaque(OpaqueStroke::new_w_preview(
+                    data,
+                    stroke.as_ref().clone(),
+                )));
+            }
+        }
+        Ok(())
+    }
+
     /// Loads a snapshot from the bytes of a .rnote file.
     ///
     /// To import this snapshot into the current engine, use [`Engine::load_snapshot()`].
@@ -54,9 +184,10 @@ impl EngineSnapshot {
 
         rayon::spawn(move || {
             let result = || -> anyhow::Result<Self> {
-                let rnote_file = rnoteformat::RnoteFile::load_from_bytes(&bytes)
-                    .context("loading RnoteFile from bytes failed.")?;
-                Ok(ijson::from_value(&rnote_file.engine_snapshot)?)
+                let (rnote_file, version) =
+                    rnoteformat::RnoteFile::load_from_bytes_w_version(&bytes)
+                        .context("loading RnoteFile from bytes failed.")?;
+                Self::from_rnote_file(&rnote_file, &version)
             };
 
             if let Err(_data) = snapshot_sender.send(result()) {
@@ -68,6 +199,35 @@ impl EngineSnapshot {
 
         snapshot_receiver.await?
     }
+
+    /// Loads a snapshot from the bytes of a password encrypted .rnote file.
+    ///
+    /// When the password is wrong, the returned error can be downcast to [`encryption::DecryptError::WrongPassword`].
+    pub async fn load_from_encrypted_rnote_bytes(
+        bytes: Vec<u8>,
+        password: Zeroizing<String>,
+    ) -> anyhow::Result<Self> {
+        let (snapshot_sender, snapshot_receiver) = oneshot::channel::<anyhow::Result<Self>>();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Self> {
+                let bytes = encryption::decrypt(&bytes, &password)?;
+                let (rnote_file, version) =
+                    rnoteformat::RnoteFile::load_from_bytes_w_version(&bytes)
+                        .context("loading RnoteFile from bytes failed.")?;
+                Self::from_rnote_file(&rnote_file, &version)
+            };
+
+            if let Err(_data) = snapshot_sender.send(result()) {
+                error!(
+                    "Sending bytes result to receiver failed while loading encrypted rnote bytes in. Receiver already dropped."
+                );
+            }
+        });
+
+        snapshot_receiver.await?
+    }
+
     /// Loads from the bytes of a Xournal++ .xopp file.
     ///
     /// To import this snapshot into the current engine, use [`Engine::load_snapshot()`].
@@ -219,3 +379,76 @@ impl EngineSnapshot {
         snapshot_receiver.await?
     }
 }
+
+/// Whether the data has object fields that are missing in the serialized data of the loaded value.
+fn has_unknown_fields(data: &serde_json::Value, serialized: &serde_json::Value) -> bool {
+    match (data, serialized) {
+        (serde_json::Value::Object(data), serde_json::Value::Object(serialized)) => {
+            data.iter().any(|(key, value)| match serialized.get(key) {
+                Some(serialized_value) => has_unknown_fields(value, serialized_value),
+                None => true,
+            })
+        }
+        (serde_json::Value::Array(data), serde_json::Value::Array(serialized)) => data
+            .iter()
+            .zip(serialized.iter())
+            .any(|(value, serialized_value)| has_unknown_fields(value, serialized_value)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::chrono_comp::StrokeLayer;
+    use crate::strokes::BrushStroke;
+    use rnote_compose::Style;
+    use rnote_compose::penpath::Element;
+
+    fn brushstroke(pos: na::Vector2<f64>) -> Arc<Stroke> {
+        Arc::new(Stroke::BrushStroke(BrushStroke::new(
+            Element::new(pos, 0.5),
+            Style::default(),
+        )))
+    }
+
+    #[test]
+    fn repair() {
+        let mut snapshot = EngineSnapshot::default();
+        let stroke_components = Arc::make_mut(&mut snapshot.stroke_components);
+        let valid = stroke_components.insert(brushstroke(na::vector![0.0, 0.0]));
+        let invalid = stroke_components.insert(brushstroke(na::vector![f64::NAN, 0.0]));
+        let without_chrono = stroke_components.insert(brushstroke(na::vector![10.0, 10.0]));
+        let removed = stroke_components.insert(brushstroke(na::vector![20.0, 20.0]));
+        stroke_components.remove(removed);
+        let chrono_components = Arc::make_mut(&mut snapshot.chrono_components);
+        for (key, t) in [(valid, 5), (invalid, 1), (removed, 3)] {
+            chrono_components.insert(
+                key,
+                Arc::new(ChronoComponent::new(t, StrokeLayer::UserLayer(0))),
+            );
+        }
+        snapshot.chrono_counter = 2;
+
+        let report = snapshot.repair();
+        assert_eq!(
+            report,
+            SnapshotRepairReport {
+                removed_invalid_strokes: 1,
+                removed_orphaned_chrono_components: 2,
+                added_missing_chrono_components: 1,
+                advanced_chrono_counter: true,
+            }
+        );
+        assert_eq!(snapshot.stroke_components.len(), 2);
+        assert!(!snapshot.stroke_components.contains_key(invalid));
+        assert_eq!(snapshot.chrono_components.len(), 2);
+        assert_eq!(snapshot.chrono_components[valid].t(), 5);
+        // the added chrono component comes after all existing ones
+        assert_eq!(snapshot.chrono_components[without_chrono].t(), 6);
+        assert_eq!(snapshot.chrono_counter, 6);
+
+        // a repaired snapshot needs no further repairs
+        assert!(snapshot.repair().is_empty());
+    }
+}
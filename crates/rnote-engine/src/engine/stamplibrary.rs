@@ -0,0 +1,44 @@
+// Imports
+use crate::strokes::stampstroke::Stamp;
+use serde::{Deserialize, Serialize};
+
+/// The stamps that can be placed with the stamp brush.
+///
+/// Holds the built-in stamps first, followed by the custom stamps that were added.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "stamp_library")]
+pub struct StampLibrary {
+    #[serde(rename = "stamps")]
+    stamps: Vec<Stamp>,
+}
+
+impl Default for StampLibrary {
+    fn default() -> Self {
+        Self {
+            stamps: vec![Stamp::Arrow, Stamp::Star, Stamp::Checkmark],
+        }
+    }
+}
+
+impl StampLibrary {
+    pub fn stamps(&self) -> &[Stamp] {
+        &self.stamps
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Stamp> {
+        self.stamps.get(index)
+    }
+
+    /// Add a custom stamp from a Svg string.
+    ///
+    /// Returns the index of the new stamp.
+    pub fn add_svg(&mut self, name: String, svg_data: &str) -> anyhow::Result<usize> {
+        self.stamps.push(Stamp::from_svg_str(name, svg_data)?);
+        Ok(self.stamps.len() - 1)
+    }
+
+    /// Remove the stamp at the index.
+    pub fn remove(&mut self, index: usize) -> Option<Stamp> {
+        (index < self.stamps.len()).then(|| self.stamps.remove(index))
+    }
+}
@@ -2,8 +2,10 @@
 use crate::Drawable;
 use crate::Svg;
 use crate::document::Background;
+use crate::store::chrono_comp::StrokeLayer;
 use crate::strokes::Stroke;
 use p2d::bounding_volume::{Aabb, BoundingVolume};
+use rnote_compose::Color;
 use rnote_compose::shapes::Shapeable;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -17,6 +19,18 @@ use tracing::warn;
 pub struct StrokeContent {
     #[serde(rename = "strokes")]
     pub strokes: Vec<Arc<Stroke>>,
+    /// The layers of the strokes, in the same order as the strokes.
+    ///
+    /// Might be empty for content that was created by older versions,
+    /// then the default layer of the strokes is used.
+    #[serde(rename = "stroke_layers")]
+    pub stroke_layers: Vec<StrokeLayer>,
+    /// The ids of the groups of the strokes, in the same order as the strokes.
+    ///
+    /// The ids are only meaningful within the content, the strokes are put into new groups when they are inserted.
+    /// Might be empty for content that was created by older versions, then the strokes are not grouped.
+    #[serde(rename = "stroke_groups")]
+    pub stroke_groups: Vec<Vec<u32>>,
     #[serde(rename = "bounds")]
     pub bounds: Option<Aabb>,
     #[serde(rename = "background")]
@@ -37,11 +51,39 @@ impl StrokeContent {
         self
     }
 
+    pub fn with_stroke_layers(mut self, stroke_layers: Vec<StrokeLayer>) -> Self {
+        self.stroke_layers = stroke_layers;
+        self
+    }
+
+    pub fn with_stroke_groups(mut self, stroke_groups: Vec<Vec<u32>>) -> Self {
+        self.stroke_groups = stroke_groups;
+        self
+    }
+
     pub fn with_background(mut self, background: Option<Background>) -> Self {
         self.background = background;
         self
     }
 
+    /// Makes the background color fully transparent, keeping the pattern.
+    pub fn set_background_transparent(&mut self) {
+        if let Some(background) = self.background.as_mut() {
+            background.color = Color::TRANSPARENT;
+        }
+    }
+
+    /// Inverts the brightness of the background and stroke colors, turning dark pages into light ones and vice versa.
+    pub fn invert_colors(&mut self) {
+        if let Some(background) = self.background.as_mut() {
+            background.color = background.color.to_inverted_brightness_color();
+            background.pattern_color = background.pattern_color.to_inverted_brightness_color();
+        }
+        for stroke in self.strokes.iter_mut() {
+            Arc::make_mut(stroke).set_to_inverted_brightness_color();
+        }
+    }
+
     pub fn bounds(&self) -> Option<Aabb> {
         if self.bounds.is_some() {
             return self.bounds;
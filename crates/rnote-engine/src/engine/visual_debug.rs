@@ -196,6 +196,52 @@ pub(crate) fn draw_statistics_to_gtk_snapshot(
     Ok(())
 }
 
+/// Draw the render diagnostics as overlay.
+///
+/// Expects that the snapshot is untransformed in surface coordinate space.
+#[cfg(feature = "ui")]
+pub(crate) fn draw_render_diagnostics_to_gtk_snapshot(
+    snapshot: &gtk4::Snapshot,
+    render_diagnostics: &crate::engine::RenderDiagnostics,
+    surface_bounds: p2d::bounding_volume::Aabb,
+) -> anyhow::Result<()> {
+    use crate::ext::GrapheneRectExt;
+    use gtk4::{graphene, prelude::*};
+    use p2d::bounding_volume::Aabb;
+    use piet::{RenderContext, Text, TextLayout, TextLayoutBuilder};
+    use rnote_compose::ext::{AabbExt, Vector2Ext};
+
+    let mut piet_text = piet_cairo::CairoText::new();
+    let text_layout = piet_text
+        .new_text_layout(render_diagnostics.to_string())
+        .text_color(piet::Color::rgba(0.8, 1.0, 1.0, 1.0))
+        .font(piet::FontFamily::MONOSPACE, 10.0)
+        .build()
+        .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    let text_size = text_layout.size();
+
+    let text_bounds = Aabb::new(
+        na::point![
+            surface_bounds.maxs[0] - text_size.width - 40.0,
+            surface_bounds.maxs[1] - text_size.height - 40.0
+        ],
+        na::point![surface_bounds.maxs[0] - 20.0, surface_bounds.maxs[1] - 20.0],
+    );
+    let cairo_cx = snapshot.append_cairo(&graphene::Rect::from_p2d_aabb(text_bounds));
+    let mut piet_cx = piet_cairo::CairoRenderContext::new(&cairo_cx);
+
+    piet_cx.fill(
+        text_bounds.to_kurbo_rect(),
+        &piet::Color::rgba(0.1, 0.1, 0.1, 0.8),
+    );
+    piet_cx.draw_text(
+        &text_layout,
+        (text_bounds.mins.coords + na::vector![10.0, 10.0]).to_kurbo_point(),
+    );
+    piet_cx.finish().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    Ok(())
+}
+
 /// Draw stroke bounds, positions, etc. for visual debugging purposes.
 #[cfg(feature = "ui")]
 pub(crate) fn draw_stroke_debug_to_gtk_snapshot(
@@ -99,6 +99,41 @@ impl GrapheneRectExt for gtk4::graphene::Rect {
     }
 }
 
+/// Extension trait for [gtk4::gsk::Path].
+#[cfg(feature = "ui")]
+pub trait GskPathExt
+where
+    Self: Sized,
+{
+    fn from_kurbo_bezpath(bez_path: &kurbo::BezPath) -> Self;
+}
+
+#[cfg(feature = "ui")]
+impl GskPathExt for gtk4::gsk::Path {
+    fn from_kurbo_bezpath(bez_path: &kurbo::BezPath) -> Self {
+        let builder = gtk4::gsk::PathBuilder::new();
+        for el in bez_path.elements() {
+            match *el {
+                kurbo::PathEl::MoveTo(p) => builder.move_to(p.x as f32, p.y as f32),
+                kurbo::PathEl::LineTo(p) => builder.line_to(p.x as f32, p.y as f32),
+                kurbo::PathEl::QuadTo(p1, p2) => {
+                    builder.quad_to(p1.x as f32, p1.y as f32, p2.x as f32, p2.y as f32)
+                }
+                kurbo::PathEl::CurveTo(p1, p2, p3) => builder.cubic_to(
+                    p1.x as f32,
+                    p1.y as f32,
+                    p2.x as f32,
+                    p2.y as f32,
+                    p3.x as f32,
+                    p3.y as f32,
+                ),
+                kurbo::PathEl::ClosePath => builder.close(),
+            }
+        }
+        builder.to_path()
+    }
+}
+
 pub trait EventPropagationExt {
     fn into_glib(self) -> glib::Propagation;
     fn from_glib(value: glib::Propagation) -> Self;
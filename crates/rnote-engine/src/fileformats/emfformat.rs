@@ -0,0 +1,540 @@
+// Imports
+use super::FileFormatSaver;
+use crate::Svg;
+use anyhow::Context;
+use std::sync::Arc;
+use tracing::warn;
+use usvg::tiny_skia_path::{self, PathSegment};
+
+/// An Enhanced Metafile (EMF), converted from a Svg.
+///
+/// Paths are written as Gdi vector paths, raster images as device independent bitmaps.
+/// Gdi does not support transparency, so translucent colors are blended against white.
+///
+/// The older Windows Metafile (WMF) format is not supported. It has no bezier curves and only 16 bit coordinates,
+/// and Office applications accept EMF everywhere they accept WMF.
+#[derive(Debug, Clone)]
+pub struct EmfFile {
+    /// The width and height of the metafile in Svg user units.
+    size: na::Vector2<f64>,
+    /// The resolution of the Svg user units.
+    dpi: f64,
+    /// The encoded records, excluding the header and the end-of-file record.
+    records: Vec<u8>,
+    /// The amount of records, excluding the header and the end-of-file record.
+    n_records: u32,
+}
+
+/// Emf record types.
+mod record_type {
+    pub(super) const HEADER: u32 = 1;
+    pub(super) const POLYBEZIERTO: u32 = 5;
+    pub(super) const EOF: u32 = 14;
+    pub(super) const SETBKMODE: u32 = 18;
+    pub(super) const SETPOLYFILLMODE: u32 = 19;
+    pub(super) const MOVETOEX: u32 = 27;
+    pub(super) const SELECTOBJECT: u32 = 37;
+    pub(super) const CREATEBRUSHINDIRECT: u32 = 39;
+    pub(super) const DELETEOBJECT: u32 = 40;
+    pub(super) const LINETO: u32 = 54;
+    pub(super) const BEGINPATH: u32 = 59;
+    pub(super) const ENDPATH: u32 = 60;
+    pub(super) const CLOSEFIGURE: u32 = 61;
+    pub(super) const FILLPATH: u32 = 62;
+    pub(super) const STROKEPATH: u32 = 64;
+    pub(super) const STRETCHDIBITS: u32 = 81;
+    pub(super) const EXTCREATEPEN: u32 = 95;
+}
+
+impl EmfFile {
+    /// The amount of logical units per Svg user unit, for sub-pixel precision of the integer coordinates.
+    const SUBDIVISIONS: f64 = 16.0;
+    /// The handle index of the created pen and brush. Every object is deleted right after it was used.
+    const OBJECT_HANDLE: u32 = 1;
+    const STOCK_NULL_BRUSH: u32 = 0x8000_0005;
+    const STOCK_NULL_PEN: u32 = 0x8000_0008;
+
+    /// Converts the Svg to an Emf.
+    ///
+    /// `dpi` is the resolution of the Svg user units, used to determine the physical size of the metafile.
+    pub fn from_svg(svg: &Svg, dpi: f64) -> anyhow::Result<Self> {
+        let size = na::Vector2::from(svg.bounds.extents());
+        if size[0] <= 0.0 || size[1] <= 0.0 {
+            return Err(anyhow::anyhow!(
+                "Converting Svg to Emf failed, Svg has invalid bounds {:?}.",
+                svg.bounds
+            ));
+        }
+        let svg_data = rnote_compose::utils::wrap_svg_root(
+            &rnote_compose::utils::remove_xml_header(&svg.svg_data),
+            Some(p2d::bounding_volume::Aabb::new(
+                na::point![0.0, 0.0],
+                size.into(),
+            )),
+            Some(svg.bounds),
+            false,
+        );
+        let tree = usvg::Tree::from_str(
+            &svg_data,
+            &usvg::Options {
+                fontdb: Arc::clone(&crate::svg::USVG_FONTDB),
+                ..Default::default()
+            },
+        )
+        .context("Parsing Svg failed.")?;
+
+        let mut emf = Self {
+            size,
+            dpi,
+            records: Vec::new(),
+            n_records: 0,
+        };
+        // Transparent background mode
+        emf.push_record(record_type::SETBKMODE, &[1]);
+        emf.push_group(tree.root(), 1.0)?;
+        Ok(emf)
+    }
+
+    fn push_group(&mut self, group: &usvg::Group, opacity: f32) -> anyhow::Result<()> {
+        let opacity = opacity * group.opacity().get();
+        for node in group.children() {
+            match node {
+                usvg::Node::Group(group) => self.push_group(group, opacity)?,
+                usvg::Node::Path(path) => self.push_path(path, opacity),
+                usvg::Node::Image(image) => self.push_image(image)?,
+                usvg::Node::Text(text) => self.push_group(text.flattened(), opacity)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn push_path(&mut self, path: &usvg::Path, opacity: f32) {
+        if !path.is_visible() {
+            return;
+        }
+        let transform = path.abs_transform();
+        let Some(data) = path.data().clone().transform(transform) else {
+            return;
+        };
+
+        if let Some(fill) = path.fill()
+            && let usvg::Paint::Color(color) = fill.paint()
+        {
+            let fill_mode = match fill.rule() {
+                usvg::FillRule::EvenOdd => 1,
+                usvg::FillRule::NonZero => 2,
+            };
+            self.push_record(record_type::SETPOLYFILLMODE, &[fill_mode]);
+            self.push_record(
+                record_type::CREATEBRUSHINDIRECT,
+                &[
+                    Self::OBJECT_HANDLE,
+                    // solid brush style
+                    0,
+                    Self::colorref(*color, fill.opacity().get() * opacity),
+                    0,
+                ],
+            );
+            self.push_record(record_type::SELECTOBJECT, &[Self::OBJECT_HANDLE]);
+            self.push_record(record_type::SELECTOBJECT, &[Self::STOCK_NULL_PEN]);
+            self.push_path_data(&data);
+            self.push_record(record_type::FILLPATH, &self.bounds_rect());
+            self.push_record(record_type::SELECTOBJECT, &[Self::STOCK_NULL_BRUSH]);
+            self.push_record(record_type::DELETEOBJECT, &[Self::OBJECT_HANDLE]);
+        }
+
+        if let Some(stroke) = path.stroke()
+            && let usvg::Paint::Color(color) = stroke.paint()
+        {
+            // non-uniform scaling can't be represented by the pen width, so the average is used
+            let transform_scale = (transform.sx * transform.sy - transform.kx * transform.ky)
+                .abs()
+                .sqrt();
+            let width = (f64::from(stroke.width().get() * transform_scale) * Self::SUBDIVISIONS)
+                .round()
+                .max(1.0) as u32;
+            let end_cap = match stroke.linecap() {
+                usvg::LineCap::Round => 0x0000_0000,
+                usvg::LineCap::Square => 0x0000_0100,
+                usvg::LineCap::Butt => 0x0000_0200,
+            };
+            let join = match stroke.linejoin() {
+                usvg::LineJoin::Round => 0x0000_0000,
+                usvg::LineJoin::Bevel => 0x0000_1000,
+                usvg::LineJoin::Miter | usvg::LineJoin::MiterClip => 0x0000_2000,
+            };
+            // geometric solid pen
+            let pen_style = 0x0001_0000 | end_cap | join;
+            self.push_record(
+                record_type::EXTCREATEPEN,
+                &[
+                    Self::OBJECT_HANDLE,
+                    // no bitmap for the brush of the pen
+                    0,
+                    0,
+                    0,
+                    0,
+                    pen_style,
+                    width,
+                    // solid brush style
+                    0,
+                    Self::colorref(*color, stroke.opacity().get() * opacity),
+                    0,
+                    // no custom style entries
+                    0,
+                ],
+            );
+            self.push_record(record_type::SELECTOBJECT, &[Self::OBJECT_HANDLE]);
+            self.push_record(record_type::SELECTOBJECT, &[Self::STOCK_NULL_BRUSH]);
+            self.push_path_data(&data);
+            self.push_record(record_type::STROKEPATH, &self.bounds_rect());
+            self.push_record(record_type::SELECTOBJECT, &[Self::STOCK_NULL_PEN]);
+            self.push_record(record_type::DELETEOBJECT, &[Self::OBJECT_HANDLE]);
+        }
+    }
+
+    fn push_path_data(&mut self, data: &tiny_skia_path::Path) {
+        self.push_record(record_type::BEGINPATH, &[]);
+        let mut current = tiny_skia_path::Point::zero();
+        for segment in data.segments() {
+            match segment {
+                PathSegment::MoveTo(p) => {
+                    self.push_record(record_type::MOVETOEX, &self.point(p));
+                    current = p;
+                }
+                PathSegment::LineTo(p) => {
+                    self.push_record(record_type::LINETO, &self.point(p));
+                    current = p;
+                }
+                PathSegment::QuadTo(q, p) => {
+                    // elevate to a cubic bezier
+                    let c1 = tiny_skia_path::Point::from_xy(
+                        current.x + 2.0 / 3.0 * (q.x - current.x),
+                        current.y + 2.0 / 3.0 * (q.y - current.y),
+                    );
+                    let c2 = tiny_skia_path::Point::from_xy(
+                        p.x + 2.0 / 3.0 * (q.x - p.x),
+                        p.y + 2.0 / 3.0 * (q.y - p.y),
+                    );
+                    self.push_bezier_to(c1, c2, p);
+                    current = p;
+                }
+                PathSegment::CubicTo(c1, c2, p) => {
+                    self.push_bezier_to(c1, c2, p);
+                    current = p;
+                }
+                PathSegment::Close => self.push_record(record_type::CLOSEFIGURE, &[]),
+            }
+        }
+        self.push_record(record_type::ENDPATH, &[]);
+    }
+
+    fn push_bezier_to(
+        &mut self,
+        c1: tiny_skia_path::Point,
+        c2: tiny_skia_path::Point,
+        p: tiny_skia_path::Point,
+    ) {
+        let mut values = self.bounds_rect().to_vec();
+        values.push(3);
+        values.extend(self.point(c1));
+        values.extend(self.point(c2));
+        values.extend(self.point(p));
+        self.push_record(record_type::POLYBEZIERTO, &values);
+    }
+
+    fn push_image(&mut self, image: &usvg::Image) -> anyhow::Result<()> {
+        if !image.is_visible() {
+            return Ok(());
+        }
+        let encoded = match image.kind() {
+            usvg::ImageKind::PNG(data)
+            | usvg::ImageKind::JPEG(data)
+            | usvg::ImageKind::GIF(data)
+            | usvg::ImageKind::WEBP(data) => data,
+            usvg::ImageKind::SVG(tree) => return self.push_group(tree.root(), 1.0),
+        };
+        let bitmap = match image::load_from_memory(encoded) {
+            Ok(bitmap) => bitmap.into_rgba8(),
+            Err(e) => {
+                warn!("Decoding image failed while converting Svg to Emf, skipping it. Err: {e:?}");
+                return Ok(());
+            }
+        };
+        let (width, height) = bitmap.dimensions();
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+
+        // Rotations and skews can't be represented, so the image is drawn into its transformed bounds
+        let Some(rect) =
+            tiny_skia_path::Rect::from_xywh(0.0, 0.0, image.size().width(), image.size().height())
+                .and_then(|rect| rect.transform(image.abs_transform()))
+        else {
+            return Ok(());
+        };
+        let dest_pos = self.point(tiny_skia_path::Point::from_xy(rect.left(), rect.top()));
+        let dest_size = [
+            (f64::from(rect.width()) * Self::SUBDIVISIONS).round() as u32,
+            (f64::from(rect.height()) * Self::SUBDIVISIONS).round() as u32,
+        ];
+
+        // bottom-up BGRA rows, blended against white
+        let mut bits = Vec::with_capacity(width as usize * height as usize * 4);
+        for row in bitmap.rows().rev() {
+            for pixel in row {
+                let [r, g, b, a] = pixel.0;
+                let blend = |c: u8| {
+                    ((u32::from(c) * u32::from(a) + 255 * (255 - u32::from(a))) / 255) as u8
+                };
+                bits.extend_from_slice(&[blend(b), blend(g), blend(r), 0]);
+            }
+        }
+        const RECORD_FIXED_SIZE: u32 = 80;
+        const BITMAPINFOHEADER_SIZE: u32 = 40;
+
+        let mut values = self.bounds_rect().to_vec();
+        values.extend([
+            dest_pos[0],
+            dest_pos[1],
+            // source position and size
+            0,
+            0,
+            width,
+            height,
+            // offset and size of the bitmap info and bits
+            RECORD_FIXED_SIZE,
+            BITMAPINFOHEADER_SIZE,
+            RECORD_FIXED_SIZE + BITMAPINFOHEADER_SIZE,
+            bits.len() as u32,
+            // DIB_RGB_COLORS
+            0,
+            // SRCCOPY
+            0x00CC_0020,
+            dest_size[0],
+            dest_size[1],
+            // BITMAPINFOHEADER
+            BITMAPINFOHEADER_SIZE,
+            width,
+            height,
+            // one plane, 32 bits per pixel
+            1 | (32 << 16),
+            // BI_RGB
+            0,
+            bits.len() as u32,
+            0,
+            0,
+            0,
+            0,
+        ]);
+        let mut data = values
+            .into_iter()
+            .flat_map(u32::to_le_bytes)
+            .collect::<Vec<u8>>();
+        data.extend(bits);
+        self.push_record_bytes(record_type::STRETCHDIBITS, &data);
+        Ok(())
+    }
+
+    /// The bounds of the entire metafile in logical units, used for records that require a bounding rectangle.
+    fn bounds_rect(&self) -> [u32; 4] {
+        let device_size = self.device_size();
+        [
+            0,
+            0,
+            device_size[0].saturating_sub(1),
+            device_size[1].saturating_sub(1),
+        ]
+    }
+
+    fn device_size(&self) -> [u32; 2] {
+        [
+            (self.size[0] * Self::SUBDIVISIONS).ceil() as u32,
+            (self.size[1] * Self::SUBDIVISIONS).ceil() as u32,
+        ]
+    }
+
+    /// Converts the point to logical coordinates. Signed coordinates are written as their two's complement.
+    fn point(&self, p: tiny_skia_path::Point) -> [u32; 2] {
+        [
+            (f64::from(p.x) * Self::SUBDIVISIONS).round() as i32 as u32,
+            (f64::from(p.y) * Self::SUBDIVISIONS).round() as i32 as u32,
+        ]
+    }
+
+    /// A Gdi color reference, with the alpha blended against white.
+    fn colorref(color: usvg::Color, alpha: f32) -> u32 {
+        let alpha = alpha.clamp(0.0, 1.0);
+        let blend = |c: u8| (f32::from(c) * alpha + 255.0 * (1.0 - alpha)).round() as u32;
+        blend(color.red) | (blend(color.green) << 8) | (blend(color.blue) << 16)
+    }
+
+    fn push_record(&mut self, record_type: u32, values: &[u32]) {
+        let data = values
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<u8>>();
+        self.push_record_bytes(record_type, &data);
+    }
+
+    fn push_record_bytes(&mut self, record_type: u32, data: &[u8]) {
+        // records are aligned to 4 bytes
+        let padding = (4 - data.len() % 4) % 4;
+        let size = (8 + data.len() + padding) as u32;
+        self.records.extend(record_type.to_le_bytes());
+        self.records.extend(size.to_le_bytes());
+        self.records.extend(data);
+        self.records.extend(std::iter::repeat_n(0, padding));
+        self.n_records += 1;
+    }
+}
+
+impl FileFormatSaver for EmfFile {
+    fn save_as_bytes(&self, _file_name: &str) -> anyhow::Result<Vec<u8>> {
+        const HEADER_SIZE: u32 = 108;
+        const EOF_SIZE: u32 = 20;
+
+        let device_size = self.device_size();
+        let size_mm = self.size / self.dpi.max(1.0) * 25.4;
+        let bytes_len = HEADER_SIZE + self.records.len() as u32 + EOF_SIZE;
+
+        let header = [
+            record_type::HEADER,
+            HEADER_SIZE,
+            // bounds in logical units
+            0,
+            0,
+            device_size[0].saturating_sub(1),
+            device_size[1].saturating_sub(1),
+            // frame in 0.01 mm
+            0,
+            0,
+            (size_mm[0] * 100.0).round() as u32,
+            (size_mm[1] * 100.0).round() as u32,
+            // " EMF" signature
+            0x464D_4520,
+            // version
+            0x0001_0000,
+            bytes_len,
+            // including the header and end-of-file record
+            self.n_records + 2,
+            // one handle for the pen or brush in addition to the reserved index zero, no description
+            2,
+            0,
+            0,
+            // palette entries
+            0,
+            // the reference device size in pixels and millimeters
+            device_size[0],
+            device_size[1],
+            (size_mm[0].round() as u32).max(1),
+            (size_mm[1].round() as u32).max(1),
+            // no pixel format, no OpenGL
+            0,
+            0,
+            0,
+            // the reference device size in micrometers
+            (size_mm[0] * 1000.0).round() as u32,
+            (size_mm[1] * 1000.0).round() as u32,
+        ];
+        let eof = [
+            record_type::EOF,
+            EOF_SIZE,
+            // no palette entries, the offset to them and the size of the record are repeated
+            0,
+            16,
+            EOF_SIZE,
+        ];
+
+        let mut bytes = Vec::with_capacity(bytes_len as usize);
+        bytes.extend(header.into_iter().flat_map(u32::to_le_bytes));
+        bytes.extend(&self.records);
+        bytes.extend(eof.into_iter().flat_map(u32::to_le_bytes));
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p2d::bounding_volume::Aabb;
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    /// Walks the record stream and returns the types and offsets of the records.
+    fn records(bytes: &[u8]) -> Vec<(u32, usize)> {
+        let mut records = vec![];
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let size = u32_at(bytes, offset + 4) as usize;
+            assert!(size >= 8 && size.is_multiple_of(4) && offset + size <= bytes.len());
+            records.push((u32_at(bytes, offset), offset));
+            offset += size;
+        }
+        records
+    }
+
+    #[test]
+    fn emf_header_and_records() {
+        let svg = Svg {
+            svg_data: String::from(
+                r##"<rect x="20" y="30" width="40" height="20" fill="#ff0000" stroke="#000000" stroke-width="2"/>"##,
+            ),
+            bounds: Aabb::new(na::point![10.0, 20.0], na::point![110.0, 70.0]),
+        };
+        let bytes = EmfFile::from_svg(&svg, 96.0)
+            .unwrap()
+            .save_as_bytes("")
+            .unwrap();
+
+        assert_eq!(u32_at(&bytes, 0), record_type::HEADER);
+        // The bounds in logical units, 16 per Svg user unit
+        assert_eq!(
+            [8, 12, 16, 20].map(|offset| u32_at(&bytes, offset)),
+            [0, 0, 1599, 799]
+        );
+        // The frame in 0.01 mm, 100 x 50 user units at 96 dpi
+        assert_eq!(
+            [24, 28, 32, 36].map(|offset| u32_at(&bytes, offset)),
+            [0, 0, 2646, 1323]
+        );
+        // The " EMF" signature and the size of the file
+        assert_eq!(u32_at(&bytes, 40), 0x464D_4520);
+        assert_eq!(u32_at(&bytes, 48) as usize, bytes.len());
+
+        let records = records(&bytes);
+        let types = records.iter().map(|(t, _)| *t).collect::<Vec<u32>>();
+        assert_eq!(types.len(), u32_at(&bytes, 52) as usize);
+        assert_eq!(types.first(), Some(&record_type::HEADER));
+        assert_eq!(types.last(), Some(&record_type::EOF));
+        // The rectangle is filled and then stroked, both times as a closed path with four corners
+        let count = |record_type| types.iter().filter(|&&t| t == record_type).count();
+        assert_eq!(count(record_type::FILLPATH), 1);
+        assert_eq!(count(record_type::STROKEPATH), 1);
+        assert_eq!(count(record_type::MOVETOEX), 2);
+        assert_eq!(count(record_type::LINETO), 6);
+        assert_eq!(count(record_type::CLOSEFIGURE), 2);
+        assert_eq!(types.len(), 30);
+
+        // The path starts at the top left corner of the rectangle, relative to the bounds
+        let (_, move_to) = records
+            .iter()
+            .find(|(t, _)| *t == record_type::MOVETOEX)
+            .unwrap();
+        assert_eq!(
+            [u32_at(&bytes, move_to + 8), u32_at(&bytes, move_to + 12)],
+            [160, 160]
+        );
+    }
+
+    #[test]
+    fn emf_invalid_bounds() {
+        let svg = Svg {
+            svg_data: String::new(),
+            bounds: Aabb::new(na::point![10.0, 20.0], na::point![10.0, 70.0]),
+        };
+        assert!(EmfFile::from_svg(&svg, 96.0).is_err());
+    }
+}
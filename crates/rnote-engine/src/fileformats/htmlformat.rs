@@ -0,0 +1,497 @@
+// Imports
+use crate::strokes::textstroke::{FontStyle, RangedTextAttribute, TextAttribute};
+use rnote_compose::Color;
+
+/// Formatted text, converted from a Html fragment.
+///
+/// Only the basic formatting is retained: bold, italic, underline, strikethrough, headings, lists, links and tables,
+/// of which the cells are separated by tabs.
+#[derive(Debug, Clone, Default)]
+pub struct HtmlRichText {
+    /// The plain text.
+    pub text: String,
+    /// The text attributes, the ranges are byte indices into the text.
+    pub ranged_text_attributes: Vec<RangedTextAttribute>,
+}
+
+/// An element that is currently open while parsing.
+#[derive(Debug, Clone)]
+struct OpenElement {
+    tag: String,
+    start: usize,
+    attributes: Vec<TextAttribute>,
+    href: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ListKind {
+    Unordered,
+    Ordered(usize),
+}
+
+impl HtmlRichText {
+    /// The color of links.
+    pub const LINK_COLOR: Color = Color {
+        r: 0.1,
+        g: 0.35,
+        b: 0.8,
+        a: 1.0,
+    };
+    /// The font weight of bold text.
+    const BOLD_WEIGHT: u16 = 700;
+    /// Content of these elements is never displayed.
+    const SKIPPED_ELEMENTS: [&'static str; 5] = ["head", "style", "script", "title", "template"];
+    /// Elements that can't have content and don't have a closing tag.
+    const VOID_ELEMENTS: [&'static str; 8] =
+        ["br", "hr", "img", "meta", "link", "input", "col", "wbr"];
+    /// Elements that start and end on their own line.
+    const BLOCK_ELEMENTS: [&'static str; 17] = [
+        "p",
+        "div",
+        "h1",
+        "h2",
+        "h3",
+        "h4",
+        "h5",
+        "h6",
+        "ul",
+        "ol",
+        "li",
+        "blockquote",
+        "pre",
+        "table",
+        "tr",
+        "section",
+        "article",
+    ];
+
+    /// Converts a Html fragment, as it is for example put into the clipboard by browsers and office suites.
+    ///
+    /// Heading sizes are calculated relative to the base font size.
+    pub fn from_html(html: &str, base_font_size: f64) -> Self {
+        // Only the actual fragment is of interest, if it is marked
+        let html = match (
+            html.find("<!--StartFragment-->"),
+            html.find("<!--EndFragment-->"),
+        ) {
+            (Some(start), Some(end)) if start < end => {
+                &html[start + "<!--StartFragment-->".len()..end]
+            }
+            _ => html,
+        };
+
+        let mut rich_text = Self::default();
+        let mut open_elements: Vec<OpenElement> = Vec::new();
+        let mut lists: Vec<ListKind> = Vec::new();
+        let mut skip_depth: usize = 0;
+        let mut pre_depth: usize = 0;
+        let mut rest = html;
+
+        while !rest.is_empty() {
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                rest = comment
+                    .find("-->")
+                    .map(|end| &comment[end + 3..])
+                    .unwrap_or_default();
+                continue;
+            }
+            if rest.starts_with('<') {
+                let Some(end) = rest.find('>') else {
+                    break;
+                };
+                let tag_content = &rest[1..end];
+                rest = &rest[end + 1..];
+                if tag_content.starts_with('!') || tag_content.starts_with('?') {
+                    continue;
+                }
+
+                let closing = tag_content.starts_with('/');
+                let tag_content = tag_content.trim_start_matches('/');
+                let self_closing = tag_content.ends_with('/');
+                let tag_name = tag_content
+                    .split(|c: char| c.is_whitespace() || c == '/')
+                    .next()
+                    .unwrap_or_default()
+                    .to_lowercase();
+                let tag = tag_name.as_str();
+
+                if Self::SKIPPED_ELEMENTS.contains(&tag) {
+                    if closing {
+                        skip_depth = skip_depth.saturating_sub(1);
+                    } else if !self_closing {
+                        skip_depth += 1;
+                    }
+                    continue;
+                }
+                if skip_depth > 0 {
+                    continue;
+                }
+
+                if closing {
+                    let Some(pos) = open_elements.iter().rposition(|e| e.tag == tag) else {
+                        continue;
+                    };
+                    // Elements that were not closed explicitly are closed together with their parent
+                    for element in open_elements.drain(pos..).rev().collect::<Vec<_>>() {
+                        rich_text.close_element(element, &mut lists, &mut pre_depth);
+                    }
+                    continue;
+                }
+
+                if tag == "br" {
+                    rich_text.text.push('\n');
+                    continue;
+                }
+                if Self::BLOCK_ELEMENTS.contains(&tag) {
+                    rich_text.start_new_line();
+                }
+                if Self::VOID_ELEMENTS.contains(&tag) || self_closing {
+                    continue;
+                }
+
+                if tag == "td" || tag == "th" {
+                    rich_text.start_new_cell();
+                }
+
+                let style = attribute_value(tag_content, "style").unwrap_or_default();
+                let mut attributes = Vec::new();
+                match tag {
+                    "b" | "strong" | "th" => {
+                        attributes.push(TextAttribute::FontWeight(Self::BOLD_WEIGHT))
+                    }
+                    "i" | "em" | "cite" => attributes.push(TextAttribute::Style(FontStyle::Italic)),
+                    "u" | "ins" => attributes.push(TextAttribute::Underline(true)),
+                    "s" | "strike" | "del" => attributes.push(TextAttribute::Strikethrough(true)),
+                    "a" => attributes.extend([
+                        TextAttribute::Underline(true),
+                        TextAttribute::TextColor(Self::LINK_COLOR),
+                    ]),
+                    "ul" => lists.push(ListKind::Unordered),
+                    "ol" => lists.push(ListKind::Ordered(0)),
+                    "pre" => pre_depth += 1,
+                    "li" => {
+                        let indent = "    ".repeat(lists.len().saturating_sub(1));
+                        let marker = match lists.last_mut() {
+                            Some(ListKind::Ordered(n)) => {
+                                *n += 1;
+                                format!("{n}. ")
+                            }
+                            _ => String::from("• "),
+                        };
+                        rich_text.text.push_str(&indent);
+                        rich_text.text.push_str(&marker);
+                    }
+                    _ => {}
+                }
+                if let Some(level) = tag
+                    .strip_prefix('h')
+                    .and_then(|l| l.parse::<usize>().ok())
+                    .filter(|l| (1..=6).contains(l))
+                {
+                    const HEADING_SCALES: [f64; 6] = [2.0, 1.5, 1.25, 1.1, 1.0, 0.9];
+                    attributes.extend([
+                        TextAttribute::FontSize(base_font_size * HEADING_SCALES[level - 1]),
+                        TextAttribute::FontWeight(Self::BOLD_WEIGHT),
+                    ]);
+                }
+                attributes = apply_inline_style(attributes, &style);
+
+                open_elements.push(OpenElement {
+                    start: rich_text.text.len(),
+                    href: (tag == "a")
+                        .then(|| attribute_value(tag_content, "href"))
+                        .flatten(),
+                    tag: tag_name,
+                    attributes,
+                });
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                let text = decode_entities(&rest[..end]);
+                rest = &rest[end..];
+                if skip_depth > 0 {
+                    continue;
+                }
+                if pre_depth > 0 {
+                    rich_text.text.push_str(&text);
+                } else {
+                    rich_text.push_collapsed_whitespace(&text);
+                }
+            }
+        }
+        for element in open_elements.into_iter().rev() {
+            rich_text.close_element(element, &mut lists, &mut pre_depth);
+        }
+
+        // Remove trailing whitespace, the ranges are clamped accordingly
+        let trimmed_len = rich_text.text.trim_end().len();
+        rich_text.text.truncate(trimmed_len);
+        rich_text.ranged_text_attributes.retain_mut(|attr| {
+            attr.range.end = attr.range.end.min(trimmed_len);
+            !attr.range.is_empty()
+        });
+        rich_text
+    }
+
+    fn close_element(
+        &mut self,
+        element: OpenElement,
+        lists: &mut Vec<ListKind>,
+        pre_depth: &mut usize,
+    ) {
+        let range = element.start..self.text.len();
+        match element.tag.as_str() {
+            "ul" | "ol" => {
+                lists.pop();
+            }
+            "pre" => *pre_depth = pre_depth.saturating_sub(1),
+            "a" => {
+                // Keep the link target, if it is not already visible
+                if let Some(href) = element.href
+                    && !href.is_empty()
+                    && self.text[element.start..].trim() != href
+                {
+                    self.text.push_str(&format!(" ({href})"));
+                }
+            }
+            _ => {}
+        }
+        if !range.is_empty() {
+            self.ranged_text_attributes
+                .extend(
+                    element
+                        .attributes
+                        .into_iter()
+                        .map(|attribute| RangedTextAttribute {
+                            range: range.clone(),
+                            attribute,
+                        }),
+                );
+        }
+        if Self::BLOCK_ELEMENTS.contains(&element.tag.as_str()) {
+            self.start_new_line();
+        }
+    }
+
+    fn start_new_line(&mut self) {
+        // Remove trailing spaces of the current line
+        let trimmed_len = self.text.trim_end_matches(' ').len();
+        self.text.truncate(trimmed_len);
+        if !self.text.is_empty() && !self.text.ends_with('\n') {
+            self.text.push('\n');
+        }
+    }
+
+    /// Cells after the first one of a table row are separated by a tab.
+    fn start_new_cell(&mut self) {
+        let trimmed_len = self.text.trim_end_matches(' ').len();
+        self.text.truncate(trimmed_len);
+        if !self.text.is_empty() && !self.text.ends_with('\n') {
+            self.text.push('\t');
+        }
+    }
+
+    /// Pushes the text with whitespace collapsed the way it is displayed in Html.
+    fn push_collapsed_whitespace(&mut self, text: &str) {
+        for c in text.chars() {
+            if c.is_whitespace() && c != '\u{a0}' {
+                if !self.text.is_empty() && !self.text.ends_with([' ', '\t', '\n']) {
+                    self.text.push(' ');
+                }
+            } else {
+                self.text.push(c);
+            }
+        }
+    }
+}
+
+/// Get the value of a tag attribute.
+fn attribute_value(tag_content: &str, name: &str) -> Option<String> {
+    let lowercase = tag_content.to_ascii_lowercase();
+    let mut search_start = 0;
+    while let Some(found) = lowercase[search_start..].find(name) {
+        let start = search_start + found;
+        search_start = start + name.len();
+        // Must be a complete attribute name
+        if !lowercase[..start].ends_with(char::is_whitespace) {
+            continue;
+        }
+        let after = tag_content[start + name.len()..].trim_start();
+        let Some(value) = after.strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let value = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or_default(),
+            _ => value.split_whitespace().next().unwrap_or_default(),
+        };
+        return Some(decode_entities(value));
+    }
+    None
+}
+
+/// Applies the inline css declarations that map to text attributes.
+///
+/// They take precedence over the attributes implied by the element, e.g. office suites wrap content into
+/// `<b style="font-weight:normal">`.
+fn apply_inline_style(mut attributes: Vec<TextAttribute>, style: &str) -> Vec<TextAttribute> {
+    for declaration in style.split(';') {
+        let Some((property, value)) = declaration.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().to_lowercase();
+        let new_attribute = match property.trim().to_lowercase().as_str() {
+            "font-weight" => {
+                let weight = match value.as_str() {
+                    "bold" | "bolder" => HtmlRichText::BOLD_WEIGHT,
+                    "normal" | "lighter" => 400,
+                    v => v.parse::<u16>().unwrap_or(400),
+                };
+                TextAttribute::FontWeight(weight)
+            }
+            "font-style" => TextAttribute::Style(if value == "italic" || value == "oblique" {
+                FontStyle::Italic
+            } else {
+                FontStyle::Regular
+            }),
+            "text-decoration" | "text-decoration-line" if value.contains("underline") => {
+                TextAttribute::Underline(true)
+            }
+            "text-decoration" | "text-decoration-line" if value.contains("line-through") => {
+                TextAttribute::Strikethrough(true)
+            }
+            _ => continue,
+        };
+        attributes.retain(|attr| !attr.same_variant(&new_attribute));
+        // Regular text doesn't need an attribute
+        if !matches!(
+            new_attribute,
+            TextAttribute::FontWeight(400) | TextAttribute::Style(FontStyle::Regular)
+        ) {
+            attributes.push(new_attribute);
+        }
+    }
+    attributes
+}
+
+/// Decodes the common named and all numeric character references.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';').filter(|&end| end <= 10) else {
+            decoded.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..end];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match c {
+            Some(c) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Collects the attributes that cover exactly the given range.
+    fn attributes_on(
+        rich_text: &HtmlRichText,
+        range: std::ops::Range<usize>,
+    ) -> Vec<TextAttribute> {
+        rich_text
+            .ranged_text_attributes
+            .iter()
+            .filter(|attr| attr.range == range)
+            .map(|attr| attr.attribute.clone())
+            .collect()
+    }
+
+    #[test]
+    fn nested_lists() {
+        let rich_text = HtmlRichText::from_html(
+            "<ul><li>a</li><li>b<ol><li>c</li><li>d</li></ol></li></ul><p>after</p>",
+            12.0,
+        );
+        assert_eq!(rich_text.text, "• a\n• b\n    1. c\n    2. d\nafter");
+    }
+
+    #[test]
+    fn table() {
+        let rich_text = HtmlRichText::from_html(
+            "<table>\n<tr>\n<th>Name</th> <th>Age</th>\n</tr>\n<tr><td>Ann</td>\n<td>42</td></tr>\n</table>",
+            12.0,
+        );
+        assert_eq!(rich_text.text, "Name\tAge\nAnn\t42");
+        assert!(matches!(
+            attributes_on(&rich_text, 0..4).as_slice(),
+            [TextAttribute::FontWeight(HtmlRichText::BOLD_WEIGHT)]
+        ));
+        assert!(matches!(
+            attributes_on(&rich_text, 5..8).as_slice(),
+            [TextAttribute::FontWeight(HtmlRichText::BOLD_WEIGHT)]
+        ));
+        assert!(attributes_on(&rich_text, 9..12).is_empty());
+    }
+
+    #[test]
+    fn inline_styles() {
+        let rich_text = HtmlRichText::from_html(
+            r#"<p><span style="font-weight: bold">bold</span> and <b style="font-weight:normal">normal</b> <span style="font-style:italic; text-decoration: underline">both</span></p>"#,
+            12.0,
+        );
+        assert_eq!(rich_text.text, "bold and normal both");
+        assert!(matches!(
+            attributes_on(&rich_text, 0..4).as_slice(),
+            [TextAttribute::FontWeight(HtmlRichText::BOLD_WEIGHT)]
+        ));
+        assert!(attributes_on(&rich_text, 9..15).is_empty());
+        assert!(matches!(
+            attributes_on(&rich_text, 16..20).as_slice(),
+            [
+                TextAttribute::Style(FontStyle::Italic),
+                TextAttribute::Underline(true)
+            ]
+        ));
+    }
+
+    #[test]
+    fn headings_and_links() {
+        let rich_text = HtmlRichText::from_html(
+            r#"<!--StartFragment--><h1>Title</h1><a href="https://rnote.flxzt.net">site</a><!--EndFragment-->"#,
+            12.0,
+        );
+        assert_eq!(rich_text.text, "Title\nsite (https://rnote.flxzt.net)");
+        assert!(matches!(
+            attributes_on(&rich_text, 0..5).as_slice(),
+            [TextAttribute::FontSize(size), TextAttribute::FontWeight(HtmlRichText::BOLD_WEIGHT)]
+                if *size == 24.0
+        ));
+        assert_eq!(attributes_on(&rich_text, 6..10).len(), 2);
+    }
+}
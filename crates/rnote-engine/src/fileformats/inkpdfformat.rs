@@ -0,0 +1,354 @@
+//! Handwriting in Pdfs exported by note taking apps like GoodNotes or Notability.
+//!
+//! These apps export handwriting as stroked paths on top of the page content. The paths are split off the Svg of a
+//! converted page and turned into brush strokes, so that they stay editable after importing.
+//! Ruling and grid lines of page templates are stroked paths as well, they are recognized by their geometry and stay
+//! part of the page.
+
+// Imports
+use super::usvgpaths::{penpaths_from_path_data, smooth_options_from_stroke};
+use crate::store::chrono_comp::StrokeLayer;
+use crate::strokes::BrushStroke;
+use anyhow::Context;
+use p2d::bounding_volume::Aabb;
+use rnote_compose::Style;
+use std::collections::HashSet;
+use std::sync::Arc;
+use usvg::tiny_skia_path::{self, PathSegment};
+
+/// The id prefix that marks ink paths, so that they can be found again after parsing.
+const INK_ID_PREFIX: &str = "rnote-ink-";
+/// Ink with a lower opacity is imported as highlighter strokes.
+const HIGHLIGHTER_OPACITY_THRESHOLD: f64 = 0.8;
+/// Straight lines spanning at least this fraction of the page width or height are considered template lines.
+const TEMPLATE_LINE_MIN_SPAN: f64 = 0.5;
+/// The tolerance in Svg user units when checking whether a line is horizontal or vertical.
+const TEMPLATE_LINE_TOLERANCE: f64 = 0.1;
+/// Elements whose content is not rendered directly. Paths inside them are left untouched.
+const NON_RENDERED_ELEMENTS: [&str; 6] =
+    ["defs", "clipPath", "mask", "pattern", "symbol", "marker"];
+
+/// A page with the ink split off.
+#[derive(Debug, Clone)]
+pub struct InkPage {
+    /// The Svg data of the page without the ink.
+    pub background_svg_data: String,
+    /// The ink as brush strokes in document coordinates, with the layer they should be inserted into.
+    pub strokes: Vec<(BrushStroke, StrokeLayer)>,
+}
+
+impl InkPage {
+    /// Splits the ink off the Svg of a page that gets placed at the given bounds.
+    ///
+    /// Ink are the stroked paths without a fill, except the lines of page templates.
+    pub fn split(svg_data: &str, bounds: Aabb) -> anyhow::Result<Self> {
+        let document = roxmltree::Document::parse(svg_data).context("Parsing page Svg failed.")?;
+
+        // All stroked paths are marked with an id, which ones are ink is decided by their geometry after parsing.
+        let mut marked_svg_data = String::with_capacity(svg_data.len());
+        let mut candidates = Vec::new();
+        let mut last = 0;
+        for node in document.descendants().filter(is_ink_path) {
+            let range = node.range();
+            if !svg_data[range.start..].starts_with("<path") {
+                continue;
+            }
+            let id = match node.attribute("id") {
+                Some(id) => {
+                    marked_svg_data.push_str(&svg_data[last..range.end]);
+                    id.to_string()
+                }
+                None => {
+                    let id = format!("{INK_ID_PREFIX}{}", candidates.len());
+                    let tag_end = range.start + "<path".len();
+                    marked_svg_data.push_str(&svg_data[last..tag_end]);
+                    marked_svg_data.push_str(&format!(" id=\"{id}\""));
+                    marked_svg_data.push_str(&svg_data[tag_end..range.end]);
+                    id
+                }
+            };
+            last = range.end;
+            candidates.push((id, range));
+        }
+        if candidates.is_empty() {
+            return Ok(Self {
+                background_svg_data: svg_data.to_string(),
+                strokes: vec![],
+            });
+        }
+        marked_svg_data.push_str(&svg_data[last..]);
+
+        let tree = usvg::Tree::from_str(
+            &marked_svg_data,
+            &usvg::Options {
+                fontdb: Arc::clone(&crate::svg::USVG_FONTDB),
+                ..Default::default()
+            },
+        )
+        .context("Parsing page Svg failed.")?;
+        let size = tree.size();
+        let page_size = na::vector![f64::from(size.width()), f64::from(size.height())];
+        let scale = na::vector![
+            bounds.extents()[0] / page_size[0],
+            bounds.extents()[1] / page_size[1]
+        ];
+
+        let candidate_ids = candidates.iter().map(|(id, _)| id.clone()).collect();
+        let mut ink_ids = HashSet::new();
+        let mut strokes = Vec::new();
+        collect_ink(
+            tree.root(),
+            1.0,
+            &candidate_ids,
+            page_size,
+            bounds.mins.coords,
+            scale,
+            &mut ink_ids,
+            &mut strokes,
+        );
+
+        let mut background_svg_data = String::with_capacity(svg_data.len());
+        let mut last = 0;
+        for (_, range) in candidates
+            .into_iter()
+            .filter(|(id, _)| ink_ids.contains(id))
+        {
+            background_svg_data.push_str(&svg_data[last..range.start]);
+            last = range.end;
+        }
+        background_svg_data.push_str(&svg_data[last..]);
+        Ok(Self {
+            background_svg_data,
+            strokes,
+        })
+    }
+}
+
+fn is_ink_path(node: &roxmltree::Node) -> bool {
+    node.is_element()
+        && node.tag_name().name() == "path"
+        && !node
+            .ancestors()
+            .any(|n| NON_RENDERED_ELEMENTS.contains(&n.tag_name().name()))
+        && resolved_presentation_attr(node, "fill").is_some_and(|fill| fill == "none")
+        && resolved_presentation_attr(node, "stroke").is_some_and(|stroke| stroke != "none")
+}
+
+/// The value of an inherited presentation attribute, either set as attribute or in the style attribute.
+///
+/// The fill defaults to black, so `None` for it means the path is filled.
+fn resolved_presentation_attr<'a>(node: &roxmltree::Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.ancestors().filter(|n| n.is_element()).find_map(|n| {
+        n.attribute("style")
+            .and_then(|style| {
+                style.split(';').find_map(|declaration| {
+                    let (property, value) = declaration.split_once(':')?;
+                    (property.trim() == name).then_some(value.trim())
+                })
+            })
+            .or_else(|| n.attribute(name).map(|value| value.trim()))
+    })
+}
+
+/// Whether the path data in Svg user units is made of the lines of a page template, like ruling or grid lines.
+///
+/// These are straight horizontal or vertical lines spanning a large part of the page, which handwriting rarely is.
+fn is_template_path(data: &tiny_skia_path::Path, page_size: na::Vector2<f64>) -> bool {
+    let mut sub_paths: Vec<Vec<na::Vector2<f64>>> = Vec::new();
+    for segment in data.segments() {
+        match segment {
+            PathSegment::MoveTo(p) => {
+                sub_paths.push(vec![na::vector![f64::from(p.x), f64::from(p.y)]])
+            }
+            PathSegment::LineTo(p) => match sub_paths.last_mut() {
+                Some(points) => points.push(na::vector![f64::from(p.x), f64::from(p.y)]),
+                None => return false,
+            },
+            PathSegment::Close => {}
+            // curves are never template lines
+            PathSegment::QuadTo(..) | PathSegment::CubicTo(..) => return false,
+        }
+    }
+    !sub_paths.is_empty()
+        && sub_paths.iter().all(|points| {
+            let (mins, maxs) = points.iter().fold(
+                (
+                    na::Vector2::repeat(f64::INFINITY),
+                    na::Vector2::repeat(f64::NEG_INFINITY),
+                ),
+                |(mins, maxs), p| (mins.inf(p), maxs.sup(p)),
+            );
+            let extents = maxs - mins;
+            (extents[1] <= TEMPLATE_LINE_TOLERANCE
+                && extents[0] >= page_size[0] * TEMPLATE_LINE_MIN_SPAN)
+                || (extents[0] <= TEMPLATE_LINE_TOLERANCE
+                    && extents[1] >= page_size[1] * TEMPLATE_LINE_MIN_SPAN)
+        })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn collect_ink(
+    group: &usvg::Group,
+    opacity: f64,
+    candidate_ids: &HashSet<String>,
+    page_size: na::Vector2<f64>,
+    offset: na::Vector2<f64>,
+    scale: na::Vector2<f64>,
+    ink_ids: &mut HashSet<String>,
+    strokes: &mut Vec<(BrushStroke, StrokeLayer)>,
+) {
+    let opacity = opacity * f64::from(group.opacity().get());
+    for node in group.children() {
+        match node {
+            usvg::Node::Group(group) => collect_ink(
+                group,
+                opacity,
+                candidate_ids,
+                page_size,
+                offset,
+                scale,
+                ink_ids,
+                strokes,
+            ),
+            usvg::Node::Path(path) if candidate_ids.contains(path.id()) && path.is_visible() => {
+                let Some(stroke) = path.stroke() else {
+                    continue;
+                };
+                let transform = path.abs_transform();
+                let Some(data) = path.data().clone().transform(transform) else {
+                    continue;
+                };
+                if is_template_path(&data, page_size) {
+                    continue;
+                }
+                let stroke_opacity = opacity * f64::from(stroke.opacity().get());
+                let Some(smooth_options) = smooth_options_from_stroke(
+                    stroke,
+                    stroke_opacity,
+                    transform,
+                    (scale[0] + scale[1]) * 0.5,
+                ) else {
+                    continue;
+                };
+                let layer = if stroke_opacity < HIGHLIGHTER_OPACITY_THRESHOLD {
+                    StrokeLayer::Highlighter
+                } else {
+                    StrokeLayer::UserLayer(0)
+                };
+                ink_ids.insert(path.id().to_string());
+
+                strokes.extend(
+                    penpaths_from_path_data(&data, offset, scale)
+                        .into_iter()
+                        .map(|penpath| {
+                            (
+                                BrushStroke::from_penpath(
+                                    penpath,
+                                    Style::Smooth(smooth_options.clone()),
+                                ),
+                                layer,
+                            )
+                        }),
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A ruled page like it is exported by note taking apps, with a margin line, a grid drawn as one path and ink.
+    const TEMPLATED_PAGE_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="600" height="800" viewBox="0 0 600 800">
+  <rect width="600" height="800" fill="white"/>
+  <g fill="none" stroke="#a0c0e0" stroke-width="0.5">
+    <path d="M 0 100 L 600 100"/>
+    <path d="M 0 130 L 600 130"/>
+    <path d="M 0 160 L 600 160 M 0 190 L 600 190 M 300 160 L 300 760"/>
+  </g>
+  <path d="M 60 0 L 60 800" style="fill:none;stroke:#ff0000;stroke-width:0.5"/>
+  <g fill="none" stroke="#000000" stroke-width="2">
+    <path d="M 100 200 C 120 180 140 220 160 200"/>
+    <path d="M 100 300 L 200 300"/>
+  </g>
+</svg>"##;
+
+    #[test]
+    fn template_lines_stay_in_the_page() {
+        let bounds = Aabb::new(na::point![0.0, 0.0], na::point![300.0, 400.0]);
+        let ink_page = InkPage::split(TEMPLATED_PAGE_SVG, bounds).unwrap();
+
+        assert_eq!(ink_page.strokes.len(), 2);
+        for template_path in [
+            "M 0 100 L 600 100",
+            "M 0 130 L 600 130",
+            "M 0 160 L 600 160 M 0 190 L 600 190 M 300 160 L 300 760",
+            "M 60 0 L 60 800",
+        ] {
+            assert!(ink_page.background_svg_data.contains(template_path));
+        }
+        assert!(!ink_page.background_svg_data.contains("C 120 180"));
+        assert!(!ink_page.background_svg_data.contains("M 100 300"));
+    }
+
+    #[test]
+    fn ink_is_placed_in_the_bounds() {
+        let bounds = Aabb::new(na::point![0.0, 0.0], na::point![300.0, 400.0]);
+        let ink_page = InkPage::split(TEMPLATED_PAGE_SVG, bounds).unwrap();
+
+        let (curve, layer) = &ink_page.strokes[0];
+        assert_eq!(*layer, StrokeLayer::UserLayer(0));
+        approx::assert_relative_eq!(curve.path.start.pos, na::vector![50.0, 100.0]);
+        let Style::Smooth(smooth_options) = &curve.style else {
+            panic!("ink is not imported with the smooth style");
+        };
+        approx::assert_relative_eq!(smooth_options.stroke_width, 1.0);
+    }
+
+    #[test]
+    fn template_path_detection() {
+        let page_size = na::vector![600.0, 800.0];
+        let path = |build: &dyn Fn(&mut tiny_skia_path::PathBuilder)| {
+            let mut builder = tiny_skia_path::PathBuilder::new();
+            build(&mut builder);
+            builder.finish().unwrap()
+        };
+
+        let ruling = path(&|b| {
+            b.move_to(0.0, 100.0);
+            b.line_to(600.0, 100.0);
+        });
+        assert!(is_template_path(&ruling, page_size));
+        let grid = path(&|b| {
+            b.move_to(0.0, 100.0);
+            b.line_to(600.0, 100.0);
+            b.move_to(100.0, 0.0);
+            b.line_to(100.0, 800.0);
+        });
+        assert!(is_template_path(&grid, page_size));
+        let short_line = path(&|b| {
+            b.move_to(100.0, 300.0);
+            b.line_to(200.0, 300.0);
+        });
+        assert!(!is_template_path(&short_line, page_size));
+        let diagonal = path(&|b| {
+            b.move_to(0.0, 0.0);
+            b.line_to(600.0, 800.0);
+        });
+        assert!(!is_template_path(&diagonal, page_size));
+        let ruling_and_ink = path(&|b| {
+            b.move_to(0.0, 100.0);
+            b.line_to(600.0, 100.0);
+            b.move_to(100.0, 200.0);
+            b.line_to(150.0, 210.0);
+        });
+        assert!(!is_template_path(&ruling_and_ink, page_size));
+        let curve = path(&|b| {
+            b.move_to(0.0, 100.0);
+            b.quad_to(300.0, 100.0, 600.0, 100.0);
+        });
+        assert!(!is_template_path(&curve, page_size));
+    }
+}
@@ -1,5 +1,12 @@
 // Modules
+pub mod emfformat;
+pub mod htmlformat;
+pub mod inkpdfformat;
+pub mod pdfannotationformat;
 pub mod rnoteformat;
+pub mod svgformat;
+pub mod tableformat;
+pub mod usvgpaths;
 pub mod xoppformat;
 
 // Imports
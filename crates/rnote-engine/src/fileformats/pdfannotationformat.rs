@@ -0,0 +1,605 @@
+//! Strokes as standard annotations on an existing Pdf.
+//!
+//! The annotations are appended to the Pdf as incremental update, so its original content stays untouched and other
+//! Pdf readers can show, hide or edit them.
+
+// Imports
+use anyhow::Context;
+use lopdf::{Dictionary, IncrementalDocument, Object, ObjectId, Stream, dictionary};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use rnote_compose::Color;
+use std::collections::BTreeMap;
+
+/// The name of the font resource used in the appearance of text annotations.
+const FONT_RESOURCE_NAME: &str = "Helv";
+/// The line height of text annotations relative to the font size.
+const TEXT_LINE_HEIGHT: f64 = 1.2;
+/// The annotation flag that the annotation is printed.
+const ANNOTATION_FLAG_PRINT: i64 = 4;
+
+/// An annotation, in document coordinates.
+#[derive(Debug, Clone)]
+pub enum Annotation {
+    /// Freehand ink, made of polylines.
+    Ink {
+        polylines: Vec<Vec<na::Vector2<f64>>>,
+        width: f64,
+        color: Color,
+    },
+    /// A highlighted area.
+    Highlight { bounds: Aabb, color: Color },
+    /// Text inside a box.
+    FreeText {
+        bounds: Aabb,
+        text: String,
+        font_size: f64,
+        color: Color,
+    },
+}
+
+impl Annotation {
+    /// Ink from the given path, flattened into polylines.
+    pub fn ink_from_bezpath(bezpath: &kurbo::BezPath, width: f64, color: Color) -> Self {
+        let mut polylines: Vec<Vec<na::Vector2<f64>>> = Vec::new();
+        kurbo::flatten(bezpath, 0.1, |el| match el {
+            kurbo::PathEl::MoveTo(p) => polylines.push(vec![na::vector![p.x, p.y]]),
+            kurbo::PathEl::LineTo(p) => {
+                if let Some(polyline) = polylines.last_mut() {
+                    polyline.push(na::vector![p.x, p.y]);
+                }
+            }
+            kurbo::PathEl::ClosePath => {
+                if let Some(polyline) = polylines.last_mut()
+                    && let Some(&start) = polyline.first()
+                {
+                    polyline.push(start);
+                }
+            }
+            // flattening only emits lines
+            kurbo::PathEl::QuadTo(..) | kurbo::PathEl::CurveTo(..) => {}
+        });
+        Self::Ink {
+            polylines,
+            width,
+            color,
+        }
+    }
+}
+
+/// The annotations on a page of the Pdf.
+#[derive(Debug, Clone)]
+pub struct PageAnnotations {
+    /// The zero-based index of the page in the Pdf.
+    pub page_index: usize,
+    /// The bounds of the page in the document.
+    pub bounds: Aabb,
+    pub annotations: Vec<Annotation>,
+}
+
+/// Writes the annotations onto the Pdf and returns the bytes of the updated Pdf.
+pub fn annotate_pdf(pdf_bytes: &[u8], pages: &[PageAnnotations]) -> anyhow::Result<Vec<u8>> {
+    let mut pdf = IncrementalDocument::load_from(pdf_bytes).context("Loading the Pdf failed.")?;
+    if pdf.get_prev_documents().is_encrypted() {
+        return Err(anyhow::anyhow!(
+            "Writing annotations onto encrypted Pdfs is not supported."
+        ));
+    }
+    let page_ids = pdf.get_prev_documents().get_pages();
+
+    let mut page_annots: BTreeMap<ObjectId, Vec<Object>> = BTreeMap::new();
+    for page in pages {
+        let Some(&page_id) = u32::try_from(page.page_index + 1)
+            .ok()
+            .and_then(|page_number| page_ids.get(&page_number))
+        else {
+            return Err(anyhow::anyhow!(
+                "The Pdf has no page with index {}.",
+                page.page_index
+            ));
+        };
+        let transform = PageTransform::new(pdf.get_prev_documents(), page_id, page.bounds)?;
+        for annotation in &page.annotations {
+            let Some(annot) = annotation_dict(&mut pdf, annotation, &transform, page_id) else {
+                continue;
+            };
+            let annot_id = pdf.new_document.add_object(annot);
+            page_annots
+                .entry(page_id)
+                .or_default()
+                .push(Object::Reference(annot_id));
+        }
+    }
+    for (page_id, annots) in page_annots {
+        append_page_annots(&mut pdf, page_id, annots)?;
+    }
+
+    let mut bytes = Vec::new();
+    pdf.save_to(&mut bytes)
+        .context("Writing the annotated Pdf failed.")?;
+    Ok(bytes)
+}
+
+/// Maps document coordinates on a page to the user space of the Pdf page.
+#[derive(Debug, Clone, Copy)]
+struct PageTransform {
+    bounds: Aabb,
+    /// The visible box of the page, as `[x_min, y_min, x_max, y_max]`.
+    page_box: [f64; 4],
+    /// The clockwise rotation of the page when it is displayed, in degrees.
+    rotate: i64,
+}
+
+impl PageTransform {
+    fn new(pdf: &lopdf::Document, page_id: ObjectId, bounds: Aabb) -> anyhow::Result<Self> {
+        let page_box = inherited_page_attr(pdf, page_id, b"CropBox")
+            .or_else(|| inherited_page_attr(pdf, page_id, b"MediaBox"))
+            .and_then(|page_box| {
+                let values = page_box
+                    .as_array()
+                    .ok()?
+                    .iter()
+                    .map(|value| value.as_float().map(f64::from).ok())
+                    .collect::<Option<Vec<f64>>>()?;
+                let [x0, y0, x1, y1] = values[..] else {
+                    return None;
+                };
+                Some([x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)])
+            })
+            .context("The page has no valid media box.")?;
+        let rotate = inherited_page_attr(pdf, page_id, b"Rotate")
+            .and_then(|rotate| rotate.as_i64().ok())
+            .unwrap_or(0)
+            .rem_euclid(360);
+        Ok(Self {
+            bounds,
+            page_box,
+            rotate,
+        })
+    }
+
+    fn apply(&self, pos: na::Vector2<f64>) -> na::Vector2<f64> {
+        let rel = (pos - self.bounds.mins.coords).component_div(&self.bounds.extents());
+        // relative coordinates on the unrotated page, y pointing down
+        let (u, v) = match self.rotate {
+            90 => (rel[1], 1.0 - rel[0]),
+            180 => (1.0 - rel[0], 1.0 - rel[1]),
+            270 => (1.0 - rel[1], rel[0]),
+            _ => (rel[0], rel[1]),
+        };
+        let [x0, y0, x1, y1] = self.page_box;
+        na::vector![x0 + u * (x1 - x0), y1 - v * (y1 - y0)]
+    }
+
+    fn apply_to_bounds(&self, bounds: Aabb) -> Aabb {
+        let a = self.apply(bounds.mins.coords);
+        let b = self.apply(bounds.maxs.coords);
+        Aabb::new(a.inf(&b).into(), a.sup(&b).into())
+    }
+
+    /// The scale from document to Pdf units, for widths and sizes.
+    fn scale(&self) -> f64 {
+        let [x0, y0, x1, y1] = self.page_box;
+        let (width, height) = if self.rotate == 90 || self.rotate == 270 {
+            (y1 - y0, x1 - x0)
+        } else {
+            (x1 - x0, y1 - y0)
+        };
+        (width / self.bounds.extents()[0] + height / self.bounds.extents()[1]) * 0.5
+    }
+}
+
+/// An attribute of the page dictionary, which may be inherited from the page tree.
+fn inherited_page_attr<'a>(
+    pdf: &'a lopdf::Document,
+    page_id: ObjectId,
+    key: &[u8],
+) -> Option<&'a Object> {
+    let mut dict = pdf.get_dictionary(page_id).ok()?;
+    // the depth is limited to not loop endlessly in malformed page trees
+    for _ in 0..64 {
+        if let Ok(value) = dict.get(key) {
+            return pdf.dereference(value).ok().map(|(_, value)| value);
+        }
+        dict = dict
+            .get(b"Parent")
+            .and_then(Object::as_reference)
+            .and_then(|parent_id| pdf.get_dictionary(parent_id))
+            .ok()?;
+    }
+    None
+}
+
+/// Adds the annotation references to the annotations array of the page.
+fn append_page_annots(
+    pdf: &mut IncrementalDocument,
+    page_id: ObjectId,
+    annots: Vec<Object>,
+) -> anyhow::Result<()> {
+    let page = pdf.get_prev_documents().get_dictionary(page_id)?;
+    if let Ok(&Object::Reference(annots_id)) = page.get(b"Annots") {
+        pdf.opt_clone_object_to_new_document(annots_id)?;
+        pdf.new_document
+            .get_object_mut(annots_id)?
+            .as_array_mut()?
+            .extend(annots);
+    } else {
+        pdf.opt_clone_object_to_new_document(page_id)?;
+        let page = pdf.new_document.get_dictionary_mut(page_id)?;
+        match page.get_mut(b"Annots") {
+            Ok(Object::Array(page_annots)) => page_annots.extend(annots),
+            _ => page.set("Annots", Object::Array(annots)),
+        }
+    }
+    Ok(())
+}
+
+fn annotation_dict(
+    pdf: &mut IncrementalDocument,
+    annotation: &Annotation,
+    transform: &PageTransform,
+    page_id: ObjectId,
+) -> Option<Dictionary> {
+    let (mut dict, rect, appearance, resources) = match annotation {
+        Annotation::Ink {
+            polylines,
+            width,
+            color,
+        } => {
+            let polylines = polylines
+                .iter()
+                .filter(|polyline| !polyline.is_empty())
+                .map(|polyline| {
+                    polyline
+                        .iter()
+                        .map(|&pos| transform.apply(pos))
+                        .collect::<Vec<na::Vector2<f64>>>()
+                })
+                .collect::<Vec<Vec<na::Vector2<f64>>>>();
+            let width = width * transform.scale();
+            let rect = points_bounds(polylines.iter().flatten())?.loosened(width * 0.5);
+
+            let mut appearance = format!("{} w 1 J 1 j {} RG\n", fmt(width), fmt_color(color));
+            for polyline in polylines.iter() {
+                appearance.push_str(&format!(
+                    "{} {} m\n",
+                    fmt(polyline[0][0]),
+                    fmt(polyline[0][1])
+                ));
+                // a single point is drawn as dot with the round line cap
+                for pos in polyline
+                    .iter()
+                    .skip(1)
+                    .chain((polyline.len() == 1).then_some(&polyline[0]))
+                {
+                    appearance.push_str(&format!("{} {} l\n", fmt(pos[0]), fmt(pos[1])));
+                }
+                appearance.push_str("S\n");
+            }
+            let ink_list = polylines
+                .iter()
+                .map(|polyline| {
+                    Object::Array(
+                        polyline
+                            .iter()
+                            .flat_map(|pos| {
+                                [Object::Real(pos[0] as f32), Object::Real(pos[1] as f32)]
+                            })
+                            .collect(),
+                    )
+                })
+                .collect::<Vec<Object>>();
+            (
+                dictionary! {
+                    "Subtype" => "Ink",
+                    "InkList" => ink_list,
+                    "BS" => dictionary! { "W" => width },
+                },
+                rect,
+                appearance,
+                Dictionary::new(),
+            )
+        }
+        Annotation::Highlight { bounds, color } => {
+            let rect = transform.apply_to_bounds(*bounds);
+            let quad_points = [
+                (rect.mins[0], rect.maxs[1]),
+                (rect.maxs[0], rect.maxs[1]),
+                (rect.mins[0], rect.mins[1]),
+                (rect.maxs[0], rect.mins[1]),
+            ]
+            .into_iter()
+            .flat_map(|(x, y)| [Object::Real(x as f32), Object::Real(y as f32)])
+            .collect::<Vec<Object>>();
+            let appearance = format!(
+                "/GS gs {} rg {} {} {} {} re f\n",
+                fmt_color(color),
+                fmt(rect.mins[0]),
+                fmt(rect.mins[1]),
+                fmt(rect.extents()[0]),
+                fmt(rect.extents()[1])
+            );
+            (
+                dictionary! {
+                    "Subtype" => "Highlight",
+                    "QuadPoints" => quad_points,
+                },
+                rect,
+                appearance,
+                dictionary! {
+                    "ExtGState" => dictionary! {
+                        "GS" => dictionary! { "Type" => "ExtGState", "BM" => "Multiply" },
+                    },
+                },
+            )
+        }
+        Annotation::FreeText {
+            bounds,
+            text,
+            font_size,
+            color,
+        } => {
+            let rect = transform.apply_to_bounds(*bounds);
+            let font_size = font_size * transform.scale();
+            let mut appearance = format!(
+                "BT /{FONT_RESOURCE_NAME} {} Tf {} rg {} TL {} {} Td\n",
+                fmt(font_size),
+                fmt_color(color),
+                fmt(font_size * TEXT_LINE_HEIGHT),
+                fmt(rect.mins[0]),
+                fmt(rect.maxs[1] - font_size)
+            );
+            for line in text.lines() {
+                appearance.push_str(&format!("({}) Tj T*\n", win_ansi_literal(line)));
+            }
+            appearance.push_str("ET\n");
+            (
+                dictionary! {
+                    "Subtype" => "FreeText",
+                    "Contents" => lopdf::text_string(text),
+                    "DA" => Object::string_literal(format!(
+                        "/{FONT_RESOURCE_NAME} {} Tf {} rg",
+                        fmt(font_size),
+                        fmt_color(color)
+                    )),
+                },
+                rect,
+                appearance,
+                dictionary! {
+                    "Font" => dictionary! {
+                        FONT_RESOURCE_NAME => dictionary! {
+                            "Type" => "Font",
+                            "Subtype" => "Type1",
+                            "BaseFont" => "Helvetica",
+                            "Encoding" => "WinAnsiEncoding",
+                        },
+                    },
+                },
+            )
+        }
+    };
+    let color = match annotation {
+        Annotation::Ink { color, .. }
+        | Annotation::Highlight { color, .. }
+        | Annotation::FreeText { color, .. } => color,
+    };
+    let rect_array = vec![
+        Object::Real(rect.mins[0] as f32),
+        Object::Real(rect.mins[1] as f32),
+        Object::Real(rect.maxs[0] as f32),
+        Object::Real(rect.maxs[1] as f32),
+    ];
+    let appearance_id = pdf.new_document.add_object(Stream::new(
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Form",
+            "BBox" => rect_array.clone(),
+            "Resources" => resources,
+        },
+        appearance.into_bytes(),
+    ));
+
+    dict.set("Type", "Annot");
+    dict.set("Rect", rect_array);
+    dict.set("P", page_id);
+    dict.set("F", ANNOTATION_FLAG_PRINT);
+    dict.set(
+        "C",
+        vec![
+            Object::Real(color.r as f32),
+            Object::Real(color.g as f32),
+            Object::Real(color.b as f32),
+        ],
+    );
+    dict.set("CA", color.a);
+    dict.set("AP", dictionary! { "N" => appearance_id });
+    Some(dict)
+}
+
+fn points_bounds<'a>(mut points: impl Iterator<Item = &'a na::Vector2<f64>>) -> Option<Aabb> {
+    let first = *points.next()?;
+    let (mins, maxs) = points.fold((first, first), |(mins, maxs), p| (mins.inf(p), maxs.sup(p)));
+    Some(Aabb::new(mins.into(), maxs.into()))
+}
+
+fn fmt(value: f64) -> String {
+    format!("{value:.3}")
+}
+
+fn fmt_color(color: &Color) -> String {
+    format!("{} {} {}", fmt(color.r), fmt(color.g), fmt(color.b))
+}
+
+/// The text as content of a literal string in WinAnsi encoding, characters that can't be encoded are replaced.
+fn win_ansi_literal(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{c}"),
+            // WinAnsi matches Latin-1 in these ranges
+            ' '..='~' => c.to_string(),
+            '\u{a0}'..='\u{ff}' => format!("\\{:03o}", c as u32),
+            _ => String::from("?"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A Pdf with the given pages, each described by its media box and rotation.
+    fn test_pdf(pages: &[([i64; 4], i64)]) -> Vec<u8> {
+        let mut pdf = lopdf::Document::with_version("1.7");
+        let pages_id = pdf.new_object_id();
+        let kids = pages
+            .iter()
+            .map(|&(media_box, rotate)| {
+                let content_id = pdf.add_object(Stream::new(dictionary! {}, b"".to_vec()));
+                Object::Reference(pdf.add_object(dictionary! {
+                    "Type" => "Page",
+                    "Parent" => pages_id,
+                    "MediaBox" => media_box.iter().map(|&v| Object::Integer(v)).collect::<Vec<Object>>(),
+                    "Rotate" => rotate,
+                    "Contents" => content_id,
+                }))
+            })
+            .collect::<Vec<Object>>();
+        pdf.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Count" => kids.len() as i64,
+                "Kids" => kids,
+            }),
+        );
+        let catalog_id = pdf.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        pdf.trailer.set("Root", catalog_id);
+        let mut bytes = Vec::new();
+        pdf.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn rect(annot: &Dictionary) -> [f64; 4] {
+        let values = annot
+            .get(b"Rect")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| f64::from(v.as_float().unwrap()))
+            .collect::<Vec<f64>>();
+        values.try_into().unwrap()
+    }
+
+    #[test]
+    fn annotations_are_appended() {
+        let original = test_pdf(&[([0, 0, 600, 800], 0), ([0, 0, 600, 800], 0)]);
+        let pages = vec![PageAnnotations {
+            page_index: 1,
+            bounds: Aabb::new(na::point![0.0, 1000.0], na::point![300.0, 1400.0]),
+            annotations: vec![
+                Annotation::Ink {
+                    polylines: vec![vec![na::vector![30.0, 1050.0], na::vector![60.0, 1050.0]]],
+                    width: 1.0,
+                    color: Color::BLACK,
+                },
+                Annotation::Highlight {
+                    bounds: Aabb::new(na::point![0.0, 1000.0], na::point![150.0, 1010.0]),
+                    color: Color::new(1.0, 1.0, 0.0, 0.5),
+                },
+                Annotation::FreeText {
+                    bounds: Aabb::new(na::point![150.0, 1200.0], na::point![300.0, 1220.0]),
+                    text: String::from("Note (1)\nÜber"),
+                    font_size: 10.0,
+                    color: Color::BLACK,
+                },
+            ],
+        }];
+
+        let annotated = annotate_pdf(&original, &pages).unwrap();
+        // incremental update
+        assert!(annotated.starts_with(&original));
+
+        let pdf = lopdf::Document::load_mem(&annotated).unwrap();
+        let page_ids = pdf.get_pages();
+        assert!(pdf.get_page_annotations(page_ids[&1]).unwrap().is_empty());
+        let annots = pdf.get_page_annotations(page_ids[&2]).unwrap();
+        assert_eq!(annots.len(), 3);
+
+        let ink = annots[0];
+        assert_eq!(ink.get(b"Subtype").unwrap().as_name().unwrap(), b"Ink");
+        let ink_list = ink.get(b"InkList").unwrap().as_array().unwrap();
+        let points = ink_list[0]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| f64::from(v.as_float().unwrap()))
+            .collect::<Vec<f64>>();
+        // scaled by 2, y flipped
+        approx::assert_relative_eq!(points.as_slice(), [60.0, 700.0, 120.0, 700.0].as_slice());
+        assert!(ink.get(b"AP").is_ok());
+
+        let highlight = annots[1];
+        assert_eq!(
+            highlight.get(b"Subtype").unwrap().as_name().unwrap(),
+            b"Highlight"
+        );
+        approx::assert_relative_eq!(
+            rect(highlight).as_slice(),
+            [0.0, 780.0, 300.0, 800.0].as_slice()
+        );
+        approx::assert_relative_eq!(highlight.get(b"CA").unwrap().as_float().unwrap(), 0.5);
+
+        let free_text = annots[2];
+        assert_eq!(
+            lopdf::decode_text_string(free_text.get(b"Contents").unwrap()).unwrap(),
+            "Note (1)\nÜber"
+        );
+        approx::assert_relative_eq!(
+            rect(free_text).as_slice(),
+            [300.0, 360.0, 600.0, 400.0].as_slice()
+        );
+    }
+
+    #[test]
+    fn rotated_page() {
+        // displayed as landscape page
+        let original = test_pdf(&[([0, 0, 600, 800], 90)]);
+        let pages = vec![PageAnnotations {
+            page_index: 0,
+            bounds: Aabb::new(na::point![0.0, 0.0], na::point![800.0, 600.0]),
+            annotations: vec![Annotation::Highlight {
+                bounds: Aabb::new(na::point![0.0, 0.0], na::point![100.0, 50.0]),
+                color: Color::BLACK,
+            }],
+        }];
+
+        let annotated = annotate_pdf(&original, &pages).unwrap();
+        let pdf = lopdf::Document::load_mem(&annotated).unwrap();
+        let annots = pdf.get_page_annotations(pdf.get_pages()[&1]).unwrap();
+        // the top left corner of the displayed page is the bottom left corner of the unrotated page
+        approx::assert_relative_eq!(
+            rect(annots[0]).as_slice(),
+            [0.0, 0.0, 50.0, 100.0].as_slice()
+        );
+    }
+
+    #[test]
+    fn missing_page() {
+        let original = test_pdf(&[([0, 0, 600, 800], 0)]);
+        let pages = vec![PageAnnotations {
+            page_index: 1,
+            bounds: Aabb::new(na::point![0.0, 0.0], na::point![600.0, 800.0]),
+            annotations: vec![],
+        }];
+        assert!(annotate_pdf(&original, &pages).is_err());
+    }
+
+    #[test]
+    fn win_ansi_escapes() {
+        assert_eq!(win_ansi_literal("a(b)\\"), "a\\(b\\)\\\\");
+        assert_eq!(win_ansi_literal("Ü€"), "\\334?");
+    }
+}
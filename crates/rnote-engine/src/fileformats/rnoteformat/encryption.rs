@@ -0,0 +1,245 @@
+//! Password based encryption of `.rnote` files.
+//!
+//! The compressed file is encrypted with XChaCha20-Poly1305, the key is derived from the password with Argon2id.
+//! The layout of an encrypted file is:
+//!
+//! | magic (8) | version (1) | argon2 m_cost (4) | argon2 t_cost (4) | argon2 p_cost (4) | salt (16) | nonce (24) | ciphertext |
+//!
+//! All integers are little endian. The header is authenticated as associated data.
+
+// Imports
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+const MAGIC: &[u8; 8] = b"RNOTEENC";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const KEY_LEN: usize = 32;
+const HEADER_LEN: usize = MAGIC.len() + 1 + 3 * 4 + SALT_LEN + NONCE_LEN;
+
+/// The Argon2id memory cost in KiB.
+const ARGON2_M_COST: u32 = 64 * 1024;
+/// The Argon2id iterations.
+const ARGON2_T_COST: u32 = 3;
+/// The Argon2id parallelism.
+const ARGON2_P_COST: u32 = 1;
+/// Upper limit of the memory cost accepted when decrypting, to not exhaust memory with crafted files.
+const ARGON2_M_COST_MAX: u32 = 1024 * 1024;
+/// Upper limit of the iterations accepted when decrypting, to not stall with crafted files.
+const ARGON2_T_COST_MAX: u32 = 16;
+/// Upper limit of the parallelism accepted when decrypting.
+const ARGON2_P_COST_MAX: u32 = 16;
+
+#[derive(Debug, Error)]
+pub enum DecryptError {
+    #[error("the password is wrong or the file is corrupted")]
+    WrongPassword,
+    #[error("the file is not a valid encrypted rnote file: {0}")]
+    Invalid(String),
+}
+
+/// Whether the bytes are an encrypted `.rnote` file.
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Encrypts the bytes of a `.rnote` file with the password.
+///
+/// A new salt and nonce is generated every time, so that re-encrypting with a different password re-keys the file.
+pub fn encrypt(bytes: &[u8], password: &str) -> anyhow::Result<Vec<u8>> {
+    encrypt_w_costs(bytes, password, ARGON2_M_COST, ARGON2_T_COST, ARGON2_P_COST)
+}
+
+fn encrypt_w_costs(
+    bytes: &[u8],
+    password: &str,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+) -> anyhow::Result<Vec<u8>> {
+    let mut rng = rand::rng();
+    let mut salt = [0_u8; SALT_LEN];
+    let mut nonce = [0_u8; NONCE_LEN];
+    rng.fill_bytes(&mut salt);
+    rng.fill_bytes(&mut nonce);
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.push(VERSION);
+    header.extend_from_slice(&m_cost.to_le_bytes());
+    header.extend_from_slice(&t_cost.to_le_bytes());
+    header.extend_from_slice(&p_cost.to_le_bytes());
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&nonce);
+
+    let key = derive_key(password, &salt, m_cost, t_cost, p_cost)?;
+    let cipher = XChaCha20Poly1305::new(chacha20poly1305::Key::from_slice(key.as_slice()));
+    let ciphertext = cipher
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: bytes,
+                aad: &header,
+            },
+        )
+        .map_err(|e| anyhow::anyhow!("Encrypting failed, Err: {e}"))?;
+
+    let mut encrypted = header;
+    encrypted.extend_from_slice(&ciphertext);
+    Ok(encrypted)
+}
+
+/// Decrypts the bytes of an encrypted `.rnote` file with the password.
+pub fn decrypt(bytes: &[u8], password: &str) -> Result<Vec<u8>, DecryptError> {
+    if !is_encrypted(bytes) {
+        return Err(DecryptError::Invalid(String::from("magic bytes missing")));
+    }
+    if bytes.len() < HEADER_LEN {
+        return Err(DecryptError::Invalid(String::from("header is truncated")));
+    }
+    let (header, ciphertext) = bytes.split_at(HEADER_LEN);
+    let version = header[MAGIC.len()];
+    if version != VERSION {
+        return Err(DecryptError::Invalid(format!(
+            "unsupported encryption version {version}"
+        )));
+    }
+    let read_u32 = |offset: usize| {
+        let offset = MAGIC.len() + 1 + offset * 4;
+        u32::from_le_bytes(header[offset..offset + 4].try_into().unwrap())
+    };
+    let (m_cost, t_cost, p_cost) = (read_u32(0), read_u32(1), read_u32(2));
+    if m_cost > ARGON2_M_COST_MAX {
+        return Err(DecryptError::Invalid(format!(
+            "key derivation memory cost {m_cost} KiB is too large"
+        )));
+    }
+    if t_cost > ARGON2_T_COST_MAX {
+        return Err(DecryptError::Invalid(format!(
+            "key derivation iterations {t_cost} are too many"
+        )));
+    }
+    if p_cost > ARGON2_P_COST_MAX {
+        return Err(DecryptError::Invalid(format!(
+            "key derivation parallelism {p_cost} is too large"
+        )));
+    }
+    let salt_start = MAGIC.len() + 1 + 3 * 4;
+    let salt = &header[salt_start..salt_start + SALT_LEN];
+    let nonce = &header[salt_start + SALT_LEN..];
+
+    let key = derive_key(password, salt, m_cost, t_cost, p_cost)
+        .map_err(|e| DecryptError::Invalid(e.to_string()))?;
+    let cipher = XChaCha20Poly1305::new(chacha20poly1305::Key::from_slice(key.as_slice()));
+    cipher
+        .decrypt(
+            XNonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| DecryptError::WrongPassword)
+}
+
+fn derive_key(
+    password: &str,
+    salt: &[u8],
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+) -> anyhow::Result<Zeroizing<[u8; KEY_LEN]>> {
+    let params = argon2::Params::new(m_cost, t_cost, p_cost, Some(KEY_LEN))
+        .map_err(|e| anyhow::anyhow!("Invalid key derivation parameters, Err: {e}"))?;
+    let mut key = Zeroizing::new([0_u8; KEY_LEN]);
+    Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, key.as_mut_slice())
+        .map_err(|e| anyhow::anyhow!("Deriving key from password failed, Err: {e}"))?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Low costs to keep the tests fast, the defaults are exercised by the application.
+    const TEST_M_COST: u32 = 64;
+    const TEST_T_COST: u32 = 1;
+    const TEST_P_COST: u32 = 1;
+
+    #[test]
+    fn roundtrip() {
+        let bytes = b"rnote file content".to_vec();
+        let encrypted =
+            encrypt_w_costs(&bytes, "secret", TEST_M_COST, TEST_T_COST, TEST_P_COST).unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert_ne!(&encrypted[HEADER_LEN..], bytes.as_slice());
+        assert_eq!(decrypt(&encrypted, "secret").unwrap(), bytes);
+    }
+
+    #[test]
+    fn wrong_password() {
+        let encrypted = encrypt_w_costs(
+            b"rnote file content",
+            "secret",
+            TEST_M_COST,
+            TEST_T_COST,
+            TEST_P_COST,
+        )
+        .unwrap();
+        assert!(matches!(
+            decrypt(&encrypted, "wrong"),
+            Err(DecryptError::WrongPassword)
+        ));
+    }
+
+    #[test]
+    fn tampered_header() {
+        let mut encrypted = encrypt_w_costs(
+            b"rnote file content",
+            "secret",
+            TEST_M_COST,
+            TEST_T_COST,
+            TEST_P_COST,
+        )
+        .unwrap();
+        // the salt is part of the authenticated header
+        encrypted[HEADER_LEN - NONCE_LEN - 1] ^= 1;
+        assert!(matches!(
+            decrypt(&encrypted, "secret"),
+            Err(DecryptError::WrongPassword)
+        ));
+    }
+
+    #[test]
+    fn rejects_excessive_costs() {
+        for (m_cost, t_cost, p_cost) in [
+            (ARGON2_M_COST_MAX + 1, TEST_T_COST, TEST_P_COST),
+            (TEST_M_COST, ARGON2_T_COST_MAX + 1, TEST_P_COST),
+            (TEST_M_COST, TEST_T_COST, ARGON2_P_COST_MAX + 1),
+        ] {
+            let mut encrypted = encrypt_w_costs(
+                b"rnote file content",
+                "secret",
+                TEST_M_COST,
+                TEST_T_COST,
+                TEST_P_COST,
+            )
+            .unwrap();
+            let costs_start = MAGIC.len() + 1;
+            for (i, cost) in [m_cost, t_cost, p_cost].into_iter().enumerate() {
+                encrypted[costs_start + i * 4..costs_start + (i + 1) * 4]
+                    .copy_from_slice(&cost.to_le_bytes());
+            }
+            assert!(matches!(
+                decrypt(&encrypted, "secret"),
+                Err(DecryptError::Invalid(_))
+            ));
+        }
+    }
+}
@@ -6,6 +6,7 @@
 //! Then [TryFrom] can be implemented to allow conversions and chaining from older to newer versions.
 
 // Modules
+pub mod encryption;
 pub(crate) mod maj0min13;
 pub(crate) mod maj0min5patch8;
 pub(crate) mod maj0min5patch9;
@@ -20,15 +21,172 @@ use self::maj0min9::RnoteFileMaj0Min9;
 use self::maj0min13::RnoteFileMaj0Min13;
 
 use super::{FileFormatLoader, FileFormatSaver};
+use crate::engine::EngineSnapshot;
 use anyhow::Context;
+use rnote_compose::penpath;
 use serde::{Deserialize, Serialize};
-use std::io::{Read, Write};
+use std::io::Read;
+use tracing::warn;
 
-/// Compress bytes with gzip.
-fn compress_to_gzip(to_compress: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
-    let mut encoder = flate2::write::GzEncoder::new(Vec::<u8>::new(), flate2::Compression::new(5));
-    encoder.write_all(to_compress)?;
-    Ok(encoder.finish()?)
+/// The compression method of `.rnote` files.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename = "compression_method")]
+pub enum CompressionMethod {
+    /// Gzip, compatible with all versions.
+    #[serde(rename = "gzip")]
+    Gzip = 0,
+    /// Zstandard, faster and with a better compression ratio, but can't be opened by older versions.
+    #[serde(rename = "zstd")]
+    Zstd,
+}
+
+impl Default for CompressionMethod {
+    fn default() -> Self {
+        Self::Gzip
+    }
+}
+
+impl TryFrom<u32> for CompressionMethod {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!(
+                "CompressionMethod try_from::<u32>() for value {} failed",
+                value
+            )
+        })
+    }
+}
+
+impl CompressionMethod {
+    /// The magic bytes at the start of zstd compressed data.
+    const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+    /// The range of the supported compression levels.
+    pub fn level_range(self) -> std::ops::RangeInclusive<i32> {
+        match self {
+            Self::Gzip => 0..=9,
+            Self::Zstd => 1..=22,
+        }
+    }
+
+    /// Detect the compression method from the magic bytes, falling back to gzip.
+    fn detect(compressed: &[u8]) -> Self {
+        if compressed.starts_with(&Self::ZSTD_MAGIC) {
+            Self::Zstd
+        } else {
+            Self::Gzip
+        }
+    }
+}
+
+/// Compression preferences for saving `.rnote` files.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename = "compression_prefs")]
+pub struct CompressionPrefs {
+    /// The compression method.
+    #[serde(rename = "method")]
+    pub method: CompressionMethod,
+    /// The gzip compression level.
+    #[serde(rename = "gzip_level")]
+    pub gzip_level: i32,
+    /// The zstd compression level.
+    #[serde(rename = "zstd_level")]
+    pub zstd_level: i32,
+    /// Store the pen paths of the strokes in a compact binary encoding.
+    ///
+    /// Smaller and faster to save and load for ink-heavy documents, but can't be opened by older versions.
+    #[serde(rename = "binary_pen_paths")]
+    pub binary_pen_paths: bool,
+}
+
+impl Default for CompressionPrefs {
+    fn default() -> Self {
+        Self {
+            method: CompressionMethod::default(),
+            gzip_level: 5,
+            zstd_level: 9,
+            binary_pen_paths: false,
+        }
+    }
+}
+
+impl CompressionPrefs {
+    /// The compression level of the selected method, clamped to its supported range.
+    pub fn level(&self) -> i32 {
+        let range = self.method.level_range();
+        match self.method {
+            CompressionMethod::Gzip => self.gzip_level,
+            CompressionMethod::Zstd => self.zstd_level,
+        }
+        .clamp(*range.start(), *range.end())
+    }
+
+    /// Set the compression level of the selected method.
+    pub fn set_level(&mut self, level: i32) {
+        let range = self.method.level_range();
+        let level = level.clamp(*range.start(), *range.end());
+        match self.method {
+            CompressionMethod::Gzip => self.gzip_level = level,
+            CompressionMethod::Zstd => self.zstd_level = level,
+        }
+    }
+}
+
+/// Serialize the value as JSON and compress it while it is being serialized,
+/// without building the entire uncompressed data in memory.
+///
+/// The pen paths are written in their binary encoding when enabled in the preferences.
+fn serialize_compressed<T>(value: &T, compression: CompressionPrefs) -> anyhow::Result<Vec<u8>>
+where
+    T: Serialize,
+{
+    if compression.binary_pen_paths {
+        penpath::with_binary_encoding(|| serialize_compressed_json(value, compression))
+    } else {
+        serialize_compressed_json(value, compression)
+    }
+}
+
+fn serialize_compressed_json<T>(value: &T, compression: CompressionPrefs) -> anyhow::Result<Vec<u8>>
+where
+    T: Serialize,
+{
+    match compression.method {
+        CompressionMethod::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(
+                Vec::<u8>::new(),
+                flate2::Compression::new(compression.level() as u32),
+            );
+            serde_json::to_writer(&mut encoder, value)?;
+            Ok(encoder.finish()?)
+        }
+        CompressionMethod::Zstd => {
+            let mut encoder = zstd::Encoder::new(Vec::<u8>::new(), compression.level())?;
+            serde_json::to_writer(&mut encoder, value)?;
+            Ok(encoder.finish()?)
+        }
+    }
+}
+
+/// Decompress the bytes, detecting the used compression method.
+fn decompress(compressed: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+    match CompressionMethod::detect(compressed) {
+        CompressionMethod::Gzip => decompress_from_gzip(compressed),
+        CompressionMethod::Zstd => Ok(zstd::decode_all(compressed)?),
+    }
 }
 
 /// Decompress from gzip.
@@ -72,6 +230,61 @@ struct RnotefileWrapper {
     data: ijson::IValue,
 }
 
+/// The rnote file wrapper, borrowing the data for serialization.
+#[derive(Debug, Serialize)]
+#[serde(rename = "rnotefile_wrapper")]
+struct RnotefileWrapperRef<'a, T: Serialize> {
+    #[serde(rename = "version")]
+    version: semver::Version,
+    #[serde(rename = "data")]
+    data: &'a T,
+}
+
+/// The rnote file in the newest format version, borrowing the engine snapshot for serialization.
+///
+/// Serializes to the same data as [RnoteFile].
+#[derive(Debug, Serialize)]
+#[serde(rename = "rnotefile")]
+struct RnoteFileRef<'a> {
+    #[serde(rename = "engine_snapshot")]
+    engine_snapshot: &'a EngineSnapshot,
+}
+
+/// The header of a `.rnote` file, holding the information that is available without deserializing the data.
+#[derive(Debug, Clone)]
+pub struct RnoteFileHeader {
+    /// The version the file was saved with.
+    pub version: semver::Version,
+    /// The compression method of the file.
+    pub compression: CompressionMethod,
+}
+
+impl RnoteFileHeader {
+    /// Reads the header from the bytes of an unencrypted `.rnote` file.
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        #[derive(Deserialize)]
+        struct VersionOnly {
+            #[serde(rename = "version")]
+            version: semver::Version,
+        }
+
+        if encryption::is_encrypted(bytes) {
+            return Err(anyhow::anyhow!(
+                "the file is encrypted and needs to be decrypted before reading the header."
+            ));
+        }
+        let compression = CompressionMethod::detect(bytes);
+        let version_only = serde_json::from_slice::<VersionOnly>(
+            &decompress(bytes).context("decompressing bytes failed.")?,
+        )
+        .context("deserializing version from bytes failed.")?;
+        Ok(Self {
+            version: version_only.version,
+            compression,
+        })
+    }
+}
+
 /// The Rnote file in the newest format version.
 ///
 /// This struct exists to allow for upgrading older versions before loading the file in.
@@ -79,17 +292,66 @@ pub type RnoteFile = RnoteFileMaj0Min13;
 
 impl RnoteFile {
     pub const SEMVER: &'static str = crate::utils::crate_version();
+
+    /// Save as bytes, compressed with the given compression preferences.
+    pub fn save_as_bytes_w_compression(
+        &self,
+        compression: CompressionPrefs,
+    ) -> anyhow::Result<Vec<u8>> {
+        let wrapper = RnotefileWrapperRef {
+            version: semver::Version::parse(Self::SEMVER).unwrap(),
+            data: self,
+        };
+        serialize_compressed(&wrapper, compression)
+            .context("Serializing and compressing RnoteFileWrapper failed.")
+    }
+
+    /// Save the engine snapshot as the bytes of a `.rnote` file, compressed with the given compression preferences.
+    ///
+    /// The snapshot is serialized straight into the compressor, without converting it into a [RnoteFile] first.
+    pub fn save_engine_snapshot_as_bytes(
+        engine_snapshot: &EngineSnapshot,
+        compression: CompressionPrefs,
+    ) -> anyhow::Result<Vec<u8>> {
+        let wrapper = RnotefileWrapperRef {
+            version: semver::Version::parse(Self::SEMVER).unwrap(),
+            data: &RnoteFileRef { engine_snapshot },
+        };
+        serialize_compressed(&wrapper, compression)
+            .context("Serializing and compressing engine snapshot failed.")
+    }
 }
 
 impl FileFormatLoader for RnoteFile {
     fn load_from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        Self::load_from_bytes_w_version(bytes).map(|(rnote_file, _)| rnote_file)
+    }
+}
+
+impl RnoteFile {
+    /// Load from bytes, additionally returning the version the file was saved with.
+    pub fn load_from_bytes_w_version(bytes: &[u8]) -> anyhow::Result<(Self, semver::Version)> {
+        if encryption::is_encrypted(bytes) {
+            return Err(anyhow::anyhow!(
+                "the file is encrypted and needs to be decrypted before loading."
+            ));
+        }
         let wrapper = serde_json::from_slice::<RnotefileWrapper>(
-            &decompress_from_gzip(bytes).context("decompressing bytes failed.")?,
+            &decompress(bytes).context("decompressing bytes failed.")?,
         )
         .context("deserializing RnotefileWrapper from bytes failed.")?;
 
+        if wrapper.version > semver::Version::parse(Self::SEMVER).unwrap() {
+            // Strokes of unknown types or with unknown fields are loaded as opaque strokes and written back unchanged
+            // when saving, unknown fields of the document are dropped.
+            warn!(
+                "Loading rnote file saved with newer version {}, content unsupported by this version is only preserved partially.",
+                wrapper.version
+            );
+        }
+
         // Conversions for older file format versions happen here
-        if semver::VersionReq::parse(">=0.13.0")
+        let rnote_file = if semver::VersionReq::parse(">=0.13.0")
             .unwrap()
             .matches(&wrapper.version)
         {
@@ -138,21 +400,13 @@ impl FileFormatLoader for RnoteFile {
                 "failed to load rnote file from bytes, unsupported version: {}.",
                 wrapper.version
             ))
-        }
+        }?;
+        Ok((rnote_file, wrapper.version))
     }
 }
 
 impl FileFormatSaver for RnoteFile {
     fn save_as_bytes(&self, _file_name: &str) -> anyhow::Result<Vec<u8>> {
-        let wrapper = RnotefileWrapper {
-            version: semver::Version::parse(Self::SEMVER).unwrap(),
-            data: ijson::to_value(self).context("converting RnoteFile to JSON value failed.")?,
-        };
-        let compressed = compress_to_gzip(
-            &serde_json::to_vec(&wrapper).context("Serializing RnoteFileWrapper failed.")?,
-        )
-        .context("compressing bytes failed.")?;
-
-        Ok(compressed)
+        self.save_as_bytes_w_compression(CompressionPrefs::default())
     }
 }
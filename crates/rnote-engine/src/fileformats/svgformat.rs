@@ -0,0 +1,374 @@
+//! Converting Svg content into native strokes, so that it stays editable after importing.
+//!
+//! Stroked paths become brush strokes and filled paths become filled polygon shapes. Text is converted to its glyph
+//! outlines. Embedded images are not converted.
+
+// Imports
+use super::usvgpaths::{color_from_usvg, penpaths_from_path_data, smooth_options_from_stroke};
+use crate::strokes::resize::{ImageSizeOption, calculate_resize_ratio};
+use crate::strokes::{BrushStroke, ShapeStroke, Stroke};
+use rnote_compose::shapes::{Polygon, Shape};
+use rnote_compose::style::smooth::SmoothOptions;
+use rnote_compose::{Color, Style};
+use std::sync::Arc;
+use tracing::debug;
+use usvg::tiny_skia_path::{self, PathSegment};
+
+/// The tolerance when flattening the curves of filled paths, in document coordinates.
+const FLATTEN_TOLERANCE: f64 = 0.1;
+
+/// Converts the Svg into strokes, placed with their top left corner at `pos`.
+pub fn strokes_from_svg(
+    svg_data: &str,
+    pos: na::Vector2<f64>,
+    size_option: ImageSizeOption,
+) -> anyhow::Result<Vec<Stroke>> {
+    let tree = usvg::Tree::from_str(
+        svg_data,
+        &usvg::Options {
+            fontdb: Arc::clone(&crate::svg::USVG_FONTDB),
+            ..Default::default()
+        },
+    )?;
+    let intrinsic_size = na::vector![
+        f64::from(tree.size().width()),
+        f64::from(tree.size().height())
+    ];
+    let scale = match size_option {
+        ImageSizeOption::RespectOriginalSize => na::vector![1.0, 1.0],
+        ImageSizeOption::ImposeSize(size) => size.component_div(&intrinsic_size),
+        ImageSizeOption::ResizeImage(resize) => {
+            let ratio = calculate_resize_ratio(resize, intrinsic_size, pos);
+            na::vector![ratio, ratio]
+        }
+    };
+
+    let mut strokes = Vec::new();
+    collect_strokes(tree.root(), 1.0, pos, scale, &mut strokes);
+    Ok(strokes)
+}
+
+fn collect_strokes(
+    group: &usvg::Group,
+    opacity: f64,
+    offset: na::Vector2<f64>,
+    scale: na::Vector2<f64>,
+    strokes: &mut Vec<Stroke>,
+) {
+    let opacity = opacity * f64::from(group.opacity().get());
+    for node in group.children() {
+        match node {
+            usvg::Node::Group(group) => collect_strokes(group, opacity, offset, scale, strokes),
+            usvg::Node::Text(text) => {
+                collect_strokes(text.flattened(), opacity, offset, scale, strokes)
+            }
+            usvg::Node::Path(path) if path.is_visible() => {
+                let transform = path.abs_transform();
+                let Some(data) = path.data().clone().transform(transform) else {
+                    continue;
+                };
+                // The fill is added first, so that it is rendered below the stroke
+                if let Some(fill) = path.fill()
+                    && let Some(color) =
+                        paint_color(fill.paint(), opacity * f64::from(fill.opacity().get()))
+                    && let Some(polygon) = polygon_from_path_data(&data, fill.rule(), offset, scale)
+                {
+                    let smooth_options = SmoothOptions {
+                        stroke_width: 0.0,
+                        stroke_color: Some(Color::TRANSPARENT),
+                        fill_color: Some(color),
+                        ..Default::default()
+                    };
+                    strokes.push(Stroke::ShapeStroke(ShapeStroke::new(
+                        Shape::Polygon(polygon),
+                        Style::Smooth(smooth_options),
+                    )));
+                }
+                if let Some(stroke) = path.stroke()
+                    && let Some(smooth_options) = smooth_options_from_stroke(
+                        stroke,
+                        opacity * f64::from(stroke.opacity().get()),
+                        transform,
+                        (scale[0] + scale[1]) * 0.5,
+                    )
+                {
+                    strokes.extend(
+                        penpaths_from_path_data(&data, offset, scale)
+                            .into_iter()
+                            .map(|penpath| {
+                                Stroke::BrushStroke(BrushStroke::from_penpath(
+                                    penpath,
+                                    Style::Smooth(smooth_options.clone()),
+                                ))
+                            }),
+                    );
+                }
+            }
+            usvg::Node::Image(image) => {
+                debug!(
+                    "Skipping image with id '{}' while converting Svg into strokes.",
+                    image.id()
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The color of the paint. Gradients are approximated by the average of their stops, patterns are not supported.
+fn paint_color(paint: &usvg::Paint, opacity: f64) -> Option<Color> {
+    let stops = match paint {
+        usvg::Paint::Color(color) => return Some(color_from_usvg(*color, opacity)),
+        usvg::Paint::LinearGradient(gradient) => gradient.stops(),
+        usvg::Paint::RadialGradient(gradient) => gradient.stops(),
+        usvg::Paint::Pattern(_) => return None,
+    };
+    if stops.is_empty() {
+        return None;
+    }
+    let n = stops.len() as f64;
+    let (r, g, b, a) = stops.iter().fold((0.0, 0.0, 0.0, 0.0), |acc, stop| {
+        (
+            acc.0 + f64::from(stop.color().red) / 255.0,
+            acc.1 + f64::from(stop.color().green) / 255.0,
+            acc.2 + f64::from(stop.color().blue) / 255.0,
+            acc.3 + f64::from(stop.opacity().get()),
+        )
+    });
+    Some(Color::new(r / n, g / n, b / n, a / n * opacity))
+}
+
+/// Flattens the path data into a single polygon.
+///
+/// The sub paths are closed and chained together, returning to the start of the first sub path after each one. The
+/// connecting lines are traversed in both directions and cancel out, so the polygon is filled like the path with the
+/// nonzero rule. For the evenodd rule the sub paths are reoriented by how deeply they are nested, so that holes stay
+/// holes when the polygon is filled with the nonzero rule. Self-intersecting sub paths are not reoriented.
+fn polygon_from_path_data(
+    data: &tiny_skia_path::Path,
+    fill_rule: usvg::FillRule,
+    offset: na::Vector2<f64>,
+    scale: na::Vector2<f64>,
+) -> Option<Polygon> {
+    let point = |p: tiny_skia_path::Point| {
+        kurbo::Point::new(
+            offset[0] + f64::from(p.x) * scale[0],
+            offset[1] + f64::from(p.y) * scale[1],
+        )
+    };
+    let mut bez_path = kurbo::BezPath::new();
+    for segment in data.segments() {
+        match segment {
+            PathSegment::MoveTo(p) => bez_path.move_to(point(p)),
+            PathSegment::LineTo(p) => bez_path.line_to(point(p)),
+            PathSegment::QuadTo(cp, p) => bez_path.quad_to(point(cp), point(p)),
+            PathSegment::CubicTo(cp1, cp2, p) => {
+                bez_path.curve_to(point(cp1), point(cp2), point(p))
+            }
+            PathSegment::Close => bez_path.close_path(),
+        }
+    }
+
+    let mut subpaths: Vec<Vec<na::Vector2<f64>>> = Vec::new();
+    kurbo::flatten(&bez_path, FLATTEN_TOLERANCE, |el| match el {
+        kurbo::PathEl::MoveTo(p) => subpaths.push(vec![na::vector![p.x, p.y]]),
+        kurbo::PathEl::LineTo(p) => {
+            if let Some(subpath) = subpaths.last_mut() {
+                subpath.push(na::vector![p.x, p.y]);
+            }
+        }
+        _ => {}
+    });
+    subpaths.retain(|subpath| subpath.len() > 1);
+    if fill_rule == usvg::FillRule::EvenOdd {
+        orient_for_even_odd(&mut subpaths);
+    }
+
+    let first_start = *subpaths.first()?.first()?;
+    let mut points: Vec<na::Vector2<f64>> = Vec::new();
+    for subpath in subpaths.iter() {
+        // every sub path is closed, then returns to the start of the first sub path
+        for &p in subpath.iter().chain([&subpath[0], &first_start]) {
+            if points.last() != Some(&p) {
+                points.push(p);
+            }
+        }
+    }
+
+    let mut points = points.into_iter();
+    let start = points.next()?;
+    let path = points.collect::<Vec<na::Vector2<f64>>>();
+    if path.len() < 2 {
+        return None;
+    }
+    Some(Polygon { start, path })
+}
+
+/// Orients the closed sub paths so that filling them with the nonzero rule matches filling with the evenodd rule.
+///
+/// Sub paths nested in an even number of others are oriented counterclockwise, the others clockwise. The winding
+/// numbers then alternate between one and zero from the outside in.
+fn orient_for_even_odd(subpaths: &mut [Vec<na::Vector2<f64>>]) {
+    let depths = subpaths
+        .iter()
+        .enumerate()
+        .map(|(i, subpath)| {
+            subpaths
+                .iter()
+                .enumerate()
+                .filter(|(j, other)| *j != i && polygon_contains(other, subpath[0]))
+                .count()
+        })
+        .collect::<Vec<usize>>();
+    for (subpath, depth) in subpaths.iter_mut().zip(depths) {
+        if (signed_area(subpath) > 0.0) != (depth % 2 == 0) {
+            subpath.reverse();
+        }
+    }
+}
+
+/// The signed area of the closed polygon, positive when it is oriented counterclockwise in a y-up coordinate system.
+fn signed_area(points: &[na::Vector2<f64>]) -> f64 {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a[0] * b[1] - b[0] * a[1])
+        .sum::<f64>()
+        * 0.5
+}
+
+/// Whether the point is inside the closed polygon, with the evenodd rule.
+fn polygon_contains(points: &[na::Vector2<f64>], p: na::Vector2<f64>) -> bool {
+    let mut inside = false;
+    for (a, b) in points.iter().zip(points.iter().cycle().skip(1)) {
+        if (a[1] > p[1]) != (b[1] > p[1])
+            && p[0] < a[0] + (p[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0])
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    /// The winding number of the polygon around the point.
+    fn winding(polygon: &Polygon, x: f64, y: f64) -> i32 {
+        let mut bez_path = kurbo::BezPath::new();
+        bez_path.move_to((polygon.start[0], polygon.start[1]));
+        for p in polygon.path.iter() {
+            bez_path.line_to((p[0], p[1]));
+        }
+        bez_path.close_path();
+        kurbo::Shape::winding(&bez_path, kurbo::Point::new(x, y))
+    }
+
+    fn filled_polygon(svg_data: &str) -> Polygon {
+        let strokes = strokes_from_svg(
+            svg_data,
+            na::Vector2::zeros(),
+            ImageSizeOption::RespectOriginalSize,
+        )
+        .unwrap();
+        match strokes.as_slice() {
+            [Stroke::ShapeStroke(shapestroke)] => match &shapestroke.shape {
+                Shape::Polygon(polygon) => polygon.clone(),
+                _ => panic!("filled path was not converted into a polygon"),
+            },
+            _ => panic!("filled path was not converted into a single shape stroke"),
+        }
+    }
+
+    #[test]
+    fn stroked_and_filled_path() {
+        let svg_data = r##"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="100">
+            <rect x="10" y="10" width="20" height="10" fill="#ff0000" stroke="#0000ff" stroke-width="2"/>
+        </svg>"##;
+        let strokes = strokes_from_svg(
+            svg_data,
+            na::vector![100.0, 100.0],
+            ImageSizeOption::RespectOriginalSize,
+        )
+        .unwrap();
+        let [Stroke::ShapeStroke(fill), Stroke::BrushStroke(stroke)] = strokes.as_slice() else {
+            panic!("expected the fill followed by the stroke");
+        };
+
+        let Style::Smooth(fill_options) = &fill.style else {
+            panic!("fill is not a smooth style");
+        };
+        assert_eq!(
+            fill_options.fill_color,
+            Some(Color::new(1.0, 0.0, 0.0, 1.0))
+        );
+        let Shape::Polygon(polygon) = &fill.shape else {
+            panic!("fill is not a polygon");
+        };
+        assert_ne!(winding(polygon, 120.0, 115.0), 0);
+        assert_eq!(winding(polygon, 105.0, 115.0), 0);
+
+        let Style::Smooth(stroke_options) = &stroke.style else {
+            panic!("stroke is not a smooth style");
+        };
+        assert_eq!(
+            stroke_options.stroke_color,
+            Some(Color::new(0.0, 0.0, 1.0, 1.0))
+        );
+        assert_relative_eq!(stroke_options.stroke_width, 2.0);
+        assert_relative_eq!(stroke.path.start.pos, na::vector![110.0, 110.0]);
+    }
+
+    #[test]
+    fn fill_rules() {
+        // three nested squares, all drawn in the same direction
+        let path_data = "M0 0 H50 V50 H0 Z M10 10 H40 V40 H10 Z M20 20 H30 V30 H20 Z";
+        let svg = |fill_rule: &str| {
+            format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="50" height="50">
+                    <path d="{path_data}" fill="black" fill-rule="{fill_rule}"/>
+                </svg>"#
+            )
+        };
+
+        let nonzero = filled_polygon(&svg("nonzero"));
+        assert_ne!(winding(&nonzero, 5.0, 5.0), 0);
+        assert_ne!(winding(&nonzero, 15.0, 15.0), 0);
+        assert_ne!(winding(&nonzero, 25.0, 25.0), 0);
+
+        let evenodd = filled_polygon(&svg("evenodd"));
+        assert_ne!(winding(&evenodd, 5.0, 5.0), 0);
+        assert_eq!(winding(&evenodd, 15.0, 15.0), 0);
+        assert_ne!(winding(&evenodd, 25.0, 25.0), 0);
+        assert_eq!(winding(&evenodd, 60.0, 25.0), 0);
+    }
+
+    #[test]
+    fn imposed_size() {
+        let svg_data = r#"<svg xmlns="http://www.w3.org/2000/svg" width="50" height="50">
+            <rect width="50" height="50" fill="black"/>
+        </svg>"#;
+        let strokes = strokes_from_svg(
+            svg_data,
+            na::vector![10.0, 20.0],
+            ImageSizeOption::ImposeSize(na::vector![100.0, 200.0]),
+        )
+        .unwrap();
+        let [Stroke::ShapeStroke(fill)] = strokes.as_slice() else {
+            panic!("expected a single fill");
+        };
+        let Shape::Polygon(polygon) = &fill.shape else {
+            panic!("fill is not a polygon");
+        };
+        let (mins, maxs) = polygon
+            .path
+            .iter()
+            .fold((polygon.start, polygon.start), |(mins, maxs), p| {
+                (mins.inf(p), maxs.sup(p))
+            });
+        assert_relative_eq!(mins, na::vector![10.0, 20.0]);
+        assert_relative_eq!(maxs, na::vector![110.0, 220.0]);
+    }
+}
@@ -0,0 +1,163 @@
+/// Tabular text, parsed from comma or tab separated values.
+#[derive(Debug, Clone, Default)]
+pub struct TableText {
+    /// The rows, each containing the text of its cells.
+    ///
+    /// All rows have the same amount of cells.
+    pub rows: Vec<Vec<String>>,
+}
+
+impl TableText {
+    /// The maximum amount of cells that are accepted.
+    const MAX_CELLS: usize = 10_000;
+
+    /// Tries to parse the text as a table.
+    ///
+    /// Tab separated values are preferred over comma separated values. Returns `None` when the text does not look
+    /// tabular, which is when it has less than two rows or columns or the rows have a differing amount of cells.
+    pub fn from_text(text: &str) -> Option<Self> {
+        let text = text.trim_end_matches(['\r', '\n']);
+        if text.is_empty() {
+            return None;
+        }
+
+        [b'\t', b',']
+            .into_iter()
+            .filter(|delimiter| text.as_bytes().contains(delimiter))
+            .find_map(|delimiter| Self::parse_w_delimiter(text, delimiter as char))
+    }
+
+    /// The amount of columns.
+    pub fn columns_amount(&self) -> usize {
+        self.rows.first().map(|row| row.len()).unwrap_or(0)
+    }
+
+    fn parse_w_delimiter(text: &str, delimiter: char) -> Option<Self> {
+        let mut rows: Vec<Vec<String>> = vec![];
+        let mut row: Vec<String> = vec![];
+        let mut cell = String::new();
+        let mut in_quotes = false;
+        let mut cell_start = true;
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if in_quotes {
+                if c == '"' {
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        cell.push('"');
+                    } else {
+                        in_quotes = false;
+                    }
+                } else {
+                    cell.push(c);
+                }
+                continue;
+            }
+            match c {
+                '"' if cell_start => {
+                    in_quotes = true;
+                    cell_start = false;
+                }
+                '\r' if chars.peek() == Some(&'\n') => {}
+                '\n' => {
+                    row.push(std::mem::take(&mut cell).trim().to_string());
+                    rows.push(std::mem::take(&mut row));
+                    cell_start = true;
+                }
+                c if c == delimiter => {
+                    row.push(std::mem::take(&mut cell).trim().to_string());
+                    cell_start = true;
+                }
+                ' ' if cell_start => {}
+                c => {
+                    cell.push(c);
+                    cell_start = false;
+                }
+            }
+        }
+        if in_quotes {
+            return None;
+        }
+        row.push(cell.trim().to_string());
+        rows.push(row);
+
+        let columns_amount = rows[0].len();
+        if rows.len() < 2
+            || columns_amount < 2
+            || rows.iter().any(|row| row.len() != columns_amount)
+            || rows.len() * columns_amount > Self::MAX_CELLS
+        {
+            return None;
+        }
+        Some(Self { rows })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(text: &str) -> Vec<Vec<String>> {
+        TableText::from_text(text)
+            .expect("not parsed as table")
+            .rows
+    }
+
+    #[test]
+    fn quoted_cells() {
+        assert_eq!(
+            rows("name,quote\n\"Doe, Jane\",\"She said \"\"hi\"\"\"\n"),
+            vec![
+                vec!["name".to_string(), "quote".to_string()],
+                vec!["Doe, Jane".to_string(), "She said \"hi\"".to_string()],
+            ]
+        );
+        // A tab separated cell can contain commas without quotes
+        assert_eq!(
+            rows("a\tb, c\nd\te"),
+            vec![
+                vec!["a".to_string(), "b, c".to_string()],
+                vec!["d".to_string(), "e".to_string()],
+            ]
+        );
+        // An unterminated quote
+        assert!(TableText::from_text("a,\"b\nc,d").is_none());
+    }
+
+    #[test]
+    fn line_endings() {
+        let expected = vec![
+            vec!["1".to_string(), "2".to_string()],
+            vec!["3".to_string(), "4".to_string()],
+        ];
+        assert_eq!(rows("1,2\r\n3,4"), expected);
+        assert_eq!(rows("1,2\r\n3,4\r\n"), expected);
+        assert_eq!(rows("1,2\n3,4\n"), expected);
+        assert_eq!(rows("1,2\n3,4\n\n"), expected);
+    }
+
+    #[test]
+    fn ragged_rows() {
+        assert!(TableText::from_text("a,b,c\nd,e\nf,g,h").is_none());
+        assert!(TableText::from_text("a\tb\nc").is_none());
+        // Empty cells still count
+        let table = TableText::from_text("a,,c\n,e,").unwrap();
+        assert_eq!(table.columns_amount(), 3);
+        assert_eq!(table.rows[1], vec!["", "e", ""]);
+    }
+
+    #[test]
+    fn prose() {
+        assert!(TableText::from_text("").is_none());
+        assert!(TableText::from_text("Hello, world").is_none());
+        assert!(
+            TableText::from_text(
+                "First of all, thanks for the notes.\nThey were helpful, clear, and short.\nSee you tomorrow."
+            )
+            .is_none()
+        );
+        // A single column
+        assert!(TableText::from_text("a\nb\nc").is_none());
+    }
+}
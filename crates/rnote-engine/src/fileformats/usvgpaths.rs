@@ -0,0 +1,106 @@
+//! Conversions of usvg paths and their styles into native pen paths and style options.
+//!
+//! Shared by the Svg import and the handwriting import of Pdfs.
+
+// Imports
+use rnote_compose::penpath::{Element, Segment};
+use rnote_compose::style::PressureCurve;
+use rnote_compose::style::smooth::{LineCap, SmoothOptions};
+use rnote_compose::{Color, PenPath};
+use usvg::tiny_skia_path::{self, PathSegment};
+
+/// The smooth style options of a stroked path, `None` if the stroke is not painted with a plain color.
+///
+/// `opacity` is the resolved opacity of the stroke, `scale` the scale applied in addition to the transform.
+pub(crate) fn smooth_options_from_stroke(
+    stroke: &usvg::Stroke,
+    opacity: f64,
+    transform: tiny_skia_path::Transform,
+    scale: f64,
+) -> Option<SmoothOptions> {
+    let usvg::Paint::Color(color) = stroke.paint() else {
+        return None;
+    };
+    // non-uniform scaling can't be represented by the stroke width, so the average is used
+    let transform_scale = f64::from(
+        (transform.sx * transform.sy - transform.kx * transform.ky)
+            .abs()
+            .sqrt(),
+    );
+
+    let mut smooth_options = SmoothOptions {
+        stroke_width: f64::from(stroke.width().get()) * transform_scale * scale,
+        stroke_color: Some(color_from_usvg(*color, opacity)),
+        pressure_curve: PressureCurve::Const,
+        ..Default::default()
+    };
+    smooth_options.update_line_cap(match stroke.linecap() {
+        usvg::LineCap::Butt => LineCap::Straight,
+        usvg::LineCap::Round | usvg::LineCap::Square => LineCap::Rounded,
+    });
+    Some(smooth_options)
+}
+
+/// Converts the usvg color into a color with the given opacity.
+pub(crate) fn color_from_usvg(color: usvg::Color, opacity: f64) -> Color {
+    Color::new(
+        f64::from(color.red) / 255.0,
+        f64::from(color.green) / 255.0,
+        f64::from(color.blue) / 255.0,
+        opacity,
+    )
+}
+
+/// Converts every sub path of the path data into a pen path.
+pub(crate) fn penpaths_from_path_data(
+    data: &tiny_skia_path::Path,
+    offset: na::Vector2<f64>,
+    scale: na::Vector2<f64>,
+) -> Vec<PenPath> {
+    let element = |p: tiny_skia_path::Point| {
+        Element::new(
+            offset + na::vector![f64::from(p.x) * scale[0], f64::from(p.y) * scale[1]],
+            1.0,
+        )
+    };
+    let mut penpaths = Vec::new();
+    let mut current: Option<PenPath> = None;
+    for segment in data.segments() {
+        match segment {
+            PathSegment::MoveTo(p) => {
+                penpaths.extend(current.take());
+                current = Some(PenPath::new(element(p)));
+            }
+            PathSegment::LineTo(p) => {
+                if let Some(penpath) = current.as_mut() {
+                    penpath.segments.push(Segment::LineTo { end: element(p) });
+                }
+            }
+            PathSegment::QuadTo(cp, p) => {
+                if let Some(penpath) = current.as_mut() {
+                    penpath.segments.push(Segment::QuadBezTo {
+                        cp: element(cp).pos,
+                        end: element(p),
+                    });
+                }
+            }
+            PathSegment::CubicTo(cp1, cp2, p) => {
+                if let Some(penpath) = current.as_mut() {
+                    penpath.segments.push(Segment::CubBezTo {
+                        cp1: element(cp1).pos,
+                        cp2: element(cp2).pos,
+                        end: element(p),
+                    });
+                }
+            }
+            PathSegment::Close => {
+                if let Some(penpath) = current.as_mut() {
+                    let start = penpath.start;
+                    penpath.segments.push(Segment::LineTo { end: start });
+                }
+            }
+        }
+    }
+    penpaths.extend(current);
+    penpaths
+}
@@ -1,5 +1,6 @@
 // Imports
 use crate::Drawable;
+use crate::engine::rendering::{ColorDepth, RenderQuality};
 use anyhow::Context;
 use core::fmt::Debug;
 use image::ImageReader;
@@ -9,23 +10,29 @@ use rnote_compose::ext::AabbExt;
 use rnote_compose::shapes::{Rectangle, Shapeable};
 use rnote_compose::transform::Transformable;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::io::{self, Cursor};
 
 /// Px unit (96 DPI ) to Point unit ( 72 DPI ) conversion factor.
 pub const PX_TO_POINT_CONV_FACTOR: f64 = 96.0 / 72.0;
 /// Point unit ( 72 DPI ) to Px unit (96 DPI ) conversion factor.
 pub const POINT_TO_PX_CONV_FACTOR: f64 = 72.0 / 96.0;
-/// The factor for which the rendering for the current viewport is extended by.
+/// The default factor for which the rendering for the current viewport is extended by, configurable as prerender margin.
 /// For example:: 1.0 means the viewport is extended by its own extents on all sides.
 ///
 /// Used when checking rendering for new zooms or a moved viewport.
 /// There is a trade off: a larger value will consume more memory, a smaller value will mean more stuttering on zooms and when moving the view.
 pub const VIEWPORT_EXTENTS_MARGIN_FACTOR: f64 = 0.4;
 
+/// `CAIRO_FORMAT_RGBA128F`, available since cairo 1.17.2 but not yet exposed by the bindings.
+const CAIRO_FORMAT_RGBA128F: cairo::Format = cairo::Format::__Unknown(7);
+
 #[non_exhaustive]
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ImageMemoryFormat {
     R8g8b8a8Premultiplied,
+    /// Native endian 32-bit floats per channel.
+    R32g32b32a32FloatPremultiplied,
 }
 
 impl Default for ImageMemoryFormat {
@@ -34,12 +41,51 @@ impl Default for ImageMemoryFormat {
     }
 }
 
+impl From<ColorDepth> for ImageMemoryFormat {
+    fn from(value: ColorDepth) -> Self {
+        match value {
+            ColorDepth::Standard => Self::R8g8b8a8Premultiplied,
+            ColorDepth::High => Self::R32g32b32a32FloatPremultiplied,
+        }
+    }
+}
+
+impl ImageMemoryFormat {
+    pub fn bytes_per_pixel(self) -> u32 {
+        match self {
+            Self::R8g8b8a8Premultiplied => 4,
+            Self::R32g32b32a32FloatPremultiplied => 16,
+        }
+    }
+
+    /// The format of the cairo image surfaces that images in this memory format are rendered to.
+    pub(crate) fn cairo_format(self) -> cairo::Format {
+        match self {
+            Self::R8g8b8a8Premultiplied => cairo::Format::ARgb32,
+            Self::R32g32b32a32FloatPremultiplied => CAIRO_FORMAT_RGBA128F,
+        }
+    }
+
+    /// Convert the data of a cairo image surface in the cairo format of this memory format.
+    pub(crate) fn from_cairo_surface_data(self, width: u32, height: u32, data: Vec<u8>) -> Vec<u8> {
+        match self {
+            // cairo renders to bgra8-premultiplied, but we convert it to rgba8-premultiplied
+            Self::R8g8b8a8Premultiplied => convert_image_bgra_to_rgba(width, height, data),
+            // cairo already renders to rgba in native endian floats
+            Self::R32g32b32a32FloatPremultiplied => data,
+        }
+    }
+}
+
 #[cfg(feature = "ui")]
 impl TryFrom<gtk4::gdk::MemoryFormat> for ImageMemoryFormat {
     type Error = anyhow::Error;
     fn try_from(value: gtk4::gdk::MemoryFormat) -> Result<Self, Self::Error> {
         match value {
             gtk4::gdk::MemoryFormat::R8g8b8a8Premultiplied => Ok(Self::R8g8b8a8Premultiplied),
+            gtk4::gdk::MemoryFormat::R32g32b32a32FloatPremultiplied => {
+                Ok(Self::R32g32b32a32FloatPremultiplied)
+            }
             _ => Err(anyhow::anyhow!(
                 "ImageMemoryFormat try_from() gdk::MemoryFormat failed, unsupported MemoryFormat `{:?}`",
                 value
@@ -55,6 +101,9 @@ impl From<ImageMemoryFormat> for gtk4::gdk::MemoryFormat {
             ImageMemoryFormat::R8g8b8a8Premultiplied => {
                 gtk4::gdk::MemoryFormat::R8g8b8a8Premultiplied
             }
+            ImageMemoryFormat::R32g32b32a32FloatPremultiplied => {
+                gtk4::gdk::MemoryFormat::R32g32b32a32FloatPremultiplied
+            }
         }
     }
 }
@@ -62,7 +111,9 @@ impl From<ImageMemoryFormat> for gtk4::gdk::MemoryFormat {
 impl From<ImageMemoryFormat> for piet::ImageFormat {
     fn from(value: ImageMemoryFormat) -> Self {
         match value {
-            ImageMemoryFormat::R8g8b8a8Premultiplied => piet::ImageFormat::RgbaPremul,
+            // float images are converted to rgba8-premultiplied when drawn
+            ImageMemoryFormat::R8g8b8a8Premultiplied
+            | ImageMemoryFormat::R32g32b32a32FloatPremultiplied => piet::ImageFormat::RgbaPremul,
         }
     }
 }
@@ -149,7 +200,7 @@ impl Drawable for Image {
             .make_image(
                 self.pixel_width as usize,
                 self.pixel_height as usize,
-                &self.data,
+                &self.rgba8_premultiplied_data(),
                 piet_image_format,
             )
             .map_err(|e| anyhow::anyhow!("{e:?}"))?;
@@ -184,7 +235,8 @@ impl Image {
 
         if self.pixel_width == 0
             || self.pixel_height == 0
-            || self.data.len() as u32 != 4 * self.pixel_width * self.pixel_height
+            || self.data.len() as u32
+                != self.memory_format.bytes_per_pixel() * self.pixel_width * self.pixel_height
         {
             Err(anyhow::anyhow!(
                 "Asserting image validity failed, invalid size or data."
@@ -222,18 +274,33 @@ impl Image {
     ) -> Result<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>, anyhow::Error> {
         self.assert_valid()?;
 
-        match self.memory_format {
-            ImageMemoryFormat::R8g8b8a8Premultiplied => image::RgbaImage::from_vec(
-                self.pixel_width,
-                self.pixel_height,
-                self.data.to_vec(),
+        image::RgbaImage::from_vec(
+            self.pixel_width,
+            self.pixel_height,
+            self.rgba8_premultiplied_data().into_owned(),
+        )
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Creating RgbaImage from data failed for image with memory-format {:?}.",
+                self.memory_format
             )
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "Creating RgbaImage from data failed for image with memory-format {:?}.",
-                    self.memory_format
-                )
-            }),
+        })
+    }
+
+    /// The image data in rgba8-premultiplied format, converted when the image has another memory format.
+    pub fn rgba8_premultiplied_data(&self) -> Cow<'_, [u8]> {
+        match self.memory_format {
+            ImageMemoryFormat::R8g8b8a8Premultiplied => Cow::Borrowed(&self.data),
+            ImageMemoryFormat::R32g32b32a32FloatPremultiplied => Cow::Owned(
+                self.data
+                    .chunks_exact(4)
+                    .map(|channel| {
+                        let value =
+                            f32::from_ne_bytes([channel[0], channel[1], channel[2], channel[3]]);
+                        (value.clamp(0.0, 1.0) * 255.0).round() as u8
+                    })
+                    .collect(),
+            ),
         }
     }
 
@@ -272,6 +339,38 @@ impl Image {
         Ok(bytes_buf.into_inner())
     }
 
+    /// Downscales the image data so that it fits into the given maximum dimension and amount of pixels.
+    ///
+    /// The target rect stays the same. Images that already fit are returned unchanged.
+    pub fn downscale_to_fit(self, max_dimension: u32, max_pixels: u64) -> anyhow::Result<Self> {
+        let width = f64::from(self.pixel_width);
+        let height = f64::from(self.pixel_height);
+        let scale = (f64::from(max_dimension) / width)
+            .min(f64::from(max_dimension) / height)
+            .min((max_pixels as f64 / (width * height)).sqrt());
+        if scale >= 1.0 {
+            return Ok(self);
+        }
+        let new_width = ((width * scale).floor() as u32).max(1);
+        let new_height = ((height * scale).floor() as u32).max(1);
+        let rect = self.rect;
+
+        let downscaled = image::imageops::resize(
+            &self.into_imgbuf()?,
+            new_width,
+            new_height,
+            image::imageops::FilterType::Triangle,
+        );
+
+        Ok(Self {
+            data: glib::Bytes::from_owned(downscaled.into_raw()),
+            rect,
+            pixel_width: new_width,
+            pixel_height: new_height,
+            memory_format: ImageMemoryFormat::R8g8b8a8Premultiplied,
+        })
+    }
+
     #[cfg(feature = "ui")]
     pub fn to_memtexture(&self) -> Result<gtk4::gdk::MemoryTexture, anyhow::Error> {
         self.assert_valid()?;
@@ -281,7 +380,7 @@ impl Image {
             self.pixel_height as i32,
             self.memory_format.into(),
             &self.data,
-            (self.pixel_width * 4) as usize,
+            (self.pixel_width * self.memory_format.bytes_per_pixel()) as usize,
         ))
     }
 
@@ -315,10 +414,13 @@ impl Image {
     }
 
     /// Generates an image with a provided closure that draws onto a [cairo::Context].
+    ///
+    /// The image is generated with the given render quality.
     pub fn gen_with_cairo<F>(
         draw_func: F,
         mut bounds: Aabb,
         image_scale: f64,
+        render_quality: RenderQuality,
     ) -> anyhow::Result<Self>
     where
         F: FnOnce(&cairo::Context) -> anyhow::Result<()>,
@@ -330,8 +432,9 @@ impl Image {
         let width_scaled = ((bounds.extents()[0]) * image_scale).round() as u32;
         let height_scaled = ((bounds.extents()[1]) * image_scale).round() as u32;
 
+        let memory_format = ImageMemoryFormat::from(render_quality.color_depth);
         let mut image_surface = cairo::ImageSurface::create(
-            cairo::Format::ARgb32,
+            memory_format.cairo_format(),
             width_scaled as i32,
             height_scaled as i32,
         )
@@ -345,6 +448,8 @@ impl Image {
 
         {
             let cairo_cx = cairo::Context::new(&image_surface)?;
+            cairo_cx.set_antialias(render_quality.antialias.into());
+            cairo_cx.set_tolerance(render_quality.flattening_tolerance());
             cairo_cx.scale(image_scale, image_scale);
             cairo_cx.translate(-bounds.mins[0], -bounds.mins[1]);
             // Apply the draw function
@@ -357,7 +462,7 @@ impl Image {
             .to_vec();
 
         Ok(Image {
-            data: glib::Bytes::from_owned(convert_image_bgra_to_rgba(
+            data: glib::Bytes::from_owned(memory_format.from_cairo_surface_data(
                 width_scaled,
                 height_scaled,
                 data,
@@ -365,13 +470,19 @@ impl Image {
             rect: Rectangle::from_p2d_aabb(bounds),
             pixel_width: width_scaled,
             pixel_height: height_scaled,
-            // cairo renders to bgra8-premultiplied, but we convert it to rgba8-premultiplied
-            memory_format: ImageMemoryFormat::R8g8b8a8Premultiplied,
+            memory_format,
         })
     }
 
     /// Generates an image with a provided closure that draws onto a [piet_cairo::CairoRenderContext].
-    pub fn gen_with_piet<F>(draw_func: F, bounds: Aabb, image_scale: f64) -> anyhow::Result<Self>
+    ///
+    /// The image is generated with the given render quality.
+    pub fn gen_with_piet<F>(
+        draw_func: F,
+        bounds: Aabb,
+        image_scale: f64,
+        render_quality: RenderQuality,
+    ) -> anyhow::Result<Self>
     where
         F: FnOnce(&mut piet_cairo::CairoRenderContext) -> anyhow::Result<()>,
     {
@@ -385,7 +496,7 @@ impl Image {
             Ok(())
         };
 
-        Self::gen_with_cairo(cairo_draw_fn, bounds, image_scale)
+        Self::gen_with_cairo(cairo_draw_fn, bounds, image_scale, render_quality)
     }
 }
 
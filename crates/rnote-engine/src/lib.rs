@@ -20,6 +20,7 @@ pub mod image;
 pub mod pens;
 pub mod selectioncollision;
 pub mod snap;
+pub mod storage;
 pub mod store;
 pub mod strokes;
 pub mod svg;
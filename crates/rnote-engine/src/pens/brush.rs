@@ -2,22 +2,30 @@
 use super::PenBehaviour;
 use super::PenStyle;
 use super::pensconfig::brushconfig::BrushStyle;
+use super::ruler::RulerEdge;
 use crate::engine::{EngineView, EngineViewMut};
 use crate::store::StrokeKey;
 use crate::strokes::BrushStroke;
+use crate::strokes::ShapeStroke;
+use crate::strokes::SprayStroke;
+use crate::strokes::StampStroke;
 use crate::strokes::Stroke;
-use crate::{DrawableOnDoc, WidgetFlags};
+use crate::{Drawable, DrawableOnDoc, WidgetFlags};
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use piet::RenderContext;
 use rnote_compose::Constraints;
+use rnote_compose::Style;
 use rnote_compose::builders::buildable::{Buildable, BuilderCreator, BuilderProgress};
 use rnote_compose::builders::{
-    PenPathBuilderType, PenPathCurvedBuilder, PenPathModeledBuilder, PenPathSimpleBuilder,
+    InputSmoothingConfig, PenPathBuilderType, PenPathCurvedBuilder, PenPathModeledBuilder,
+    PenPathSimpleBuilder,
 };
 use rnote_compose::eventresult::{EventPropagation, EventResult};
 use rnote_compose::penevent::{PenEvent, PenProgress};
-use rnote_compose::penpath::{Element, Segment};
-use std::time::Instant;
+use rnote_compose::penpath::{Element, PenPath, Segment};
+use rnote_compose::recognition;
+use rnote_compose::shapes::Shapeable;
+use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 enum BrushState {
@@ -25,18 +33,134 @@ enum BrushState {
     Drawing {
         path_builder: Box<dyn Buildable<Emit = Segment>>,
         current_stroke_key: StrokeKey,
+        /// Whether the stroke is drawn directly by the pen while in progress, deferring its image generation
+        /// until it is finished.
+        direct_draw: bool,
+        /// Coalesces the rendering of the emitted segments when the stroke is rendered through the store.
+        coalescer: LastSegmentsCoalescer,
+        /// The time the stroke was started, for the timestamps of the segments.
+        start_time: Instant,
+        /// Detects holding the pen still to straighten the stroke. Only present for marker strokes.
+        hold: Option<HoldToStraighten>,
     },
+    /// Spraying with the airbrush. Emissions are added at the configured rate for as long as the pen is down.
+    Spraying {
+        current_stroke_key: StrokeKey,
+        /// The last position and pressure of the pen.
+        element: Element,
+        /// The time of the last emission.
+        last_emission: Instant,
+    },
+    /// A stamp was placed, waiting for the pen to be lifted.
+    Stamping,
+}
+
+/// Detects when the pen is held still at the end of a marker stroke, which snaps the stroke to a straight line.
+///
+/// Once straightened, the end of the line follows the pen until it is lifted.
+#[derive(Debug, Clone, Copy)]
+struct HoldToStraighten {
+    /// The element where the pen came to rest and the time it did.
+    anchor: (Element, Instant),
+    /// Whether the stroke was already snapped to a straight line.
+    straightened: bool,
+}
+
+impl HoldToStraighten {
+    /// How long the pen needs to be held still.
+    const HOLD_DURATION: Duration = Duration::from_millis(300);
+    /// The distance in surface coordinates the pen may move while it is held still.
+    const MOVE_TOLERANCE: f64 = 4.0;
+
+    fn new(element: Element, now: Instant) -> Self {
+        Self {
+            anchor: (element, now),
+            straightened: false,
+        }
+    }
+
+    /// Restart the hold when the pen moved away from where it came to rest.
+    fn update(&mut self, element: Element, now: Instant, total_zoom: f64) {
+        if (element.pos - self.anchor.0.pos).magnitude() > Self::MOVE_TOLERANCE / total_zoom {
+            self.anchor = (element, now);
+        }
+    }
+
+    fn held_long_enough(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.anchor.1) >= Self::HOLD_DURATION
+    }
+}
+
+/// Coalesces the rendering of the last emitted segments of the in-progress stroke.
+///
+/// When segments are emitted faster than their images can be generated, they are accumulated
+/// and the combined range is rendered at once, instead of generating an image for every tiny batch.
+#[derive(Debug, Clone, Copy)]
+struct LastSegmentsCoalescer {
+    /// The amount of emitted segments that are not yet rendered.
+    pending: usize,
+    /// The time the last rendering of segments finished.
+    last_finished: Instant,
+    /// How long the last rendering of segments took.
+    last_duration: Duration,
+}
+
+impl LastSegmentsCoalescer {
+    /// The maximum amount of segments that are accumulated before they are rendered regardless.
+    const MAX_PENDING: usize = 64;
+
+    fn new(now: Instant) -> Self {
+        Self {
+            pending: 0,
+            last_finished: now,
+            last_duration: Duration::ZERO,
+        }
+    }
+
+    /// Add the newly emitted segments and render all pending ones, when the renderer has caught up
+    /// or `flush` is true.
+    fn push(
+        &mut self,
+        engine_view: &mut EngineViewMut,
+        key: StrokeKey,
+        n_segments: usize,
+        flush: bool,
+    ) {
+        self.pending += n_segments;
+        if self.pending == 0 {
+            return;
+        }
+        let now = Instant::now();
+        let caught_up = now.saturating_duration_since(self.last_finished) >= self.last_duration;
+        if !(flush || caught_up || self.pending >= Self::MAX_PENDING) {
+            return;
+        }
+
+        engine_view.store.append_rendering_last_segments(
+            engine_view.tasks_tx.clone(),
+            key,
+            self.pending,
+            engine_view.camera.viewport(),
+            engine_view.camera.image_scale(),
+        );
+        self.pending = 0;
+        self.last_finished = Instant::now();
+        self.last_duration = self.last_finished.saturating_duration_since(now);
+    }
 }
 
 #[derive(Debug)]
 pub struct Brush {
     state: BrushState,
+    /// The edge of the ruler the current stroke is snapped onto.
+    ruler_edge: Option<RulerEdge>,
 }
 
 impl Default for Brush {
     fn default() -> Self {
         Self {
             state: BrushState::Idle,
+            ruler_edge: None,
         }
     }
 }
@@ -58,6 +182,52 @@ impl PenBehaviour for Brush {
         WidgetFlags::default()
     }
 
+    fn handle_animation_frame(&mut self, engine_view: &mut EngineViewMut) {
+        if let BrushState::Spraying {
+            current_stroke_key,
+            element,
+            last_emission,
+        } = &mut self.state
+        {
+            // The pen does not emit events while it is held still, so the spraying continues on every frame
+            spray(
+                engine_view,
+                *current_stroke_key,
+                *element,
+                last_emission,
+                Instant::now(),
+            );
+            engine_view.animation.claim_frame();
+            return;
+        }
+        let BrushState::Drawing {
+            current_stroke_key,
+            start_time,
+            hold: Some(hold),
+            ..
+        } = &mut self.state
+        else {
+            return;
+        };
+        if hold.straightened {
+            return;
+        }
+
+        let now = Instant::now();
+        if hold.held_long_enough(now) {
+            straighten_stroke(
+                engine_view,
+                *current_stroke_key,
+                hold.anchor.0,
+                now.saturating_duration_since(*start_time).as_secs_f64(),
+            );
+            hold.straightened = true;
+        } else {
+            // The pen does not emit events while it is held still, so the hold is polled on every frame
+            engine_view.animation.claim_frame();
+        }
+    }
+
     fn handle_event(
         &mut self,
         event: PenEvent,
@@ -66,7 +236,115 @@ impl PenBehaviour for Brush {
     ) -> (EventResult<PenProgress>, WidgetFlags) {
         let mut widget_flags = WidgetFlags::default();
 
+        // Strokes that are started along an edge of the ruler are snapped onto it
+        if let BrushState::Idle = self.state {
+            self.ruler_edge = match (&event, engine_view.config.pens_config.brush_config.style) {
+                (
+                    PenEvent::Down { element, .. },
+                    BrushStyle::Marker | BrushStyle::Solid | BrushStyle::Textured,
+                ) => engine_view.ruler.edge_near(
+                    element.pos,
+                    engine_view
+                        .config
+                        .pens_config
+                        .brush_config
+                        .style_for_current_options()
+                        .stroke_width()
+                        * 0.5,
+                    engine_view.camera.total_zoom(),
+                ),
+                _ => None,
+            };
+        }
+        let event = match &self.ruler_edge {
+            Some(ruler_edge) => ruler_edge.snap_event(event),
+            None => event,
+        };
+
         let event_result = match (&mut self.state, event) {
+            (BrushState::Idle, PenEvent::Down { element, .. })
+                if engine_view.config.pens_config.brush_config.style == BrushStyle::Airbrush =>
+            {
+                if !element.filter_by_bounds(
+                    engine_view
+                        .document
+                        .bounds()
+                        .loosened(Self::INPUT_OVERSHOOT),
+                ) {
+                    engine_view
+                        .config
+                        .pens_config
+                        .brush_config
+                        .new_style_seeds();
+
+                    let mut spraystroke = SprayStroke::new(
+                        engine_view
+                            .config
+                            .pens_config
+                            .brush_config
+                            .airbrush_options
+                            .clone(),
+                    );
+                    spraystroke.emit(element);
+                    let current_stroke_key = engine_view.store.insert_new_stroke(
+                        Stroke::SprayStroke(spraystroke),
+                        Some(
+                            engine_view
+                                .config
+                                .pens_config
+                                .brush_config
+                                .layer_for_current_options(),
+                        ),
+                    );
+                    engine_view
+                        .store
+                        .set_rendering_direct_draw(current_stroke_key);
+                    engine_view.animation.claim_frame();
+
+                    self.state = BrushState::Spraying {
+                        current_stroke_key,
+                        element,
+                        last_emission: now,
+                    };
+
+                    EventResult {
+                        handled: true,
+                        propagate: EventPropagation::Stop,
+                        progress: PenProgress::InProgress,
+                    }
+                } else {
+                    EventResult {
+                        handled: false,
+                        propagate: EventPropagation::Proceed,
+                        progress: PenProgress::Idle,
+                    }
+                }
+            }
+            (BrushState::Idle, PenEvent::Down { element, .. })
+                if engine_view.config.pens_config.brush_config.style == BrushStyle::Stamp =>
+            {
+                if !element.filter_by_bounds(
+                    engine_view
+                        .document
+                        .bounds()
+                        .loosened(Self::INPUT_OVERSHOOT),
+                ) {
+                    widget_flags |= place_stamp(engine_view, element.pos);
+                    self.state = BrushState::Stamping;
+
+                    EventResult {
+                        handled: true,
+                        propagate: EventPropagation::Stop,
+                        progress: PenProgress::InProgress,
+                    }
+                } else {
+                    EventResult {
+                        handled: false,
+                        propagate: EventPropagation::Proceed,
+                        progress: PenProgress::Idle,
+                    }
+                }
+            }
             (BrushState::Idle, PenEvent::Down { element, .. }) => {
                 if !element.filter_by_bounds(
                     engine_view
@@ -86,15 +364,21 @@ impl PenBehaviour for Brush {
                         .brush_config
                         .new_style_seeds();
 
-                    let brushstroke = Stroke::BrushStroke(BrushStroke::new(
+                    let mut brushstroke = BrushStroke::new(
                         element,
                         engine_view
                             .config
                             .pens_config
                             .brush_config
                             .style_for_current_options(),
-                    ));
-                    let current_stroke_key = engine_view.store.insert_stroke(
+                    );
+                    brushstroke.blend_mode = engine_view
+                        .config
+                        .pens_config
+                        .brush_config
+                        .blend_mode_for_current_options();
+                    let brushstroke = Stroke::BrushStroke(brushstroke);
+                    let current_stroke_key = engine_view.store.insert_new_stroke(
                         brushstroke,
                         Some(
                             engine_view
@@ -105,19 +389,46 @@ impl PenBehaviour for Brush {
                         ),
                     );
 
-                    engine_view.store.regenerate_rendering_for_stroke(
-                        current_stroke_key,
-                        engine_view.camera.viewport(),
-                        engine_view.camera.image_scale(),
-                    );
+                    // The marker is rendered through the store, because it needs to be drawn underneath other strokes.
+                    let direct_draw =
+                        engine_view.config.pens_config.brush_config.style != BrushStyle::Marker;
+
+                    if direct_draw {
+                        engine_view
+                            .store
+                            .set_rendering_direct_draw(current_stroke_key);
+                    } else {
+                        engine_view.store.regenerate_rendering_for_stroke(
+                            current_stroke_key,
+                            engine_view.camera.viewport(),
+                            engine_view.camera.image_scale(),
+                        );
+                    }
+
+                    let hold = (engine_view.config.pens_config.brush_config.style
+                        == BrushStyle::Marker
+                        && engine_view
+                            .config
+                            .pens_config
+                            .brush_config
+                            .marker_hold_to_straighten)
+                        .then(|| HoldToStraighten::new(element, now));
+                    if hold.is_some() {
+                        engine_view.animation.claim_frame();
+                    }
 
                     self.state = BrushState::Drawing {
                         path_builder: new_builder(
                             engine_view.config.pens_config.brush_config.builder_type,
+                            engine_view.config.pens_config.brush_config.input_smoothing,
                             element,
                             now,
                         ),
                         current_stroke_key,
+                        direct_draw,
+                        coalescer: LastSegmentsCoalescer::new(now),
+                        start_time: now,
+                        hold,
                     };
 
                     EventResult {
@@ -138,22 +449,102 @@ impl PenBehaviour for Brush {
                 propagate: EventPropagation::Proceed,
                 progress: PenProgress::Idle,
             },
+            (
+                BrushState::Spraying {
+                    current_stroke_key,
+                    element,
+                    last_emission,
+                },
+                PenEvent::Down {
+                    element: new_element,
+                    ..
+                },
+            ) => {
+                *element = new_element;
+                if spray(
+                    engine_view,
+                    *current_stroke_key,
+                    *element,
+                    last_emission,
+                    now,
+                ) {
+                    widget_flags.store_modified = true;
+                }
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            (
+                BrushState::Spraying {
+                    current_stroke_key,
+                    last_emission,
+                    ..
+                },
+                pen_event @ (PenEvent::Up { .. } | PenEvent::Cancel),
+            ) => {
+                if let PenEvent::Up { element, .. } = pen_event {
+                    spray(
+                        engine_view,
+                        *current_stroke_key,
+                        element,
+                        last_emission,
+                        now,
+                    );
+                }
+
+                finish_stroke_rendering(engine_view, *current_stroke_key, true);
+                widget_flags |= engine_view
+                    .document
+                    .resize_autoexpand(engine_view.store, engine_view.camera);
+
+                self.state = BrushState::Idle;
+
+                widget_flags |= engine_view.store.record(Instant::now());
+                widget_flags.store_modified = true;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::Finished,
+                }
+            }
+            (BrushState::Spraying { .. }, _) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::InProgress,
+            },
+            (BrushState::Stamping, PenEvent::Down { .. }) => EventResult {
+                handled: true,
+                propagate: EventPropagation::Stop,
+                progress: PenProgress::InProgress,
+            },
+            (BrushState::Stamping, PenEvent::Up { .. } | PenEvent::Cancel) => {
+                self.state = BrushState::Idle;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::Finished,
+                }
+            }
+            (BrushState::Stamping, _) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::InProgress,
+            },
             (
                 BrushState::Drawing {
-                    current_stroke_key, ..
+                    current_stroke_key,
+                    direct_draw,
+                    ..
                 },
                 PenEvent::Cancel,
             ) => {
                 // Finish up the last stroke
-                engine_view
-                    .store
-                    .update_geometry_for_stroke(*current_stroke_key);
-                engine_view.store.regenerate_rendering_for_stroke_threaded(
-                    engine_view.tasks_tx.clone(),
-                    *current_stroke_key,
-                    engine_view.camera.viewport(),
-                    engine_view.camera.image_scale(),
-                );
+                finish_stroke_rendering(engine_view, *current_stroke_key, *direct_draw);
                 widget_flags |= engine_view
                     .document
                     .resize_autoexpand(engine_view.store, engine_view.camera);
@@ -173,9 +564,31 @@ impl PenBehaviour for Brush {
                 BrushState::Drawing {
                     path_builder,
                     current_stroke_key,
+                    direct_draw,
+                    coalescer,
+                    start_time,
+                    hold,
                 },
                 pen_event,
             ) => {
+                if let Some(hold) = hold
+                    && let PenEvent::Down { element, .. } = &pen_event
+                {
+                    if hold.straightened {
+                        straighten_stroke(
+                            engine_view,
+                            *current_stroke_key,
+                            *element,
+                            now.saturating_duration_since(*start_time).as_secs_f64(),
+                        );
+                        widget_flags.store_modified = true;
+                    } else {
+                        hold.update(*element, now, engine_view.camera.total_zoom());
+                    }
+                }
+                // The segments are discarded once the stroke is straightened, its end follows the pen instead
+                let straightened = hold.is_some_and(|hold| hold.straightened);
+
                 let builder_result =
                     path_builder.handle_event(pen_event, now, Constraints::default());
                 let handled = builder_result.handled;
@@ -187,6 +600,11 @@ impl PenBehaviour for Brush {
                             trigger_brush_sound(engine_view);
                         }
 
+                        // render the segments that are still pending once the renderer has caught up
+                        if !*direct_draw {
+                            coalescer.push(engine_view, *current_stroke_key, 0, false);
+                        }
+
                         PenProgress::InProgress
                     }
                     BuilderProgress::EmitContinue(segments) => {
@@ -194,57 +612,54 @@ impl PenBehaviour for Brush {
                             trigger_brush_sound(engine_view);
                         }
 
-                        let n_segments = segments.len();
+                        let n_segments = if straightened { 0 } else { segments.len() };
 
                         if n_segments != 0 {
                             if let Some(Stroke::BrushStroke(brushstroke)) =
                                 engine_view.store.get_stroke_mut(*current_stroke_key)
                             {
-                                brushstroke.extend_w_segments(segments);
+                                brushstroke.extend_w_timed_segments(
+                                    segments,
+                                    now.saturating_duration_since(*start_time).as_secs_f64(),
+                                );
                                 widget_flags.store_modified = true;
                             }
+                        }
 
-                            engine_view.store.append_rendering_last_segments(
-                                engine_view.tasks_tx.clone(),
-                                *current_stroke_key,
-                                n_segments,
-                                engine_view.camera.viewport(),
-                                engine_view.camera.image_scale(),
-                            );
+                        if !*direct_draw {
+                            coalescer.push(engine_view, *current_stroke_key, n_segments, false);
                         }
 
                         PenProgress::InProgress
                     }
                     BuilderProgress::Finished(segments) => {
-                        let n_segments = segments.len();
+                        let n_segments = if straightened { 0 } else { segments.len() };
 
                         if n_segments != 0 {
                             if let Some(Stroke::BrushStroke(brushstroke)) =
                                 engine_view.store.get_stroke_mut(*current_stroke_key)
                             {
-                                brushstroke.extend_w_segments(segments);
+                                brushstroke.extend_w_timed_segments(
+                                    segments,
+                                    now.saturating_duration_since(*start_time).as_secs_f64(),
+                                );
                                 widget_flags.store_modified = true;
                             }
+                        }
+
+                        let brush_config = &engine_view.config.pens_config.brush_config;
+                        let recognized = !straightened
+                            && brush_config.shape_recognition
+                            && matches!(brush_config.style, BrushStyle::Marker | BrushStyle::Solid)
+                            && recognize_shape(engine_view, *current_stroke_key);
 
-                            engine_view.store.append_rendering_last_segments(
-                                engine_view.tasks_tx.clone(),
-                                *current_stroke_key,
-                                n_segments,
-                                engine_view.camera.viewport(),
-                                engine_view.camera.image_scale(),
-                            );
+                        // A recognized shape is rendered entirely when the stroke is finished
+                        if !*direct_draw && !recognized {
+                            coalescer.push(engine_view, *current_stroke_key, n_segments, true);
                         }
 
                         // Finish up the last stroke
-                        engine_view
-                            .store
-                            .update_geometry_for_stroke(*current_stroke_key);
-                        engine_view.store.regenerate_rendering_for_stroke_threaded(
-                            engine_view.tasks_tx.clone(),
-                            *current_stroke_key,
-                            engine_view.camera.viewport(),
-                            engine_view.camera.image_scale(),
-                        );
+                        finish_stroke_rendering(engine_view, *current_stroke_key, *direct_draw);
                         widget_flags |= engine_view
                             .document
                             .resize_autoexpand(engine_view.store, engine_view.camera);
@@ -280,9 +695,32 @@ impl DrawableOnDoc for Brush {
 
         match &self.state {
             BrushState::Idle => None,
-            BrushState::Drawing { path_builder, .. } => {
-                path_builder.bounds(&style, engine_view.camera.zoom())
+            BrushState::Drawing {
+                path_builder,
+                current_stroke_key,
+                direct_draw,
+                ..
+            } => {
+                let builder_bounds = path_builder.bounds(&style, engine_view.camera.zoom());
+                let stroke_bounds = direct_draw
+                    .then(|| engine_view.store.get_stroke_ref(*current_stroke_key))
+                    .flatten()
+                    .map(|stroke| stroke.bounds());
+
+                match (builder_bounds, stroke_bounds) {
+                    (Some(builder_bounds), Some(stroke_bounds)) => {
+                        Some(builder_bounds.merged(&stroke_bounds))
+                    }
+                    (builder_bounds, stroke_bounds) => builder_bounds.or(stroke_bounds),
+                }
             }
+            BrushState::Spraying {
+                current_stroke_key, ..
+            } => engine_view
+                .store
+                .get_stroke_ref(*current_stroke_key)
+                .map(|stroke| stroke.bounds()),
+            BrushState::Stamping => None,
         }
     }
 
@@ -295,12 +733,27 @@ impl DrawableOnDoc for Brush {
 
         match &self.state {
             BrushState::Idle => {}
-            BrushState::Drawing { path_builder, .. } => {
+            BrushState::Drawing {
+                path_builder,
+                current_stroke_key,
+                direct_draw,
+                ..
+            } => {
+                // Draw the already emitted segments of the in-progress stroke directly, bypassing the image generation
+                if *direct_draw
+                    && let Some(stroke) = engine_view.store.get_stroke_ref(*current_stroke_key)
+                {
+                    stroke.draw(cx, engine_view.camera.image_scale())?;
+                }
+
                 match engine_view.config.pens_config.brush_config.style {
                     BrushStyle::Marker => {
                         // Don't draw the marker, as the pen would render on top of other strokes, while the stroke itself would render underneath them.
                     }
-                    BrushStyle::Solid | BrushStyle::Textured => {
+                    BrushStyle::Solid
+                    | BrushStyle::Textured
+                    | BrushStyle::Airbrush
+                    | BrushStyle::Stamp => {
                         let style = engine_view
                             .config
                             .pens_config
@@ -310,6 +763,15 @@ impl DrawableOnDoc for Brush {
                     }
                 }
             }
+            BrushState::Spraying {
+                current_stroke_key, ..
+            } => {
+                // The stroke is drawn directly while spraying, its image is generated when it is finished
+                if let Some(stroke) = engine_view.store.get_stroke_ref(*current_stroke_key) {
+                    stroke.draw(cx, engine_view.camera.image_scale())?;
+                }
+            }
+            BrushState::Stamping => {}
         }
 
         cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
@@ -321,6 +783,127 @@ impl Brush {
     const INPUT_OVERSHOOT: f64 = 30.0;
 }
 
+/// Replace the path of the in-progress stroke with a straight line from its start to the given end.
+fn straighten_stroke(engine_view: &mut EngineViewMut, key: StrokeKey, end: Element, time: f64) {
+    if let Some(Stroke::BrushStroke(brushstroke)) = engine_view.store.get_stroke_mut(key) {
+        let mut path = PenPath::new(brushstroke.path.start);
+        path.extend_timed([Segment::LineTo { end }], time);
+        brushstroke.replace_path(path);
+    }
+    engine_view.store.update_geometry_for_stroke(key);
+    engine_view.store.regenerate_rendering_for_stroke(
+        key,
+        engine_view.camera.viewport(),
+        engine_view.camera.image_scale(),
+    );
+}
+
+/// Replace the brush stroke with a shape stroke when a shape is recognized in its path with a high confidence.
+///
+/// Returns true if the stroke was replaced.
+fn recognize_shape(engine_view: &mut EngineViewMut, key: StrokeKey) -> bool {
+    let Some(Stroke::BrushStroke(brushstroke)) = engine_view.store.get_stroke_ref(key) else {
+        return false;
+    };
+    // Textured strokes can't be drawn as shapes
+    if !matches!(brushstroke.style, Style::Smooth(_)) {
+        return false;
+    }
+    let Some(recognition) = recognition::recognize(&brushstroke.path)
+        .filter(|result| result.confidence >= recognition::CONFIDENCE_MIN)
+    else {
+        return false;
+    };
+    let shapestroke = ShapeStroke::new(recognition.shape, brushstroke.style.clone());
+
+    let Some(stroke) = engine_view.store.get_stroke_mut(key) else {
+        return false;
+    };
+    *stroke = Stroke::ShapeStroke(shapestroke);
+    true
+}
+
+/// Add the emissions of the spray stroke that are due until `now` at the given element.
+///
+/// Returns true if emissions were added.
+fn spray(
+    engine_view: &mut EngineViewMut,
+    key: StrokeKey,
+    element: Element,
+    last_emission: &mut Instant,
+    now: Instant,
+) -> bool {
+    let Some(Stroke::SprayStroke(spraystroke)) = engine_view.store.get_stroke_mut(key) else {
+        return false;
+    };
+    let interval = spraystroke.options.emission_interval();
+    let mut emitted = false;
+    while now.saturating_duration_since(*last_emission) >= interval {
+        spraystroke.emit(element);
+        *last_emission += interval;
+        emitted = true;
+    }
+    emitted
+}
+
+/// Place the stamp that is selected in the stamp library, centered at the position.
+fn place_stamp(engine_view: &mut EngineViewMut, pos: na::Vector2<f64>) -> WidgetFlags {
+    let mut widget_flags = WidgetFlags::default();
+    let brush_config = &engine_view.config.pens_config.brush_config;
+    let Some(stamp) = engine_view
+        .config
+        .stamp_library
+        .get(brush_config.stamp_options.stamp_index)
+        .cloned()
+    else {
+        return widget_flags;
+    };
+    let stampstroke = StampStroke::new(
+        stamp,
+        pos,
+        brush_config.stamp_options.stroke_width,
+        brush_config.stamp_options.stroke_color,
+    );
+    let layer = brush_config.layer_for_current_options();
+
+    let key = engine_view
+        .store
+        .insert_new_stroke(Stroke::StampStroke(stampstroke), Some(layer));
+    engine_view.store.regenerate_rendering_for_stroke(
+        key,
+        engine_view.camera.viewport(),
+        engine_view.camera.image_scale(),
+    );
+
+    widget_flags |= engine_view
+        .document
+        .resize_autoexpand(engine_view.store, engine_view.camera);
+    widget_flags |= engine_view.store.record(Instant::now());
+    widget_flags.store_modified = true;
+    widget_flags
+}
+
+/// Update the geometry and regenerate the rendering of the stroke that was just finished.
+fn finish_stroke_rendering(engine_view: &mut EngineViewMut, key: StrokeKey, direct_draw: bool) {
+    engine_view.store.update_geometry_for_stroke(key);
+
+    if direct_draw {
+        // Generated synchronously, so that the stroke does not disappear until a rendering task has finished
+        engine_view.store.regenerate_rendering_for_stroke(
+            key,
+            engine_view.camera.viewport(),
+            engine_view.camera.image_scale(),
+        );
+    } else {
+        engine_view.store.regenerate_rendering_for_stroke_threaded(
+            engine_view.tasks_tx.clone(),
+            key,
+            engine_view.camera.viewport(),
+            engine_view.camera.image_scale(),
+        );
+    }
+}
+
 fn play_marker_sound(engine_view: &mut EngineViewMut) {
     if let Some(audioplayer) = engine_view.audioplayer {
         audioplayer.play_random_marker_sound();
@@ -335,12 +918,17 @@ fn trigger_brush_sound(engine_view: &mut EngineViewMut) {
 
 fn new_builder(
     builder_type: PenPathBuilderType,
+    input_smoothing: InputSmoothingConfig,
     element: Element,
     now: Instant,
 ) -> Box<dyn Buildable<Emit = Segment>> {
     match builder_type {
         PenPathBuilderType::Simple => Box::new(PenPathSimpleBuilder::start(element, now)),
         PenPathBuilderType::Curved => Box::new(PenPathCurvedBuilder::start(element, now)),
-        PenPathBuilderType::Modeled => Box::new(PenPathModeledBuilder::start(element, now)),
+        PenPathBuilderType::Modeled => Box::new(PenPathModeledBuilder::start_w_input_smoothing(
+            element,
+            now,
+            input_smoothing,
+        )),
     }
 }
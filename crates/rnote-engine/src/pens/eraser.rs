@@ -8,7 +8,7 @@ use p2d::bounding_volume::{Aabb, BoundingVolume};
 use piet::RenderContext;
 use rnote_compose::color;
 use rnote_compose::eventresult::{EventPropagation, EventResult};
-use rnote_compose::ext::AabbExt;
+use rnote_compose::ext::{AabbExt, Vector2Ext};
 use rnote_compose::penevent::{PenEvent, PenProgress};
 use rnote_compose::penpath::Element;
 use std::time::Instant;
@@ -60,7 +60,7 @@ impl PenBehaviour for Eraser {
 
         let event_result = match (&mut self.state, event) {
             (EraserState::Up | EraserState::Proximity { .. }, PenEvent::Down { element, .. }) => {
-                widget_flags |= erase(element, engine_view);
+                widget_flags |= erase(None, element, engine_view);
                 self.state = EraserState::Down(element);
                 EventResult {
                     handled: true,
@@ -85,7 +85,7 @@ impl PenBehaviour for Eraser {
                 progress: PenProgress::Idle,
             },
             (EraserState::Down(current_element), PenEvent::Down { element, .. }) => {
-                widget_flags |= erase(element, engine_view);
+                widget_flags |= erase(Some(*current_element), element, engine_view);
                 *current_element = element;
                 EventResult {
                     handled: true,
@@ -93,9 +93,9 @@ impl PenBehaviour for Eraser {
                     progress: PenProgress::InProgress,
                 }
             }
-            (EraserState::Down { .. }, PenEvent::Up { element, .. }) => {
-                widget_flags |=
-                    erase(element, engine_view) | engine_view.store.record(Instant::now());
+            (EraserState::Down(current_element), PenEvent::Up { element, .. }) => {
+                widget_flags |= erase(Some(*current_element), element, engine_view)
+                    | engine_view.store.record(Instant::now());
                 self.state = EraserState::Up;
                 EventResult {
                     handled: true,
@@ -138,17 +138,26 @@ impl PenBehaviour for Eraser {
                 propagate: EventPropagation::Proceed,
                 progress: PenProgress::Idle,
             },
-            (EraserState::Up, PenEvent::Text { .. }) => EventResult {
+            (
+                EraserState::Up,
+                PenEvent::Text { .. } | PenEvent::LongPress { .. } | PenEvent::DoubleTap { .. },
+            ) => EventResult {
                 handled: false,
                 propagate: EventPropagation::Proceed,
                 progress: PenProgress::Idle,
             },
-            (EraserState::Proximity(_), PenEvent::Text { .. }) => EventResult {
+            (
+                EraserState::Proximity(_),
+                PenEvent::Text { .. } | PenEvent::LongPress { .. } | PenEvent::DoubleTap { .. },
+            ) => EventResult {
                 handled: false,
                 propagate: EventPropagation::Proceed,
                 progress: PenProgress::Idle,
             },
-            (EraserState::Down(_), PenEvent::Text { .. }) => EventResult {
+            (
+                EraserState::Down(_),
+                PenEvent::Text { .. } | PenEvent::LongPress { .. } | PenEvent::DoubleTap { .. },
+            ) => EventResult {
                 handled: false,
                 propagate: EventPropagation::Proceed,
                 progress: PenProgress::InProgress,
@@ -185,33 +194,39 @@ impl DrawableOnDoc for Eraser {
         const PROXIMITY_FILL_COLOR: piet::Color = color::GNOME_REDS[0].with_a8(51);
         let outline_width = 2.0 / engine_view.camera.total_zoom();
 
-        match &self.state {
-            EraserState::Up => {}
+        let (current_element, fill_color) = match &self.state {
+            EraserState::Up => (None, FILL_COLOR),
             EraserState::Proximity(current_element) => {
-                let bounds = engine_view
-                    .config
-                    .pens_config
-                    .eraser_config
-                    .eraser_bounds(*current_element);
+                (Some(*current_element), PROXIMITY_FILL_COLOR)
+            }
+            EraserState::Down(current_element) => (Some(*current_element), FILL_COLOR),
+        };
 
-                let fill_rect = bounds.to_kurbo_rect();
-                let outline_rect = bounds.tightened(outline_width * 0.5).to_kurbo_rect();
+        if let Some(current_element) = current_element {
+            let eraser_config = &engine_view.config.pens_config.eraser_config;
 
-                cx.fill(fill_rect, &PROXIMITY_FILL_COLOR);
-                cx.stroke(outline_rect, &OUTLINE_COLOR, outline_width);
-            }
-            EraserState::Down(current_element) => {
-                let bounds = engine_view
-                    .config
-                    .pens_config
-                    .eraser_config
-                    .eraser_bounds(*current_element);
+            match eraser_config.style {
+                EraserStyle::TrashCollidingStrokes | EraserStyle::SplitCollidingStrokes => {
+                    let bounds = eraser_config.eraser_bounds(current_element);
 
-                let fill_rect = bounds.to_kurbo_rect();
-                let outline_rect = bounds.tightened(outline_width * 0.5).to_kurbo_rect();
+                    let fill_rect = bounds.to_kurbo_rect();
+                    let outline_rect = bounds.tightened(outline_width * 0.5).to_kurbo_rect();
+
+                    cx.fill(fill_rect, &fill_color);
+                    cx.stroke(outline_rect, &OUTLINE_COLOR, outline_width);
+                }
+                // The precise eraser is round
+                EraserStyle::ErasePrecisely => {
+                    let center = current_element.pos.to_kurbo_point();
+                    let radius = eraser_config.width * 0.5;
 
-                cx.fill(fill_rect, &FILL_COLOR);
-                cx.stroke(outline_rect, &OUTLINE_COLOR, outline_width);
+                    cx.fill(kurbo::Circle::new(center, radius), &fill_color);
+                    cx.stroke(
+                        kurbo::Circle::new(center, (radius - outline_width * 0.5).max(0.0)),
+                        &OUTLINE_COLOR,
+                        outline_width,
+                    );
+                }
             }
         }
 
@@ -220,7 +235,8 @@ impl DrawableOnDoc for Eraser {
     }
 }
 
-fn erase(element: Element, engine_view: &mut EngineViewMut) -> WidgetFlags {
+/// Erase at the element. The previous element of the eraser is used to erase precisely along its path.
+fn erase(prev: Option<Element>, element: Element, engine_view: &mut EngineViewMut) -> WidgetFlags {
     // the widget_flags.store_modified flag is set in the `.trash_..()` methods
     let mut widget_flags = WidgetFlags::default();
 
@@ -246,6 +262,21 @@ fn erase(element: Element, engine_view: &mut EngineViewMut) -> WidgetFlags {
             );
             widget_flags |= wf;
 
+            engine_view.store.regenerate_rendering_for_strokes(
+                &modified_strokes,
+                engine_view.camera.viewport(),
+                engine_view.camera.image_scale(),
+            );
+        }
+        EraserStyle::ErasePrecisely => {
+            let (modified_strokes, wf) = engine_view.store.erase_precisely(
+                prev.unwrap_or(element).pos,
+                element.pos,
+                engine_view.config.pens_config.eraser_config.width * 0.5,
+                engine_view.camera.viewport(),
+            );
+            widget_flags |= wf;
+
             engine_view.store.regenerate_rendering_for_strokes(
                 &modified_strokes,
                 engine_view.camera.viewport(),
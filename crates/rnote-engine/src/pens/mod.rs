@@ -4,7 +4,10 @@ pub mod eraser;
 pub mod penbehaviour;
 pub mod penholder;
 pub mod penmode;
+pub mod penpreset;
 pub mod pensconfig;
+pub mod protractor;
+pub mod ruler;
 pub mod selector;
 pub mod shaper;
 pub mod shortcuts;
@@ -17,7 +20,10 @@ pub use eraser::Eraser;
 pub use penbehaviour::PenBehaviour;
 pub use penholder::PenHolder;
 pub use penmode::PenMode;
+pub use penpreset::PenPreset;
 pub use pensconfig::PensConfig;
+pub use protractor::Protractor;
+pub use ruler::Ruler;
 pub use selector::Selector;
 pub use shaper::Shaper;
 pub use shortcuts::Shortcuts;
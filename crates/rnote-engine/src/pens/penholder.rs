@@ -10,10 +10,14 @@ use crate::engine::{EngineView, EngineViewMut};
 use crate::pens::shortcuts::ShortcutAction;
 use crate::widgetflags::WidgetFlags;
 use futures::channel::oneshot;
-use p2d::bounding_volume::Aabb;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
 use piet::RenderContext;
 use rnote_compose::eventresult::EventPropagation;
-use rnote_compose::penevent::{KeyboardKey, ModifierKey, PenEvent, PenProgress, ShortcutKey};
+use rnote_compose::inputtransform::JitterFilter;
+use rnote_compose::penevent::{
+    KeyboardKey, ModifierKey, PenEvent, PenGestureDetector, PenGestureThresholds, PenProgress,
+    ShortcutKey,
+};
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
 
@@ -37,6 +41,13 @@ pub struct PenHolder {
     /// The policy for the retrieval of input event backlogs.
     #[serde(rename = "backlog_policy")]
     backlog_policy: BacklogPolicy,
+    /// The thresholds for synthesizing long-press and double-tap events.
+    #[serde(rename = "gesture_thresholds")]
+    gesture_thresholds: PenGestureThresholds,
+    #[serde(skip)]
+    gesture_detector: PenGestureDetector,
+    #[serde(skip)]
+    jitter_filter: JitterFilter,
     #[serde(skip)]
     current_pen: Pen,
     #[serde(skip)]
@@ -52,6 +63,9 @@ impl Default for PenHolder {
         Self {
             pen_mode_state: PenModeState::default(),
             backlog_policy: BacklogPolicy::NoLimit,
+            gesture_thresholds: PenGestureThresholds::default(),
+            gesture_detector: PenGestureDetector::default(),
+            jitter_filter: JitterFilter::default(),
 
             current_pen: Pen::default(),
             progress: PenProgress::Idle,
@@ -76,6 +90,14 @@ impl PenHolder {
         self.backlog_policy
     }
 
+    pub fn gesture_thresholds(&self) -> PenGestureThresholds {
+        self.gesture_thresholds
+    }
+
+    pub fn set_gesture_thresholds(&mut self, gesture_thresholds: PenGestureThresholds) {
+        self.gesture_thresholds = gesture_thresholds;
+    }
+
     /// Get the style without the temporary override.
     pub fn current_pen_style(&self, engine_view: &EngineView) -> PenStyle {
         self.pen_mode_state.style(&engine_view.config.pens_config)
@@ -180,6 +202,9 @@ impl PenHolder {
     }
 
     /// Handle a pen event.
+    ///
+    /// The event is preprocessed first, filtering the jitter of the input and possibly synthesizing long-press and
+    /// double-tap events that are handled right after it.
     pub fn handle_pen_event(
         &mut self,
         event: PenEvent,
@@ -188,21 +213,55 @@ impl PenHolder {
         engine_view: &mut EngineViewMut,
     ) -> (EventPropagation, WidgetFlags) {
         let mut widget_flags = WidgetFlags::default();
+        let mut propagate = EventPropagation::Proceed;
 
         if let Some(pen_mode) = pen_mode {
             widget_flags |= self.change_pen_mode(pen_mode, engine_view);
         }
 
-        // Handle the event with the current pen
-        let (mut event_result, wf) = self
-            .current_pen
-            .handle_event(event.clone(), now, engine_view);
-        widget_flags |= wf | self.handle_pen_progress(event_result.progress, engine_view);
+        let event = self
+            .jitter_filter
+            .filter(event, engine_view.config.input_transform.jitter_threshold);
+        for event in self
+            .gesture_detector
+            .process(event, now, &self.gesture_thresholds)
+        {
+            // The ruler is grabbed instead, when the current pen is not in use
+            if (self.progress != PenProgress::InProgress || engine_view.ruler.grabbed())
+                && let Some(wf) = engine_view
+                    .ruler
+                    .handle_event(&event, engine_view.camera.total_zoom())
+            {
+                widget_flags |= wf;
+                propagate |= EventPropagation::Stop;
+                continue;
+            }
+            // The same for the protractor
+            if (self.progress != PenProgress::InProgress || engine_view.protractor.grabbed())
+                && let Some(wf) = engine_view
+                    .protractor
+                    .handle_event(&event, engine_view.camera.total_zoom())
+            {
+                widget_flags |= wf;
+                propagate |= EventPropagation::Stop;
+                continue;
+            }
+            if let Some(wf) = engine_view.protractor.update_pointer(&event) {
+                widget_flags |= wf;
+            }
 
-        if !event_result.handled {
-            let (propagate, wf) = self.handle_pen_event_global(event, now, engine_view);
-            event_result.propagate |= propagate;
-            widget_flags |= wf;
+            // Handle the event with the current pen
+            let (mut event_result, wf) =
+                self.current_pen
+                    .handle_event(event.clone(), now, engine_view);
+            widget_flags |= wf | self.handle_pen_progress(event_result.progress, engine_view);
+
+            if !event_result.handled {
+                let (propagate, wf) = self.handle_pen_event_global(event, now, engine_view);
+                event_result.propagate |= propagate;
+                widget_flags |= wf;
+            }
+            propagate |= event_result.propagate;
         }
 
         // Always redraw after handling a pen event.
@@ -210,7 +269,7 @@ impl PenHolder {
         // This is also needed because pens might have claimed/requested an animation frame.
         widget_flags.redraw = true;
 
-        (event_result.propagate, widget_flags)
+        (propagate, widget_flags)
     }
 
     /// Handle a requested animation frame.
@@ -267,6 +326,9 @@ impl PenHolder {
                     }
                     ShortcutMode::Disabled => {}
                 },
+                ShortcutAction::OpenRadialMenu => {
+                    widget_flags.open_radial_menu = true;
+                }
             }
 
             propagate = EventPropagation::Stop;
@@ -336,6 +398,8 @@ impl PenHolder {
             | PenEvent::Up { .. }
             | PenEvent::Proximity { .. }
             | PenEvent::Text { .. }
+            | PenEvent::LongPress { .. }
+            | PenEvent::DoubleTap { .. }
             | PenEvent::Cancel => EventPropagation::Proceed,
             PenEvent::KeyPressed {
                 keyboard_key,
@@ -477,7 +541,14 @@ impl PenHolder {
 
 impl DrawableOnDoc for PenHolder {
     fn bounds_on_doc(&self, engine_view: &EngineView) -> Option<Aabb> {
-        self.current_pen.bounds_on_doc(engine_view)
+        [
+            self.current_pen.bounds_on_doc(engine_view),
+            engine_view.ruler.bounds_on_doc(engine_view),
+            engine_view.protractor.bounds_on_doc(engine_view),
+        ]
+        .into_iter()
+        .flatten()
+        .reduce(|acc, bounds| acc.merged(&bounds))
     }
     fn draw_on_doc(
         &self,
@@ -486,6 +557,8 @@ impl DrawableOnDoc for PenHolder {
     ) -> anyhow::Result<()> {
         cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
 
+        engine_view.ruler.draw_on_doc(cx, engine_view)?;
+        engine_view.protractor.draw_on_doc(cx, engine_view)?;
         self.current_pen.draw_on_doc(cx, engine_view)?;
 
         cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
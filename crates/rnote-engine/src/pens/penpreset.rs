@@ -0,0 +1,35 @@
+// Imports
+use super::PenStyle;
+use rnote_compose::Color;
+use serde::{Deserialize, Serialize};
+
+/// A pen with its stroke width and color, saved under a name.
+///
+/// Presets can be pinned to the quick-access toolbar and applied with a single action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "pen_preset")]
+pub struct PenPreset {
+    /// The name of the preset.
+    #[serde(rename = "name")]
+    pub name: String,
+    /// The pen style that is selected when applying the preset.
+    #[serde(rename = "pen_style")]
+    pub pen_style: PenStyle,
+    /// The stroke width of the pen.
+    #[serde(rename = "stroke_width", with = "rnote_compose::serialize::f64_dp3")]
+    pub stroke_width: f64,
+    /// The stroke color of the pen. None for pens without a color.
+    #[serde(rename = "stroke_color")]
+    pub stroke_color: Option<Color>,
+}
+
+impl Default for PenPreset {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            pen_style: PenStyle::default(),
+            stroke_width: 1.0,
+            stroke_color: None,
+        }
+    }
+}
@@ -1,11 +1,12 @@
 // Imports
 use crate::store::chrono_comp::StrokeLayer;
+use crate::strokes::{BlendMode, SprayOptions, StampOptions};
 use rand::{Rng, SeedableRng};
-use rnote_compose::Style;
-use rnote_compose::builders::PenPathBuilderType;
+use rnote_compose::builders::{InputSmoothingConfig, PenPathBuilderType};
 use rnote_compose::style::PressureCurve;
-use rnote_compose::style::smooth::SmoothOptions;
+use rnote_compose::style::smooth::{ColorGradient, SmoothOptions};
 use rnote_compose::style::textured::TexturedOptions;
+use rnote_compose::{Color, Style};
 use serde::{Deserialize, Serialize};
 
 #[derive(
@@ -27,6 +28,10 @@ pub enum BrushStyle {
     Solid,
     #[serde(rename = "textured")]
     Textured,
+    #[serde(rename = "airbrush")]
+    Airbrush,
+    #[serde(rename = "stamp")]
+    Stamp,
 }
 
 impl Default for BrushStyle {
@@ -53,6 +58,7 @@ impl Default for MarkerOptions {
     fn default() -> Self {
         let mut options = SmoothOptions::default();
         options.pressure_curve = PressureCurve::Const;
+        options.pressure_width_min_ratio = 0.5;
         options.stroke_width = 12.0;
 
         Self(options)
@@ -97,11 +103,14 @@ impl std::ops::DerefMut for SolidOptions {
     }
 }
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default, rename = "brush_config")]
 pub struct BrushConfig {
     #[serde(rename = "builder_type")]
     pub builder_type: PenPathBuilderType,
+    /// The input smoothing of the modeled builder, shared by all brush styles.
+    #[serde(rename = "input_smoothing")]
+    pub input_smoothing: InputSmoothingConfig,
     #[serde(rename = "style")]
     pub style: BrushStyle,
     #[serde(rename = "marker_options")]
@@ -110,6 +119,41 @@ pub struct BrushConfig {
     pub solid_options: SolidOptions,
     #[serde(rename = "textured_options")]
     pub textured_options: TexturedOptions,
+    #[serde(rename = "airbrush_options")]
+    pub airbrush_options: SprayOptions,
+    #[serde(rename = "stamp_options")]
+    pub stamp_options: StampOptions,
+    /// How marker strokes are blended with the content underneath them.
+    #[serde(rename = "marker_blend_mode")]
+    pub marker_blend_mode: BlendMode,
+    /// Whether holding the pen still at the end of a marker stroke snaps it to a straight line.
+    #[serde(rename = "marker_hold_to_straighten")]
+    pub marker_hold_to_straighten: bool,
+    /// Whether finished marker and solid strokes are replaced with a shape when one is recognized in their path.
+    #[serde(rename = "shape_recognition")]
+    pub shape_recognition: bool,
+    /// The end color of a gradient from the stroke color, for the marker and solid styles. No gradient when None.
+    #[serde(rename = "gradient_end_color")]
+    pub gradient_end_color: Option<Color>,
+}
+
+impl Default for BrushConfig {
+    fn default() -> Self {
+        Self {
+            builder_type: PenPathBuilderType::default(),
+            input_smoothing: InputSmoothingConfig::default(),
+            style: BrushStyle::default(),
+            marker_options: MarkerOptions::default(),
+            solid_options: SolidOptions::default(),
+            textured_options: TexturedOptions::default(),
+            airbrush_options: SprayOptions::default(),
+            stamp_options: StampOptions::default(),
+            marker_blend_mode: BlendMode::default(),
+            marker_hold_to_straighten: false,
+            shape_recognition: false,
+            gradient_end_color: None,
+        }
+    }
 }
 
 impl BrushConfig {
@@ -119,33 +163,72 @@ impl BrushConfig {
     pub(crate) fn layer_for_current_options(&self) -> StrokeLayer {
         match &self.style {
             BrushStyle::Marker => StrokeLayer::Highlighter,
-            BrushStyle::Solid | BrushStyle::Textured => StrokeLayer::UserLayer(0),
+            BrushStyle::Solid | BrushStyle::Textured | BrushStyle::Airbrush | BrushStyle::Stamp => {
+                StrokeLayer::UserLayer(0)
+            }
+        }
+    }
+
+    pub(crate) fn blend_mode_for_current_options(&self) -> BlendMode {
+        match &self.style {
+            BrushStyle::Marker => self.marker_blend_mode,
+            BrushStyle::Solid | BrushStyle::Textured | BrushStyle::Airbrush | BrushStyle::Stamp => {
+                BlendMode::Normal
+            }
         }
     }
 
     /// A new seed for new shapes
     pub(crate) fn new_style_seeds(&mut self) {
-        let seed = Some(rand_pcg::Pcg64::from_os_rng().random());
-        self.textured_options.seed = seed;
+        let mut rng = rand_pcg::Pcg64::from_os_rng();
+        self.textured_options.seed = Some(rng.random());
+        self.airbrush_options.seed = rng.random();
     }
 
     pub(crate) fn style_for_current_options(&self) -> Style {
         match &self.style {
             BrushStyle::Marker => {
-                let MarkerOptions(options) = self.marker_options.clone();
+                let MarkerOptions(mut options) = self.marker_options.clone();
+                // The dash pattern scales with the stroke width, which is set without updating it
+                options.update_piet_stroke_style();
 
-                Style::Smooth(options)
+                Style::Smooth(self.with_gradient(options))
             }
             BrushStyle::Solid => {
-                let SolidOptions(options) = self.solid_options.clone();
+                let SolidOptions(mut options) = self.solid_options.clone();
+                options.update_piet_stroke_style();
 
-                Style::Smooth(options)
+                Style::Smooth(self.with_gradient(options))
             }
             BrushStyle::Textured => {
                 let options = self.textured_options.clone();
 
                 Style::Textured(options)
             }
+            BrushStyle::Airbrush => {
+                // Sprayed strokes have no style, this only approximates the area the airbrush covers
+                let mut options = SmoothOptions::default();
+                options.stroke_width = self.airbrush_options.stroke_width;
+                options.stroke_color = self.airbrush_options.stroke_color;
+
+                Style::Smooth(options)
+            }
+            BrushStyle::Stamp => {
+                // Stamps have no style, this only carries their size and color
+                let mut options = SmoothOptions::default();
+                options.stroke_width = self.stamp_options.stroke_width;
+                options.stroke_color = self.stamp_options.stroke_color;
+
+                Style::Smooth(options)
+            }
         }
     }
+
+    fn with_gradient(&self, mut options: SmoothOptions) -> SmoothOptions {
+        options.stroke_gradient = options
+            .stroke_color
+            .zip(self.gradient_end_color)
+            .map(|(start, end)| ColorGradient::new_two_colors(start, end));
+        options
+    }
 }
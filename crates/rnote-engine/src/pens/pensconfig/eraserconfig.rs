@@ -12,6 +12,9 @@ pub enum EraserStyle {
     TrashCollidingStrokes,
     #[serde(rename = "split_colliding_strokes")]
     SplitCollidingStrokes,
+    /// Cuts brush strokes exactly along the round eraser.
+    #[serde(rename = "erase_precisely")]
+    ErasePrecisely,
 }
 
 impl Default for EraserStyle {
@@ -17,9 +17,13 @@ pub use typewriterconfig::TypewriterConfig;
 // Imports
 use super::shortcuts::ShortcutAction;
 use super::{PenStyle, Shortcuts};
-use rnote_compose::Color;
+use crate::store::chrono_comp::StrokeLayer;
+use crate::strokes::Stroke;
+use brushconfig::BrushStyle;
 use rnote_compose::penevent::ShortcutKey;
+use rnote_compose::{Color, Style};
 use serde::{Deserialize, Serialize};
+use shaperconfig::ShaperStyle;
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(default, rename = "pens_config")]
@@ -50,6 +54,8 @@ impl PensConfig {
         self.brush_config.marker_options.stroke_color = Some(stroke_color);
         self.brush_config.solid_options.stroke_color = Some(stroke_color);
         self.brush_config.textured_options.stroke_color = Some(stroke_color);
+        self.brush_config.airbrush_options.stroke_color = Some(stroke_color);
+        self.brush_config.stamp_options.stroke_color = Some(stroke_color);
         self.shaper_config.smooth_options.stroke_color = Some(stroke_color);
         self.shaper_config.rough_options.stroke_color = Some(stroke_color);
         self.typewriter_config.text_style.color = stroke_color;
@@ -62,6 +68,220 @@ impl PensConfig {
         self.shaper_config.rough_options.fill_color = Some(fill_color);
     }
 
+    /// The stroke width of the pen style in its current sub-style. The font size for the typewriter.
+    ///
+    /// None for pen styles without a stroke width.
+    pub fn stroke_width(&self, pen_style: PenStyle) -> Option<f64> {
+        match pen_style {
+            PenStyle::Brush => Some(match self.brush_config.style {
+                BrushStyle::Marker => self.brush_config.marker_options.stroke_width,
+                BrushStyle::Solid => self.brush_config.solid_options.stroke_width,
+                BrushStyle::Textured => self.brush_config.textured_options.stroke_width,
+                BrushStyle::Airbrush => self.brush_config.airbrush_options.stroke_width,
+                BrushStyle::Stamp => self.brush_config.stamp_options.stroke_width,
+            }),
+            PenStyle::Shaper => Some(match self.shaper_config.style {
+                ShaperStyle::Smooth => self.shaper_config.smooth_options.stroke_width,
+                ShaperStyle::Rough => self.shaper_config.rough_options.stroke_width,
+            }),
+            PenStyle::Typewriter => Some(self.typewriter_config.text_style.font_size),
+            PenStyle::Eraser => Some(self.eraser_config.width),
+            PenStyle::Selector | PenStyle::Tools => None,
+        }
+    }
+
+    /// Set the stroke width of the pen style in its current sub-style, see [PensConfig::stroke_width].
+    pub fn set_stroke_width(&mut self, pen_style: PenStyle, stroke_width: f64) {
+        match pen_style {
+            PenStyle::Brush => match self.brush_config.style {
+                BrushStyle::Marker => self.brush_config.marker_options.stroke_width = stroke_width,
+                BrushStyle::Solid => self.brush_config.solid_options.stroke_width = stroke_width,
+                BrushStyle::Textured => {
+                    self.brush_config.textured_options.stroke_width = stroke_width
+                }
+                BrushStyle::Airbrush => {
+                    self.brush_config.airbrush_options.stroke_width = stroke_width
+                }
+                BrushStyle::Stamp => self.brush_config.stamp_options.stroke_width = stroke_width,
+            },
+            PenStyle::Shaper => match self.shaper_config.style {
+                ShaperStyle::Smooth => {
+                    self.shaper_config.smooth_options.stroke_width = stroke_width
+                }
+                ShaperStyle::Rough => self.shaper_config.rough_options.stroke_width = stroke_width,
+            },
+            PenStyle::Typewriter => self.typewriter_config.text_style.font_size = stroke_width,
+            PenStyle::Eraser => self.eraser_config.width = stroke_width,
+            PenStyle::Selector | PenStyle::Tools => {}
+        }
+    }
+
+    /// The stroke color of the pen style in its current sub-style.
+    ///
+    /// None for pen styles without a stroke color, or when the stroke color is disabled.
+    pub fn stroke_color(&self, pen_style: PenStyle) -> Option<Color> {
+        match pen_style {
+            PenStyle::Brush => match self.brush_config.style {
+                BrushStyle::Marker => self.brush_config.marker_options.stroke_color,
+                BrushStyle::Solid => self.brush_config.solid_options.stroke_color,
+                BrushStyle::Textured => self.brush_config.textured_options.stroke_color,
+                BrushStyle::Airbrush => self.brush_config.airbrush_options.stroke_color,
+                BrushStyle::Stamp => self.brush_config.stamp_options.stroke_color,
+            },
+            PenStyle::Shaper => match self.shaper_config.style {
+                ShaperStyle::Smooth => self.shaper_config.smooth_options.stroke_color,
+                ShaperStyle::Rough => self.shaper_config.rough_options.stroke_color,
+            },
+            PenStyle::Typewriter => Some(self.typewriter_config.text_style.color),
+            PenStyle::Eraser | PenStyle::Selector | PenStyle::Tools => None,
+        }
+    }
+
+    /// Set the stroke color of the pen style in its current sub-style, see [PensConfig::stroke_color].
+    pub fn set_stroke_color(&mut self, pen_style: PenStyle, stroke_color: Color) {
+        match pen_style {
+            PenStyle::Brush => match self.brush_config.style {
+                BrushStyle::Marker => {
+                    self.brush_config.marker_options.stroke_color = Some(stroke_color)
+                }
+                BrushStyle::Solid => {
+                    self.brush_config.solid_options.stroke_color = Some(stroke_color)
+                }
+                BrushStyle::Textured => {
+                    self.brush_config.textured_options.stroke_color = Some(stroke_color)
+                }
+                BrushStyle::Airbrush => {
+                    self.brush_config.airbrush_options.stroke_color = Some(stroke_color)
+                }
+                BrushStyle::Stamp => {
+                    self.brush_config.stamp_options.stroke_color = Some(stroke_color)
+                }
+            },
+            PenStyle::Shaper => match self.shaper_config.style {
+                ShaperStyle::Smooth => {
+                    self.shaper_config.smooth_options.stroke_color = Some(stroke_color)
+                }
+                ShaperStyle::Rough => {
+                    self.shaper_config.rough_options.stroke_color = Some(stroke_color)
+                }
+            },
+            PenStyle::Typewriter => self.typewriter_config.text_style.color = stroke_color,
+            PenStyle::Eraser | PenStyle::Selector | PenStyle::Tools => {}
+        }
+    }
+
+    /// Copy the visual style of the stroke into the config of the pen that creates this kind of stroke.
+    ///
+    /// Marker strokes are told apart from solid strokes by being on the highlighter layer.
+    /// Returns the pen style the style was copied into, None when the style of the stroke can't be copied.
+    pub(crate) fn copy_style_from_stroke(
+        &mut self,
+        stroke: &Stroke,
+        layer: StrokeLayer,
+    ) -> Option<PenStyle> {
+        match stroke {
+            Stroke::BrushStroke(brushstroke) => match &brushstroke.style {
+                Style::Smooth(options) => {
+                    self.brush_config.gradient_end_color = options
+                        .stroke_gradient
+                        .as_ref()
+                        .map(|gradient| gradient.color_at(1.0));
+                    let mut options = options.clone();
+                    options.stroke_gradient = None;
+                    if layer == StrokeLayer::Highlighter {
+                        self.brush_config.style = BrushStyle::Marker;
+                        *self.brush_config.marker_options = options;
+                        self.brush_config.marker_blend_mode = brushstroke.blend_mode;
+                    } else {
+                        self.brush_config.style = BrushStyle::Solid;
+                        *self.brush_config.solid_options = options;
+                    }
+                    Some(PenStyle::Brush)
+                }
+                Style::Textured(options) => {
+                    self.brush_config.style = BrushStyle::Textured;
+                    self.brush_config.textured_options = options.clone();
+                    Some(PenStyle::Brush)
+                }
+                Style::Rough(_) => None,
+            },
+            Stroke::ShapeStroke(shapestroke) => {
+                match &shapestroke.style {
+                    Style::Smooth(options) => {
+                        self.shaper_config.style = ShaperStyle::Smooth;
+                        self.shaper_config.smooth_options = options.clone();
+                    }
+                    Style::Rough(options) => {
+                        self.shaper_config.style = ShaperStyle::Rough;
+                        self.shaper_config.rough_options = options.clone();
+                    }
+                    Style::Textured(_) => return None,
+                }
+                // The opacity of highlighted shapes is already part of the copied colors
+                self.shaper_config.highlight_mode = false;
+                Some(PenStyle::Shaper)
+            }
+            Stroke::SprayStroke(spraystroke) => {
+                self.brush_config.style = BrushStyle::Airbrush;
+                self.brush_config.airbrush_options = spraystroke.options.clone();
+                Some(PenStyle::Brush)
+            }
+            Stroke::TextStroke(textstroke) => {
+                let text_style = &mut self.typewriter_config.text_style;
+                text_style.font_family = textstroke.text_style.font_family.clone();
+                text_style.font_size = textstroke.text_style.font_size;
+                text_style.font_weight = textstroke.text_style.font_weight;
+                text_style.font_style = textstroke.text_style.font_style;
+                text_style.color = textstroke.text_style.color;
+                text_style.alignment = textstroke.text_style.alignment;
+                Some(PenStyle::Typewriter)
+            }
+            _ => None,
+        }
+    }
+
+    /// Apply the style of the config of the pen that creates this kind of stroke onto the stroke.
+    ///
+    /// Returns whether the stroke was changed. The stroke then needs to update its geometry.
+    pub(crate) fn apply_style_to_stroke(&self, stroke: &mut Stroke) -> bool {
+        match stroke {
+            Stroke::BrushStroke(brushstroke) => match self.brush_config.style {
+                BrushStyle::Marker | BrushStyle::Solid | BrushStyle::Textured => {
+                    brushstroke.style = self.brush_config.style_for_current_options();
+                    brushstroke.blend_mode = self.brush_config.blend_mode_for_current_options();
+                    true
+                }
+                // Sprayed and stamped strokes are different kinds of strokes
+                BrushStyle::Airbrush | BrushStyle::Stamp => false,
+            },
+            Stroke::ShapeStroke(shapestroke) => {
+                shapestroke.style = self.shaper_config.gen_style_for_current_options();
+                true
+            }
+            Stroke::SprayStroke(spraystroke) => {
+                if self.brush_config.style != BrushStyle::Airbrush {
+                    return false;
+                }
+                // Keep the seed, so that the sprayed dots stay where they are
+                let seed = spraystroke.options.seed;
+                spraystroke.options = self.brush_config.airbrush_options.clone();
+                spraystroke.options.seed = seed;
+                true
+            }
+            Stroke::TextStroke(textstroke) => {
+                let text_style = &self.typewriter_config.text_style;
+                textstroke.text_style.font_family = text_style.font_family.clone();
+                textstroke.text_style.font_size = text_style.font_size;
+                textstroke.text_style.font_weight = text_style.font_weight;
+                textstroke.text_style.font_style = text_style.font_style;
+                textstroke.text_style.color = text_style.color;
+                textstroke.text_style.alignment = text_style.alignment;
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Get the current registered shortcuts.
     pub fn shortcuts(&self) -> Shortcuts {
         self.shortcuts.clone()
@@ -1,4 +1,5 @@
 // Imports
+use crate::store::stroke_comp::StrokeKindFilter;
 use serde::{Deserialize, Serialize};
 
 #[derive(
@@ -24,6 +25,9 @@ pub enum SelectorStyle {
     Single,
     #[serde(rename = "intersectingpath")]
     IntersectingPath,
+    /// Selects all strokes with the color of the picked stroke.
+    #[serde(rename = "color")]
+    Color,
 }
 
 impl Default for SelectorStyle {
@@ -49,6 +53,15 @@ pub struct SelectorConfig {
     pub style: SelectorStyle,
     #[serde(rename = "resize_lock_aspectratio")]
     pub resize_lock_aspectratio: bool,
+    /// Dragging the corners of the selection warps it into perspective instead of resizing it.
+    #[serde(rename = "resize_warp")]
+    pub resize_warp: bool,
+    /// The maximum distance between colors that are selected together when selecting by color.
+    #[serde(rename = "color_tolerance", with = "rnote_compose::serialize::f64_dp3")]
+    pub color_tolerance: f64,
+    /// Restricts new selections to strokes of this kind.
+    #[serde(rename = "stroke_filter")]
+    pub stroke_filter: StrokeKindFilter,
 }
 
 impl Default for SelectorConfig {
@@ -56,6 +69,14 @@ impl Default for SelectorConfig {
         Self {
             style: SelectorStyle::default(),
             resize_lock_aspectratio: false,
+            resize_warp: false,
+            color_tolerance: 0.05,
+            stroke_filter: StrokeKindFilter::default(),
         }
     }
 }
+
+impl SelectorConfig {
+    pub const COLOR_TOLERANCE_MIN: f64 = 0.0;
+    pub const COLOR_TOLERANCE_MAX: f64 = 0.5;
+}
@@ -1,5 +1,8 @@
 // Imports
+use crate::strokes::{SmudgeOptions, TapeOptions};
+use rnote_compose::Color;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 #[derive(
     Debug,
@@ -24,6 +27,12 @@ pub enum ToolStyle {
     Zoom,
     #[serde(rename = "laser")]
     Laser,
+    #[serde(rename = "fill")]
+    Fill,
+    #[serde(rename = "tape")]
+    Tape,
+    #[serde(rename = "smudge")]
+    Smudge,
 }
 
 impl Default for ToolStyle {
@@ -51,10 +60,77 @@ pub struct VerticalSpaceToolConfig {
     pub limit_movement_vertical_borders: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "laser_tool_config")]
+pub struct LaserToolConfig {
+    /// The duration in seconds the trail takes to fade out after the pen is lifted.
+    #[serde(rename = "fade_duration")]
+    pub fade_duration: f64,
+}
+
+impl Default for LaserToolConfig {
+    fn default() -> Self {
+        Self { fade_duration: 1.0 }
+    }
+}
+
+impl LaserToolConfig {
+    pub const FADE_DURATION_MIN: f64 = 0.1;
+    pub const FADE_DURATION_MAX: f64 = 10.0;
+
+    /// The fade duration, clamped to the allowed range.
+    pub fn fade_duration(&self) -> Duration {
+        Duration::from_secs_f64(
+            self.fade_duration
+                .clamp(Self::FADE_DURATION_MIN, Self::FADE_DURATION_MAX),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "fill_tool_config")]
+pub struct FillToolConfig {
+    /// The color enclosed regions are filled with.
+    #[serde(rename = "fill_color")]
+    pub fill_color: Color,
+}
+
+impl Default for FillToolConfig {
+    fn default() -> Self {
+        Self {
+            fill_color: Color::BLUE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "tape_tool_config")]
+pub struct TapeToolConfig {
+    /// The options of newly placed tapes.
+    #[serde(rename = "tape_options")]
+    pub tape_options: TapeOptions,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "smudge_tool_config")]
+pub struct SmudgeToolConfig {
+    /// The options of newly created smudge strokes.
+    #[serde(rename = "smudge_options")]
+    pub smudge_options: SmudgeOptions,
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(default, rename = "tools_config")]
 pub struct ToolsConfig {
     #[serde(rename = "style")]
     pub style: ToolStyle,
     pub verticalspace_tool_config: VerticalSpaceToolConfig,
+    #[serde(rename = "laser_tool_config")]
+    pub laser_tool_config: LaserToolConfig,
+    #[serde(rename = "fill_tool_config")]
+    pub fill_tool_config: FillToolConfig,
+    #[serde(rename = "tape_tool_config")]
+    pub tape_tool_config: TapeToolConfig,
+    #[serde(rename = "smudge_tool_config")]
+    pub smudge_tool_config: SmudgeToolConfig,
 }
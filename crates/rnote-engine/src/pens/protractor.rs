@@ -0,0 +1,337 @@
+// Imports
+use crate::engine::EngineView;
+use crate::{DrawableOnDoc, WidgetFlags};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::{RenderContext, Text, TextLayout, TextLayoutBuilder};
+use rnote_compose::color;
+use rnote_compose::ext::Vector2Ext;
+use rnote_compose::penevent::PenEvent;
+use rnote_compose::transform::Transform;
+use std::f64::consts::PI;
+
+/// How the protractor is currently moved.
+#[derive(Debug, Clone, Copy)]
+enum ProtractorGrab {
+    /// Moved along with the pen.
+    Translate { last_pos: na::Vector2<f64> },
+    /// Rotated around its center, keeping the angle between the pen and the baseline.
+    Rotate { angle_offset: f64 },
+}
+
+/// A half circle overlay that shows the angle of the pen relative to its baseline.
+///
+/// It is positioned in document coordinates, but keeps its size on the screen regardless of the zoom.
+/// Grabbing its body moves it, grabbing its rim rotates the baseline.
+#[derive(Debug, Clone)]
+pub struct Protractor {
+    visible: bool,
+    center: na::Vector2<f64>,
+    /// The angle of the baseline.
+    angle: f64,
+    grab: Option<ProtractorGrab>,
+    /// The last position of the pen, to which the angle is shown.
+    pointer: Option<na::Vector2<f64>>,
+}
+
+impl Default for Protractor {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            center: na::Vector2::zeros(),
+            angle: 0.0,
+            grab: None,
+            pointer: None,
+        }
+    }
+}
+
+impl Protractor {
+    /// The radius of the protractor in surface coordinates.
+    const RADIUS: f64 = 220.0;
+    /// The height of the strip below the baseline in surface coordinates.
+    const BASE_HEIGHT: f64 = 18.0;
+    /// The fraction of the radius at the rim where the protractor is rotated instead of moved.
+    const ROTATE_RIM_FRACTION: f64 = 0.2;
+    /// The angles are snapped to multiples of this angle when they are close to them.
+    const ANGLE_SNAP_STEP: f64 = PI / 12.0;
+    /// The tolerance of the angle snapping.
+    const ANGLE_SNAP_TOLERANCE: f64 = PI / 90.0;
+    const FILL_COLOR: piet::Color = color::GNOME_BRIGHTS[1].with_a8(90);
+    const OUTLINE_COLOR: piet::Color = color::GNOME_DARKS[3].with_a8(200);
+    const TICK_COLOR: piet::Color = color::GNOME_DARKS[3].with_a8(160);
+    const POINTER_COLOR: piet::Color = color::GNOME_BLUES[3].with_a8(220);
+    const OUTLINE_WIDTH: f64 = 1.5;
+    /// The font size of the labels in surface coordinates.
+    const FONT_SIZE: f64 = 11.0;
+
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Show the protractor centered at the given position, or hide it.
+    pub(crate) fn set_visible(&mut self, visible: bool, center: na::Vector2<f64>) {
+        if visible && !self.visible {
+            self.center = center;
+        }
+        self.visible = visible;
+        self.grab = None;
+        self.pointer = None;
+    }
+
+    /// Whether the protractor is currently grabbed and moved by the pen.
+    pub(crate) fn grabbed(&self) -> bool {
+        self.grab.is_some()
+    }
+
+    fn transform(&self) -> Transform {
+        Transform::new_w_isometry(na::Isometry2::new(self.center, self.angle))
+    }
+
+    /// The position in the coordinate space of the protractor, with the baseline along the x-axis
+    /// and the half circle above it.
+    fn to_local(&self, pos: na::Vector2<f64>) -> na::Vector2<f64> {
+        na::Rotation2::new(-self.angle) * (pos - self.center)
+    }
+
+    fn angle_to(&self, pos: na::Vector2<f64>) -> f64 {
+        let offset = pos - self.center;
+        offset[1].atan2(offset[0])
+    }
+
+    /// The angle of the position relative to the baseline in degrees, counter-clockwise in `[0, 360)`.
+    fn angle_deg_to(&self, pos: na::Vector2<f64>) -> f64 {
+        let local = self.to_local(pos);
+        (-local[1]).atan2(local[0]).to_degrees().rem_euclid(360.0)
+    }
+
+    fn contains(&self, pos: na::Vector2<f64>, total_zoom: f64) -> bool {
+        let local = self.to_local(pos);
+        let radius = Self::RADIUS / total_zoom;
+        if local[1] <= 0.0 {
+            local.magnitude() <= radius
+        } else {
+            local[1] <= Self::BASE_HEIGHT / total_zoom && local[0].abs() <= radius
+        }
+    }
+
+    /// Track the position of the pen to show its angle.
+    ///
+    /// Returns None when the pointer did not change.
+    pub(crate) fn update_pointer(&mut self, event: &PenEvent) -> Option<WidgetFlags> {
+        if !self.visible || self.grab.is_some() {
+            return None;
+        }
+        let pointer = match event {
+            PenEvent::Down { element, .. } | PenEvent::Proximity { element, .. } => {
+                Some(element.pos)
+            }
+            PenEvent::Cancel => None,
+            _ => return None,
+        };
+        if pointer == self.pointer {
+            return None;
+        }
+        self.pointer = pointer;
+
+        let mut widget_flags = WidgetFlags::default();
+        widget_flags.redraw = true;
+        Some(widget_flags)
+    }
+
+    /// Handle the event when it grabs or moves the protractor.
+    ///
+    /// Returns None when the event was not handled by the protractor.
+    pub(crate) fn handle_event(
+        &mut self,
+        event: &PenEvent,
+        total_zoom: f64,
+    ) -> Option<WidgetFlags> {
+        if !self.visible {
+            return None;
+        }
+        let mut widget_flags = WidgetFlags::default();
+
+        match (&mut self.grab, event) {
+            (None, PenEvent::Down { element, .. }) => {
+                if !self.contains(element.pos, total_zoom) {
+                    return None;
+                }
+
+                let distance = self.to_local(element.pos).magnitude();
+                self.grab =
+                    if distance > Self::RADIUS * (1.0 - Self::ROTATE_RIM_FRACTION) / total_zoom {
+                        Some(ProtractorGrab::Rotate {
+                            angle_offset: self.angle - self.angle_to(element.pos),
+                        })
+                    } else {
+                        Some(ProtractorGrab::Translate {
+                            last_pos: element.pos,
+                        })
+                    };
+                self.pointer = None;
+            }
+            (Some(ProtractorGrab::Translate { last_pos }), PenEvent::Down { element, .. }) => {
+                self.center += element.pos - *last_pos;
+                *last_pos = element.pos;
+            }
+            (Some(ProtractorGrab::Rotate { angle_offset }), PenEvent::Down { element, .. }) => {
+                let angle_offset = *angle_offset;
+                let angle = self.angle_to(element.pos) + angle_offset;
+                let snapped = (angle / Self::ANGLE_SNAP_STEP).round() * Self::ANGLE_SNAP_STEP;
+                self.angle = if (angle - snapped).abs() < Self::ANGLE_SNAP_TOLERANCE {
+                    snapped
+                } else {
+                    angle
+                };
+            }
+            (Some(_), PenEvent::Up { .. } | PenEvent::Cancel) => {
+                self.grab = None;
+            }
+            (Some(_), _) => {}
+            (None, _) => return None,
+        }
+
+        widget_flags.redraw = true;
+        Some(widget_flags)
+    }
+
+    /// Draw the label centered at the position, which is in the coordinate space of the protractor.
+    fn draw_label(
+        cx: &mut piet_cairo::CairoRenderContext,
+        text: String,
+        pos: na::Vector2<f64>,
+        color: &piet::Color,
+        total_zoom: f64,
+    ) -> anyhow::Result<()> {
+        let layout = cx
+            .text()
+            .new_text_layout(text)
+            .text_color(*color)
+            .font(piet::FontFamily::SANS_SERIF, Self::FONT_SIZE / total_zoom)
+            .build()
+            .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        let size = layout.size();
+        cx.draw_text(
+            &layout,
+            (pos - na::vector![size.width, size.height] * 0.5).to_kurbo_point(),
+        );
+        Ok(())
+    }
+}
+
+impl DrawableOnDoc for Protractor {
+    fn bounds_on_doc(&self, engine_view: &EngineView) -> Option<Aabb> {
+        if !self.visible {
+            return None;
+        }
+        let total_zoom = engine_view.camera.total_zoom();
+        let mut bounds = Aabb::from_half_extents(
+            self.center.into(),
+            na::Vector2::repeat(Self::RADIUS / total_zoom),
+        );
+        if let Some(pointer) = self.pointer {
+            bounds.take_point(pointer.into());
+        }
+        Some(bounds.loosened((Self::OUTLINE_WIDTH + Self::FONT_SIZE * 2.0) / total_zoom))
+    }
+
+    fn draw_on_doc(
+        &self,
+        cx: &mut piet_cairo::CairoRenderContext,
+        engine_view: &EngineView,
+    ) -> anyhow::Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        let total_zoom = engine_view.camera.total_zoom();
+        let radius = Self::RADIUS / total_zoom;
+        let base_height = Self::BASE_HEIGHT / total_zoom;
+
+        cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        cx.transform(self.transform().to_kurbo());
+
+        let mut body = kurbo::BezPath::new();
+        body.move_to((-radius, base_height));
+        body.line_to((-radius, 0.0));
+        body.extend(
+            kurbo::Arc::new((0.0, 0.0), (radius, radius), PI, PI, 0.0)
+                .append_iter(0.1 / total_zoom),
+        );
+        body.line_to((radius, base_height));
+        body.close_path();
+        cx.fill(body.clone(), &Self::FILL_COLOR);
+        cx.stroke(body, &Self::OUTLINE_COLOR, Self::OUTLINE_WIDTH / total_zoom);
+
+        // The baseline and the rim that rotates the protractor
+        cx.stroke(
+            kurbo::Line::new((-radius, 0.0), (radius, 0.0)),
+            &Self::TICK_COLOR,
+            Self::OUTLINE_WIDTH / total_zoom,
+        );
+        let rim_radius = radius * (1.0 - Self::ROTATE_RIM_FRACTION);
+        cx.stroke(
+            kurbo::Arc::new((0.0, 0.0), (rim_radius, rim_radius), PI, PI, 0.0),
+            &Self::TICK_COLOR,
+            1.0 / total_zoom,
+        );
+        cx.fill(
+            kurbo::Circle::new((0.0, 0.0), 3.0 / total_zoom),
+            &Self::OUTLINE_COLOR,
+        );
+
+        // Tick marks every degree, every fifth and tenth one is longer, labels every thirty degrees
+        for deg in 0..=180_u32 {
+            let length = if deg % 10 == 0 {
+                14.0
+            } else if deg % 5 == 0 {
+                10.0
+            } else {
+                6.0
+            } / total_zoom;
+            let direction = na::vector![
+                (deg as f64).to_radians().cos(),
+                -(deg as f64).to_radians().sin()
+            ];
+            cx.stroke(
+                kurbo::Line::new(
+                    (direction * radius).to_kurbo_point(),
+                    (direction * (radius - length)).to_kurbo_point(),
+                ),
+                &Self::TICK_COLOR,
+                1.0 / total_zoom,
+            );
+            if deg % 30 == 0 {
+                Self::draw_label(
+                    cx,
+                    format!("{deg}°"),
+                    direction * (radius - 28.0 / total_zoom),
+                    &Self::OUTLINE_COLOR,
+                    total_zoom,
+                )?;
+            }
+        }
+
+        // The ray to the pen and its angle relative to the baseline
+        if let Some(pointer) = self.pointer {
+            let local = self.to_local(pointer);
+            if local.magnitude() > 0.0 {
+                let direction = local.normalize();
+                cx.stroke(
+                    kurbo::Line::new((0.0, 0.0), local.to_kurbo_point()),
+                    &Self::POINTER_COLOR,
+                    Self::OUTLINE_WIDTH / total_zoom,
+                );
+                Self::draw_label(
+                    cx,
+                    format!("{:.1}°", self.angle_deg_to(pointer)),
+                    direction * (radius * 0.5),
+                    &Self::POINTER_COLOR,
+                    total_zoom,
+                )?;
+            }
+        }
+
+        cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+// Imports
+use crate::engine::EngineView;
+use crate::{DrawableOnDoc, WidgetFlags};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::RenderContext;
+use rnote_compose::color;
+use rnote_compose::ext::Vector2Ext;
+use rnote_compose::penevent::PenEvent;
+use rnote_compose::shapes::{Rectangle, Shapeable};
+use rnote_compose::transform::Transform;
+
+/// How the ruler is currently moved.
+#[derive(Debug, Clone, Copy)]
+enum RulerGrab {
+    /// Moved along with the pen.
+    Translate { last_pos: na::Vector2<f64> },
+    /// Rotated around its center, keeping the angle between the pen and the ruler.
+    Rotate { angle_offset: f64 },
+}
+
+/// A straight edge overlay that brush strokes started along its edges snap onto.
+///
+/// It is positioned in document coordinates, but keeps its size on the screen regardless of the zoom.
+/// Grabbing its body moves it, grabbing one of its ends rotates it.
+#[derive(Debug, Clone)]
+pub struct Ruler {
+    visible: bool,
+    center: na::Vector2<f64>,
+    angle: f64,
+    grab: Option<RulerGrab>,
+}
+
+impl Default for Ruler {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            center: na::Vector2::zeros(),
+            angle: 0.0,
+            grab: None,
+        }
+    }
+}
+
+/// An edge of the ruler that a stroke is snapped onto.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RulerEdge {
+    origin: na::Vector2<f64>,
+    /// The normalized direction of the edge.
+    direction: na::Vector2<f64>,
+}
+
+impl RulerEdge {
+    /// Project the position onto the edge.
+    pub(crate) fn project(&self, pos: na::Vector2<f64>) -> na::Vector2<f64> {
+        self.origin + self.direction * (pos - self.origin).dot(&self.direction)
+    }
+
+    /// Project the positions of the down and up events onto the edge.
+    pub(crate) fn snap_event(&self, event: PenEvent) -> PenEvent {
+        match event {
+            PenEvent::Down {
+                mut element,
+                modifier_keys,
+            } => {
+                element.pos = self.project(element.pos);
+                PenEvent::Down {
+                    element,
+                    modifier_keys,
+                }
+            }
+            PenEvent::Up {
+                mut element,
+                modifier_keys,
+            } => {
+                element.pos = self.project(element.pos);
+                PenEvent::Up {
+                    element,
+                    modifier_keys,
+                }
+            }
+            event => event,
+        }
+    }
+}
+
+impl Ruler {
+    /// The length of the ruler in surface coordinates.
+    const LENGTH: f64 = 800.0;
+    /// The width of the ruler in surface coordinates.
+    const WIDTH: f64 = 72.0;
+    /// The fraction of the length at both ends of the ruler where it is rotated instead of moved.
+    const ROTATE_HANDLE_FRACTION: f64 = 0.15;
+    /// The distance in surface coordinates outside of the edges, inside which strokes are snapped onto them.
+    const EDGE_SNAP_DISTANCE: f64 = 24.0;
+    /// The angles are snapped to multiples of this angle when they are close to them.
+    const ANGLE_SNAP_STEP: f64 = std::f64::consts::PI / 12.0;
+    /// The tolerance of the angle snapping.
+    const ANGLE_SNAP_TOLERANCE: f64 = std::f64::consts::PI / 90.0;
+    /// The distance between the tick marks in document coordinates.
+    const TICK_SPACING: f64 = 10.0;
+    /// Tick marks closer than this in surface coordinates are not drawn.
+    const TICK_SPACING_MIN: f64 = 4.0;
+    const FILL_COLOR: piet::Color = color::GNOME_BRIGHTS[1].with_a8(90);
+    const OUTLINE_COLOR: piet::Color = color::GNOME_DARKS[3].with_a8(200);
+    const TICK_COLOR: piet::Color = color::GNOME_DARKS[3].with_a8(160);
+    const OUTLINE_WIDTH: f64 = 1.5;
+
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Show the ruler centered at the given position, or hide it.
+    pub(crate) fn set_visible(&mut self, visible: bool, center: na::Vector2<f64>) {
+        if visible && !self.visible {
+            self.center = center;
+        }
+        self.visible = visible;
+        self.grab = None;
+    }
+
+    /// Whether the ruler is currently grabbed and moved by the pen.
+    pub(crate) fn grabbed(&self) -> bool {
+        self.grab.is_some()
+    }
+
+    fn half_extents(&self, total_zoom: f64) -> na::Vector2<f64> {
+        na::vector![Self::LENGTH * 0.5, Self::WIDTH * 0.5] / total_zoom
+    }
+
+    fn rectangle(&self, total_zoom: f64) -> Rectangle {
+        Rectangle {
+            cuboid: p2d::shape::Cuboid::new(self.half_extents(total_zoom)),
+            transform: Transform::new_w_isometry(na::Isometry2::new(self.center, self.angle)),
+        }
+    }
+
+    /// The position in the coordinate space of the ruler, with its length along the x-axis.
+    fn to_local(&self, pos: na::Vector2<f64>) -> na::Vector2<f64> {
+        na::Rotation2::new(-self.angle) * (pos - self.center)
+    }
+
+    fn angle_to(&self, pos: na::Vector2<f64>) -> f64 {
+        let offset = pos - self.center;
+        offset[1].atan2(offset[0])
+    }
+
+    /// Handle the event when it grabs or moves the ruler.
+    ///
+    /// Returns None when the event was not handled by the ruler.
+    pub(crate) fn handle_event(
+        &mut self,
+        event: &PenEvent,
+        total_zoom: f64,
+    ) -> Option<WidgetFlags> {
+        if !self.visible {
+            return None;
+        }
+        let mut widget_flags = WidgetFlags::default();
+
+        match (&mut self.grab, event) {
+            (None, PenEvent::Down { element, .. }) => {
+                let local = self.to_local(element.pos);
+                let half_extents = self.half_extents(total_zoom);
+                if local[0].abs() > half_extents[0] || local[1].abs() > half_extents[1] {
+                    return None;
+                }
+
+                self.grab =
+                    if local[0].abs() > half_extents[0] * (1.0 - Self::ROTATE_HANDLE_FRACTION) {
+                        Some(RulerGrab::Rotate {
+                            angle_offset: self.angle - self.angle_to(element.pos),
+                        })
+                    } else {
+                        Some(RulerGrab::Translate {
+                            last_pos: element.pos,
+                        })
+                    };
+            }
+            (Some(RulerGrab::Translate { last_pos }), PenEvent::Down { element, .. }) => {
+                self.center += element.pos - *last_pos;
+                *last_pos = element.pos;
+            }
+            (Some(RulerGrab::Rotate { angle_offset }), PenEvent::Down { element, .. }) => {
+                let angle_offset = *angle_offset;
+                let angle = self.angle_to(element.pos) + angle_offset;
+                let snapped = (angle / Self::ANGLE_SNAP_STEP).round() * Self::ANGLE_SNAP_STEP;
+                self.angle = if (angle - snapped).abs() < Self::ANGLE_SNAP_TOLERANCE {
+                    snapped
+                } else {
+                    angle
+                };
+            }
+            (Some(_), PenEvent::Up { .. } | PenEvent::Cancel) => {
+                self.grab = None;
+            }
+            (Some(_), _) => {}
+            (None, _) => return None,
+        }
+
+        widget_flags.redraw = true;
+        Some(widget_flags)
+    }
+
+    /// The edge the stroke starting at the position snaps onto, if it is close to one outside of the ruler.
+    ///
+    /// The edge is offset outwards by the given distance, usually half of the stroke width, so that the stroke
+    /// lines up with the ruler.
+    pub(crate) fn edge_near(
+        &self,
+        pos: na::Vector2<f64>,
+        offset: f64,
+        total_zoom: f64,
+    ) -> Option<RulerEdge> {
+        if !self.visible {
+            return None;
+        }
+        let local = self.to_local(pos);
+        let half_extents = self.half_extents(total_zoom);
+        let distance = local[1].abs() - half_extents[1];
+        if local[0].abs() > half_extents[0]
+            || !(0.0..=Self::EDGE_SNAP_DISTANCE / total_zoom).contains(&distance)
+        {
+            return None;
+        }
+
+        let rotation = na::Rotation2::new(self.angle);
+        let side = local[1].signum();
+        Some(RulerEdge {
+            origin: self.center + rotation * na::vector![0.0, side * (half_extents[1] + offset)],
+            direction: rotation * na::Vector2::x(),
+        })
+    }
+}
+
+impl DrawableOnDoc for Ruler {
+    fn bounds_on_doc(&self, engine_view: &EngineView) -> Option<Aabb> {
+        self.visible.then(|| {
+            self.rectangle(engine_view.camera.total_zoom())
+                .bounds()
+                .loosened(Self::OUTLINE_WIDTH / engine_view.camera.total_zoom())
+        })
+    }
+
+    fn draw_on_doc(
+        &self,
+        cx: &mut piet_cairo::CairoRenderContext,
+        engine_view: &EngineView,
+    ) -> anyhow::Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        let total_zoom = engine_view.camera.total_zoom();
+        let half_extents = self.half_extents(total_zoom);
+
+        cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        cx.transform(self.rectangle(total_zoom).transform.to_kurbo());
+
+        let body = kurbo::Rect::from_points(
+            (-half_extents[0], -half_extents[1]),
+            (half_extents[0], half_extents[1]),
+        );
+        cx.fill(body, &Self::FILL_COLOR);
+        cx.stroke(body, &Self::OUTLINE_COLOR, Self::OUTLINE_WIDTH / total_zoom);
+
+        // The ends that rotate the ruler
+        for side in [-1.0, 1.0] {
+            let x = side * half_extents[0] * (1.0 - Self::ROTATE_HANDLE_FRACTION);
+            cx.stroke(
+                kurbo::Line::new((x, -half_extents[1]), (x, half_extents[1])),
+                &Self::TICK_COLOR,
+                Self::OUTLINE_WIDTH / total_zoom,
+            );
+        }
+
+        // Tick marks along both edges, every fifth one is longer
+        if Self::TICK_SPACING * total_zoom >= Self::TICK_SPACING_MIN {
+            let n_ticks = (half_extents[0] / Self::TICK_SPACING).floor() as i64;
+            for i in -n_ticks..=n_ticks {
+                let x = i as f64 * Self::TICK_SPACING;
+                let length = if i % 5 == 0 { 12.0 } else { 6.0 };
+                for side in [-1.0, 1.0] {
+                    let edge = na::vector![x, side * half_extents[1]];
+                    cx.stroke(
+                        kurbo::Line::new(
+                            edge.to_kurbo_point(),
+                            (edge - na::vector![0.0, side * length / total_zoom]).to_kurbo_point(),
+                        ),
+                        &Self::TICK_COLOR,
+                        1.0 / total_zoom,
+                    );
+                }
+            }
+        }
+
+        cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+}
@@ -21,6 +21,7 @@ use rnote_compose::ext::{AabbExt, Vector2Ext};
 use rnote_compose::penevent::{PenEvent, PenProgress, PenState};
 use rnote_compose::penpath::Element;
 use rnote_compose::style::indicators;
+use rnote_compose::transform;
 use rnote_compose::{Color, color};
 use std::time::Instant;
 use tracing::error;
@@ -33,6 +34,18 @@ pub(super) enum ResizeCorner {
     BottomRight,
 }
 
+impl ResizeCorner {
+    /// The index of the corner in the corners of a quadrilateral, ordered top-left, top-right, bottom-right, bottom-left.
+    pub(super) fn quad_index(self) -> usize {
+        match self {
+            Self::TopLeft => 0,
+            Self::TopRight => 1,
+            Self::BottomRight => 2,
+            Self::BottomLeft => 3,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub(super) enum ModifyState {
     Idle,
@@ -52,6 +65,14 @@ pub(super) enum ModifyState {
         start_pos: na::Vector2<f64>,
         last_rendered_bounds: Aabb,
     },
+    /// Warp the selection into perspective by dragging a single corner, or skew it by dragging an edge.
+    Warp {
+        from_corner: ResizeCorner,
+        start_bounds: Aabb,
+        start_pos: na::Vector2<f64>,
+        /// The corners of the warped start bounds, in the order top-left, top-right, bottom-right, bottom-left.
+        current_corners: [na::Vector2<f64>; 4],
+    },
 }
 
 impl Default for ModifyState {
@@ -152,6 +173,9 @@ impl PenBehaviour for Selector {
                 modifier_keys,
             } => self.handle_pen_event_keypressed(keyboard_key, modifier_keys, now, engine_view),
             PenEvent::Text { text } => self.handle_pen_event_text(text, now, engine_view),
+            PenEvent::LongPress { .. } | PenEvent::DoubleTap { .. } => {
+                self.handle_pen_event_gesture(now, engine_view)
+            }
             PenEvent::Cancel => self.handle_pen_event_cancel(now, engine_view),
         }
     }
@@ -374,7 +398,7 @@ impl DrawableOnDoc for Selector {
                             );
                         }
                     }
-                    SelectorStyle::Single => {
+                    SelectorStyle::Single | SelectorStyle::Color => {
                         if let Some(last) = path.last() {
                             cx.stroke(
                                 kurbo::Circle::new(
@@ -450,6 +474,11 @@ impl DrawableOnDoc for Selector {
                             engine_view.camera,
                         )?;
                     }
+                    ModifyState::Warp {
+                        current_corners, ..
+                    } => {
+                        Self::draw_warp_indicator(cx, *current_corners, engine_view.camera)?;
+                    }
                     _ => {}
                 }
             }
@@ -463,6 +492,8 @@ impl DrawableOnDoc for Selector {
 impl Selector {
     /// The threshold where above it the translation is applied. In surface coordinates.
     const TRANSLATE_OFFSET_THRESHOLD: f64 = 1.414;
+    /// The threshold where above it the warp is applied. In surface coordinates.
+    const WARP_OFFSET_THRESHOLD: f64 = 1.414;
     /// The threshold angle (in radians) where above it the rotation is applied.
     const ROTATE_ANGLE_THRESHOLD: f64 = ((2.0 * std::f64::consts::PI) / 360.0) * 0.2;
     /// The outline stroke width when drawing a selection.
@@ -482,7 +513,10 @@ impl Selector {
 
     fn add_to_select_path(style: SelectorStyle, path: &mut Vec<Element>, element: Element) {
         match style {
-            SelectorStyle::Polygon | SelectorStyle::Single | SelectorStyle::IntersectingPath => {
+            SelectorStyle::Polygon
+            | SelectorStyle::Single
+            | SelectorStyle::IntersectingPath
+            | SelectorStyle::Color => {
                 path.push(element);
             }
             SelectorStyle::Rectangle => {
@@ -569,6 +603,9 @@ impl Selector {
             ModifyState::Resize {
                 from_corner: ResizeCorner::TopLeft,
                 ..
+            } | ModifyState::Warp {
+                from_corner: ResizeCorner::TopLeft,
+                ..
             }
         ) {
             PenState::Down
@@ -589,6 +626,9 @@ impl Selector {
             ModifyState::Resize {
                 from_corner: ResizeCorner::TopRight,
                 ..
+            } | ModifyState::Warp {
+                from_corner: ResizeCorner::TopRight,
+                ..
             }
         ) {
             PenState::Down
@@ -609,6 +649,9 @@ impl Selector {
             ModifyState::Resize {
                 from_corner: ResizeCorner::BottomLeft,
                 ..
+            } | ModifyState::Warp {
+                from_corner: ResizeCorner::BottomLeft,
+                ..
             }
         ) {
             PenState::Down
@@ -629,6 +672,9 @@ impl Selector {
             ModifyState::Resize {
                 from_corner: ResizeCorner::BottomRight,
                 ..
+            } | ModifyState::Warp {
+                from_corner: ResizeCorner::BottomRight,
+                ..
             }
         ) {
             PenState::Down
@@ -791,9 +837,70 @@ impl Selector {
         Ok(())
     }
 
+    fn draw_warp_indicator(
+        piet_cx: &mut impl RenderContext,
+        corners: [na::Vector2<f64>; 4],
+        camera: &Camera,
+    ) -> anyhow::Result<()> {
+        piet_cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        let total_zoom = camera.total_zoom();
+
+        let mut quad = kurbo::BezPath::new();
+        quad.move_to(corners[0].to_kurbo_point());
+        for corner in &corners[1..] {
+            quad.line_to(corner.to_kurbo_point());
+        }
+        quad.close_path();
+
+        let mut stroke_style = piet::StrokeStyle::new();
+        stroke_style.set_dash_pattern(
+            Self::SELECTING_DASH_PATTERN
+                .into_iter()
+                .map(|x| x / total_zoom)
+                .collect::<Vec<f64>>(),
+        );
+        piet_cx.stroke_styled(
+            quad,
+            &Self::SELECTION_OUTLINE_COLOR,
+            Self::OUTLINE_STROKE_WIDTH / total_zoom,
+            &stroke_style,
+        );
+
+        piet_cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+
+    /// The modify state when starting to drag one of the corner nodes, either resizing or warping the selection.
+    fn corner_modify_state(
+        from_corner: ResizeCorner,
+        selection_bounds: Aabb,
+        start_pos: na::Vector2<f64>,
+        warp: bool,
+    ) -> ModifyState {
+        if warp {
+            ModifyState::Warp {
+                from_corner,
+                start_bounds: selection_bounds,
+                start_pos,
+                current_corners: transform::bounds_corners(selection_bounds),
+            }
+        } else {
+            ModifyState::Resize {
+                from_corner,
+                start_bounds: selection_bounds,
+                start_pos,
+                last_rendered_bounds: selection_bounds,
+            }
+        }
+    }
+
     fn select_all(&mut self, engine_view: &mut EngineViewMut, widget_flags: &mut WidgetFlags) {
         // Select all keys
-        let all_strokes = engine_view.store.stroke_keys_as_rendered();
+        let all_strokes = engine_view.store.expand_to_groups(
+            engine_view
+                .store
+                .filter_unlocked(engine_view.store.stroke_keys_as_rendered()),
+        );
 
         if !all_strokes.is_empty() {
             engine_view.store.set_selected_keys(&all_strokes, true);
@@ -12,6 +12,7 @@ use rnote_compose::eventresult::{EventPropagation, EventResult};
 use rnote_compose::ext::{AabbExt, Vector2Ext};
 use rnote_compose::penevent::{KeyboardKey, ModifierKey, PenProgress};
 use rnote_compose::penpath::Element;
+use rnote_compose::transform::{self, Projective};
 use std::collections::HashSet;
 use std::time::Instant;
 
@@ -80,14 +81,16 @@ impl Selector {
 
                 match modify_state {
                     ModifyState::Idle => {
+                        let warp = engine_view.config.pens_config.selector_config.resize_warp
+                            || modifier_keys.contains(&ModifierKey::KeyboardAlt);
                         // If we click on another, not-already selected stroke while in separate style or
                         // while pressing Shift, we add it to the selection
                         let key_to_add = engine_view
                             .store
-                            .stroke_hitboxes_contain_coord(
+                            .filter_unlocked(engine_view.store.stroke_hitboxes_contain_coord(
                                 engine_view.camera.viewport(),
                                 element.pos,
-                            )
+                            ))
                             .pop();
 
                         if (modifier_keys.contains(&ModifierKey::KeyboardShift))
@@ -95,9 +98,14 @@ impl Selector {
                                 .and_then(|key| engine_view.store.selected(key).map(|s| !s))
                                 .unwrap_or(false)
                         {
-                            let key_to_add = key_to_add.unwrap();
-                            engine_view.store.set_selected(key_to_add, true);
-                            selection.push(key_to_add);
+                            let keys_to_add = engine_view
+                                .store
+                                .expand_to_groups(vec![key_to_add.unwrap()])
+                                .into_iter()
+                                .filter(|key| !selection.contains(key))
+                                .collect::<Vec<StrokeKey>>();
+                            engine_view.store.set_selected_keys(&keys_to_add, true);
+                            selection.extend(keys_to_add);
                             if let Some(new_bounds) =
                                 engine_view.store.bounds_for_strokes(selection)
                             {
@@ -125,12 +133,12 @@ impl Selector {
                         )
                         .contains_local_point(&element.pos.into())
                         {
-                            *modify_state = ModifyState::Resize {
-                                from_corner: ResizeCorner::TopLeft,
-                                start_bounds: *selection_bounds,
-                                start_pos: element.pos,
-                                last_rendered_bounds: *selection_bounds,
-                            }
+                            *modify_state = Self::corner_modify_state(
+                                ResizeCorner::TopLeft,
+                                *selection_bounds,
+                                element.pos,
+                                warp,
+                            );
                         } else if Self::resize_node_bounds(
                             ResizeCorner::TopRight,
                             *selection_bounds,
@@ -138,12 +146,12 @@ impl Selector {
                         )
                         .contains_local_point(&element.pos.into())
                         {
-                            *modify_state = ModifyState::Resize {
-                                from_corner: ResizeCorner::TopRight,
-                                start_bounds: *selection_bounds,
-                                start_pos: element.pos,
-                                last_rendered_bounds: *selection_bounds,
-                            }
+                            *modify_state = Self::corner_modify_state(
+                                ResizeCorner::TopRight,
+                                *selection_bounds,
+                                element.pos,
+                                warp,
+                            );
                         } else if Self::resize_node_bounds(
                             ResizeCorner::BottomLeft,
                             *selection_bounds,
@@ -151,12 +159,12 @@ impl Selector {
                         )
                         .contains_local_point(&element.pos.into())
                         {
-                            *modify_state = ModifyState::Resize {
-                                from_corner: ResizeCorner::BottomLeft,
-                                start_bounds: *selection_bounds,
-                                start_pos: element.pos,
-                                last_rendered_bounds: *selection_bounds,
-                            }
+                            *modify_state = Self::corner_modify_state(
+                                ResizeCorner::BottomLeft,
+                                *selection_bounds,
+                                element.pos,
+                                warp,
+                            );
                         } else if Self::resize_node_bounds(
                             ResizeCorner::BottomRight,
                             *selection_bounds,
@@ -164,12 +172,12 @@ impl Selector {
                         )
                         .contains_local_point(&element.pos.into())
                         {
-                            *modify_state = ModifyState::Resize {
-                                from_corner: ResizeCorner::BottomRight,
-                                start_bounds: *selection_bounds,
-                                start_pos: element.pos,
-                                last_rendered_bounds: *selection_bounds,
-                            }
+                            *modify_state = Self::corner_modify_state(
+                                ResizeCorner::BottomRight,
+                                *selection_bounds,
+                                element.pos,
+                                warp,
+                            );
                         } else if engine_view.config.pens_config.selector_config.style
                             == SelectorStyle::Single
                             && key_to_add
@@ -178,9 +186,14 @@ impl Selector {
                         {
                             // defer the [SelectorStyle::Single] after checking whether we are
                             // clicking on a selection node
-                            let key_to_add = key_to_add.unwrap();
-                            engine_view.store.set_selected(key_to_add, true);
-                            selection.push(key_to_add);
+                            let keys_to_add = engine_view
+                                .store
+                                .expand_to_groups(vec![key_to_add.unwrap()])
+                                .into_iter()
+                                .filter(|key| !selection.contains(key))
+                                .collect::<Vec<StrokeKey>>();
+                            engine_view.store.set_selected_keys(&keys_to_add, true);
+                            selection.extend(keys_to_add);
                             if let Some(new_bounds) =
                                 engine_view.store.bounds_for_strokes(selection)
                             {
@@ -394,6 +407,70 @@ impl Selector {
                             *last_rendered_bounds = *selection_bounds;
                         }
                     }
+                    ModifyState::Warp {
+                        from_corner,
+                        start_bounds,
+                        start_pos,
+                        current_corners,
+                    } => {
+                        let skew = modifier_keys.contains(&ModifierKey::KeyboardShift);
+                        let offset = element.pos - *start_pos;
+                        let mut new_corners = transform::bounds_corners(*start_bounds);
+                        let i = from_corner.quad_index();
+
+                        if skew {
+                            // Move the edge along its direction together with the adjacent corner
+                            let (adjacent, offset) = if offset[0].abs() >= offset[1].abs() {
+                                // top-left <-> top-right, bottom-right <-> bottom-left
+                                (i ^ 1, na::vector![offset[0], 0.0])
+                            } else {
+                                // top-left <-> bottom-left, top-right <-> bottom-right
+                                (3 - i, na::vector![0.0, offset[1]])
+                            };
+                            new_corners[i] += offset;
+                            new_corners[adjacent] += offset;
+                        } else {
+                            new_corners[i] = engine_view
+                                .document
+                                .snap_position(new_corners[i] + offset, engine_view.config);
+                        }
+
+                        let moved = new_corners
+                            .iter()
+                            .zip(current_corners.iter())
+                            .map(|(new, current)| (new - current).magnitude())
+                            .fold(0.0, f64::max);
+
+                        // Warps that fold the selection over itself are not applied
+                        if moved > Self::WARP_OFFSET_THRESHOLD / engine_view.camera.total_zoom()
+                            && transform::quad_is_convex(new_corners)
+                            && let Some(projective) =
+                                Projective::from_quad_to_quad(*current_corners, new_corners)
+                        {
+                            engine_view.store.warp_strokes(selection, &projective);
+                            *current_corners = new_corners;
+
+                            if let Some(new_bounds) =
+                                engine_view.store.bounds_for_strokes(selection)
+                            {
+                                *selection_bounds = new_bounds;
+                            }
+                            engine_view.store.regenerate_rendering_in_viewport_threaded(
+                                engine_view.tasks_tx.clone(),
+                                false,
+                                engine_view.camera.viewport(),
+                                engine_view.camera.image_scale(),
+                            );
+                        }
+
+                        // possibly nudge camera
+                        widget_flags |= engine_view
+                            .camera
+                            .nudge_w_pos(element.pos, engine_view.document);
+                        widget_flags |= engine_view
+                            .document
+                            .expand_autoexpand(engine_view.camera, engine_view.store);
+                    }
                 }
 
                 widget_flags.store_modified = true;
@@ -458,10 +535,10 @@ impl Selector {
                         if let Some(key) = path.last().and_then(|last| {
                             engine_view
                                 .store
-                                .stroke_hitboxes_contain_coord(
+                                .filter_unlocked(engine_view.store.stroke_hitboxes_contain_coord(
                                     engine_view.camera.viewport(),
                                     last.pos,
-                                )
+                                ))
                                 .pop()
                         }) {
                             vec![key]
@@ -479,7 +556,42 @@ impl Selector {
                             vec![]
                         }
                     }
+                    SelectorStyle::Color => {
+                        if let Some(color) = path
+                            .last()
+                            .and_then(|last| {
+                                engine_view
+                                    .store
+                                    .stroke_hitboxes_contain_coord(
+                                        engine_view.camera.viewport(),
+                                        last.pos,
+                                    )
+                                    .pop()
+                            })
+                            .and_then(|key| engine_view.store.get_stroke_ref(key))
+                            .and_then(|stroke| stroke.stroke_color())
+                        {
+                            engine_view.store.filter_unlocked(
+                                engine_view.store.stroke_keys_as_rendered_with_color(
+                                    color,
+                                    engine_view
+                                        .config
+                                        .pens_config
+                                        .selector_config
+                                        .color_tolerance,
+                                ),
+                            )
+                        } else {
+                            vec![]
+                        }
+                    }
                 };
+                let new_selection = engine_view.store.filter_stroke_kind(
+                    new_selection,
+                    engine_view.config.pens_config.selector_config.stroke_filter,
+                );
+                // Grouped strokes are always selected together
+                let new_selection = engine_view.store.expand_to_groups(new_selection);
 
                 if !new_selection.is_empty() {
                     engine_view.store.set_selected_keys(&new_selection, true);
@@ -506,7 +618,8 @@ impl Selector {
                 match modify_state {
                     ModifyState::Translate { .. }
                     | ModifyState::Rotate { .. }
-                    | ModifyState::Resize { .. } => {
+                    | ModifyState::Resize { .. }
+                    | ModifyState::Warp { .. } => {
                         engine_view.store.update_geometry_for_strokes(selection);
                         widget_flags |= engine_view
                             .document
@@ -748,6 +861,35 @@ impl Selector {
         (event_result, widget_flags)
     }
 
+    /// Long-press and double-tap events are not used yet.
+    pub(super) fn handle_pen_event_gesture(
+        &mut self,
+        _now: Instant,
+        _engine_view: &mut EngineViewMut,
+    ) -> (EventResult<PenProgress>, WidgetFlags) {
+        let widget_flags = WidgetFlags::default();
+
+        let event_result = match &self.state {
+            SelectorState::Idle => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::Idle,
+            },
+            SelectorState::Selecting { .. } => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::InProgress,
+            },
+            SelectorState::ModifySelection { .. } => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::InProgress,
+            },
+        };
+
+        (event_result, widget_flags)
+    }
+
     pub(super) fn handle_pen_event_cancel(
         &mut self,
         _now: Instant,
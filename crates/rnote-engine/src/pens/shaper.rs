@@ -7,16 +7,20 @@ use crate::strokes::Stroke;
 use crate::{DrawableOnDoc, WidgetFlags};
 use p2d::bounding_volume::Aabb;
 use piet::RenderContext;
-use rnote_compose::Shape;
 use rnote_compose::builders::buildable::{Buildable, BuilderCreator, BuilderProgress};
-use rnote_compose::builders::{ArrowBuilder, GridBuilder, PolygonBuilder, PolylineBuilder};
+use rnote_compose::builders::{
+    ArcBuilder, ArrowBuilder, CompassBuilder, GridBuilder, PolygonBuilder, PolylineBuilder,
+    SplineBuilder,
+};
 use rnote_compose::builders::{
     CoordSystem2DBuilder, CoordSystem3DBuilder, CubBezBuilder, EllipseBuilder, FociEllipseBuilder,
-    LineBuilder, QuadBezBuilder, QuadrantCoordSystem2DBuilder, RectangleBuilder, ShapeBuilderType,
+    LineBuilder, QuadBezBuilder, QuadrantCoordSystem2DBuilder, RectangleBuilder,
+    RegularPolygonBuilder, RoundedRectangleBuilder, ShapeBuilderType, StarBuilder,
 };
 use rnote_compose::eventresult::{EventPropagation, EventResult};
 use rnote_compose::penevent::{KeyboardKey, ModifierKey, PenEvent, PenProgress};
 use rnote_compose::penpath::Element;
+use rnote_compose::{Constraints, Shape};
 use std::time::Instant;
 
 #[derive(Debug)]
@@ -24,6 +28,8 @@ enum ShaperState {
     Idle,
     BuildShape {
         builder: Box<dyn Buildable<Emit = Shape>>,
+        /// The points of the existing geometry that positions snap onto.
+        snap_candidates: Vec<na::Vector2<f64>>,
     },
 }
 
@@ -72,9 +78,25 @@ impl PenBehaviour for Shaper {
                     .pens_config
                     .shaper_config
                     .new_style_seeds();
-                element.pos = engine_view
-                    .document
-                    .snap_position(element.pos, engine_view.config);
+                let snap_candidates = if engine_view
+                    .config
+                    .pens_config
+                    .shaper_config
+                    .constraints
+                    .object_snap
+                {
+                    engine_view
+                        .store
+                        .snap_candidates_in_bounds(engine_view.camera.viewport())
+                } else {
+                    vec![]
+                };
+                let constraints = object_snap_constraints(engine_view, snap_candidates.clone());
+                element.pos = constraints.snap_to_objects(element.pos).unwrap_or_else(|| {
+                    engine_view
+                        .document
+                        .snap_position(element.pos, engine_view.config)
+                });
 
                 self.state = ShaperState::BuildShape {
                     builder: new_builder(
@@ -82,6 +104,7 @@ impl PenBehaviour for Shaper {
                         element,
                         now,
                     ),
+                    snap_candidates,
                 };
 
                 EventResult {
@@ -104,14 +127,15 @@ impl PenBehaviour for Shaper {
                     progress: PenProgress::Finished,
                 }
             }
-            (ShaperState::BuildShape { builder }, mut event) => {
+            (
+                ShaperState::BuildShape {
+                    builder,
+                    snap_candidates,
+                },
+                mut event,
+            ) => {
                 // Use Ctrl to temporarily enable/disable constraints when the switch is off/on
-                let mut constraints = engine_view
-                    .config
-                    .pens_config
-                    .shaper_config
-                    .constraints
-                    .clone();
+                let mut constraints = object_snap_constraints(engine_view, snap_candidates.clone());
                 constraints.enabled = match event {
                     PenEvent::Down {
                         ref modifier_keys, ..
@@ -124,14 +148,26 @@ impl PenBehaviour for Shaper {
                     }
                     | PenEvent::KeyPressed {
                         ref modifier_keys, ..
+                    }
+                    | PenEvent::LongPress {
+                        ref modifier_keys, ..
+                    }
+                    | PenEvent::DoubleTap {
+                        ref modifier_keys, ..
                     } => constraints.enabled ^ modifier_keys.contains(&ModifierKey::KeyboardCtrl),
                     PenEvent::Text { .. } | PenEvent::Cancel => false,
                 };
                 match &mut event {
                     PenEvent::Down { element, .. } | PenEvent::Up { element, .. } => {
-                        element.pos = engine_view
-                            .document
-                            .snap_position(element.pos, engine_view.config);
+                        if let Some(snapped) = constraints.snap_to_objects(element.pos) {
+                            // connect exactly to the existing geometry, other constraints would move it away again
+                            element.pos = snapped;
+                            constraints.enabled = false;
+                        } else {
+                            element.pos = engine_view
+                                .document
+                                .snap_position(element.pos, engine_view.config);
+                        }
                     }
                     _ => {}
                 }
@@ -151,7 +187,7 @@ impl PenBehaviour for Shaper {
                         let shapes_emitted = !shapes.is_empty();
 
                         for shape in shapes {
-                            let key = engine_view.store.insert_stroke(
+                            let key = engine_view.store.insert_new_stroke(
                                 Stroke::ShapeStroke(ShapeStroke::new(shape, style.clone())),
                                 None,
                             );
@@ -178,7 +214,7 @@ impl PenBehaviour for Shaper {
 
                         let shapes_emitted = !shapes.is_empty();
                         for shape in shapes {
-                            let key = engine_view.store.insert_stroke(
+                            let key = engine_view.store.insert_new_stroke(
                                 Stroke::ShapeStroke(ShapeStroke::new(shape, style.clone())),
                                 None,
                             );
@@ -237,7 +273,7 @@ impl DrawableOnDoc for Shaper {
 
         match &self.state {
             ShaperState::Idle => None,
-            ShaperState::BuildShape { builder } => {
+            ShaperState::BuildShape { builder, .. } => {
                 builder.bounds(&style, engine_view.camera.total_zoom())
             }
         }
@@ -257,7 +293,7 @@ impl DrawableOnDoc for Shaper {
 
         match &self.state {
             ShaperState::Idle => {}
-            ShaperState::BuildShape { builder } => {
+            ShaperState::BuildShape { builder, .. } => {
                 builder.draw_styled(cx, &style, engine_view.camera.total_zoom())
             }
         }
@@ -267,6 +303,22 @@ impl DrawableOnDoc for Shaper {
     }
 }
 
+/// The shaper constraints, with the candidate points for object snapping.
+fn object_snap_constraints(
+    engine_view: &EngineViewMut,
+    snap_candidates: Vec<na::Vector2<f64>>,
+) -> Constraints {
+    let mut constraints = engine_view
+        .config
+        .pens_config
+        .shaper_config
+        .constraints
+        .clone();
+    constraints.snap_candidates = snap_candidates;
+    constraints.snap_distance = Constraints::OBJECT_SNAP_DISTANCE / engine_view.camera.total_zoom();
+    constraints
+}
+
 fn new_builder(
     builder_type: ShapeBuilderType,
     element: Element,
@@ -288,5 +340,13 @@ fn new_builder(
         ShapeBuilderType::CubBez => Box::new(CubBezBuilder::start(element, now)),
         ShapeBuilderType::Polyline => Box::new(PolylineBuilder::start(element, now)),
         ShapeBuilderType::Polygon => Box::new(PolygonBuilder::start(element, now)),
+        ShapeBuilderType::RoundedRectangle => {
+            Box::new(RoundedRectangleBuilder::start(element, now))
+        }
+        ShapeBuilderType::RegularPolygon => Box::new(RegularPolygonBuilder::start(element, now)),
+        ShapeBuilderType::Star => Box::new(StarBuilder::start(element, now)),
+        ShapeBuilderType::Arc => Box::new(ArcBuilder::start(element, now)),
+        ShapeBuilderType::Spline => Box::new(SplineBuilder::start(element, now)),
+        ShapeBuilderType::Compass => Box::new(CompassBuilder::start(element, now)),
     }
 }
@@ -49,6 +49,9 @@ pub enum ShortcutAction {
         #[serde(rename = "mode")]
         mode: ShortcutMode,
     },
+    /// Open the radial quick menu at the pointer position.
+    #[serde(rename = "open_radial_menu")]
+    OpenRadialMenu,
 }
 
 /// The registered shortcut actions for the given shortcut keys.
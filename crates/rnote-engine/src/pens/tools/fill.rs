@@ -0,0 +1,164 @@
+// Imports
+use super::ToolsState;
+use crate::engine::{EngineView, EngineViewMut};
+use crate::store::chrono_comp::StrokeLayer;
+use crate::strokes::{ShapeStroke, Stroke};
+use crate::{DrawableOnDoc, WidgetFlags};
+use p2d::bounding_volume::Aabb;
+use rnote_compose::eventresult::EventPropagation;
+use rnote_compose::penevent::PenProgress;
+use rnote_compose::regions::{self, Boundary};
+use rnote_compose::shapes::{Shape, Shapeable};
+use rnote_compose::style::smooth::SmoothOptions;
+use rnote_compose::{EventResult, PenEvent, Style};
+use std::time::Instant;
+
+/// Fills the region enclosed by the strokes around the tapped position.
+#[derive(Clone, Debug, Default)]
+pub(super) struct FillTool {
+    state: ToolsState,
+}
+
+impl FillTool {
+    /// The size of the grid cells the regions are detected in, in surface coordinates.
+    const REGION_CELL_SIZE: f64 = 2.0;
+    /// The width of the outline of the fill in surface coordinates, covering the edges of the detected region.
+    const OUTLINE_WIDTH: f64 = 1.0;
+
+    pub(super) fn handle_event(
+        &mut self,
+        event: PenEvent,
+        _now: Instant,
+        engine_view: &mut EngineViewMut,
+    ) -> (EventResult<PenProgress>, WidgetFlags) {
+        let mut widget_flags = WidgetFlags::default();
+
+        let result = match (&mut self.state, event) {
+            (ToolsState::Idle, PenEvent::Down { element, .. }) => {
+                widget_flags |= fill_region(engine_view, element.pos);
+                self.state = ToolsState::Active;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            (ToolsState::Idle, _) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::Idle,
+            },
+            (ToolsState::Active, PenEvent::Down { .. }) => EventResult {
+                handled: true,
+                propagate: EventPropagation::Stop,
+                progress: PenProgress::InProgress,
+            },
+            (ToolsState::Active, PenEvent::Up { .. } | PenEvent::Cancel) => {
+                self.state = ToolsState::Idle;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::Finished,
+                }
+            }
+            (
+                ToolsState::Active,
+                PenEvent::Proximity { .. }
+                | PenEvent::KeyPressed { .. }
+                | PenEvent::Text { .. }
+                | PenEvent::LongPress { .. }
+                | PenEvent::DoubleTap { .. },
+            ) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::InProgress,
+            },
+        };
+
+        (result, widget_flags)
+    }
+}
+
+impl DrawableOnDoc for FillTool {
+    fn bounds_on_doc(&self, _engine_view: &EngineView) -> Option<Aabb> {
+        None
+    }
+
+    fn draw_on_doc(
+        &self,
+        _cx: &mut piet_cairo::CairoRenderContext,
+        _engine_view: &EngineView,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Insert a filled shape for the region enclosed by the strokes around the position.
+///
+/// The region is searched in the viewport. Nothing is inserted when the position isn't enclosed.
+fn fill_region(engine_view: &mut EngineViewMut, pos: na::Vector2<f64>) -> WidgetFlags {
+    let mut widget_flags = WidgetFlags::default();
+    let viewport = engine_view.camera.viewport();
+    let total_zoom = engine_view.camera.total_zoom();
+
+    // The boundaries are narrower than the strokes, so that the fill reaches underneath them
+    let boundaries = engine_view
+        .store
+        .stroke_keys_as_rendered_intersecting_bounds(viewport)
+        .into_iter()
+        .filter_map(|key| match engine_view.store.get_stroke_ref(key)? {
+            Stroke::BrushStroke(brushstroke) => Some(Boundary {
+                path: brushstroke.outline_path(),
+                width: brushstroke.style.stroke_width() * 0.5,
+            }),
+            Stroke::ShapeStroke(shapestroke) => Some(Boundary {
+                path: shapestroke.outline_path(),
+                width: shapestroke.style.stroke_width() * 0.5,
+            }),
+            _ => None,
+        })
+        .collect::<Vec<Boundary>>();
+
+    let Some(polygon) = regions::enclosed_region(
+        &boundaries,
+        pos,
+        viewport,
+        FillTool::REGION_CELL_SIZE / total_zoom,
+    ) else {
+        return widget_flags;
+    };
+
+    let fill_color = engine_view
+        .config
+        .pens_config
+        .tools_config
+        .fill_tool_config
+        .fill_color;
+    let mut options = SmoothOptions::default();
+    options.stroke_width = FillTool::OUTLINE_WIDTH / total_zoom;
+    options.stroke_color = Some(fill_color);
+    options.fill_color = Some(fill_color);
+
+    // Fills are placed underneath the strokes, like the marker
+    let key = engine_view.store.insert_stroke(
+        Stroke::ShapeStroke(ShapeStroke::new(
+            Shape::Polygon(polygon),
+            Style::Smooth(options),
+        )),
+        Some(StrokeLayer::Highlighter),
+    );
+    engine_view.store.regenerate_rendering_for_stroke(
+        key,
+        viewport,
+        engine_view.camera.image_scale(),
+    );
+
+    widget_flags |= engine_view
+        .document
+        .resize_autoexpand(engine_view.store, engine_view.camera);
+    widget_flags |= engine_view.store.record(Instant::now());
+    widget_flags.store_modified = true;
+    widget_flags
+}
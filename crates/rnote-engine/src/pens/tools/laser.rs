@@ -43,8 +43,6 @@ impl Default for LaserTool {
 }
 
 impl LaserTool {
-    const FULL_FADE_DURATION: Duration = Duration::from_secs(1);
-
     const OUTER_STROKE_WIDTH: f64 = 6.0;
     const INNER_STROKE_WIDTH: f64 = 1.0;
 
@@ -81,9 +79,9 @@ impl LaserTool {
     }
 
     /// Returns `Some(bool)` if the fade is in progress, otherwise `None`.
-    pub fn has_fully_faded(&self) -> Option<bool> {
+    pub fn has_fully_faded(&self, fade_duration: Duration) -> Option<bool> {
         self.fade_start_time
-            .map(|time| time.elapsed() >= LaserTool::FULL_FADE_DURATION)
+            .map(|time| time.elapsed() >= fade_duration)
     }
 
     pub fn handle_event(
@@ -162,7 +160,10 @@ impl LaserTool {
                     progress: PenProgress::Finished,
                 }
             }
-            (ToolsState::Active, PenEvent::Text { .. }) => EventResult {
+            (
+                ToolsState::Active,
+                PenEvent::Text { .. } | PenEvent::LongPress { .. } | PenEvent::DoubleTap { .. },
+            ) => EventResult {
                 handled: false,
                 propagate: EventPropagation::Proceed,
                 progress: PenProgress::InProgress,
@@ -173,7 +174,13 @@ impl LaserTool {
     }
 
     pub fn handle_animation_frame(&mut self, engine_view: &mut EngineViewMut) {
-        let Some(faded) = self.has_fully_faded() else {
+        let fade_duration = engine_view
+            .config
+            .pens_config
+            .tools_config
+            .laser_tool_config
+            .fade_duration();
+        let Some(faded) = self.has_fully_faded(fade_duration) else {
             return;
         };
 
@@ -185,7 +192,7 @@ impl LaserTool {
                     .fade_start_time
                     .unwrap() // Never fails because `has_fully_faded` has not returned `None`.
                     .elapsed()
-                    .div_duration_f64(Self::FULL_FADE_DURATION)
+                    .div_duration_f64(fade_duration)
                     .clamp(0.0, 1.0);
 
                 self.opacity = ((1.0 - transparency) * 255.0) as u8;
@@ -208,7 +215,13 @@ impl LaserTool {
 
 impl DrawableOnDoc for LaserTool {
     fn bounds_on_doc(&self, engine_view: &EngineView) -> Option<Aabb> {
-        if self.has_fully_faded() == Some(true) {
+        let fade_duration = engine_view
+            .config
+            .pens_config
+            .tools_config
+            .laser_tool_config
+            .fade_duration();
+        if self.has_fully_faded(fade_duration) == Some(true) {
             return None;
         }
 
@@ -1,12 +1,18 @@
 // Modules
+mod fill;
 mod laser;
 mod offsetcamera;
+mod smudge;
+mod tape;
 mod verticalspace;
 mod zoom;
 
 // Re-Exports
+use fill::FillTool;
 use laser::LaserTool;
 use offsetcamera::OffsetCameraTool;
+use smudge::SmudgeTool;
+use tape::TapeTool;
 use verticalspace::VerticalSpaceTool;
 use zoom::ZoomTool;
 
@@ -40,6 +46,9 @@ pub struct Tools {
     offsetcamera_tool: OffsetCameraTool,
     zoom_tool: ZoomTool,
     laser_tool: LaserTool,
+    fill_tool: FillTool,
+    tape_tool: TapeTool,
+    smudge_tool: SmudgeTool,
 }
 
 impl PenBehaviour for Tools {
@@ -73,6 +82,9 @@ impl PenBehaviour for Tools {
             ToolStyle::OffsetCamera => self.offsetcamera_tool.handle_event(event, now, engine_view),
             ToolStyle::Zoom => self.zoom_tool.handle_event(event, now, engine_view),
             ToolStyle::Laser => self.laser_tool.handle_event(event, now, engine_view),
+            ToolStyle::Fill => self.fill_tool.handle_event(event, now, engine_view),
+            ToolStyle::Tape => self.tape_tool.handle_event(event, now, engine_view),
+            ToolStyle::Smudge => self.smudge_tool.handle_event(event, now, engine_view),
         }
     }
 
@@ -91,6 +103,9 @@ impl DrawableOnDoc for Tools {
             ToolStyle::OffsetCamera => self.offsetcamera_tool.bounds_on_doc(engine_view),
             ToolStyle::Zoom => self.zoom_tool.bounds_on_doc(engine_view),
             ToolStyle::Laser => self.laser_tool.bounds_on_doc(engine_view),
+            ToolStyle::Fill => self.fill_tool.bounds_on_doc(engine_view),
+            ToolStyle::Tape => self.tape_tool.bounds_on_doc(engine_view),
+            ToolStyle::Smudge => self.smudge_tool.bounds_on_doc(engine_view),
         }
     }
 
@@ -114,6 +129,15 @@ impl DrawableOnDoc for Tools {
             ToolStyle::Laser => {
                 self.laser_tool.draw_on_doc(cx, engine_view)?;
             }
+            ToolStyle::Fill => {
+                self.fill_tool.draw_on_doc(cx, engine_view)?;
+            }
+            ToolStyle::Tape => {
+                self.tape_tool.draw_on_doc(cx, engine_view)?;
+            }
+            ToolStyle::Smudge => {
+                self.smudge_tool.draw_on_doc(cx, engine_view)?;
+            }
         }
 
         cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
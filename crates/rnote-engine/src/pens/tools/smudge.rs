@@ -0,0 +1,251 @@
+// Imports
+use crate::engine::rendering::RenderQuality;
+use crate::engine::{EngineView, EngineViewMut};
+use crate::strokes::{SmudgeMode, SmudgeOptions, SmudgeStroke, Stroke};
+use crate::{Drawable, DrawableOnDoc, Image, WidgetFlags};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use rnote_compose::eventresult::EventPropagation;
+use rnote_compose::penevent::PenProgress;
+use rnote_compose::shapes::Shapeable;
+use rnote_compose::{EventResult, PenEvent};
+use std::time::Instant;
+use tracing::error;
+
+#[derive(Clone, Debug, Default)]
+enum SmudgeState {
+    #[default]
+    Idle,
+    Smudging {
+        /// The dabs generated so far, each one drawn over the content and the previous dabs.
+        dabs: Vec<Image>,
+        /// The position of the last dab.
+        last_pos: na::Vector2<f64>,
+    },
+}
+
+/// Blurs or smears the rendered content under the pen.
+///
+/// While dragging, dabs are generated from the content rendered in the tiles. When the pen is lifted,
+/// they are composited into a smudge stroke.
+#[derive(Clone, Debug, Default)]
+pub(super) struct SmudgeTool {
+    state: SmudgeState,
+}
+
+impl SmudgeTool {
+    /// The spacing between the dabs, relative to the smudge radius.
+    const DAB_SPACING_FACTOR: f64 = 0.25;
+
+    pub(super) fn handle_event(
+        &mut self,
+        event: PenEvent,
+        _now: Instant,
+        engine_view: &mut EngineViewMut,
+    ) -> (EventResult<PenProgress>, WidgetFlags) {
+        let mut widget_flags = WidgetFlags::default();
+        let options = engine_view
+            .config
+            .pens_config
+            .tools_config
+            .smudge_tool_config
+            .smudge_options
+            .clone();
+
+        let result = match (&mut self.state, event) {
+            (SmudgeState::Idle, PenEvent::Down { element, .. }) => {
+                let mut dabs = vec![];
+                // Smearing needs movement, blurring already applies at the start
+                if options.mode == SmudgeMode::Blur {
+                    push_dab(
+                        &mut dabs,
+                        element.pos,
+                        na::Vector2::zeros(),
+                        &options,
+                        engine_view,
+                    );
+                }
+                self.state = SmudgeState::Smudging {
+                    dabs,
+                    last_pos: element.pos,
+                };
+                widget_flags.redraw = true;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            (SmudgeState::Idle, _) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::Idle,
+            },
+            (SmudgeState::Smudging { dabs, last_pos }, PenEvent::Down { element, .. }) => {
+                let spacing = options.radius * Self::DAB_SPACING_FACTOR;
+                let offset = element.pos - *last_pos;
+                let distance = offset.magnitude();
+
+                if distance >= spacing {
+                    // Fill in dabs when the pen moved quickly
+                    let n_dabs = (distance / spacing).floor() as usize;
+                    let step = offset / n_dabs as f64;
+                    for i in 1..=n_dabs {
+                        push_dab(
+                            dabs,
+                            *last_pos + step * i as f64,
+                            step,
+                            &options,
+                            engine_view,
+                        );
+                    }
+                    *last_pos += step * n_dabs as f64;
+                    widget_flags.redraw = true;
+                }
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            (SmudgeState::Smudging { dabs, .. }, PenEvent::Up { .. }) => {
+                let dabs = std::mem::take(dabs);
+                widget_flags |= insert_smudge_stroke(engine_view, &dabs, options);
+                self.state = SmudgeState::Idle;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::Finished,
+                }
+            }
+            (SmudgeState::Smudging { .. }, PenEvent::Cancel) => {
+                self.state = SmudgeState::Idle;
+                widget_flags.redraw = true;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::Finished,
+                }
+            }
+            (
+                SmudgeState::Smudging { .. },
+                PenEvent::Proximity { .. }
+                | PenEvent::KeyPressed { .. }
+                | PenEvent::Text { .. }
+                | PenEvent::LongPress { .. }
+                | PenEvent::DoubleTap { .. },
+            ) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::InProgress,
+            },
+        };
+
+        (result, widget_flags)
+    }
+}
+
+impl DrawableOnDoc for SmudgeTool {
+    fn bounds_on_doc(&self, _engine_view: &EngineView) -> Option<Aabb> {
+        match &self.state {
+            SmudgeState::Idle => None,
+            SmudgeState::Smudging { dabs, .. } => dabs
+                .iter()
+                .map(|dab| dab.rect.bounds())
+                .reduce(|acc, bounds| acc.merged(&bounds)),
+        }
+    }
+
+    fn draw_on_doc(
+        &self,
+        cx: &mut piet_cairo::CairoRenderContext,
+        engine_view: &EngineView,
+    ) -> anyhow::Result<()> {
+        if let SmudgeState::Smudging { dabs, .. } = &self.state {
+            for dab in dabs {
+                dab.draw(cx, engine_view.camera.image_scale())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Generate a dab at the position from the rendered content and the previous dabs, and append it.
+fn push_dab(
+    dabs: &mut Vec<Image>,
+    pos: na::Vector2<f64>,
+    offset: na::Vector2<f64>,
+    options: &SmudgeOptions,
+    engine_view: &EngineViewMut,
+) {
+    let image_scale = engine_view.camera.image_scale();
+    let bounds = SmudgeStroke::dab_sample_bounds(pos, offset, options);
+
+    let sample = match Image::gen_with_piet(
+        |piet_cx| {
+            engine_view
+                .store
+                .draw_content_from_tiles(piet_cx, bounds, image_scale)?;
+            for dab in dabs
+                .iter()
+                .filter(|dab| dab.rect.bounds().intersects(&bounds))
+            {
+                dab.draw(piet_cx, image_scale)?;
+            }
+            Ok(())
+        },
+        bounds,
+        image_scale,
+        RenderQuality::FULL,
+    ) {
+        Ok(sample) => sample,
+        Err(e) => {
+            error!("Sampling content for smudge dab failed, Err: {e:?}");
+            return;
+        }
+    };
+
+    match SmudgeStroke::gen_dab(&sample, pos, offset, options) {
+        Ok(dab) => dabs.push(dab),
+        Err(e) => error!("Generating smudge dab failed, Err: {e:?}"),
+    }
+}
+
+/// Composite the dabs into a smudge stroke and insert it.
+fn insert_smudge_stroke(
+    engine_view: &mut EngineViewMut,
+    dabs: &[Image],
+    options: SmudgeOptions,
+) -> WidgetFlags {
+    let mut widget_flags = WidgetFlags::default();
+    widget_flags.redraw = true;
+
+    let smudgestroke =
+        match SmudgeStroke::from_dabs(dabs, options, engine_view.camera.image_scale()) {
+            Ok(Some(smudgestroke)) => smudgestroke,
+            Ok(None) => return widget_flags,
+            Err(e) => {
+                error!("Creating smudge stroke from dabs failed, Err: {e:?}");
+                return widget_flags;
+            }
+        };
+
+    let key = engine_view
+        .store
+        .insert_new_stroke(Stroke::SmudgeStroke(smudgestroke), None);
+    engine_view.store.regenerate_rendering_for_stroke(
+        key,
+        engine_view.camera.viewport(),
+        engine_view.camera.image_scale(),
+    );
+
+    widget_flags |= engine_view
+        .document
+        .resize_autoexpand(engine_view.store, engine_view.camera);
+    widget_flags |= engine_view.store.record(Instant::now());
+    widget_flags.store_modified = true;
+    widget_flags
+}
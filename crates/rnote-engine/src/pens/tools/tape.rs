@@ -0,0 +1,210 @@
+// Imports
+use crate::engine::{EngineView, EngineViewMut};
+use crate::store::chrono_comp::StrokeLayer;
+use crate::strokes::{Stroke, TapeStroke};
+use crate::{Drawable, DrawableOnDoc, WidgetFlags};
+use p2d::bounding_volume::Aabb;
+use rnote_compose::eventresult::EventPropagation;
+use rnote_compose::penevent::PenProgress;
+use rnote_compose::shapes::Shapeable;
+use rnote_compose::{EventResult, PenEvent};
+use std::time::Instant;
+
+#[derive(Clone, Debug, Default)]
+enum TapeState {
+    #[default]
+    Idle,
+    Placing {
+        start: na::Vector2<f64>,
+        current: na::Vector2<f64>,
+    },
+}
+
+/// Places tape by dragging. Tapping an existing tape reveals or covers the content underneath it.
+#[derive(Clone, Debug, Default)]
+pub(super) struct TapeTool {
+    state: TapeState,
+}
+
+impl TapeTool {
+    /// Drags shorter than this distance in surface coordinates are treated as taps.
+    const TAP_DISTANCE_THRESHOLD: f64 = 4.0;
+
+    pub(super) fn handle_event(
+        &mut self,
+        event: PenEvent,
+        _now: Instant,
+        engine_view: &mut EngineViewMut,
+    ) -> (EventResult<PenProgress>, WidgetFlags) {
+        let mut widget_flags = WidgetFlags::default();
+
+        let result = match (&mut self.state, event) {
+            (TapeState::Idle, PenEvent::Down { element, .. }) => {
+                self.state = TapeState::Placing {
+                    start: element.pos,
+                    current: element.pos,
+                };
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            (TapeState::Idle, _) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::Idle,
+            },
+            (TapeState::Placing { current, .. }, PenEvent::Down { element, .. }) => {
+                *current = element.pos;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            (TapeState::Placing { start, .. }, PenEvent::Up { element, .. }) => {
+                let start = *start;
+                let tap_threshold = Self::TAP_DISTANCE_THRESHOLD / engine_view.camera.total_zoom();
+
+                if (element.pos - start).magnitude() < tap_threshold {
+                    widget_flags |= toggle_revealed(engine_view, element.pos);
+                } else {
+                    widget_flags |= place_tape(engine_view, start, element.pos);
+                }
+                self.state = TapeState::Idle;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::Finished,
+                }
+            }
+            (TapeState::Placing { .. }, PenEvent::Cancel) => {
+                self.state = TapeState::Idle;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::Finished,
+                }
+            }
+            (
+                TapeState::Placing { .. },
+                PenEvent::Proximity { .. }
+                | PenEvent::KeyPressed { .. }
+                | PenEvent::Text { .. }
+                | PenEvent::LongPress { .. }
+                | PenEvent::DoubleTap { .. },
+            ) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::InProgress,
+            },
+        };
+
+        (result, widget_flags)
+    }
+
+    /// The tape that is currently being placed.
+    fn preview(&self, engine_view: &EngineView) -> Option<TapeStroke> {
+        match &self.state {
+            TapeState::Idle => None,
+            TapeState::Placing { start, current } => Some(TapeStroke::new(
+                *start,
+                *current,
+                engine_view
+                    .config
+                    .pens_config
+                    .tools_config
+                    .tape_tool_config
+                    .tape_options
+                    .clone(),
+            )),
+        }
+    }
+}
+
+impl DrawableOnDoc for TapeTool {
+    fn bounds_on_doc(&self, engine_view: &EngineView) -> Option<Aabb> {
+        self.preview(engine_view).map(|tape| tape.bounds())
+    }
+
+    fn draw_on_doc(
+        &self,
+        cx: &mut piet_cairo::CairoRenderContext,
+        engine_view: &EngineView,
+    ) -> anyhow::Result<()> {
+        if let Some(tape) = self.preview(engine_view) {
+            tape.draw(cx, engine_view.camera.image_scale())?;
+        }
+        Ok(())
+    }
+}
+
+/// Insert a tape from start to end on the tape layer.
+fn place_tape(
+    engine_view: &mut EngineViewMut,
+    start: na::Vector2<f64>,
+    end: na::Vector2<f64>,
+) -> WidgetFlags {
+    let mut widget_flags = WidgetFlags::default();
+    let options = engine_view
+        .config
+        .pens_config
+        .tools_config
+        .tape_tool_config
+        .tape_options
+        .clone();
+
+    let key = engine_view.store.insert_stroke(
+        Stroke::TapeStroke(TapeStroke::new(start, end, options)),
+        Some(StrokeLayer::Tape),
+    );
+    engine_view.store.regenerate_rendering_for_stroke(
+        key,
+        engine_view.camera.viewport(),
+        engine_view.camera.image_scale(),
+    );
+
+    widget_flags |= engine_view
+        .document
+        .resize_autoexpand(engine_view.store, engine_view.camera);
+    widget_flags |= engine_view.store.record(Instant::now());
+    widget_flags.store_modified = true;
+    widget_flags
+}
+
+/// Reveal or cover the content underneath the topmost unlocked tape at the position.
+fn toggle_revealed(engine_view: &mut EngineViewMut, pos: na::Vector2<f64>) -> WidgetFlags {
+    let mut widget_flags = WidgetFlags::default();
+    let keys = engine_view.store.filter_unlocked(
+        engine_view
+            .store
+            .stroke_keys_as_rendered_intersecting_bounds(Aabb::new(pos.into(), pos.into())),
+    );
+    let Some(key) = keys.into_iter().rev().find(|key| {
+        matches!(
+            engine_view.store.get_stroke_ref(*key),
+            Some(Stroke::TapeStroke(tape)) if tape.contains_pos(pos)
+        )
+    }) else {
+        return widget_flags;
+    };
+
+    if let Some(Stroke::TapeStroke(tape)) = engine_view.store.get_stroke_mut(key) {
+        tape.revealed = !tape.revealed;
+    }
+    engine_view.store.regenerate_rendering_for_stroke(
+        key,
+        engine_view.camera.viewport(),
+        engine_view.camera.image_scale(),
+    );
+
+    widget_flags |= engine_view.store.record(Instant::now());
+    widget_flags.redraw = true;
+    widget_flags.store_modified = true;
+    widget_flags
+}
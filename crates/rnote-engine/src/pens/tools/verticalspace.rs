@@ -186,7 +186,10 @@ impl VerticalSpaceTool {
                 propagate: EventPropagation::Proceed,
                 progress: PenProgress::InProgress,
             },
-            (ToolsState::Active, PenEvent::Text { .. }) => EventResult {
+            (
+                ToolsState::Active,
+                PenEvent::Text { .. } | PenEvent::LongPress { .. } | PenEvent::DoubleTap { .. },
+            ) => EventResult {
                 handled: false,
                 propagate: EventPropagation::Proceed,
                 progress: PenProgress::InProgress,
@@ -140,7 +140,10 @@ impl ZoomTool {
                 propagate: EventPropagation::Proceed,
                 progress: PenProgress::InProgress,
             },
-            (ToolsState::Active, PenEvent::Text { .. }) => EventResult {
+            (
+                ToolsState::Active,
+                PenEvent::Text { .. } | PenEvent::LongPress { .. } | PenEvent::DoubleTap { .. },
+            ) => EventResult {
                 handled: false,
                 propagate: EventPropagation::Proceed,
                 progress: PenProgress::InProgress,
@@ -6,6 +6,7 @@ use super::PenBehaviour;
 use super::PenStyle;
 use super::pensconfig::TypewriterConfig;
 use crate::engine::{EngineTask, EngineView, EngineViewMut};
+use crate::fileformats::htmlformat::HtmlRichText;
 use crate::store::StrokeKey;
 use crate::strokes::textstroke::{RangedTextAttribute, TextAttribute, TextStyle};
 use crate::strokes::{Stroke, TextStroke};
@@ -420,6 +421,9 @@ impl PenBehaviour for Typewriter {
                 modifier_keys,
             } => self.handle_pen_event_keypressed(keyboard_key, modifier_keys, now, engine_view),
             PenEvent::Text { text } => self.handle_pen_event_text(text, now, engine_view),
+            PenEvent::LongPress { .. } | PenEvent::DoubleTap { .. } => {
+                self.handle_pen_event_gesture(now, engine_view)
+            }
             PenEvent::Cancel => self.handle_pen_event_cancel(now, engine_view),
         };
 
@@ -701,7 +705,7 @@ impl Typewriter {
 
                 let stroke_key = engine_view
                     .store
-                    .insert_stroke(Stroke::TextStroke(textstroke), None);
+                    .insert_new_stroke(Stroke::TextStroke(textstroke), None);
                 engine_view.store.regenerate_rendering_for_stroke(
                     stroke_key,
                     engine_view.camera.viewport(),
@@ -728,7 +732,7 @@ impl Typewriter {
 
                 let stroke_key = engine_view
                     .store
-                    .insert_stroke(Stroke::TextStroke(textstroke), None);
+                    .insert_new_stroke(Stroke::TextStroke(textstroke), None);
                 engine_view.store.regenerate_rendering_for_stroke(
                     stroke_key,
                     engine_view.camera.viewport(),
@@ -812,6 +816,59 @@ impl Typewriter {
         widget_flags
     }
 
+    /// Insert formatted text, the same way as [Self::insert_text], and apply its text attributes.
+    pub(crate) fn insert_rich_text(
+        &mut self,
+        rich_text: HtmlRichText,
+        preferred_pos: Option<na::Vector2<f64>>,
+        engine_view: &mut EngineViewMut,
+    ) -> WidgetFlags {
+        // the position where the text will be inserted
+        let offset = match &self.state {
+            TypewriterState::Modifying {
+                modify_state:
+                    ModifyState::Selecting {
+                        selection_cursor, ..
+                    },
+                cursor,
+                ..
+            } => cursor.cur_cursor().min(selection_cursor.cur_cursor()),
+            TypewriterState::Modifying { cursor, .. } => cursor.cur_cursor(),
+            TypewriterState::Idle | TypewriterState::Start(_) => 0,
+        };
+        let mut widget_flags = self.insert_text(rich_text.text, preferred_pos, engine_view);
+
+        if let TypewriterState::Modifying { stroke_key, .. } = &self.state
+            && let Some(Stroke::TextStroke(textstroke)) =
+                engine_view.store.get_stroke_mut(*stroke_key)
+        {
+            textstroke.text_style.ranged_text_attributes.extend(
+                rich_text
+                    .ranged_text_attributes
+                    .into_iter()
+                    .map(|mut attr| {
+                        attr.range = attr.range.start + offset..attr.range.end + offset;
+                        attr
+                    }),
+            );
+            engine_view.store.update_geometry_for_stroke(*stroke_key);
+            engine_view.store.regenerate_rendering_for_stroke(
+                *stroke_key,
+                engine_view.camera.viewport(),
+                engine_view.camera.image_scale(),
+            );
+            widget_flags |= engine_view
+                .document
+                .resize_autoexpand(engine_view.store, engine_view.camera);
+            // the insertion is a single step in the history
+            widget_flags |= engine_view
+                .store
+                .update_latest_history_entry(Instant::now());
+        }
+
+        widget_flags
+    }
+
     // Change the text style of the text stroke that is currently being modified.
     pub(crate) fn change_text_style_in_modifying_stroke<F>(
         &mut self,
@@ -38,10 +38,14 @@ impl Typewriter {
                         .snap_position(element.pos, engine_view.config),
                 );
 
-                if let Some(&stroke_key) = engine_view
-                    .store
-                    .stroke_hitboxes_contain_coord(engine_view.camera.viewport(), element.pos)
-                    .last()
+                if let Some(&stroke_key) =
+                    engine_view
+                        .store
+                        .filter_unlocked(engine_view.store.stroke_hitboxes_contain_coord(
+                            engine_view.camera.viewport(),
+                            element.pos,
+                        ))
+                        .last()
                 {
                     // When clicked on a textstroke, we start modifying it
                     if let Some(Stroke::TextStroke(textstroke)) =
@@ -553,7 +557,7 @@ impl Typewriter {
                         textstroke.move_cursor_forward(&mut cursor);
                         let stroke_key = engine_view
                             .store
-                            .insert_stroke(Stroke::TextStroke(textstroke), None);
+                            .insert_new_stroke(Stroke::TextStroke(textstroke), None);
                         widget_flags |= engine_view
                             .document
                             .resize_autoexpand(engine_view.store, engine_view.camera);
@@ -1141,7 +1145,7 @@ impl Typewriter {
 
                 let stroke_key = engine_view
                     .store
-                    .insert_stroke(Stroke::TextStroke(textstroke), None);
+                    .insert_new_stroke(Stroke::TextStroke(textstroke), None);
                 engine_view.store.regenerate_rendering_for_stroke(
                     stroke_key,
                     engine_view.camera.viewport(),
@@ -1267,6 +1271,35 @@ impl Typewriter {
         (event_result, widget_flags)
     }
 
+    /// Long-press and double-tap events are not used yet.
+    pub(super) fn handle_pen_event_gesture(
+        &mut self,
+        _now: Instant,
+        _engine_view: &mut EngineViewMut,
+    ) -> (EventResult<PenProgress>, WidgetFlags) {
+        let widget_flags = WidgetFlags::default();
+
+        let event_result = match &self.state {
+            TypewriterState::Idle => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::Idle,
+            },
+            TypewriterState::Start(_) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::InProgress,
+            },
+            TypewriterState::Modifying { .. } => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::InProgress,
+            },
+        };
+
+        (event_result, widget_flags)
+    }
+
     pub(super) fn handle_pen_event_cancel(
         &mut self,
         _now: Instant,
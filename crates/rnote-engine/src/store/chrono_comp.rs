@@ -1,9 +1,11 @@
 // Imports
 use super::{StrokeKey, StrokeStore};
-use p2d::bounding_volume::Aabb;
+use crate::strokes::Stroke;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rayon::slice::ParallelSliceMut;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq)]
@@ -17,6 +19,9 @@ pub enum StrokeLayer {
     Image,
     #[serde(rename = "document", alias = "Document")]
     Document,
+    /// Tapes cover all other layers.
+    #[serde(rename = "tape", alias = "Tape")]
+    Tape,
 }
 
 impl Default for StrokeLayer {
@@ -43,6 +48,9 @@ impl PartialOrd for StrokeLayer {
 impl Ord for StrokeLayer {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         match (self, other) {
+            (StrokeLayer::Tape, StrokeLayer::Tape) => Ordering::Equal,
+            (StrokeLayer::Tape, _) => Ordering::Greater,
+            (_, StrokeLayer::Tape) => Ordering::Less,
             (StrokeLayer::UserLayer(this_ul), StrokeLayer::UserLayer(other_ul)) => {
                 this_ul.cmp(other_ul)
             }
@@ -68,6 +76,9 @@ pub struct ChronoComponent {
     t: u32,
     #[serde(rename = "layer")]
     pub layer: StrokeLayer,
+    /// Locked strokes can't be selected, erased or edited with the pens.
+    #[serde(rename = "locked")]
+    pub locked: bool,
 }
 
 impl Default for ChronoComponent {
@@ -75,13 +86,23 @@ impl Default for ChronoComponent {
         Self {
             t: 0,
             layer: StrokeLayer::default(),
+            locked: false,
         }
     }
 }
 
 impl ChronoComponent {
     pub(crate) fn new(t: u32, layer: StrokeLayer) -> Self {
-        Self { t, layer }
+        Self {
+            t,
+            layer,
+            locked: false,
+        }
+    }
+
+    /// The chronological position.
+    pub(crate) fn t(&self) -> u32 {
+        self.t
     }
 }
 
@@ -91,9 +112,33 @@ impl StrokeStore {
         if let Some(chrono_comp) = Arc::make_mut(&mut self.chrono_components).get_mut(key) {
             self.chrono_counter += 1;
             Arc::make_mut(chrono_comp).t = self.chrono_counter;
+            self.mark_dirty_for_stroke(key);
+        }
+    }
+
+    /// The layer of the stroke for the given key.
+    pub(crate) fn stroke_layer(&self, key: StrokeKey) -> Option<StrokeLayer> {
+        self.chrono_components.get(key).map(|c| c.layer)
+    }
+
+    /// Set the layer of the stroke for the given key.
+    pub(crate) fn set_stroke_layer(&mut self, key: StrokeKey, layer: StrokeLayer) {
+        if let Some(chrono_comp) = Arc::make_mut(&mut self.chrono_components)
+            .get_mut(key)
+            .map(Arc::make_mut)
+        {
+            chrono_comp.layer = layer;
+            self.mark_dirty_for_stroke(key);
         }
     }
 
+    /// The chronological time of the stroke for the given key.
+    ///
+    /// It increases with every stroke that is inserted or updated to be the most recent one.
+    pub(crate) fn chrono_t(&self, key: StrokeKey) -> Option<u32> {
+        self.chrono_components.get(key).map(|c| c.t)
+    }
+
     /// Returns the keys in chronological order, as in first: gets drawn first, last: gets drawn last.
     pub(crate) fn keys_sorted_chrono(&self) -> Vec<StrokeKey> {
         let chrono_components = &self.chrono_components;
@@ -167,3 +212,200 @@ impl StrokeStore {
         keys
     }
 }
+
+/// A change of the stacking order of strokes on their layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Restack {
+    /// Move the strokes above the next stroke that overlaps them. Does nothing when no stroke above overlaps them.
+    Raise,
+    /// Move the strokes below the next stroke that overlaps them. Does nothing when no stroke below overlaps them.
+    Lower,
+    /// Move the strokes above all other strokes.
+    Front,
+    /// Move the strokes below all other strokes.
+    Back,
+}
+
+/// Systems that are related to the stacking order of strokes on their layer.
+impl StrokeStore {
+    /// Change the stacking order of the strokes for the given keys.
+    ///
+    /// The strokes only change their order in relation to the other strokes on the same layer. On every layer the
+    /// moved strokes keep their order among each other. Returns whether the order has changed.
+    pub(crate) fn restack_keys(&mut self, keys: &[StrokeKey], restack: Restack) -> bool {
+        let moved_keys = keys.iter().copied().collect::<HashSet<StrokeKey>>();
+        let mut layers = Vec::<StrokeLayer>::new();
+        for &key in keys {
+            if let Some(layer) = self.stroke_layer(key)
+                && !layers.contains(&layer)
+            {
+                layers.push(layer);
+            }
+        }
+        let mut changed = false;
+
+        for layer in layers {
+            let mut stack = self
+                .chrono_components
+                .iter()
+                .filter(|&(key, chrono_comp)| {
+                    chrono_comp.layer == layer && !self.trashed(key).unwrap_or(true)
+                })
+                .map(|(key, chrono_comp)| (key, chrono_comp.t))
+                .collect::<Vec<(StrokeKey, u32)>>();
+            stack.sort_unstable_by_key(|&(_, t)| t);
+            let (stack, ts): (Vec<StrokeKey>, Vec<u32>) = stack.into_iter().unzip();
+            let (moved, others): (Vec<StrokeKey>, Vec<StrokeKey>) =
+                stack.iter().partition(|key| moved_keys.contains(key));
+            let Some(moved_bounds) = self.bounds_for_strokes(&moved) else {
+                continue;
+            };
+            let overlaps_moved = |key: StrokeKey| {
+                self.bounds_for_strokes(&[key])
+                    .is_some_and(|bounds| bounds.intersects(&moved_bounds))
+            };
+
+            // The index in the other strokes where the moved strokes get inserted
+            let Some(insert_index) = (match restack {
+                Restack::Raise => {
+                    let above = stack
+                        .iter()
+                        .rposition(|key| moved_keys.contains(key))
+                        .map_or(0, |top| {
+                            stack[..top]
+                                .iter()
+                                .filter(|key| !moved_keys.contains(key))
+                                .count()
+                        });
+                    (above..others.len())
+                        .find(|&i| overlaps_moved(others[i]))
+                        .map(|i| i + 1)
+                }
+                Restack::Lower => {
+                    let below = stack
+                        .iter()
+                        .position(|key| moved_keys.contains(key))
+                        .map_or(0, |bottom| {
+                            stack[..bottom]
+                                .iter()
+                                .filter(|key| !moved_keys.contains(key))
+                                .count()
+                        });
+                    (0..below).rfind(|&i| overlaps_moved(others[i]))
+                }
+                Restack::Front => Some(others.len()),
+                Restack::Back => Some(0),
+            }) else {
+                // No overlapping stroke to raise or lower the strokes past
+                continue;
+            };
+
+            let restacked = others[..insert_index]
+                .iter()
+                .chain(moved.iter())
+                .chain(others[insert_index..].iter())
+                .copied()
+                .collect::<Vec<StrokeKey>>();
+            for (key, t) in restacked.into_iter().zip(ts) {
+                if self.chrono_t(key) == Some(t) {
+                    continue;
+                }
+                if let Some(chrono_comp) = Arc::make_mut(&mut self.chrono_components)
+                    .get_mut(key)
+                    .map(Arc::make_mut)
+                {
+                    chrono_comp.t = t;
+                    changed = true;
+                    self.mark_dirty_for_stroke(key);
+                }
+            }
+        }
+
+        changed
+    }
+}
+
+/// Systems that are related to the layers and the locking of strokes.
+impl StrokeStore {
+    /// Whether the stroke for the given key is locked, either by itself or through its layer.
+    ///
+    /// Opaque strokes can't be transformed and are always locked.
+    pub(crate) fn locked(&self, key: StrokeKey) -> Option<bool> {
+        self.chrono_components.get(key).map(|c| {
+            c.locked
+                || self.layer_locked(c.layer)
+                || matches!(
+                    self.stroke_components
+                        .get(key)
+                        .map(|stroke| stroke.as_ref()),
+                    Some(Stroke::Opaque(_))
+                )
+        })
+    }
+
+    /// The keys without the locked strokes.
+    pub(crate) fn filter_unlocked(&self, keys: Vec<StrokeKey>) -> Vec<StrokeKey> {
+        keys.into_iter()
+            .filter(|&key| !self.locked(key).unwrap_or(false))
+            .collect()
+    }
+
+    pub(crate) fn set_locked_keys(&mut self, keys: &[StrokeKey], locked: bool) {
+        for &key in keys {
+            if let Some(chrono_comp) = Arc::make_mut(&mut self.chrono_components)
+                .get_mut(key)
+                .map(Arc::make_mut)
+            {
+                chrono_comp.locked = locked;
+            }
+        }
+    }
+
+    /// Move the strokes that are on user layers to the layer returned by `new_layer` for their current layer.
+    ///
+    /// Strokes on the other layers stay where they are, because their layer is determined by their type.
+    /// The new layers are clamped to the existing layers.
+    pub(crate) fn move_user_layers(&mut self, keys: &[StrokeKey], new_layer: impl Fn(u32) -> u32) {
+        let top_layer = self.layers().len().saturating_sub(1) as u32;
+        for &key in keys {
+            if let Some(StrokeLayer::UserLayer(layer)) = self.stroke_layer(key) {
+                self.set_stroke_layer(key, StrokeLayer::UserLayer(new_layer(layer).min(top_layer)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strokes::BrushStroke;
+    use rnote_compose::Style;
+    use rnote_compose::penpath::Element;
+
+    fn brushstroke(pos: na::Vector2<f64>) -> Stroke {
+        Stroke::BrushStroke(BrushStroke::new(Element::new(pos, 0.5), Style::default()))
+    }
+
+    #[test]
+    fn restack_overlapping() {
+        let mut store = StrokeStore::default();
+        let layer = Some(StrokeLayer::UserLayer(0));
+        let first = store.insert_stroke(brushstroke(na::vector![10.0, 10.0]), layer);
+        // does not overlap the other strokes
+        let apart = store.insert_stroke(brushstroke(na::vector![100.0, 100.0]), layer);
+        let last = store.insert_stroke(brushstroke(na::vector![10.0, 10.0]), layer);
+
+        assert!(store.restack_keys(&[first], Restack::Raise));
+        assert_eq!(store.keys_sorted_chrono(), vec![apart, last, first]);
+        assert!(store.restack_keys(&[first], Restack::Lower));
+        assert_eq!(store.keys_sorted_chrono(), vec![apart, first, last]);
+
+        // without an overlapping stroke above or below, the order stays the same
+        assert!(!store.restack_keys(&[apart], Restack::Raise));
+        assert!(!store.restack_keys(&[apart], Restack::Lower));
+        assert_eq!(store.keys_sorted_chrono(), vec![apart, first, last]);
+
+        assert!(store.restack_keys(&[apart], Restack::Front));
+        assert_eq!(store.keys_sorted_chrono(), vec![first, last, apart]);
+    }
+}
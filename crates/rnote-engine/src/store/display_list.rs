@@ -0,0 +1,62 @@
+// Imports
+use crate::strokes::Stroke;
+use rnote_compose::style::Style;
+use rnote_compose::style::smooth::LineStyle;
+
+/// A retained vector display list of a stroke.
+///
+/// Holds the already composed paths of the stroke, so that it can be rasterized again at another image scale
+/// without re-walking the stroke data structures.
+#[derive(Debug, Clone, Default)]
+pub struct DisplayList {
+    /// The paths that are filled with their color, in drawing order.
+    fills: Vec<(kurbo::BezPath, piet::Color)>,
+}
+
+impl DisplayList {
+    /// Record the display list for the stroke.
+    ///
+    /// Returns None for strokes that don't support it, which then need to be drawn directly.
+    pub(crate) fn record(stroke: &Stroke) -> Option<Self> {
+        match stroke {
+            Stroke::BrushStroke(brushstroke) => match &brushstroke.style {
+                // Dashed and dotted paths are stroked, not filled
+                Style::Smooth(options) if options.line_style != LineStyle::Solid => None,
+                Style::Smooth(options) => {
+                    let fills = match options.stroke_color {
+                        Some(color) => {
+                            let colors = brushstroke
+                                .path
+                                .composed_outline_colors(color, options.stroke_gradient.as_ref());
+                            brushstroke
+                                .smooth_composed_outlines(options)
+                                .iter()
+                                .zip(colors)
+                                .map(|(bez_path, color)| (bez_path.clone(), color.into()))
+                                .collect()
+                        }
+                        None => vec![],
+                    };
+                    Some(Self { fills })
+                }
+                Style::Rough(_) | Style::Textured(_) => None,
+            },
+            Stroke::ShapeStroke(_)
+            | Stroke::TextStroke(_)
+            | Stroke::VectorImage(_)
+            | Stroke::BitmapImage(_)
+            | Stroke::SprayStroke(_)
+            | Stroke::StampStroke(_)
+            | Stroke::TapeStroke(_)
+            | Stroke::SmudgeStroke(_)
+            | Stroke::Opaque(_) => None,
+        }
+    }
+
+    /// Draw the display list.
+    pub(crate) fn draw(&self, cx: &mut impl piet::RenderContext) {
+        for (bez_path, color) in self.fills.iter() {
+            cx.fill(bez_path, color);
+        }
+    }
+}
@@ -0,0 +1,184 @@
+// Imports
+use super::{StrokeKey, StrokeStore};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, rename = "group_component")]
+pub struct GroupComponent {
+    /// The ids of the groups the stroke is a member of, from the outermost to the innermost group.
+    #[serde(rename = "groups")]
+    pub groups: Vec<u32>,
+}
+
+/// Systems that are related to grouping strokes.
+impl StrokeStore {
+    /// Rebuild the slotmap with the group components of the stored strokes, adding empty components for strokes
+    /// that have none and dropping the ones of strokes that don't exist.
+    pub(crate) fn rebuild_group_components_slotmap(&mut self) {
+        let mut group_components = slotmap::SecondaryMap::new();
+        for key in self.stroke_components.keys() {
+            group_components.insert(
+                key,
+                self.group_components.get(key).cloned().unwrap_or_default(),
+            );
+        }
+        self.group_components = Arc::new(group_components);
+    }
+
+    /// The outermost group of the stroke for the given key, if it is grouped.
+    pub(crate) fn outermost_group(&self, key: StrokeKey) -> Option<u32> {
+        self.group_components
+            .get(key)
+            .and_then(|group_comp| group_comp.groups.first().copied())
+    }
+
+    /// The ids of the groups the stroke for the given key is a member of, from the outermost to the innermost group.
+    pub(crate) fn stroke_groups(&self, key: StrokeKey) -> Vec<u32> {
+        self.group_components
+            .get(key)
+            .map(|group_comp| group_comp.groups.clone())
+            .unwrap_or_default()
+    }
+
+    /// Whether the stroke for the given key is a member of a group.
+    pub(crate) fn grouped(&self, key: StrokeKey) -> bool {
+        self.outermost_group(key).is_some()
+    }
+
+    /// An id that is not used by any existing group.
+    fn unused_group_id(&self) -> u32 {
+        self.group_components
+            .values()
+            .flat_map(|group_comp| group_comp.groups.iter().copied())
+            .max()
+            .map_or(0, |id| id + 1)
+    }
+
+    /// Put the strokes for the given keys into a new group, which encloses the groups they are already members of.
+    pub(crate) fn group_keys(&mut self, keys: &[StrokeKey]) {
+        let id = self.unused_group_id();
+        for &key in keys {
+            if let Some(group_comp) = Arc::make_mut(&mut self.group_components)
+                .get_mut(key)
+                .map(Arc::make_mut)
+            {
+                group_comp.groups.insert(0, id);
+            }
+        }
+    }
+
+    /// Remove the strokes for the given keys from their outermost group, keeping the nested groups.
+    pub(crate) fn ungroup_keys(&mut self, keys: &[StrokeKey]) {
+        for &key in keys {
+            if let Some(group_comp) = Arc::make_mut(&mut self.group_components)
+                .get_mut(key)
+                .map(Arc::make_mut)
+                && !group_comp.groups.is_empty()
+            {
+                group_comp.groups.remove(0);
+            }
+        }
+    }
+
+    /// Copy the group membership of a stroke to another, so that both are members of the same groups.
+    pub(crate) fn copy_groups(&mut self, from: StrokeKey, to: StrokeKey) {
+        let Some(groups) = self
+            .group_components
+            .get(from)
+            .map(|group_comp| group_comp.groups.clone())
+        else {
+            return;
+        };
+        if let Some(group_comp) = Arc::make_mut(&mut self.group_components)
+            .get_mut(to)
+            .map(Arc::make_mut)
+        {
+            group_comp.groups = groups;
+        }
+    }
+
+    /// Recreate the groups of the original strokes for their copies, with new group ids.
+    ///
+    /// Expects pairs of the original key and the key of the copy.
+    pub(crate) fn duplicate_groups(&mut self, keys: &[(StrokeKey, StrokeKey)]) {
+        let groups = keys
+            .iter()
+            .map(|&(original, copy)| (copy, self.stroke_groups(original)))
+            .collect::<Vec<(StrokeKey, Vec<u32>)>>();
+        self.insert_groups(groups);
+    }
+
+    /// Put the strokes into the given groups under new group ids, so that they don't join any existing group.
+    ///
+    /// Strokes that share a given id end up in the same new group. Used for strokes that come from the clipboard or
+    /// another document, where the ids of their groups have a different meaning.
+    pub(crate) fn insert_groups(
+        &mut self,
+        groups: impl IntoIterator<Item = (StrokeKey, Vec<u32>)>,
+    ) {
+        let mut next_id = self.unused_group_id();
+        let mut new_ids = HashMap::<u32, u32>::new();
+        for (key, groups) in groups {
+            if groups.is_empty() {
+                continue;
+            }
+            let groups = groups
+                .into_iter()
+                .map(|id| {
+                    *new_ids.entry(id).or_insert_with(|| {
+                        next_id += 1;
+                        next_id - 1
+                    })
+                })
+                .collect();
+            if let Some(group_comp) = Arc::make_mut(&mut self.group_components)
+                .get_mut(key)
+                .map(Arc::make_mut)
+            {
+                group_comp.groups = groups;
+            }
+        }
+    }
+
+    /// Extend the keys with the other members of their outermost groups, so that the groups are handled as a unit.
+    ///
+    /// A group with a member that is locked or on a hidden layer can't be handled as a whole, so its members are
+    /// removed from the keys instead. Trashed members are ignored.
+    pub(crate) fn expand_to_groups(&self, keys: Vec<StrokeKey>) -> Vec<StrokeKey> {
+        let groups = keys
+            .iter()
+            .filter_map(|&key| self.outermost_group(key))
+            .collect::<HashSet<u32>>();
+        if groups.is_empty() {
+            return keys;
+        }
+        let given = keys.iter().copied().collect::<HashSet<StrokeKey>>();
+        let mut excluded_groups = HashSet::<u32>::new();
+        let mut members = vec![];
+        for key in self.stroke_components.keys() {
+            let Some(group) = self
+                .outermost_group(key)
+                .filter(|group| groups.contains(group))
+            else {
+                continue;
+            };
+            if self.trashed(key).unwrap_or(true) {
+                continue;
+            }
+            if self.locked(key).unwrap_or(false) || !self.on_visible_layer(key) {
+                excluded_groups.insert(group);
+            } else if !given.contains(&key) {
+                members.push(key);
+            }
+        }
+        keys.into_iter()
+            .chain(members)
+            .filter(|&key| {
+                self.outermost_group(key)
+                    .is_none_or(|group| !excluded_groups.contains(&group))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,65 @@
+// Imports
+#[cfg(feature = "ui")]
+use crate::Image;
+#[cfg(feature = "ui")]
+use gtk4::{gdk, prelude::*};
+#[cfg(feature = "ui")]
+use std::collections::HashMap;
+#[cfg(feature = "ui")]
+use std::hash::{Hash, Hasher};
+
+/// Shares the textures of bitmap images between the strokes that display the same image.
+///
+/// The strokes are rendered with the shared texture and their own transform, instead of generating
+/// separate images for every stroke.
+#[derive(Debug, Default)]
+pub(crate) struct InstanceCache {
+    /// The textures keyed by the hash of the image data, together with the data they were created from.
+    #[cfg(feature = "ui")]
+    textures: HashMap<u64, Vec<(glib::Bytes, gdk::Texture)>>,
+}
+
+impl InstanceCache {
+    #[cfg(feature = "ui")]
+    fn image_hash(image: &Image) -> u64 {
+        let mut hasher = std::hash::DefaultHasher::new();
+        image.pixel_width.hash(&mut hasher);
+        image.pixel_height.hash(&mut hasher);
+        image.data.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// The shared texture of the image, created when no stroke with the same image is rendered yet.
+    #[cfg(feature = "ui")]
+    pub(crate) fn texture(&mut self, image: &Image) -> anyhow::Result<gdk::Texture> {
+        let entries = self.textures.entry(Self::image_hash(image)).or_default();
+        if let Some((_, texture)) = entries.iter().find(|(data, _)| *data == image.data) {
+            return Ok(texture.clone());
+        }
+        let texture = image.to_memtexture()?.upcast::<gdk::Texture>();
+        entries.push((image.data.clone(), texture.clone()));
+        Ok(texture)
+    }
+
+    /// Drop the textures that are not used by any rendering anymore.
+    pub(crate) fn remove_unused(&mut self) {
+        #[cfg(feature = "ui")]
+        self.textures.retain(|_, entries| {
+            // textures that are only referenced by the cache are not used by any rendering
+            entries.retain(|(_, texture)| texture.ref_count() > 1);
+            !entries.is_empty()
+        });
+    }
+
+    /// The amount of shared textures.
+    pub(crate) fn len(&self) -> usize {
+        #[cfg(feature = "ui")]
+        {
+            self.textures.values().map(|entries| entries.len()).sum()
+        }
+        #[cfg(not(feature = "ui"))]
+        {
+            0
+        }
+    }
+}
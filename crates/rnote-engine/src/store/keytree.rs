@@ -1,28 +1,83 @@
 // Imports
 use super::StrokeKey;
-use p2d::bounding_volume::Aabb;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use rstar::Envelope;
+use rstar::RTreeObject;
 use rstar::primitives::GeomWithData;
 
 /// The rtree object that holds the bounds and [StrokeKey].
 type KeyTreeObject = GeomWithData<rstar::primitives::Rectangle<[f64; 2]>, StrokeKey>;
 
+/// Regions of the document where strokes have changed.
+#[derive(Debug, Clone)]
+pub(crate) enum DirtyRegions {
+    /// Everything is considered changed.
+    All,
+    /// The bounds of the changed regions.
+    Regions(Vec<Aabb>),
+}
+
+impl Default for DirtyRegions {
+    fn default() -> Self {
+        Self::All
+    }
+}
+
+impl DirtyRegions {
+    /// The amount of regions above which everything is considered changed, to bound the memory when they don't get
+    /// taken.
+    const MAX_REGIONS: usize = 1024;
+
+    fn push(&mut self, bounds: Aabb) {
+        if let Self::Regions(regions) = self {
+            if regions.len() >= Self::MAX_REGIONS {
+                *self = Self::All;
+            } else {
+                regions.push(bounds);
+            }
+        }
+    }
+
+    /// Whether the given bounds intersect with a changed region.
+    pub(crate) fn intersects(&self, bounds: &Aabb) -> bool {
+        match self {
+            Self::All => true,
+            Self::Regions(regions) => regions.iter().any(|region| region.intersects(bounds)),
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 /// A Rtree with [StrokeKey]'s as associated data.
 ///
-/// Used for faster spatial queries.
-pub(super) struct KeyTree(rstar::RTree<KeyTreeObject, rstar::DefaultParams>);
+/// Used for faster spatial queries. Also tracks the regions where strokes have changed,
+/// so that only renderings intersecting them need to be regenerated.
+pub(super) struct KeyTree {
+    tree: rstar::RTree<KeyTreeObject, rstar::DefaultParams>,
+    dirty_regions: DirtyRegions,
+}
 
 impl KeyTree {
     /// Insert a new tree object with the given [StrokeKey] and bounds.
     pub(crate) fn insert_with_key(&mut self, key: StrokeKey, bounds: Aabb) {
-        self.0.insert(new_keytree_object(key, bounds));
+        self.dirty_regions.push(bounds);
+        self.tree.insert(new_keytree_object(key, bounds));
     }
 
     /// Removes the [KeyTreeObject] for the given key.
     pub(crate) fn remove_with_key(&mut self, key: StrokeKey) -> Option<KeyTreeObject> {
-        let object_to_remove = self.0.iter().find(|&object| object.data == key)?.to_owned();
+        let object_to_remove = self
+            .tree
+            .iter()
+            .find(|&object| object.data == key)?
+            .to_owned();
+        let envelope = object_to_remove.geom().envelope();
+        self.dirty_regions.push(Aabb::new(
+            na::Point2::from(envelope.lower()),
+            na::Point2::from(envelope.upper()),
+        ));
 
-        self.0.remove(&object_to_remove)
+        self.tree.remove(&object_to_remove)
     }
 
     /// Update the Tree with new bounds for the given key.
@@ -33,9 +88,19 @@ impl KeyTree {
         self.insert_with_key(key, new_bounds);
     }
 
+    /// Mark the region as changed, for changes that don't modify the geometry of strokes.
+    pub(crate) fn mark_dirty(&mut self, bounds: Aabb) {
+        self.dirty_regions.push(bounds);
+    }
+
+    /// Take the regions that have changed since they were last taken.
+    pub(crate) fn take_dirty_regions(&mut self) -> DirtyRegions {
+        std::mem::replace(&mut self.dirty_regions, DirtyRegions::Regions(vec![]))
+    }
+
     /// Return the keys that intersect with the given bounds.
     pub(crate) fn keys_intersecting_bounds(&self, bounds: Aabb) -> Vec<StrokeKey> {
-        self.0
+        self.tree
             .locate_in_envelope_intersecting(&rstar::AABB::from_corners(
                 [bounds.mins[0], bounds.mins[1]],
                 [bounds.maxs[0], bounds.maxs[1]],
@@ -46,7 +111,7 @@ impl KeyTree {
 
     /// Return the keys that are completely contained in the given bounds.
     pub(crate) fn keys_in_bounds(&self, bounds: Aabb) -> Vec<StrokeKey> {
-        self.0
+        self.tree
             .locate_in_envelope(&rstar::AABB::from_corners(
                 [bounds.mins[0], bounds.mins[1]],
                 [bounds.maxs[0], bounds.maxs[1]],
@@ -62,7 +127,8 @@ impl KeyTree {
             .map(|(key, bounds)| new_keytree_object(key, bounds))
             .collect();
 
-        self.0 = rstar::RTree::bulk_load(objects);
+        self.tree = rstar::RTree::bulk_load(objects);
+        self.dirty_regions = DirtyRegions::All;
     }
 
     ///  Clear the entire tree.
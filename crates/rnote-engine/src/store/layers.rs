@@ -0,0 +1,233 @@
+// Imports
+use super::chrono_comp::StrokeLayer;
+use super::{StrokeKey, StrokeStore};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// A user layer. Holds the strokes that are placed on the [StrokeLayer::UserLayer] with the index of the layer.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, rename = "layer")]
+pub struct Layer {
+    /// The name of the layer. When empty, the UI displays a name derived from the index.
+    #[serde(rename = "name")]
+    pub name: String,
+    /// Strokes on hidden layers are not drawn and can't be selected, erased or edited with the pens.
+    #[serde(rename = "visible")]
+    pub visible: bool,
+    /// Strokes on locked layers are drawn, but can't be selected, erased or edited with the pens.
+    #[serde(rename = "locked")]
+    pub locked: bool,
+}
+
+impl Default for Layer {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            visible: true,
+            locked: false,
+        }
+    }
+}
+
+/// Systems that are related to the user layers.
+impl StrokeStore {
+    /// The user layers, ordered from bottom to top.
+    pub(crate) fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// The index of the layer that new strokes are placed on.
+    pub(crate) fn active_layer(&self) -> u32 {
+        self.active_layer
+    }
+
+    /// Set the layer that new strokes are placed on. The index is clamped to the existing layers.
+    pub(crate) fn set_active_layer(&mut self, index: u32) {
+        self.active_layer = index.min(self.layers.len().saturating_sub(1) as u32);
+    }
+
+    /// Whether strokes on the given layer are visible. Only user layers can be hidden.
+    pub(crate) fn layer_visible(&self, layer: StrokeLayer) -> bool {
+        match layer {
+            StrokeLayer::UserLayer(index) => self
+                .layers
+                .get(index as usize)
+                .is_none_or(|layer| layer.visible),
+            _ => true,
+        }
+    }
+
+    /// Whether the stroke for the given key is on a visible layer.
+    pub(crate) fn on_visible_layer(&self, key: StrokeKey) -> bool {
+        self.chrono_components
+            .get(key)
+            .is_none_or(|chrono_comp| self.layer_visible(chrono_comp.layer))
+    }
+
+    /// Whether strokes on the given layer are locked. Only user layers can be locked.
+    pub(crate) fn layer_locked(&self, layer: StrokeLayer) -> bool {
+        match layer {
+            StrokeLayer::UserLayer(index) => self
+                .layers
+                .get(index as usize)
+                .is_some_and(|layer| layer.locked),
+            _ => false,
+        }
+    }
+
+    /// Whether the stroke for the given key is on a locked layer.
+    pub(crate) fn on_locked_layer(&self, key: StrokeKey) -> bool {
+        self.chrono_components
+            .get(key)
+            .is_some_and(|chrono_comp| self.layer_locked(chrono_comp.layer))
+    }
+
+    /// Ensure that there is at least one layer and a layer for every user layer the strokes are placed on.
+    pub(crate) fn ensure_layers(&mut self) {
+        let n_layers = self
+            .chrono_components
+            .values()
+            .filter_map(|chrono_comp| match chrono_comp.layer {
+                StrokeLayer::UserLayer(index) => Some(index as usize + 1),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        if self.layers.len() < n_layers {
+            Arc::make_mut(&mut self.layers).resize_with(n_layers, Layer::default);
+        }
+        self.set_active_layer(self.active_layer);
+    }
+
+    /// Extend the layers so that the given layer exists.
+    pub(super) fn ensure_layer_exists(&mut self, layer: StrokeLayer) {
+        if let StrokeLayer::UserLayer(index) = layer
+            && index as usize >= self.layers.len()
+        {
+            Arc::make_mut(&mut self.layers).resize_with(index as usize + 1, Layer::default);
+        }
+    }
+
+    /// The keys of all strokes on the user layer with the given index, including trashed strokes.
+    pub(crate) fn keys_on_layer(&self, index: u32) -> Vec<StrokeKey> {
+        self.chrono_components
+            .iter()
+            .filter(|(_, chrono_comp)| chrono_comp.layer == StrokeLayer::UserLayer(index))
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    /// Insert a new, empty layer at the given index. The layers above are shifted up.
+    pub(crate) fn insert_layer(&mut self, index: u32) {
+        let index = index.min(self.layers.len() as u32);
+        self.remap_user_layers(|i| if i >= index { i + 1 } else { i });
+        Arc::make_mut(&mut self.layers).insert(index as usize, Layer::default());
+    }
+
+    /// Remove the layer at the given index, together with its strokes. The layers above are shifted down.
+    ///
+    /// The last remaining layer can't be removed. Returns false if nothing was removed.
+    pub(crate) fn remove_layer(&mut self, index: u32) -> bool {
+        if self.layers.len() <= 1 || index as usize >= self.layers.len() {
+            return false;
+        }
+        for key in self.keys_on_layer(index) {
+            self.mark_dirty_for_stroke(key);
+            self.remove_stroke(key);
+        }
+        self.remap_user_layers(|i| if i > index { i - 1 } else { i });
+        Arc::make_mut(&mut self.layers).remove(index as usize);
+        self.set_active_layer(self.active_layer);
+        true
+    }
+
+    /// Move the layer from the given index to the other index, shifting the layers in between.
+    pub(crate) fn move_layer(&mut self, from: u32, to: u32) {
+        let n_layers = self.layers.len() as u32;
+        if from >= n_layers || to >= n_layers || from == to {
+            return;
+        }
+        self.remap_user_layers(|i| {
+            if i == from {
+                to
+            } else if from < to && i > from && i <= to {
+                i - 1
+            } else if to < from && i >= to && i < from {
+                i + 1
+            } else {
+                i
+            }
+        });
+        let layers = Arc::make_mut(&mut self.layers);
+        let layer = layers.remove(from as usize);
+        layers.insert(to as usize, layer);
+    }
+
+    pub(crate) fn set_layer_name(&mut self, index: u32, name: String) {
+        if let Some(layer) = Arc::make_mut(&mut self.layers).get_mut(index as usize) {
+            layer.name = name;
+        }
+    }
+
+    /// Show or hide the layer at the given index. The strokes on a layer that gets hidden are deselected.
+    pub(crate) fn set_layer_visible(&mut self, index: u32, visible: bool) {
+        if self
+            .layers
+            .get(index as usize)
+            .is_none_or(|layer| layer.visible == visible)
+        {
+            return;
+        }
+        let keys = self.keys_on_layer(index);
+        if !visible {
+            let selected = keys
+                .iter()
+                .copied()
+                .filter(|&key| self.selected(key).unwrap_or(false))
+                .collect::<Vec<StrokeKey>>();
+            self.set_selected_keys(&selected, false);
+        }
+        if let Some(layer) = Arc::make_mut(&mut self.layers).get_mut(index as usize) {
+            layer.visible = visible;
+        }
+        for key in keys {
+            self.mark_dirty_for_stroke(key);
+        }
+    }
+
+    /// Lock or unlock the layer at the given index. The strokes on a layer that gets locked are deselected.
+    pub(crate) fn set_layer_locked(&mut self, index: u32, locked: bool) {
+        if self
+            .layers
+            .get(index as usize)
+            .is_none_or(|layer| layer.locked == locked)
+        {
+            return;
+        }
+        if locked {
+            let selected = self
+                .keys_on_layer(index)
+                .into_iter()
+                .filter(|&key| self.selected(key).unwrap_or(false))
+                .collect::<Vec<StrokeKey>>();
+            self.set_selected_keys(&selected, false);
+        }
+        if let Some(layer) = Arc::make_mut(&mut self.layers).get_mut(index as usize) {
+            layer.locked = locked;
+        }
+    }
+
+    /// Change the indices of the strokes on the user layers and of the active layer with the given mapping.
+    fn remap_user_layers(&mut self, map: impl Fn(u32) -> u32) {
+        let keys = self.chrono_components.keys().collect::<Vec<StrokeKey>>();
+        for key in keys {
+            if let Some(StrokeLayer::UserLayer(index)) = self.stroke_layer(key)
+                && map(index) != index
+            {
+                self.set_stroke_layer(key, StrokeLayer::UserLayer(map(index)));
+            }
+        }
+        self.active_layer = map(self.active_layer);
+    }
+}
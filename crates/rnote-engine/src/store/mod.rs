@@ -1,22 +1,32 @@
 // Modules
 pub mod chrono_comp;
+pub mod display_list;
+pub mod group_comp;
+pub mod instance_cache;
 pub mod keytree;
+pub mod layers;
 pub mod render_comp;
 pub mod selection_comp;
 pub mod stroke_comp;
+pub mod tile_cache;
 pub mod trash_comp;
 
 // Re-exports
 pub use chrono_comp::ChronoComponent;
+pub use group_comp::GroupComponent;
+use instance_cache::InstanceCache;
 use keytree::KeyTree;
+pub use layers::Layer;
 pub use render_comp::RenderComponent;
 pub use selection_comp::SelectionComponent;
+use tile_cache::TileCache;
 pub use trash_comp::TrashComponent;
 
 // Imports
 use self::chrono_comp::StrokeLayer;
 use crate::WidgetFlags;
 use crate::engine::EngineSnapshot;
+use crate::engine::rendering::{PrerenderMargin, RenderBackend, RenderLimits, RenderQuality};
 use crate::strokes::Stroke;
 use rnote_compose::shapes::Shapeable;
 use serde::{Deserialize, Serialize};
@@ -41,6 +51,10 @@ pub struct HistoryEntry {
     pub chrono_components: Arc<SecondaryMap<StrokeKey, Arc<ChronoComponent>>>,
     #[serde(rename = "chrono_counter")]
     pub chrono_counter: u32,
+    #[serde(rename = "group_components")]
+    pub group_components: Arc<SecondaryMap<StrokeKey, Arc<GroupComponent>>>,
+    #[serde(rename = "layers")]
+    pub layers: Arc<Vec<Layer>>,
 }
 
 impl Default for HistoryEntry {
@@ -51,6 +65,8 @@ impl Default for HistoryEntry {
             chrono_components: Arc::new(SecondaryMap::new()),
 
             chrono_counter: 0,
+            group_components: Arc::new(SecondaryMap::new()),
+            layers: Arc::new(vec![Layer::default()]),
         }
     }
 }
@@ -62,6 +78,8 @@ impl Default for HistoryEntry {
 ///     * 'trash_components': Holds state whether the strokes are trashed
 ///     * 'selection_components': Holds state whether the strokes are selected
 ///     * 'chrono_components': Holds state about the chronological ordering
+///     * 'group_components': Holds state about the groups the strokes are members of
+///     * 'layers': Holds the user layers the strokes are placed on, indexed by their [StrokeLayer::UserLayer]
 ///     * 'render_components': Holds state about the rendering.
 ///
 /// The systems are implemented as methods on StrokesStore, loosely categorized to the different components (but often modify others as well).
@@ -84,8 +102,45 @@ pub struct StrokeStore {
     /// Value must be kept equal to the [ChronoComponent] of the newest inserted or modified stroke.
     #[serde(rename = "chrono_counter")]
     chrono_counter: u32,
+    #[serde(rename = "group_components")]
+    group_components: Arc<SecondaryMap<StrokeKey, Arc<GroupComponent>>>,
+    /// The user layers, ordered from bottom to top.
+    #[serde(rename = "layers")]
+    layers: Arc<Vec<Layer>>,
+    /// The index of the layer that new strokes are placed on.
+    #[serde(skip)]
+    active_layer: u32,
     #[serde(skip)]
     render_components: SecondaryMap<StrokeKey, RenderComponent>,
+    /// The backend the render components are generated with.
+    #[serde(skip)]
+    render_backend: RenderBackend,
+    /// The limits for the images generated by the rendering.
+    #[serde(skip)]
+    render_limits: RenderLimits,
+    /// The margin around the viewport in which the strokes are already rendered.
+    #[serde(skip)]
+    prerender_margin: PrerenderMargin,
+    /// The quality of the images generated by the rendering.
+    #[serde(skip)]
+    render_quality: RenderQuality,
+    /// Whether the camera is moving, which lowers the resolution of newly generated images.
+    #[serde(skip)]
+    in_motion: bool,
+    /// The strokes whose images were generated with a lowered resolution while the camera was moving.
+    #[serde(skip)]
+    reduced_quality_keys: Vec<StrokeKey>,
+    /// The image scale the rendering in the viewport was last regenerated with.
+    ///
+    /// When it stays the same, the camera was only translated and the existing images can be reused.
+    #[serde(skip)]
+    viewport_image_scale: f64,
+    /// Caches the rendering of the strokes in tiles.
+    #[serde(skip)]
+    tile_cache: TileCache,
+    /// Shares the textures of bitmap images between strokes that display the same image.
+    #[serde(skip)]
+    instance_cache: InstanceCache,
     #[serde(skip)]
     history: VecDeque<HistoryEntry>,
     /// The index of the current live document in the history stack.
@@ -105,7 +160,17 @@ impl Default for StrokeStore {
             trash_components: Arc::new(SecondaryMap::new()),
             selection_components: Arc::new(SecondaryMap::new()),
             chrono_components: Arc::new(SecondaryMap::new()),
+            group_components: Arc::new(SecondaryMap::new()),
             render_components: SecondaryMap::new(),
+            render_backend: RenderBackend::default(),
+            render_limits: RenderLimits::default(),
+            prerender_margin: PrerenderMargin::default(),
+            render_quality: RenderQuality::default(),
+            in_motion: false,
+            reduced_quality_keys: vec![],
+            viewport_image_scale: 0.0,
+            tile_cache: TileCache::default(),
+            instance_cache: InstanceCache::default(),
 
             // Start off with state in the history
             history: VecDeque::from(vec![HistoryEntry::default()]),
@@ -114,6 +179,8 @@ impl Default for StrokeStore {
             key_tree: KeyTree::default(),
 
             chrono_counter: 0,
+            layers: Arc::new(vec![Layer::default()]),
+            active_layer: 0,
         }
     }
 }
@@ -132,13 +199,18 @@ impl StrokeStore {
         self.stroke_components = Arc::clone(&snapshot.stroke_components);
         self.chrono_components = Arc::clone(&snapshot.chrono_components);
         self.chrono_counter = snapshot.chrono_counter;
+        self.group_components = Arc::clone(&snapshot.group_components);
+        self.layers = Arc::clone(&snapshot.layers);
+        self.ensure_layers();
 
         self.update_geometry_for_strokes(&self.keys_unordered());
         self.rebuild_selection_components_slotmap();
+        self.rebuild_group_components_slotmap();
         self.rebuild_trash_components_slotmap();
         self.rebuild_render_components_slotmap();
         self.rebuild_rtree();
         widget_flags |= self.clear_history(self.create_history_entry());
+        widget_flags.refresh_layers = true;
         widget_flags
     }
 
@@ -159,6 +231,8 @@ impl StrokeStore {
             && Arc::ptr_eq(&self.trash_components, &history_entry.trash_components)
             && Arc::ptr_eq(&self.chrono_components, &history_entry.chrono_components)
             && self.chrono_counter == history_entry.chrono_counter
+            && Arc::ptr_eq(&self.group_components, &history_entry.group_components)
+            && Arc::ptr_eq(&self.layers, &history_entry.layers)
     }
 
     /// Create a history entry from the current state.
@@ -168,6 +242,8 @@ impl StrokeStore {
             trash_components: Arc::clone(&self.trash_components),
             chrono_components: Arc::clone(&self.chrono_components),
             chrono_counter: self.chrono_counter,
+            group_components: Arc::clone(&self.group_components),
+            layers: Arc::clone(&self.layers),
         }
     }
 
@@ -177,6 +253,9 @@ impl StrokeStore {
         self.trash_components = Arc::clone(&history_entry.trash_components);
         self.chrono_components = Arc::clone(&history_entry.chrono_components);
         self.chrono_counter = history_entry.chrono_counter;
+        self.group_components = Arc::clone(&history_entry.group_components);
+        self.layers = Arc::clone(&history_entry.layers);
+        self.set_active_layer(self.active_layer);
 
         // Since we don't store the rtree in the history, we need to rebuild it.
         self.rebuild_rtree();
@@ -263,6 +342,7 @@ impl StrokeStore {
         widget_flags.hide_undo = Some(!self.can_undo());
         widget_flags.hide_redo = Some(!self.can_redo());
         widget_flags.store_modified = true;
+        widget_flags.refresh_layers = true;
 
         widget_flags
     }
@@ -284,6 +364,7 @@ impl StrokeStore {
         widget_flags.hide_undo = Some(!self.can_undo());
         widget_flags.hide_redo = Some(!self.can_redo());
         widget_flags.store_modified = true;
+        widget_flags.refresh_layers = true;
 
         widget_flags
     }
@@ -321,6 +402,7 @@ impl StrokeStore {
     ) -> StrokeKey {
         let bounds = stroke.bounds();
         let layer = layer.unwrap_or_else(|| stroke.extract_default_layer());
+        self.ensure_layer_exists(layer);
 
         let key = Arc::make_mut(&mut self.stroke_components).insert(Arc::new(stroke));
         self.key_tree.insert_with_key(key, bounds);
@@ -333,18 +415,36 @@ impl StrokeStore {
             key,
             Arc::new(ChronoComponent::new(self.chrono_counter, layer)),
         );
+        Arc::make_mut(&mut self.group_components).insert(key, Arc::new(GroupComponent::default()));
         self.render_components
             .insert(key, RenderComponent::default());
 
         key
     }
 
+    /// Insert a new stroke into the store, which was created by the user.
+    ///
+    /// In contrast to [Self::insert_stroke], strokes that belong to a user layer are placed on the active layer.
+    ///
+    /// The stroke then needs to update its rendering.
+    pub(crate) fn insert_new_stroke(
+        &mut self,
+        stroke: Stroke,
+        layer: Option<StrokeLayer>,
+    ) -> StrokeKey {
+        let layer = match layer.unwrap_or_else(|| stroke.extract_default_layer()) {
+            StrokeLayer::UserLayer(_) => StrokeLayer::UserLayer(self.active_layer),
+            layer => layer,
+        };
+        self.insert_stroke(stroke, Some(layer))
+    }
+
     /// Permanently remove a stroke with the given key from the store.
-    #[allow(unused)]
     pub(crate) fn remove_stroke(&mut self, key: StrokeKey) -> Option<Stroke> {
         Arc::make_mut(&mut self.trash_components).remove(key);
         Arc::make_mut(&mut self.selection_components).remove(key);
         Arc::make_mut(&mut self.chrono_components).remove(key);
+        Arc::make_mut(&mut self.group_components).remove(key);
         self.render_components.remove(key);
 
         self.key_tree.remove_with_key(key);
@@ -359,9 +459,13 @@ impl StrokeStore {
         Arc::make_mut(&mut self.trash_components).clear();
         Arc::make_mut(&mut self.selection_components).clear();
         Arc::make_mut(&mut self.chrono_components).clear();
+        Arc::make_mut(&mut self.group_components).clear();
 
         self.chrono_counter = 0;
-        let widget_flags = self.clear_history(HistoryEntry::default());
+        self.layers = Arc::new(vec![Layer::default()]);
+        self.active_layer = 0;
+        let mut widget_flags = self.clear_history(HistoryEntry::default());
+        widget_flags.refresh_layers = true;
 
         self.render_components.clear();
         self.key_tree.clear();
@@ -1,13 +1,18 @@
 // Imports
+use super::instance_cache::InstanceCache;
 use super::{Stroke, StrokeKey, StrokeStore};
+use crate::Drawable;
 use crate::Image;
+use crate::engine::rendering::{self, PrerenderMargin, RenderBackend, RenderLimits, RenderQuality};
 use crate::engine::{EngineTask, EngineTaskSender};
 use crate::strokes::Content;
 use crate::strokes::content::GeneratedContentImages;
-use crate::{Drawable, image};
 use p2d::bounding_volume::{Aabb, BoundingVolume};
+use rayon::prelude::*;
 use rnote_compose::ext::AabbExt;
 use rnote_compose::shapes::Shapeable;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tracing::error;
 
 /// The tolerance where check between scale-factors are considered "equal".
@@ -17,7 +22,14 @@ pub(crate) const RENDER_IMAGE_SCALE_TOLERANCE: f64 = 0.01;
 pub enum RenderCompState {
     Complete,
     ForViewport(Aabb),
+    /// Rendered with paths by the [RenderBackend::GpuPaths] backend, independent of the viewport and image scale.
+    Paths,
     BusyRenderingInTask,
+    /// Drawn directly by the pen while the stroke is in progress, the images are generated once it is finished.
+    DirectDraw,
+    /// Rendered with a texture that is shared between the strokes displaying the same image,
+    /// independent of the viewport and image scale.
+    Instanced,
     Dirty,
 }
 
@@ -73,6 +85,96 @@ impl StrokeStore {
         self.render_components.get(key).is_some()
     }
 
+    pub(crate) fn render_backend(&self) -> RenderBackend {
+        self.render_backend
+    }
+
+    /// Set the render backend.
+    ///
+    /// The rendering then needs to be regenerated.
+    pub(crate) fn set_render_backend(&mut self, render_backend: RenderBackend) {
+        self.render_backend = render_backend;
+        self.clear_rendering();
+    }
+
+    pub(crate) fn render_limits(&self) -> RenderLimits {
+        self.render_limits
+    }
+
+    /// Set the render limits.
+    ///
+    /// The rendering then needs to be regenerated.
+    pub(crate) fn set_render_limits(&mut self, render_limits: RenderLimits) {
+        self.render_limits = render_limits;
+        self.clear_rendering();
+    }
+
+    /// The memory in bytes that is held by the images of the rendering.
+    pub(crate) fn rendering_memory_usage(&self) -> usize {
+        self.render_components
+            .values()
+            .flat_map(|render_comp| render_comp.images.iter())
+            .chain(self.tile_images())
+            .map(|image| image.data.len())
+            .sum()
+    }
+
+    /// The memory in bytes that is held by the images of each stroke rendering, for the strokes that hold images.
+    pub(crate) fn stroke_images_memory_usage(&self) -> Vec<(StrokeKey, usize)> {
+        self.render_components
+            .iter()
+            .filter(|(_, render_comp)| !render_comp.images.is_empty())
+            .map(|(key, render_comp)| {
+                (
+                    key,
+                    render_comp
+                        .images
+                        .iter()
+                        .map(|image| image.data.len())
+                        .sum(),
+                )
+            })
+            .collect()
+    }
+
+    pub(crate) fn n_instanced_textures(&self) -> usize {
+        self.instance_cache.len()
+    }
+
+    pub(crate) fn render_quality(&self) -> RenderQuality {
+        self.render_quality
+    }
+
+    /// Set the quality of the generated images.
+    ///
+    /// The rendering then needs to be regenerated when the antialiasing or flattening tolerance has changed.
+    pub(crate) fn set_render_quality(&mut self, render_quality: RenderQuality) {
+        let changed = self.render_quality.antialias != render_quality.antialias
+            || self.render_quality.flattening_tolerance != render_quality.flattening_tolerance
+            || self.render_quality.color_depth != render_quality.color_depth;
+        self.render_quality = render_quality;
+        if changed {
+            self.clear_rendering();
+        }
+    }
+
+    /// Set whether the camera is moving.
+    ///
+    /// When it stops, the renderings that were generated with a lowered resolution in the meantime are flagged dirty.
+    pub(crate) fn set_in_motion(&mut self, in_motion: bool) {
+        if self.in_motion && !in_motion {
+            let keys = std::mem::take(&mut self.reduced_quality_keys);
+            self.set_rendering_dirty_for_strokes(&keys);
+            self.set_reduced_quality_tiles_dirty();
+        }
+        self.in_motion = in_motion;
+    }
+
+    /// Set the margin around the viewport in which the strokes are already rendered.
+    pub(crate) fn set_prerender_margin(&mut self, prerender_margin: PrerenderMargin) {
+        self.prerender_margin = prerender_margin;
+    }
+
     pub(crate) fn render_comp_state(&self, key: StrokeKey) -> Option<RenderCompState> {
         self.render_components
             .get(key)
@@ -83,6 +185,32 @@ impl StrokeStore {
         if let Some(render_comp) = self.render_components.get_mut(key) {
             render_comp.state = RenderCompState::Dirty;
         }
+        self.mark_dirty_for_stroke(key);
+    }
+
+    /// Clear the rendering of the stroke and flag it to be drawn directly by the pen while it is in progress.
+    ///
+    /// Image generation for the stroke is deferred until the rendering is regenerated.
+    pub(crate) fn set_rendering_direct_draw(&mut self, key: StrokeKey) {
+        if let Some(render_comp) = self.render_components.get_mut(key) {
+            #[cfg(feature = "ui")]
+            {
+                render_comp.rendernodes = vec![];
+            }
+            render_comp.images = vec![];
+            render_comp.state = RenderCompState::DirectDraw;
+        }
+    }
+
+    /// Marks the region of the stroke as changed, so that the renderings intersecting it get regenerated.
+    pub(crate) fn mark_dirty_for_stroke(&mut self, key: StrokeKey) {
+        if let Some(bounds) = self
+            .stroke_components
+            .get(key)
+            .map(|stroke| stroke.bounds())
+        {
+            self.key_tree.mark_dirty(bounds);
+        }
     }
 
     pub(crate) fn set_rendering_dirty_for_strokes(&mut self, keys: &[StrokeKey]) {
@@ -106,14 +234,18 @@ impl StrokeStore {
         if let Some(stroke) = self.stroke_components.get(key)
             && let Some(render_comp) = self.render_components.get_mut(key)
         {
-            if render_comp.state == RenderCompState::BusyRenderingInTask {
+            if render_comp.state == RenderCompState::BusyRenderingInTask
+                || render_with_paths(self.render_backend, stroke, render_comp)
+                || render_instanced(&mut self.instance_cache, stroke, render_comp)
+            {
                 return;
             }
 
-            let viewport_extended =
-                viewport.extend_by(viewport.extents() * image::VIEWPORT_EXTENTS_MARGIN_FACTOR);
+            let viewport_extended = self.prerender_margin.extend_viewport(viewport);
+            let image_scale =
+                stroke_image_scale(self.render_limits, stroke, viewport_extended, image_scale);
 
-            match stroke.gen_images(viewport_extended, image_scale) {
+            match stroke.gen_images(viewport_extended, image_scale, self.render_quality) {
                 Ok(GeneratedContentImages::Partial { images, viewport }) => {
                     #[cfg(feature = "ui")]
                     match Image::images_to_rendernodes(&images) {
@@ -185,20 +317,27 @@ impl StrokeStore {
         if let Some(stroke) = self.stroke_components.get(key)
             && let Some(render_comp) = self.render_components.get_mut(key)
         {
-            if render_comp.state == RenderCompState::BusyRenderingInTask {
+            if render_comp.state == RenderCompState::BusyRenderingInTask
+                || render_with_paths(self.render_backend, stroke, render_comp)
+                || render_instanced(&mut self.instance_cache, stroke, render_comp)
+            {
                 return;
             }
 
             let stroke = stroke.clone();
-            let viewport_extended =
-                viewport.extend_by(viewport.extents() * image::VIEWPORT_EXTENTS_MARGIN_FACTOR);
+            let render_quality = self.render_quality;
+            let viewport_extended = self.prerender_margin.extend_viewport(viewport);
+            let gen_image_scale =
+                stroke_image_scale(self.render_limits, &stroke, viewport_extended, image_scale);
 
             // indicates that a task is now started rendering the stroke
             render_comp.state = RenderCompState::BusyRenderingInTask;
 
             // Spawn a new thread for image rendering
-            rayon::spawn(
-                move || match stroke.gen_images(viewport_extended, image_scale) {
+            rendering::spawn_render(move || {
+                match tasks_tx.render_counters().time_raster(|| {
+                    stroke.gen_images(viewport_extended, gen_image_scale, render_quality)
+                }) {
                     Ok(images) => {
                         tasks_tx.send(EngineTask::UpdateStrokeWithImages {
                             key,
@@ -211,11 +350,12 @@ impl StrokeStore {
                             "Generating images of stroke failed while regenerating stroke rendering, stroke key {key:?} , Err: {e:?}"
                         );
                     }
-                },
-            );
+                }
+            });
         }
     }
 
+    /// Regenerate the rendering of the strokes, generating the images of all strokes in parallel in a single task.
     pub(crate) fn regenerate_rendering_for_strokes_threaded(
         &mut self,
         tasks_tx: EngineTaskSender,
@@ -223,14 +363,35 @@ impl StrokeStore {
         viewport: Aabb,
         image_scale: f64,
     ) {
+        let viewport_extended = self.prerender_margin.extend_viewport(viewport);
+        let mut jobs = Vec::with_capacity(keys.len());
+
         for &key in keys {
-            self.regenerate_rendering_for_stroke_threaded(
-                tasks_tx.clone(),
-                key,
-                viewport,
-                image_scale,
-            );
+            if let Some(stroke) = self.stroke_components.get(key)
+                && let Some(render_comp) = self.render_components.get_mut(key)
+            {
+                if render_comp.state == RenderCompState::BusyRenderingInTask
+                    || render_with_paths(self.render_backend, stroke, render_comp)
+                    || render_instanced(&mut self.instance_cache, stroke, render_comp)
+                {
+                    continue;
+                }
+
+                // indicates that a task is now started rendering the stroke
+                render_comp.state = RenderCompState::BusyRenderingInTask;
+                let gen_image_scale =
+                    stroke_image_scale(self.render_limits, stroke, viewport_extended, image_scale);
+                jobs.push((key, Arc::clone(stroke), gen_image_scale));
+            }
         }
+
+        gen_images_batched_threaded(
+            tasks_tx,
+            jobs,
+            viewport_extended,
+            image_scale,
+            self.render_quality,
+        );
     }
 
     /// Regenerate the rendering of all keys for the given viewport that need to be rerendered.
@@ -241,16 +402,37 @@ impl StrokeStore {
         viewport: Aabb,
         image_scale: f64,
     ) {
+        self.regenerate_tiles_in_viewport_threaded(tasks_tx.clone(), viewport, image_scale);
         let keys = self.render_components.keys().collect::<Vec<StrokeKey>>();
+        let viewport_extended = self.prerender_margin.extend_viewport(viewport);
+        // when the image scale did not change the camera was only translated
+        let translated_only = !force_regenerate
+            && (self.viewport_image_scale - image_scale).abs() <= RENDER_IMAGE_SCALE_TOLERANCE;
+        self.viewport_image_scale = image_scale;
+        let mut jobs = vec![];
+        let mut translation_jobs = vec![];
+        let mut replaced_memory = 0;
 
         for key in keys {
+            let covered_by_tiles = self.stroke_components.get(key).is_some_and(|stroke| {
+                let stroke_bounds = stroke.bounds();
+                self.stroke_covered_by_tiles(
+                    key,
+                    stroke_bounds
+                        .intersection(&viewport_extended)
+                        .unwrap_or(stroke_bounds),
+                )
+            });
+
             if let Some(stroke) = self.stroke_components.get(key)
                 && let Some(render_comp) = self.render_components.get_mut(key)
             {
-                let tasks_tx = tasks_tx.clone();
                 let stroke_bounds = stroke.bounds();
-                let viewport_extended =
-                    viewport.extend_by(viewport.extents() * image::VIEWPORT_EXTENTS_MARGIN_FACTOR);
+
+                // the stroke is drawn directly until it is finished
+                if render_comp.state == RenderCompState::DirectDraw {
+                    continue;
+                }
 
                 // skip and clear image buffer if stroke is not in viewport
                 if !viewport_extended.intersects(&stroke_bounds) {
@@ -263,10 +445,30 @@ impl StrokeStore {
                     continue;
                 }
 
+                // strokes that are rendered in the tiles don't need their own rendering
+                if covered_by_tiles {
+                    #[cfg(feature = "ui")]
+                    {
+                        render_comp.rendernodes = vec![];
+                    }
+                    render_comp.images = vec![];
+                    render_comp.state = RenderCompState::Dirty;
+                    continue;
+                }
+
+                // path and instanced renderings don't depend on the viewport and image scale
+                if (render_comp.state == RenderCompState::Paths
+                    && self.render_backend == RenderBackend::GpuPaths)
+                    || render_comp.state == RenderCompState::Instanced
+                {
+                    continue;
+                }
+
                 // only check if rerendering is not forced
                 if !force_regenerate {
                     match render_comp.state {
                         RenderCompState::Complete | RenderCompState::BusyRenderingInTask => {
+                            tasks_tx.render_counters().record_cache_access(true);
                             continue;
                         }
                         RenderCompState::ForViewport(old_viewport) => {
@@ -278,44 +480,149 @@ impl StrokeStore {
                             if old_viewport.contains(
                                 &(viewport.extend_by(
                                     viewport.extents()
-                                        * image::VIEWPORT_EXTENTS_MARGIN_FACTOR
+                                        * self.prerender_margin.factor()
                                         * VIEWPORT_EXTENTS_MARGIN_RERENDER_THRESHOLD,
                                 )),
                             ) {
+                                tasks_tx.render_counters().record_cache_access(true);
+                                continue;
+                            }
+
+                            // keep the existing images and only generate the parts of the stroke
+                            // that newly entered the viewport
+                            if translated_only {
+                                let gen_image_scale = stroke_image_scale(
+                                    self.render_limits,
+                                    stroke,
+                                    viewport_extended,
+                                    image_scale,
+                                );
+                                let regions = viewport_extended
+                                    .subtract(&old_viewport)
+                                    .into_iter()
+                                    .filter_map(|region| region.intersection(&stroke_bounds))
+                                    .collect::<Vec<Aabb>>();
+                                let retained_images = render_comp
+                                    .images
+                                    .iter()
+                                    .filter(|image| {
+                                        image.rect.bounds().intersects(&viewport_extended)
+                                    })
+                                    .cloned()
+                                    .collect::<Vec<Image>>();
+
+                                render_comp.state = RenderCompState::BusyRenderingInTask;
+                                tasks_tx.render_counters().record_cache_access(false);
+                                translation_jobs.push(TranslationJob {
+                                    key,
+                                    stroke: Arc::clone(stroke),
+                                    image_scale: gen_image_scale,
+                                    retained_images,
+                                    regions,
+                                });
                                 continue;
                             }
                         }
-                        RenderCompState::Dirty => {}
+                        RenderCompState::Paths
+                        | RenderCompState::Instanced
+                        | RenderCompState::DirectDraw
+                        | RenderCompState::Dirty => {}
                     }
                 }
 
+                if render_with_paths(self.render_backend, stroke, render_comp)
+                    || render_instanced(&mut self.instance_cache, stroke, render_comp)
+                {
+                    continue;
+                }
+
                 // indicates that a task has now started to render the stroke
                 render_comp.state = RenderCompState::BusyRenderingInTask;
-                let stroke = stroke.clone();
-
-                // Spawn a new thread for image rendering
-                rayon::spawn(
-                    move || match stroke.gen_images(viewport_extended, image_scale) {
-                        Ok(images) => {
-                            tasks_tx.send(EngineTask::UpdateStrokeWithImages {
-                                key,
-                                images,
-                                image_scale,
-                            });
-                        }
-                        Err(e) => {
-                            error!(
-                                "Generating stroke images failed stroke while regenerating rendering in viewport `{viewport:?}`, stroke key: {key:?}, Err: {e:?}"
-                            );
-                        }
-                    },
-                );
+                tasks_tx.render_counters().record_cache_access(false);
+                // the images that are about to be replaced don't count against the budget
+                replaced_memory += render_comp
+                    .images
+                    .iter()
+                    .map(|image| image.data.len())
+                    .sum::<usize>();
+                let gen_image_scale =
+                    stroke_image_scale(self.render_limits, stroke, viewport_extended, image_scale);
+                jobs.push((key, Arc::clone(stroke), gen_image_scale));
             }
         }
+
+        // lower the image scale of the new images when they would exceed the memory budget
+        let projected = jobs
+            .iter()
+            .map(|(_, stroke, gen_image_scale)| {
+                let stroke_bounds = stroke.bounds();
+                rendering::projected_image_size(
+                    stroke_bounds
+                        .intersection(&viewport_extended)
+                        .unwrap_or(stroke_bounds),
+                    *gen_image_scale,
+                    self.render_quality.color_depth,
+                )
+            })
+            .chain(translation_jobs.iter().flat_map(|job| {
+                job.regions.iter().map(|region| {
+                    rendering::projected_image_size(
+                        *region,
+                        job.image_scale,
+                        self.render_quality.color_depth,
+                    )
+                })
+            }))
+            .sum();
+        let budget_scale_factor = self.render_limits.budget_scale_factor(
+            self.rendering_memory_usage()
+                .saturating_sub(replaced_memory),
+            projected,
+        );
+        if budget_scale_factor < 1.0 {
+            jobs.iter_mut()
+                .for_each(|(_, _, gen_image_scale)| *gen_image_scale *= budget_scale_factor);
+            translation_jobs
+                .iter_mut()
+                .for_each(|job| job.image_scale *= budget_scale_factor);
+        }
+
+        // lower the resolution while the camera is moving, regenerated in full once it stops
+        if self.in_motion {
+            jobs.iter_mut().for_each(|(_, _, gen_image_scale)| {
+                *gen_image_scale *= RenderQuality::IN_MOTION_IMAGE_SCALE_FACTOR
+            });
+            translation_jobs
+                .iter_mut()
+                .for_each(|job| job.image_scale *= RenderQuality::IN_MOTION_IMAGE_SCALE_FACTOR);
+            self.reduced_quality_keys.extend(
+                jobs.iter()
+                    .map(|(key, _, _)| *key)
+                    .chain(translation_jobs.iter().map(|job| job.key)),
+            );
+        }
+
+        self.instance_cache.remove_unused();
+
+        gen_images_translated_batched_threaded(
+            tasks_tx.clone(),
+            translation_jobs,
+            viewport_extended,
+            image_scale,
+            self.render_quality,
+        );
+        gen_images_batched_threaded(
+            tasks_tx,
+            jobs,
+            viewport_extended,
+            image_scale,
+            self.render_quality,
+        );
     }
 
     /// Clear all rendering for all strokes.
     pub(crate) fn clear_rendering(&mut self) {
+        self.clear_tiles();
         for (_key, render_comp) in self.render_components.iter_mut() {
             #[cfg(feature = "ui")]
             {
@@ -340,9 +647,17 @@ impl StrokeStore {
         if let Some(stroke) = self.stroke_components.get(key)
             && let Some(render_comp) = self.render_components.get_mut(key)
         {
+            if render_with_paths(self.render_backend, stroke, render_comp) {
+                return;
+            }
+
             match stroke.as_ref() {
                 Stroke::BrushStroke(brushstroke) => {
-                    match brushstroke.gen_image_for_last_segments(n_last_segments, image_scale) {
+                    match brushstroke.gen_image_for_last_segments(
+                        n_last_segments,
+                        image_scale,
+                        self.render_quality,
+                    ) {
                         Ok(Some(image)) => {
                             #[cfg(feature = "ui")]
                             match Image::images_to_rendernodes([&image]) {
@@ -376,7 +691,12 @@ impl StrokeStore {
                 Stroke::ShapeStroke(_)
                 | Stroke::TextStroke(_)
                 | Stroke::VectorImage(_)
-                | Stroke::BitmapImage(_) => {
+                | Stroke::BitmapImage(_)
+                | Stroke::SprayStroke(_)
+                | Stroke::StampStroke(_)
+                | Stroke::TapeStroke(_)
+                | Stroke::SmudgeStroke(_)
+                | Stroke::Opaque(_) => {
                     self.regenerate_rendering_for_stroke_threaded(
                         tasks_tx,
                         key,
@@ -444,6 +764,36 @@ impl StrokeStore {
         }
     }
 
+    /// Replace the current rendering with low resolution preview images.
+    ///
+    /// Keeps the render component state, because the images in full resolution are still being generated.
+    pub(crate) fn replace_rendering_with_preview_images(
+        &mut self,
+        key: StrokeKey,
+        images: GeneratedContentImages,
+    ) {
+        if let Some(render_comp) = self.render_components.get_mut(key) {
+            let (GeneratedContentImages::Partial { images, .. }
+            | GeneratedContentImages::Full(images)) = images;
+            #[cfg(feature = "ui")]
+            match Image::images_to_rendernodes(&images) {
+                Ok(rendernodes) => {
+                    render_comp.rendernodes = rendernodes;
+                    render_comp.images = images;
+                }
+                Err(e) => {
+                    error!(
+                        "Generating rendernodes failed while replacing rendering with preview images, Err: {e:?}"
+                    );
+                }
+            }
+            #[cfg(not(feature = "ui"))]
+            {
+                render_comp.images = images;
+            }
+        }
+    }
+
     /// Appends the images to the render component of the stroke.
     ///
     /// Not modifying the render component state, that is the responsibility of the caller.
@@ -482,44 +832,114 @@ impl StrokeStore {
     }
 
     /// Draw all strokes on the gtk snapshot.
+    ///
+    /// Returns the amount of drawn strokes.
     #[cfg(feature = "ui")]
     pub(crate) fn draw_strokes_to_gtk_snapshot(
         &self,
         snapshot: &gtk4::Snapshot,
         doc_bounds: Aabb,
         viewport: Aabb,
+    ) -> usize {
+        use crate::ext::GrapheneRectExt;
+        use crate::strokes::BlendMode;
+        use gtk4::{graphene, prelude::*};
+
+        snapshot.push_clip(&graphene::Rect::from_p2d_aabb(doc_bounds));
+
+        let strokes_drawn = if self.draw_tiles_to_gtk_snapshot(snapshot, viewport) {
+            self.keys_unordered_intersecting_bounds(viewport)
+                .into_iter()
+                .filter(|&key| !self.trashed(key).unwrap_or(true))
+                .count()
+        } else {
+            let keys = self.stroke_keys_as_rendered_intersecting_bounds(viewport);
+            let blending = keys.iter().any(|&key| {
+                self.stroke_components
+                    .get(key)
+                    .is_some_and(|stroke| stroke.blend_mode() != BlendMode::Normal)
+            });
+            if blending {
+                self.draw_strokes_blended_to_gtk_snapshot(snapshot, &keys);
+            } else {
+                for &key in keys.iter() {
+                    self.draw_stroke_rendering_to_gtk_snapshot(snapshot, key, true);
+                }
+            }
+            keys.len()
+        };
+
+        snapshot.pop();
+        strokes_drawn
+    }
+
+    /// Draw the renderings of the strokes on the gtk snapshot, blending them with the renderings of the strokes
+    /// before them according to their blend mode.
+    #[cfg(feature = "ui")]
+    fn draw_strokes_blended_to_gtk_snapshot(&self, snapshot: &gtk4::Snapshot, keys: &[StrokeKey]) {
+        use crate::strokes::BlendMode;
+        use gtk4::{gsk, prelude::*};
+
+        let mut nodes: Vec<gsk::RenderNode> = vec![];
+        for &key in keys {
+            let node_snapshot = gtk4::Snapshot::new();
+            self.draw_stroke_rendering_to_gtk_snapshot(&node_snapshot, key, true);
+            let Some(node) = node_snapshot.to_node() else {
+                continue;
+            };
+            let blend_mode = self
+                .stroke_components
+                .get(key)
+                .map(|stroke| stroke.blend_mode())
+                .unwrap_or_default();
+            if blend_mode == BlendMode::Normal || nodes.is_empty() {
+                nodes.push(node);
+            } else {
+                let bottom = gsk::ContainerNode::new(&nodes);
+                nodes =
+                    vec![gsk::BlendNode::new(&bottom, &node, blend_mode.gsk_blend_mode()).upcast()];
+            }
+        }
+        for node in nodes.iter() {
+            snapshot.append_node(node);
+        }
+    }
+
+    /// Draw the rendering of the stroke on the gtk snapshot.
+    ///
+    /// When `placeholder` is true and the stroke currently does not have a rendering but will create one,
+    /// a placeholder filled rect is drawn instead.
+    #[cfg(feature = "ui")]
+    pub(crate) fn draw_stroke_rendering_to_gtk_snapshot(
+        &self,
+        snapshot: &gtk4::Snapshot,
+        key: StrokeKey,
+        placeholder: bool,
     ) {
         use crate::ext::{GdkRGBAExt, GrapheneRectExt};
         use gtk4::{gdk, graphene, prelude::*};
         use rnote_compose::color;
 
-        snapshot.push_clip(&graphene::Rect::from_p2d_aabb(doc_bounds));
-
-        for key in self.stroke_keys_as_rendered_intersecting_bounds(viewport) {
-            if let Some(stroke) = self.stroke_components.get(key)
-                && let Some(render_comp) = self.render_components.get(key)
+        if let Some(stroke) = self.stroke_components.get(key)
+            && let Some(render_comp) = self.render_components.get(key)
+        {
+            if placeholder
+                && render_comp.rendernodes.is_empty()
+                && matches!(
+                    render_comp.state,
+                    RenderCompState::Dirty | RenderCompState::BusyRenderingInTask
+                )
             {
-                // if the stroke currently does not have a rendering and is will create one,
-                // draw a placeholder filled rect
-                if render_comp.rendernodes.is_empty()
-                    && matches!(
-                        render_comp.state,
-                        RenderCompState::Dirty | RenderCompState::BusyRenderingInTask
-                    )
-                {
-                    snapshot.append_color(
-                        &gdk::RGBA::from_piet_color(color::GNOME_BRIGHTS[1].with_alpha(0.13)),
-                        &graphene::Rect::from_p2d_aabb(stroke.bounds()),
-                    );
-                }
+                snapshot.append_color(
+                    &gdk::RGBA::from_piet_color(color::GNOME_BRIGHTS[1].with_alpha(0.13)),
+                    &graphene::Rect::from_p2d_aabb(stroke.bounds()),
+                );
+            }
 
-                for rendernode in render_comp.rendernodes.iter() {
-                    snapshot.append_node(rendernode);
-                }
+            for rendernode in render_comp.rendernodes.iter() {
+                snapshot.append_node(rendernode);
             }
         }
-
-        snapshot.pop();
     }
 
     /// Draw the strokes for the given keys on the [piet::RenderContext].
@@ -574,6 +994,8 @@ impl StrokeStore {
 
         let border_widths = 1.0 / engine.camera.total_zoom();
 
+        self.draw_tiles_debug_to_gtk_snapshot(snapshot, border_widths);
+
         for key in self.keys_sorted_chrono() {
             if let Some(stroke) = self.stroke_components.get(key)
                 && let Some(trash_comp) = self.trash_components.get(key)
@@ -655,3 +1077,274 @@ impl StrokeStore {
         Ok(())
     }
 }
+
+/// The image scale for generating the images of the stroke in the viewport, capped by the render limits.
+fn stroke_image_scale(
+    render_limits: RenderLimits,
+    stroke: &Stroke,
+    viewport: Aabb,
+    image_scale: f64,
+) -> f64 {
+    let stroke_bounds = stroke.bounds();
+    render_limits.capped_image_scale(
+        stroke_bounds
+            .intersection(&viewport)
+            .unwrap_or(stroke_bounds),
+        image_scale,
+    )
+}
+
+/// Generate the images of the strokes in a single task, which distributes them across the threads of the pool.
+///
+/// Compared to spawning a task for each stroke, the work is bounded by the size of the pool and submitted at once.
+/// The results are sent individually as soon as they are ready.
+///
+/// Each job holds the image scale its images are generated with, which might be lowered from the given image scale
+/// by the render limits.
+fn gen_images_batched_threaded(
+    tasks_tx: EngineTaskSender,
+    jobs: Vec<(StrokeKey, Arc<Stroke>, f64)>,
+    viewport: Aabb,
+    image_scale: f64,
+    render_quality: RenderQuality,
+) {
+    /// From this amount of strokes on, low resolution preview images are generated first and the progress is reported.
+    const PROGRESSIVE_MIN_JOBS: usize = 256;
+    /// The factor applied to the image scale of the preview images.
+    const PREVIEW_IMAGE_SCALE_FACTOR: f64 = 0.25;
+    /// The amount of progress reports over the entire task.
+    const N_PROGRESS_REPORTS: usize = 50;
+
+    if jobs.is_empty() {
+        return;
+    }
+
+    rendering::spawn_render(move || {
+        let total = jobs.len();
+        let progressive = total >= PROGRESSIVE_MIN_JOBS;
+
+        if progressive {
+            tasks_tx.send(EngineTask::RenderProgress { done: 0, total });
+            jobs.par_iter().for_each(|(key, stroke, gen_image_scale)| {
+                if let Ok(images) = stroke.gen_images(
+                    viewport,
+                    gen_image_scale * PREVIEW_IMAGE_SCALE_FACTOR,
+                    render_quality,
+                ) {
+                    tasks_tx.send(EngineTask::UpdateStrokeWithPreviewImages {
+                        key: *key,
+                        images,
+                        image_scale,
+                    });
+                }
+            });
+        }
+
+        let done = AtomicUsize::new(0);
+        let report_interval = (total / N_PROGRESS_REPORTS).max(1);
+        jobs.into_par_iter()
+            .for_each(|(key, stroke, gen_image_scale)| {
+                match tasks_tx
+                    .render_counters()
+                    .time_raster(|| stroke.gen_images(viewport, gen_image_scale, render_quality))
+                {
+                    Ok(images) => {
+                        tasks_tx.send(EngineTask::UpdateStrokeWithImages {
+                            key,
+                            images,
+                            image_scale,
+                        });
+                    }
+                    Err(e) => {
+                        error!(
+                            "Generating stroke images failed while regenerating rendering in viewport `{viewport:?}`, stroke key: {key:?}, Err: {e:?}"
+                        );
+                    }
+                }
+
+                // failed strokes count as done as well, so that the progress always finishes
+                let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+                if progressive && (done % report_interval == 0 || done == total) {
+                    tasks_tx.send(EngineTask::RenderProgress { done, total });
+                }
+            });
+    });
+}
+
+/// Generating the images for a stroke whose rendering is kept while the camera is translated.
+struct TranslationJob {
+    key: StrokeKey,
+    stroke: Arc<Stroke>,
+    /// The image scale the images are generated with.
+    image_scale: f64,
+    /// The existing images that are still in the viewport.
+    retained_images: Vec<Image>,
+    /// The regions of the stroke that newly entered the viewport.
+    regions: Vec<Aabb>,
+}
+
+/// Generate the images for the regions of the strokes that newly entered the viewport in a single task.
+///
+/// The results replace the rendering together with the retained images, so that they are not regenerated.
+fn gen_images_translated_batched_threaded(
+    tasks_tx: EngineTaskSender,
+    jobs: Vec<TranslationJob>,
+    viewport: Aabb,
+    image_scale: f64,
+    render_quality: RenderQuality,
+) {
+    if jobs.is_empty() {
+        return;
+    }
+
+    rendering::spawn_render(move || {
+        jobs.into_par_iter().for_each(|job| {
+            let mut images = job.retained_images;
+            for region in job.regions {
+                match tasks_tx
+                    .render_counters()
+                    .time_raster(|| job.stroke.gen_images(region, job.image_scale, render_quality))
+                {
+                    Ok(GeneratedContentImages::Partial {
+                        images: mut region_images,
+                        ..
+                    })
+                    | Ok(GeneratedContentImages::Full(mut region_images)) => {
+                        images.append(&mut region_images);
+                    }
+                    Err(e) => {
+                        error!(
+                            "Generating stroke images failed while translating rendering to viewport `{viewport:?}`, stroke key: {:?}, Err: {e:?}",
+                            job.key
+                        );
+                        tasks_tx.send(EngineTask::StrokeRenderingFailed { key: job.key });
+                        return;
+                    }
+                }
+            }
+            tasks_tx.send(EngineTask::UpdateStrokeWithImages {
+                key: job.key,
+                images: GeneratedContentImages::Partial { images, viewport },
+                image_scale,
+            });
+        });
+    });
+}
+
+/// Render the stroke with paths into the render component when the render backend is [RenderBackend::GpuPaths]
+/// and the stroke supports it.
+///
+/// Returns true when the stroke was rendered, else the rendering needs to be generated with images.
+#[cfg(feature = "ui")]
+fn render_with_paths(
+    render_backend: RenderBackend,
+    stroke: &Stroke,
+    render_comp: &mut RenderComponent,
+) -> bool {
+    use crate::ext::{GdkRGBAExt, GskPathExt};
+    use gtk4::{gdk, gsk, prelude::*};
+    use rnote_compose::Style;
+    use rnote_compose::style::smooth::LineStyle;
+
+    if render_backend != RenderBackend::GpuPaths {
+        return false;
+    }
+    let Stroke::BrushStroke(brushstroke) = stroke else {
+        return false;
+    };
+    let Style::Smooth(options) = &brushstroke.style else {
+        return false;
+    };
+    // Dashed and dotted paths are stroked, not filled
+    if options.line_style != LineStyle::Solid {
+        return false;
+    }
+
+    render_comp.images = vec![];
+    render_comp.rendernodes = vec![];
+    render_comp.state = RenderCompState::Paths;
+
+    let Some(color) = options.stroke_color else {
+        return true;
+    };
+    let snapshot = gtk4::Snapshot::new();
+    let colors = brushstroke
+        .path
+        .composed_outline_colors(color, options.stroke_gradient.as_ref());
+    for (outline, color) in brushstroke
+        .path
+        .composed_outlines(options)
+        .iter()
+        .zip(colors)
+    {
+        snapshot.append_fill(
+            &gsk::Path::from_kurbo_bezpath(outline),
+            gsk::FillRule::Winding,
+            &gdk::RGBA::from_compose_color(color),
+        );
+    }
+    if let Some(rendernode) = snapshot.to_node() {
+        render_comp.rendernodes.push(rendernode);
+    }
+
+    true
+}
+
+#[cfg(not(feature = "ui"))]
+fn render_with_paths(
+    _render_backend: RenderBackend,
+    _stroke: &Stroke,
+    _render_comp: &mut RenderComponent,
+) -> bool {
+    false
+}
+
+/// Render the bitmap image stroke with the texture that is shared with all strokes displaying the same image.
+///
+/// Returns true when the stroke was rendered, else the rendering needs to be generated with images.
+#[cfg(feature = "ui")]
+fn render_instanced(
+    instance_cache: &mut InstanceCache,
+    stroke: &Stroke,
+    render_comp: &mut RenderComponent,
+) -> bool {
+    use crate::ext::GrapheneRectExt;
+    use gtk4::{graphene, gsk, prelude::*};
+
+    let Stroke::BitmapImage(bitmapimage) = stroke else {
+        return false;
+    };
+    let texture = match instance_cache.texture(&bitmapimage.image) {
+        Ok(texture) => texture,
+        Err(e) => {
+            error!("Creating shared texture for bitmap image failed, Err: {e:?}");
+            return false;
+        }
+    };
+
+    let texture_node = gsk::TextureNode::new(
+        &texture,
+        &graphene::Rect::from_p2d_aabb(bitmapimage.rectangle.cuboid.local_aabb()),
+    )
+    .upcast();
+    let transform_node = gsk::TransformNode::new(
+        &texture_node,
+        &crate::utils::transform_to_gsk(&bitmapimage.rectangle.transform),
+    )
+    .upcast();
+
+    render_comp.images = vec![];
+    render_comp.rendernodes = vec![transform_node];
+    render_comp.state = RenderCompState::Instanced;
+
+    true
+}
+
+#[cfg(not(feature = "ui"))]
+fn render_instanced(
+    _instance_cache: &mut InstanceCache,
+    _stroke: &Stroke,
+    _render_comp: &mut RenderComponent,
+) -> bool {
+    false
+}
@@ -99,11 +99,13 @@ impl StrokeStore {
         let old_selected = self.selection_keys_as_rendered();
         self.set_selected_keys(&old_selected, false);
 
-        let new_selected = old_selected
+        let duplicated = old_selected
             .iter()
             .filter_map(|&old_key| {
-                let new_key =
-                    self.insert_stroke((**self.stroke_components.get(old_key)?).clone(), None);
+                let new_key = self.insert_stroke(
+                    (**self.stroke_components.get(old_key)?).clone(),
+                    self.stroke_layer(old_key),
+                );
                 self.set_selected(new_key, true);
 
                 // duplicate and insert the render images of the old stroke to avoid flickering
@@ -121,8 +123,14 @@ impl StrokeStore {
                         );
                     }
                 }
-                Some(new_key)
+                Some((old_key, new_key))
             })
+            .collect::<Vec<(StrokeKey, StrokeKey)>>();
+        // The copies of grouped strokes form new groups
+        self.duplicate_groups(&duplicated);
+        let new_selected = duplicated
+            .into_iter()
+            .map(|(_, new_key)| new_key)
             .collect::<Vec<StrokeKey>>();
 
         // Offsetting the new selected stroke to make the duplication apparent
@@ -1,5 +1,6 @@
 // Imports
 use super::StrokeKey;
+use super::chrono_comp::StrokeLayer;
 use super::render_comp::RenderCompState;
 use crate::engine::StrokeContent;
 use crate::strokes::{Content, Stroke};
@@ -10,11 +11,55 @@ use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::Color;
 use rnote_compose::penpath::Element;
 use rnote_compose::shapes::Shapeable;
-use rnote_compose::transform::Transformable;
+use rnote_compose::transform::{Projective, Transformable, Warpable};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 #[cfg(feature = "ui")]
 use tracing::error;
 
+/// The kinds of strokes that a selection can be restricted to.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "stroke_kind_filter")]
+pub enum StrokeKindFilter {
+    /// All strokes.
+    #[default]
+    #[serde(rename = "all")]
+    All = 0,
+    /// Brush strokes drawn with the marker.
+    #[serde(rename = "markers")]
+    Markers,
+    /// Vector and bitmap images.
+    #[serde(rename = "images")]
+    Images,
+    /// Text strokes.
+    #[serde(rename = "text")]
+    Text,
+}
+
+impl TryFrom<u32> for StrokeKindFilter {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!(
+                "StrokeKindFilter try_from::<u32>() for value {} failed",
+                value
+            )
+        })
+    }
+}
+
 /// Systems that are related to the stroke components.
 impl StrokeStore {
     /// Gets a immutable reference to a stroke.
@@ -67,30 +112,93 @@ impl StrokeStore {
             .collect()
     }
 
+    /// All stroke keys that are not trashed, including the ones on hidden layers, in chronological order.
+    pub(crate) fn stroke_keys_sorted_chrono(&self) -> Vec<StrokeKey> {
+        self.keys_sorted_chrono()
+            .into_iter()
+            .filter(|&key| !(self.trashed(key).unwrap_or(false)))
+            .collect::<Vec<StrokeKey>>()
+    }
+
     /// Storke keys in the order that they should be rendered.
+    ///
+    /// The strokes on hidden layers are excluded.
     pub(crate) fn stroke_keys_as_rendered(&self) -> Vec<StrokeKey> {
         self.keys_sorted_chrono()
             .into_iter()
-            .filter(|&key| !(self.trashed(key).unwrap_or(false)))
+            .filter(|&key| !(self.trashed(key).unwrap_or(false)) && self.on_visible_layer(key))
             .collect::<Vec<StrokeKey>>()
     }
 
     /// Stroke keys intersecting the given bounds, in the order that they should be rendered.
+    ///
+    /// The strokes on hidden layers are excluded.
     pub(crate) fn stroke_keys_as_rendered_intersecting_bounds(
         &self,
         bounds: Aabb,
     ) -> Vec<StrokeKey> {
         self.keys_sorted_chrono_intersecting_bounds(bounds)
             .into_iter()
-            .filter(|&key| !(self.trashed(key).unwrap_or(false)))
+            .filter(|&key| !(self.trashed(key).unwrap_or(false)) && self.on_visible_layer(key))
             .collect::<Vec<StrokeKey>>()
     }
 
+    /// Stroke keys whose color is within the tolerance of the given color, in the order that they should be rendered.
+    ///
+    /// Strokes without a color, like images, are never included.
+    pub(crate) fn stroke_keys_as_rendered_with_color(
+        &self,
+        color: Color,
+        tolerance: f64,
+    ) -> Vec<StrokeKey> {
+        self.stroke_keys_as_rendered()
+            .into_iter()
+            .filter(|&key| {
+                self.stroke_components
+                    .get(key)
+                    .and_then(|stroke| stroke.stroke_color())
+                    .is_some_and(|stroke_color| stroke_color.distance(&color) <= tolerance)
+            })
+            .collect::<Vec<StrokeKey>>()
+    }
+
+    /// The keys of the strokes that are of the kind that passes the filter.
+    pub(crate) fn filter_stroke_kind(
+        &self,
+        keys: Vec<StrokeKey>,
+        filter: StrokeKindFilter,
+    ) -> Vec<StrokeKey> {
+        if filter == StrokeKindFilter::All {
+            return keys;
+        }
+        keys.into_iter()
+            .filter(|&key| {
+                let Some(stroke) = self.stroke_components.get(key) else {
+                    return false;
+                };
+                match filter {
+                    StrokeKindFilter::All => true,
+                    // Marker strokes are distinguished from other brush strokes by the layer they are placed on
+                    StrokeKindFilter::Markers => {
+                        matches!(**stroke, Stroke::BrushStroke(_))
+                            && self.stroke_layer(key) == Some(StrokeLayer::Highlighter)
+                    }
+                    StrokeKindFilter::Images => {
+                        matches!(**stroke, Stroke::VectorImage(_) | Stroke::BitmapImage(_))
+                    }
+                    StrokeKindFilter::Text => matches!(**stroke, Stroke::TextStroke(_)),
+                }
+            })
+            .collect()
+    }
+
     /// Stroke keys contained in the given bounds, in the order that they should be rendered.
+    ///
+    /// The strokes on hidden layers are excluded.
     pub(crate) fn stroke_keys_as_rendered_in_bounds(&self, bounds: Aabb) -> Vec<StrokeKey> {
         self.keys_sorted_chrono_in_bounds(bounds)
             .into_iter()
-            .filter(|&key| !(self.trashed(key).unwrap_or(false)))
+            .filter(|&key| !(self.trashed(key).unwrap_or(false)) && self.on_visible_layer(key))
             .collect::<Vec<StrokeKey>>()
     }
 
@@ -257,6 +365,23 @@ impl StrokeStore {
         });
     }
 
+    /// Warp the strokes with the projective transform.
+    ///
+    /// The strokes then need to update their rendering.
+    pub(crate) fn warp_strokes(&mut self, keys: &[StrokeKey], projective: &Projective) {
+        keys.iter().for_each(|&key| {
+            if let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
+                .get_mut(key)
+                .map(Arc::make_mut)
+            {
+                stroke.warp(projective);
+                self.key_tree.update_with_key(key, stroke.bounds());
+            }
+        });
+        // The rendered images can't be warped, so they are regenerated
+        self.set_rendering_dirty_for_strokes(keys);
+    }
+
     /// Change the stroke and text color for the given keys.
     ///
     /// The strokes then need to update their rendering.
@@ -286,6 +411,18 @@ impl StrokeStore {
                             text_stroke.text_style.color = color;
                             self.set_rendering_dirty(key);
                         }
+                        Stroke::SprayStroke(spray_stroke) => {
+                            spray_stroke.options.stroke_color = Some(color);
+                            self.set_rendering_dirty(key);
+                        }
+                        Stroke::StampStroke(stamp_stroke) => {
+                            stamp_stroke.color = Some(color);
+                            self.set_rendering_dirty(key);
+                        }
+                        Stroke::TapeStroke(tape_stroke) => {
+                            tape_stroke.options.color = color;
+                            self.set_rendering_dirty(key);
+                        }
                         _ => {}
                     }
                 }
@@ -298,6 +435,30 @@ impl StrokeStore {
         widget_flags
     }
 
+    /// Change the styles of the strokes for the given keys with the given function, which returns whether it
+    /// changed the stroke.
+    ///
+    /// Returns the keys of the changed strokes. They then need to update their rendering.
+    pub(crate) fn change_stroke_styles(
+        &mut self,
+        keys: &[StrokeKey],
+        change_style: impl Fn(&mut Stroke) -> bool,
+    ) -> Vec<StrokeKey> {
+        let changed_keys = keys
+            .iter()
+            .copied()
+            .filter(|&key| {
+                Arc::make_mut(&mut self.stroke_components)
+                    .get_mut(key)
+                    .map(Arc::make_mut)
+                    .is_some_and(&change_style)
+            })
+            .collect::<Vec<StrokeKey>>();
+        // Style changes like the stroke width affect the bounds and hitboxes
+        self.update_geometry_for_strokes(&changed_keys);
+        changed_keys
+    }
+
     /// Invert the stroke, text and fill color of the given keys.
     ///
     /// Strokes then need to update their rendering.
@@ -488,11 +649,11 @@ impl StrokeStore {
             geo::Polygon::new(selector_path_points.into(), vec![])
         };
 
-        self.keys_sorted_chrono_intersecting_bounds(bounds)
+        self.stroke_keys_as_rendered_intersecting_bounds(bounds)
             .into_iter()
             .filter_map(|key| {
-                // skip if stroke is trashed
-                if self.trashed(key)? {
+                // skip if stroke is trashed or locked
+                if self.trashed(key)? || self.locked(key)? {
                     return None;
                 }
 
@@ -543,11 +704,11 @@ impl StrokeStore {
             geo::LineString::new(selector_path_points)
         };
 
-        self.keys_sorted_chrono_intersecting_bounds(bounds)
+        self.stroke_keys_as_rendered_intersecting_bounds(bounds)
             .into_iter()
             .filter_map(|key| {
-                // skip if stroke is trashed
-                if self.trashed(key)? {
+                // skip if stroke is trashed or locked
+                if self.trashed(key)? || self.locked(key)? {
                     return None;
                 }
 
@@ -576,11 +737,11 @@ impl StrokeStore {
         aabb: Aabb,
         viewport: Aabb,
     ) -> Vec<StrokeKey> {
-        self.keys_sorted_chrono_intersecting_bounds(viewport.merged(&aabb))
+        self.stroke_keys_as_rendered_intersecting_bounds(viewport.merged(&aabb))
             .into_iter()
             .filter_map(|key| {
-                // skip if stroke is trashed
-                if self.trashed(key)? {
+                // skip if stroke is trashed or locked
+                if self.trashed(key)? || self.locked(key)? {
                     return None;
                 }
 
@@ -628,6 +789,36 @@ impl StrokeStore {
             .collect()
     }
 
+    /// Return the points of the strokes in the given bounds that new shapes can snap onto.
+    ///
+    /// These are the snap points of shapes and the endpoints of brush strokes.
+    pub(crate) fn snap_candidates_in_bounds(&self, bounds: Aabb) -> Vec<na::Vector2<f64>> {
+        self.stroke_keys_as_rendered_intersecting_bounds(bounds)
+            .into_iter()
+            .filter_map(|key| self.stroke_components.get(key))
+            .flat_map(|stroke| match stroke.as_ref() {
+                Stroke::ShapeStroke(shapestroke) => shapestroke.shape.snap_points(),
+                Stroke::BrushStroke(brushstroke) => {
+                    let path = &brushstroke.path;
+                    let mut snap_points = vec![path.start.pos];
+                    if let Some(last) = path.segments.last() {
+                        snap_points.push(last.end().pos);
+                    }
+                    snap_points
+                }
+                Stroke::TextStroke(_)
+                | Stroke::VectorImage(_)
+                | Stroke::BitmapImage(_)
+                | Stroke::SprayStroke(_)
+                | Stroke::StampStroke(_)
+                | Stroke::TapeStroke(_)
+                | Stroke::SmudgeStroke(_)
+                | Stroke::Opaque(_) => vec![],
+            })
+            .filter(|point| bounds.contains_local_point(&(*point).into()))
+            .collect()
+    }
+
     /// Return all keys below the given `y`.
     pub(crate) fn keys_below_y(&self, y: f64) -> Vec<StrokeKey> {
         self.stroke_components
@@ -688,26 +879,40 @@ impl StrokeStore {
     }
 
     pub(crate) fn fetch_stroke_content(&self, keys: &[StrokeKey]) -> StrokeContent {
-        let strokes = keys
+        let (strokes, (stroke_layers, stroke_groups)) = keys
             .iter()
-            .filter_map(|k| self.stroke_components.get(*k).cloned())
-            .collect();
+            .filter_map(|k| {
+                Some((
+                    self.stroke_components.get(*k).cloned()?,
+                    (self.stroke_layer(*k)?, self.stroke_groups(*k)),
+                ))
+            })
+            .unzip();
 
-        StrokeContent::default().with_strokes(strokes)
+        StrokeContent::default()
+            .with_strokes(strokes)
+            .with_stroke_layers(stroke_layers)
+            .with_stroke_groups(stroke_groups)
     }
 
     /// Cut the strokes for the given keys and return them as stroke content.
     pub(crate) fn cut_stroke_content(&mut self, keys: &[StrokeKey]) -> StrokeContent {
-        let strokes = keys
+        let (strokes, (stroke_layers, stroke_groups)) = keys
             .iter()
             .filter_map(|k| {
                 self.set_selected(*k, false);
                 self.set_trashed(*k, true);
-                self.stroke_components.get(*k).cloned()
+                Some((
+                    self.stroke_components.get(*k).cloned()?,
+                    (self.stroke_layer(*k)?, self.stroke_groups(*k)),
+                ))
             })
-            .collect();
+            .unzip();
 
-        StrokeContent::default().with_strokes(strokes)
+        StrokeContent::default()
+            .with_strokes(strokes)
+            .with_stroke_layers(stroke_layers)
+            .with_stroke_groups(stroke_groups)
     }
 
     /// Paste the clipboard content as a selection.
@@ -729,12 +934,14 @@ impl StrokeStore {
             .iter()
             .fold(Aabb::new_invalid(), |acc, s| acc.merged(&s.bounds()));
 
-        clipboard_content
+        let stroke_layers = clipboard_content.stroke_layers;
+        let inserted = clipboard_content
             .strokes
             .into_iter()
-            .map(|s| {
+            .enumerate()
+            .map(|(i, s)| {
                 let offset = s.bounds().mins.coords - clipboard_bounds.mins.coords;
-                let key = self.insert_stroke((*s).clone(), None);
+                let key = self.insert_new_stroke((*s).clone(), stroke_layers.get(i).copied());
                 // position strokes without resizing
                 self.set_stroke_pos(key, pos);
                 self.translate_strokes(&[key], offset);
@@ -747,6 +954,14 @@ impl StrokeStore {
                 self.set_selected(key, true);
                 key
             })
-            .collect()
+            .collect::<Vec<StrokeKey>>();
+        // The pasted strokes form new groups
+        self.insert_groups(
+            inserted
+                .iter()
+                .copied()
+                .zip(clipboard_content.stroke_groups),
+        );
+        inserted
     }
 }
@@ -0,0 +1,845 @@
+// Imports
+use super::display_list::DisplayList;
+use super::keytree::DirtyRegions;
+use super::render_comp::RENDER_IMAGE_SCALE_TOLERANCE;
+use super::{StrokeKey, StrokeStore};
+use crate::engine::rendering::{self, RenderBackend, RenderQuality};
+use crate::engine::{EngineTask, EngineTaskSender};
+use crate::strokes::Stroke;
+use crate::{Drawable, Image};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::RenderContext;
+use rnote_compose::ext::AabbExt;
+use rnote_compose::shapes::Shapeable;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::error;
+
+/// The width and height of the content tiles in pixels on the surface.
+pub(crate) const TILE_SIZE: f64 = 512.0;
+
+/// Below this image scale the strokes in the tiles are drawn with a simplified level of detail.
+pub(crate) const LEVEL_OF_DETAIL_IMAGE_SCALE_THRESHOLD: f64 = 0.3;
+
+/// Above this amount of outdated regions the entire rendering of a tile is considered outdated.
+const OUTDATED_REGIONS_MAX: usize = 16;
+
+/// The index of a tile in the tile grid of the current image scale.
+pub type TileIndex = (i64, i64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TileState {
+    Complete,
+    BusyRenderingInTask,
+    Dirty,
+}
+
+impl Default for TileState {
+    fn default() -> Self {
+        Self::Dirty
+    }
+}
+
+/// The rendering of a tile, together with the strokes it was rendered from.
+#[derive(Debug, Clone)]
+struct TileRendering {
+    /// The keys of the rendered strokes, in the order they were rendered.
+    order: Vec<StrokeKey>,
+    /// The rendered strokes.
+    ///
+    /// Holding on to them makes modifying them through [Arc::make_mut] create new instances,
+    /// so changed strokes can be detected by pointer comparison.
+    strokes: HashMap<StrokeKey, Arc<Stroke>>,
+    /// Is None when no strokes intersect the tile.
+    image: Option<Image>,
+    #[cfg(feature = "ui")]
+    rendernode: Option<gtk4::gsk::RenderNode>,
+}
+
+impl TileRendering {
+    /// Whether the stroke is rendered in its current state.
+    fn contains(&self, key: StrokeKey, stroke: &Arc<Stroke>) -> bool {
+        self.strokes
+            .get(&key)
+            .is_some_and(|rendered| Arc::ptr_eq(rendered, stroke))
+    }
+
+    /// Whether the rendering is up to date with the given strokes, in the order they should be rendered.
+    fn renders(&self, strokes: &[(StrokeKey, Arc<Stroke>)]) -> bool {
+        self.order.len() == strokes.len()
+            && self
+                .order
+                .iter()
+                .zip(strokes.iter())
+                .all(|(&rendered_key, (key, stroke))| {
+                    rendered_key == *key && self.contains(*key, stroke)
+                })
+    }
+}
+
+/// Whether both lists hold the same stroke instances in the same order.
+fn strokes_render_equal(a: &[(StrokeKey, Arc<Stroke>)], b: &[(StrokeKey, Arc<Stroke>)]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b.iter())
+            .all(|((a_key, a_stroke), (b_key, b_stroke))| {
+                a_key == b_key && Arc::ptr_eq(a_stroke, b_stroke)
+            })
+}
+
+#[derive(Debug, Clone, Default)]
+struct Tile {
+    state: TileState,
+    rendering: Option<TileRendering>,
+    /// Whether the rendering was generated with a lowered resolution while the camera was moving.
+    reduced_quality: bool,
+    /// The strokes intersecting the tile in the order that they should be rendered.
+    ///
+    /// Updated when the tile is regenerated, so that drawing does not need to query them for every tile.
+    strokes: Vec<(StrokeKey, Arc<Stroke>)>,
+    /// The regions in the tile where the rendering shows strokes that have changed or were removed since.
+    outdated_regions: Vec<Aabb>,
+}
+
+impl Tile {
+    /// Update the regions where the rendering is outdated compared to the current strokes.
+    ///
+    /// `margin` loosens the regions to include the antialiasing of the rendered strokes.
+    fn update_outdated_regions(&mut self, bounds: Aabb, margin: f64) {
+        let Some(rendering) = self.rendering.as_ref() else {
+            self.outdated_regions.clear();
+            return;
+        };
+        let current = self
+            .strokes
+            .iter()
+            .map(|(key, stroke)| (*key, stroke))
+            .collect::<HashMap<StrokeKey, &Arc<Stroke>>>();
+        self.outdated_regions = rendering
+            .strokes
+            .iter()
+            .filter(|(key, rendered)| {
+                !current
+                    .get(key)
+                    .is_some_and(|stroke| Arc::ptr_eq(stroke, rendered))
+            })
+            .filter_map(|(_, rendered)| rendered.bounds().loosened(margin).intersection(&bounds))
+            .collect();
+        if self.outdated_regions.len() > OUTDATED_REGIONS_MAX {
+            self.outdated_regions = vec![bounds];
+        }
+    }
+
+    /// Whether the stroke is rendered in its current state and does not intersect an outdated region.
+    ///
+    /// A tile without rendering counts as covering, because the stroke will be included when it is rendered.
+    fn covers(&self, key: StrokeKey, stroke: &Arc<Stroke>, bounds: Aabb) -> bool {
+        self.rendering.as_ref().is_none_or(|rendering| {
+            rendering.contains(key, stroke)
+                && !self
+                    .outdated_regions
+                    .iter()
+                    .any(|region| region.intersects(&bounds))
+        })
+    }
+}
+
+/// Splits the bounds along the edges of the regions into the cells outside of all regions and the cells inside of them.
+///
+/// Neighbouring cells in a row are merged.
+#[cfg(any(feature = "ui", test))]
+fn split_by_regions(bounds: Aabb, regions: &[Aabb]) -> (Vec<Aabb>, Vec<Aabb>) {
+    let regions = regions
+        .iter()
+        .filter_map(|region| region.intersection(&bounds))
+        .collect::<Vec<Aabb>>();
+    if regions.is_empty() {
+        return (vec![bounds], vec![]);
+    }
+    let edges = |axis: usize| {
+        let mut edges = regions
+            .iter()
+            .flat_map(|region| [region.mins[axis], region.maxs[axis]])
+            .chain([bounds.mins[axis], bounds.maxs[axis]])
+            .collect::<Vec<f64>>();
+        edges.sort_by(f64::total_cmp);
+        edges.dedup();
+        edges
+    };
+    let (xs, ys) = (edges(0), edges(1));
+
+    let (mut outside, mut inside) = (vec![], vec![]);
+    for y in ys.windows(2) {
+        let mut push_cell = |x0: f64, x1: f64, in_region: bool| {
+            let cell = Aabb::new(na::point![x0, y[0]], na::point![x1, y[1]]);
+            if in_region {
+                inside.push(cell);
+            } else {
+                outside.push(cell);
+            }
+        };
+        // the start and whether the cells of the current run are inside a region
+        let mut run: Option<(f64, bool)> = None;
+        for x in xs.windows(2) {
+            let center = na::point![(x[0] + x[1]) * 0.5, (y[0] + y[1]) * 0.5];
+            let in_region = regions.iter().any(|region| {
+                (region.mins[0]..=region.maxs[0]).contains(&center[0])
+                    && (region.mins[1]..=region.maxs[1]).contains(&center[1])
+            });
+            match run {
+                Some((_, run_in_region)) if run_in_region == in_region => {}
+                Some((start, run_in_region)) => {
+                    push_cell(start, x[0], run_in_region);
+                    run = Some((x[0], in_region));
+                }
+                None => run = Some((x[0], in_region)),
+            }
+        }
+        if let Some((start, run_in_region)) = run {
+            push_cell(start, bounds.maxs[0], run_in_region);
+        }
+    }
+    (outside, inside)
+}
+
+/// Caches the rendering of the strokes in fixed-size tiles on the surface.
+///
+/// Panning only renders the tiles that became visible, and changing strokes only rerenders the tiles they intersect.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TileCache {
+    /// The image scale the tiles are rendered with.
+    image_scale: f64,
+    tiles: HashMap<TileIndex, Tile>,
+    /// Tile renderings of a previous image scale.
+    ///
+    /// Displayed while the tiles for the current image scale are being rendered.
+    #[cfg(feature = "ui")]
+    stale_rendernodes: Vec<gtk4::gsk::RenderNode>,
+    /// The display lists of the strokes rendered in the tiles, together with the stroke they were recorded from.
+    ///
+    /// Tiles are rerendered from them when the image scale changes, which keeps the lines crisp
+    /// while avoiding to compose the strokes again.
+    display_lists: HashMap<StrokeKey, (Arc<Stroke>, Arc<DisplayList>)>,
+}
+
+impl TileCache {
+    /// Whether tiles were rendered for an image scale.
+    fn is_active(&self) -> bool {
+        self.image_scale > 0.0
+    }
+
+    fn tile_extents(&self) -> f64 {
+        TILE_SIZE / self.image_scale
+    }
+
+    fn tile_bounds(&self, index: TileIndex) -> Aabb {
+        let extents = self.tile_extents();
+        let mins = na::point![index.0 as f64 * extents, index.1 as f64 * extents];
+        Aabb::new(mins, mins + na::Vector2::repeat(extents))
+    }
+
+    fn tile_indices_intersecting_bounds(&self, bounds: Aabb) -> impl Iterator<Item = TileIndex> {
+        let extents = self.tile_extents();
+        let x_range =
+            (bounds.mins[0] / extents).floor() as i64..(bounds.maxs[0] / extents).ceil() as i64;
+        let y_range =
+            (bounds.mins[1] / extents).floor() as i64..(bounds.maxs[1] / extents).ceil() as i64;
+        y_range.flat_map(move |y| x_range.clone().map(move |x| (x, y)))
+    }
+
+    /// Switch the tile grid to the new image scale, keeping the current renderings as stale.
+    fn set_image_scale(&mut self, image_scale: f64) {
+        #[cfg(feature = "ui")]
+        self.stale_rendernodes.extend(
+            self.tiles
+                .drain()
+                .filter_map(|(_, tile)| tile.rendering?.rendernode),
+        );
+        #[cfg(not(feature = "ui"))]
+        self.tiles.clear();
+        self.image_scale = image_scale;
+    }
+
+    /// The display list of the stroke, if it was recorded from the stroke in its current state.
+    fn display_list(&self, key: StrokeKey, stroke: &Arc<Stroke>) -> Option<Arc<DisplayList>> {
+        self.display_lists
+            .get(&key)
+            .filter(|(recorded, _)| Arc::ptr_eq(recorded, stroke))
+            .map(|(_, display_list)| Arc::clone(display_list))
+    }
+
+    /// Whether the stroke is rendered in its current state by all tiles in the given bounds.
+    ///
+    /// Tiles without rendering count as covering, because the stroke will be included when they are rendered.
+    /// Strokes in outdated regions of a rendering are not covered, because the rendering is not drawn there.
+    fn covers_stroke(&self, key: StrokeKey, stroke: &Arc<Stroke>, bounds: Aabb) -> bool {
+        self.tile_indices_intersecting_bounds(bounds).all(|index| {
+            self.tiles
+                .get(&index)
+                .is_none_or(|tile| tile.covers(key, stroke, bounds))
+        })
+    }
+
+    /// The margin of the outdated regions, one pixel to include the antialiasing.
+    fn outdated_regions_margin(&self) -> f64 {
+        1.0 / self.image_scale
+    }
+}
+
+impl StrokeStore {
+    /// Whether the strokes are rendered in tiles, which is the case for the [RenderBackend::Cairo] backend.
+    pub(crate) fn tiles_enabled(&self) -> bool {
+        self.render_backend == RenderBackend::Cairo
+    }
+
+    /// The strokes intersecting the bounds, in the order that they should be rendered.
+    fn strokes_as_rendered_intersecting_bounds(
+        &self,
+        bounds: Aabb,
+    ) -> Vec<(StrokeKey, Arc<Stroke>)> {
+        self.stroke_keys_as_rendered_intersecting_bounds(bounds)
+            .into_iter()
+            .filter_map(|key| Some((key, Arc::clone(self.stroke_components.get(key)?))))
+            .collect()
+    }
+
+    /// The strokes of the tile in the order that they should be rendered.
+    ///
+    /// Taken from the tile when it exists, which holds them since it was last regenerated.
+    fn tile_strokes<'a>(
+        &self,
+        tile: Option<&'a Tile>,
+        bounds: Aabb,
+    ) -> std::borrow::Cow<'a, [(StrokeKey, Arc<Stroke>)]> {
+        match tile {
+            Some(tile) => std::borrow::Cow::Borrowed(&tile.strokes),
+            None => std::borrow::Cow::Owned(self.strokes_as_rendered_intersecting_bounds(bounds)),
+        }
+    }
+
+    /// Whether the stroke does not need to be rendered individually, because it is rendered in the tiles
+    /// in the given bounds.
+    pub(crate) fn stroke_covered_by_tiles(&self, key: StrokeKey, bounds: Aabb) -> bool {
+        let Some(stroke) = self.stroke_components.get(key) else {
+            return false;
+        };
+        self.tiles_enabled()
+            && self.tile_cache.is_active()
+            && self.tile_cache.covers_stroke(key, stroke, bounds)
+    }
+
+    /// Regenerate the tiles in the viewport whose strokes have changed, and drop the tiles outside of it.
+    ///
+    /// Cheap when nothing has changed.
+    pub(crate) fn regenerate_tiles_in_viewport_threaded(
+        &mut self,
+        tasks_tx: EngineTaskSender,
+        viewport: Aabb,
+        image_scale: f64,
+    ) {
+        // always take the changed regions, so that they don't accumulate while tiles are disabled
+        let mut dirty_regions = self.key_tree.take_dirty_regions();
+        if !self.tiles_enabled() {
+            return;
+        }
+        if (self.tile_cache.image_scale - image_scale).abs() > RENDER_IMAGE_SCALE_TOLERANCE {
+            self.tile_cache.set_image_scale(image_scale);
+            // drop the display lists of strokes that have changed or were removed
+            self.tile_cache.display_lists.retain(|key, (recorded, _)| {
+                self.stroke_components
+                    .get(*key)
+                    .is_some_and(|stroke| Arc::ptr_eq(stroke, recorded))
+            });
+            dirty_regions = DirtyRegions::All;
+        }
+        let image_scale = self.tile_cache.image_scale;
+        let in_motion = self.in_motion;
+        let render_quality = self.render_quality;
+        // lower the resolution while the camera is moving, regenerated in full once it stops
+        let gen_image_scale = if in_motion {
+            image_scale * RenderQuality::IN_MOTION_IMAGE_SCALE_FACTOR
+        } else {
+            image_scale
+        };
+        let viewport_extended = self.prerender_margin.extend_viewport(viewport);
+        let indices = self
+            .tile_cache
+            .tile_indices_intersecting_bounds(viewport_extended)
+            .collect::<HashSet<TileIndex>>();
+        self.tile_cache
+            .tiles
+            .retain(|index, _| indices.contains(index));
+
+        for index in indices {
+            let bounds = self.tile_cache.tile_bounds(index);
+            // only tiles that intersect a changed region need to be validated against the strokes
+            if self
+                .tile_cache
+                .tiles
+                .get(&index)
+                .is_some_and(|tile| tile.state != TileState::Dirty)
+                && !dirty_regions.intersects(&bounds)
+            {
+                tasks_tx.render_counters().record_cache_access(true);
+                continue;
+            }
+            let strokes = self.strokes_as_rendered_intersecting_bounds(bounds);
+            let margin = self.tile_cache.outdated_regions_margin();
+            let tile = self.tile_cache.tiles.entry(index).or_default();
+            tile.strokes = strokes.clone();
+            tile.update_outdated_regions(bounds, margin);
+
+            match tile.state {
+                TileState::BusyRenderingInTask => continue,
+                TileState::Complete
+                    if tile
+                        .rendering
+                        .as_ref()
+                        .is_some_and(|rendering| rendering.renders(&strokes)) =>
+                {
+                    tasks_tx.render_counters().record_cache_access(true);
+                    continue;
+                }
+                TileState::Complete | TileState::Dirty => {}
+            }
+
+            // indicates that a task has now started to render the tile
+            tile.state = TileState::BusyRenderingInTask;
+            tile.reduced_quality = in_motion;
+            tasks_tx.render_counters().record_cache_access(false);
+            let tasks_tx = tasks_tx.clone();
+            let display_lists = strokes
+                .iter()
+                .map(|(key, stroke)| self.tile_cache.display_list(*key, stroke))
+                .collect::<Vec<Option<Arc<DisplayList>>>>();
+
+            rendering::spawn_render(move || {
+                let mut recorded_display_lists = vec![];
+                let image = if strokes.is_empty() {
+                    None
+                } else {
+                    match tasks_tx.render_counters().time_raster(|| {
+                        Image::gen_with_cairo(
+                            |cairo_cx| {
+                                let mut piet_cx = piet_cairo::CairoRenderContext::new(cairo_cx);
+                                for ((key, stroke), display_list) in
+                                    strokes.iter().zip(display_lists)
+                                {
+                                    // Piet draws with the operator of the underlying cairo context
+                                    cairo_cx.set_operator(stroke.blend_mode().cairo_operator());
+                                    if image_scale < LEVEL_OF_DETAIL_IMAGE_SCALE_THRESHOLD {
+                                        stroke.draw_level_of_detail(&mut piet_cx, image_scale)?;
+                                        continue;
+                                    }
+                                    match display_list.or_else(|| {
+                                        let display_list = Arc::new(DisplayList::record(stroke)?);
+                                        recorded_display_lists
+                                            .push((*key, Arc::clone(&display_list)));
+                                        Some(display_list)
+                                    }) {
+                                        Some(display_list) => display_list.draw(&mut piet_cx),
+                                        None => stroke.draw(&mut piet_cx, image_scale)?,
+                                    }
+                                }
+                                piet_cx.finish().map_err(|e| {
+                                    anyhow::anyhow!("finishing piet context failed, Err: {e:?}")
+                                })
+                            },
+                            bounds,
+                            gen_image_scale,
+                            render_quality,
+                        )
+                    }) {
+                        Ok(image) => Some(image),
+                        Err(e) => {
+                            error!("Generating image of tile {index:?} failed, Err: {e:?}");
+                            return;
+                        }
+                    }
+                };
+                tasks_tx.send(EngineTask::UpdateTileWithImage {
+                    index,
+                    image,
+                    strokes,
+                    display_lists: recorded_display_lists,
+                    image_scale,
+                });
+            });
+        }
+
+        // The stale renderings are not needed anymore once all tiles in the viewport are rendered
+        #[cfg(feature = "ui")]
+        if !self.tile_cache.stale_rendernodes.is_empty()
+            && self
+                .tile_cache
+                .tile_indices_intersecting_bounds(viewport)
+                .all(|index| {
+                    self.tile_cache
+                        .tiles
+                        .get(&index)
+                        .is_some_and(|tile| tile.rendering.is_some())
+                })
+        {
+            self.tile_cache.stale_rendernodes.clear();
+        }
+    }
+
+    /// Replace the rendering of the tile with the generated image.
+    ///
+    /// Discarded when the tile grid has changed in the meantime.
+    pub(crate) fn replace_tile_rendering(
+        &mut self,
+        index: TileIndex,
+        image: Option<Image>,
+        strokes: Vec<(StrokeKey, Arc<Stroke>)>,
+        display_lists: Vec<(StrokeKey, Arc<DisplayList>)>,
+        image_scale: f64,
+    ) {
+        // the display lists stay valid independent of the image scale
+        for (key, display_list) in display_lists {
+            if let Some((_, stroke)) = strokes.iter().find(|(stroke_key, _)| *stroke_key == key) {
+                self.tile_cache
+                    .display_lists
+                    .insert(key, (Arc::clone(stroke), display_list));
+            }
+        }
+        if (self.tile_cache.image_scale - image_scale).abs() > RENDER_IMAGE_SCALE_TOLERANCE {
+            return;
+        }
+        let bounds = self.tile_cache.tile_bounds(index);
+        let margin = self.tile_cache.outdated_regions_margin();
+        // the strokes might have changed while the tile was rendered in the task,
+        // with the changed regions already taken
+        let outdated = !strokes_render_equal(
+            &strokes,
+            &self.strokes_as_rendered_intersecting_bounds(bounds),
+        );
+        let Some(tile) = self.tile_cache.tiles.get_mut(&index) else {
+            return;
+        };
+        if tile.state != TileState::BusyRenderingInTask {
+            return;
+        }
+
+        #[cfg(feature = "ui")]
+        let rendernode = match image
+            .as_ref()
+            .map(|image| image.to_rendernode())
+            .transpose()
+        {
+            Ok(rendernode) => rendernode.map(|rendernode| {
+                use crate::ext::GrapheneRectExt;
+                use gtk4::{graphene, gsk, prelude::*};
+
+                // the image bounds are slightly loosened, so clip them to the tile to not overlap with neighbours
+                gsk::ClipNode::new(&rendernode, &graphene::Rect::from_p2d_aabb(bounds)).upcast()
+            }),
+            Err(e) => {
+                error!("Generating rendernode for tile {index:?} failed, Err: {e:?}");
+                tile.state = TileState::Dirty;
+                return;
+            }
+        };
+
+        tile.rendering = Some(TileRendering {
+            order: strokes.iter().map(|(key, _)| *key).collect(),
+            strokes: strokes.into_iter().collect(),
+            image,
+            #[cfg(feature = "ui")]
+            rendernode,
+        });
+        tile.update_outdated_regions(bounds, margin);
+        tile.state = if outdated {
+            TileState::Dirty
+        } else {
+            TileState::Complete
+        };
+    }
+
+    /// Draw the rendered content in the bounds, for example to sample it for raster effects.
+    ///
+    /// Takes the tile renderings where they are up to date, and draws the strokes of the other tiles directly.
+    pub(crate) fn draw_content_from_tiles(
+        &self,
+        cx: &mut impl piet::RenderContext,
+        bounds: Aabb,
+        image_scale: f64,
+    ) -> anyhow::Result<()> {
+        if !self.tiles_enabled() || !self.tile_cache.is_active() {
+            for (_, stroke) in self.strokes_as_rendered_intersecting_bounds(bounds) {
+                stroke.draw(cx, image_scale)?;
+            }
+            return Ok(());
+        }
+
+        for index in self.tile_cache.tile_indices_intersecting_bounds(bounds) {
+            let tile_bounds = self.tile_cache.tile_bounds(index);
+            let tile = self.tile_cache.tiles.get(&index);
+            let strokes = self.tile_strokes(tile, tile_bounds);
+            let rendering = tile
+                .and_then(|tile| tile.rendering.as_ref())
+                .filter(|rendering| rendering.renders(&strokes));
+
+            cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+            cx.clip(tile_bounds.to_kurbo_rect());
+            match rendering {
+                Some(rendering) => {
+                    if let Some(image) = &rendering.image {
+                        image.draw(cx, image_scale)?;
+                    }
+                }
+                None => {
+                    for (_, stroke) in strokes.iter() {
+                        stroke.draw(cx, image_scale)?;
+                    }
+                }
+            }
+            cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// The images held by the tiles.
+    pub(crate) fn tile_images(&self) -> impl Iterator<Item = &Image> {
+        self.tile_cache
+            .tiles
+            .values()
+            .filter_map(|tile| tile.rendering.as_ref()?.image.as_ref())
+    }
+
+    /// The amount of tiles that hold a rendering.
+    pub(crate) fn n_rendered_tiles(&self) -> usize {
+        self.tile_images().count()
+    }
+
+    /// The amount of cached stroke display lists.
+    pub(crate) fn n_display_lists(&self) -> usize {
+        self.tile_cache.display_lists.len()
+    }
+
+    /// Flag the tiles that were rendered with a lowered resolution while the camera was moving dirty.
+    pub(crate) fn set_reduced_quality_tiles_dirty(&mut self) {
+        for tile in self
+            .tile_cache
+            .tiles
+            .values_mut()
+            .filter(|tile| tile.reduced_quality)
+        {
+            tile.state = TileState::Dirty;
+            tile.reduced_quality = false;
+        }
+    }
+
+    /// Clear the rendering of all tiles.
+    pub(crate) fn clear_tiles(&mut self) {
+        self.tile_cache = TileCache::default();
+    }
+
+    /// Draw the tiles in the viewport on the gtk snapshot.
+    ///
+    /// Strokes that changed since their tile was rendered are drawn individually on top. In the regions where the
+    /// rendering shows strokes that have changed or were removed since, it is left out and all strokes are drawn
+    /// individually, so that no outdated content shows through.
+    ///
+    /// Returns false when no tiles were rendered yet, the strokes then need to be drawn individually.
+    #[cfg(feature = "ui")]
+    pub(crate) fn draw_tiles_to_gtk_snapshot(
+        &self,
+        snapshot: &gtk4::Snapshot,
+        viewport: Aabb,
+    ) -> bool {
+        use crate::ext::GrapheneRectExt;
+        use gtk4::{graphene, prelude::*};
+
+        if !self.tiles_enabled() || !self.tile_cache.is_active() {
+            return false;
+        }
+
+        for rendernode in self.tile_cache.stale_rendernodes.iter() {
+            snapshot.append_node(rendernode);
+        }
+        let stale_present = !self.tile_cache.stale_rendernodes.is_empty();
+
+        for index in self.tile_cache.tile_indices_intersecting_bounds(viewport) {
+            let bounds = self.tile_cache.tile_bounds(index);
+            let tile = self.tile_cache.tiles.get(&index);
+            let strokes = self.tile_strokes(tile, bounds);
+            let Some((tile, rendering)) =
+                tile.and_then(|tile| Some((tile, tile.rendering.as_ref()?)))
+            else {
+                snapshot.push_clip(&graphene::Rect::from_p2d_aabb(bounds));
+                for (key, _) in strokes.iter() {
+                    // Placeholders would cover the stale renderings
+                    self.draw_stroke_rendering_to_gtk_snapshot(snapshot, *key, !stale_present);
+                }
+                snapshot.pop();
+                continue;
+            };
+            if rendering.renders(&strokes) {
+                if let Some(rendernode) = &rendering.rendernode {
+                    snapshot.append_node(rendernode);
+                }
+                continue;
+            }
+
+            let (up_to_date_cells, outdated_cells) =
+                split_by_regions(bounds, &tile.outdated_regions);
+            for cell in up_to_date_cells {
+                snapshot.push_clip(&graphene::Rect::from_p2d_aabb(cell));
+                if let Some(rendernode) = &rendering.rendernode {
+                    snapshot.append_node(rendernode);
+                }
+                for (key, stroke) in strokes.iter() {
+                    if !rendering.contains(*key, stroke) {
+                        self.draw_stroke_rendering_to_gtk_snapshot(snapshot, *key, true);
+                    }
+                }
+                snapshot.pop();
+            }
+            for cell in outdated_cells {
+                snapshot.push_clip(&graphene::Rect::from_p2d_aabb(cell));
+                for (key, stroke) in strokes.iter() {
+                    if stroke.bounds().intersects(&cell) {
+                        self.draw_stroke_rendering_to_gtk_snapshot(snapshot, *key, true);
+                    }
+                }
+                snapshot.pop();
+            }
+        }
+
+        true
+    }
+
+    /// Draw the bounds of the tile images for visual debugging purposes.
+    #[cfg(feature = "ui")]
+    pub(crate) fn draw_tiles_debug_to_gtk_snapshot(
+        &self,
+        snapshot: &gtk4::Snapshot,
+        border_widths: f64,
+    ) {
+        use crate::engine::visual_debug;
+        use rnote_compose::shapes::Shapeable;
+
+        for tile in self.tile_cache.tiles.values() {
+            if let Some(image) = tile
+                .rendering
+                .as_ref()
+                .and_then(|rendering| rendering.image.as_ref())
+            {
+                visual_debug::draw_bounds_to_gtk_snapshot(
+                    image.rect.bounds(),
+                    visual_debug::COLOR_IMAGE_BOUNDS,
+                    snapshot,
+                    border_widths,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strokes::BrushStroke;
+    use rnote_compose::Style;
+    use rnote_compose::penpath::Element;
+
+    fn aabb(mins: [f64; 2], maxs: [f64; 2]) -> Aabb {
+        Aabb::new(na::point![mins[0], mins[1]], na::point![maxs[0], maxs[1]])
+    }
+
+    fn brushstroke(pos: na::Vector2<f64>) -> Arc<Stroke> {
+        Arc::new(Stroke::BrushStroke(BrushStroke::new(
+            Element::new(pos, 0.5),
+            Style::default(),
+        )))
+    }
+
+    #[test]
+    fn split_bounds_by_regions() {
+        let bounds = aabb([0.0, 0.0], [10.0, 10.0]);
+        assert_eq!(split_by_regions(bounds, &[]), (vec![bounds], vec![]));
+
+        let regions = [
+            aabb([2.0, 3.0], [4.0, 5.0]),
+            aabb([3.0, 4.0], [6.0, 6.0]),
+            // outside of the bounds
+            aabb([20.0, 20.0], [30.0, 30.0]),
+        ];
+        let (outside, inside) = split_by_regions(bounds, &regions);
+        let area = |cells: &[Aabb]| {
+            cells
+                .iter()
+                .map(|cell| cell.extents().product())
+                .sum::<f64>()
+        };
+        // the overlapping regions cover 4 + 6 - 1
+        approx::assert_relative_eq!(area(&inside), 9.0);
+        approx::assert_relative_eq!(area(&outside), 91.0);
+        // together with the areas, this leaves the cells of the overlapping regions inside
+        for cell in outside.iter() {
+            assert!(regions.iter().all(|region| {
+                region
+                    .intersection(cell)
+                    .is_none_or(|intersection| intersection.extents().product() == 0.0)
+            }));
+        }
+    }
+
+    #[test]
+    fn outdated_regions() {
+        let mut keys = slotmap::SlotMap::<StrokeKey, ()>::with_key();
+        let (unchanged_key, changed_key, removed_key) =
+            (keys.insert(()), keys.insert(()), keys.insert(()));
+        let unchanged = brushstroke(na::vector![10.0, 10.0]);
+        let changed = brushstroke(na::vector![50.0, 50.0]);
+        let removed = brushstroke(na::vector![90.0, 90.0]);
+        let bounds = aabb([0.0, 0.0], [100.0, 100.0]);
+
+        let mut tile = Tile {
+            state: TileState::Complete,
+            rendering: Some(TileRendering {
+                order: vec![unchanged_key, changed_key, removed_key],
+                strokes: HashMap::from([
+                    (unchanged_key, Arc::clone(&unchanged)),
+                    (changed_key, Arc::clone(&changed)),
+                    (removed_key, Arc::clone(&removed)),
+                ]),
+                image: None,
+                #[cfg(feature = "ui")]
+                rendernode: None,
+            }),
+            ..Default::default()
+        };
+        tile.strokes = vec![
+            (unchanged_key, Arc::clone(&unchanged)),
+            (changed_key, Arc::new(changed.as_ref().clone())),
+        ];
+        tile.update_outdated_regions(bounds, 0.0);
+
+        assert_eq!(tile.outdated_regions.len(), 2);
+        for stroke in [&changed, &removed] {
+            assert!(
+                tile.outdated_regions
+                    .iter()
+                    .any(|region| *region == stroke.bounds())
+            );
+        }
+        assert!(tile.covers(unchanged_key, &unchanged, unchanged.bounds()));
+        assert!(!tile.covers(changed_key, &tile.strokes[1].1, changed.bounds()));
+        // unchanged strokes in the outdated regions need to be drawn individually
+        assert!(!tile.covers(unchanged_key, &unchanged, changed.bounds()));
+
+        // once rendered again the rendering is up to date
+        tile.rendering = Some(TileRendering {
+            order: tile.strokes.iter().map(|(key, _)| *key).collect(),
+            strokes: tile.strokes.iter().cloned().collect(),
+            image: None,
+            #[cfg(feature = "ui")]
+            rendernode: None,
+        });
+        tile.update_outdated_regions(bounds, 0.0);
+        assert!(tile.outdated_regions.is_empty());
+    }
+}
@@ -85,14 +85,19 @@ impl StrokeStore {
     ) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
 
-        self.stroke_keys_as_rendered_intersecting_bounds(viewport)
+        self.filter_unlocked(self.stroke_keys_as_rendered_intersecting_bounds(viewport))
             .into_iter()
             .for_each(|key| {
                 let mut trash_current_stroke = false;
 
                 if let Some(stroke) = self.stroke_components.get(key) {
                     match stroke.as_ref() {
-                        Stroke::BrushStroke(_) | Stroke::ShapeStroke(_) => {
+                        Stroke::BrushStroke(_)
+                        | Stroke::ShapeStroke(_)
+                        | Stroke::SprayStroke(_)
+                        | Stroke::StampStroke(_)
+                        | Stroke::TapeStroke(_)
+                        | Stroke::SmudgeStroke(_) => {
                             // First check if eraser even intersects stroke bounds, avoiding unnecessary work
                             if eraser_bounds.intersects(&stroke.bounds()) {
                                 for hitbox in stroke.hitboxes().into_iter() {
@@ -105,8 +110,10 @@ impl StrokeStore {
                             }
                         }
                         // Ignore other strokes when trashing with the Eraser
-                        Stroke::TextStroke(_) | Stroke::VectorImage(_) | Stroke::BitmapImage(_) => {
-                        }
+                        Stroke::TextStroke(_)
+                        | Stroke::VectorImage(_)
+                        | Stroke::BitmapImage(_)
+                        | Stroke::Opaque(_) => {}
                     }
                 }
 
@@ -135,7 +142,7 @@ impl StrokeStore {
         let mut modified_keys = vec![];
 
         let new_strokes = self
-            .stroke_keys_as_rendered_intersecting_bounds(viewport)
+            .filter_unlocked(self.stroke_keys_as_rendered_intersecting_bounds(viewport))
             .into_iter()
             .flat_map(|key| {
                 let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
@@ -186,12 +193,15 @@ impl StrokeStore {
                                     let mut next_split_iter = next_split.into_iter();
                                     let next_start = next_split_iter.next().unwrap().end();
 
+                                    let mut new_brushstroke = BrushStroke::from_penpath(
+                                        PenPath::new_w_segments(next_start, next_split_iter),
+                                        brushstroke.style.clone(),
+                                    );
+                                    new_brushstroke.blend_mode = brushstroke.blend_mode;
                                     new_strokes.push((
-                                        Stroke::BrushStroke(BrushStroke::from_penpath(
-                                            PenPath::new_w_segments(next_start, next_split_iter),
-                                            brushstroke.style.clone(),
-                                        )),
+                                        Stroke::BrushStroke(new_brushstroke),
                                         chrono_comp.layer,
+                                        key,
                                     ));
                                 }
 
@@ -211,7 +221,11 @@ impl StrokeStore {
                             }
                         }
                     }
-                    Stroke::ShapeStroke(_) => {
+                    Stroke::ShapeStroke(_)
+                    | Stroke::SprayStroke(_)
+                    | Stroke::StampStroke(_)
+                    | Stroke::TapeStroke(_)
+                    | Stroke::SmudgeStroke(_) => {
                         if eraser_bounds.intersects(&stroke_bounds) {
                             for hitbox_elem in stroke.hitboxes().iter() {
                                 if eraser_bounds.intersects(hitbox_elem) {
@@ -222,7 +236,10 @@ impl StrokeStore {
                         }
                     }
                     // Ignore other strokes when trashing with the Eraser
-                    Stroke::TextStroke(_) | Stroke::VectorImage(_) | Stroke::BitmapImage(_) => {}
+                    Stroke::TextStroke(_)
+                    | Stroke::VectorImage(_)
+                    | Stroke::BitmapImage(_)
+                    | Stroke::Opaque(_) => {}
                 }
 
                 if trash_current_stroke {
@@ -233,12 +250,135 @@ impl StrokeStore {
 
                 new_strokes
             })
-            .collect::<Vec<(Stroke, StrokeLayer)>>();
+            .collect::<Vec<(Stroke, StrokeLayer, StrokeKey)>>();
+
+        modified_keys.append(
+            &mut new_strokes
+                .into_iter()
+                .map(|(new_stroke, layer, split_from)| {
+                    let new_key = self.insert_stroke(new_stroke, Some(layer));
+                    // The split parts stay in the groups of the original stroke
+                    self.copy_groups(split_from, new_key);
+                    new_key
+                })
+                .collect(),
+        );
+
+        if !modified_keys.is_empty() {
+            widget_flags.store_modified = true;
+            widget_flags.resize = true;
+        }
+
+        (modified_keys, widget_flags)
+    }
+
+    /// Erase precisely along the path of the eraser from `from` to `to`, which has the given radius.
+    ///
+    /// Brush strokes are cut exactly where their outline meets the eraser and split into the remaining parts,
+    /// which keep the style and the pressure of the original stroke. Other strokes that collide with the eraser
+    /// are trashed completely.
+    ///
+    /// Returns the keys of all created or modified strokes.
+    ///
+    /// The returned strokes need to update their rendering.
+    pub(crate) fn erase_precisely(
+        &mut self,
+        from: na::Vector2<f64>,
+        to: na::Vector2<f64>,
+        radius: f64,
+        viewport: Aabb,
+    ) -> (Vec<StrokeKey>, WidgetFlags) {
+        let mut widget_flags = WidgetFlags::default();
+        let mut modified_keys = vec![];
+        let eraser_bounds = Aabb::new_positive(from.into(), to.into()).loosened(radius);
+
+        let new_strokes = self
+            .filter_unlocked(self.stroke_keys_as_rendered_intersecting_bounds(viewport))
+            .into_iter()
+            .flat_map(|key| {
+                let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
+                    .get_mut(key)
+                    .map(Arc::make_mut)
+                else {
+                    return vec![];
+                };
+
+                let Some(chrono_comp) = self.chrono_components.get(key) else {
+                    return vec![];
+                };
+
+                let mut new_strokes = vec![];
+                let mut trash_current_stroke = false;
+                if !eraser_bounds.intersects(&stroke.bounds()) {
+                    return new_strokes;
+                }
+
+                match stroke {
+                    Stroke::BrushStroke(brushstroke) => {
+                        let Some(remaining) = brushstroke.path.erase_along(
+                            from,
+                            to,
+                            radius + brushstroke.style.stroke_width() * 0.5,
+                        ) else {
+                            return new_strokes;
+                        };
+                        let mut remaining = remaining.into_iter();
+
+                        // The original stroke keeps the first remaining part
+                        match remaining.next() {
+                            Some(first) => brushstroke.replace_path(first),
+                            None => trash_current_stroke = true,
+                        }
+                        for path in remaining {
+                            let mut new_brushstroke =
+                                BrushStroke::from_penpath(path, brushstroke.style.clone());
+                            new_brushstroke.blend_mode = brushstroke.blend_mode;
+                            new_strokes.push((
+                                Stroke::BrushStroke(new_brushstroke),
+                                chrono_comp.layer,
+                                key,
+                            ));
+                        }
+                        modified_keys.push(key);
+                    }
+                    Stroke::ShapeStroke(_)
+                    | Stroke::SprayStroke(_)
+                    | Stroke::StampStroke(_)
+                    | Stroke::TapeStroke(_)
+                    | Stroke::SmudgeStroke(_) => {
+                        if stroke
+                            .hitboxes()
+                            .iter()
+                            .any(|hitbox| eraser_bounds.intersects(hitbox))
+                        {
+                            trash_current_stroke = true;
+                            modified_keys.push(key);
+                        }
+                    }
+                    // Ignore other strokes when trashing with the Eraser
+                    Stroke::TextStroke(_)
+                    | Stroke::VectorImage(_)
+                    | Stroke::BitmapImage(_)
+                    | Stroke::Opaque(_) => {}
+                }
+
+                if trash_current_stroke {
+                    self.set_trashed(key, true);
+                }
+
+                new_strokes
+            })
+            .collect::<Vec<(Stroke, StrokeLayer, StrokeKey)>>();
 
         modified_keys.append(
             &mut new_strokes
                 .into_iter()
-                .map(|(new_stroke, layer)| self.insert_stroke(new_stroke, Some(layer)))
+                .map(|(new_stroke, layer, split_from)| {
+                    let new_key = self.insert_stroke(new_stroke, Some(layer));
+                    // The split parts stay in the groups of the original stroke
+                    self.copy_groups(split_from, new_key);
+                    new_key
+                })
                 .collect(),
         );
 
@@ -16,7 +16,6 @@ use rnote_compose::shapes::Shapeable;
 use rnote_compose::transform::Transform;
 use rnote_compose::transform::Transformable;
 use serde::{Deserialize, Serialize};
-use std::ops::Range;
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +29,9 @@ pub struct BitmapImage {
     pub image: Image,
     #[serde(rename = "rectangle")]
     pub rectangle: Rectangle,
+    /// The URL the image was imported from, if it was fetched remotely.
+    #[serde(rename = "source_url")]
+    pub source_url: Option<String>,
 }
 
 impl Default for BitmapImage {
@@ -37,6 +39,7 @@ impl Default for BitmapImage {
         Self {
             image: Image::default(),
             rectangle: Rectangle::default(),
+            source_url: None,
         }
     }
 }
@@ -124,14 +127,18 @@ impl BitmapImage {
             cuboid: p2d::shape::Cuboid::new(size * 0.5),
             transform,
         };
-        Ok(Self { image, rectangle })
+        Ok(Self {
+            image,
+            rectangle,
+            source_url: None,
+        })
     }
 
     pub fn from_pdf_bytes(
         to_be_read: &[u8],
         pdf_import_prefs: PdfImportPrefs,
         insert_pos: na::Vector2<f64>,
-        page_range: Option<Range<usize>>,
+        page_indices: Option<Vec<usize>>,
         format: &Format,
         password: Option<String>,
     ) -> Result<Vec<Self>, anyhow::Error> {
@@ -146,7 +153,7 @@ impl BitmapImage {
         };
         let interpreter_settings = hayro_interpret::InterpreterSettings::default();
         let pages = pdf.pages();
-        let page_range = page_range.unwrap_or(0..pages.len());
+        let page_indices = page_indices.unwrap_or_else(|| (0..pages.len()).collect());
         let page_width = if pdf_import_prefs.adjust_document {
             format.width()
         } else {
@@ -163,7 +170,8 @@ impl BitmapImage {
         let mut y = insert_pos[1];
 
         // TODO: investigate if this can be parallelized with rayon's `par_iter()`
-        let pngs = page_range
+        let pngs = page_indices
+            .into_iter()
             .map(|page_i| {
                 let page = pages
                     .get(page_i)
@@ -3,15 +3,20 @@ use super::Content;
 use super::content::GeneratedContentImages;
 use crate::Drawable;
 use crate::Image;
+use crate::engine::rendering::RenderQuality;
 use crate::strokes::content;
 use p2d::bounding_volume::{Aabb, BoundingVolume};
-use rnote_compose::ext::AabbExt;
+use piet::RenderContext;
+use rnote_compose::ext::{AabbExt, Vector2Ext};
 use rnote_compose::penpath::{Element, Segment};
 use rnote_compose::shapes::Shapeable;
 use rnote_compose::style::Composer;
-use rnote_compose::transform::Transformable;
+use rnote_compose::style::PressureCurve;
+use rnote_compose::style::smooth::{LineStyle, PenTip, SmoothOptions};
+use rnote_compose::transform::{Projective, Transformable, Warpable};
 use rnote_compose::{PenPath, Style};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use tracing::error;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,9 +26,94 @@ pub struct BrushStroke {
     pub path: PenPath,
     #[serde(default, rename = "style")]
     pub style: Style,
+    /// How the stroke is blended with the content underneath it.
+    #[serde(default, rename = "blend_mode")]
+    pub blend_mode: BlendMode,
     // since the path can have many hitboxes, we store them here and update them when the stroke geometry changes
     #[serde(skip)]
     hitboxes: Vec<Aabb>,
+    // composing the outlines of the smooth style is expensive, so they are cached for repeated draws
+    #[serde(skip)]
+    composed_outlines: Option<ComposedOutlines>,
+}
+
+/// How a stroke is blended with the content underneath it.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "blend_mode")]
+pub enum BlendMode {
+    /// Drawn over the content underneath.
+    #[default]
+    #[serde(rename = "normal")]
+    Normal = 0,
+    /// Multiplied with the content underneath, so that dark content stays dark.
+    #[serde(rename = "multiply")]
+    Multiply,
+    /// The darker color of the stroke and the content underneath is used.
+    #[serde(rename = "darken")]
+    Darken,
+}
+
+impl TryFrom<u32> for BlendMode {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!("BlendMode try_from::<u32>() for value {} failed", value)
+        })
+    }
+}
+
+impl BlendMode {
+    pub fn cairo_operator(self) -> cairo::Operator {
+        match self {
+            Self::Normal => cairo::Operator::Over,
+            Self::Multiply => cairo::Operator::Multiply,
+            Self::Darken => cairo::Operator::Darken,
+        }
+    }
+
+    #[cfg(feature = "ui")]
+    pub fn gsk_blend_mode(self) -> gtk4::gsk::BlendMode {
+        match self {
+            Self::Normal => gtk4::gsk::BlendMode::Default,
+            Self::Multiply => gtk4::gsk::BlendMode::Multiply,
+            Self::Darken => gtk4::gsk::BlendMode::Darken,
+        }
+    }
+}
+
+/// The cached outlines of a brush stroke composed with the smooth style.
+#[derive(Debug, Clone)]
+struct ComposedOutlines {
+    /// The stroke width the outlines were composed with.
+    stroke_width: f64,
+    /// The pressure curve the outlines were composed with.
+    pressure_curve: PressureCurve,
+    /// The minimum width ratio the outlines were composed with.
+    pressure_width_min_ratio: f64,
+    /// The pen tip the outlines were composed with.
+    pen_tip: PenTip,
+    outlines: Vec<kurbo::BezPath>,
+}
+
+impl ComposedOutlines {
+    fn matches(&self, options: &SmoothOptions) -> bool {
+        self.stroke_width == options.stroke_width
+            && self.pressure_curve == options.pressure_curve
+            && self.pressure_width_min_ratio == options.pressure_width_min_ratio
+            && self.pen_tip == options.pen_tip
+    }
 }
 
 impl Content for BrushStroke {
@@ -31,6 +121,7 @@ impl Content for BrushStroke {
         &self,
         viewport: Aabb,
         image_scale: f64,
+        render_quality: RenderQuality,
     ) -> Result<GeneratedContentImages, anyhow::Error> {
         /// The threshold of the image size on either axis.
         /// When below it the stroke is rendered as a single image
@@ -63,11 +154,12 @@ impl Content for BrushStroke {
                 Style::Smooth(options) => {
                     let image = Image::gen_with_piet(
                         |piet_cx| {
-                            self.path.draw_composed(piet_cx, options);
+                            self.draw_smooth(piet_cx, options);
                             Ok(())
                         },
                         bounds,
                         image_scale,
+                        render_quality,
                     );
 
                     match image {
@@ -90,6 +182,7 @@ impl Content for BrushStroke {
                         },
                         bounds,
                         image_scale,
+                        render_quality,
                     );
 
                     match image {
@@ -105,17 +198,30 @@ impl Content for BrushStroke {
             match &self.style {
                 Style::Smooth(options) => {
                     let mut images = Vec::with_capacity(self.path.segments.len());
+                    // The dash pattern continues across the segment images
+                    let dashed = options.line_style != LineStyle::Solid;
+                    let segment_lengths = if dashed {
+                        self.path.segment_lengths()
+                    } else {
+                        vec![]
+                    };
+                    let mut dash_offset = 0.0;
 
                     let mut prev = self.path.start;
-                    for seg in self.path.segments.iter() {
+                    for (i, seg) in self.path.segments.iter().enumerate() {
                         let seg_path = PenPath::new_w_segments(prev, [*seg]);
                         let image = Image::gen_with_piet(
                             |piet_cx| {
-                                seg_path.draw_composed(piet_cx, options);
+                                if dashed {
+                                    seg_path.draw_dashed(piet_cx, options, dash_offset);
+                                } else {
+                                    seg_path.draw_composed(piet_cx, options);
+                                }
                                 Ok(())
                             },
                             seg_path.composed_bounds(options),
                             image_scale,
+                            render_quality,
                         );
                         match image {
                             Ok(image) => images.push(image),
@@ -124,6 +230,7 @@ impl Content for BrushStroke {
                             }
                         }
 
+                        dash_offset += segment_lengths.get(i).copied().unwrap_or(0.0);
                         prev = seg.end();
                     }
 
@@ -147,6 +254,7 @@ impl Content for BrushStroke {
                             },
                             seg_path.composed_bounds(&options),
                             image_scale,
+                            render_quality,
                         );
                         match image {
                             Ok(image) => images.push(image),
@@ -200,6 +308,7 @@ impl Content for BrushStroke {
 
     fn update_geometry(&mut self) {
         self.hitboxes = self.gen_hitboxes_int();
+        self.composed_outlines = self.gen_composed_outlines_int();
     }
 }
 
@@ -208,7 +317,7 @@ impl Drawable for BrushStroke {
         cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
 
         match &self.style {
-            Style::Smooth(options) => self.path.draw_composed(cx, options),
+            Style::Smooth(options) => self.draw_smooth(cx, options),
             Style::Rough(_) => {
                 // Rough style currently unsupported for pen paths
                 unimplemented!()
@@ -219,6 +328,16 @@ impl Drawable for BrushStroke {
         cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
         Ok(())
     }
+
+    fn draw_to_cairo(&self, cx: &cairo::Context, image_scale: f64) -> anyhow::Result<()> {
+        cx.save()?;
+        cx.set_operator(self.blend_mode.cairo_operator());
+        let mut piet_cx = piet_cairo::CairoRenderContext::new(cx);
+        self.draw(&mut piet_cx, image_scale)?;
+        piet_cx.finish().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        cx.restore()?;
+        Ok(())
+    }
 }
 
 impl Shapeable for BrushStroke {
@@ -242,12 +361,15 @@ impl Shapeable for BrushStroke {
 impl Transformable for BrushStroke {
     fn translate(&mut self, offset: na::Vector2<f64>) {
         self.path.translate(offset);
+        self.composed_outlines = None;
     }
     fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
         self.path.rotate(angle, center);
+        self.composed_outlines = None;
     }
     fn scale(&mut self, scale: na::Vector2<f64>) {
         self.path.scale(scale);
+        self.composed_outlines = None;
         // Using the geometric mean behaves the best when scaling non-uniformly.
         let scale_scalar = (scale[0] * scale[1]).sqrt();
         self.style
@@ -255,6 +377,13 @@ impl Transformable for BrushStroke {
     }
 }
 
+impl Warpable for BrushStroke {
+    fn warp(&mut self, projective: &Projective) {
+        self.path.warp(projective);
+        self.composed_outlines = None;
+    }
+}
+
 impl BrushStroke {
     pub fn new(start: Element, style: Style) -> Self {
         let path = PenPath::new(start);
@@ -267,7 +396,9 @@ impl BrushStroke {
         let mut new_brushstroke = Self {
             path,
             style,
+            blend_mode: BlendMode::default(),
             hitboxes: vec![],
+            composed_outlines: None,
         };
         new_brushstroke.update_geometry();
 
@@ -276,10 +407,22 @@ impl BrushStroke {
 
     pub fn push_segment(&mut self, segment: Segment) {
         self.path.segments.push(segment);
+        self.composed_outlines = None;
     }
 
     pub fn extend_w_segments(&mut self, segments: impl IntoIterator<Item = Segment>) {
         self.path.extend(segments);
+        self.composed_outlines = None;
+    }
+
+    /// Extend with segments captured at the given time, in seconds since the stroke was started.
+    pub fn extend_w_timed_segments(
+        &mut self,
+        segments: impl IntoIterator<Item = Segment>,
+        time: f64,
+    ) {
+        self.path.extend_timed(segments, time);
+        self.composed_outlines = None;
     }
 
     /// Replace the current path with the given new one. the new path must not be empty.
@@ -299,10 +442,99 @@ impl BrushStroke {
             .collect()
     }
 
+    // internal method composing the outlines of the smooth style.
+    fn gen_composed_outlines_int(&self) -> Option<ComposedOutlines> {
+        match &self.style {
+            Style::Smooth(options) => Some(ComposedOutlines {
+                stroke_width: options.stroke_width,
+                pressure_curve: options.pressure_curve,
+                pressure_width_min_ratio: options.pressure_width_min_ratio,
+                pen_tip: options.pen_tip,
+                outlines: self.path.composed_outlines(options),
+            }),
+            Style::Rough(_) | Style::Textured(_) => None,
+        }
+    }
+
+    /// The outlines of the path composed with the given smooth options.
+    ///
+    /// Borrowed from the cache when it is up to date.
+    pub(crate) fn smooth_composed_outlines(
+        &self,
+        options: &SmoothOptions,
+    ) -> Cow<'_, [kurbo::BezPath]> {
+        match &self.composed_outlines {
+            Some(composed) if composed.matches(options) => Cow::Borrowed(&composed.outlines),
+            _ => Cow::Owned(self.path.composed_outlines(options)),
+        }
+    }
+
+    fn draw_smooth(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
+        let Some(color) = options.stroke_color else {
+            return;
+        };
+        if options.line_style != LineStyle::Solid {
+            self.path.draw_dashed(cx, options, 0.0);
+            return;
+        }
+        let colors = self
+            .path
+            .composed_outline_colors(color, options.stroke_gradient.as_ref());
+
+        for (bez_path, color) in self.smooth_composed_outlines(options).iter().zip(colors) {
+            cx.fill(bez_path, &Into::<piet::Color>::into(color));
+        }
+    }
+
+    /// Draw a simplified representation of the stroke, for when the details would not be visible anyway.
+    ///
+    /// The path is decimated to positions that are at least a pixel apart and drawn with a constant width.
+    /// Strokes smaller than a pixel are drawn as their filled bounds.
+    pub(crate) fn draw_level_of_detail(
+        &self,
+        cx: &mut impl piet::RenderContext,
+        image_scale: f64,
+    ) -> anyhow::Result<()> {
+        let Some(color) = self.style.stroke_color() else {
+            return Ok(());
+        };
+        let color = Into::<piet::Color>::into(color);
+        let bounds = self.bounds();
+
+        if bounds.extents().max() * image_scale < 1.0 {
+            cx.fill(bounds.to_kurbo_rect(), &color);
+            return Ok(());
+        }
+
+        let min_dist = 1.0 / image_scale;
+        let mut prev = self.path.start.pos;
+        let mut bez_path = kurbo::BezPath::new();
+        bez_path.move_to(prev.to_kurbo_point());
+        for (i, seg) in self.path.segments.iter().enumerate() {
+            let pos = seg.end().pos;
+            // always include the last position
+            if (pos - prev).magnitude() >= min_dist || i == self.path.segments.len() - 1 {
+                bez_path.line_to(pos.to_kurbo_point());
+                prev = pos;
+            }
+        }
+
+        cx.stroke_styled(
+            bez_path,
+            &color,
+            self.style.stroke_width(),
+            &piet::StrokeStyle::new()
+                .line_join(piet::LineJoin::Round)
+                .line_cap(piet::LineCap::Round),
+        );
+        Ok(())
+    }
+
     pub fn gen_image_for_last_segments(
         &self,
         n_last_segments: usize,
         image_scale: f64,
+        render_quality: RenderQuality,
     ) -> Result<Option<Image>, anyhow::Error> {
         let image = match &self.style {
             Style::Smooth(options) => {
@@ -322,14 +554,32 @@ impl BrushStroke {
                         .copied(),
                 );
 
-                let image = Image::gen_with_piet(
-                    |piet_cx| {
-                        range_path.draw_composed(piet_cx, options);
-                        Ok(())
-                    },
-                    range_path.composed_bounds(options),
-                    image_scale,
-                )?;
+                let image = if options.line_style != LineStyle::Solid {
+                    // Continue the dash pattern from the segments before the range
+                    let dash_offset = self.path.segment_lengths()
+                        [..path_len.saturating_sub(n_last_segments)]
+                        .iter()
+                        .sum::<f64>();
+                    Image::gen_with_piet(
+                        |piet_cx| {
+                            range_path.draw_dashed(piet_cx, options, dash_offset);
+                            Ok(())
+                        },
+                        range_path.composed_bounds(options),
+                        image_scale,
+                        render_quality,
+                    )?
+                } else {
+                    Image::gen_with_piet(
+                        |piet_cx| {
+                            range_path.draw_composed(piet_cx, options);
+                            Ok(())
+                        },
+                        range_path.composed_bounds(options),
+                        image_scale,
+                        render_quality,
+                    )?
+                };
 
                 Some(image)
             }
@@ -363,6 +613,7 @@ impl BrushStroke {
                     },
                     range_path.composed_bounds(&options),
                     image_scale,
+                    render_quality,
                 )?;
 
                 Some(image)
@@ -1,5 +1,6 @@
 // Imports
 use crate::Image;
+use crate::engine::rendering::RenderQuality;
 use crate::{Drawable, Svg};
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::ext::AabbExt;
@@ -34,11 +35,12 @@ where
     /// Generate bitmap images for rendering in the app.
     ///
     /// A larger `image_scale` value renders them in a higher than native resolution (usually set as the camera zoom).
-    /// The bounds are not scaled by it.
+    /// The bounds are not scaled by it. The images are generated with the given render quality.
     fn gen_images(
         &self,
         viewport: Aabb,
         image_scale: f64,
+        render_quality: RenderQuality,
     ) -> Result<GeneratedContentImages, anyhow::Error> {
         let bounds = self.bounds();
 
@@ -47,6 +49,7 @@ where
                 |piet_cx| self.draw(piet_cx, image_scale),
                 bounds,
                 image_scale,
+                render_quality,
             )?]))
         } else if let Some(intersection_bounds) = viewport.intersection(&bounds) {
             Ok(GeneratedContentImages::Partial {
@@ -54,6 +57,7 @@ where
                     |piet_cx| self.draw(piet_cx, image_scale),
                     intersection_bounds,
                     image_scale,
+                    render_quality,
                 )?],
                 viewport,
             })
@@ -110,6 +114,7 @@ where
             |piet_cx| self.draw(piet_cx, image_scale),
             self.bounds(),
             image_scale,
+            RenderQuality::FULL,
         )?
         .into_encoded_bytes(format, None)
     }
@@ -2,18 +2,28 @@
 pub mod bitmapimage;
 pub mod brushstroke;
 pub mod content;
+pub mod opaquestroke;
 pub mod resize;
 pub mod shapestroke;
+pub mod smudgestroke;
+pub mod spraystroke;
+pub mod stampstroke;
 pub mod stroke;
+pub mod tapestroke;
 pub mod textstroke;
 pub mod vectorimage;
 
 // Re-exports
 pub use bitmapimage::BitmapImage;
-pub use brushstroke::BrushStroke;
+pub use brushstroke::{BlendMode, BrushStroke};
 pub use content::Content;
+pub use opaquestroke::OpaqueStroke;
 pub use resize::Resize;
 pub use shapestroke::ShapeStroke;
+pub use smudgestroke::{SmudgeMode, SmudgeOptions, SmudgeStroke};
+pub use spraystroke::{SprayOptions, SprayStroke};
+pub use stampstroke::{StampOptions, StampStroke};
 pub use stroke::Stroke;
+pub use tapestroke::{TapeOptions, TapeStroke};
 pub use textstroke::TextStroke;
 pub use vectorimage::VectorImage;
@@ -0,0 +1,205 @@
+// Imports
+use super::{Content, Stroke};
+use crate::Drawable;
+use kurbo::Shape;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use rnote_compose::Color;
+use rnote_compose::ext::AabbExt;
+use rnote_compose::shapes::Shapeable;
+use rnote_compose::transform::Transformable;
+use serde::{Deserialize, Serialize};
+
+/// A stroke that is unknown to this version, usually originating from a file saved by a newer version.
+///
+/// Either the stroke type is unknown or a stroke of a known type has fields that are unknown.
+/// The data is kept as is, so that it is written back unchanged when saving. It is rendered as a placeholder, or as
+/// the stroke of the known type without the unknown fields. It can't be transformed and is therefore always locked.
+#[derive(Debug, Clone)]
+pub struct OpaqueStroke {
+    /// The raw stroke data, a object with the stroke type as the single key.
+    data: serde_json::Value,
+    /// The estimated bounds of the placeholder.
+    bounds: Aabb,
+    /// The stroke loaded without the unknown fields, when the stroke type is known.
+    preview: Option<Box<Stroke>>,
+}
+
+impl Serialize for OpaqueStroke {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.data.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for OpaqueStroke {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let data = serde_json::Value::deserialize(deserializer)?;
+        match data.as_object() {
+            Some(object) if object.len() == 1 => {}
+            _ => {
+                return Err(serde::de::Error::custom(
+                    "opaque stroke data is not an object with a single stroke type key",
+                ));
+            }
+        }
+        let bounds = Self::estimate_bounds(&data);
+        Ok(Self {
+            data,
+            bounds,
+            preview: None,
+        })
+    }
+}
+
+impl Content for OpaqueStroke {
+    fn update_geometry(&mut self) {}
+}
+
+impl Drawable for OpaqueStroke {
+    fn draw(&self, cx: &mut impl piet::RenderContext, image_scale: f64) -> anyhow::Result<()> {
+        if let Some(preview) = &self.preview {
+            return preview.draw(cx, image_scale);
+        }
+        const OUTLINE_WIDTH: f64 = 1.5;
+        const OUTLINE_COLOR: Color = Color {
+            r: 0.5,
+            g: 0.5,
+            b: 0.5,
+            a: 0.8,
+        };
+        const FILL_COLOR: Color = Color {
+            r: 0.5,
+            g: 0.5,
+            b: 0.5,
+            a: 0.1,
+        };
+
+        let rect = self.bounds.loosened(-OUTLINE_WIDTH * 0.5).to_kurbo_rect();
+        let stroke_style = piet::StrokeStyle::new().dash_pattern(&[6.0, 4.0]);
+
+        cx.fill(rect, &piet::Color::from(FILL_COLOR));
+        cx.stroke_styled(
+            rect,
+            &piet::Color::from(OUTLINE_COLOR),
+            OUTLINE_WIDTH,
+            &stroke_style,
+        );
+        cx.stroke_styled(
+            kurbo::Line::new((rect.x0, rect.y0), (rect.x1, rect.y1)),
+            &piet::Color::from(OUTLINE_COLOR),
+            OUTLINE_WIDTH,
+            &stroke_style,
+        );
+        cx.stroke_styled(
+            kurbo::Line::new((rect.x1, rect.y0), (rect.x0, rect.y1)),
+            &piet::Color::from(OUTLINE_COLOR),
+            OUTLINE_WIDTH,
+            &stroke_style,
+        );
+        Ok(())
+    }
+}
+
+impl Shapeable for OpaqueStroke {
+    fn bounds(&self) -> Aabb {
+        self.bounds
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        match &self.preview {
+            Some(preview) => preview.hitboxes(),
+            None => vec![self.bounds],
+        }
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        match &self.preview {
+            Some(preview) => preview.outline_path(),
+            None => self.bounds.to_kurbo_rect().to_path(0.25),
+        }
+    }
+}
+
+// The raw data can't be transformed, so transformations are ignored to not get out of sync with the placeholder.
+impl Transformable for OpaqueStroke {
+    fn translate(&mut self, _offset: na::Vector2<f64>) {}
+
+    fn rotate(&mut self, _angle: f64, _center: na::Point2<f64>) {}
+
+    fn scale(&mut self, _scale: na::Vector2<f64>) {}
+}
+
+impl OpaqueStroke {
+    /// The size of the placeholder when no coordinates could be found in the data.
+    const FALLBACK_SIZE: f64 = 64.0;
+    /// The maximum nesting depth that is searched for coordinates.
+    const MAX_DEPTH: usize = 64;
+
+    /// Keeps the data of a stroke of a known type that has unknown fields, the loaded stroke is used as the preview.
+    pub(crate) fn new_w_preview(data: serde_json::Value, preview: Stroke) -> Self {
+        Self {
+            data,
+            bounds: preview.bounds(),
+            preview: Some(Box::new(preview)),
+        }
+    }
+
+    /// The stroke type, as stored in the file.
+    pub fn stroke_type(&self) -> &str {
+        self.data
+            .as_object()
+            .and_then(|object| object.keys().next())
+            .map(|key| key.as_str())
+            .unwrap_or_default()
+    }
+
+    /// Estimates the bounds by collecting all arrays of two numbers in the data, which is how coordinates are
+    /// stored in the file format.
+    fn estimate_bounds(data: &serde_json::Value) -> Aabb {
+        fn collect(value: &serde_json::Value, depth: usize, bounds: &mut Option<Aabb>) {
+            if depth > OpaqueStroke::MAX_DEPTH {
+                return;
+            }
+            match value {
+                serde_json::Value::Array(array) => {
+                    if let [x, y] = array.as_slice()
+                        && let (Some(x), Some(y)) = (x.as_f64(), y.as_f64())
+                        && x.is_finite()
+                        && y.is_finite()
+                    {
+                        let point = na::point![x, y];
+                        match bounds {
+                            Some(bounds) => bounds.take_point(point),
+                            None => *bounds = Some(Aabb::new(point, point)),
+                        }
+                        return;
+                    }
+                    for value in array {
+                        collect(value, depth + 1, bounds);
+                    }
+                }
+                serde_json::Value::Object(object) => {
+                    for value in object.values() {
+                        collect(value, depth + 1, bounds);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut bounds = None;
+        collect(data, 0, &mut bounds);
+        match bounds {
+            Some(bounds) => bounds.loosened(Self::FALLBACK_SIZE * 0.125),
+            None => Aabb::new(
+                na::point![0.0, 0.0],
+                na::point![Self::FALLBACK_SIZE, Self::FALLBACK_SIZE],
+            ),
+        }
+    }
+}
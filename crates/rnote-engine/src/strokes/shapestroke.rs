@@ -7,7 +7,7 @@ use rnote_compose::ext::AabbExt;
 use rnote_compose::shapes::Shape;
 use rnote_compose::shapes::Shapeable;
 use rnote_compose::style::Composer;
-use rnote_compose::transform::Transformable;
+use rnote_compose::transform::{Projective, Transformable, Warpable};
 use serde::{Deserialize, Serialize};
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
@@ -100,6 +100,12 @@ impl Transformable for ShapeStroke {
     }
 }
 
+impl Warpable for ShapeStroke {
+    fn warp(&mut self, projective: &Projective) {
+        self.shape.warp(projective);
+    }
+}
+
 impl ShapeStroke {
     pub fn new(shape: Shape, style: Style) -> Self {
         let mut shapestroke = Self {
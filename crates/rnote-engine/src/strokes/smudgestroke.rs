@@ -0,0 +1,300 @@
+// Imports
+use super::Content;
+use crate::engine::rendering::RenderQuality;
+use crate::image::ImageMemoryFormat;
+use crate::{Drawable, Image};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use rnote_compose::shapes::Shapeable;
+use rnote_compose::transform::Transformable;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "smudge_mode")]
+pub enum SmudgeMode {
+    /// Softens the content underneath.
+    #[default]
+    #[serde(rename = "blur")]
+    Blur = 0,
+    /// Drags the content underneath along with the pen.
+    #[serde(rename = "smear")]
+    Smear,
+}
+
+impl TryFrom<u32> for SmudgeMode {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!("SmudgeMode try_from::<u32>() for value {} failed", value)
+        })
+    }
+}
+
+/// The options of smudge strokes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "smudge_options")]
+pub struct SmudgeOptions {
+    #[serde(rename = "mode")]
+    pub mode: SmudgeMode,
+    /// The radius of the smudged area around the pen.
+    #[serde(rename = "radius", with = "rnote_compose::serialize::f64_dp3")]
+    pub radius: f64,
+    /// How strongly the content is smudged, between 0.0 and 1.0.
+    #[serde(rename = "strength", with = "rnote_compose::serialize::f64_dp3")]
+    pub strength: f64,
+}
+
+impl Default for SmudgeOptions {
+    fn default() -> Self {
+        Self {
+            mode: SmudgeMode::default(),
+            radius: 16.0,
+            strength: 0.5,
+        }
+    }
+}
+
+impl SmudgeOptions {
+    pub const RADIUS_MIN: f64 = 2.0;
+    pub const RADIUS_MAX: f64 = 100.0;
+    pub const STRENGTH_MIN: f64 = 0.05;
+    pub const STRENGTH_MAX: f64 = 1.0;
+}
+
+/// A raster effect that blurs or smears the content underneath it.
+///
+/// The effect is applied to the rendered content when the stroke is created. Only its result is stored,
+/// so it stays the same when the content underneath changes and survives saving and reloading the document.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "smudgestroke")]
+pub struct SmudgeStroke {
+    #[serde(rename = "options")]
+    pub options: SmudgeOptions,
+    /// The smudged content, drawn over the content underneath.
+    #[serde(rename = "image")]
+    pub image: Image,
+}
+
+impl Content for SmudgeStroke {
+    fn update_geometry(&mut self) {}
+}
+
+impl Drawable for SmudgeStroke {
+    fn draw(&self, cx: &mut impl piet::RenderContext, image_scale: f64) -> anyhow::Result<()> {
+        self.image.draw(cx, image_scale)
+    }
+}
+
+impl Shapeable for SmudgeStroke {
+    fn bounds(&self) -> Aabb {
+        self.image.rect.bounds()
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        vec![self.bounds()]
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        self.image.rect.outline_path()
+    }
+}
+
+impl Transformable for SmudgeStroke {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.image.translate(offset);
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        self.image.rotate(angle, center);
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.image.scale(scale);
+    }
+}
+
+impl SmudgeStroke {
+    /// The radius of the blur relative to the smudge radius.
+    const BLUR_RADIUS_FACTOR: f64 = 0.25;
+
+    /// Composite the dabs into a single smudge stroke.
+    ///
+    /// Returns None when there are no dabs.
+    pub fn from_dabs(
+        dabs: &[Image],
+        options: SmudgeOptions,
+        image_scale: f64,
+    ) -> anyhow::Result<Option<Self>> {
+        let Some(bounds) = dabs
+            .iter()
+            .map(|dab| dab.rect.bounds())
+            .reduce(|acc, bounds| acc.merged(&bounds))
+        else {
+            return Ok(None);
+        };
+        let image = Image::gen_with_piet(
+            |piet_cx| {
+                for dab in dabs {
+                    dab.draw(piet_cx, image_scale)?;
+                }
+                Ok(())
+            },
+            bounds,
+            image_scale,
+            RenderQuality::FULL,
+        )?;
+
+        Ok(Some(Self { options, image }))
+    }
+
+    /// The bounds of the content that needs to be sampled for a dab at the position.
+    ///
+    /// Includes the area the content is smeared from and the surroundings the blur reaches into.
+    pub fn dab_sample_bounds(
+        pos: na::Vector2<f64>,
+        offset: na::Vector2<f64>,
+        options: &SmudgeOptions,
+    ) -> Aabb {
+        let radius = options.radius * (1.0 + Self::BLUR_RADIUS_FACTOR * 2.0) + offset.magnitude();
+        Aabb::from_half_extents(pos.into(), na::Vector2::repeat(radius))
+    }
+
+    /// Generate a dab at the position from the sampled content.
+    ///
+    /// The content is blurred or smeared by the offset the pen moved since the last dab, masked with a soft circle
+    /// and weighted by the strength. The returned image is meant to be drawn over the content.
+    pub fn gen_dab(
+        sample: &Image,
+        pos: na::Vector2<f64>,
+        offset: na::Vector2<f64>,
+        options: &SmudgeOptions,
+    ) -> anyhow::Result<Image> {
+        let width = sample.pixel_width as usize;
+        let height = sample.pixel_height as usize;
+        let sample_bounds = sample.rect.bounds();
+        let sample_extents = sample_bounds.extents();
+        if width == 0 || height == 0 || sample_extents[0] <= 0.0 || sample_extents[1] <= 0.0 {
+            return Err(anyhow::anyhow!(
+                "Generating smudge dab failed, sample is empty."
+            ));
+        }
+        // Pixels per document unit
+        let scale = na::vector![
+            width as f64 / sample_extents[0],
+            height as f64 / sample_extents[1]
+        ];
+        let center = (pos - sample_bounds.mins.coords).component_mul(&scale);
+        let radius = options.radius * scale[0];
+        let strength = options.strength.clamp(0.0, 1.0);
+
+        let data = sample.rgba8_premultiplied_data();
+        let source = match options.mode {
+            SmudgeMode::Blur => {
+                let blur_radius = (radius * Self::BLUR_RADIUS_FACTOR).round().max(1.0) as usize;
+                // Repeated box blurs approximate a gaussian blur
+                let blurred = box_blur(&data, width, height, blur_radius);
+                box_blur(&blurred, width, height, blur_radius)
+            }
+            SmudgeMode::Smear => {
+                let shift = offset.component_mul(&scale);
+                let (shift_x, shift_y) = (shift[0].round() as isize, shift[1].round() as isize);
+                let mut shifted = vec![0; data.len()];
+                for y in 0..height {
+                    for x in 0..width {
+                        let src_x = (x as isize - shift_x).clamp(0, width as isize - 1) as usize;
+                        let src_y = (y as isize - shift_y).clamp(0, height as isize - 1) as usize;
+                        let dst = (y * width + x) * 4;
+                        let src = (src_y * width + src_x) * 4;
+                        shifted[dst..dst + 4].copy_from_slice(&data[src..src + 4]);
+                    }
+                }
+                shifted
+            }
+        };
+
+        let mut dab = vec![0; data.len()];
+        for y in 0..height {
+            for x in 0..width {
+                let distance =
+                    (na::vector![x as f64 + 0.5, y as f64 + 0.5] - center).magnitude() / radius;
+                if distance >= 1.0 {
+                    continue;
+                }
+                // Smooth falloff towards the edge of the dab
+                let falloff = 1.0 - distance;
+                let weight = strength * falloff * falloff * (3.0 - 2.0 * falloff);
+                let i = (y * width + x) * 4;
+                for (dst, src) in dab[i..i + 4].iter_mut().zip(&source[i..i + 4]) {
+                    *dst = (*src as f64 * weight).round() as u8;
+                }
+            }
+        }
+
+        Ok(Image {
+            data: glib::Bytes::from_owned(dab),
+            rect: sample.rect,
+            pixel_width: sample.pixel_width,
+            pixel_height: sample.pixel_height,
+            memory_format: ImageMemoryFormat::R8g8b8a8Premultiplied,
+        })
+    }
+}
+
+/// Blur the rgba8 data with a box of the given radius, separately in horizontal and vertical direction.
+///
+/// Pixels outside of the data are treated as the closest pixel at the border.
+fn box_blur(data: &[u8], width: usize, height: usize, radius: usize) -> Vec<u8> {
+    let blur_pass = |src: &[u8], horizontal: bool| -> Vec<u8> {
+        let (len, lines) = if horizontal {
+            (width, height)
+        } else {
+            (height, width)
+        };
+        let index = |line: usize, pos: usize| {
+            if horizontal {
+                (line * width + pos) * 4
+            } else {
+                (pos * width + line) * 4
+            }
+        };
+        let window = (radius * 2 + 1) as u32;
+        let mut dst = vec![0; src.len()];
+
+        for line in 0..lines {
+            let mut sums = [0_u32; 4];
+            for offset in 0..window as usize {
+                let pos = (offset as isize - radius as isize).clamp(0, len as isize - 1) as usize;
+                let i = index(line, pos);
+                for (sum, value) in sums.iter_mut().zip(&src[i..i + 4]) {
+                    *sum += *value as u32;
+                }
+            }
+            for pos in 0..len {
+                let i = index(line, pos);
+                for (value, sum) in dst[i..i + 4].iter_mut().zip(&sums) {
+                    *value = ((sum + window / 2) / window) as u8;
+                }
+                let removed = index(line, pos.saturating_sub(radius));
+                let added = index(line, (pos + radius + 1).min(len - 1));
+                for (c, sum) in sums.iter_mut().enumerate() {
+                    *sum = *sum + src[added + c] as u32 - src[removed + c] as u32;
+                }
+            }
+        }
+        dst
+    };
+
+    let horizontal = blur_pass(data, true);
+    blur_pass(&horizontal, false)
+}
@@ -0,0 +1,209 @@
+// Imports
+use super::Content;
+use crate::Drawable;
+use kurbo::Shape;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use rand::Rng;
+use rnote_compose::Color;
+use rnote_compose::ext::AabbExt;
+use rnote_compose::penpath::Element;
+use rnote_compose::shapes::Shapeable;
+use rnote_compose::transform::Transformable;
+use serde::{Deserialize, Serialize};
+
+/// The options of sprayed strokes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "spray_options")]
+pub struct SprayOptions {
+    /// The diameter of the area the dabs are sprayed into.
+    #[serde(rename = "stroke_width", with = "rnote_compose::serialize::f64_dp3")]
+    pub stroke_width: f64,
+    /// The color of the dabs.
+    #[serde(rename = "stroke_color")]
+    pub stroke_color: Option<Color>,
+    /// The emissions per second while the pen is down.
+    #[serde(rename = "rate", with = "rnote_compose::serialize::f64_dp3")]
+    pub rate: f64,
+    /// The amount of dabs of an emission at full pressure.
+    #[serde(rename = "density", with = "rnote_compose::serialize::f64_dp3")]
+    pub density: f64,
+    /// How much the dabs gather towards the center. At 0.0 they are spread evenly over the area.
+    #[serde(rename = "falloff", with = "rnote_compose::serialize::f64_dp3")]
+    pub falloff: f64,
+    /// The seed the dabs of the emissions are generated from.
+    #[serde(rename = "seed")]
+    pub seed: u64,
+}
+
+impl Default for SprayOptions {
+    fn default() -> Self {
+        Self {
+            stroke_width: 40.0,
+            stroke_color: Some(Color::BLACK),
+            rate: 30.0,
+            density: 20.0,
+            falloff: 0.5,
+            seed: 0,
+        }
+    }
+}
+
+impl SprayOptions {
+    pub const RATE_MIN: f64 = 1.0;
+    pub const RATE_MAX: f64 = 120.0;
+    pub const DENSITY_MIN: f64 = 1.0;
+    pub const DENSITY_MAX: f64 = 200.0;
+    pub const FALLOFF_MIN: f64 = 0.0;
+    pub const FALLOFF_MAX: f64 = 2.0;
+
+    /// The time between two emissions.
+    pub fn emission_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs_f64(1.0 / self.rate.clamp(Self::RATE_MIN, Self::RATE_MAX))
+    }
+}
+
+/// A stroke of paint dabs, sprayed around the positions of the pen.
+///
+/// Only the emissions are stored, the dabs are generated from the seed of the options,
+/// so that they are the same every time the stroke is rendered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "spraystroke")]
+pub struct SprayStroke {
+    /// The positions and pressures of the pen at every emission.
+    #[serde(rename = "emissions")]
+    pub emissions: Vec<Element>,
+    #[serde(rename = "options")]
+    pub options: SprayOptions,
+}
+
+impl Content for SprayStroke {
+    fn update_geometry(&mut self) {}
+}
+
+impl Drawable for SprayStroke {
+    fn draw(&self, cx: &mut impl piet::RenderContext, _image_scale: f64) -> anyhow::Result<()> {
+        let Some(color) = self.options.stroke_color else {
+            return Ok(());
+        };
+        let color = Into::<piet::Color>::into(color);
+
+        // The dabs of an emission are filled together, so that they only add up with the dabs of other emissions
+        for i in 0..self.emissions.len() {
+            let mut bez_path = kurbo::BezPath::new();
+            for (pos, radius) in self.dabs_of_emission(i) {
+                bez_path.extend(kurbo::Circle::new((pos[0], pos[1]), radius).path_elements(0.1));
+            }
+            cx.fill(bez_path, &color);
+        }
+        Ok(())
+    }
+}
+
+impl Shapeable for SprayStroke {
+    fn bounds(&self) -> Aabb {
+        let radius = self.spray_radius() + self.dab_radius();
+        self.emissions
+            .iter()
+            .map(|emission| {
+                Aabb::from_half_extents(emission.pos.into(), na::Vector2::repeat(radius))
+            })
+            .fold(Aabb::new_invalid(), |acc, x| acc.merged(&x))
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        let radius = self.spray_radius();
+        self.emissions
+            .iter()
+            .map(|emission| {
+                Aabb::from_half_extents(emission.pos.into(), na::Vector2::repeat(radius))
+            })
+            .collect()
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        self.bounds().to_kurbo_rect().to_path(0.25)
+    }
+}
+
+impl Transformable for SprayStroke {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.emissions
+            .iter_mut()
+            .for_each(|emission| emission.translate(offset));
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        self.emissions
+            .iter_mut()
+            .for_each(|emission| emission.rotate(angle, center));
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.emissions
+            .iter_mut()
+            .for_each(|emission| emission.scale(scale));
+        // Using the geometric mean behaves the best when scaling non-uniformly.
+        self.options.stroke_width *= (scale[0] * scale[1]).sqrt();
+    }
+}
+
+impl SprayStroke {
+    /// The ratio between the diameter of the dabs and the stroke width.
+    const DAB_SIZE_RATIO: f64 = 0.03;
+    /// The minimum radius of the dabs.
+    const DAB_RADIUS_MIN: f64 = 0.3;
+
+    pub fn new(options: SprayOptions) -> Self {
+        Self {
+            emissions: vec![],
+            options,
+        }
+    }
+
+    /// Add an emission at the given element.
+    pub fn emit(&mut self, element: Element) {
+        self.emissions.push(element);
+    }
+
+    fn spray_radius(&self) -> f64 {
+        self.options.stroke_width * 0.5
+    }
+
+    fn dab_radius(&self) -> f64 {
+        (self.options.stroke_width * Self::DAB_SIZE_RATIO * 0.5).max(Self::DAB_RADIUS_MIN)
+    }
+
+    /// The positions and radii of the dabs of the emission at the given index.
+    ///
+    /// Every emission has its own seed derived from the seed of the options, so that the dabs stay the same when
+    /// emissions are added.
+    fn dabs_of_emission(&self, i: usize) -> impl Iterator<Item = (na::Vector2<f64>, f64)> {
+        let emission = self.emissions[i];
+        let mut rng = rnote_compose::utils::new_rng_default_pcg64(Some(
+            self.options.seed.wrapping_add(i as u64),
+        ));
+        let n_dabs = (self
+            .options
+            .density
+            .clamp(SprayOptions::DENSITY_MIN, SprayOptions::DENSITY_MAX)
+            * emission.pressure)
+            .round() as usize;
+        let spray_radius = self.spray_radius();
+        let dab_radius = self.dab_radius();
+        // Uniformly distributed over the area for an exponent of 0.5, gathering towards the center above
+        let exponent = 0.5
+            + self
+                .options
+                .falloff
+                .clamp(SprayOptions::FALLOFF_MIN, SprayOptions::FALLOFF_MAX);
+
+        (0..n_dabs).map(move |_| {
+            let angle = rng.random_range(0.0..std::f64::consts::TAU);
+            let distance = spray_radius * rng.random::<f64>().powf(exponent);
+            (
+                emission.pos + na::vector![angle.cos(), angle.sin()] * distance,
+                dab_radius,
+            )
+        })
+    }
+}
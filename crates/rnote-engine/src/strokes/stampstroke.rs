@@ -0,0 +1,291 @@
+// Imports
+use super::resize::ImageSizeOption;
+use super::{Content, VectorImage};
+use crate::Drawable;
+use crate::Svg;
+use crate::engine::rendering::RenderQuality;
+use crate::strokes::content::GeneratedContentImages;
+use kurbo::Shape;
+use p2d::bounding_volume::Aabb;
+use rnote_compose::Color;
+use rnote_compose::ext::AabbExt;
+use rnote_compose::shapes::{Rectangle, Shapeable};
+use rnote_compose::transform::Transformable;
+use serde::{Deserialize, Serialize};
+
+/// The vector content that is placed by the stamp brush.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "stamp")]
+pub enum Stamp {
+    #[serde(rename = "arrow")]
+    Arrow,
+    #[serde(rename = "star")]
+    Star,
+    #[serde(rename = "checkmark")]
+    Checkmark,
+    /// A custom Svg. It keeps its own colors.
+    #[serde(rename = "svg")]
+    Svg {
+        #[serde(rename = "name")]
+        name: String,
+        #[serde(rename = "svg_data")]
+        svg_data: String,
+        #[serde(
+            rename = "intrinsic_size",
+            with = "rnote_compose::serialize::na_vector2_f64_dp3"
+        )]
+        intrinsic_size: na::Vector2<f64>,
+    },
+}
+
+impl Stamp {
+    /// Load a custom stamp from a Svg string.
+    pub fn from_svg_str(name: String, svg_data: &str) -> anyhow::Result<Self> {
+        let vectorimage = VectorImage::from_svg_str(
+            svg_data,
+            na::Vector2::zeros(),
+            ImageSizeOption::RespectOriginalSize,
+        )?;
+        if vectorimage.intrinsic_size.min() <= 0.0 {
+            return Err(anyhow::anyhow!("Svg of stamp '{name}' has an empty size"));
+        }
+
+        Ok(Self::Svg {
+            name,
+            svg_data: vectorimage.svg_data,
+            intrinsic_size: vectorimage.intrinsic_size,
+        })
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Stamp::Arrow => String::from("Arrow"),
+            Stamp::Star => String::from("Star"),
+            Stamp::Checkmark => String::from("Checkmark"),
+            Stamp::Svg { name, .. } => name.clone(),
+        }
+    }
+
+    /// The half extents of the stamp when its larger side has the given size.
+    fn half_extents(&self, size: f64) -> na::Vector2<f64> {
+        match self {
+            Stamp::Arrow | Stamp::Star | Stamp::Checkmark => na::Vector2::repeat(size * 0.5),
+            Stamp::Svg { intrinsic_size, .. } => {
+                intrinsic_size * (size * 0.5 / intrinsic_size.max())
+            }
+        }
+    }
+
+    /// The path of the built-in stamps, inside the square from (-1.0, -1.0) to (1.0, 1.0).
+    ///
+    /// None for custom stamps.
+    fn unit_path(&self) -> Option<kurbo::BezPath> {
+        let points = match self {
+            Stamp::Arrow => vec![
+                (-1.0, -0.3),
+                (0.2, -0.3),
+                (0.2, -0.8),
+                (1.0, 0.0),
+                (0.2, 0.8),
+                (0.2, 0.3),
+                (-1.0, 0.3),
+            ],
+            Stamp::Star => (0..10)
+                .map(|i| {
+                    let angle =
+                        -std::f64::consts::FRAC_PI_2 + i as f64 * std::f64::consts::PI / 5.0;
+                    let radius = if i % 2 == 0 { 1.0 } else { 0.4 };
+                    (angle.cos() * radius, angle.sin() * radius)
+                })
+                .collect(),
+            Stamp::Checkmark => vec![
+                (-1.0, 0.1),
+                (-0.65, -0.25),
+                (-0.3, 0.1),
+                (0.65, -0.85),
+                (1.0, -0.5),
+                (-0.3, 0.8),
+            ],
+            Stamp::Svg { .. } => return None,
+        };
+
+        let mut path = kurbo::BezPath::new();
+        for (i, point) in points.into_iter().enumerate() {
+            if i == 0 {
+                path.move_to(point);
+            } else {
+                path.line_to(point);
+            }
+        }
+        path.close_path();
+        Some(path)
+    }
+}
+
+/// The options of stamped strokes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "stamp_options")]
+pub struct StampOptions {
+    /// The size of the larger side of the stamps.
+    #[serde(rename = "stroke_width", with = "rnote_compose::serialize::f64_dp3")]
+    pub stroke_width: f64,
+    /// The color of the built-in stamps.
+    #[serde(rename = "stroke_color")]
+    pub stroke_color: Option<Color>,
+    /// The index of the stamp in the stamp library.
+    #[serde(rename = "stamp_index")]
+    pub stamp_index: usize,
+}
+
+impl Default for StampOptions {
+    fn default() -> Self {
+        Self {
+            stroke_width: 24.0,
+            stroke_color: Some(Color::BLACK),
+            stamp_index: 0,
+        }
+    }
+}
+
+/// A stamp placed on the document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "stampstroke")]
+pub struct StampStroke {
+    #[serde(rename = "stamp")]
+    pub stamp: Stamp,
+    /// The color of built-in stamps.
+    #[serde(rename = "color")]
+    pub color: Option<Color>,
+    #[serde(rename = "rectangle")]
+    pub rectangle: Rectangle,
+}
+
+impl Content for StampStroke {
+    fn gen_svg(&self) -> Result<Svg, anyhow::Error> {
+        match self.vectorimage() {
+            Some(vectorimage) => vectorimage.gen_svg(),
+            None => {
+                let bounds = self.bounds();
+                Svg::gen_with_cairo(|cx| self.draw_to_cairo(cx, 1.0), bounds)
+            }
+        }
+    }
+
+    fn gen_images(
+        &self,
+        viewport: Aabb,
+        image_scale: f64,
+        render_quality: RenderQuality,
+    ) -> Result<GeneratedContentImages, anyhow::Error> {
+        match self.vectorimage() {
+            Some(vectorimage) => vectorimage.gen_images(viewport, image_scale, render_quality),
+            None => {
+                let bounds = self.bounds();
+                Ok(GeneratedContentImages::Full(vec![
+                    crate::Image::gen_with_piet(
+                        |piet_cx| self.draw(piet_cx, image_scale),
+                        bounds,
+                        image_scale,
+                        render_quality,
+                    )?,
+                ]))
+            }
+        }
+    }
+
+    fn update_geometry(&mut self) {}
+}
+
+impl Drawable for StampStroke {
+    fn draw(&self, cx: &mut impl piet::RenderContext, image_scale: f64) -> anyhow::Result<()> {
+        if let Some(vectorimage) = self.vectorimage() {
+            return vectorimage.draw(cx, image_scale);
+        }
+        let (Some(path), Some(color)) = (self.path(), self.color) else {
+            return Ok(());
+        };
+        cx.fill(path, &Into::<piet::Color>::into(color));
+        Ok(())
+    }
+
+    fn draw_to_cairo(&self, cx: &cairo::Context, image_scale: f64) -> anyhow::Result<()> {
+        match self.vectorimage() {
+            Some(vectorimage) => vectorimage.draw_to_cairo(cx, image_scale),
+            None => {
+                let mut piet_cx = piet_cairo::CairoRenderContext::new(cx);
+                self.draw(&mut piet_cx, image_scale)?;
+                piet_cx.finish().map_err(|e| anyhow::anyhow!("{e:?}"))
+            }
+        }
+    }
+}
+
+impl Shapeable for StampStroke {
+    fn bounds(&self) -> Aabb {
+        self.rectangle.bounds()
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        vec![self.bounds()]
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        self.path()
+            .unwrap_or_else(|| self.bounds().to_kurbo_rect().to_path(0.25))
+    }
+}
+
+impl Transformable for StampStroke {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.rectangle.translate(offset);
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        self.rectangle.rotate(angle, center);
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.rectangle.scale(scale);
+    }
+}
+
+impl StampStroke {
+    /// A new stamp centered at the position, with its larger side having the given size.
+    pub fn new(stamp: Stamp, pos: na::Vector2<f64>, size: f64, color: Option<Color>) -> Self {
+        let rectangle = Rectangle::from_half_extents(pos, stamp.half_extents(size));
+
+        Self {
+            stamp,
+            color,
+            rectangle,
+        }
+    }
+
+    /// The path of built-in stamps, transformed onto the document.
+    fn path(&self) -> Option<kurbo::BezPath> {
+        let mut path = self.stamp.unit_path()?;
+        let half_extents = self.rectangle.cuboid.half_extents;
+        path.apply_affine(
+            self.rectangle.transform.to_kurbo()
+                * kurbo::Affine::scale_non_uniform(half_extents[0], half_extents[1]),
+        );
+        Some(path)
+    }
+
+    /// Custom stamps are rendered as vector image with the same rectangle.
+    fn vectorimage(&self) -> Option<VectorImage> {
+        match &self.stamp {
+            Stamp::Svg {
+                svg_data,
+                intrinsic_size,
+                ..
+            } => Some(VectorImage {
+                svg_data: svg_data.clone(),
+                intrinsic_size: *intrinsic_size,
+                rectangle: self.rectangle,
+                source_url: None,
+            }),
+            _ => None,
+        }
+    }
+}
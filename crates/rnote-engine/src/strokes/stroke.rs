@@ -1,13 +1,19 @@
 // Imports
 use super::bitmapimage::BitmapImage;
-use super::brushstroke::BrushStroke;
+use super::brushstroke::{BlendMode, BrushStroke};
 use super::content::GeneratedContentImages;
+use super::opaquestroke::OpaqueStroke;
 use super::shapestroke::ShapeStroke;
+use super::smudgestroke::SmudgeStroke;
+use super::spraystroke::SprayStroke;
+use super::stampstroke::StampStroke;
+use super::tapestroke::TapeStroke;
 use super::vectorimage::VectorImage;
 use super::{Content, TextStroke};
 use crate::Engine;
 use crate::Image;
 use crate::Svg;
+use crate::engine::rendering::RenderQuality;
 use crate::fileformats::xoppformat::{self, XoppColor};
 use crate::store::chrono_comp::StrokeLayer;
 use crate::strokes::textstroke::TextStyle;
@@ -18,13 +24,14 @@ use rnote_compose::penpath::Element;
 use rnote_compose::shapes::{Rectangle, Shapeable};
 use rnote_compose::style::smooth::SmoothOptions;
 use rnote_compose::transform::Transform;
-use rnote_compose::transform::Transformable;
+use rnote_compose::transform::{Projective, Transformable, Warpable};
 use rnote_compose::{Color, PenPath, Style};
 use serde::{Deserialize, Serialize};
-use tracing::error;
+use tracing::{error, warn};
 
+// Serialization is implemented below, to load strokes of unknown types as opaque strokes
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename = "stroke")]
+#[serde(remote = "Self", rename = "stroke")]
 pub enum Stroke {
     #[serde(rename = "brushstroke")]
     BrushStroke(BrushStroke),
@@ -36,6 +43,53 @@ pub enum Stroke {
     VectorImage(VectorImage),
     #[serde(rename = "bitmapimage")]
     BitmapImage(BitmapImage),
+    #[serde(rename = "spraystroke")]
+    SprayStroke(SprayStroke),
+    #[serde(rename = "stampstroke")]
+    StampStroke(StampStroke),
+    #[serde(rename = "tapestroke")]
+    TapeStroke(TapeStroke),
+    #[serde(rename = "smudgestroke")]
+    SmudgeStroke(SmudgeStroke),
+    /// A stroke of an unknown type, preserved as is.
+    #[serde(skip)]
+    Opaque(OpaqueStroke),
+}
+
+impl Serialize for Stroke {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            // The raw data already contains the stroke type
+            Self::Opaque(opaque) => opaque.serialize(serializer),
+            _ => Self::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Stroke {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let data = serde_json::Value::deserialize(deserializer)?;
+        let known_type = data
+            .as_object()
+            .filter(|object| object.len() == 1)
+            .and_then(|object| object.keys().next())
+            .is_some_and(|stroke_type| Self::TYPES.contains(&stroke_type.as_str()));
+
+        if known_type {
+            // Errors of known strokes are not hidden by falling back to an opaque stroke
+            Self::deserialize(data).map_err(serde::de::Error::custom)
+        } else {
+            OpaqueStroke::deserialize(data)
+                .map(Self::Opaque)
+                .map_err(serde::de::Error::custom)
+        }
+    }
 }
 
 impl Content for Stroke {
@@ -46,6 +100,11 @@ impl Content for Stroke {
             Stroke::TextStroke(textstroke) => textstroke.gen_svg(),
             Stroke::VectorImage(vectorimage) => vectorimage.gen_svg(),
             Stroke::BitmapImage(bitmapimage) => bitmapimage.gen_svg(),
+            Stroke::SprayStroke(spraystroke) => spraystroke.gen_svg(),
+            Stroke::StampStroke(stampstroke) => stampstroke.gen_svg(),
+            Stroke::TapeStroke(tapestroke) => tapestroke.gen_svg(),
+            Stroke::SmudgeStroke(smudgestroke) => smudgestroke.gen_svg(),
+            Stroke::Opaque(opaque) => opaque.gen_svg(),
         }
     }
 
@@ -53,13 +112,37 @@ impl Content for Stroke {
         &self,
         viewport: Aabb,
         image_scale: f64,
+        render_quality: RenderQuality,
     ) -> Result<GeneratedContentImages, anyhow::Error> {
         match self {
-            Stroke::BrushStroke(brushstroke) => brushstroke.gen_images(viewport, image_scale),
-            Stroke::ShapeStroke(shapestroke) => shapestroke.gen_images(viewport, image_scale),
-            Stroke::TextStroke(textstroke) => textstroke.gen_images(viewport, image_scale),
-            Stroke::VectorImage(vectorimage) => vectorimage.gen_images(viewport, image_scale),
-            Stroke::BitmapImage(bitmapimage) => bitmapimage.gen_images(viewport, image_scale),
+            Stroke::BrushStroke(brushstroke) => {
+                brushstroke.gen_images(viewport, image_scale, render_quality)
+            }
+            Stroke::ShapeStroke(shapestroke) => {
+                shapestroke.gen_images(viewport, image_scale, render_quality)
+            }
+            Stroke::TextStroke(textstroke) => {
+                textstroke.gen_images(viewport, image_scale, render_quality)
+            }
+            Stroke::VectorImage(vectorimage) => {
+                vectorimage.gen_images(viewport, image_scale, render_quality)
+            }
+            Stroke::BitmapImage(bitmapimage) => {
+                bitmapimage.gen_images(viewport, image_scale, render_quality)
+            }
+            Stroke::SprayStroke(spraystroke) => {
+                spraystroke.gen_images(viewport, image_scale, render_quality)
+            }
+            Stroke::StampStroke(stampstroke) => {
+                stampstroke.gen_images(viewport, image_scale, render_quality)
+            }
+            Stroke::TapeStroke(tapestroke) => {
+                tapestroke.gen_images(viewport, image_scale, render_quality)
+            }
+            Stroke::SmudgeStroke(smudgestroke) => {
+                smudgestroke.gen_images(viewport, image_scale, render_quality)
+            }
+            Stroke::Opaque(opaque) => opaque.gen_images(viewport, image_scale, render_quality),
         }
     }
 
@@ -74,6 +157,11 @@ impl Content for Stroke {
             Stroke::TextStroke(textstroke) => textstroke.draw_highlight(cx, total_zoom),
             Stroke::VectorImage(vectorimage) => vectorimage.draw_highlight(cx, total_zoom),
             Stroke::BitmapImage(bitmapimage) => bitmapimage.draw_highlight(cx, total_zoom),
+            Stroke::SprayStroke(spraystroke) => spraystroke.draw_highlight(cx, total_zoom),
+            Stroke::StampStroke(stampstroke) => stampstroke.draw_highlight(cx, total_zoom),
+            Stroke::TapeStroke(tapestroke) => tapestroke.draw_highlight(cx, total_zoom),
+            Stroke::SmudgeStroke(smudgestroke) => smudgestroke.draw_highlight(cx, total_zoom),
+            Stroke::Opaque(opaque) => opaque.draw_highlight(cx, total_zoom),
         }
     }
 
@@ -84,6 +172,11 @@ impl Content for Stroke {
             Stroke::TextStroke(textstroke) => textstroke.update_geometry(),
             Stroke::VectorImage(vectorimage) => vectorimage.update_geometry(),
             Stroke::BitmapImage(bitmapimage) => bitmapimage.update_geometry(),
+            Stroke::SprayStroke(spraystroke) => spraystroke.update_geometry(),
+            Stroke::StampStroke(stampstroke) => stampstroke.update_geometry(),
+            Stroke::TapeStroke(tapestroke) => tapestroke.update_geometry(),
+            Stroke::SmudgeStroke(smudgestroke) => smudgestroke.update_geometry(),
+            Stroke::Opaque(opaque) => opaque.update_geometry(),
         }
     }
 }
@@ -96,6 +189,11 @@ impl Drawable for Stroke {
             Stroke::TextStroke(textstroke) => textstroke.draw(cx, image_scale),
             Stroke::VectorImage(vectorimage) => vectorimage.draw(cx, image_scale),
             Stroke::BitmapImage(bitmapimage) => bitmapimage.draw(cx, image_scale),
+            Stroke::SprayStroke(spraystroke) => spraystroke.draw(cx, image_scale),
+            Stroke::StampStroke(stampstroke) => stampstroke.draw(cx, image_scale),
+            Stroke::TapeStroke(tapestroke) => tapestroke.draw(cx, image_scale),
+            Stroke::SmudgeStroke(smudgestroke) => smudgestroke.draw(cx, image_scale),
+            Stroke::Opaque(opaque) => opaque.draw(cx, image_scale),
         }
     }
 
@@ -106,6 +204,11 @@ impl Drawable for Stroke {
             Stroke::TextStroke(textstroke) => textstroke.draw_to_cairo(cx, image_scale),
             Stroke::VectorImage(vectorimage) => vectorimage.draw_to_cairo(cx, image_scale),
             Stroke::BitmapImage(bitmapimage) => bitmapimage.draw_to_cairo(cx, image_scale),
+            Stroke::SprayStroke(spraystroke) => spraystroke.draw_to_cairo(cx, image_scale),
+            Stroke::StampStroke(stampstroke) => stampstroke.draw_to_cairo(cx, image_scale),
+            Stroke::TapeStroke(tapestroke) => tapestroke.draw_to_cairo(cx, image_scale),
+            Stroke::SmudgeStroke(smudgestroke) => smudgestroke.draw_to_cairo(cx, image_scale),
+            Stroke::Opaque(opaque) => opaque.draw_to_cairo(cx, image_scale),
         }
     }
 }
@@ -118,6 +221,11 @@ impl Shapeable for Stroke {
             Self::TextStroke(textstroke) => textstroke.bounds(),
             Self::VectorImage(vectorimage) => vectorimage.bounds(),
             Self::BitmapImage(bitmapimage) => bitmapimage.bounds(),
+            Self::SprayStroke(spraystroke) => spraystroke.bounds(),
+            Self::StampStroke(stampstroke) => stampstroke.bounds(),
+            Self::TapeStroke(tapestroke) => tapestroke.bounds(),
+            Self::SmudgeStroke(smudgestroke) => smudgestroke.bounds(),
+            Self::Opaque(opaque) => opaque.bounds(),
         }
     }
 
@@ -128,6 +236,11 @@ impl Shapeable for Stroke {
             Self::TextStroke(textstroke) => textstroke.hitboxes(),
             Self::VectorImage(vectorimage) => vectorimage.hitboxes(),
             Self::BitmapImage(bitmapimage) => bitmapimage.hitboxes(),
+            Self::SprayStroke(spraystroke) => spraystroke.hitboxes(),
+            Self::StampStroke(stampstroke) => stampstroke.hitboxes(),
+            Self::TapeStroke(tapestroke) => tapestroke.hitboxes(),
+            Self::SmudgeStroke(smudgestroke) => smudgestroke.hitboxes(),
+            Self::Opaque(opaque) => opaque.hitboxes(),
         }
     }
 
@@ -138,6 +251,11 @@ impl Shapeable for Stroke {
             Self::TextStroke(textstroke) => textstroke.outline_path(),
             Self::VectorImage(vectorimage) => vectorimage.outline_path(),
             Self::BitmapImage(bitmapimage) => bitmapimage.outline_path(),
+            Self::SprayStroke(spraystroke) => spraystroke.outline_path(),
+            Self::StampStroke(stampstroke) => stampstroke.outline_path(),
+            Self::TapeStroke(tapestroke) => tapestroke.outline_path(),
+            Self::SmudgeStroke(smudgestroke) => smudgestroke.outline_path(),
+            Self::Opaque(opaque) => opaque.outline_path(),
         }
     }
 }
@@ -160,6 +278,21 @@ impl Transformable for Stroke {
             Self::BitmapImage(bitmapimage) => {
                 bitmapimage.translate(offset);
             }
+            Self::SprayStroke(spraystroke) => {
+                spraystroke.translate(offset);
+            }
+            Self::StampStroke(stampstroke) => {
+                stampstroke.translate(offset);
+            }
+            Self::TapeStroke(tapestroke) => {
+                tapestroke.translate(offset);
+            }
+            Self::SmudgeStroke(smudgestroke) => {
+                smudgestroke.translate(offset);
+            }
+            Self::Opaque(opaque) => {
+                opaque.translate(offset);
+            }
         }
     }
 
@@ -180,6 +313,21 @@ impl Transformable for Stroke {
             Self::BitmapImage(bitmapimage) => {
                 bitmapimage.rotate(angle, center);
             }
+            Self::SprayStroke(spraystroke) => {
+                spraystroke.rotate(angle, center);
+            }
+            Self::StampStroke(stampstroke) => {
+                stampstroke.rotate(angle, center);
+            }
+            Self::TapeStroke(tapestroke) => {
+                tapestroke.rotate(angle, center);
+            }
+            Self::SmudgeStroke(smudgestroke) => {
+                smudgestroke.rotate(angle, center);
+            }
+            Self::Opaque(opaque) => {
+                opaque.rotate(angle, center);
+            }
         }
     }
 
@@ -200,20 +348,132 @@ impl Transformable for Stroke {
             Self::BitmapImage(bitmapimage) => {
                 bitmapimage.scale(scale);
             }
+            Self::SprayStroke(spraystroke) => {
+                spraystroke.scale(scale);
+            }
+            Self::StampStroke(stampstroke) => {
+                stampstroke.scale(scale);
+            }
+            Self::TapeStroke(tapestroke) => {
+                tapestroke.scale(scale);
+            }
+            Self::SmudgeStroke(smudgestroke) => {
+                smudgestroke.scale(scale);
+            }
+            Self::Opaque(opaque) => {
+                opaque.scale(scale);
+            }
+        }
+    }
+}
+
+impl Warpable for Stroke {
+    fn warp(&mut self, projective: &Projective) {
+        match self {
+            Self::BrushStroke(brushstroke) => {
+                brushstroke.warp(projective);
+            }
+            Self::ShapeStroke(shapestroke) => {
+                shapestroke.warp(projective);
+            }
+            Self::TextStroke(_)
+            | Self::VectorImage(_)
+            | Self::BitmapImage(_)
+            | Self::SprayStroke(_)
+            | Self::StampStroke(_)
+            | Self::TapeStroke(_)
+            | Self::SmudgeStroke(_)
+            | Self::Opaque(_) => {
+                // Strokes that can't be resampled keep their shape and follow the warp of their center
+                let center = self.bounds().center().coords;
+                self.translate(projective.transform_point(center) - center);
+            }
         }
     }
 }
 
 impl Stroke {
+    /// The serialized stroke types known to this version, must match the variant names.
+    const TYPES: [&'static str; 9] = [
+        "brushstroke",
+        "shapestroke",
+        "textstroke",
+        "vectorimage",
+        "bitmapimage",
+        "spraystroke",
+        "stampstroke",
+        "tapestroke",
+        "smudgestroke",
+    ];
+
     /// The default offset in surface coords when importing a stroke.
     pub const IMPORT_OFFSET_DEFAULT: na::Vector2<f64> = na::vector![32.0, 32.0];
 
+    /// Draw a simplified representation of the stroke, used when rendering zoomed out far.
+    ///
+    /// Falls back to the regular drawing for strokes that don't have one.
+    pub(crate) fn draw_level_of_detail(
+        &self,
+        cx: &mut impl piet::RenderContext,
+        image_scale: f64,
+    ) -> anyhow::Result<()> {
+        match self {
+            Stroke::BrushStroke(brushstroke) => brushstroke.draw_level_of_detail(cx, image_scale),
+            Stroke::ShapeStroke(_)
+            | Stroke::TextStroke(_)
+            | Stroke::VectorImage(_)
+            | Stroke::BitmapImage(_)
+            | Stroke::SprayStroke(_)
+            | Stroke::StampStroke(_)
+            | Stroke::TapeStroke(_)
+            | Stroke::SmudgeStroke(_)
+            | Stroke::Opaque(_) => self.draw(cx, image_scale),
+        }
+    }
+
+    /// How the stroke is blended with the content underneath it.
+    pub fn blend_mode(&self) -> BlendMode {
+        match self {
+            Stroke::BrushStroke(brushstroke) => brushstroke.blend_mode,
+            Stroke::ShapeStroke(_)
+            | Stroke::TextStroke(_)
+            | Stroke::VectorImage(_)
+            | Stroke::BitmapImage(_)
+            | Stroke::SprayStroke(_)
+            | Stroke::StampStroke(_)
+            | Stroke::TapeStroke(_)
+            | Stroke::SmudgeStroke(_)
+            | Stroke::Opaque(_) => BlendMode::Normal,
+        }
+    }
+
     pub fn extract_default_layer(&self) -> StrokeLayer {
         match self {
             Stroke::BrushStroke(_) => StrokeLayer::UserLayer(0),
             Stroke::ShapeStroke(_) => StrokeLayer::UserLayer(0),
             Stroke::TextStroke(_) => StrokeLayer::UserLayer(0),
             Stroke::VectorImage(_) | Stroke::BitmapImage(_) => StrokeLayer::Image,
+            Stroke::SprayStroke(_) => StrokeLayer::UserLayer(0),
+            Stroke::StampStroke(_) => StrokeLayer::UserLayer(0),
+            Stroke::TapeStroke(_) => StrokeLayer::Tape,
+            Stroke::SmudgeStroke(_) => StrokeLayer::UserLayer(0),
+            Stroke::Opaque(_) => StrokeLayer::UserLayer(0),
+        }
+    }
+
+    /// The color of the stroke outline or the text. None for images.
+    pub fn stroke_color(&self) -> Option<Color> {
+        match self {
+            Stroke::BrushStroke(brush_stroke) => brush_stroke.style.stroke_color(),
+            Stroke::ShapeStroke(shape_stroke) => shape_stroke.style.stroke_color(),
+            Stroke::TextStroke(text_stroke) => Some(text_stroke.text_style.color),
+            Stroke::SprayStroke(spray_stroke) => spray_stroke.options.stroke_color,
+            Stroke::StampStroke(stamp_stroke) => stamp_stroke.color,
+            Stroke::TapeStroke(tape_stroke) => Some(tape_stroke.options.color),
+            Stroke::VectorImage(_)
+            | Stroke::BitmapImage(_)
+            | Stroke::SmudgeStroke(_)
+            | Stroke::Opaque(_) => None,
         }
     }
 
@@ -258,8 +518,30 @@ impl Stroke {
 
                 true
             }
+            Stroke::SprayStroke(spray_stroke) => {
+                if let Some(color) = spray_stroke.options.stroke_color.as_mut() {
+                    *color = color.to_inverted_brightness_color();
+                }
+
+                true
+            }
+            Stroke::StampStroke(stamp_stroke) => {
+                if let Some(color) = stamp_stroke.color.as_mut() {
+                    *color = color.to_inverted_brightness_color();
+                }
+
+                true
+            }
+            Stroke::TapeStroke(tape_stroke) => {
+                tape_stroke.options.color =
+                    tape_stroke.options.color.to_inverted_brightness_color();
+
+                true
+            }
             Stroke::VectorImage(_) => false,
             Stroke::BitmapImage(_) => false,
+            Stroke::SmudgeStroke(_) => false,
+            Stroke::Opaque(_) => false,
         }
     }
 
@@ -299,8 +581,38 @@ impl Stroke {
 
                 true
             }
+            Stroke::SprayStroke(spray_stroke) => {
+                if let Some(color) = spray_stroke.options.stroke_color.as_mut() {
+                    *color = color.to_darkest_color();
+                }
+
+                true
+            }
+            Stroke::StampStroke(stamp_stroke) => {
+                if let Some(color) = stamp_stroke.color.as_mut() {
+                    *color = color.to_darkest_color();
+                }
+
+                true
+            }
+            Stroke::TapeStroke(tape_stroke) => {
+                tape_stroke.options.color = tape_stroke.options.color.to_darkest_color();
+
+                true
+            }
             Stroke::VectorImage(_) => false,
             Stroke::BitmapImage(_) => false,
+            Stroke::SmudgeStroke(_) => false,
+            Stroke::Opaque(_) => false,
+        }
+    }
+
+    /// Records the URL the content of image strokes was fetched from. Other strokes are left unchanged.
+    pub fn set_source_url(&mut self, source_url: Option<String>) {
+        match self {
+            Stroke::VectorImage(vectorimage) => vectorimage.source_url = source_url,
+            Stroke::BitmapImage(bitmapimage) => bitmapimage.source_url = source_url,
+            _ => {}
         }
     }
 
@@ -485,10 +797,15 @@ impl Stroke {
                 let mut pressures: Vec<f64> = elements_vec
                     .iter()
                     .map(|element| match &stroke_style {
-                        Style::Smooth(options) => {
-                            options.pressure_curve.apply(stroke_width, element.pressure)
+                        // Options with a zero width can't give a width ratio, the pressure is used as for the
+                        // other styles
+                        Style::Smooth(options) if options.stroke_width > 0.0 => {
+                            stroke_width * options.pressure_width(element.pressure)
+                                / options.stroke_width
+                        }
+                        Style::Smooth(_) | Style::Rough(_) | Style::Textured(_) => {
+                            stroke_width * element.pressure
                         }
-                        Style::Rough(_) | Style::Textured(_) => stroke_width * element.pressure,
                     })
                     .collect();
                 width_vec.append(&mut pressures);
@@ -711,6 +1028,197 @@ impl Stroke {
                     },
                 ))
             }
+            Stroke::SprayStroke(spraystroke) => {
+                // The dabs are too many to export them as single xopp strokes
+                let png_data = match spraystroke.export_to_bitmap_image_bytes(
+                    image::ImageFormat::Png,
+                    Engine::STROKE_EXPORT_IMAGE_SCALE,
+                ) {
+                    Ok(image_bytes) => image_bytes,
+                    Err(e) => {
+                        error!(
+                            "Exporting SprayStroke to image bytes failed while converting Stroke to Xopp, Err: {e:?}"
+                        );
+                        return None;
+                    }
+                };
+
+                let bounds = spraystroke.bounds();
+
+                Some(xoppformat::XoppStrokeType::XoppImage(
+                    xoppformat::XoppImage {
+                        left: utils::convert_value_dpi(
+                            bounds.mins[0],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        top: utils::convert_value_dpi(
+                            bounds.mins[1],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        right: utils::convert_value_dpi(
+                            bounds.maxs[0],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        bottom: utils::convert_value_dpi(
+                            bounds.maxs[1],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        data: base64::Engine::encode(
+                            &base64::engine::general_purpose::STANDARD,
+                            png_data,
+                        ),
+                    },
+                ))
+            }
+            Stroke::StampStroke(stampstroke) => {
+                // Stamps can be custom svgs, which are not supported by xournalpp
+                let png_data = match stampstroke.export_to_bitmap_image_bytes(
+                    image::ImageFormat::Png,
+                    Engine::STROKE_EXPORT_IMAGE_SCALE,
+                ) {
+                    Ok(image_bytes) => image_bytes,
+                    Err(e) => {
+                        error!(
+                            "Exporting StampStroke to image bytes failed while converting Stroke to Xopp, Err: {e:?}"
+                        );
+                        return None;
+                    }
+                };
+
+                let bounds = stampstroke.bounds();
+
+                Some(xoppformat::XoppStrokeType::XoppImage(
+                    xoppformat::XoppImage {
+                        left: utils::convert_value_dpi(
+                            bounds.mins[0],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        top: utils::convert_value_dpi(
+                            bounds.mins[1],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        right: utils::convert_value_dpi(
+                            bounds.maxs[0],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        bottom: utils::convert_value_dpi(
+                            bounds.maxs[1],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        data: base64::Engine::encode(
+                            &base64::engine::general_purpose::STANDARD,
+                            png_data,
+                        ),
+                    },
+                ))
+            }
+            Stroke::TapeStroke(tapestroke) => {
+                // Xournalpp has no tape, so the pattern is preserved as image
+                let png_data = match tapestroke.export_to_bitmap_image_bytes(
+                    image::ImageFormat::Png,
+                    Engine::STROKE_EXPORT_IMAGE_SCALE,
+                ) {
+                    Ok(image_bytes) => image_bytes,
+                    Err(e) => {
+                        error!(
+                            "Exporting TapeStroke to image bytes failed while converting Stroke to Xopp, Err: {e:?}"
+                        );
+                        return None;
+                    }
+                };
+
+                let bounds = tapestroke.bounds();
+
+                Some(xoppformat::XoppStrokeType::XoppImage(
+                    xoppformat::XoppImage {
+                        left: utils::convert_value_dpi(
+                            bounds.mins[0],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        top: utils::convert_value_dpi(
+                            bounds.mins[1],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        right: utils::convert_value_dpi(
+                            bounds.maxs[0],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        bottom: utils::convert_value_dpi(
+                            bounds.maxs[1],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        data: base64::Engine::encode(
+                            &base64::engine::general_purpose::STANDARD,
+                            png_data,
+                        ),
+                    },
+                ))
+            }
+            Stroke::SmudgeStroke(smudgestroke) => {
+                // The smudged content already is an image
+                let png_data = match smudgestroke.export_to_bitmap_image_bytes(
+                    image::ImageFormat::Png,
+                    Engine::STROKE_EXPORT_IMAGE_SCALE,
+                ) {
+                    Ok(image_bytes) => image_bytes,
+                    Err(e) => {
+                        error!(
+                            "Exporting SmudgeStroke to image bytes failed while converting Stroke to Xopp, Err: {e:?}"
+                        );
+                        return None;
+                    }
+                };
+
+                let bounds = smudgestroke.bounds();
+
+                Some(xoppformat::XoppStrokeType::XoppImage(
+                    xoppformat::XoppImage {
+                        left: utils::convert_value_dpi(
+                            bounds.mins[0],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        top: utils::convert_value_dpi(
+                            bounds.mins[1],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        right: utils::convert_value_dpi(
+                            bounds.maxs[0],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        bottom: utils::convert_value_dpi(
+                            bounds.maxs[1],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        data: base64::Engine::encode(
+                            &base64::engine::general_purpose::STANDARD,
+                            png_data,
+                        ),
+                    },
+                ))
+            }
+            Stroke::Opaque(opaque) => {
+                warn!(
+                    "Skipping stroke of unknown type '{}' while converting Stroke to Xopp",
+                    opaque.stroke_type()
+                );
+                None
+            }
         }
     }
 }
@@ -0,0 +1,292 @@
+// Imports
+use super::Content;
+use crate::Drawable;
+use p2d::bounding_volume::Aabb;
+use rnote_compose::Color;
+use rnote_compose::ext::Vector2Ext;
+use rnote_compose::shapes::{Rectangle, Shapeable};
+use rnote_compose::transform::{Transform, Transformable};
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "tape_pattern")]
+pub enum TapePattern {
+    #[serde(rename = "plain")]
+    Plain = 0,
+    #[default]
+    #[serde(rename = "stripes")]
+    Stripes,
+    #[serde(rename = "dots")]
+    Dots,
+    #[serde(rename = "grid")]
+    Grid,
+}
+
+impl TryFrom<u32> for TapePattern {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!("TapePattern try_from::<u32>() for value {} failed", value)
+        })
+    }
+}
+
+/// The options of tape strokes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "tape_options")]
+pub struct TapeOptions {
+    /// The width of the tape.
+    #[serde(rename = "width", with = "rnote_compose::serialize::f64_dp3")]
+    pub width: f64,
+    /// The color of the tape. Usually semi-transparent.
+    #[serde(rename = "color")]
+    pub color: Color,
+    /// The pattern printed on the tape.
+    #[serde(rename = "pattern")]
+    pub pattern: TapePattern,
+}
+
+impl Default for TapeOptions {
+    fn default() -> Self {
+        Self {
+            width: 24.0,
+            color: Color::new(0.96, 0.66, 0.72, 0.75),
+            pattern: TapePattern::default(),
+        }
+    }
+}
+
+impl TapeOptions {
+    pub const WIDTH_MIN: f64 = 4.0;
+    pub const WIDTH_MAX: f64 = 200.0;
+}
+
+/// A strip of tape that covers the content underneath it, without modifying it.
+///
+/// Tapes are placed on their own layer above all other strokes. Revealed tapes are drawn faintly,
+/// so that the covered content can be seen.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "tapestroke")]
+pub struct TapeStroke {
+    /// The rectangle of the tape, its length is along the x-axis of the cuboid.
+    #[serde(rename = "rectangle")]
+    pub rectangle: Rectangle,
+    #[serde(rename = "options")]
+    pub options: TapeOptions,
+    #[serde(rename = "revealed")]
+    pub revealed: bool,
+}
+
+impl Content for TapeStroke {
+    fn update_geometry(&mut self) {}
+}
+
+impl Drawable for TapeStroke {
+    fn draw(&self, cx: &mut impl piet::RenderContext, _image_scale: f64) -> anyhow::Result<()> {
+        let opacity = if self.revealed {
+            Self::REVEALED_OPACITY
+        } else {
+            1.0
+        };
+        let mut color = self.options.color;
+        color.a *= opacity;
+        let pattern_color = piet::Color::rgba(1.0, 1.0, 1.0, Self::PATTERN_OPACITY * opacity);
+
+        cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        cx.transform(self.rectangle.transform.to_kurbo());
+        let outline = self.local_outline();
+        cx.clip(outline.clone());
+        cx.fill(outline, &Into::<piet::Color>::into(color));
+        self.draw_pattern(cx, &pattern_color);
+        cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+}
+
+impl Shapeable for TapeStroke {
+    fn bounds(&self) -> Aabb {
+        self.rectangle.bounds()
+    }
+
+    /// The tape covers its entire area, so the hitboxes are squares along its length.
+    fn hitboxes(&self) -> Vec<Aabb> {
+        let half_extents = self.rectangle.cuboid.half_extents;
+        let n_boxes = (half_extents[0] / half_extents[1].max(1.0)).ceil().max(1.0) as usize;
+        let box_half_length = half_extents[0] / n_boxes as f64;
+
+        (0..n_boxes)
+            .map(|i| {
+                let center = -half_extents[0] + box_half_length * (2 * i + 1) as f64;
+                Rectangle::from_half_extents(
+                    na::vector![center, 0.0],
+                    na::vector![box_half_length, half_extents[1]],
+                )
+            })
+            .map(|mut hitbox| {
+                hitbox.transform =
+                    Transform::new(self.rectangle.transform.affine * hitbox.transform.affine);
+                hitbox.bounds()
+            })
+            .collect()
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        let mut outline = self.local_outline();
+        outline.apply_affine(self.rectangle.transform.to_kurbo());
+        outline
+    }
+}
+
+impl Transformable for TapeStroke {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.rectangle.translate(offset);
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        self.rectangle.rotate(angle, center);
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.rectangle.scale(scale);
+    }
+}
+
+impl TapeStroke {
+    /// The opacity of revealed tapes, relative to their color.
+    const REVEALED_OPACITY: f64 = 0.2;
+    /// The opacity of the pattern.
+    const PATTERN_OPACITY: f64 = 0.45;
+    /// The amount of teeth of the torn ends across the width of the tape.
+    const N_TEETH: usize = 6;
+
+    /// A new tape along the line from start to end.
+    pub fn new(start: na::Vector2<f64>, end: na::Vector2<f64>, options: TapeOptions) -> Self {
+        let direction = end - start;
+        let center = start + direction * 0.5;
+        let rectangle = Rectangle {
+            cuboid: p2d::shape::Cuboid::new(na::vector![
+                direction.magnitude() * 0.5,
+                options.width * 0.5
+            ]),
+            transform: Transform::new_w_isometry(na::Isometry2::new(
+                center,
+                direction[1].atan2(direction[0]),
+            )),
+        };
+
+        Self {
+            rectangle,
+            options,
+            revealed: false,
+        }
+    }
+
+    /// Whether the position lies on the tape.
+    pub fn contains_pos(&self, pos: na::Vector2<f64>) -> bool {
+        let Some(inverse) = self.rectangle.transform.affine.try_inverse() else {
+            return false;
+        };
+        let local = inverse * na::Point2::from(pos);
+        let half_extents = self.rectangle.cuboid.half_extents;
+        local[0].abs() <= half_extents[0] && local[1].abs() <= half_extents[1]
+    }
+
+    /// The outline of the tape with torn ends, in the coordinate space of the rectangle.
+    fn local_outline(&self) -> kurbo::BezPath {
+        let half_extents = self.rectangle.cuboid.half_extents;
+        let tooth_height = half_extents[1] * 2.0 / Self::N_TEETH as f64;
+        let tooth_depth = (tooth_height * 0.5).min(half_extents[0] * 0.5);
+        let mut outline = kurbo::BezPath::new();
+
+        outline.move_to(na::vector![-half_extents[0], -half_extents[1]].to_kurbo_point());
+        outline.line_to(na::vector![half_extents[0], -half_extents[1]].to_kurbo_point());
+        for i in 0..Self::N_TEETH {
+            let y = -half_extents[1] + tooth_height * i as f64;
+            outline.line_to(
+                na::vector![half_extents[0] - tooth_depth, y + tooth_height * 0.5].to_kurbo_point(),
+            );
+            outline.line_to(na::vector![half_extents[0], y + tooth_height].to_kurbo_point());
+        }
+        outline.line_to(na::vector![-half_extents[0], half_extents[1]].to_kurbo_point());
+        for i in 0..Self::N_TEETH {
+            let y = half_extents[1] - tooth_height * i as f64;
+            outline.line_to(
+                na::vector![-half_extents[0] + tooth_depth, y - tooth_height * 0.5]
+                    .to_kurbo_point(),
+            );
+            outline.line_to(na::vector![-half_extents[0], y - tooth_height].to_kurbo_point());
+        }
+        outline.close_path();
+        outline
+    }
+
+    /// Draw the pattern in the coordinate space of the rectangle. Its size follows the width of the tape.
+    fn draw_pattern(&self, cx: &mut impl piet::RenderContext, color: &piet::Color) {
+        let half_extents = self.rectangle.cuboid.half_extents;
+        let width = half_extents[1] * 2.0;
+        if width <= 0.0 {
+            return;
+        }
+
+        match self.options.pattern {
+            TapePattern::Plain => {}
+            TapePattern::Stripes => {
+                let spacing = width * 0.4;
+                let mut x = -half_extents[0] - width;
+                while x <= half_extents[0] {
+                    cx.stroke(
+                        kurbo::Line::new((x, half_extents[1]), (x + width, -half_extents[1])),
+                        color,
+                        width * 0.12,
+                    );
+                    x += spacing;
+                }
+            }
+            TapePattern::Dots => {
+                let spacing = width * 0.35;
+                let mut x = -half_extents[0] + spacing * 0.5;
+                while x <= half_extents[0] {
+                    let mut y = -half_extents[1] + spacing * 0.5;
+                    while y <= half_extents[1] {
+                        cx.fill(kurbo::Circle::new((x, y), width * 0.07), color);
+                        y += spacing;
+                    }
+                    x += spacing;
+                }
+            }
+            TapePattern::Grid => {
+                let spacing = width / 3.0;
+                let line_width = width * 0.05;
+                let mut x = -half_extents[0] + spacing * 0.5;
+                while x <= half_extents[0] {
+                    cx.stroke(
+                        kurbo::Line::new((x, -half_extents[1]), (x, half_extents[1])),
+                        color,
+                        line_width,
+                    );
+                    x += spacing;
+                }
+                for i in 0..3 {
+                    let y = -half_extents[1] + spacing * (i as f64 + 0.5);
+                    cx.stroke(
+                        kurbo::Line::new((-half_extents[0], y), (half_extents[0], y)),
+                        color,
+                        line_width,
+                    );
+                }
+            }
+        }
+    }
+}
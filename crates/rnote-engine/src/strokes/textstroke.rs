@@ -155,7 +155,7 @@ impl TextAttribute {
         }
     }
 
-    fn same_variant(&self, other: &TextAttribute) -> bool {
+    pub(crate) fn same_variant(&self, other: &TextAttribute) -> bool {
         match (self, other) {
             (TextAttribute::FontFamily(_), TextAttribute::FontFamily(_))
             | (TextAttribute::FontSize(_), TextAttribute::FontSize(_))
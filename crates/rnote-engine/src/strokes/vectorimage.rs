@@ -1,10 +1,13 @@
 // Imports
 use super::content::GeneratedContentImages;
 use super::resize::{ImageSizeOption, calculate_resize_ratio};
-use super::{Content, Stroke};
+use super::{BrushStroke, Content, Stroke};
 use crate::Image;
 use crate::document::Format;
 use crate::engine::import::{PdfImportPageSpacing, PdfImportPrefs};
+use crate::engine::rendering::RenderQuality;
+use crate::fileformats::inkpdfformat::InkPage;
+use crate::store::chrono_comp::StrokeLayer;
 use crate::svg::USVG_FONTDB;
 use crate::{Drawable, Svg};
 use anyhow::anyhow;
@@ -18,7 +21,6 @@ use rnote_compose::shapes::Shapeable;
 use rnote_compose::transform::Transform;
 use rnote_compose::transform::Transformable;
 use serde::{Deserialize, Serialize};
-use std::ops::Range;
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +35,9 @@ pub struct VectorImage {
     pub intrinsic_size: na::Vector2<f64>,
     #[serde(rename = "rectangle")]
     pub rectangle: Rectangle,
+    /// The URL the image was imported from, if it was fetched remotely.
+    #[serde(rename = "source_url")]
+    pub source_url: Option<String>,
 }
 
 impl Default for VectorImage {
@@ -41,6 +46,7 @@ impl Default for VectorImage {
             svg_data: String::default(),
             intrinsic_size: na::Vector2::zeros(),
             rectangle: Rectangle::default(),
+            source_url: None,
         }
     }
 }
@@ -79,6 +85,7 @@ impl Content for VectorImage {
         &self,
         _viewport: Aabb,
         image_scale: f64,
+        render_quality: RenderQuality,
     ) -> Result<GeneratedContentImages, anyhow::Error> {
         let bounds = self.bounds();
         // always generate full stroke images for vectorimages, they are too expensive to be repeatedly rendered
@@ -86,6 +93,7 @@ impl Content for VectorImage {
             |piet_cx| self.draw(piet_cx, image_scale),
             bounds,
             image_scale,
+            render_quality,
         )?]))
     }
 
@@ -201,6 +209,7 @@ impl VectorImage {
             svg_data,
             intrinsic_size,
             rectangle,
+            source_url: None,
         })
     }
 
@@ -208,10 +217,73 @@ impl VectorImage {
         to_be_read: &[u8],
         pdf_import_prefs: PdfImportPrefs,
         insert_pos: na::Vector2<f64>,
-        page_range: Option<Range<usize>>,
+        page_indices: Option<Vec<usize>>,
         format: &Format,
         password: Option<String>,
     ) -> Result<Vec<Self>, anyhow::Error> {
+        let svgs = Self::pdf_pages_as_svgs(
+            to_be_read,
+            pdf_import_prefs,
+            insert_pos,
+            page_indices,
+            format,
+            password,
+        )?;
+
+        svgs.into_par_iter()
+            .map(|svg| {
+                Self::from_svg_str(
+                    svg.svg_data.as_str(),
+                    svg.bounds.mins.coords,
+                    ImageSizeOption::ImposeSize(svg.bounds.extents()),
+                )
+            })
+            .collect()
+    }
+
+    /// Like [Self::from_pdf_bytes], but the ink is split off the pages and returned as brush strokes.
+    ///
+    /// Intended for Pdfs exported by note taking apps, see [InkPage].
+    #[allow(clippy::type_complexity)]
+    pub fn from_pdf_bytes_w_ink(
+        to_be_read: &[u8],
+        pdf_import_prefs: PdfImportPrefs,
+        insert_pos: na::Vector2<f64>,
+        page_indices: Option<Vec<usize>>,
+        format: &Format,
+        password: Option<String>,
+    ) -> Result<Vec<(Self, Vec<(BrushStroke, StrokeLayer)>)>, anyhow::Error> {
+        let svgs = Self::pdf_pages_as_svgs(
+            to_be_read,
+            pdf_import_prefs,
+            insert_pos,
+            page_indices,
+            format,
+            password,
+        )?;
+
+        svgs.into_par_iter()
+            .map(|svg| {
+                let ink_page = InkPage::split(&svg.svg_data, svg.bounds)?;
+                let vectorimage = Self::from_svg_str(
+                    ink_page.background_svg_data.as_str(),
+                    svg.bounds.mins.coords,
+                    ImageSizeOption::ImposeSize(svg.bounds.extents()),
+                )?;
+                Ok((vectorimage, ink_page.strokes))
+            })
+            .collect()
+    }
+
+    /// Converts the pages of the Pdf to Svgs, positioned according to the import preferences.
+    fn pdf_pages_as_svgs(
+        to_be_read: &[u8],
+        pdf_import_prefs: PdfImportPrefs,
+        insert_pos: na::Vector2<f64>,
+        page_indices: Option<Vec<usize>>,
+        format: &Format,
+        password: Option<String>,
+    ) -> Result<Vec<Svg>, anyhow::Error> {
         // TODO: how to avoid this allocation without lifetime issues?
         let data = Arc::new(to_be_read.to_vec());
         let pdf = if let Some(password) = password {
@@ -226,7 +298,7 @@ impl VectorImage {
             bg_color: [255, 255, 255, 255],
         };
         let pages = pdf.pages();
-        let page_range = page_range.unwrap_or(0..pages.len());
+        let page_indices = page_indices.unwrap_or_else(|| (0..pages.len()).collect());
         let page_width = if pdf_import_prefs.adjust_document {
             format.width()
         } else {
@@ -243,7 +315,8 @@ impl VectorImage {
         let mut y = insert_pos[1];
 
         // TODO: investigate if this can be parallelized with rayon's `par_iter()`
-        let svgs = page_range
+        let svgs = page_indices
+            .into_iter()
             .filter_map(|page_i| {
                 let page = pages.get(page_i)?;
                 let (intrinsic_width, intrinsic_height) = {
@@ -271,14 +344,6 @@ impl VectorImage {
             })
             .collect::<Vec<Svg>>();
 
-        svgs.into_par_iter()
-            .map(|svg| {
-                Self::from_svg_str(
-                    svg.svg_data.as_str(),
-                    svg.bounds.mins.coords,
-                    ImageSizeOption::ImposeSize(svg.bounds.extents()),
-                )
-            })
-            .collect()
+        Ok(svgs)
     }
 }
@@ -1,5 +1,5 @@
 // Imports
-use crate::image::{Image, ImageMemoryFormat, convert_image_bgra_to_rgba};
+use crate::image::{Image, ImageMemoryFormat};
 use anyhow::Context;
 use once_cell::sync::Lazy;
 use p2d::bounding_volume::{Aabb, BoundingVolume};
@@ -203,7 +203,17 @@ impl Svg {
     ///
     /// Using rsvg for rendering.
     pub fn gen_image(&self, image_scale: f64) -> Result<Image, anyhow::Error> {
-        let mut bounds = self.bounds;
+        self.gen_image_of_region(self.bounds, image_scale)
+    }
+
+    /// Generate an image of a region of the Svg.
+    ///
+    /// Using rsvg for rendering.
+    pub fn gen_image_of_region(
+        &self,
+        mut bounds: Aabb,
+        image_scale: f64,
+    ) -> Result<Image, anyhow::Error> {
         bounds.ensure_positive();
         bounds.assert_valid()?;
 
@@ -216,8 +226,9 @@ impl Svg {
         let width_scaled = ((bounds.extents()[0]) * image_scale).round() as u32;
         let height_scaled = ((bounds.extents()[1]) * image_scale).round() as u32;
 
+        let memory_format = ImageMemoryFormat::default();
         let mut surface = cairo::ImageSurface::create(
-                cairo::Format::ARgb32,
+                memory_format.cairo_format(),
                 width_scaled as i32,
                 height_scaled as i32,
             )
@@ -264,7 +275,7 @@ impl Svg {
             .to_vec();
 
         Ok(Image {
-            data: glib::Bytes::from_owned(convert_image_bgra_to_rgba(
+            data: glib::Bytes::from_owned(memory_format.from_cairo_surface_data(
                 width_scaled,
                 height_scaled,
                 data,
@@ -272,8 +283,7 @@ impl Svg {
             rect: Rectangle::from_p2d_aabb(bounds),
             pixel_width: width_scaled,
             pixel_height: height_scaled,
-            // cairo renders to bgra8-premultiplied, but we convert it to rgba8-premultiplied
-            memory_format: ImageMemoryFormat::R8g8b8a8Premultiplied,
+            memory_format,
         })
     }
 }
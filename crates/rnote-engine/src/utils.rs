@@ -57,6 +57,23 @@ pub fn doc_pages_files_names(file_stem_name: String, i: usize) -> String {
     file_stem_name + &format!(" - Page {i:02}")
 }
 
+/// Generate the file name (without extension) for an exported page from a naming pattern.
+///
+/// The placeholder `{stem}` is replaced with the file stem name, `{page}` with the one-indexed page number
+/// zero-padded to the digit count of `pages_amount`, and `{pages}` with the total amount of pages.
+pub fn doc_pages_files_names_w_pattern(
+    pattern: &str,
+    file_stem_name: &str,
+    page_number: usize,
+    pages_amount: usize,
+) -> String {
+    let width = pages_amount.max(1).to_string().len();
+    pattern
+        .replace("{stem}", file_stem_name)
+        .replace("{page}", &format!("{page_number:0width$}"))
+        .replace("{pages}", &pages_amount.to_string())
+}
+
 pub fn convert_value_dpi(value: f64, current_dpi: f64, target_dpi: f64) -> f64 {
     (value / current_dpi) * target_dpi
 }
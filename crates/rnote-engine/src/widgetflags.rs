@@ -1,3 +1,6 @@
+// Imports
+use crate::engine::rendering::RenderProgress;
+
 /// Flags returned to the UI widget that holds the engine.
 #[must_use]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -18,6 +21,10 @@ pub struct WidgetFlags {
     pub zoomed: bool,
     /// Deselect the elements of the global color picker.
     pub deselect_color_setters: bool,
+    /// Open the radial quick menu at the pointer position.
+    pub open_radial_menu: bool,
+    /// Refresh the UI with the state of the user layers.
+    pub refresh_layers: bool,
     /// Is Some when undo button visibility should be changed. Is None if should not be changed.
     pub hide_undo: Option<bool>,
     /// Is Some when redo button visibility should be changed. Is None if should not be changed.
@@ -26,6 +33,8 @@ pub struct WidgetFlags {
     /// Meaning, when enabled instead of key events, text events are then emitted
     /// for regular unicode text. Used when writing text with the typewriter.
     pub enable_text_preprocessing: Option<bool>,
+    /// Is Some when the progress of rendering many strokes has changed.
+    pub render_progress: Option<RenderProgress>,
 }
 
 impl Default for WidgetFlags {
@@ -39,9 +48,12 @@ impl Default for WidgetFlags {
             zoomed_temporarily: false,
             zoomed: false,
             deselect_color_setters: false,
+            open_radial_menu: false,
+            refresh_layers: false,
             hide_undo: None,
             hide_redo: None,
             enable_text_preprocessing: None,
+            render_progress: None,
         }
     }
 }
@@ -65,6 +77,8 @@ impl std::ops::BitOrAssign for WidgetFlags {
         self.zoomed_temporarily |= rhs.zoomed_temporarily;
         self.zoomed |= rhs.zoomed;
         self.deselect_color_setters |= rhs.deselect_color_setters;
+        self.open_radial_menu |= rhs.open_radial_menu;
+        self.refresh_layers |= rhs.refresh_layers;
         if rhs.hide_undo.is_some() {
             self.hide_undo = rhs.hide_undo
         }
@@ -74,5 +88,8 @@ impl std::ops::BitOrAssign for WidgetFlags {
         if rhs.enable_text_preprocessing.is_some() {
             self.enable_text_preprocessing = rhs.enable_text_preprocessing;
         }
+        if rhs.render_progress.is_some() {
+            self.render_progress = rhs.render_progress;
+        }
     }
 }
@@ -65,7 +65,6 @@ impl RnApp {
 
     // Accelerators / Keyboard Shortcuts
     pub(crate) fn setup_action_accels(&self) {
-        self.set_accels_for_action("app.quit", &["<Ctrl>q"]);
-        self.set_accels_for_action("app.new-window", &["<Ctrl>n"]);
+        crate::keyboardshortcuts::apply_accels(self);
     }
 }
@@ -4,17 +4,20 @@ mod appactions;
 // Imports
 use crate::{
     RnAppMenu, RnAppWindow, RnCanvas, RnCanvasMenu, RnCanvasWrapper, RnColorPicker, RnIconPicker,
-    RnMainHeader, RnOverlays, RnPenPicker, RnPensSideBar, RnSettingsPanel, RnSidebar,
-    RnStrokeContentPreview, RnStrokeWidthPicker, RnUnitEntry, RnWorkspaceBrowser,
-    colorpicker::RnColorPad, colorpicker::RnColorSetter, config, penssidebar::RnBrushPage,
-    penssidebar::RnEraserPage, penssidebar::RnSelectorPage, penssidebar::RnShaperPage,
-    penssidebar::RnToolsPage, penssidebar::RnTypewriterPage, settingspanel::RnPenShortcutRow,
-    strokewidthpicker::RnStrokeWidthPreview, strokewidthpicker::RnStrokeWidthSetter,
-    strokewidthpicker::StrokeWidthPreviewStyle, workspacebrowser::RnFileRow,
-    workspacebrowser::RnWorkspacesBar, workspacebrowser::workspacesbar::RnWorkspaceRow,
+    RnLayersPanel, RnMainHeader, RnOverlays, RnPenPicker, RnPenPresetBar, RnPensSideBar,
+    RnRadialMenu, RnSettingsPanel, RnSidebar, RnStrokeContentPreview, RnStrokeWidthPicker,
+    RnUnitEntry, RnWorkspaceBrowser, colorpicker::RnColorPad, colorpicker::RnColorSetter, config,
+    penssidebar::RnBrushPage, penssidebar::RnEraserPage, penssidebar::RnSelectorPage,
+    penssidebar::RnShaperPage, penssidebar::RnToolsPage, penssidebar::RnTypewriterPage,
+    settingspanel::RnPenShortcutRow, strokewidthpicker::RnStrokeWidthPreview,
+    strokewidthpicker::RnStrokeWidthSetter, strokewidthpicker::StrokeWidthPreviewStyle,
+    workspacebrowser::RnFileRow, workspacebrowser::RnWorkspacesBar,
+    workspacebrowser::workspacesbar::RnWorkspaceRow,
 };
 use adw::subclass::prelude::AdwApplicationImpl;
 use gtk4::{WindowGroup, gio, glib, glib::clone, prelude::*, subclass::prelude::*};
+use rnote_engine::storage::StorageBackends;
+use std::rc::Rc;
 
 mod imp {
     use super::*;
@@ -22,6 +25,8 @@ mod imp {
     #[derive(Debug)]
     pub(crate) struct RnApp {
         pub(crate) app_settings: Option<gio::Settings>,
+        /// The storage backends documents are loaded from and saved to.
+        pub(crate) storage_backends: Rc<StorageBackends>,
     }
 
     impl Default for RnApp {
@@ -34,7 +39,10 @@ mod imp {
                 ))
             });
 
-            Self { app_settings }
+            Self {
+                app_settings,
+                storage_backends: Rc::new(StorageBackends::default()),
+            }
         }
     }
 
@@ -104,6 +112,7 @@ mod imp {
             RnColorPad::static_type();
             RnCanvasMenu::static_type();
             RnSettingsPanel::static_type();
+            RnLayersPanel::static_type();
             RnAppMenu::static_type();
             RnMainHeader::static_type();
             RnPensSideBar::static_type();
@@ -127,6 +136,8 @@ mod imp {
             RnStrokeContentPreview::static_type();
             RnSidebar::static_type();
             RnPenPicker::static_type();
+            RnPenPresetBar::static_type();
+            RnRadialMenu::static_type();
         }
 
         /// Initializes and shows a new app window
@@ -189,6 +200,10 @@ impl RnApp {
         self.app_settings().is_some()
     }
 
+    pub(crate) fn storage_backends(&self) -> Rc<StorageBackends> {
+        self.imp().storage_backends.clone()
+    }
+
     pub(crate) fn new_appwindow_init_show(&self) {
         self.imp().new_appwindow_init_show(None);
     }
@@ -3,19 +3,16 @@ use crate::{RnAppWindow, RnCanvas, config, dialogs};
 use gettextrs::gettext;
 use gtk4::gio::InputStream;
 use gtk4::graphene;
-use gtk4::{
-    PrintOperation, PrintOperationAction, Unit, UriLauncher, Window, gdk, gio, glib, glib::clone,
-    prelude::*,
-};
-use p2d::bounding_volume::BoundingVolume;
-use rnote_compose::SplitOrder;
+use gtk4::{ColorDialog, UriLauncher, Window, gdk, gio, glib, glib::clone, prelude::*};
+use rnote_compose::Color;
 use rnote_compose::penevent::ShortcutKey;
+use rnote_engine::Camera;
 use rnote_engine::engine::StrokeContent;
-use rnote_engine::ext::GraphenePointExt;
+use rnote_engine::ext::{GdkRGBAExt, GraphenePointExt};
+use rnote_engine::fileformats::tableformat::TableText;
 use rnote_engine::pens::PenStyle;
 use rnote_engine::strokes::resize::{ImageSizeOption, Resize};
 use rnote_engine::strokes::textstroke::TextAttribute;
-use rnote_engine::{Camera, Engine};
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Instant;
@@ -44,6 +41,10 @@ impl RnAppWindow {
         self.add_action(&action_donate);
         let action_keyboard_shortcuts_dialog = gio::SimpleAction::new("keyboard-shortcuts", None);
         self.add_action(&action_keyboard_shortcuts_dialog);
+        let action_command_palette = gio::SimpleAction::new("command-palette", None);
+        self.add_action(&action_command_palette);
+        let action_stylus_calibration = gio::SimpleAction::new("stylus-calibration", None);
+        self.add_action(&action_stylus_calibration);
         let action_open_canvasmenu = gio::SimpleAction::new("open-canvasmenu", None);
         self.add_action(&action_open_canvasmenu);
         let action_open_appmenu = gio::SimpleAction::new("open-appmenu", None);
@@ -68,6 +69,8 @@ impl RnAppWindow {
         self.add_action(&action_zoom_reset);
         let action_zoom_fit_width = gio::SimpleAction::new("zoom-fit-width", None);
         self.add_action(&action_zoom_fit_width);
+        let action_zoom_to = gio::SimpleAction::new("zoom-to", Some(&f64::static_variant_type()));
+        self.add_action(&action_zoom_to);
         let action_zoomin = gio::SimpleAction::new("zoom-in", None);
         self.add_action(&action_zoomin);
         let action_zoomout = gio::SimpleAction::new("zoom-out", None);
@@ -86,6 +89,41 @@ impl RnAppWindow {
         self.add_action(&action_selection_duplicate);
         let action_selection_invert_color = gio::SimpleAction::new("selection-invert-color", None);
         self.add_action(&action_selection_invert_color);
+        let action_selection_copy_style = gio::SimpleAction::new("selection-copy-style", None);
+        self.add_action(&action_selection_copy_style);
+        let action_selection_paste_style = gio::SimpleAction::new("selection-paste-style", None);
+        self.add_action(&action_selection_paste_style);
+        let action_selection_change_color = gio::SimpleAction::new("selection-change-color", None);
+        self.add_action(&action_selection_change_color);
+        let action_selection_change_fill_color =
+            gio::SimpleAction::new("selection-change-fill-color", None);
+        self.add_action(&action_selection_change_fill_color);
+        let action_selection_bring_to_front =
+            gio::SimpleAction::new("selection-bring-to-front", None);
+        self.add_action(&action_selection_bring_to_front);
+        let action_selection_send_to_back = gio::SimpleAction::new("selection-send-to-back", None);
+        self.add_action(&action_selection_send_to_back);
+        let action_selection_raise = gio::SimpleAction::new("selection-raise", None);
+        self.add_action(&action_selection_raise);
+        let action_selection_lower = gio::SimpleAction::new("selection-lower", None);
+        self.add_action(&action_selection_lower);
+        let action_selection_layer_up = gio::SimpleAction::new("selection-layer-up", None);
+        self.add_action(&action_selection_layer_up);
+        let action_selection_layer_down = gio::SimpleAction::new("selection-layer-down", None);
+        self.add_action(&action_selection_layer_down);
+        let action_selection_group = gio::SimpleAction::new("selection-group", None);
+        self.add_action(&action_selection_group);
+        let action_selection_ungroup = gio::SimpleAction::new("selection-ungroup", None);
+        self.add_action(&action_selection_ungroup);
+        let action_selection_locked =
+            gio::SimpleAction::new_stateful("selection-locked", None, &false.to_variant());
+        self.add_action(&action_selection_locked);
+        let action_selection_select_similar =
+            gio::SimpleAction::new("selection-select-similar", None);
+        self.add_action(&action_selection_select_similar);
+        let action_selection_select_by_color =
+            gio::SimpleAction::new("selection-select-by-color", None);
+        self.add_action(&action_selection_select_by_color);
         let action_selection_select_all = gio::SimpleAction::new("selection-select-all", None);
         self.add_action(&action_selection_select_all);
         let action_selection_deselect_all = gio::SimpleAction::new("selection-deselect-all", None);
@@ -104,6 +142,8 @@ impl RnAppWindow {
         self.add_action(&action_print_doc);
         let action_import_file = gio::SimpleAction::new("import-file", None);
         self.add_action(&action_import_file);
+        let action_import_url = gio::SimpleAction::new("import-url", None);
+        self.add_action(&action_import_url);
         let action_export_doc = gio::SimpleAction::new("export-doc", None);
         self.add_action(&action_export_doc);
         let action_export_doc_pages = gio::SimpleAction::new("export-doc-pages", None);
@@ -131,6 +171,11 @@ impl RnAppWindow {
         self.add_action(&action_active_tab_move_right);
         let action_active_tab_close = gio::SimpleAction::new("active-tab-close", None);
         self.add_action(&action_active_tab_close);
+        let action_active_tab_split = gio::SimpleAction::new("active-tab-split", None);
+        self.add_action(&action_active_tab_split);
+        let action_split_view_close = gio::SimpleAction::new("split-view-close", None);
+        action_split_view_close.set_enabled(false);
+        self.add_action(&action_split_view_close);
         let action_drawing_pad_pressed_button_0 =
             gio::SimpleAction::new("drawing-pad-pressed-button-0", None);
         self.add_action(&action_drawing_pad_pressed_button_0);
@@ -151,6 +196,13 @@ impl RnAppWindow {
         self.add_action(&action_touch_drawing);
         let action_focus_mode = gio::PropertyAction::new("focus-mode", self, "focus-mode");
         self.add_action(&action_focus_mode);
+        let action_ruler = gio::PropertyAction::new("ruler", self, "ruler");
+        self.add_action(&action_ruler);
+        let action_protractor = gio::PropertyAction::new("protractor", self, "protractor");
+        self.add_action(&action_protractor);
+        let action_presentation_mode =
+            gio::PropertyAction::new("presentation-mode", self, "presentation-mode");
+        self.add_action(&action_presentation_mode);
         let action_pen_sounds = gio::PropertyAction::new("pen-sounds", self, "pen-sounds");
         self.add_action(&action_pen_sounds);
         let action_snap_positions =
@@ -168,6 +220,9 @@ impl RnAppWindow {
         self.add_action(&action_devel_mode);
         let action_visual_debug = gio::PropertyAction::new("visual-debug", self, "visual-debug");
         self.add_action(&action_visual_debug);
+        let action_render_diagnostics =
+            gio::PropertyAction::new("render-diagnostics", self, "render-diagnostics");
+        self.add_action(&action_render_diagnostics);
 
         let action_pen_style = gio::SimpleAction::new_stateful(
             "pen-style",
@@ -175,6 +230,14 @@ impl RnAppWindow {
             &String::from("brush").to_variant(),
         );
         self.add_action(&action_pen_style);
+        let action_pen_preset_save = gio::SimpleAction::new("pen-preset-save", None);
+        self.add_action(&action_pen_preset_save);
+        let action_pen_preset_apply =
+            gio::SimpleAction::new("pen-preset-apply", Some(&u32::static_variant_type()));
+        self.add_action(&action_pen_preset_apply);
+        let action_pen_preset_remove =
+            gio::SimpleAction::new("pen-preset-remove", Some(&u32::static_variant_type()));
+        self.add_action(&action_pen_preset_remove);
 
         // Open settings
         action_open_settings.connect_activate(clone!(
@@ -220,6 +283,24 @@ impl RnAppWindow {
             }
         ));
 
+        // Command palette
+        action_command_palette.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                dialogs::commandpalette::dialog_command_palette(&appwindow);
+            }
+        ));
+
+        // Stylus calibration
+        action_stylus_calibration.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                dialogs::stylus::dialog_stylus_calibration(&appwindow);
+            }
+        ));
+
         // Open Canvas Menu
         action_open_canvasmenu.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -340,6 +421,66 @@ impl RnAppWindow {
             }
         ));
 
+        // Pin the current pen as preset
+        action_pen_preset_save.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let pen_style = canvas.engine_ref().current_pen_style_w_override();
+                let Some(stroke_width) = appwindow
+                    .engine_config()
+                    .read()
+                    .pens_config
+                    .stroke_width(pen_style)
+                else {
+                    appwindow
+                        .overlays()
+                        .dispatch_toast_error(&gettext("The current pen can't be pinned"));
+                    return;
+                };
+                let name = format!("{} {stroke_width:.1}", pen_style_display_name(pen_style));
+                canvas.engine_ref().save_pen_preset(name);
+                appwindow.overlays().penpresetbar().refresh_ui(&appwindow);
+            }
+        ));
+
+        // Apply a pen preset
+        action_pen_preset_apply.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(index) = target.and_then(|target| target.get::<u32>()) else {
+                    error!("Activated pen-preset-apply action without a valid target.");
+                    return;
+                };
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().apply_pen_preset(index as usize);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Remove a pen preset
+        action_pen_preset_remove.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(index) = target.and_then(|target| target.get::<u32>()) else {
+                    error!("Activated pen-preset-remove action without a valid target.");
+                    return;
+                };
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                canvas.engine_ref().remove_pen_preset(index as usize);
+                appwindow.overlays().penpresetbar().refresh_ui(&appwindow);
+            }
+        ));
+
         // Tab actions
         action_active_tab_move_left.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -382,6 +523,20 @@ impl RnAppWindow {
                 }
             }
         ));
+        action_active_tab_split.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                appwindow.overlays().split_view_open();
+            }
+        ));
+        action_split_view_close.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                appwindow.overlays().split_view_close();
+            }
+        ));
 
         // Drawing pad buttons
         action_drawing_pad_pressed_button_0.connect_activate(clone!(
@@ -483,6 +638,227 @@ impl RnAppWindow {
             }
         ));
 
+        // Copy the style of the selected stroke into the pen config
+        action_selection_copy_style.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().selection_copy_style();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Apply the style of the pen configs onto the selection
+        action_selection_paste_style.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().selection_paste_style();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Change the stroke color of the selection
+        action_selection_change_color.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let Some(color) = appwindow.choose_selection_color().await else {
+                            return;
+                        };
+                        let Some(canvas) = appwindow.active_tab_canvas() else {
+                            return;
+                        };
+                        let widget_flags =
+                            canvas.engine_mut().change_selection_stroke_colors(color);
+                        appwindow.handle_widget_flags(widget_flags, &canvas);
+                    }
+                ));
+            }
+        ));
+
+        // Change the fill color of the selection
+        action_selection_change_fill_color.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let Some(color) = appwindow.choose_selection_color().await else {
+                            return;
+                        };
+                        let Some(canvas) = appwindow.active_tab_canvas() else {
+                            return;
+                        };
+                        let widget_flags = canvas.engine_mut().change_selection_fill_colors(color);
+                        appwindow.handle_widget_flags(widget_flags, &canvas);
+                    }
+                ));
+            }
+        ));
+
+        // Bring the selection to the front
+        action_selection_bring_to_front.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().selection_bring_to_front();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Send the selection to the back
+        action_selection_send_to_back.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().selection_send_to_back();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Raise the selection one step
+        action_selection_raise.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().selection_raise();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Lower the selection one step
+        action_selection_lower.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().selection_lower();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Move the selection one layer up
+        action_selection_layer_up.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().selection_move_layer(true);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Move the selection one layer down
+        action_selection_layer_down.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().selection_move_layer(false);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Group the selection
+        action_selection_group.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().selection_group();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Dissolve the outermost groups of the selection
+        action_selection_ungroup.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().selection_ungroup();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Lock or unlock the selection
+        action_selection_locked.connect_change_state(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |action, state_request| {
+                let locked = state_request.unwrap().get::<bool>().unwrap();
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().set_selection_locked(locked);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+                action.set_state(&locked.to_variant());
+            }
+        ));
+
+        // Select the strokes similar to the selection
+        action_selection_select_similar.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().select_similar_strokes();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Select the strokes with the current stroke color
+        action_selection_select_by_color.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let color = appwindow
+                    .overlays()
+                    .colorpicker()
+                    .stroke_color()
+                    .into_compose_color();
+                let widget_flags = canvas.engine_mut().select_strokes_with_color(color);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
         // select all strokes
         action_selection_select_all.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -643,6 +1019,28 @@ impl RnAppWindow {
             }
         ));
 
+        // Zoom to a fixed zoom level
+        action_zoom_to.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(new_zoom) = target.and_then(|target| target.get::<f64>()) else {
+                    error!("Activated zoom-to action without a valid target.");
+                    return;
+                };
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let viewport_center = canvas.engine_ref().camera.viewport_center();
+                let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom);
+                widget_flags |= canvas
+                    .engine_mut()
+                    .camera
+                    .set_viewport_center(viewport_center);
+                appwindow.handle_widget_flags(widget_flags, &canvas)
+            }
+        ));
+
         // Zoom in
         action_zoomin.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -829,64 +1227,10 @@ impl RnAppWindow {
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
-                // TODO: Expose these variables as options in the print dialog
-                let draw_background = true;
-                let draw_pattern = true;
-                let optimize_printing = false;
-                let page_order = SplitOrder::default();
-                let margin = 0.0;
                 let Some(canvas) = appwindow.active_tab_canvas() else {
                     return;
                 };
-                let pages_content = canvas.engine_ref().extract_pages_content(page_order);
-                let n_pages = pages_content.len();
-
-                appwindow.overlays().progressbar_start_pulsing();
-
-                let print_op = PrintOperation::builder().unit(Unit::None).build();
-
-                print_op.connect_begin_print(clone!(move |print_op, _print_cx| {
-                    print_op.set_n_pages(n_pages as i32);
-                }));
-
-                print_op.connect_draw_page(clone!(move |_print_op, print_cx, page_no| {
-                    let page_content = &pages_content[page_no as usize];
-                    let page_bounds = page_content.bounds.unwrap().loosened(margin);
-                    let print_scale = (print_cx.width() / page_bounds.extents()[0])
-                        .min(print_cx.height() / page_bounds.extents()[1]);
-                    let cairo_cx = print_cx.cairo_context();
-
-                    cairo_cx.scale(print_scale, print_scale);
-                    cairo_cx.translate(-page_bounds.mins[0], -page_bounds.mins[1]);
-                    if let Err(e) = page_content.draw_to_cairo(
-                        &cairo_cx,
-                        draw_background,
-                        draw_pattern,
-                        optimize_printing,
-                        margin,
-                        Engine::STROKE_EXPORT_IMAGE_SCALE,
-                    ) {
-                        error!("Drawing page no: {page_no} while printing failed, Err: {e:?}");
-                    }
-                }));
-
-                print_op.connect_status_changed(clone!(move |print_op| {
-                    debug!(
-                        "Print operation status has changed to: {:?}",
-                        print_op.status()
-                    );
-                }));
-
-                // Run the print op
-                if let Err(e) = print_op.run(PrintOperationAction::PrintDialog, Some(&appwindow)) {
-                    error!("Running print operation failed , Err: {e:?}");
-                    appwindow
-                        .overlays()
-                        .dispatch_toast_error(&gettext("Printing document failed"));
-                    appwindow.overlays().progressbar_abort();
-                } else {
-                    appwindow.overlays().progressbar_finish();
-                }
+                dialogs::print::print_doc(&appwindow, &canvas);
             }
         ));
 
@@ -905,6 +1249,21 @@ impl RnAppWindow {
             }
         ));
 
+        // Import from URL
+        action_import_url.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::import::dialog_import_url(&appwindow, None, None).await;
+                    }
+                ));
+            }
+        ));
+
         // Export document
         action_export_doc.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -1086,51 +1445,48 @@ impl RnAppWindow {
         ));
     }
 
+    /// Let the user choose a color for the selection, starting from the color of the selection.
+    async fn choose_selection_color(&self) -> Option<Color> {
+        let initial_color = self
+            .active_tab_canvas()
+            .and_then(|canvas| canvas.engine_ref().selection_stroke_color())
+            .map(gdk::RGBA::from_compose_color);
+        match ColorDialog::new()
+            .choose_rgba_future(Some(self), initial_color.as_ref())
+            .await
+        {
+            Ok(rgba) => Some(rgba.into_compose_color()),
+            Err(e) => {
+                debug!("Choosing the color for the selection was cancelled or failed, Err: {e:?}");
+                None
+            }
+        }
+    }
+
     pub(crate) fn setup_action_accels(&self) {
-        let app = self.app();
-
-        app.set_accels_for_action("win.active-tab-close", &["<Ctrl>w"]);
-        app.set_accels_for_action("win.fullscreen", &["F11"]);
-        app.set_accels_for_action("win.keyboard-shortcuts", &["<Ctrl>question"]);
-        app.set_accels_for_action("win.toggle-overview", &["<Ctrl><Shift>o"]);
-        app.set_accels_for_action("win.open-canvasmenu", &["F9"]);
-        app.set_accels_for_action("win.open-appmenu", &["F10"]);
-        app.set_accels_for_action("win.open-doc", &["<Ctrl>o"]);
-        app.set_accels_for_action("win.save-doc", &["<Ctrl>s"]);
-        app.set_accels_for_action("win.save-doc-as", &["<Ctrl><Shift>s"]);
-        app.set_accels_for_action("win.new-tab", &["<Ctrl>t"]);
-        app.set_accels_for_action("win.snap-positions", &["<Ctrl><Shift>p"]);
-        app.set_accels_for_action("win.clear-doc", &["<Ctrl>l"]);
-        app.set_accels_for_action("win.print-doc", &["<Ctrl>p"]);
-        app.set_accels_for_action("win.add-page-to-doc", &["<Ctrl><Shift>a"]);
-        app.set_accels_for_action("win.remove-page-from-doc", &["<Ctrl><Shift>r"]);
-        app.set_accels_for_action(
-            "win.zoom-in",
-            &["<Ctrl>plus", "<Ctrl>equal", "<Ctrl>KP_Add"],
-        );
-        app.set_accels_for_action("win.zoom-reset", &["<Ctrl>0", "<Ctrl>KP_0"]);
-        app.set_accels_for_action("win.zoom-out", &["<Ctrl>minus", "<Ctrl>KP_Subtract"]);
-        app.set_accels_for_action("win.import-file", &["<Ctrl><Shift>i"]);
-        app.set_accels_for_action("win.undo", &["<Ctrl>z"]);
-        app.set_accels_for_action("win.redo", &["<Ctrl><Shift>z"]);
-        app.set_accels_for_action("win.clipboard-copy", &["<Ctrl>c"]);
-        app.set_accels_for_action("win.clipboard-cut", &["<Ctrl>x"]);
-        app.set_accels_for_action("win.clipboard-paste", &["<Ctrl>v"]);
-        app.set_accels_for_action("win.text-bold", &["<Ctrl>b"]);
-        app.set_accels_for_action("win.text-italic", &["<Ctrl>i"]);
-        app.set_accels_for_action("win.text-underline", &["<Ctrl>u"]);
-        app.set_accels_for_action("win.pen-style::brush", &["<Ctrl>1", "<Ctrl>KP_1"]);
-        app.set_accels_for_action("win.pen-style::shaper", &["<Ctrl>2", "<Ctrl>KP_2"]);
-        app.set_accels_for_action("win.pen-style::typewriter", &["<Ctrl>3", "<Ctrl>KP_3"]);
-        app.set_accels_for_action("win.pen-style::eraser", &["<Ctrl>4", "<Ctrl>KP_4"]);
-        app.set_accels_for_action("win.pen-style::selector", &["<Ctrl>5", "<Ctrl>KP_5"]);
-        app.set_accels_for_action("win.pen-style::tools", &["<Ctrl>6", "<Ctrl>KP_6"]);
+        // The editable keyboard shortcuts are applied by the app on startup,
+        // see `keyboardshortcuts::apply_accels()`.
+
         // shortcuts for devel build
         if config::PROFILE.to_lowercase().as_str() == "devel" {
-            app.set_accels_for_action("win.visual-debug", &["<Ctrl><Shift>v"]);
+            self.app()
+                .set_accels_for_action("win.visual-debug", &["<Ctrl><Shift>v"]);
         }
     }
 
+    /// How pasted or dropped in stroke content is resized to fit the document of the canvas.
+    pub(crate) fn stroke_content_size_option(&self, canvas: &RnCanvas) -> ImageSizeOption {
+        let engine = canvas.engine_ref();
+        ImageSizeOption::ResizeImage(Resize {
+            width: engine.document.config.format.width(),
+            height: engine.document.config.format.height(),
+            layout_fixed_width: engine.document.config.layout.is_fixed_width(),
+            max_viewpoint: None,
+            restrain_to_viewport: false,
+            respect_borders: self.respect_borders(),
+        })
+    }
+
     fn clipboard_paste(&self, target_pos: Option<na::Vector2<f64>>) {
         let content_formats = self.clipboard().formats();
         let Some(canvas) = self.active_tab_canvas() else {
@@ -1221,34 +1577,10 @@ impl RnAppWindow {
                             if !acc.is_empty() {
                                 match crate::utils::str_from_u8_nul_utf8(&acc) {
                                     Ok(json_string) => {
-                                        let resize_argument =
-                                            ImageSizeOption::ResizeImage(Resize {
-                                                width: canvas
-                                                    .engine_ref()
-                                                    .document
-                                                    .config
-                                                    .format
-                                                    .width(),
-                                                height: canvas
-                                                    .engine_ref()
-                                                    .document
-                                                    .config
-                                                    .format
-                                                    .height(),
-                                                layout_fixed_width: canvas
-                                                    .engine_ref()
-                                                    .document
-                                                    .config
-                                                    .layout
-                                                    .is_fixed_width(),
-                                                max_viewpoint: None,
-                                                restrain_to_viewport: false,
-                                                respect_borders: appwindow.respect_borders(),
-                                            });
                                         if let Err(e) = canvas
                                             .insert_stroke_content(
                                                 json_string.to_string(),
-                                                resize_argument,
+                                                appwindow.stroke_content_size_option(&canvas),
                                                 target_pos,
                                             )
                                             .await
@@ -1292,18 +1624,13 @@ impl RnAppWindow {
                             if !acc.is_empty() {
                                 match crate::utils::str_from_u8_nul_utf8(&acc) {
                                     Ok(text) => {
-                                        if let Err(e) = canvas
-                                            .load_in_vectorimage_bytes(
-                                                text.as_bytes().to_vec(),
-                                                target_pos,
-                                                appwindow.respect_borders(),
-                                            )
-                                            .await
-                                        {
-                                            error!(
-                                                "Loading VectorImage bytes failed while pasting as Svg failed, Err: {e:?}"
-                                            );
-                                        };
+                                        dialogs::dialog_paste_svg(
+                                            &appwindow,
+                                            &canvas,
+                                            text.as_bytes().to_vec(),
+                                            target_pos,
+                                        )
+                                        .await;
                                     }
                                     Err(e) => error!(
                                         "Failed to get string from clipboard data while pasting as Svg, Err: {e:?}"
@@ -1369,6 +1696,58 @@ impl RnAppWindow {
                     }
                 ));
             }
+        } else if content_formats.contain_mime_type("text/html") {
+            glib::spawn_future_local(clone!(
+                #[weak]
+                canvas,
+                #[weak(rename_to=appwindow)]
+                self,
+                async move {
+                    debug!("Recognized clipboard content: html text");
+
+                    match appwindow
+                        .clipboard()
+                        .read_future(&["text/html"], glib::source::Priority::DEFAULT)
+                        .await
+                    {
+                        Ok((input_stream, _)) => {
+                            let acc = collect_clipboard_data(input_stream).await;
+
+                            if !acc.is_empty() {
+                                // Some applications (e.g. Firefox) put UTF-16 encoded html with a BOM into the clipboard
+                                let html = match acc.strip_prefix(&[0xFF, 0xFE]) {
+                                    Some(utf16_bytes) => Ok(String::from_utf16_lossy(
+                                        &utf16_bytes
+                                            .chunks_exact(2)
+                                            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                                            .collect::<Vec<u16>>(),
+                                    )),
+                                    None => crate::utils::str_from_u8_nul_utf8(&acc)
+                                        .map(|html| html.to_string()),
+                                };
+                                match html {
+                                    Ok(html) => {
+                                        if let Err(e) = canvas.load_in_html_text(&html, target_pos)
+                                        {
+                                            error!(
+                                                "Failed to paste clipboard html text, Err: {e:?}"
+                                            );
+                                        }
+                                    }
+                                    Err(e) => error!(
+                                        "Failed to read html text from clipboard data, Err: {e:?}"
+                                    ),
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            error!(
+                                "Reading clipboard failed while pasting clipboard as html text, Err: {e:?}"
+                            );
+                        }
+                    }
+                }
+            ));
         } else if content_formats.contain_mime_type("text/plain")
             || content_formats.contain_mime_type("text/plain;charset=utf-8")
         {
@@ -1382,7 +1761,24 @@ impl RnAppWindow {
 
                     match appwindow.clipboard().read_text_future().await {
                         Ok(Some(text)) => {
-                            if let Err(e) = canvas.load_in_text(text.to_string(), target_pos) {
+                            if dialogs::import::http_url_from_text(&text).is_some() {
+                                dialogs::import::dialog_import_url(
+                                    &appwindow,
+                                    Some(text.to_string()),
+                                    target_pos,
+                                )
+                                .await;
+                            } else if let Some(table) = TableText::from_text(&text) {
+                                dialogs::dialog_paste_table(
+                                    &appwindow,
+                                    &canvas,
+                                    text.to_string(),
+                                    table,
+                                    target_pos,
+                                )
+                                .await;
+                            } else if let Err(e) = canvas.load_in_text(text.to_string(), target_pos)
+                            {
                                 error!("Failed to paste clipboard text, Err: {e:?}");
                             }
                         }
@@ -1429,3 +1825,14 @@ async fn collect_clipboard_data(input_stream: InputStream) -> Vec<u8> {
     }
     acc
 }
+
+fn pen_style_display_name(pen_style: PenStyle) -> String {
+    match pen_style {
+        PenStyle::Brush => gettext("Brush"),
+        PenStyle::Shaper => gettext("Shaper"),
+        PenStyle::Typewriter => gettext("Typewriter"),
+        PenStyle::Eraser => gettext("Eraser"),
+        PenStyle::Selector => gettext("Selector"),
+        PenStyle::Tools => gettext("Tools"),
+    }
+}
@@ -1,5 +1,6 @@
 // Imports
 use crate::appwindow::RnAppWindow;
+use crate::settingsprofiles::{self, SettingsProfile};
 use adw::{prelude::*, subclass::prelude::*};
 use gtk4::{gdk, glib, glib::clone};
 use tracing::error;
@@ -96,6 +97,57 @@ impl RnAppWindow {
             .get_no_changes()
             .build();
 
+        // touch gestures
+        for (key, row) in [
+            (
+                "touch-gesture-two-finger-tap",
+                self.sidebar()
+                    .settings_panel()
+                    .touch_gesture_two_finger_tap_row(),
+            ),
+            (
+                "touch-gesture-three-finger-tap",
+                self.sidebar()
+                    .settings_panel()
+                    .touch_gesture_three_finger_tap_row(),
+            ),
+            (
+                "touch-gesture-three-finger-swipe",
+                self.sidebar()
+                    .settings_panel()
+                    .touch_gesture_three_finger_swipe_row(),
+            ),
+        ] {
+            app_settings
+                .bind(key, &row, "active")
+                .get_no_changes()
+                .build();
+        }
+
+        // presentation
+        app_settings
+            .bind(
+                "presentation-lock-editing",
+                &self
+                    .sidebar()
+                    .settings_panel()
+                    .presentation_lock_editing_row(),
+                "active",
+            )
+            .get_no_changes()
+            .build();
+        app_settings
+            .bind(
+                "presentation-show-timer",
+                &self
+                    .sidebar()
+                    .settings_panel()
+                    .presentation_show_timer_row(),
+                "active",
+            )
+            .get_no_changes()
+            .build();
+
         // regular cursor
         app_settings
             .bind(
@@ -173,96 +225,6 @@ impl RnAppWindow {
             .set_mapping(gdk_color_set_mapping)
             .get_no_changes()
             .build();
-        app_settings
-            .bind(
-                "colorpicker-color-1",
-                &self.overlays().colorpicker().setter_1(),
-                "color",
-            )
-            .mapping(gdk_color_mapping)
-            .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
-            .build();
-        app_settings
-            .bind(
-                "colorpicker-color-2",
-                &self.overlays().colorpicker().setter_2(),
-                "color",
-            )
-            .mapping(gdk_color_mapping)
-            .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
-            .build();
-        app_settings
-            .bind(
-                "colorpicker-color-3",
-                &self.overlays().colorpicker().setter_3(),
-                "color",
-            )
-            .mapping(gdk_color_mapping)
-            .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
-            .build();
-        app_settings
-            .bind(
-                "colorpicker-color-4",
-                &self.overlays().colorpicker().setter_4(),
-                "color",
-            )
-            .mapping(gdk_color_mapping)
-            .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
-            .build();
-        app_settings
-            .bind(
-                "colorpicker-color-5",
-                &self.overlays().colorpicker().setter_5(),
-                "color",
-            )
-            .mapping(gdk_color_mapping)
-            .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
-            .build();
-        app_settings
-            .bind(
-                "colorpicker-color-6",
-                &self.overlays().colorpicker().setter_6(),
-                "color",
-            )
-            .mapping(gdk_color_mapping)
-            .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
-            .build();
-        app_settings
-            .bind(
-                "colorpicker-color-7",
-                &self.overlays().colorpicker().setter_7(),
-                "color",
-            )
-            .mapping(gdk_color_mapping)
-            .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
-            .build();
-        app_settings
-            .bind(
-                "colorpicker-color-8",
-                &self.overlays().colorpicker().setter_8(),
-                "color",
-            )
-            .mapping(gdk_color_mapping)
-            .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
-            .build();
-        app_settings
-            .bind(
-                "colorpicker-color-9",
-                &self.overlays().colorpicker().setter_9(),
-                "color",
-            )
-            .mapping(gdk_color_mapping)
-            .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
-            .build();
 
         // brush stroke widths
         app_settings
@@ -425,6 +387,28 @@ impl RnAppWindow {
                 .load_from_settings(&app_settings);
         }
 
+        {
+            // Workspace browser pinned folders, recent files
+            self.sidebar()
+                .workspacebrowser()
+                .load_from_settings(&app_settings);
+        }
+
+        {
+            // Colorpicker palettes
+            self.overlays()
+                .colorpicker()
+                .load_from_settings(&app_settings);
+        }
+
+        {
+            // Recent marker colors
+            self.overlays()
+                .penssidebar()
+                .brush_page()
+                .load_from_settings(&app_settings);
+        }
+
         {
             // Global config
             if let Err(err) = self.load_global_config_from_settings(&app_settings) {
@@ -468,6 +452,97 @@ impl RnAppWindow {
                 .save_to_settings(&app_settings);
         }
 
+        {
+            // Workspace browser pinned folders, recent files
+            self.sidebar()
+                .workspacebrowser()
+                .save_to_settings(&app_settings);
+        }
+
+        {
+            // Colorpicker palettes
+            self.overlays()
+                .colorpicker()
+                .save_to_settings(&app_settings);
+        }
+
+        {
+            // Recent marker colors
+            self.overlays()
+                .penssidebar()
+                .brush_page()
+                .save_to_settings(&app_settings);
+        }
+
+        Ok(())
+    }
+
+    /// Capture the current settings, including the engine configuration, as settings profile.
+    pub(crate) fn current_settings_profile(&self) -> anyhow::Result<SettingsProfile> {
+        let app_settings = self
+            .app()
+            .app_settings()
+            .ok_or_else(|| anyhow::anyhow!("Settings schema not found."))?;
+
+        // Settings that are not bound need to be written first
+        self.save_to_settings()?;
+        Ok(SettingsProfile::from_settings(&app_settings))
+    }
+
+    /// Apply a settings profile and reload all settings that are not bound.
+    pub(crate) fn apply_settings_profile(&self, profile: &SettingsProfile) -> anyhow::Result<()> {
+        let app = self.app();
+        let app_settings = app
+            .app_settings()
+            .ok_or_else(|| anyhow::anyhow!("Settings schema not found."))?;
+
+        // Save first, so that the settings which are not part of profiles are kept when reloading
+        self.save_to_settings()?;
+        profile.apply_to_settings(&app_settings)?;
+
+        self.app().activate_action(
+            "color-scheme",
+            Some(&app_settings.string("color-scheme").to_variant()),
+        );
+        self.sidebar()
+            .workspacebrowser()
+            .load_from_settings(&app_settings);
+        self.overlays()
+            .colorpicker()
+            .load_from_settings(&app_settings);
+        self.overlays()
+            .penssidebar()
+            .brush_page()
+            .load_from_settings(&app_settings);
+        self.load_global_config_from_settings(&app_settings)?;
+        crate::keyboardshortcuts::apply_accels(&app);
+        self.sidebar().settings_panel().refresh_ui(self);
+        Ok(())
+    }
+
+    /// Switch to the saved profile with the name.
+    ///
+    /// The current settings are saved to the previously active profile before switching, so that changes are kept.
+    pub(crate) fn switch_settings_profile(&self, name: &str) -> anyhow::Result<()> {
+        let app_settings = self
+            .app()
+            .app_settings()
+            .ok_or_else(|| anyhow::anyhow!("Settings schema not found."))?;
+        let profile = settingsprofiles::saved_profile(&app_settings, name)
+            .ok_or_else(|| anyhow::anyhow!("No saved settings profile with name `{name}`."))?;
+
+        if let Some(active_name) = settingsprofiles::active_profile_name(&app_settings)
+            && active_name != name
+            && settingsprofiles::saved_profile(&app_settings, &active_name).is_some()
+        {
+            settingsprofiles::save_profile(
+                &app_settings,
+                &active_name,
+                &self.current_settings_profile()?,
+            )?;
+        }
+        self.apply_settings_profile(&profile)?;
+        app_settings.set_string("active-settings-profile", name)?;
         Ok(())
     }
 
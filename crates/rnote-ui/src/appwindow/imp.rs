@@ -1,4 +1,5 @@
 // Imports
+use super::presentation::PresentationState;
 use crate::{RnMainHeader, RnOverlays, RnSidebar, config, dialogs};
 use adw::{prelude::*, subclass::prelude::*};
 use gettextrs::gettext;
@@ -29,15 +30,22 @@ pub(crate) struct RnAppWindow {
     pub(crate) respect_borders: Cell<bool>,
     pub(crate) touch_drawing: Cell<bool>,
     pub(crate) focus_mode: Cell<bool>,
+    pub(crate) ruler: Cell<bool>,
+    pub(crate) protractor: Cell<bool>,
+    pub(crate) presentation_mode: Cell<bool>,
+    pub(crate) editing_locked: Cell<bool>,
     pub(crate) devel_mode: Cell<bool>,
     pub(crate) visual_debug: Cell<bool>,
+    pub(crate) render_diagnostics: Cell<bool>,
 
     pub(crate) drawing_pad_controller: RefCell<Option<PadController>>,
     pub(crate) autosave_source_id: RefCell<Option<glib::SourceId>>,
     pub(crate) periodic_configsave_source_id: RefCell<Option<glib::SourceId>>,
+    pub(crate) recovery_source_id: RefCell<Option<glib::SourceId>>,
     pub(crate) save_in_progress: Cell<bool>,
     pub(crate) save_in_progress_toast: RefCell<Option<adw::Toast>>,
     pub(crate) close_in_progress: Cell<bool>,
+    pub(crate) presentation_state: RefCell<Option<PresentationState>>,
 
     #[template_child]
     pub(crate) overview: TemplateChild<adw::TabOverview>,
@@ -68,15 +76,22 @@ impl Default for RnAppWindow {
             respect_borders: Cell::new(false),
             touch_drawing: Cell::new(false),
             focus_mode: Cell::new(false),
+            ruler: Cell::new(false),
+            protractor: Cell::new(false),
+            presentation_mode: Cell::new(false),
+            editing_locked: Cell::new(false),
             devel_mode: Cell::new(false),
             visual_debug: Cell::new(false),
+            render_diagnostics: Cell::new(false),
 
             drawing_pad_controller: RefCell::new(None),
             autosave_source_id: RefCell::new(None),
             periodic_configsave_source_id: RefCell::new(None),
+            recovery_source_id: RefCell::new(None),
             save_in_progress: Cell::new(false),
             save_in_progress_toast: RefCell::new(None),
             close_in_progress: Cell::new(false),
+            presentation_state: RefCell::new(None),
 
             overview: TemplateChild::<adw::TabOverview>::default(),
             main_header: TemplateChild::<RnMainHeader>::default(),
@@ -172,12 +187,27 @@ impl ObjectImpl for RnAppWindow {
                 glib::ParamSpecBoolean::builder("focus-mode")
                     .default_value(false)
                     .build(),
+                glib::ParamSpecBoolean::builder("ruler")
+                    .default_value(false)
+                    .build(),
+                glib::ParamSpecBoolean::builder("protractor")
+                    .default_value(false)
+                    .build(),
+                glib::ParamSpecBoolean::builder("presentation-mode")
+                    .default_value(false)
+                    .build(),
+                glib::ParamSpecBoolean::builder("editing-locked")
+                    .default_value(false)
+                    .build(),
                 glib::ParamSpecBoolean::builder("devel-mode")
                     .default_value(false)
                     .build(),
                 glib::ParamSpecBoolean::builder("visual-debug")
                     .default_value(false)
                     .build(),
+                glib::ParamSpecBoolean::builder("render-diagnostics")
+                    .default_value(false)
+                    .build(),
                 glib::ParamSpecBoolean::builder("save-in-progress")
                     .default_value(false)
                     .build(),
@@ -198,8 +228,13 @@ impl ObjectImpl for RnAppWindow {
             "respect-borders" => self.respect_borders.get().to_value(),
             "touch-drawing" => self.touch_drawing.get().to_value(),
             "focus-mode" => self.focus_mode.get().to_value(),
+            "ruler" => self.ruler.get().to_value(),
+            "protractor" => self.protractor.get().to_value(),
+            "presentation-mode" => self.presentation_mode.get().to_value(),
+            "editing-locked" => self.editing_locked.get().to_value(),
             "devel-mode" => self.devel_mode.get().to_value(),
             "visual-debug" => self.visual_debug.get().to_value(),
+            "render-diagnostics" => self.render_diagnostics.get().to_value(),
             "save-in-progress" => self.save_in_progress.get().to_value(),
             _ => unimplemented!(),
         }
@@ -297,6 +332,41 @@ impl ObjectImpl for RnAppWindow {
                 self.overlays.penpicker().set_visible(!focus_mode);
                 self.overlays.colorpicker().set_visible(!focus_mode);
                 self.overlays.sidebar_box().set_visible(!focus_mode);
+                self.overlays.penpresetbar().set_visible(!focus_mode);
+            }
+            "ruler" => {
+                let ruler: bool = value.get().expect("The value needs to be of type `bool`");
+                self.ruler.replace(ruler);
+                if let Some(canvas) = obj.active_tab_canvas() {
+                    let widget_flags = canvas.engine_mut().set_ruler_visible(ruler);
+                    canvas.emit_handle_widget_flags(widget_flags);
+                }
+            }
+            "protractor" => {
+                let protractor: bool = value.get().expect("The value needs to be of type `bool`");
+                self.protractor.replace(protractor);
+                if let Some(canvas) = obj.active_tab_canvas() {
+                    let widget_flags = canvas.engine_mut().set_protractor_visible(protractor);
+                    canvas.emit_handle_widget_flags(widget_flags);
+                }
+            }
+            "presentation-mode" => {
+                let presentation_mode: bool =
+                    value.get().expect("The value needs to be of type `bool`");
+                self.presentation_mode.replace(presentation_mode);
+
+                if presentation_mode {
+                    obj.enter_presentation_mode();
+                } else {
+                    obj.leave_presentation_mode();
+                }
+            }
+            "editing-locked" => {
+                let editing_locked: bool =
+                    value.get().expect("The value needs to be of type `bool`");
+                self.editing_locked.replace(editing_locked);
+
+                obj.update_editing_actions(editing_locked);
             }
             "devel-mode" => {
                 let devel_mode = value
@@ -311,10 +381,13 @@ impl ObjectImpl for RnAppWindow {
                 // Enable the devel menu action to reveal it in the app menu
                 action_devel_menu.set_enabled(devel_mode);
 
-                // Always disable visual-debugging when disabling the developer mode
+                // Always disable visual-debugging and the render diagnostics when disabling the developer mode
                 if !devel_mode {
-                    debug!("Disabling developer mode, disabling visual debugging.");
+                    debug!(
+                        "Disabling developer mode, disabling visual debugging and render diagnostics."
+                    );
                     obj.set_visual_debug(false);
+                    obj.set_render_diagnostics(false);
                 }
             }
             "visual-debug" => {
@@ -327,6 +400,16 @@ impl ObjectImpl for RnAppWindow {
                     canvas.queue_draw();
                 }
             }
+            "render-diagnostics" => {
+                let render_diagnostics = value
+                    .get::<bool>()
+                    .expect("The value needs to be of type `bool`");
+                self.render_diagnostics.replace(render_diagnostics);
+                self.engine_config.write().render_diagnostics = render_diagnostics;
+                if let Some(canvas) = obj.active_tab_canvas() {
+                    canvas.queue_draw();
+                }
+            }
             "save-in-progress" => {
                 let save_in_progress = value
                     .get::<bool>()
@@ -347,6 +430,9 @@ impl WindowImpl for RnAppWindow {
             return glib::Propagation::Stop;
         }
 
+        // The document in the split pane is moved back to the tabs, where unsaved changes are handled.
+        obj.overlays().split_view_close();
+
         if obj.tabs_any_saves_in_progress() {
             obj.connect_notify_local(Some("save-in-progress"), move |appwindow, _| {
                 if !appwindow.save_in_progress() {
@@ -685,6 +771,7 @@ impl RnAppWindow {
                 .set_placement(CornerType::TopRight);
 
             obj.overlays().sidebar_box().set_halign(Align::Start);
+            obj.overlays().penpresetbar().set_halign(Align::End);
             obj.overlays()
                 .sidebar_scroller()
                 .set_placement(CornerType::TopRight);
@@ -802,6 +889,7 @@ impl RnAppWindow {
                 .set_placement(CornerType::TopLeft);
 
             obj.overlays().sidebar_box().set_halign(Align::End);
+            obj.overlays().penpresetbar().set_halign(Align::Start);
             obj.overlays()
                 .sidebar_scroller()
                 .set_placement(CornerType::TopLeft);
@@ -2,8 +2,10 @@
 mod actions;
 mod appsettings;
 mod imp;
+mod presentation;
 
 // Imports
+use crate::canvas::recovery::list_recovery_entries;
 use crate::{
     FileType, RnApp, RnCanvas, RnCanvasWrapper, RnMainHeader, RnOverlays, RnSidebar, config,
     dialogs, env,
@@ -11,18 +13,24 @@ use crate::{
 use adw::{prelude::*, subclass::prelude::*};
 use core::cell::{Ref, RefMut};
 use gettextrs::gettext;
-use gtk4::{Application, IconTheme, Widget, gdk, gio, glib};
+use gtk4::{Application, IconTheme, Widget, gdk, gio, glib, glib::clone};
 use rnote_compose::Color;
 use rnote_engine::document::DocumentConfig;
 use rnote_engine::engine::{EngineConfig, EngineConfigShared};
 use rnote_engine::ext::GdkRGBAExt;
+use rnote_engine::fileformats::rnoteformat::encryption;
 use rnote_engine::pens::PenStyle;
 use rnote_engine::pens::pensconfig::brushconfig::BrushStyle;
 use rnote_engine::pens::pensconfig::shaperconfig::ShaperStyle;
+use rnote_engine::storage::StoredDocument;
 use rnote_engine::{WidgetFlags, engine::EngineTask};
 use std::path::Path;
+use std::sync::atomic::AtomicBool;
 use tracing::{debug, error};
 
+/// Whether leftover recovery snapshots were already looked for in this session.
+static RECOVERY_CHECKED: AtomicBool = AtomicBool::new(false);
+
 glib::wrapper! {
     pub(crate) struct RnAppWindow(ObjectSubclass<imp::RnAppWindow>)
         @extends Widget, gtk4::Window, adw::Window, gtk4::ApplicationWindow, adw::ApplicationWindow,
@@ -150,6 +158,44 @@ impl RnAppWindow {
         self.set_property("focus-mode", focus_mode.to_value());
     }
 
+    #[allow(unused)]
+    pub(crate) fn ruler(&self) -> bool {
+        self.property::<bool>("ruler")
+    }
+
+    pub(crate) fn set_ruler(&self, ruler: bool) {
+        self.set_property("ruler", ruler.to_value());
+    }
+
+    #[allow(unused)]
+    pub(crate) fn protractor(&self) -> bool {
+        self.property::<bool>("protractor")
+    }
+
+    pub(crate) fn set_protractor(&self, protractor: bool) {
+        self.set_property("protractor", protractor.to_value());
+    }
+
+    #[allow(unused)]
+    pub(crate) fn presentation_mode(&self) -> bool {
+        self.property::<bool>("presentation-mode")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_presentation_mode(&self, presentation_mode: bool) {
+        self.set_property("presentation-mode", presentation_mode.to_value());
+    }
+
+    #[allow(unused)]
+    pub(crate) fn editing_locked(&self) -> bool {
+        self.property::<bool>("editing-locked")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_editing_locked(&self, editing_locked: bool) {
+        self.set_property("editing-locked", editing_locked.to_value());
+    }
+
     #[allow(unused)]
     pub(crate) fn devel_mode(&self) -> bool {
         self.property::<bool>("devel-mode")
@@ -170,6 +216,16 @@ impl RnAppWindow {
         self.set_property("visual-debug", visual_debug.to_value());
     }
 
+    #[allow(unused)]
+    pub(crate) fn render_diagnostics(&self) -> bool {
+        self.property::<bool>("render-diagnostics")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_render_diagnostics(&self, render_diagnostics: bool) {
+        self.set_property("render-diagnostics", render_diagnostics.to_value());
+    }
+
     #[allow(unused)]
     pub(crate) fn save_in_progress(&self) -> bool {
         self.property::<bool>("save-in-progress")
@@ -237,6 +293,22 @@ impl RnAppWindow {
         // An initial tab (canvas).
         self.add_initial_tab();
 
+        // Offer to restore documents with unsaved changes from a previous session, before writing any new
+        // recovery snapshots.
+        if !RECOVERY_CHECKED.swap(true, std::sync::atomic::Ordering::SeqCst) {
+            let recovery_entries = list_recovery_entries();
+            if !recovery_entries.is_empty() {
+                glib::spawn_future_local(clone!(
+                    #[weak(rename_to=appwindow)]
+                    self,
+                    async move {
+                        dialogs::dialog_restore_recovery(&appwindow, recovery_entries).await;
+                    }
+                ));
+            }
+        }
+        self.setup_recovery();
+
         // Anything that needs to be done right before showing the appwindow
 
         self.refresh_ui();
@@ -249,6 +321,41 @@ impl RnAppWindow {
         app_icon_theme.add_resource_path((String::from(config::APP_IDPATH) + "icons").as_str());
     }
 
+    /// Periodically writes recovery snapshots of all documents with unsaved changes.
+    fn setup_recovery(&self) {
+        if let Some(removed_id) = self.imp().recovery_source_id.borrow_mut().replace(
+            glib::source::timeout_add_seconds_local(
+                RnCanvas::RECOVERY_INTERVAL_SECS,
+                clone!(
+                    #[weak(rename_to=appwindow)]
+                    self,
+                    #[upgrade_or]
+                    glib::ControlFlow::Break,
+                    move || {
+                        for canvas in appwindow.get_all_tabs().iter().map(|w| w.canvas()) {
+                            if !canvas.unsaved_changes() || canvas.save_in_progress() {
+                                continue;
+                            }
+                            glib::spawn_future_local(clone!(
+                                #[weak]
+                                canvas,
+                                async move {
+                                    if let Err(e) = canvas.save_recovery().await {
+                                        error!("Writing recovery file failed, Err: {e:?}");
+                                    }
+                                }
+                            ));
+                        }
+
+                        glib::ControlFlow::Continue
+                    }
+                ),
+            ),
+        ) {
+            removed_id.remove();
+        }
+    }
+
     /// Called to close the window
     pub(crate) fn close_force(&self) {
         if self.app().settings_schema_found() {
@@ -264,6 +371,8 @@ impl RnAppWindow {
             .into_iter()
             .map(|p| p.child().downcast::<RnCanvasWrapper>().unwrap())
         {
+            // The user was already asked about unsaved changes
+            tab.canvas().remove_recovery();
             let _ = tab.canvas().engine_mut().set_active(false);
             tab.canvas()
                 .engine_ref()
@@ -320,6 +429,12 @@ impl RnAppWindow {
         if widget_flags.deselect_color_setters {
             self.overlays().colorpicker().deselect_setters();
         }
+        if widget_flags.open_radial_menu {
+            self.overlays().radialmenu().popup_at_pointer(self);
+        }
+        if widget_flags.refresh_layers {
+            self.sidebar().layers_panel().refresh_ui(self);
+        }
         if let Some(hide_undo) = widget_flags.hide_undo {
             self.overlays()
                 .penpicker()
@@ -335,6 +450,10 @@ impl RnAppWindow {
         if let Some(enable_text_preprocessing) = widget_flags.enable_text_preprocessing {
             canvas.set_text_preprocessing(enable_text_preprocessing);
         }
+        if let Some(render_progress) = widget_flags.render_progress {
+            self.overlays()
+                .progressbar_report(render_progress.fraction());
+        }
     }
 
     /// Get the active (selected) tab page.
@@ -346,10 +465,10 @@ impl RnAppWindow {
         self.imp().overlays.tabview().pages().n_items() as usize
     }
 
-    /// Returns a vector of all tabs of the current windows
+    /// Returns a vector of all tabs of the current windows, including the tab shown in the split pane.
     pub(crate) fn get_all_tabs(&self) -> Vec<RnCanvasWrapper> {
         let n_tabs = self.n_tabs_open();
-        let mut tabs = Vec::with_capacity(n_tabs);
+        let mut tabs = Vec::with_capacity(n_tabs + 1);
 
         for i in 0..n_tabs {
             let wrapper = self
@@ -366,6 +485,7 @@ impl RnAppWindow {
                 .unwrap();
             tabs.push(wrapper);
         }
+        tabs.extend(self.overlays().split_wrapper());
         tabs
     }
 
@@ -517,6 +637,14 @@ impl RnAppWindow {
     ///
     /// Closes the given tab when confirm is true, else reverts so that close_tab_request() can be called again.
     pub(crate) fn close_tab_finish(&self, tab_page: &adw::TabPage, confirm: bool) {
+        if confirm {
+            tab_page
+                .child()
+                .downcast::<RnCanvasWrapper>()
+                .unwrap()
+                .canvas()
+                .remove_recovery();
+        }
         self.overlays()
             .tabview()
             .close_page_finish(tab_page, confirm);
@@ -617,11 +745,48 @@ impl RnAppWindow {
                             (false, Some(active_wrapper)) => (false, active_wrapper),
                         };
 
-                    let (bytes, _) = input_file.load_bytes_future().await?;
-                    let widget_flags = wrapper
+                    let StoredDocument { bytes, revision } = wrapper
                         .canvas()
-                        .load_in_rnote_bytes(bytes.to_vec(), input_file.path())
+                        .storage_backends()
+                        .load(input_file.uri().as_str())
                         .await?;
+                    let widget_flags = if encryption::is_encrypted(&bytes) {
+                        // Ask for the password until it is correct or the dialog is cancelled
+                        let prev_password = wrapper.canvas().encryption_password();
+                        loop {
+                            let Some(password) = dialogs::dialog_unlock_document(self).await else {
+                                wrapper.canvas().set_encryption_password(prev_password);
+                                return Ok(false);
+                            };
+                            wrapper.canvas().set_encryption_password(Some(password));
+                            match wrapper
+                                .canvas()
+                                .load_in_rnote_bytes(bytes.to_vec(), input_file.path())
+                                .await
+                            {
+                                Ok(widget_flags) => break widget_flags,
+                                Err(e)
+                                    if matches!(
+                                        e.downcast_ref::<encryption::DecryptError>(),
+                                        Some(encryption::DecryptError::WrongPassword)
+                                    ) =>
+                                {
+                                    self.overlays()
+                                        .dispatch_toast_error(&gettext("Wrong password"));
+                                }
+                                Err(e) => {
+                                    wrapper.canvas().set_encryption_password(prev_password);
+                                    return Err(e);
+                                }
+                            }
+                        }
+                    } else {
+                        wrapper
+                            .canvas()
+                            .load_in_rnote_bytes(bytes, input_file.path())
+                            .await?
+                    };
+                    wrapper.canvas().set_output_file_revision(revision);
                     if rnote_file_new_tab {
                         self.append_wrapper_new_tab(&wrapper);
                     }
@@ -712,6 +877,8 @@ impl RnAppWindow {
             .selector_page()
             .refresh_ui(self);
         self.overlays().penssidebar().tools_page().refresh_ui(self);
+        self.overlays().penpresetbar().refresh_ui(self);
+        self.sidebar().layers_panel().refresh_ui(self);
         self.sidebar().settings_panel().refresh_ui(self);
 
         if let Some(canvas) = canvas {
@@ -720,11 +887,14 @@ impl RnAppWindow {
             // Avoids already borrowed
             let pen_style = canvas.engine_ref().current_pen_style_w_override();
             let pen_sounds = canvas.engine_ref().pen_sounds();
+            let ruler = canvas.engine_ref().ruler_visible();
+            let protractor = canvas.engine_ref().protractor_visible();
             let snap_positions = self.engine_config().read().snap_positions;
             let total_zoom = canvas.engine_ref().camera.total_zoom();
             let can_undo = canvas.engine_ref().can_undo();
             let can_redo = canvas.engine_ref().can_redo();
             let visual_debug = self.engine_config().read().visual_debug;
+            let render_diagnostics = self.engine_config().read().render_diagnostics;
 
             self.overlays()
                 .penpicker()
@@ -739,8 +909,11 @@ impl RnAppWindow {
                 .refresh_zoom_reset_label(total_zoom);
             self.set_pen_style(pen_style);
             self.set_pen_sounds(pen_sounds);
+            self.set_ruler(ruler);
+            self.set_protractor(protractor);
             self.set_snap_positions(snap_positions);
             self.set_visual_debug(visual_debug);
+            self.set_render_diagnostics(render_diagnostics);
 
             // Current pen
             match pen_style {
@@ -814,6 +987,32 @@ impl RnAppWindow {
                                 .colorpicker()
                                 .set_stroke_color(gdk::RGBA::from_compose_color(stroke_color));
                         }
+                        BrushStyle::Airbrush => {
+                            let stroke_color = self
+                                .engine_config()
+                                .read()
+                                .pens_config
+                                .brush_config
+                                .airbrush_options
+                                .stroke_color
+                                .unwrap_or(Color::TRANSPARENT);
+                            self.overlays()
+                                .colorpicker()
+                                .set_stroke_color(gdk::RGBA::from_compose_color(stroke_color));
+                        }
+                        BrushStyle::Stamp => {
+                            let stroke_color = self
+                                .engine_config()
+                                .read()
+                                .pens_config
+                                .brush_config
+                                .stamp_options
+                                .stroke_color
+                                .unwrap_or(Color::TRANSPARENT);
+                            self.overlays()
+                                .colorpicker()
+                                .set_stroke_color(gdk::RGBA::from_compose_color(stroke_color));
+                        }
                     }
                 }
                 PenStyle::Shaper => {
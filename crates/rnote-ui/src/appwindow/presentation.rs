@@ -0,0 +1,235 @@
+// Imports
+use super::RnAppWindow;
+use adw::{prelude::*, subclass::prelude::*};
+use gtk4::{EventControllerKey, PropagationPhase, gdk, gio, glib, glib::clone};
+use rnote_engine::pens::PenStyle;
+use rnote_engine::pens::pensconfig::toolsconfig::ToolStyle;
+use std::time::Instant;
+use tracing::debug;
+
+/// The window actions that modify the document, disabled while the editing is locked.
+const EDITING_ACTIONS: &[&str] = &[
+    "undo",
+    "redo",
+    "clear-doc",
+    "add-page-to-doc",
+    "remove-page-from-doc",
+    "resize-to-fit-content",
+    "selection-trash",
+    "selection-duplicate",
+    "selection-invert-color",
+    "selection-paste-style",
+    "selection-change-color",
+    "selection-change-fill-color",
+    "selection-bring-to-front",
+    "selection-send-to-back",
+    "selection-raise",
+    "selection-lower",
+    "selection-layer-up",
+    "selection-layer-down",
+    "selection-group",
+    "selection-ungroup",
+    "selection-locked",
+    "clipboard-cut",
+    "clipboard-paste",
+    "clipboard-paste-contextmenu",
+    "import-file",
+    "import-url",
+    "text-bold",
+    "text-italic",
+    "text-underline",
+    "pen-preset-apply",
+];
+
+/// The state of the window before the presentation mode was entered, restored when leaving it.
+#[derive(Debug)]
+pub(crate) struct PresentationState {
+    fullscreened: bool,
+    focus_mode: bool,
+    show_sidebar: bool,
+    pen_style: PenStyle,
+    tool_style: ToolStyle,
+    key_controller: EventControllerKey,
+    timer_source_id: Option<glib::SourceId>,
+}
+
+impl RnAppWindow {
+    /// Hide the window chrome, switch to the laser pointer and start listening for the page navigation keys.
+    pub(super) fn enter_presentation_mode(&self) {
+        let imp = self.imp();
+        if imp.presentation_state.borrow().is_some() {
+            return;
+        }
+        debug!("Entering presentation mode");
+        let settings_panel = self.sidebar().settings_panel();
+        let pen_style = self
+            .active_tab_canvas()
+            .map(|c| c.engine_ref().current_pen_style_w_override())
+            .unwrap_or_default();
+        let tool_style = self.engine_config().read().pens_config.tools_config.style;
+
+        let key_controller = EventControllerKey::builder()
+            .name("presentation_key_controller")
+            .propagation_phase(PropagationPhase::Capture)
+            .build();
+        key_controller.connect_key_pressed(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            #[upgrade_or]
+            glib::Propagation::Proceed,
+            move |_, key, _, modifiers| appwindow.handle_presentation_key(key, modifiers)
+        ));
+        self.add_controller(key_controller.clone());
+
+        let timer_source_id = settings_panel
+            .presentation_show_timer_row()
+            .is_active()
+            .then(|| self.start_presentation_timer());
+
+        imp.presentation_state.replace(Some(PresentationState {
+            fullscreened: self.is_fullscreen(),
+            focus_mode: self.focus_mode(),
+            show_sidebar: self.split_view().shows_sidebar(),
+            pen_style,
+            tool_style,
+            key_controller,
+            timer_source_id,
+        }));
+
+        self.fullscreen();
+        self.set_focus_mode(true);
+        self.main_header().set_visible(false);
+        imp.tabbar.set_visible(false);
+        self.split_view().set_show_sidebar(false);
+
+        self.overlays()
+            .penssidebar()
+            .tools_page()
+            .set_tool_style(ToolStyle::Laser);
+        adw::prelude::ActionGroupExt::activate_action(
+            self,
+            "pen-style",
+            Some(&PenStyle::Tools.to_string().to_variant()),
+        );
+        self.set_editing_locked(settings_panel.presentation_lock_editing_row().is_active());
+    }
+
+    /// Restore the window to the state before the presentation mode was entered.
+    pub(super) fn leave_presentation_mode(&self) {
+        let imp = self.imp();
+        let Some(state) = imp.presentation_state.take() else {
+            return;
+        };
+        debug!("Leaving presentation mode");
+
+        self.remove_controller(&state.key_controller);
+        if let Some(timer_source_id) = state.timer_source_id {
+            timer_source_id.remove();
+        }
+        self.overlays()
+            .presentation_timer_label()
+            .set_visible(false);
+
+        self.set_editing_locked(false);
+        self.overlays()
+            .penssidebar()
+            .tools_page()
+            .set_tool_style(state.tool_style);
+        adw::prelude::ActionGroupExt::activate_action(
+            self,
+            "pen-style",
+            Some(&state.pen_style.to_string().to_variant()),
+        );
+
+        self.split_view().set_show_sidebar(state.show_sidebar);
+        imp.tabbar.set_visible(true);
+        self.main_header().set_visible(true);
+        self.set_focus_mode(state.focus_mode);
+        if !state.fullscreened {
+            self.unfullscreen();
+        }
+    }
+
+    /// The page navigation keys of the keyboard and of presentation clickers.
+    ///
+    /// The arrow keys, space and backspace are only used for navigating when the editing is locked, otherwise they are
+    /// left to the pens.
+    fn handle_presentation_key(
+        &self,
+        key: gdk::Key,
+        modifiers: gdk::ModifierType,
+    ) -> glib::Propagation {
+        // Dialogs receive their key events through the window
+        if self.visible_dialog().is_some()
+            || modifiers.intersects(
+                gdk::ModifierType::CONTROL_MASK
+                    | gdk::ModifierType::ALT_MASK
+                    | gdk::ModifierType::SUPER_MASK,
+            )
+        {
+            return glib::Propagation::Proceed;
+        }
+        let editing_locked = self.editing_locked();
+        let pages = match key {
+            gdk::Key::Escape => {
+                self.set_presentation_mode(false);
+                return glib::Propagation::Stop;
+            }
+            gdk::Key::Page_Down | gdk::Key::KP_Next => 1,
+            gdk::Key::Page_Up | gdk::Key::KP_Prior => -1,
+            gdk::Key::Right | gdk::Key::Down | gdk::Key::space | gdk::Key::n if editing_locked => 1,
+            gdk::Key::Left | gdk::Key::Up | gdk::Key::BackSpace | gdk::Key::p if editing_locked => {
+                -1
+            }
+            _ => return glib::Propagation::Proceed,
+        };
+        if let Some(canvas) = self.active_tab_canvas() {
+            let widget_flags = canvas.engine_mut().camera_move_pages(pages);
+            self.handle_widget_flags(widget_flags, &canvas);
+        }
+        glib::Propagation::Stop
+    }
+
+    fn start_presentation_timer(&self) -> glib::SourceId {
+        let timer_label = self.overlays().presentation_timer_label();
+        let start = Instant::now();
+        timer_label.set_label(&format_elapsed(0));
+        timer_label.set_visible(true);
+
+        glib::timeout_add_seconds_local(
+            1,
+            clone!(
+                #[weak]
+                timer_label,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move || {
+                    timer_label.set_label(&format_elapsed(start.elapsed().as_secs()));
+                    glib::ControlFlow::Continue
+                }
+            ),
+        )
+    }
+
+    /// Disable or re-enable the window actions that modify the document.
+    pub(super) fn update_editing_actions(&self, editing_locked: bool) {
+        for name in EDITING_ACTIONS {
+            if let Some(action) = self
+                .lookup_action(name)
+                .and_then(|a| a.downcast::<gio::SimpleAction>().ok())
+            {
+                action.set_enabled(!editing_locked);
+            }
+        }
+    }
+}
+
+/// Format the elapsed seconds as `MM:SS`, or `H:MM:SS` after the first hour.
+fn format_elapsed(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, (secs / 60) % 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes:02}:{seconds:02}")
+    }
+}
@@ -23,3 +23,18 @@ impl WidgetFlagsBoxed {
         self.0
     }
 }
+
+/// Serialized stroke content, used to drag selections between canvases of the app.
+#[derive(Clone, Debug, PartialEq, Eq, glib::Boxed)]
+#[boxed_type(name = "StrokeContentBoxed")]
+pub(crate) struct StrokeContentBoxed(String);
+
+impl StrokeContentBoxed {
+    pub(crate) fn new(json_string: String) -> Self {
+        Self(json_string)
+    }
+
+    pub(crate) fn inner(self) -> String {
+        self.0
+    }
+}
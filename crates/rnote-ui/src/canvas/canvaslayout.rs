@@ -5,7 +5,7 @@ use gtk4::{
 };
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::ext::AabbExt;
-use rnote_engine::{Camera, image};
+use rnote_engine::Camera;
 use std::cell::Cell;
 
 mod imp {
@@ -86,8 +86,9 @@ mod imp {
             // start to get rendered. This avoids stutters, because while the rendering itself is on worker threads, we
             // still have to `integrate` the resulted textures, which can also take up quite some time on the main UI
             // thread.
-            let old_viewport_extended = old_viewport
-                .extend_by(old_viewport.extents() * image::VIEWPORT_EXTENTS_MARGIN_FACTOR * 0.8);
+            let prerender_margin = canvas.engine_ref().prerender_margin();
+            let old_viewport_extended =
+                old_viewport.extend_by(old_viewport.extents() * prerender_margin.factor() * 0.8);
 
             // always update the background rendering
             let _ = canvas
@@ -1,21 +1,24 @@
 // Imports
 use super::RnCanvas;
-use crate::RnAppWindow;
+use crate::{FileType, RnAppWindow};
 use anyhow::Context;
-use futures::AsyncWriteExt;
 use futures::channel::oneshot;
-use gtk4::{gio, prelude::*};
+use gtk4::{gio, glib, prelude::*};
 use rnote_compose::ext::Vector2Ext;
 use rnote_engine::WidgetFlags;
 use rnote_engine::engine::export::{DocExportPrefs, DocPagesExportPrefs, SelectionExportPrefs};
 use rnote_engine::engine::{EngineSnapshot, StrokeContent};
+use rnote_engine::fileformats::rnoteformat::encryption;
+use rnote_engine::fileformats::tableformat::TableText;
 use rnote_engine::strokes::Stroke;
 use rnote_engine::strokes::resize::ImageSizeOption;
-use std::ops::Range;
 use std::path::Path;
 use tracing::{debug, error};
 
 impl RnCanvas {
+    /// The maximum size of content that gets fetched when importing from an URL.
+    pub(crate) const URL_IMPORT_MAX_SIZE: usize = 64 * 1024 * 1024;
+
     /// Load the bytes of a `.rnote` file and imports it into the engine.
     ///
     /// `file_path` is optional but needs to be supplied when the origin file should be tracked.
@@ -30,13 +33,23 @@ impl RnCanvas {
     where
         P: AsRef<Path>,
     {
-        let engine_snapshot = EngineSnapshot::load_from_rnote_bytes(bytes).await?;
+        let engine_snapshot = if encryption::is_encrypted(&bytes) {
+            let password = self
+                .encryption_password()
+                .ok_or_else(|| anyhow::anyhow!("The file is encrypted, but no password is set."))?;
+            EngineSnapshot::load_from_encrypted_rnote_bytes(bytes, password).await?
+        } else {
+            self.set_encryption_password(None);
+            EngineSnapshot::load_from_rnote_bytes(bytes).await?
+        };
         let mut widget_flags = self.engine_mut().load_snapshot(engine_snapshot);
         widget_flags |= self
             .engine_mut()
             .set_scale_factor(self.scale_factor() as f64);
 
         self.set_output_file(file_path.map(gio::File::for_path));
+        // Set by the caller when the bytes are loaded from the storage
+        self.set_output_file_revision(None);
         self.dismiss_output_file_modified_toast();
         self.set_unsaved_changes(false);
         self.set_empty(false);
@@ -53,10 +66,14 @@ impl RnCanvas {
                 "Failed to reload file from disk, no file path saved."
             ));
         };
-        let (bytes, _) = output_file.load_bytes_future().await?;
+        let stored_document = self
+            .storage_backends()
+            .load(output_file.uri().as_str())
+            .await?;
         let widget_flags = self
-            .load_in_rnote_bytes(bytes.to_vec(), output_file.path())
+            .load_in_rnote_bytes(stored_document.bytes, output_file.path())
             .await?;
+        self.set_output_file_revision(stored_document.revision);
         self.emit_handle_widget_flags(widget_flags);
         Ok(())
     }
@@ -107,6 +124,27 @@ impl RnCanvas {
         Ok(())
     }
 
+    /// Loads in bytes from a Svg and imports its content as editable strokes.
+    ///
+    /// `target_pos` is in coordinate space of the doc.
+    pub(crate) async fn load_in_svg_strokes_bytes(
+        &self,
+        bytes: Vec<u8>,
+        target_pos: Option<na::Vector2<f64>>,
+        respect_borders: bool,
+    ) -> anyhow::Result<()> {
+        let pos = self.determine_stroke_import_pos(target_pos);
+
+        let strokes_receiver =
+            self.engine_mut()
+                .generate_strokes_from_svg_bytes(pos, bytes, respect_borders);
+        let strokes = strokes_receiver.await??;
+        let widget_flags = self.engine_mut().import_generated_content(strokes, false);
+
+        self.emit_handle_widget_flags(widget_flags);
+        Ok(())
+    }
+
     /// Loads in bytes from a bitmap image and imports it.
     ///
     /// `target_pos` is in coordinate space of the doc.
@@ -120,7 +158,7 @@ impl RnCanvas {
 
         let bitmapimage_receiver =
             self.engine_mut()
-                .generate_bitmapimage_from_bytes(pos, bytes, respect_borders);
+                .generate_bitmapimage_from_bytes(pos, bytes, respect_borders, None);
         let bitmapimage = bitmapimage_receiver.await??;
         let widget_flags = self
             .engine_mut()
@@ -138,7 +176,7 @@ impl RnCanvas {
         appwindow: &RnAppWindow,
         bytes: Vec<u8>,
         target_pos: Option<na::Vector2<f64>>,
-        page_range: Option<Range<usize>>,
+        page_indices: Option<Vec<usize>>,
         password: Option<String>,
     ) -> anyhow::Result<()> {
         let pos = self.determine_stroke_import_pos(target_pos);
@@ -149,13 +187,93 @@ impl RnCanvas {
             .pdf_import_prefs
             .adjust_document;
 
-        let strokes_receiver = self
-            .engine_mut()
-            .generate_pdf_pages_from_bytes(bytes, pos, page_range, password);
+        let pdf_bytes = bytes.clone();
+        let strokes_receiver = self.engine_mut().generate_pdf_pages_from_bytes(
+            bytes,
+            pos,
+            page_indices.clone(),
+            password,
+        );
         let strokes = strokes_receiver.await??;
-        let widget_flags = self
-            .engine_mut()
-            .import_generated_content(strokes, adjust_document);
+        let widget_flags = self.engine_mut().import_generated_pdf_pages(
+            strokes,
+            adjust_document,
+            pdf_bytes,
+            page_indices,
+        );
+
+        self.emit_handle_widget_flags(widget_flags);
+        Ok(())
+    }
+
+    /// Fetches an image or a Pdf from the URL and imports it.
+    ///
+    /// Fetching is aborted when the content exceeds [Self::URL_IMPORT_MAX_SIZE]. The URL is stored as source in the
+    /// imported image strokes.
+    ///
+    /// `target_pos` is in coordinate space of the doc.
+    pub(crate) async fn load_in_url(
+        &self,
+        appwindow: &RnAppWindow,
+        url: &str,
+        target_pos: Option<na::Vector2<f64>>,
+    ) -> anyhow::Result<()> {
+        let bytes = fetch_url_bytes(url, Self::URL_IMPORT_MAX_SIZE).await?;
+        let pos = self.determine_stroke_import_pos(target_pos);
+        let respect_borders = appwindow.respect_borders();
+
+        let mut pdf_bytes = None;
+        let (mut strokes, adjust_document) = match FileType::lookup_bytes_type(&bytes) {
+            FileType::VectorImageFile => {
+                let vectorimage_receiver =
+                    self.engine_mut()
+                        .generate_vectorimage_from_bytes(pos, bytes, respect_borders);
+                let vectorimage = vectorimage_receiver.await??;
+                (vec![(Stroke::VectorImage(vectorimage), None)], false)
+            }
+            FileType::BitmapImageFile => {
+                let bitmapimage_receiver = self.engine_mut().generate_bitmapimage_from_bytes(
+                    pos,
+                    bytes,
+                    respect_borders,
+                    None,
+                );
+                let bitmapimage = bitmapimage_receiver.await??;
+                (vec![(Stroke::BitmapImage(bitmapimage), None)], false)
+            }
+            FileType::PdfFile => {
+                let adjust_document = appwindow
+                    .engine_config()
+                    .read()
+                    .import_prefs
+                    .pdf_import_prefs
+                    .adjust_document;
+                pdf_bytes = Some(bytes.clone());
+                let strokes_receiver = self
+                    .engine_mut()
+                    .generate_pdf_pages_from_bytes(bytes, pos, None, None);
+                (strokes_receiver.await??, adjust_document)
+            }
+            file_type => {
+                return Err(anyhow::anyhow!(
+                    "Content fetched from URL '{url}' has unsupported file type '{file_type:?}'"
+                ));
+            }
+        };
+        for (stroke, _) in strokes.iter_mut() {
+            stroke.set_source_url(Some(url.to_string()));
+        }
+        let widget_flags = match pdf_bytes {
+            Some(pdf_bytes) => self.engine_mut().import_generated_pdf_pages(
+                strokes,
+                adjust_document,
+                pdf_bytes,
+                None,
+            ),
+            None => self
+                .engine_mut()
+                .import_generated_content(strokes, adjust_document),
+        };
 
         self.emit_handle_widget_flags(widget_flags);
         Ok(())
@@ -177,6 +295,32 @@ impl RnCanvas {
         Ok(())
     }
 
+    pub(crate) fn load_in_html_text(
+        &self,
+        html: &str,
+        target_pos: Option<na::Vector2<f64>>,
+    ) -> anyhow::Result<()> {
+        let pos = self.determine_stroke_import_pos(target_pos);
+
+        let widget_flags = self.engine_mut().insert_html_text(html, Some(pos));
+
+        self.emit_handle_widget_flags(widget_flags);
+        Ok(())
+    }
+
+    pub(crate) fn load_in_table_text(
+        &self,
+        table: TableText,
+        target_pos: Option<na::Vector2<f64>>,
+    ) -> anyhow::Result<()> {
+        let pos = self.determine_stroke_import_pos(target_pos);
+
+        let widget_flags = self.engine_mut().insert_table_text(table, pos);
+
+        self.emit_handle_widget_flags(widget_flags);
+        Ok(())
+    }
+
     /// Deserializes the stroke content and inserts it into the engine.
     ///
     /// The data is usually coming from the clipboard, drop source, etc.
@@ -231,43 +375,44 @@ impl RnCanvas {
             self.set_save_in_progress(false);
             anyhow::anyhow!("Could not retrieve basename for file: `{file:?}`.")
         })?;
-        let rnote_bytes_receiver = self
-            .engine_ref()
-            .save_as_rnote_bytes(basename.to_string_lossy().to_string());
+        let file_name = basename.to_string_lossy().to_string();
+        let rnote_bytes_receiver = match self.encryption_password() {
+            Some(password) => self
+                .engine_ref()
+                .save_as_encrypted_rnote_bytes(file_name, password),
+            None => self.engine_ref().save_as_rnote_bytes(file_name),
+        };
         let mut skip_set_output_file = false;
         if let Some(output_file_path) = self.output_file().and_then(|f| f.path())
             && crate::utils::paths_abs_eq(output_file_path, &file_path).unwrap_or(false)
         {
             skip_set_output_file = true;
         }
+        // Only the file that was loaded or saved before is checked for changes in the meantime
+        let expected_revision = if skip_set_output_file {
+            self.output_file_revision()
+        } else {
+            None
+        };
         self.dismiss_output_file_modified_toast();
 
         let file_write_operation = async move {
             let bytes = rnote_bytes_receiver.await??;
             self.set_output_file_expect_write(true);
-            let mut write_file = async_fs::OpenOptions::new()
-                .create(true)
-                .truncate(true)
-                .write(true)
-                .open(&file_path)
-                .await
-                .context(format!(
-                    "Failed to create/open/truncate file for path '{}'",
-                    file_path.display()
-                ))?;
             if !skip_set_output_file {
                 // this installs the file watcher.
                 self.set_output_file(Some(file.to_owned()));
             }
-            write_file.write_all(&bytes).await.context(format!(
-                "Failed to write bytes to file with path '{}'",
-                file_path.display()
-            ))?;
-            write_file.sync_all().await.context(format!(
-                "Failed to sync file after writing with path '{}'",
-                file_path.display()
-            ))?;
-            Ok(())
+            let revision = self
+                .storage_backends()
+                .save(file.uri().as_str(), bytes, expected_revision.as_ref())
+                .await
+                .context(format!(
+                    "Failed to save document to file with path '{}'",
+                    file_path.display()
+                ))?;
+            self.set_output_file_revision(revision);
+            anyhow::Ok(())
         };
 
         if let Err(e) = file_write_operation.await {
@@ -281,6 +426,7 @@ impl RnCanvas {
         debug!("Saving file has finished successfully");
         self.set_unsaved_changes(false);
         self.set_save_in_progress(false);
+        self.remove_recovery();
 
         Ok(true)
     }
@@ -317,26 +463,72 @@ impl RnCanvas {
                 "Supplied target file `{dir:?}` is not a directory."
             ));
         }
-        let export_prefs = export_prefs_override.unwrap_or(
+        let export_prefs = export_prefs_override.clone().unwrap_or_else(|| {
             appwindow
                 .engine_config()
                 .read()
                 .export_prefs
-                .doc_pages_export_prefs,
-        );
+                .doc_pages_export_prefs
+                .clone()
+        });
         let file_ext = export_prefs.export_format.file_ext();
 
+        if export_prefs.exports_single_file() {
+            let export_bytes = self
+                .engine_ref()
+                .export_doc_pages_as_multipage_tiff_bytes(export_prefs_override)
+                .await??;
+            crate::utils::create_replace_file_future(
+                export_bytes,
+                &dir.child(file_stem_name + "." + &file_ext),
+            )
+            .await?;
+            self.set_last_export_dir(Some(dir.clone()));
+            return Ok(());
+        }
+
+        let pages_amount = self
+            .engine_ref()
+            .pages_bounds_w_content(export_prefs.page_order)
+            .len();
+        let page_numbers = export_prefs
+            .page_selection
+            .selected_indices(pages_amount)
+            .into_iter()
+            .map(|i| i + 1);
+
+        let page_file_stem = |page_number: usize| {
+            rnote_engine::utils::doc_pages_files_names_w_pattern(
+                &export_prefs.page_files_naming_pattern,
+                &file_stem_name,
+                page_number,
+                pages_amount,
+            )
+        };
+
+        if export_prefs.exports_tiles() {
+            let tiled_pages = self
+                .engine_ref()
+                .export_doc_pages_as_bitmap_tiles(export_prefs_override)
+                .await??;
+            for (page_number, tiled_page) in page_numbers.zip(tiled_pages) {
+                for (file_name, bytes) in
+                    tiled_page.into_files(&page_file_stem(page_number), &file_ext)?
+                {
+                    crate::utils::create_replace_file_future(bytes, &dir.child(file_name)).await?;
+                }
+            }
+            self.set_last_export_dir(Some(dir.clone()));
+            return Ok(());
+        }
+
         let export_bytes_recv = self.engine_ref().export_doc_pages(export_prefs_override);
         let export_bytes = export_bytes_recv.await??;
 
-        for (i, page_bytes) in export_bytes.into_iter().enumerate() {
+        for (page_number, page_bytes) in page_numbers.zip(export_bytes) {
             crate::utils::create_replace_file_future(
                 page_bytes,
-                &dir.child(
-                    &(rnote_engine::utils::doc_pages_files_names(file_stem_name.clone(), i + 1)
-                        + "."
-                        + &file_ext),
-                ),
+                &dir.child(page_file_stem(page_number) + "." + &file_ext),
             )
             .await?;
         }
@@ -348,9 +540,47 @@ impl RnCanvas {
 
     pub(crate) async fn export_selection(
         &self,
+        appwindow: &RnAppWindow,
         file: &gio::File,
         export_prefs_override: Option<SelectionExportPrefs>,
     ) -> anyhow::Result<()> {
+        let exports_tiles = export_prefs_override
+            .unwrap_or(
+                appwindow
+                    .engine_config()
+                    .read()
+                    .export_prefs
+                    .selection_export_prefs,
+            )
+            .exports_tiles();
+        if exports_tiles {
+            // The tiles and the manifest are written next to the chosen file
+            let (Some(dir), Some(file_name)) = (file.parent(), file.basename()) else {
+                return Err(anyhow::anyhow!(
+                    "Supplied target file `{file:?}` has no parent directory or file name."
+                ));
+            };
+            let file_stem = file_name
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let file_ext = file_name
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if let Some(tiled_bitmap) = self
+                .engine_ref()
+                .export_selection_as_bitmap_tiles(export_prefs_override)
+                .await??
+            {
+                for (file_name, bytes) in tiled_bitmap.into_files(&file_stem, &file_ext)? {
+                    crate::utils::create_replace_file_future(bytes, &dir.child(file_name)).await?;
+                }
+            }
+            self.set_last_export_dir(Some(dir));
+            return Ok(());
+        }
+
         let export_bytes = self.engine_ref().export_selection(export_prefs_override);
 
         if let Some(export_bytes) = export_bytes.await?? {
@@ -362,6 +592,26 @@ impl RnCanvas {
         Ok(())
     }
 
+    /// Applies the export preferences that were remembered for the document.
+    ///
+    /// The remembered export directory is only used when nothing was exported in the current session yet.
+    pub(crate) fn apply_document_export_prefs(&self) {
+        self.engine_ref().apply_document_export_prefs();
+        if self.last_export_dir().is_none()
+            && let Some(export_dir) = self.engine_ref().document.export_dir.clone()
+            && export_dir.is_dir()
+        {
+            self.set_last_export_dir(Some(gio::File::for_path(export_dir)));
+        }
+    }
+
+    /// Remembers the current export preferences and the last export directory for the document.
+    pub(crate) fn remember_document_export_prefs(&self, appwindow: &RnAppWindow) {
+        let export_dir = self.last_export_dir().and_then(|dir| dir.path());
+        let widget_flags = self.engine_mut().remember_document_export_prefs(export_dir);
+        appwindow.handle_widget_flags(widget_flags, self);
+    }
+
     /// exports and writes the engine state as json into the file.
     /// Only for debugging!
     pub(crate) async fn export_engine_state(&self, file: &gio::File) -> anyhow::Result<()> {
@@ -392,3 +642,30 @@ impl RnCanvas {
         })
     }
 }
+
+/// Fetches the content at the URL, failing when it exceeds the maximum size in bytes.
+async fn fetch_url_bytes(url: &str, max_size: usize) -> anyhow::Result<Vec<u8>> {
+    const CHUNK_SIZE: usize = 64 * 1024;
+    let input_stream = gio::File::for_uri(url)
+        .read_future(glib::Priority::DEFAULT)
+        .await
+        .context(format!("Failed to open URL '{url}'"))?;
+
+    let mut bytes = Vec::new();
+    loop {
+        let chunk = input_stream
+            .read_bytes_future(CHUNK_SIZE, glib::Priority::DEFAULT)
+            .await
+            .context(format!("Failed to read from URL '{url}'"))?;
+        if chunk.is_empty() {
+            break;
+        }
+        if bytes.len() + chunk.len() > max_size {
+            return Err(anyhow::anyhow!(
+                "Content at URL '{url}' exceeds the maximum size of {max_size} bytes"
+            ));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
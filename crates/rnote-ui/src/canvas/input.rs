@@ -21,6 +21,7 @@ pub(crate) fn handle_pointer_controller_event(
     let now = Instant::now();
     let mut widget_flags = WidgetFlags::default();
     let touch_drawing = canvas.touch_drawing();
+    let editing_locked = canvas.editing_locked();
     let gdk_event_type = event.event_type();
     let gdk_modifiers = event.modifier_state();
     let _gdk_device = event.device().unwrap();
@@ -33,6 +34,10 @@ pub(crate) fn handle_pointer_controller_event(
     if reject_pointer_input(event, touch_drawing) {
         return (glib::Propagation::Proceed, pen_state);
     }
+    // While the editing is locked only the pens that don't modify the document receive input
+    if editing_locked && !canvas.engine_ref().current_pen_is_view_only() {
+        return (glib::Propagation::Proceed, pen_state);
+    }
 
     let mut handle_pen_event = false;
     let mut propagation = glib::Propagation::Proceed;
@@ -78,7 +83,8 @@ pub(crate) fn handle_pointer_controller_event(
                 }
             }
 
-            if handle_shortcut_key {
+            // Shortcuts could switch to pens that modify the document
+            if handle_shortcut_key && !editing_locked {
                 let shortcut_key = retrieve_button_shortcut_key(gdk_button, is_stylus);
 
                 if let Some(shortcut_key) = shortcut_key {
@@ -161,7 +167,12 @@ pub(crate) fn handle_pointer_controller_event(
             return (glib::Propagation::Proceed, pen_state);
         };
         let modifier_keys = retrieve_modifier_keys(event.modifier_state());
-        let pen_mode = retrieve_pen_mode(event);
+        // The eraser mode of the stylus would override the pen
+        let pen_mode = if editing_locked {
+            None
+        } else {
+            retrieve_pen_mode(event)
+        };
 
         for (element, event_time) in elements {
             trace!(?element, ?pen_state, ?modifier_keys, ?pen_mode, event_time_delta=?now.duration_since(event_time), msg="handle pen event element");
@@ -237,6 +248,9 @@ pub(crate) fn handle_key_controller_key_pressed(
 ) -> glib::Propagation {
     trace!("canvas event key pressed - gdk_key: {gdk_key:?}, gdk_modifiers: {gdk_modifiers:?}");
     canvas.grab_focus();
+    if canvas.editing_locked() {
+        return glib::Propagation::Proceed;
+    }
 
     let now = Instant::now();
     let keyboard_key = retrieve_keyboard_key(gdk_key);
@@ -271,6 +285,9 @@ pub(crate) fn handle_key_controller_key_released(
 }
 
 pub(crate) fn handle_imcontext_text_commit(canvas: &RnCanvas, text: &str) {
+    if canvas.editing_locked() {
+        return;
+    }
     let now = Instant::now();
 
     let (_ep, widget_flags) = canvas.engine_mut().handle_pen_event(
@@ -319,7 +336,7 @@ pub(crate) fn reject_pointer_input(event: &gdk::Event, touch_drawing: bool) -> b
     false
 }
 
-fn event_is_stylus(event: &gdk::Event) -> bool {
+pub(crate) fn event_is_stylus(event: &gdk::Event) -> bool {
     // As in gtk4 'gtkgesturestylus.c:106' we detect if the pointer is a stylus when it has a device tool
     event.device_tool().is_some()
 }
@@ -337,6 +354,7 @@ fn retrieve_pointer_elements(
     // else we get SIGSEGV when trying to access (TODO: report this to gtk-rs)
     let is_stylus = event_is_stylus(event);
     let event_time = event.time();
+    let input_transform = canvas.engine_ref().input_transform();
 
     let mut elements = Vec::with_capacity(1);
 
@@ -389,13 +407,27 @@ fn retrieve_pointer_elements(
                 axes[crate::utils::axis_use_idx(gdk::AxisUse::X)],
                 axes[crate::utils::axis_use_idx(gdk::AxisUse::Y)]
             ]);
-            let pressure = if is_stylus {
-                axes[crate::utils::axis_use_idx(gdk::AxisUse::Pressure)]
+            let element = if is_stylus {
+                let tilt = (available_axes.contains(gdk::AxisFlags::XTILT)
+                    && available_axes.contains(gdk::AxisFlags::YTILT))
+                .then(|| {
+                    na::vector![
+                        axes[crate::utils::axis_use_idx(gdk::AxisUse::Xtilt)],
+                        axes[crate::utils::axis_use_idx(gdk::AxisUse::Ytilt)]
+                    ]
+                });
+                input_transform.transform_element(
+                    Element::new(
+                        pos,
+                        axes[crate::utils::axis_use_idx(gdk::AxisUse::Pressure)],
+                    ),
+                    tilt,
+                )
             } else {
-                Element::PRESSURE_DEFAULT
+                Element::new(pos, Element::PRESSURE_DEFAULT)
             };
 
-            entries.push((Element::new(pos, pressure), entry_time));
+            entries.push((element, entry_time));
         }
 
         elements.extend(entries.into_iter().rev());
@@ -405,13 +437,20 @@ fn retrieve_pointer_elements(
         .position()
         .map(|(x, y)| transform_pos(na::vector![x, y]))?;
 
-    let pressure = if is_stylus {
-        event.axis(gdk::AxisUse::Pressure).unwrap()
+    let element = if is_stylus {
+        let tilt = event
+            .axis(gdk::AxisUse::Xtilt)
+            .zip(event.axis(gdk::AxisUse::Ytilt))
+            .map(|(xtilt, ytilt)| na::vector![xtilt, ytilt]);
+        input_transform.transform_element(
+            Element::new(pos, event.axis(gdk::AxisUse::Pressure).unwrap()),
+            tilt,
+        )
     } else {
-        Element::PRESSURE_DEFAULT
+        Element::new(pos, Element::PRESSURE_DEFAULT)
     };
 
-    elements.push((Element::new(pos, pressure), now));
+    elements.push((element, now));
 
     Some(elements)
 }
@@ -2,13 +2,15 @@
 mod canvaslayout;
 pub(crate) mod imexport;
 mod input;
+pub(crate) mod recovery;
+pub(crate) mod touchgestures;
 
 // Re-exports
 pub(crate) use canvaslayout::RnCanvasLayout;
-pub(crate) use input::reject_pointer_input;
+pub(crate) use input::{event_is_stylus, reject_pointer_input};
 
 // Imports
-use crate::boxed::WidgetFlagsBoxed;
+use crate::boxed::{StrokeContentBoxed, WidgetFlagsBoxed};
 use crate::{RnAppWindow, config, env};
 use futures::StreamExt;
 use gettextrs::gettext;
@@ -27,11 +29,14 @@ use rnote_compose::penevent::PenState;
 use rnote_engine::Camera;
 use rnote_engine::ext::GraphenePointExt;
 use rnote_engine::ext::GrapheneRectExt;
+use rnote_engine::storage::{Revision, StorageBackends};
 use rnote_engine::{Engine, WidgetFlags};
 use std::cell::{Cell, Ref, RefCell, RefMut};
 use std::path::Path;
-use std::time::Duration;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 use tracing::{debug, error, warn};
+use zeroize::Zeroizing;
 
 #[derive(Debug, Default)]
 struct Connections {
@@ -45,6 +50,7 @@ struct Connections {
     appwindow_save_in_progress: Option<glib::SignalHandlerId>,
     appwindow_unsaved_changes: Option<glib::SignalHandlerId>,
     appwindow_touch_drawing: Option<glib::Binding>,
+    appwindow_editing_locked: Option<glib::Binding>,
     appwindow_show_drawing_cursor: Option<glib::Binding>,
     appwindow_regular_cursor: Option<glib::Binding>,
     appwindow_drawing_cursor: Option<glib::Binding>,
@@ -85,9 +91,18 @@ mod imp {
         pub(crate) unsaved_changes: Cell<bool>,
         pub(crate) empty: Cell<bool>,
         pub(crate) touch_drawing: Cell<bool>,
+        pub(crate) editing_locked: Cell<bool>,
         pub(crate) show_drawing_cursor: Cell<bool>,
 
         pub(crate) last_export_dir: RefCell<Option<gio::File>>,
+        /// The password the document is encrypted with when saving, if any.
+        /// Zeroized when it is replaced or the canvas is dropped.
+        pub(crate) encryption_password: RefCell<Option<Zeroizing<String>>>,
+        /// The storage revision of the output file when it was last loaded or saved, for detecting conflicting
+        /// changes when saving.
+        pub(crate) output_file_revision: RefCell<Option<Revision>>,
+        /// Identifies the recovery snapshot of this canvas.
+        pub(crate) recovery_id: String,
     }
 
     impl Default for RnCanvas {
@@ -110,8 +125,12 @@ mod imp {
                 .actions(gdk::DragAction::COPY)
                 .build();
 
-            // the order here is important: first files, then text
-            drop_target.set_types(&[gio::File::static_type(), glib::types::Type::STRING]);
+            // the order here is important: first stroke content dragged from another canvas, then files, then text
+            drop_target.set_types(&[
+                StrokeContentBoxed::static_type(),
+                gio::File::static_type(),
+                glib::types::Type::STRING,
+            ]);
 
             let regular_cursor_icon_name = String::from("cursor-dot-medium");
             let regular_cursor = gdk::Cursor::from_texture(
@@ -180,9 +199,13 @@ mod imp {
                 unsaved_changes: Cell::new(false),
                 empty: Cell::new(true),
                 touch_drawing: Cell::new(false),
+                editing_locked: Cell::new(false),
                 show_drawing_cursor: Cell::new(false),
 
                 last_export_dir: RefCell::new(None),
+                encryption_password: RefCell::new(None),
+                output_file_revision: RefCell::new(None),
+                recovery_id: format!("{:016x}", rand::random::<u64>()),
             }
         }
     }
@@ -232,14 +255,29 @@ mod imp {
                         return;
                     };
 
+                    // the time tasks were handled since the handler last yielded to the input handling
+                    let mut handling_duration = Duration::ZERO;
+
                     loop {
                         if let Some(task) = task_rx.recv().await {
+                            let handling_start = Instant::now();
                             let (widget_flags, quit) = canvas.engine_mut().handle_engine_task(task);
                             canvas.emit_handle_widget_flags(widget_flags);
 
                             if quit {
                                 break;
                             }
+
+                            handling_duration += handling_start.elapsed();
+                            if canvas.engine_ref().yield_to_input(handling_duration) {
+                                // resumes after the pending input events with a higher priority were handled
+                                glib::timeout_future_with_priority(
+                                    glib::Priority::LOW,
+                                    Duration::ZERO,
+                                )
+                                .await;
+                                handling_duration = Duration::ZERO;
+                            }
                         }
                     }
                 }
@@ -299,6 +337,9 @@ mod imp {
                     glib::ParamSpecBoolean::builder("touch-drawing")
                         .default_value(false)
                         .build(),
+                    glib::ParamSpecBoolean::builder("editing-locked")
+                        .default_value(false)
+                        .build(),
                     glib::ParamSpecBoolean::builder("show-drawing-cursor")
                         .default_value(true)
                         .build(),
@@ -329,6 +370,7 @@ mod imp {
                 "hscroll-policy" => self.hscroll_policy.get().to_value(),
                 "vscroll-policy" => self.vscroll_policy.get().to_value(),
                 "touch-drawing" => self.touch_drawing.get().to_value(),
+                "editing-locked" => self.editing_locked.get().to_value(),
                 "show-drawing-cursor" => self.show_drawing_cursor.get().to_value(),
                 "regular-cursor" => self.regular_cursor_icon_name.borrow().to_value(),
                 "drawing-cursor" => self.drawing_cursor_icon_name.borrow().to_value(),
@@ -384,6 +426,11 @@ mod imp {
                         value.get().expect("The value needs to be of type `bool`");
                     self.touch_drawing.replace(touch_drawing);
                 }
+                "editing-locked" => {
+                    let editing_locked: bool =
+                        value.get().expect("The value needs to be of type `bool`");
+                    self.editing_locked.replace(editing_locked);
+                }
                 "show-drawing-cursor" => {
                     let show_drawing_cursor: bool =
                         value.get().expect("The value needs to be of type `bool`");
@@ -479,6 +526,11 @@ mod imp {
                 // push the clip
                 snapshot.push_clip(&graphene::Rect::from_p2d_aabb(clip_bounds));
 
+                // Regenerate the content tiles where strokes have changed
+                self.engine
+                    .borrow_mut()
+                    .update_content_tiles_current_viewport();
+
                 // Draw the entire engine
                 self.engine
                     .borrow()
@@ -753,6 +805,18 @@ impl RnCanvas {
         }
     }
 
+    #[allow(unused)]
+    pub(crate) fn editing_locked(&self) -> bool {
+        self.property::<bool>("editing-locked")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_editing_locked(&self, editing_locked: bool) {
+        if self.imp().editing_locked.get() != editing_locked {
+            self.set_property("editing-locked", editing_locked.to_value());
+        }
+    }
+
     #[allow(unused)]
     pub(crate) fn show_drawing_cursor(&self) -> bool {
         self.property::<bool>("show-drawing-cursor")
@@ -785,6 +849,35 @@ impl RnCanvas {
         self.imp().last_export_dir.replace(dir);
     }
 
+    pub(crate) fn encryption_password(&self) -> Option<Zeroizing<String>> {
+        self.imp().encryption_password.borrow().clone()
+    }
+
+    /// Set the password the document is encrypted with when saving, `None` to save it unencrypted.
+    pub(crate) fn set_encryption_password(&self, password: Option<Zeroizing<String>>) {
+        if password.is_some() {
+            // Recovery snapshots are unencrypted
+            self.remove_recovery();
+        }
+        self.imp().encryption_password.replace(password);
+    }
+
+    pub(crate) fn output_file_revision(&self) -> Option<Revision> {
+        self.imp().output_file_revision.borrow().clone()
+    }
+
+    pub(crate) fn set_output_file_revision(&self, revision: Option<Revision>) {
+        self.imp().output_file_revision.replace(revision);
+    }
+
+    /// The storage backends of the app, that the output file is loaded from and saved to.
+    pub(crate) fn storage_backends(&self) -> Rc<StorageBackends> {
+        gio::Application::default()
+            .and_downcast::<crate::RnApp>()
+            .map(|app| app.storage_backends())
+            .unwrap_or_default()
+    }
+
     pub(crate) fn canvas_layout_manager(&self) -> RnCanvasLayout {
         self.layout_manager()
             .and_downcast::<RnCanvasLayout>()
@@ -986,6 +1079,13 @@ impl RnCanvas {
                     else {
                         return;
                     };
+                    if canvas.output_file_expect_write()
+                        && crate::utils::paths_abs_eq(file_path, to_path).unwrap_or(false)
+                    {
+                        // Own file writing has finished, saving replaces the file with a temporary file
+                        canvas.set_output_file_expect_write(false);
+                        return;
+                    }
                     if !crate::utils::paths_abs_eq(file_path, from_path).unwrap_or(false) {
                         return;
                     }
@@ -999,6 +1099,11 @@ impl RnCanvas {
                     if !crate::utils::paths_abs_eq(file_path, event_path).unwrap_or(false) {
                         return;
                     }
+                    if canvas.output_file_expect_write() {
+                        // Own file writing has finished, saving replaces the file with a temporary file
+                        canvas.set_output_file_expect_write(false);
+                        return;
+                    }
                     dispatch_toast_reload_modified_file(appwindow, canvas);
                 }
                 EventKind::Modify(ModifyKind::Name(_)) => {
@@ -1132,6 +1237,12 @@ impl RnCanvas {
                 move |canvas, _pspec| {
                     if let Some(output_file) = canvas.output_file() {
                         canvas.create_output_file_watcher(&output_file, &appwindow);
+                        if let Some(path) = output_file.path() {
+                            appwindow
+                                .sidebar()
+                                .workspacebrowser()
+                                .push_recent_file(path);
+                        }
                     } else {
                         canvas.clear_output_file_watcher();
                         canvas.dismiss_output_file_modified_toast();
@@ -1188,6 +1299,12 @@ impl RnCanvas {
             .sync_create()
             .build();
 
+        // and for locking the editing while presenting
+        let appwindow_editing_locked = appwindow
+            .bind_property("editing-locked", self, "editing-locked")
+            .sync_create()
+            .build();
+
         // bind cursors
         let appwindow_regular_cursor = appwindow
             .sidebar()
@@ -1229,7 +1346,30 @@ impl RnCanvas {
                     (canvas.engine_ref().camera.transform().inverse() * na::point![x, y]).coords;
                 let mut accept_drop = false;
 
-                if value.is::<gio::File>() {
+                if let Ok(stroke_content) = value.get::<StrokeContentBoxed>() {
+                    glib::spawn_future_local(clone!(
+                        #[weak]
+                        canvas,
+                        #[weak]
+                        appwindow,
+                        async move {
+                            if let Err(e) = canvas
+                                .insert_stroke_content(
+                                    stroke_content.inner(),
+                                    appwindow.stroke_content_size_option(&canvas),
+                                    Some(pos),
+                                )
+                                .await
+                            {
+                                error!("Failed to insert dropped in stroke content, Err: {e:?}");
+                                appwindow
+                                    .overlays()
+                                    .dispatch_toast_error(&gettext("Inserting strokes failed"));
+                            }
+                        }
+                    ));
+                    accept_drop = true;
+                } else if value.is::<gio::File>() {
                     // In some scenarios, get() can fail with `UnexpectedNone` even though is() returned true, e.g. when dealing with trashed files.
                     match value.get::<gio::File>() {
                         Ok(file) => {
@@ -1320,6 +1460,12 @@ impl RnCanvas {
         {
             old.unbind();
         }
+        if let Some(old) = connections
+            .appwindow_editing_locked
+            .replace(appwindow_editing_locked)
+        {
+            old.unbind();
+        }
         if let Some(old) = connections
             .appwindow_show_drawing_cursor
             .replace(appwindow_show_drawing_cursor)
@@ -1379,6 +1525,9 @@ impl RnCanvas {
         if let Some(old) = connections.appwindow_touch_drawing.take() {
             old.unbind();
         }
+        if let Some(old) = connections.appwindow_editing_locked.take() {
+            old.unbind();
+        }
         if let Some(old) = connections.appwindow_show_drawing_cursor.take() {
             old.unbind();
         }
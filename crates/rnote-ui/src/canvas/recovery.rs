@@ -0,0 +1,175 @@
+// Imports
+use super::RnCanvas;
+use crate::config;
+use anyhow::Context;
+use gtk4::{glib, prelude::*, subclass::prelude::*};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tracing::{debug, error};
+
+/// Metadata about a recovery snapshot, stored next to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "recovery_metadata")]
+pub(crate) struct RecoveryMetadata {
+    /// The file the document was opened from, if any.
+    #[serde(rename = "original_file")]
+    pub(crate) original_file: Option<PathBuf>,
+    /// The title of the document.
+    #[serde(rename = "title")]
+    pub(crate) title: String,
+}
+
+/// A recovery snapshot that was left over from a previous session.
+#[derive(Debug, Clone)]
+pub(crate) struct RecoveryEntry {
+    pub(crate) rnote_path: PathBuf,
+    pub(crate) metadata_path: PathBuf,
+    pub(crate) metadata: RecoveryMetadata,
+}
+
+impl RecoveryEntry {
+    /// Loads the bytes of the recovery snapshot.
+    pub(crate) async fn load_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        async_fs::read(&self.rnote_path).await.context(format!(
+            "Failed to read recovery file with path '{}'",
+            self.rnote_path.display()
+        ))
+    }
+
+    /// Removes the recovery snapshot and its metadata.
+    pub(crate) fn remove(&self) {
+        for path in [&self.rnote_path, &self.metadata_path] {
+            if let Err(e) = std::fs::remove_file(path)
+                && e.kind() != std::io::ErrorKind::NotFound
+            {
+                error!(
+                    "Removing recovery file with path '{}' failed, Err: {e:?}",
+                    path.display()
+                );
+            }
+        }
+    }
+}
+
+/// The directory where recovery snapshots are stored.
+pub(crate) fn recovery_dir() -> PathBuf {
+    glib::user_data_dir()
+        .join(config::APP_NAME)
+        .join("recovery")
+}
+
+/// Lists all recovery snapshots currently found in the recovery directory.
+///
+/// Must only be called before any recovery snapshots are written in the current session, else the snapshots of
+/// currently opened documents are included as well.
+pub(crate) fn list_recovery_entries() -> Vec<RecoveryEntry> {
+    let Ok(read_dir) = std::fs::read_dir(recovery_dir()) else {
+        return vec![];
+    };
+    read_dir
+        .filter_map(|entry| {
+            let rnote_path = entry.ok()?.path();
+            if rnote_path.extension()? != "rnote" {
+                return None;
+            }
+            let metadata_path = rnote_path.with_extension("json");
+            let metadata = match std::fs::read(&metadata_path)
+                .map_err(anyhow::Error::from)
+                .and_then(|bytes| Ok(serde_json::from_slice::<RecoveryMetadata>(&bytes)?))
+            {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    error!(
+                        "Reading metadata of recovery file with path '{}' failed, Err: {e:?}",
+                        rnote_path.display()
+                    );
+                    RecoveryMetadata {
+                        original_file: None,
+                        title: rnote_path.file_stem()?.to_string_lossy().to_string(),
+                    }
+                }
+            };
+            Some(RecoveryEntry {
+                rnote_path,
+                metadata_path,
+                metadata,
+            })
+        })
+        .collect()
+}
+
+impl RnCanvas {
+    /// The interval in which recovery snapshots of documents with unsaved changes are written.
+    pub(crate) const RECOVERY_INTERVAL_SECS: u32 = 60;
+
+    fn recovery_paths(&self) -> (PathBuf, PathBuf) {
+        let dir = recovery_dir();
+        let id = &self.imp().recovery_id;
+        (
+            dir.join(format!("{id}.rnote")),
+            dir.join(format!("{id}.json")),
+        )
+    }
+
+    /// Writes a recovery snapshot of the current document state to the recovery directory.
+    ///
+    /// The snapshot is removed again when the document is saved or closed. Encrypted documents are skipped, so that
+    /// their content is never written to disk unencrypted.
+    pub(crate) async fn save_recovery(&self) -> anyhow::Result<()> {
+        if self.encryption_password().is_some() {
+            return Ok(());
+        }
+        let (rnote_path, metadata_path) = self.recovery_paths();
+        let title = self.doc_title_display();
+        let metadata = RecoveryMetadata {
+            original_file: self.output_file().and_then(|f| f.path()),
+            title: title.clone(),
+        };
+        let rnote_bytes_receiver = self.engine_ref().save_as_rnote_bytes(title + ".rnote");
+        let bytes = rnote_bytes_receiver.await??;
+
+        async_fs::create_dir_all(recovery_dir())
+            .await
+            .context("Failed to create recovery directory")?;
+        async_fs::write(&metadata_path, serde_json::to_vec(&metadata)?)
+            .await
+            .context(format!(
+                "Failed to write recovery metadata to path '{}'",
+                metadata_path.display()
+            ))?;
+        // Write to a temporary file first, so that a crash while writing does not destroy the previous snapshot
+        let tmp_path = rnote_path.with_extension("rnote.tmp");
+        async_fs::write(&tmp_path, bytes).await.context(format!(
+            "Failed to write recovery file to path '{}'",
+            tmp_path.display()
+        ))?;
+        async_fs::rename(&tmp_path, &rnote_path)
+            .await
+            .context(format!(
+                "Failed to move recovery file to path '{}'",
+                rnote_path.display()
+            ))?;
+
+        // The document might have been saved while the snapshot was written
+        if !self.unsaved_changes() {
+            self.remove_recovery();
+        } else {
+            debug!("Written recovery file to path '{}'", rnote_path.display());
+        }
+        Ok(())
+    }
+
+    /// Removes the recovery snapshot of the document, if one exists.
+    pub(crate) fn remove_recovery(&self) {
+        let (rnote_path, metadata_path) = self.recovery_paths();
+        RecoveryEntry {
+            rnote_path,
+            metadata_path,
+            metadata: RecoveryMetadata {
+                original_file: None,
+                title: String::default(),
+            },
+        }
+        .remove();
+    }
+}
@@ -0,0 +1,211 @@
+// Imports
+use super::RnCanvas;
+use gtk4::{gdk, prelude::*};
+use rnote_engine::pens::PenStyle;
+use std::time::{Duration, Instant};
+use tracing::{debug, error};
+
+/// A multi-finger touch gesture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TouchGesture {
+    TwoFingerTap,
+    ThreeFingerTap,
+    ThreeFingerSwipe(SwipeDirection),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone)]
+struct TouchPoint {
+    sequence: Option<gdk::EventSequence>,
+    start: na::Vector2<f64>,
+    pos: na::Vector2<f64>,
+}
+
+/// Detects multi-finger taps and swipes from the touch events.
+///
+/// A gesture starts with the first touch and is detected when the last touch is lifted.
+#[derive(Debug, Default)]
+pub(crate) struct TouchGestureDetector {
+    start_time: Option<Instant>,
+    active: Vec<TouchPoint>,
+    /// The offsets from start to end of the touches that were already lifted.
+    ended_offsets: Vec<na::Vector2<f64>>,
+    max_touches: usize,
+    cancelled: bool,
+}
+
+impl TouchGestureDetector {
+    /// The maximum duration of a tap.
+    const TAP_DURATION_MAX: Duration = Duration::from_millis(300);
+    /// The maximum distance a touch may move during a tap.
+    const TAP_MOVE_MAX: f64 = 16.0;
+    /// The maximum duration of a swipe.
+    const SWIPE_DURATION_MAX: Duration = Duration::from_millis(800);
+    /// The minimum average distance the touches need to move for a swipe.
+    const SWIPE_DISTANCE_MIN: f64 = 80.0;
+
+    /// The number of touches that are currently on the screen.
+    pub(crate) fn n_touches(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Feed a touch event to the detector. Returns the detected gesture when the last touch was lifted.
+    pub(crate) fn handle_event(
+        &mut self,
+        event: &gdk::Event,
+        now: Instant,
+    ) -> Option<TouchGesture> {
+        let sequence = event.event_sequence();
+        // Only the distances are relevant, so the surface coordinates can be used as they are
+        let pos = event
+            .position()
+            .map(|(x, y)| na::vector![x, y])
+            .unwrap_or_else(na::Vector2::zeros);
+
+        match event.event_type() {
+            gdk::EventType::TouchBegin => {
+                if self.active.is_empty() {
+                    self.reset(now);
+                }
+                self.active.push(TouchPoint {
+                    sequence,
+                    start: pos,
+                    pos,
+                });
+                self.max_touches = self.max_touches.max(self.active.len());
+                None
+            }
+            gdk::EventType::TouchUpdate => {
+                if let Some(point) = self.active.iter_mut().find(|p| p.sequence == sequence) {
+                    point.pos = pos;
+                }
+                None
+            }
+            gdk::EventType::TouchEnd => {
+                let i = self.active.iter().position(|p| p.sequence == sequence)?;
+                let point = self.active.remove(i);
+                self.ended_offsets.push(point.pos - point.start);
+                if self.active.is_empty() {
+                    self.detect(now)
+                } else {
+                    None
+                }
+            }
+            gdk::EventType::TouchCancel => {
+                self.active.retain(|p| p.sequence != sequence);
+                self.cancelled = true;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn reset(&mut self, now: Instant) {
+        self.start_time = Some(now);
+        self.ended_offsets.clear();
+        self.max_touches = 0;
+        self.cancelled = false;
+    }
+
+    fn detect(&self, now: Instant) -> Option<TouchGesture> {
+        if self.cancelled || !(2..=3).contains(&self.max_touches) {
+            return None;
+        }
+        let duration = now.saturating_duration_since(self.start_time?);
+        let max_move = self
+            .ended_offsets
+            .iter()
+            .map(|offset| offset.magnitude())
+            .fold(0.0, f64::max);
+
+        if duration <= Self::TAP_DURATION_MAX && max_move <= Self::TAP_MOVE_MAX {
+            return Some(if self.max_touches == 2 {
+                TouchGesture::TwoFingerTap
+            } else {
+                TouchGesture::ThreeFingerTap
+            });
+        }
+
+        if self.max_touches == 3 && duration <= Self::SWIPE_DURATION_MAX {
+            let mean_offset = self.ended_offsets.iter().sum::<na::Vector2<f64>>()
+                / self.ended_offsets.len() as f64;
+            if mean_offset.magnitude() >= Self::SWIPE_DISTANCE_MIN {
+                let direction = if mean_offset[0].abs() >= mean_offset[1].abs() {
+                    if mean_offset[0] < 0.0 {
+                        SwipeDirection::Left
+                    } else {
+                        SwipeDirection::Right
+                    }
+                } else if mean_offset[1] < 0.0 {
+                    SwipeDirection::Up
+                } else {
+                    SwipeDirection::Down
+                };
+                return Some(TouchGesture::ThreeFingerSwipe(direction));
+            }
+        }
+
+        None
+    }
+}
+
+/// Execute the action of the touch gesture on the canvas.
+///
+/// Two-finger taps undo, three-finger taps redo. Horizontal three-finger swipes switch to the previous or next pen
+/// style, vertical swipes move to the previous or next page.
+pub(crate) fn handle_touch_gesture(canvas: &RnCanvas, gesture: TouchGesture) {
+    debug!("Touch gesture {gesture:?} detected");
+    let (action, target) = match gesture {
+        TouchGesture::TwoFingerTap => ("win.undo", None),
+        TouchGesture::ThreeFingerTap => ("win.redo", None),
+        TouchGesture::ThreeFingerSwipe(
+            direction @ (SwipeDirection::Left | SwipeDirection::Right),
+        ) => {
+            let pen_style = adjacent_pen_style(
+                canvas.engine_ref().current_pen_style_w_override(),
+                direction == SwipeDirection::Right,
+            );
+            ("win.pen-style", Some(pen_style.to_string().to_variant()))
+        }
+        TouchGesture::ThreeFingerSwipe(direction) => {
+            // Swiping up moves the content up, revealing the next page
+            let pages = if direction == SwipeDirection::Up {
+                1
+            } else {
+                -1
+            };
+            let widget_flags = canvas.engine_mut().camera_move_pages(pages);
+            canvas.emit_handle_widget_flags(widget_flags);
+            return;
+        }
+    };
+    if let Err(e) = canvas.activate_action(action, target.as_ref()) {
+        error!("Activating action `{action}` for touch gesture failed, Err: {e:?}");
+    }
+}
+
+/// The pen style before or after the given one, in the order of the pen styles in the sidebar.
+fn adjacent_pen_style(pen_style: PenStyle, next: bool) -> PenStyle {
+    const PEN_STYLES: [PenStyle; 6] = [
+        PenStyle::Brush,
+        PenStyle::Shaper,
+        PenStyle::Typewriter,
+        PenStyle::Eraser,
+        PenStyle::Selector,
+        PenStyle::Tools,
+    ];
+    let i = PEN_STYLES.iter().position(|s| *s == pen_style).unwrap_or(0);
+    let i = if next {
+        (i + 1) % PEN_STYLES.len()
+    } else {
+        (i + PEN_STYLES.len() - 1) % PEN_STYLES.len()
+    };
+    PEN_STYLES[i]
+}
@@ -1,10 +1,12 @@
 // Imports
-use crate::{RnAppWindow, RnCanvas, RnContextMenu, canvas::reject_pointer_input};
+use crate::canvas::touchgestures::{self, TouchGesture, TouchGestureDetector};
+use crate::canvas::{event_is_stylus, reject_pointer_input};
+use crate::{RnAppWindow, RnCanvas, RnContextMenu};
 use gtk4::{
-    CompositeTemplate, CornerType, EventControllerMotion, EventControllerScroll,
-    EventControllerScrollFlags, EventSequenceState, GestureClick, GestureDrag, GestureLongPress,
-    GestureZoom, PropagationPhase, ScrolledWindow, Widget, gdk, glib, glib::clone, graphene,
-    prelude::*, subclass::prelude::*,
+    CompositeTemplate, CornerType, EventControllerLegacy, EventControllerMotion,
+    EventControllerScroll, EventControllerScrollFlags, EventSequenceState, GestureClick,
+    GestureDrag, GestureLongPress, GestureZoom, PropagationPhase, ScrolledWindow, Widget, gdk, gio,
+    glib, glib::clone, graphene, prelude::*, subclass::prelude::*,
 };
 use once_cell::sync::Lazy;
 use rnote_compose::penevent::ShortcutKey;
@@ -20,6 +22,7 @@ struct Connections {
     appwindow_show_scrollbars_bind: Option<glib::Binding>,
     appwindow_inertial_scrolling_bind: Option<glib::Binding>,
     appwindow_righthanded_bind: Option<glib::Binding>,
+    appwindow_touch_gesture_binds: Vec<glib::Binding>,
 }
 
 mod imp {
@@ -33,6 +36,10 @@ mod imp {
         pub(crate) show_scrollbars: Cell<bool>,
         pub(crate) block_pinch_zoom: Cell<bool>,
         pub(crate) inertial_scrolling: Cell<bool>,
+        pub(crate) touch_gesture_two_finger_tap: Cell<bool>,
+        pub(crate) touch_gesture_three_finger_tap: Cell<bool>,
+        pub(crate) touch_gesture_three_finger_swipe: Cell<bool>,
+        pub(crate) touch_gesture_detector: RefCell<TouchGestureDetector>,
         pub(crate) pointer_pos: Cell<Option<na::Vector2<f64>>>,
         pub(crate) last_contextmenu_pos: Cell<Option<na::Vector2<f64>>>,
 
@@ -46,6 +53,8 @@ mod imp {
         pub(crate) canvas_alt_shift_drag_gesture: GestureDrag,
         pub(crate) touch_two_finger_long_press_gesture: GestureLongPress,
         pub(crate) touch_long_press_gesture: GestureLongPress,
+        pub(crate) canvas_contextmenu_gesture: GestureClick,
+        pub(crate) touch_gesture_controller: EventControllerLegacy,
 
         #[template_child]
         pub(crate) scroller: TemplateChild<ScrolledWindow>,
@@ -126,12 +135,29 @@ mod imp {
                 .touch_only(true)
                 .build();
 
+            // Right click on strokes opens the context menu, before the canvas receives the event as a pen shortcut.
+            let canvas_contextmenu_gesture = GestureClick::builder()
+                .name("canvas_contextmenu_gesture")
+                .button(gdk::BUTTON_SECONDARY)
+                .propagation_phase(PropagationPhase::Capture)
+                .build();
+
+            // Observes all touch events for the multi-finger gestures, before they are claimed by other gestures.
+            let touch_gesture_controller = EventControllerLegacy::builder()
+                .name("touch_gesture_controller")
+                .propagation_phase(PropagationPhase::Capture)
+                .build();
+
             Self {
                 connections: RefCell::new(Connections::default()),
                 canvas_touch_drawing_handler: RefCell::new(None),
                 show_scrollbars: Cell::new(false),
                 block_pinch_zoom: Cell::new(false),
                 inertial_scrolling: Cell::new(true),
+                touch_gesture_two_finger_tap: Cell::new(true),
+                touch_gesture_three_finger_tap: Cell::new(true),
+                touch_gesture_three_finger_swipe: Cell::new(true),
+                touch_gesture_detector: RefCell::new(TouchGestureDetector::default()),
                 pointer_pos: Cell::new(None),
                 last_contextmenu_pos: Cell::new(None),
 
@@ -145,6 +171,8 @@ mod imp {
                 canvas_alt_shift_drag_gesture,
                 touch_two_finger_long_press_gesture,
                 touch_long_press_gesture,
+                canvas_contextmenu_gesture,
+                touch_gesture_controller,
 
                 scroller: TemplateChild::<ScrolledWindow>::default(),
                 canvas: TemplateChild::<RnCanvas>::default(),
@@ -194,6 +222,10 @@ mod imp {
                 .add_controller(self.touch_two_finger_long_press_gesture.clone());
             self.canvas
                 .add_controller(self.touch_long_press_gesture.clone());
+            self.canvas
+                .add_controller(self.canvas_contextmenu_gesture.clone());
+            self.scroller
+                .add_controller(self.touch_gesture_controller.clone());
 
             // group
             self.touch_two_finger_long_press_gesture
@@ -247,6 +279,15 @@ mod imp {
                     glib::ParamSpecBoolean::builder("inertial-scrolling")
                         .default_value(true)
                         .build(),
+                    glib::ParamSpecBoolean::builder("touch-gesture-two-finger-tap")
+                        .default_value(true)
+                        .build(),
+                    glib::ParamSpecBoolean::builder("touch-gesture-three-finger-tap")
+                        .default_value(true)
+                        .build(),
+                    glib::ParamSpecBoolean::builder("touch-gesture-three-finger-swipe")
+                        .default_value(true)
+                        .build(),
                 ]
             });
             PROPERTIES.as_ref()
@@ -257,6 +298,15 @@ mod imp {
                 "show-scrollbars" => self.show_scrollbars.get().to_value(),
                 "block-pinch-zoom" => self.block_pinch_zoom.get().to_value(),
                 "inertial-scrolling" => self.inertial_scrolling.get().to_value(),
+                "touch-gesture-two-finger-tap" => {
+                    self.touch_gesture_two_finger_tap.get().to_value()
+                }
+                "touch-gesture-three-finger-tap" => {
+                    self.touch_gesture_three_finger_tap.get().to_value()
+                }
+                "touch-gesture-three-finger-swipe" => {
+                    self.touch_gesture_three_finger_swipe.get().to_value()
+                }
                 _ => unimplemented!(),
             }
         }
@@ -287,6 +337,27 @@ mod imp {
                     self.inertial_scrolling.replace(inertial_scrolling);
                     self.canvas_kinetic_scrolling_update();
                 }
+                "touch-gesture-two-finger-tap" => {
+                    self.touch_gesture_two_finger_tap.replace(
+                        value
+                            .get::<bool>()
+                            .expect("The value needs to be of type `bool`"),
+                    );
+                }
+                "touch-gesture-three-finger-tap" => {
+                    self.touch_gesture_three_finger_tap.replace(
+                        value
+                            .get::<bool>()
+                            .expect("The value needs to be of type `bool`"),
+                    );
+                }
+                "touch-gesture-three-finger-swipe" => {
+                    self.touch_gesture_three_finger_swipe.replace(
+                        value
+                            .get::<bool>()
+                            .expect("The value needs to be of type `bool`"),
+                    );
+                }
                 _ => unimplemented!(),
             }
         }
@@ -305,6 +376,14 @@ mod imp {
             }
         }
 
+        fn touch_gesture_enabled(&self, gesture: TouchGesture) -> bool {
+            match gesture {
+                TouchGesture::TwoFingerTap => self.touch_gesture_two_finger_tap.get(),
+                TouchGesture::ThreeFingerTap => self.touch_gesture_three_finger_tap.get(),
+                TouchGesture::ThreeFingerSwipe(_) => self.touch_gesture_three_finger_swipe.get(),
+            }
+        }
+
         fn canvas_kinetic_scrolling_update(&self) {
             self.scroller.set_kinetic_scrolling(
                 !self.canvas.touch_drawing() && self.inertial_scrolling.get(),
@@ -786,20 +865,82 @@ mod imp {
                     ));
             }
 
+            {
+                // Multi-finger touch gestures
+                self.touch_gesture_controller.connect_event(clone!(
+                    #[weak(rename_to=canvaswrapper)]
+                    obj,
+                    #[upgrade_or]
+                    glib::Propagation::Proceed,
+                    move |_, event| {
+                        let event_type = event.event_type();
+                        if !matches!(
+                            event_type,
+                            gdk::EventType::TouchBegin
+                                | gdk::EventType::TouchUpdate
+                                | gdk::EventType::TouchEnd
+                                | gdk::EventType::TouchCancel
+                        ) {
+                            return glib::Propagation::Proceed;
+                        }
+                        let imp = canvaswrapper.imp();
+                        let gesture = imp
+                            .touch_gesture_detector
+                            .borrow_mut()
+                            .handle_event(event, Instant::now());
+
+                        // Three-finger gestures should not zoom and move the canvas at the same time
+                        if event_type == gdk::EventType::TouchBegin
+                            && imp.touch_gesture_detector.borrow().n_touches() >= 3
+                            && (imp.touch_gesture_three_finger_tap.get()
+                                || imp.touch_gesture_three_finger_swipe.get())
+                        {
+                            imp.canvas_zoom_gesture
+                                .set_state(EventSequenceState::Denied);
+                        }
+                        if let Some(gesture) = gesture
+                            && imp.touch_gesture_enabled(gesture)
+                        {
+                            touchgestures::handle_touch_gesture(&canvaswrapper.canvas(), gesture);
+                        }
+                        // The events are only observed, they still need to arrive at the other gestures and the canvas
+                        glib::Propagation::Proceed
+                    }
+                ));
+            }
+
             {
                 // Context menu
                 self.touch_long_press_gesture.connect_pressed(clone!(
                     #[weak(rename_to=canvaswrapper)]
                     obj,
                     move |_gesture, x, y| {
-                        let popover = canvaswrapper.contextmenu().popover();
-                        canvaswrapper
-                            .imp()
-                            .last_contextmenu_pos
-                            .set(Some(na::vector![x, y]));
-                        popover
-                            .set_pointing_to(Some(&gdk::Rectangle::new(x as i32, y as i32, 4, 4)));
-                        popover.popup();
+                        canvaswrapper.popup_contextmenu(x, y);
+                    }
+                ));
+
+                self.canvas_contextmenu_gesture.connect_pressed(clone!(
+                    #[weak(rename_to=canvaswrapper)]
+                    obj,
+                    move |gesture, _n_press, x, y| {
+                        let canvas = canvaswrapper.canvas();
+                        let on_stroke = !gesture
+                            .current_event()
+                            .is_some_and(|e| event_is_stylus(&e))
+                            && !canvas.editing_locked()
+                            && {
+                                let doc_pos = (canvas.engine_ref().camera.transform().inverse()
+                                    * na::point![x, y])
+                                .coords;
+                                canvas.engine_ref().stroke_at(doc_pos)
+                            };
+                        // Right clicks next to strokes are left to the canvas, where they might be a pen shortcut
+                        if !on_stroke {
+                            gesture.set_state(EventSequenceState::Denied);
+                            return;
+                        }
+                        gesture.set_state(EventSequenceState::Claimed);
+                        canvaswrapper.popup_contextmenu(x, y);
                     }
                 ));
             }
@@ -853,6 +994,45 @@ impl RnCanvasWrapper {
         self.set_property("inertial-scrolling", inertial_scrolling);
     }
 
+    #[allow(unused)]
+    pub(crate) fn touch_gesture_two_finger_tap(&self) -> bool {
+        self.property::<bool>("touch-gesture-two-finger-tap")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_touch_gesture_two_finger_tap(&self, touch_gesture_two_finger_tap: bool) {
+        self.set_property("touch-gesture-two-finger-tap", touch_gesture_two_finger_tap);
+    }
+
+    #[allow(unused)]
+    pub(crate) fn touch_gesture_three_finger_tap(&self) -> bool {
+        self.property::<bool>("touch-gesture-three-finger-tap")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_touch_gesture_three_finger_tap(&self, touch_gesture_three_finger_tap: bool) {
+        self.set_property(
+            "touch-gesture-three-finger-tap",
+            touch_gesture_three_finger_tap,
+        );
+    }
+
+    #[allow(unused)]
+    pub(crate) fn touch_gesture_three_finger_swipe(&self) -> bool {
+        self.property::<bool>("touch-gesture-three-finger-swipe")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_touch_gesture_three_finger_swipe(
+        &self,
+        touch_gesture_three_finger_swipe: bool,
+    ) {
+        self.set_property(
+            "touch-gesture-three-finger-swipe",
+            touch_gesture_three_finger_swipe,
+        );
+    }
+
     pub(crate) fn pointer_pos(&self) -> Option<na::Vector2<f64>> {
         self.imp().pointer_pos.get()
     }
@@ -861,6 +1041,39 @@ impl RnCanvasWrapper {
         self.imp().last_contextmenu_pos.get()
     }
 
+    /// Pop up the context menu at the given position in canvas coordinates.
+    ///
+    /// When there is a stroke at the position, it gets selected and the menu shows the items for the selection.
+    pub(crate) fn popup_contextmenu(&self, x: f64, y: f64) {
+        let canvas = self.canvas();
+        let contextmenu = self.contextmenu();
+        self.imp().last_contextmenu_pos.set(Some(na::vector![x, y]));
+
+        let on_stroke = if canvas.editing_locked() {
+            false
+        } else {
+            let doc_pos =
+                (canvas.engine_ref().camera.transform().inverse() * na::point![x, y]).coords;
+            let (on_stroke, widget_flags) = canvas.engine_mut().select_stroke_at(doc_pos);
+            canvas.emit_handle_widget_flags(widget_flags);
+            on_stroke
+        };
+        contextmenu.set_show_stroke_items(on_stroke);
+        if on_stroke
+            && let Some(action) = self
+                .root()
+                .and_downcast::<RnAppWindow>()
+                .and_then(|appwindow| appwindow.lookup_action("selection-locked"))
+                .and_downcast::<gio::SimpleAction>()
+        {
+            action.set_state(&canvas.engine_ref().selection_locked().to_variant());
+        }
+
+        let popover = contextmenu.popover();
+        popover.set_pointing_to(Some(&gdk::Rectangle::new(x as i32, y as i32, 4, 4)));
+        popover.popup();
+    }
+
     pub(crate) fn scroller(&self) -> ScrolledWindow {
         self.imp().scroller.get()
     }
@@ -914,6 +1127,29 @@ impl RnCanvasWrapper {
             .sync_create()
             .build();
 
+        let settings_panel = appwindow.sidebar().settings_panel();
+        let appwindow_touch_gesture_binds = [
+            (
+                settings_panel.touch_gesture_two_finger_tap_row(),
+                "touch-gesture-two-finger-tap",
+            ),
+            (
+                settings_panel.touch_gesture_three_finger_tap_row(),
+                "touch-gesture-three-finger-tap",
+            ),
+            (
+                settings_panel.touch_gesture_three_finger_swipe_row(),
+                "touch-gesture-three-finger-swipe",
+            ),
+        ]
+        .into_iter()
+        .map(|(row, property)| {
+            row.bind_property("active", self, property)
+                .sync_create()
+                .build()
+        })
+        .collect::<Vec<glib::Binding>>();
+
         let mut connections = self.imp().connections.borrow_mut();
         if let Some(old) = connections
             .appwindow_block_pinch_zoom_bind
@@ -939,6 +1175,12 @@ impl RnCanvasWrapper {
         {
             old.unbind();
         }
+        for old in std::mem::replace(
+            &mut connections.appwindow_touch_gesture_binds,
+            appwindow_touch_gesture_binds,
+        ) {
+            old.unbind();
+        }
     }
 
     /// This disconnects all connections with references to external objects,
@@ -961,6 +1203,9 @@ impl RnCanvasWrapper {
         if let Some(old) = connections.appwindow_righthanded_bind.take() {
             old.unbind();
         }
+        for old in connections.appwindow_touch_gesture_binds.drain(..) {
+            old.unbind();
+        }
     }
 
     /// When the widget is the child of a tab page, we want to connect the title, icons, ..
@@ -0,0 +1,140 @@
+// Imports
+use anyhow::Context;
+use rnote_compose::Color;
+
+/// The settings variant of a palette: the name and the colors as (r, g, b, a) tuples.
+pub(crate) type ColorPaletteVariant = (String, Vec<(f64, f64, f64, f64)>);
+
+/// A named palette of colors.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ColorPalette {
+    pub(crate) name: String,
+    pub(crate) colors: Vec<Color>,
+}
+
+impl ColorPalette {
+    /// The header line of GIMP palette files.
+    const GPL_HEADER: &'static str = "GIMP Palette";
+
+    pub(crate) fn new(name: String, colors: Vec<Color>) -> Self {
+        Self { name, colors }
+    }
+
+    pub(crate) fn from_variant(variant: ColorPaletteVariant) -> Self {
+        let (name, colors) = variant;
+        Self {
+            name,
+            colors: colors
+                .into_iter()
+                .map(|(r, g, b, a)| Color::new(r, g, b, a))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn to_variant(&self) -> ColorPaletteVariant {
+        (
+            self.name.clone(),
+            self.colors.iter().map(|c| (c.r, c.g, c.b, c.a)).collect(),
+        )
+    }
+
+    /// Parse a palette in the GIMP palette (.gpl) format.
+    ///
+    /// The format has no alpha channel, so all imported colors are opaque.
+    pub(crate) fn from_gpl(text: &str, fallback_name: &str) -> anyhow::Result<Self> {
+        let mut lines = text.lines().enumerate();
+        let header = lines
+            .find(|(_, line)| !line.trim().is_empty())
+            .map(|(_, line)| line.trim());
+        if header != Some(Self::GPL_HEADER) {
+            return Err(anyhow::anyhow!(
+                "Missing '{}' header, not a GIMP palette.",
+                Self::GPL_HEADER
+            ));
+        }
+
+        let mut name = None;
+        let mut colors = Vec::new();
+        for (i, line) in lines {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("Columns:") {
+                continue;
+            }
+            if let Some(palette_name) = line.strip_prefix("Name:") {
+                name = Some(palette_name.trim().to_string());
+                continue;
+            }
+
+            let mut components = line.split_whitespace().map(|c| c.parse::<u8>());
+            let mut next_component = || {
+                components
+                    .next()
+                    .context("Missing color component.")?
+                    .context("Color component is not in range 0 - 255.")
+                    .with_context(|| format!("Invalid color on line {}", i + 1))
+            };
+            let (r, g, b) = (next_component()?, next_component()?, next_component()?);
+            colors.push(Color::new(
+                f64::from(r) / 255.0,
+                f64::from(g) / 255.0,
+                f64::from(b) / 255.0,
+                1.0,
+            ));
+        }
+
+        Ok(Self {
+            name: name
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| fallback_name.to_string()),
+            colors,
+        })
+    }
+
+    /// Export the palette in the GIMP palette (.gpl) format.
+    ///
+    /// The format has no alpha channel, so the alpha values of the colors are dropped.
+    pub(crate) fn to_gpl(&self) -> String {
+        let mut gpl = format!(
+            "{}\nName: {}\nColumns: {}\n#\n",
+            Self::GPL_HEADER,
+            self.name,
+            self.colors.len()
+        );
+        for color in self.colors.iter() {
+            let (r, g, b) = (
+                (color.r * 255.0).round() as u8,
+                (color.g * 255.0).round() as u8,
+                (color.b * 255.0).round() as u8,
+            );
+            gpl.push_str(&format!("{r:3} {g:3} {b:3}\t#{r:02x}{g:02x}{b:02x}\n"));
+        }
+        gpl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gpl_roundtrip() {
+        let palette = ColorPalette::new(
+            String::from("Test"),
+            vec![Color::BLACK, Color::WHITE, Color::new(1.0, 0.0, 0.0, 1.0)],
+        );
+        let imported = ColorPalette::from_gpl(&palette.to_gpl(), "Fallback").unwrap();
+        assert_eq!(imported, palette);
+    }
+
+    #[test]
+    fn gpl_parse() {
+        let gpl = "GIMP Palette\nColumns: 2\n# comment\n\n255 128   0\tOrange\n  0   0 255 Blue\n";
+        let imported = ColorPalette::from_gpl(gpl, "Fallback").unwrap();
+        assert_eq!(imported.name, "Fallback");
+        assert_eq!(imported.colors.len(), 2);
+        assert_eq!(imported.colors[1], Color::new(0.0, 0.0, 1.0, 1.0));
+
+        assert!(ColorPalette::from_gpl("not a palette", "Fallback").is_err());
+        assert!(ColorPalette::from_gpl("GIMP Palette\n256 0 0\n", "Fallback").is_err());
+    }
+}
@@ -1,22 +1,40 @@
 // Modules
 mod colorpad;
+mod colorpalette;
 mod colorsetter;
 
 // Re-exports
 pub(crate) use colorpad::RnColorPad;
+pub(crate) use colorpalette::{ColorPalette, ColorPaletteVariant};
 pub(crate) use colorsetter::RnColorSetter;
 
 // Imports
-use crate::RnAppWindow;
+use crate::{RnAppWindow, dialogs};
+use gettextrs::gettext;
 use gtk4::{
-    Button, ColorDialog, CompositeTemplate, Label, Widget, gdk, glib, glib::clone, prelude::*,
-    subclass::prelude::*,
+    Button, ColorDialog, CompositeTemplate, DragSource, DropTarget, Label, MenuButton, Separator,
+    Widget, WidgetPaintable, gdk, gio, glib, glib::clone, prelude::*, subclass::prelude::*,
 };
 use once_cell::sync::Lazy;
 use rnote_compose::{Color, color};
 use rnote_engine::ext::GdkRGBAExt;
-use std::cell::RefCell;
-use tracing::debug;
+use std::cell::{Cell, RefCell};
+use tracing::{debug, error};
+
+/// The settings keys of the palette colors of older versions, which had a single palette.
+///
+/// They are deprecated and only read to carry the colors over into the default palette.
+const LEGACY_COLOR_KEYS: [&str; 9] = [
+    "colorpicker-color-1",
+    "colorpicker-color-2",
+    "colorpicker-color-3",
+    "colorpicker-color-4",
+    "colorpicker-color-5",
+    "colorpicker-color-6",
+    "colorpicker-color-7",
+    "colorpicker-color-8",
+    "colorpicker-color-9",
+];
 
 mod imp {
     use super::*;
@@ -27,6 +45,10 @@ mod imp {
         pub(crate) stroke_color: RefCell<gdk::RGBA>,
         pub(crate) fill_color: RefCell<gdk::RGBA>,
         pub(crate) color_dialog: glib::WeakRef<ColorDialog>,
+        pub(crate) action_group: gio::SimpleActionGroup,
+        pub(crate) palettes: RefCell<Vec<ColorPalette>>,
+        pub(crate) active_palette: Cell<usize>,
+        pub(crate) recent_colors: RefCell<Vec<Color>>,
 
         #[template_child]
         pub(crate) active_colors_box: TemplateChild<gtk4::Box>,
@@ -37,23 +59,11 @@ mod imp {
         #[template_child]
         pub(crate) setter_box: TemplateChild<gtk4::Box>,
         #[template_child]
-        pub(crate) setter_1: TemplateChild<RnColorSetter>,
+        pub(crate) recent_separator: TemplateChild<Separator>,
         #[template_child]
-        pub(crate) setter_2: TemplateChild<RnColorSetter>,
+        pub(crate) recent_box: TemplateChild<gtk4::Box>,
         #[template_child]
-        pub(crate) setter_3: TemplateChild<RnColorSetter>,
-        #[template_child]
-        pub(crate) setter_4: TemplateChild<RnColorSetter>,
-        #[template_child]
-        pub(crate) setter_5: TemplateChild<RnColorSetter>,
-        #[template_child]
-        pub(crate) setter_6: TemplateChild<RnColorSetter>,
-        #[template_child]
-        pub(crate) setter_7: TemplateChild<RnColorSetter>,
-        #[template_child]
-        pub(crate) setter_8: TemplateChild<RnColorSetter>,
-        #[template_child]
-        pub(crate) setter_9: TemplateChild<RnColorSetter>,
+        pub(crate) palette_menubutton: TemplateChild<MenuButton>,
         #[template_child]
         pub(crate) colordialog_button: TemplateChild<Button>,
         #[template_child]
@@ -68,20 +78,18 @@ mod imp {
                 )),
                 fill_color: RefCell::new(gdk::RGBA::from_compose_color(*super::FILL_COLOR_DEFAULT)),
                 color_dialog: glib::WeakRef::new(),
+                action_group: gio::SimpleActionGroup::new(),
+                palettes: RefCell::new(vec![super::default_palette()]),
+                active_palette: Cell::new(0),
+                recent_colors: RefCell::new(Vec::new()),
 
                 active_colors_box: TemplateChild::default(),
                 stroke_color_pad: TemplateChild::default(),
                 fill_color_pad: TemplateChild::default(),
                 setter_box: TemplateChild::default(),
-                setter_1: TemplateChild::default(),
-                setter_2: TemplateChild::default(),
-                setter_3: TemplateChild::default(),
-                setter_4: TemplateChild::default(),
-                setter_5: TemplateChild::default(),
-                setter_6: TemplateChild::default(),
-                setter_7: TemplateChild::default(),
-                setter_8: TemplateChild::default(),
-                setter_9: TemplateChild::default(),
+                recent_separator: TemplateChild::default(),
+                recent_box: TemplateChild::default(),
+                palette_menubutton: TemplateChild::default(),
                 colordialog_button: TemplateChild::default(),
                 active_color_label: TemplateChild::default(),
             }
@@ -108,7 +116,7 @@ mod imp {
             self.parent_constructed();
             let obj = self.obj();
 
-            self.setup_setters();
+            obj.insert_action_group("colorpicker", Some(&self.action_group));
 
             self.stroke_color_pad
                 .bind_property("color", &*obj, "stroke-color")
@@ -143,6 +151,25 @@ mod imp {
                         .update_color_label_string(colorpicker.fill_color().into_compose_color());
                 }
             ));
+
+            obj.connect_notify_local(Some("stroke-color"), move |colorpicker, _| {
+                if colorpicker.stroke_color_pad_active() {
+                    colorpicker
+                        .imp()
+                        .update_color_label_string(colorpicker.stroke_color().into_compose_color());
+                }
+            });
+
+            obj.connect_notify_local(Some("fill-color"), move |colorpicker, _| {
+                if colorpicker.fill_color_pad_active() {
+                    colorpicker
+                        .imp()
+                        .update_color_label_string(colorpicker.fill_color().into_compose_color());
+                }
+            });
+
+            obj.refresh_palette();
+            obj.refresh_recent_colors();
         }
 
         fn dispose(&self) {
@@ -194,215 +221,7 @@ mod imp {
     impl WidgetImpl for RnColorPicker {}
 
     impl RnColorPicker {
-        fn setup_setters(&self) {
-            let obj = self.obj();
-
-            self.setter_1.set_color(Self::default_color(0));
-            self.setter_2.set_color(Self::default_color(1));
-            self.setter_3.set_color(Self::default_color(2));
-            self.setter_4.set_color(Self::default_color(3));
-            self.setter_5.set_color(Self::default_color(4));
-            self.setter_6.set_color(Self::default_color(5));
-            self.setter_7.set_color(Self::default_color(6));
-            self.setter_8.set_color(Self::default_color(7));
-            self.setter_9.set_color(Self::default_color(8));
-
-            self.setter_1.connect_active_notify(clone!(
-                #[weak(rename_to=colorpicker)]
-                obj,
-                move |setter| {
-                    if setter.is_active() {
-                        colorpicker.setter_2().set_active(false);
-                        colorpicker.setter_3().set_active(false);
-                        colorpicker.setter_4().set_active(false);
-                        colorpicker.setter_5().set_active(false);
-                        colorpicker.setter_6().set_active(false);
-                        colorpicker.setter_7().set_active(false);
-                        colorpicker.setter_8().set_active(false);
-                        colorpicker.setter_9().set_active(false);
-                        // Must come after setting the other setters inactive
-                        colorpicker.set_color_active_pad(setter.color());
-                    }
-                }
-            ));
-
-            self.setter_2.connect_active_notify(clone!(
-                #[weak(rename_to=colorpicker)]
-                obj,
-                move |setter| {
-                    if setter.is_active() {
-                        colorpicker.setter_1().set_active(false);
-                        colorpicker.setter_3().set_active(false);
-                        colorpicker.setter_4().set_active(false);
-                        colorpicker.setter_5().set_active(false);
-                        colorpicker.setter_6().set_active(false);
-                        colorpicker.setter_7().set_active(false);
-                        colorpicker.setter_8().set_active(false);
-                        colorpicker.setter_9().set_active(false);
-                        colorpicker.set_color_active_pad(setter.color());
-                    }
-                }
-            ));
-
-            self.setter_3.connect_active_notify(clone!(
-                #[weak(rename_to=colorpicker)]
-                obj,
-                move |setter| {
-                    if setter.is_active() {
-                        colorpicker.setter_1().set_active(false);
-                        colorpicker.setter_2().set_active(false);
-                        colorpicker.setter_4().set_active(false);
-                        colorpicker.setter_5().set_active(false);
-                        colorpicker.setter_6().set_active(false);
-                        colorpicker.setter_7().set_active(false);
-                        colorpicker.setter_8().set_active(false);
-                        colorpicker.setter_9().set_active(false);
-                        colorpicker.set_color_active_pad(setter.color());
-                    }
-                }
-            ));
-
-            self.setter_4.connect_active_notify(clone!(
-                #[weak(rename_to=colorpicker)]
-                obj,
-                move |setter| {
-                    if setter.is_active() {
-                        colorpicker.setter_1().set_active(false);
-                        colorpicker.setter_2().set_active(false);
-                        colorpicker.setter_3().set_active(false);
-                        colorpicker.setter_5().set_active(false);
-                        colorpicker.setter_6().set_active(false);
-                        colorpicker.setter_7().set_active(false);
-                        colorpicker.setter_8().set_active(false);
-                        colorpicker.setter_9().set_active(false);
-                        colorpicker.set_color_active_pad(setter.color());
-                    }
-                }
-            ));
-
-            self.setter_5.connect_active_notify(clone!(
-                #[weak(rename_to=colorpicker)]
-                obj,
-                move |setter| {
-                    if setter.is_active() {
-                        colorpicker.setter_1().set_active(false);
-                        colorpicker.setter_2().set_active(false);
-                        colorpicker.setter_3().set_active(false);
-                        colorpicker.setter_4().set_active(false);
-                        colorpicker.setter_6().set_active(false);
-                        colorpicker.setter_7().set_active(false);
-                        colorpicker.setter_8().set_active(false);
-                        colorpicker.setter_9().set_active(false);
-                        colorpicker.set_color_active_pad(setter.color());
-                    }
-                }
-            ));
-
-            self.setter_6.connect_active_notify(clone!(
-                #[weak(rename_to=colorpicker)]
-                obj,
-                move |setter| {
-                    if setter.is_active() {
-                        colorpicker.setter_1().set_active(false);
-                        colorpicker.setter_2().set_active(false);
-                        colorpicker.setter_3().set_active(false);
-                        colorpicker.setter_4().set_active(false);
-                        colorpicker.setter_5().set_active(false);
-                        colorpicker.setter_7().set_active(false);
-                        colorpicker.setter_8().set_active(false);
-                        colorpicker.setter_9().set_active(false);
-                        colorpicker.set_color_active_pad(setter.color());
-                    }
-                }
-            ));
-
-            self.setter_7.connect_active_notify(clone!(
-                #[weak(rename_to=colorpicker)]
-                obj,
-                move |setter| {
-                    if setter.is_active() {
-                        colorpicker.setter_1().set_active(false);
-                        colorpicker.setter_2().set_active(false);
-                        colorpicker.setter_3().set_active(false);
-                        colorpicker.setter_4().set_active(false);
-                        colorpicker.setter_5().set_active(false);
-                        colorpicker.setter_6().set_active(false);
-                        colorpicker.setter_8().set_active(false);
-                        colorpicker.setter_9().set_active(false);
-                        colorpicker.set_color_active_pad(setter.color());
-                    }
-                }
-            ));
-
-            self.setter_8.connect_active_notify(clone!(
-                #[weak(rename_to=colorpicker)]
-                obj,
-                move |setter| {
-                    if setter.is_active() {
-                        colorpicker.setter_1().set_active(false);
-                        colorpicker.setter_2().set_active(false);
-                        colorpicker.setter_3().set_active(false);
-                        colorpicker.setter_4().set_active(false);
-                        colorpicker.setter_5().set_active(false);
-                        colorpicker.setter_6().set_active(false);
-                        colorpicker.setter_7().set_active(false);
-                        colorpicker.setter_9().set_active(false);
-                        colorpicker.set_color_active_pad(setter.color());
-                    }
-                }
-            ));
-
-            self.setter_9.connect_active_notify(clone!(
-                #[weak(rename_to=colorpicker)]
-                obj,
-                move |setter| {
-                    if setter.is_active() {
-                        colorpicker.setter_1().set_active(false);
-                        colorpicker.setter_2().set_active(false);
-                        colorpicker.setter_3().set_active(false);
-                        colorpicker.setter_4().set_active(false);
-                        colorpicker.setter_5().set_active(false);
-                        colorpicker.setter_6().set_active(false);
-                        colorpicker.setter_7().set_active(false);
-                        colorpicker.setter_8().set_active(false);
-                        colorpicker.set_color_active_pad(setter.color());
-                    }
-                }
-            ));
-
-            obj.connect_notify_local(Some("stroke-color"), move |colorpicker, _| {
-                if colorpicker.stroke_color_pad_active() {
-                    colorpicker
-                        .imp()
-                        .update_color_label_string(colorpicker.stroke_color().into_compose_color());
-                }
-            });
-
-            obj.connect_notify_local(Some("fill-color"), move |colorpicker, _| {
-                if colorpicker.fill_color_pad_active() {
-                    colorpicker
-                        .imp()
-                        .update_color_label_string(colorpicker.fill_color().into_compose_color());
-                }
-            });
-        }
-
-        fn default_color(i: usize) -> gdk::RGBA {
-            match i {
-                0 => gdk::RGBA::new(0.0, 0.0, 0.0, 1.0),
-                1 => gdk::RGBA::new(1.0, 1.0, 1.0, 1.0),
-                2 => gdk::RGBA::new(0.0, 0.0, 0.0, 0.0),
-                3 => gdk::RGBA::new(0.597, 0.753, 0.941, 1.0),
-                4 => gdk::RGBA::new(0.101, 0.371, 0.703, 1.0),
-                5 => gdk::RGBA::new(0.148, 0.632, 0.410, 1.0),
-                6 => gdk::RGBA::new(0.957, 0.757, 0.066, 1.0),
-                7 => gdk::RGBA::new(0.898, 0.378, 0.0, 1.0),
-                8 => gdk::RGBA::new(0.644, 0.113, 0.175, 1.0),
-                _ => gdk::RGBA::new(0.0, 0.0, 0.0, 1.0),
-            }
-        }
-
-        fn update_color_label_string(&self, color: Color) {
+        pub(super) fn update_color_label_string(&self, color: Color) {
             let color_string = crate::utils::color_to_hsv_label_string(color);
             self.active_color_label.set_label(&color_string);
         }
@@ -426,7 +245,28 @@ pub(crate) static STROKE_COLOR_DEFAULT: Lazy<Color> =
 pub(crate) static FILL_COLOR_DEFAULT: Lazy<Color> =
     Lazy::new(|| Color::from(color::GNOME_BLUES[1]));
 
+/// The palette that is used when no palettes are available.
+fn default_palette() -> ColorPalette {
+    ColorPalette::new(
+        gettext("Default"),
+        vec![
+            Color::new(0.0, 0.0, 0.0, 1.0),
+            Color::new(1.0, 1.0, 1.0, 1.0),
+            Color::new(0.0, 0.0, 0.0, 0.0),
+            Color::new(0.597, 0.753, 0.941, 1.0),
+            Color::new(0.101, 0.371, 0.703, 1.0),
+            Color::new(0.148, 0.632, 0.410, 1.0),
+            Color::new(0.957, 0.757, 0.066, 1.0),
+            Color::new(0.898, 0.378, 0.0, 1.0),
+            Color::new(0.644, 0.113, 0.175, 1.0),
+        ],
+    )
+}
+
 impl RnColorPicker {
+    /// The maximum number of colors in the recent colors history.
+    pub(crate) const RECENT_COLORS_MAX: usize = 6;
+
     pub(crate) fn new() -> Self {
         glib::Object::new()
     }
@@ -451,47 +291,139 @@ impl RnColorPicker {
         self.set_property("fill-color", color.to_value());
     }
 
-    pub(crate) fn setter_1(&self) -> RnColorSetter {
-        self.imp().setter_1.get()
-    }
-
-    pub(crate) fn setter_2(&self) -> RnColorSetter {
-        self.imp().setter_2.get()
+    pub(crate) fn active_color_label(&self) -> Label {
+        self.imp().active_color_label.get()
     }
 
-    pub(crate) fn setter_3(&self) -> RnColorSetter {
-        self.imp().setter_3.get()
+    /// The currently active palette.
+    pub(crate) fn active_palette(&self) -> ColorPalette {
+        let imp = self.imp();
+        imp.palettes.borrow()[imp.active_palette.get()].clone()
     }
 
-    pub(crate) fn setter_4(&self) -> RnColorSetter {
-        self.imp().setter_4.get()
+    /// The color at the index of the active palette.
+    pub(crate) fn palette_color(&self, index: usize) -> Option<Color> {
+        let imp = self.imp();
+        imp.palettes.borrow()[imp.active_palette.get()]
+            .colors
+            .get(index)
+            .copied()
     }
 
-    pub(crate) fn setter_5(&self) -> RnColorSetter {
-        self.imp().setter_5.get()
+    /// Select the setter of the color at the index of the active palette, applying the color to the active pad.
+    pub(crate) fn select_palette_color(&self, index: usize) {
+        let Some(setter) = self
+            .imp()
+            .setter_box
+            .observe_children()
+            .item(index as u32)
+            .and_downcast::<RnColorSetter>()
+        else {
+            return;
+        };
+        setter.set_active(true);
+        // when the setter was already active, the color would not be applied through the active notify
+        self.set_color_active_pad(setter.color());
     }
 
-    pub(crate) fn setter_6(&self) -> RnColorSetter {
-        self.imp().setter_6.get()
+    /// Add the palette and make it the active one.
+    pub(crate) fn add_palette(&self, palette: ColorPalette) {
+        let index = {
+            let mut palettes = self.imp().palettes.borrow_mut();
+            palettes.push(palette);
+            palettes.len() - 1
+        };
+        self.select_palette(index);
     }
 
-    pub(crate) fn setter_7(&self) -> RnColorSetter {
-        self.imp().setter_7.get()
+    /// Make the palette at the index the active one. Clamped to the available palettes.
+    pub(crate) fn select_palette(&self, index: usize) {
+        let imp = self.imp();
+        let n_palettes = imp.palettes.borrow().len();
+        imp.active_palette
+            .set(index.min(n_palettes.saturating_sub(1)));
+        self.refresh_palette();
     }
 
-    pub(crate) fn setter_8(&self) -> RnColorSetter {
-        self.imp().setter_8.get()
+    /// Add the color to the front of the recent colors, removing duplicates.
+    pub(crate) fn push_recent_color(&self, color: Color) {
+        {
+            let mut recent_colors = self.imp().recent_colors.borrow_mut();
+            recent_colors.retain(|c| !c.approx_eq_f32(color));
+            recent_colors.insert(0, color);
+            recent_colors.truncate(Self::RECENT_COLORS_MAX);
+        }
+        self.refresh_recent_colors();
     }
 
-    pub(crate) fn setter_9(&self) -> RnColorSetter {
-        self.imp().setter_9.get()
+    pub(crate) fn load_from_settings(&self, settings: &gio::Settings) {
+        let imp = self.imp();
+        let mut palettes = settings
+            .get::<Vec<ColorPaletteVariant>>("colorpicker-palettes")
+            .into_iter()
+            .map(ColorPalette::from_variant)
+            .collect::<Vec<ColorPalette>>();
+        // On the first start after upgrading, the customized colors of older versions become the default palette
+        if settings.user_value("colorpicker-palettes").is_none()
+            && LEGACY_COLOR_KEYS
+                .iter()
+                .any(|key| settings.user_value(key).is_some())
+            && let Some(default_palette) = palettes.first_mut()
+        {
+            default_palette.colors = LEGACY_COLOR_KEYS
+                .iter()
+                .map(|key| {
+                    let (r, g, b, a) = settings.get::<(f64, f64, f64, f64)>(key);
+                    Color::new(r, g, b, a)
+                })
+                .collect();
+        }
+        let recent_colors = settings
+            .get::<Vec<(f64, f64, f64, f64)>>("colorpicker-recent-colors")
+            .into_iter()
+            .map(|(r, g, b, a)| Color::new(r, g, b, a))
+            .collect::<Vec<Color>>();
+
+        if !palettes.is_empty() {
+            imp.palettes.replace(palettes);
+        }
+        imp.recent_colors.replace(recent_colors);
+        self.select_palette(settings.uint("colorpicker-active-palette") as usize);
+        self.refresh_recent_colors();
     }
 
-    pub(crate) fn active_color_label(&self) -> Label {
-        self.imp().active_color_label.get()
+    pub(crate) fn save_to_settings(&self, settings: &gio::Settings) {
+        let imp = self.imp();
+        let palettes = imp
+            .palettes
+            .borrow()
+            .iter()
+            .map(ColorPalette::to_variant)
+            .collect::<Vec<ColorPaletteVariant>>();
+        let recent_colors = imp
+            .recent_colors
+            .borrow()
+            .iter()
+            .map(|c| (c.r, c.g, c.b, c.a))
+            .collect::<Vec<(f64, f64, f64, f64)>>();
+
+        if let Err(e) = settings.set("colorpicker-palettes", palettes.to_variant()) {
+            error!("Saving `colorpicker-palettes` to settings failed, Err: {e:?}");
+        }
+        if let Err(e) = settings.set(
+            "colorpicker-active-palette",
+            imp.active_palette.get() as u32,
+        ) {
+            error!("Saving `colorpicker-active-palette` to settings failed, Err: {e:?}");
+        }
+        if let Err(e) = settings.set("colorpicker-recent-colors", recent_colors.to_variant()) {
+            error!("Saving `colorpicker-recent-colors` to settings failed, Err: {e:?}");
+        }
     }
 
     pub(crate) fn init(&self, appwindow: &RnAppWindow) {
+        self.setup_actions(appwindow);
+
         self.imp().colordialog_button.connect_clicked(
             clone!(#[weak(rename_to=colorpicker)] self, #[weak] appwindow , move |_| {
                 if colorpicker.imp().color_dialog.upgrade().is_some() {
@@ -510,6 +442,7 @@ impl RnColorPicker {
                             Ok(new_color) => {
                                 colorpicker.set_color_active_pad(new_color);
                                 colorpicker.set_color_active_setter(new_color);
+                                colorpicker.push_recent_color(new_color.into_compose_color());
                             },
                             // this reports as error if the dialog is dismissed by the user.
                             // The API is a bit odd, expected would be Result<Option<RGBA>>
@@ -523,27 +456,461 @@ impl RnColorPicker {
         );
     }
 
-    fn set_color_active_setter(&self, color: gdk::RGBA) {
+    fn setup_actions(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+
+        let action_select_palette = gio::SimpleAction::new_stateful(
+            "select-palette",
+            Some(&u32::static_variant_type()),
+            &(imp.active_palette.get() as u32).to_variant(),
+        );
+        imp.action_group.add_action(&action_select_palette);
+        let action_add_color = gio::SimpleAction::new("add-color", None);
+        imp.action_group.add_action(&action_add_color);
+        let action_remove_color = gio::SimpleAction::new("remove-color", None);
+        imp.action_group.add_action(&action_remove_color);
+        let action_new_palette = gio::SimpleAction::new("new-palette", None);
+        imp.action_group.add_action(&action_new_palette);
+        let action_rename_palette = gio::SimpleAction::new("rename-palette", None);
+        imp.action_group.add_action(&action_rename_palette);
+        let action_remove_palette = gio::SimpleAction::new("remove-palette", None);
+        imp.action_group.add_action(&action_remove_palette);
+        let action_import_palette = gio::SimpleAction::new("import-palette", None);
+        imp.action_group.add_action(&action_import_palette);
+        let action_export_palette = gio::SimpleAction::new("export-palette", None);
+        imp.action_group.add_action(&action_export_palette);
+        let action_clear_recent_colors = gio::SimpleAction::new("clear-recent-colors", None);
+        imp.action_group.add_action(&action_clear_recent_colors);
+
+        // Select palette
+        action_select_palette.connect_activate(clone!(
+            #[weak(rename_to=colorpicker)]
+            self,
+            move |_, target| {
+                let Some(index) = target.and_then(|t| t.get::<u32>()) else {
+                    error!("select-palette action activated with invalid target");
+                    return;
+                };
+                colorpicker.select_palette(index as usize);
+            }
+        ));
+
+        // Add the color of the active pad to the palette
+        action_add_color.connect_activate(clone!(
+            #[weak(rename_to=colorpicker)]
+            self,
+            move |_, _| {
+                let color = if colorpicker.stroke_color_pad_active() {
+                    colorpicker.stroke_color()
+                } else {
+                    colorpicker.fill_color()
+                };
+                let index = colorpicker.active_palette().colors.len();
+                colorpicker.insert_palette_color(index, color.into_compose_color());
+            }
+        ));
+
+        // Remove the color of the active setter from the palette
+        action_remove_color.connect_activate(clone!(
+            #[weak(rename_to=colorpicker)]
+            self,
+            move |_, _| {
+                let Some(index) = colorpicker.active_setter_index() else {
+                    return;
+                };
+                let imp = colorpicker.imp();
+                imp.palettes.borrow_mut()[imp.active_palette.get()]
+                    .colors
+                    .remove(index);
+                colorpicker.refresh_palette();
+            }
+        ));
+
+        // New palette
+        action_new_palette.connect_activate(clone!(
+            #[weak(rename_to=colorpicker)]
+            self,
+            #[weak]
+            appwindow,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    colorpicker,
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let Some(name) = dialogs::dialog_color_palette_name(
+                            &appwindow,
+                            &gettext("New Palette"),
+                            &gettext("New Palette"),
+                        )
+                        .await
+                        else {
+                            return;
+                        };
+                        colorpicker.add_palette(ColorPalette::new(name, Vec::new()));
+                    }
+                ));
+            }
+        ));
+
+        // Rename palette
+        action_rename_palette.connect_activate(clone!(
+            #[weak(rename_to=colorpicker)]
+            self,
+            #[weak]
+            appwindow,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    colorpicker,
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let Some(name) = dialogs::dialog_color_palette_name(
+                            &appwindow,
+                            &gettext("Rename Palette"),
+                            &colorpicker.active_palette().name,
+                        )
+                        .await
+                        else {
+                            return;
+                        };
+                        let imp = colorpicker.imp();
+                        imp.palettes.borrow_mut()[imp.active_palette.get()].name = name;
+                        colorpicker.refresh_palette();
+                    }
+                ));
+            }
+        ));
+
+        // Remove palette
+        action_remove_palette.connect_activate(clone!(
+            #[weak(rename_to=colorpicker)]
+            self,
+            move |_, _| {
+                let imp = colorpicker.imp();
+                {
+                    let mut palettes = imp.palettes.borrow_mut();
+                    // there always needs to be at least one palette
+                    if palettes.len() <= 1 {
+                        return;
+                    }
+                    palettes.remove(imp.active_palette.get());
+                }
+                colorpicker.select_palette(imp.active_palette.get().saturating_sub(1));
+            }
+        ));
+
+        // Import palette
+        action_import_palette.connect_activate(clone!(
+            #[weak]
+            appwindow,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::import::filedialog_import_color_palette(&appwindow).await;
+                    }
+                ));
+            }
+        ));
+
+        // Export palette
+        action_export_palette.connect_activate(clone!(
+            #[weak]
+            appwindow,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::export::filedialog_export_color_palette(&appwindow).await;
+                    }
+                ));
+            }
+        ));
+
+        // Clear recent colors
+        action_clear_recent_colors.connect_activate(clone!(
+            #[weak(rename_to=colorpicker)]
+            self,
+            move |_, _| {
+                colorpicker.imp().recent_colors.borrow_mut().clear();
+                colorpicker.refresh_recent_colors();
+            }
+        ));
+
+        self.update_actions_state();
+    }
+
+    /// Update the enabled state of the actions that depend on the palettes, recent colors and active setter.
+    fn update_actions_state(&self) {
         let imp = self.imp();
+        let set_enabled = |name: &str, enabled: bool| {
+            if let Some(action) = imp
+                .action_group
+                .lookup_action(name)
+                .and_downcast::<gio::SimpleAction>()
+            {
+                action.set_enabled(enabled);
+            }
+        };
 
-        if imp.setter_1.is_active() {
-            imp.setter_1.set_color(color);
-        } else if imp.setter_2.is_active() {
-            imp.setter_2.set_color(color);
-        } else if imp.setter_3.is_active() {
-            imp.setter_3.set_color(color);
-        } else if imp.setter_4.is_active() {
-            imp.setter_4.set_color(color);
-        } else if imp.setter_5.is_active() {
-            imp.setter_5.set_color(color);
-        } else if imp.setter_6.is_active() {
-            imp.setter_6.set_color(color);
-        } else if imp.setter_7.is_active() {
-            imp.setter_7.set_color(color);
-        } else if imp.setter_8.is_active() {
-            imp.setter_8.set_color(color);
-        } else if imp.setter_9.is_active() {
-            imp.setter_9.set_color(color);
+        set_enabled("remove-color", self.active_setter_index().is_some());
+        set_enabled("remove-palette", imp.palettes.borrow().len() > 1);
+        set_enabled(
+            "clear-recent-colors",
+            !imp.recent_colors.borrow().is_empty(),
+        );
+        if let Some(action) = imp.action_group.lookup_action("select-palette") {
+            action.change_state(&(imp.active_palette.get() as u32).to_variant());
+        }
+    }
+
+    /// Rebuild the setters and the menu from the active palette.
+    fn refresh_palette(&self) {
+        let imp = self.imp();
+        let setter_box = imp.setter_box.get();
+
+        while let Some(child) = setter_box.first_child() {
+            setter_box.remove(&child);
+        }
+        let colors = self.active_palette().colors;
+        for (i, color) in colors.into_iter().enumerate() {
+            setter_box.append(&self.create_palette_setter(i, color));
+        }
+
+        imp.palette_menubutton
+            .set_menu_model(Some(&self.create_palette_menu()));
+        self.update_actions_state();
+    }
+
+    /// Rebuild the setters of the recent colors.
+    fn refresh_recent_colors(&self) {
+        let imp = self.imp();
+        let recent_box = imp.recent_box.get();
+
+        while let Some(child) = recent_box.first_child() {
+            recent_box.remove(&child);
+        }
+        let recent_colors = imp.recent_colors.borrow().clone();
+        for color in recent_colors.iter() {
+            recent_box.append(&self.create_recent_setter(*color));
+        }
+        imp.recent_separator.set_visible(!recent_colors.is_empty());
+        recent_box.set_visible(!recent_colors.is_empty());
+        self.update_actions_state();
+    }
+
+    fn create_palette_menu(&self) -> gio::Menu {
+        let menu = gio::Menu::new();
+
+        let palettes_section = gio::Menu::new();
+        for (i, palette) in self.imp().palettes.borrow().iter().enumerate() {
+            let item = gio::MenuItem::new(Some(&palette.name), None);
+            item.set_action_and_target_value(
+                Some("colorpicker.select-palette"),
+                Some(&(i as u32).to_variant()),
+            );
+            palettes_section.append_item(&item);
+        }
+        menu.append_section(None, &palettes_section);
+
+        let colors_section = gio::Menu::new();
+        colors_section.append(
+            Some(&gettext("Add Current Color")),
+            Some("colorpicker.add-color"),
+        );
+        colors_section.append(
+            Some(&gettext("Remove Selected Color")),
+            Some("colorpicker.remove-color"),
+        );
+        colors_section.append(
+            Some(&gettext("Clear Recent Colors")),
+            Some("colorpicker.clear-recent-colors"),
+        );
+        menu.append_section(None, &colors_section);
+
+        let manage_section = gio::Menu::new();
+        manage_section.append(
+            Some(&gettext("New Palette…")),
+            Some("colorpicker.new-palette"),
+        );
+        manage_section.append(
+            Some(&gettext("Rename Palette…")),
+            Some("colorpicker.rename-palette"),
+        );
+        manage_section.append(
+            Some(&gettext("Remove Palette")),
+            Some("colorpicker.remove-palette"),
+        );
+        menu.append_section(None, &manage_section);
+
+        let file_section = gio::Menu::new();
+        file_section.append(
+            Some(&gettext("Import Palette…")),
+            Some("colorpicker.import-palette"),
+        );
+        file_section.append(
+            Some(&gettext("Export Palette…")),
+            Some("colorpicker.export-palette"),
+        );
+        menu.append_section(None, &file_section);
+
+        menu
+    }
+
+    /// Create a setter for the palette color at the index.
+    ///
+    /// Setters can be dragged onto each other to reorder them, and colors can be dropped onto them to insert
+    /// them into the palette.
+    fn create_palette_setter(&self, index: usize, color: Color) -> RnColorSetter {
+        let setter = self.create_setter(color);
+
+        let drag_source = DragSource::builder()
+            .actions(gdk::DragAction::MOVE)
+            .content(&gdk::ContentProvider::for_value(&(index as u32).to_value()))
+            .build();
+        drag_source.connect_drag_begin(clone!(
+            #[weak]
+            setter,
+            move |drag_source, _| {
+                drag_source.set_icon(Some(&WidgetPaintable::new(Some(&setter))), 0, 0);
+            }
+        ));
+        setter.add_controller(drag_source);
+
+        let drop_target = DropTarget::new(
+            glib::Type::INVALID,
+            gdk::DragAction::MOVE | gdk::DragAction::COPY,
+        );
+        drop_target.set_types(&[u32::static_type(), gdk::RGBA::static_type()]);
+        drop_target.connect_drop(clone!(
+            #[weak(rename_to=colorpicker)]
+            self,
+            #[upgrade_or]
+            false,
+            move |_, value, _, _| {
+                if let Ok(from) = value.get::<u32>() {
+                    colorpicker.move_palette_color(from as usize, index);
+                    true
+                } else if let Ok(color) = value.get::<gdk::RGBA>() {
+                    colorpicker.insert_palette_color(index, color.into_compose_color());
+                    true
+                } else {
+                    false
+                }
+            }
+        ));
+        setter.add_controller(drop_target);
+
+        setter
+    }
+
+    /// Create a setter for a recent color. It can be dragged onto the palette to add it.
+    fn create_recent_setter(&self, color: Color) -> RnColorSetter {
+        let setter = self.create_setter(color);
+        setter.add_css_class("recent");
+        setter.set_size_request(22, 22);
+        setter.set_valign(gtk4::Align::Center);
+
+        let drag_source = DragSource::builder()
+            .actions(gdk::DragAction::COPY)
+            .content(&gdk::ContentProvider::for_value(
+                &gdk::RGBA::from_compose_color(color).to_value(),
+            ))
+            .build();
+        setter.add_controller(drag_source);
+
+        setter
+    }
+
+    fn create_setter(&self, color: Color) -> RnColorSetter {
+        let setter = RnColorSetter::new();
+        setter.set_color(gdk::RGBA::from_compose_color(color));
+
+        setter.connect_active_notify(clone!(
+            #[weak(rename_to=colorpicker)]
+            self,
+            move |setter| {
+                if setter.is_active() {
+                    for other in colorpicker.setters() {
+                        if &other != setter {
+                            other.set_active(false);
+                        }
+                    }
+                    // Must come after setting the other setters inactive
+                    colorpicker.set_color_active_pad(setter.color());
+                }
+                colorpicker.update_actions_state();
+            }
+        ));
+
+        setter
+    }
+
+    /// All setters, first the ones of the palette, then the ones of the recent colors.
+    fn setters(&self) -> Vec<RnColorSetter> {
+        let imp = self.imp();
+        imp.setter_box
+            .observe_children()
+            .into_iter()
+            .chain(imp.recent_box.observe_children().into_iter())
+            .filter_map(|child| child.ok().and_downcast::<RnColorSetter>())
+            .collect()
+    }
+
+    /// The index of the active palette setter.
+    fn active_setter_index(&self) -> Option<usize> {
+        self.imp()
+            .setter_box
+            .observe_children()
+            .into_iter()
+            .filter_map(|child| child.ok().and_downcast::<RnColorSetter>())
+            .position(|setter| setter.is_active())
+    }
+
+    fn move_palette_color(&self, from: usize, to: usize) {
+        let imp = self.imp();
+        {
+            let mut palettes = imp.palettes.borrow_mut();
+            let colors = &mut palettes[imp.active_palette.get()].colors;
+            if from == to || from >= colors.len() || to >= colors.len() {
+                return;
+            }
+            let color = colors.remove(from);
+            colors.insert(to, color);
+        }
+        self.refresh_palette();
+    }
+
+    fn insert_palette_color(&self, index: usize, color: Color) {
+        let imp = self.imp();
+        {
+            let mut palettes = imp.palettes.borrow_mut();
+            let colors = &mut palettes[imp.active_palette.get()].colors;
+            colors.insert(index.min(colors.len()), color);
+        }
+        self.refresh_palette();
+    }
+
+    fn set_color_active_setter(&self, color: gdk::RGBA) {
+        let imp = self.imp();
+        let Some(index) = self.active_setter_index() else {
+            return;
+        };
+
+        imp.palettes.borrow_mut()[imp.active_palette.get()].colors[index] =
+            color.into_compose_color();
+        if let Some(setter) = imp
+            .setter_box
+            .observe_children()
+            .item(index as u32)
+            .and_downcast::<RnColorSetter>()
+        {
+            setter.set_color(color);
         }
     }
 
@@ -566,16 +933,8 @@ impl RnColorPicker {
     }
 
     pub(crate) fn deselect_setters(&self) {
-        let imp = self.imp();
-
-        imp.setter_1.set_active(false);
-        imp.setter_2.set_active(false);
-        imp.setter_3.set_active(false);
-        imp.setter_4.set_active(false);
-        imp.setter_5.set_active(false);
-        imp.setter_6.set_active(false);
-        imp.setter_7.set_active(false);
-        imp.setter_8.set_active(false);
-        imp.setter_9.set_active(false);
+        for setter in self.setters() {
+            setter.set_active(false);
+        }
     }
 }
@@ -1,5 +1,5 @@
 // Imports
-use gtk4::{CompositeTemplate, PopoverMenu, Widget, glib, prelude::*, subclass::prelude::*};
+use gtk4::{CompositeTemplate, PopoverMenu, Widget, gio, glib, prelude::*, subclass::prelude::*};
 
 mod imp {
     use super::*;
@@ -9,6 +9,10 @@ mod imp {
     pub(crate) struct RnContextMenu {
         #[template_child]
         pub(crate) popover: TemplateChild<PopoverMenu>,
+        #[template_child]
+        pub(crate) menu_model: TemplateChild<gio::Menu>,
+        #[template_child]
+        pub(crate) stroke_menu_model: TemplateChild<gio::Menu>,
     }
 
     #[glib::object_subclass]
@@ -67,4 +71,15 @@ impl RnContextMenu {
     pub(crate) fn popover(&self) -> PopoverMenu {
         self.imp().popover.get()
     }
+
+    /// Show the items for editing the selected strokes, or only the clipboard items.
+    pub(crate) fn set_show_stroke_items(&self, show_stroke_items: bool) {
+        let imp = self.imp();
+        if show_stroke_items {
+            imp.popover
+                .set_menu_model(Some(&imp.stroke_menu_model.get()));
+        } else {
+            imp.popover.set_menu_model(Some(&imp.menu_model.get()));
+        }
+    }
 }
@@ -0,0 +1,424 @@
+// Imports
+use crate::{RnAppWindow, config};
+use adw::prelude::*;
+use gettextrs::gettext;
+use gtk4::{
+    Builder, EventControllerKey, ListBox, PropagationPhase, SearchEntry, ShortcutLabel, Stack, gdk,
+    gio, glib, glib::clone,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+use tracing::error;
+
+/// A command that can be executed from the command palette.
+#[derive(Debug, Clone)]
+pub(crate) struct PaletteCommand {
+    /// The detailed action name, including the action group prefix and the target, e.g. `win.pen-style::brush`.
+    pub(crate) detailed_action: &'static str,
+    /// The translated title.
+    pub(crate) title: String,
+    /// The translated category the command belongs to.
+    pub(crate) category: String,
+}
+
+impl PaletteCommand {
+    fn new(detailed_action: &'static str, title: String, category: &str) -> Self {
+        Self {
+            detailed_action,
+            title,
+            category: category.to_string(),
+        }
+    }
+
+    /// Score how well the query matches the command, None when it doesn't match at all.
+    fn score(&self, query: &str) -> Option<u32> {
+        fuzzy_score(query, &self.title)
+            .into_iter()
+            .chain(fuzzy_score(
+                query,
+                &format!("{} {}", self.category, self.title),
+            ))
+            .max()
+    }
+}
+
+/// The registry of all commands that are offered in the command palette.
+///
+/// New app or window actions that are useful to trigger from the keyboard should be registered here.
+pub(crate) fn commands() -> Vec<PaletteCommand> {
+    let general = gettext("General");
+    let document = gettext("Document");
+    let edit = gettext("Edit");
+    let pens = gettext("Pens");
+    let view = gettext("View");
+    let settings = gettext("Settings");
+
+    vec![
+        PaletteCommand::new("app.new-window", gettext("New Window"), &general),
+        PaletteCommand::new("win.new-tab", gettext("New Tab"), &general),
+        PaletteCommand::new("win.active-tab-close", gettext("Close Tab"), &general),
+        PaletteCommand::new("win.open-settings", gettext("Open Settings"), &general),
+        PaletteCommand::new(
+            "win.keyboard-shortcuts",
+            gettext("Show Keyboard Shortcuts"),
+            &general,
+        ),
+        PaletteCommand::new("win.about", gettext("About Rnote"), &general),
+        PaletteCommand::new("app.quit", gettext("Quit"), &general),
+        PaletteCommand::new("win.new-doc", gettext("New Document"), &document),
+        PaletteCommand::new("win.open-doc", gettext("Open Document"), &document),
+        PaletteCommand::new("win.save-doc", gettext("Save Document"), &document),
+        PaletteCommand::new("win.save-doc-as", gettext("Save Document As"), &document),
+        PaletteCommand::new("win.print-doc", gettext("Print Document"), &document),
+        PaletteCommand::new("win.import-file", gettext("Import File"), &document),
+        PaletteCommand::new("win.export-doc", gettext("Export Document"), &document),
+        PaletteCommand::new("win.export-doc-pages", gettext("Export Pages"), &document),
+        PaletteCommand::new(
+            "win.export-selection",
+            gettext("Export Selection"),
+            &document,
+        ),
+        PaletteCommand::new("win.add-page-to-doc", gettext("Add Page"), &document),
+        PaletteCommand::new(
+            "win.remove-page-from-doc",
+            gettext("Remove Page"),
+            &document,
+        ),
+        PaletteCommand::new(
+            "win.resize-to-fit-content",
+            gettext("Resize to Fit Content"),
+            &document,
+        ),
+        PaletteCommand::new("win.clear-doc", gettext("Clear Document"), &document),
+        PaletteCommand::new("win.undo", gettext("Undo"), &edit),
+        PaletteCommand::new("win.redo", gettext("Redo"), &edit),
+        PaletteCommand::new("win.clipboard-copy", gettext("Copy"), &edit),
+        PaletteCommand::new("win.clipboard-cut", gettext("Cut"), &edit),
+        PaletteCommand::new("win.clipboard-paste", gettext("Paste"), &edit),
+        PaletteCommand::new("win.selection-select-all", gettext("Select All"), &edit),
+        PaletteCommand::new("win.selection-deselect-all", gettext("Deselect All"), &edit),
+        PaletteCommand::new(
+            "win.selection-duplicate",
+            gettext("Duplicate Selection"),
+            &edit,
+        ),
+        PaletteCommand::new(
+            "win.selection-invert-color",
+            gettext("Invert Color of Selection"),
+            &edit,
+        ),
+        PaletteCommand::new(
+            "win.selection-copy-style",
+            gettext("Copy Style of Selection"),
+            &edit,
+        ),
+        PaletteCommand::new(
+            "win.selection-paste-style",
+            gettext("Paste Style Onto Selection"),
+            &edit,
+        ),
+        PaletteCommand::new("win.selection-trash", gettext("Delete Selection"), &edit),
+        PaletteCommand::new(
+            "win.selection-select-similar",
+            gettext("Select Similar Strokes"),
+            &edit,
+        ),
+        PaletteCommand::new(
+            "win.selection-select-by-color",
+            gettext("Select Strokes With the Current Color"),
+            &edit,
+        ),
+        PaletteCommand::new(
+            "win.selection-bring-to-front",
+            gettext("Bring Selection to Front"),
+            &edit,
+        ),
+        PaletteCommand::new(
+            "win.selection-send-to-back",
+            gettext("Send Selection to Back"),
+            &edit,
+        ),
+        PaletteCommand::new("win.selection-raise", gettext("Raise Selection"), &edit),
+        PaletteCommand::new("win.selection-lower", gettext("Lower Selection"), &edit),
+        PaletteCommand::new("win.selection-group", gettext("Group Selection"), &edit),
+        PaletteCommand::new("win.selection-ungroup", gettext("Ungroup Selection"), &edit),
+        PaletteCommand::new(
+            "win.selection-locked",
+            gettext("Lock or Unlock Selection"),
+            &edit,
+        ),
+        PaletteCommand::new("win.pen-style::brush", gettext("Brush"), &pens),
+        PaletteCommand::new("win.pen-style::shaper", gettext("Shaper"), &pens),
+        PaletteCommand::new("win.pen-style::typewriter", gettext("Typewriter"), &pens),
+        PaletteCommand::new("win.pen-style::eraser", gettext("Eraser"), &pens),
+        PaletteCommand::new("win.pen-style::selector", gettext("Selector"), &pens),
+        PaletteCommand::new("win.pen-style::tools", gettext("Tools"), &pens),
+        PaletteCommand::new("win.pen-preset-save", gettext("Pin Current Pen"), &pens),
+        PaletteCommand::new("win.zoom-in", gettext("Zoom In"), &view),
+        PaletteCommand::new("win.zoom-out", gettext("Zoom Out"), &view),
+        PaletteCommand::new("win.zoom-fit-width", gettext("Zoom to Fit Width"), &view),
+        PaletteCommand::new("win.zoom-to(0.5)", gettext("Zoom to 50 %"), &view),
+        PaletteCommand::new("win.zoom-reset", gettext("Zoom to 100 %"), &view),
+        PaletteCommand::new("win.zoom-to(1.5)", gettext("Zoom to 150 %"), &view),
+        PaletteCommand::new("win.zoom-to(2.0)", gettext("Zoom to 200 %"), &view),
+        PaletteCommand::new("win.ruler", gettext("Show or Hide Ruler"), &view),
+        PaletteCommand::new("win.protractor", gettext("Show or Hide Protractor"), &view),
+        PaletteCommand::new(
+            "win.return-origin-page",
+            gettext("Return to Origin Page"),
+            &view,
+        ),
+        PaletteCommand::new(
+            "win.toggle-overview",
+            gettext("Toggle Tabs Overview"),
+            &view,
+        ),
+        PaletteCommand::new(
+            "win.active-tab-split",
+            gettext("Open Tab in Split View"),
+            &view,
+        ),
+        PaletteCommand::new("win.split-view-close", gettext("Close Split View"), &view),
+        PaletteCommand::new("win.fullscreen", gettext("Toggle Fullscreen"), &view),
+        PaletteCommand::new("win.focus-mode", gettext("Toggle Focus Mode"), &view),
+        PaletteCommand::new(
+            "win.presentation-mode",
+            gettext("Toggle Presentation Mode"),
+            &view,
+        ),
+        PaletteCommand::new(
+            "win.snap-positions",
+            gettext("Toggle Snap Positions"),
+            &settings,
+        ),
+        PaletteCommand::new(
+            "win.respect-borders",
+            gettext("Toggle Respect Borders"),
+            &settings,
+        ),
+        PaletteCommand::new(
+            "win.touch-drawing",
+            gettext("Toggle Touch Drawing"),
+            &settings,
+        ),
+        PaletteCommand::new("win.pen-sounds", gettext("Toggle Pen Sounds"), &settings),
+        PaletteCommand::new(
+            "win.stylus-calibration",
+            gettext("Calibrate Stylus"),
+            &settings,
+        ),
+        PaletteCommand::new("win.autosave", gettext("Toggle Autosave"), &settings),
+    ]
+}
+
+/// Shows the command palette, where the registered commands can be searched and executed with the keyboard.
+pub(crate) fn dialog_command_palette(appwindow: &RnAppWindow) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/commandpalette.ui").as_str(),
+    );
+    let dialog: adw::Dialog = builder.object("dialog_command_palette").unwrap();
+    let search_entry: SearchEntry = builder.object("command_palette_search_entry").unwrap();
+    let stack: Stack = builder.object("command_palette_stack").unwrap();
+    let listbox: ListBox = builder.object("command_palette_listbox").unwrap();
+
+    let commands = Rc::new(
+        commands()
+            .into_iter()
+            .filter(|command| action_enabled(appwindow, command.detailed_action))
+            .collect::<Vec<PaletteCommand>>(),
+    );
+    // The indices of the commands in the order they are currently listed
+    let listed: Rc<RefCell<Vec<usize>>> = Rc::default();
+
+    let update_list = clone!(
+        #[weak]
+        appwindow,
+        #[weak]
+        listbox,
+        #[weak]
+        stack,
+        #[strong]
+        commands,
+        #[strong]
+        listed,
+        move |query: &str| {
+            let mut matches = commands
+                .iter()
+                .enumerate()
+                .filter_map(|(i, command)| command.score(query).map(|score| (i, score)))
+                .collect::<Vec<(usize, u32)>>();
+            // stable, so the registry order is kept for equal scores
+            matches.sort_by(|(_, first), (_, second)| second.cmp(first));
+
+            listbox.remove_all();
+            for (i, _) in matches.iter() {
+                listbox.append(&create_command_row(&appwindow, &commands[*i]));
+            }
+            listbox.select_row(listbox.row_at_index(0).as_ref());
+            stack.set_visible_child_name(if matches.is_empty() {
+                "empty"
+            } else {
+                "commands"
+            });
+            *listed.borrow_mut() = matches.into_iter().map(|(i, _)| i).collect();
+        }
+    );
+    update_list("");
+
+    let execute = clone!(
+        #[weak]
+        appwindow,
+        #[weak]
+        dialog,
+        #[strong]
+        commands,
+        #[strong]
+        listed,
+        move |index: i32| {
+            let Some(command) = usize::try_from(index)
+                .ok()
+                .and_then(|index| listed.borrow().get(index).copied())
+                .map(|i| &commands[i])
+            else {
+                return;
+            };
+            dialog.close();
+            execute_command(&appwindow, command);
+        }
+    );
+
+    search_entry.connect_search_changed(clone!(
+        #[strong]
+        update_list,
+        move |search_entry| {
+            update_list(search_entry.text().as_str());
+        }
+    ));
+
+    search_entry.connect_activate(clone!(
+        #[weak]
+        listbox,
+        #[strong]
+        execute,
+        move |_| {
+            if let Some(row) = listbox.selected_row() {
+                execute(row.index());
+            }
+        }
+    ));
+
+    listbox.connect_row_activated(clone!(
+        #[strong]
+        execute,
+        move |_, row| {
+            execute(row.index());
+        }
+    ));
+
+    // Move the selection while the focus stays in the search entry
+    let key_controller = EventControllerKey::builder()
+        .propagation_phase(PropagationPhase::Capture)
+        .build();
+    key_controller.connect_key_pressed(clone!(
+        #[weak]
+        listbox,
+        #[upgrade_or]
+        glib::Propagation::Proceed,
+        move |_, key, _, _| {
+            let offset = match key {
+                gdk::Key::Down | gdk::Key::KP_Down => 1,
+                gdk::Key::Up | gdk::Key::KP_Up => -1,
+                _ => return glib::Propagation::Proceed,
+            };
+            let current = listbox.selected_row().map(|row| row.index()).unwrap_or(-1);
+            if let Some(row) = listbox.row_at_index((current + offset).max(0)) {
+                listbox.select_row(Some(&row));
+            }
+            glib::Propagation::Stop
+        }
+    ));
+    search_entry.add_controller(key_controller);
+
+    dialog.present(appwindow.root().as_ref());
+    search_entry.grab_focus();
+}
+
+fn create_command_row(appwindow: &RnAppWindow, command: &PaletteCommand) -> adw::ActionRow {
+    let row = adw::ActionRow::builder()
+        .title(command.title.as_str())
+        .subtitle(command.category.as_str())
+        .activatable(true)
+        .build();
+
+    if let Some(accel) = appwindow
+        .app()
+        .accels_for_action(command.detailed_action)
+        .first()
+    {
+        row.add_suffix(
+            &ShortcutLabel::builder()
+                .accelerator(accel.as_str())
+                .valign(gtk4::Align::Center)
+                .build(),
+        );
+    }
+
+    row
+}
+
+fn execute_command(appwindow: &RnAppWindow, command: &PaletteCommand) {
+    let (name, target) = match gio::Action::parse_detailed_name(command.detailed_action) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            error!(
+                "Parsing detailed action name `{}` of palette command failed, Err: {e:?}",
+                command.detailed_action
+            );
+            return;
+        }
+    };
+    if let Err(e) = appwindow.activate_action(&name, target.as_ref()) {
+        error!("Activating action `{name}` from the command palette failed, Err: {e:?}");
+    }
+}
+
+/// Whether the action of the detailed action name exists and is enabled.
+fn action_enabled(appwindow: &RnAppWindow, detailed_action: &str) -> bool {
+    let Ok((name, _)) = gio::Action::parse_detailed_name(detailed_action) else {
+        return false;
+    };
+    let action = if let Some(name) = name.strip_prefix("win.") {
+        appwindow.lookup_action(name)
+    } else if let Some(name) = name.strip_prefix("app.") {
+        appwindow.app().lookup_action(name)
+    } else {
+        None
+    };
+    action.is_some_and(|action| action.is_enabled())
+}
+
+/// Match the query as a case-insensitive subsequence of the text.
+///
+/// Consecutive matches and matches at the start of words score higher. Returns None when the text doesn't contain
+/// every character of the query in order.
+fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let text = text.to_lowercase().chars().collect::<Vec<char>>();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut prev_match: Option<usize> = None;
+
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = pos + text[pos..].iter().position(|c| *c == q)?;
+        score += 1;
+        if prev_match.is_some_and(|prev| prev + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        prev_match = Some(found);
+        pos = found + 1;
+    }
+
+    Some(score)
+}
@@ -3,19 +3,24 @@
 // Imports
 use crate::RnStrokeContentPreview;
 use crate::canvas::{self, RnCanvas};
-use crate::{RnAppWindow, config};
+use crate::{RnAppWindow, config, keyboardshortcuts, settingsprofiles};
 use adw::prelude::*;
 use gettextrs::gettext;
-use gtk4::{Builder, Button, FileDialog, FileFilter, Label, gio, glib, glib::clone};
+use gtk4::{
+    Builder, Button, Entry, FileDialog, FileFilter, Label, MenuButton, Orientation, Popover, gio,
+    glib, glib::clone,
+};
 use num_traits::ToPrimitive;
+use p2d::bounding_volume::Aabb;
 use rnote_compose::SplitOrder;
 use rnote_engine::document::Layout;
 use rnote_engine::engine::export::{
-    DocExportFormat, DocExportPrefs, DocPagesExportFormat, DocPagesExportPrefs,
-    SelectionExportFormat, SelectionExportPrefs,
+    DocExportFormat, DocExportPrefs, DocPagesExportFormat, DocPagesExportPrefs, PageSelection,
+    SelectionExportFormat, SelectionExportPrefs, bitmap_image_scale,
 };
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
+use std::str::FromStr;
 use tracing::{debug, error};
 
 pub(crate) async fn dialog_save_doc_as(appwindow: &RnAppWindow, canvas: &RnCanvas) {
@@ -92,6 +97,197 @@ pub(crate) async fn dialog_save_doc_as(appwindow: &RnAppWindow, canvas: &RnCanva
     }
 }
 
+/// Exports the active color palette as a GIMP palette (.gpl) file.
+pub(crate) async fn filedialog_export_color_palette(appwindow: &RnAppWindow) {
+    let palette = appwindow.overlays().colorpicker().active_palette();
+
+    let filter = FileFilter::new();
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.gpl");
+    } else {
+        filter.add_mime_type("application/x-gimp-palette");
+    }
+    filter.add_suffix("gpl");
+    filter.set_name(Some(&gettext("GIMP Palette")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let filedialog = FileDialog::builder()
+        .title(gettext("Export Palette"))
+        .modal(true)
+        .accept_label(gettext("Export"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .initial_name(palette.name.clone() + ".gpl")
+        .build();
+
+    match filedialog.save_future(Some(appwindow)).await {
+        Ok(selected_file) => {
+            match crate::utils::create_replace_file_future(
+                palette.to_gpl().into_bytes(),
+                &selected_file,
+            )
+            .await
+            {
+                Ok(()) => {
+                    appwindow.overlays().dispatch_toast_text(
+                        &gettext("Exported palette successfully"),
+                        crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                    );
+                }
+                Err(e) => {
+                    error!("Exporting color palette failed, Err: {e:?}");
+                    appwindow
+                        .overlays()
+                        .dispatch_toast_error(&gettext("Exporting palette failed"));
+                }
+            }
+        }
+        Err(e) => {
+            debug!("Did not export palette (Error or dialog dismissed by user), Err: {e:?}");
+        }
+    }
+}
+
+pub(crate) async fn filedialog_export_keyboard_shortcuts(appwindow: &RnAppWindow) {
+    let profile = match keyboardshortcuts::export_profile(&appwindow.app()) {
+        Ok(profile) => profile,
+        Err(e) => {
+            error!("Serializing keyboard shortcuts profile failed, Err: {e:?}");
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Exporting shortcuts profile failed"));
+            return;
+        }
+    };
+
+    let filter = FileFilter::new();
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.json");
+    } else {
+        filter.add_mime_type("application/json");
+    }
+    filter.add_suffix("json");
+    filter.set_name(Some(&gettext("Shortcuts Profile")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let filedialog = FileDialog::builder()
+        .title(gettext("Export Shortcuts Profile"))
+        .modal(true)
+        .accept_label(gettext("Export"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .initial_name(gettext("shortcuts") + ".json")
+        .build();
+
+    match filedialog.save_future(Some(appwindow)).await {
+        Ok(selected_file) => {
+            match crate::utils::create_replace_file_future(profile.into_bytes(), &selected_file)
+                .await
+            {
+                Ok(()) => {
+                    appwindow.overlays().dispatch_toast_text(
+                        &gettext("Exported shortcuts profile successfully"),
+                        crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                    );
+                }
+                Err(e) => {
+                    error!("Exporting keyboard shortcuts profile failed, Err: {e:?}");
+                    appwindow
+                        .overlays()
+                        .dispatch_toast_error(&gettext("Exporting shortcuts profile failed"));
+                }
+            }
+        }
+        Err(e) => {
+            debug!(
+                "Did not export shortcuts profile (Error or dialog dismissed by user), Err: {e:?}"
+            );
+        }
+    }
+}
+
+/// Export the current settings, including the engine configuration, as settings profile.
+pub(crate) async fn filedialog_export_settings_profile(appwindow: &RnAppWindow) {
+    let profile = match appwindow
+        .current_settings_profile()
+        .and_then(|profile| profile.to_json())
+    {
+        Ok(profile) => profile,
+        Err(e) => {
+            error!("Serializing settings profile failed, Err: {e:?}");
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Exporting settings profile failed"));
+            return;
+        }
+    };
+    let initial_name = appwindow
+        .app()
+        .app_settings()
+        .and_then(|app_settings| settingsprofiles::active_profile_name(&app_settings))
+        .unwrap_or_else(|| gettext("settings"));
+
+    let filter = FileFilter::new();
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.json");
+    } else {
+        filter.add_mime_type("application/json");
+    }
+    filter.add_suffix("json");
+    filter.set_name(Some(&gettext("Settings Profile")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let filedialog = FileDialog::builder()
+        .title(gettext("Export Settings Profile"))
+        .modal(true)
+        .accept_label(gettext("Export"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .initial_name(initial_name + ".json")
+        .build();
+
+    match filedialog.save_future(Some(appwindow)).await {
+        Ok(selected_file) => {
+            match crate::utils::create_replace_file_future(profile.into_bytes(), &selected_file)
+                .await
+            {
+                Ok(()) => {
+                    appwindow.overlays().dispatch_toast_text(
+                        &gettext("Exported settings profile successfully"),
+                        crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                    );
+                }
+                Err(e) => {
+                    error!("Exporting settings profile failed, Err: {e:?}");
+                    appwindow
+                        .overlays()
+                        .dispatch_toast_error(&gettext("Exporting settings profile failed"));
+                }
+            }
+        }
+        Err(e) => {
+            debug!(
+                "Did not export settings profile (Error or dialog dismissed by user), Err: {e:?}"
+            );
+        }
+    }
+}
+
 pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &RnCanvas) {
     let builder = Builder::from_resource(
         (String::from(config::APP_IDPATH) + "ui/dialogs/export.ui").as_str(),
@@ -103,20 +299,30 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
     let with_pattern_row: adw::SwitchRow = builder.object("export_doc_with_pattern_row").unwrap();
     let optimize_printing_row: adw::SwitchRow =
         builder.object("export_doc_optimize_printing_row").unwrap();
+    let invert_colors_row: adw::SwitchRow = builder.object("export_doc_invert_colors_row").unwrap();
+    let pdf_annotations_row: adw::SwitchRow =
+        builder.object("export_doc_pdf_annotations_row").unwrap();
     let export_format_row: adw::ComboRow = builder.object("export_doc_export_format_row").unwrap();
     let page_order_row: adw::ComboRow = builder.object("export_doc_page_order_row").unwrap();
+    let page_selection_entryrow: adw::EntryRow = builder
+        .object("export_doc_page_selection_entryrow")
+        .unwrap();
     let export_file_label: Label = builder.object("export_doc_export_file_label").unwrap();
     let export_file_button: Button = builder.object("export_doc_export_file_button").unwrap();
     let preview: RnStrokeContentPreview = builder.object("export_doc_preview").unwrap();
     let export_doc_button_cancel: Button = builder.object("export_doc_button_cancel").unwrap();
     let export_doc_button_confirm: Button = builder.object("export_doc_button_confirm").unwrap();
+    let presets_menubutton: MenuButton = builder.object("export_doc_presets_menubutton").unwrap();
 
+    canvas.apply_document_export_prefs();
     let initial_doc_export_prefs = appwindow
         .engine_config()
         .read()
         .export_prefs
-        .doc_export_prefs;
+        .doc_export_prefs
+        .clone();
     let doc_layout = canvas.engine_ref().document.config.layout;
+    let has_pdf_underlay = canvas.engine_ref().document.pdf_underlay.is_some();
 
     // initial widget state with the preferences
     let selected_file: Rc<RefCell<Option<gio::File>>> = Rc::new(RefCell::new(None));
@@ -126,13 +332,16 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
     preview.set_draw_background(initial_doc_export_prefs.with_background);
     preview.set_draw_pattern(initial_doc_export_prefs.with_pattern);
     preview.set_optimize_printing(initial_doc_export_prefs.optimize_printing);
-    preview.set_contents(
-        canvas
-            .engine_ref()
-            .extract_pages_content(initial_doc_export_prefs.page_order),
+    invert_colors_row.set_active(initial_doc_export_prefs.invert_colors);
+    invert_colors_row.set_sensitive(initial_doc_export_prefs.export_format == DocExportFormat::Pdf);
+    pdf_annotations_row.set_active(initial_doc_export_prefs.pdf_annotations);
+    pdf_annotations_row.set_sensitive(
+        has_pdf_underlay && initial_doc_export_prefs.export_format == DocExportFormat::Pdf,
     );
+    refresh_doc_export_preview(appwindow, canvas, &preview);
     export_format_row.set_selected(initial_doc_export_prefs.export_format.to_u32().unwrap());
     page_order_row.set_selected(initial_doc_export_prefs.page_order.to_u32().unwrap());
+    page_selection_entryrow.set_text(&initial_doc_export_prefs.page_selection.to_string());
     export_file_label.set_label(&gettext("- no file selected -"));
     page_order_row
         .set_sensitive(doc_layout == Layout::SemiInfinite || doc_layout == Layout::Infinite);
@@ -145,7 +354,7 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
             glib::spawn_future_local(clone!(#[strong] selected_file, #[weak] export_file_label, #[weak] button_confirm, #[weak] dialog, #[weak] canvas, #[weak] appwindow,  async move {
                 dialog.set_sensitive(false);
 
-                let doc_export_prefs = appwindow.engine_config().read().export_prefs.doc_export_prefs;
+                let doc_export_prefs = appwindow.engine_config().read().export_prefs.doc_export_prefs.clone();
                 let filedialog =
                     create_filedialog_export_doc(&appwindow, &canvas, &doc_export_prefs);
                 match filedialog.save_future(Some(&appwindow)).await {
@@ -229,6 +438,37 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
         }
     ));
 
+    invert_colors_row.connect_active_notify(clone!(
+        #[weak]
+        preview,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        move |invert_colors_row| {
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .doc_export_prefs
+                .invert_colors = invert_colors_row.is_active();
+            refresh_doc_export_preview(&appwindow, &canvas, &preview);
+        }
+    ));
+
+    pdf_annotations_row.connect_active_notify(clone!(
+        #[weak]
+        appwindow,
+        move |pdf_annotations_row| {
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .doc_export_prefs
+                .pdf_annotations = pdf_annotations_row.is_active();
+        }
+    ));
+
     export_format_row.connect_selected_notify(clone!(
         #[strong]
         selected_file,
@@ -237,7 +477,15 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
         #[weak]
         button_confirm,
         #[weak]
+        invert_colors_row,
+        #[weak]
+        pdf_annotations_row,
+        #[weak]
+        preview,
+        #[weak]
         appwindow,
+        #[weak]
+        canvas,
         move |row| {
             let export_format = DocExportFormat::try_from(row.selected()).unwrap();
             appwindow
@@ -246,6 +494,11 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
                 .export_prefs
                 .doc_export_prefs
                 .export_format = export_format;
+            // Only the Pdf export inverts the colors
+            invert_colors_row.set_sensitive(export_format == DocExportFormat::Pdf);
+            pdf_annotations_row
+                .set_sensitive(has_pdf_underlay && export_format == DocExportFormat::Pdf);
+            refresh_doc_export_preview(&appwindow, &canvas, &preview);
 
             // force the user to pick another file
             export_file_label.set_label(&gettext("- no file selected -"));
@@ -262,18 +515,78 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
         #[weak]
         canvas,
         move |row| {
-            let page_order = SplitOrder::try_from(row.selected()).unwrap();
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .doc_export_prefs
+                .page_order = SplitOrder::try_from(row.selected()).unwrap();
+            refresh_doc_export_preview(&appwindow, &canvas, &preview);
+        }
+    ));
 
+    page_selection_entryrow.connect_changed(clone!(
+        #[weak]
+        preview,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        move |entryrow| {
+            let Ok(page_selection) = PageSelection::from_str(&entryrow.text()) else {
+                entryrow.add_css_class("error");
+                return;
+            };
+            entryrow.remove_css_class("error");
             appwindow
                 .engine_config()
                 .write()
                 .export_prefs
                 .doc_export_prefs
-                .page_order = page_order;
-            preview.set_contents(canvas.engine_ref().extract_pages_content(page_order));
+                .page_selection = page_selection;
+            refresh_doc_export_preview(&appwindow, &canvas, &preview);
         }
     ));
 
+    // Shows the preferences of an applied preset, the handlers above update the preview
+    let show_export_prefs = clone!(
+        #[weak]
+        with_background_row,
+        #[weak]
+        with_pattern_row,
+        #[weak]
+        optimize_printing_row,
+        #[weak]
+        invert_colors_row,
+        #[weak]
+        pdf_annotations_row,
+        #[weak]
+        export_format_row,
+        #[weak]
+        page_order_row,
+        #[weak]
+        page_selection_entryrow,
+        #[weak]
+        appwindow,
+        move || {
+            let doc_export_prefs = appwindow
+                .engine_config()
+                .read()
+                .export_prefs
+                .doc_export_prefs
+                .clone();
+            export_format_row.set_selected(doc_export_prefs.export_format.to_u32().unwrap());
+            with_background_row.set_active(doc_export_prefs.with_background);
+            with_pattern_row.set_active(doc_export_prefs.with_pattern);
+            optimize_printing_row.set_active(doc_export_prefs.optimize_printing);
+            invert_colors_row.set_active(doc_export_prefs.invert_colors);
+            pdf_annotations_row.set_active(doc_export_prefs.pdf_annotations);
+            page_order_row.set_selected(doc_export_prefs.page_order.to_u32().unwrap());
+            page_selection_entryrow.set_text(&doc_export_prefs.page_selection.to_string());
+        }
+    );
+    setup_export_presets_menubutton(&presets_menubutton, appwindow, canvas, show_export_prefs);
+
     // Listen to responses
 
     export_doc_button_cancel.connect_clicked(clone!(
@@ -309,6 +622,7 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
                 appwindow.overlays().progressbar_abort();
                 return
             }
+            canvas.remember_document_export_prefs(&appwindow);
 
             appwindow.overlays().dispatch_toast_w_button(
                 &gettext("Exported document successfully"),
@@ -337,6 +651,25 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
     dialog.present(appwindow.root().as_ref());
 }
 
+/// Update the preview with the pages as they get exported with the current document export preferences.
+fn refresh_doc_export_preview(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    preview: &RnStrokeContentPreview,
+) {
+    let doc_export_prefs = appwindow
+        .engine_config()
+        .read()
+        .export_prefs
+        .doc_export_prefs
+        .clone();
+    preview.set_contents(
+        canvas
+            .engine_ref()
+            .extract_doc_export_pages_content(&doc_export_prefs),
+    );
+}
+
 fn create_filedialog_export_doc(
     appwindow: &RnAppWindow,
     canvas: &RnCanvas,
@@ -419,13 +752,32 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
     let optimize_printing_row: adw::SwitchRow = builder
         .object("export_doc_pages_optimize_printing_row")
         .unwrap();
+    let invert_colors_row: adw::SwitchRow = builder
+        .object("export_doc_pages_invert_colors_row")
+        .unwrap();
     let export_format_row: adw::ComboRow = builder
         .object("export_doc_pages_export_format_row")
         .unwrap();
     let page_order_row: adw::ComboRow = builder.object("export_doc_pages_page_order_row").unwrap();
+    let page_selection_entryrow: adw::EntryRow = builder
+        .object("export_doc_pages_page_selection_entryrow")
+        .unwrap();
     let bitmap_scalefactor_row: adw::SpinRow = builder
         .object("export_doc_pages_bitmap_scalefactor_row")
         .unwrap();
+    let bitmap_dpi_row: adw::SpinRow = builder.object("export_doc_pages_bitmap_dpi_row").unwrap();
+    let bitmap_max_dimension_row: adw::SpinRow = builder
+        .object("export_doc_pages_bitmap_max_dimension_row")
+        .unwrap();
+    let bitmap_tiling_row: adw::SwitchRow = builder
+        .object("export_doc_pages_bitmap_tiling_row")
+        .unwrap();
+    let transparent_background_row: adw::SwitchRow = builder
+        .object("export_doc_pages_transparent_background_row")
+        .unwrap();
+    let tiff_multipage_row: adw::SwitchRow = builder
+        .object("export_doc_pages_tiff_multipage_row")
+        .unwrap();
     let jpeg_quality_row: adw::SpinRow =
         builder.object("export_doc_pages_jpeg_quality_row").unwrap();
     let export_dir_label: Label = builder.object("export_doc_pages_export_dir_label").unwrap();
@@ -438,18 +790,27 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
     let page_files_naming_info_label: Label = builder
         .object("export_doc_pages_page_files_naming_info_label")
         .unwrap();
+    let page_files_naming_pattern_entryrow: adw::EntryRow = builder
+        .object("export_doc_pages_page_files_naming_pattern_entryrow")
+        .unwrap();
     let preview: RnStrokeContentPreview = builder.object("export_doc_pages_preview").unwrap();
     let export_doc_pages_button_cancel: Button =
         builder.object("export_doc_pages_button_cancel").unwrap();
     let export_doc_pages_button_confirm: Button =
         builder.object("export_doc_pages_button_confirm").unwrap();
+    let presets_menubutton: MenuButton = builder
+        .object("export_doc_pages_presets_menubutton")
+        .unwrap();
 
+    canvas.apply_document_export_prefs();
     let initial_doc_pages_export_prefs = appwindow
         .engine_config()
         .write()
         .export_prefs
-        .doc_pages_export_prefs;
+        .doc_pages_export_prefs
+        .clone();
     let doc_layout = canvas.engine_ref().document.config.layout;
+    let doc_dpi = canvas.engine_ref().document.config.format.dpi();
 
     // initial widget state with the preferences
     let selected_file: Rc<RefCell<Option<gio::File>>> = Rc::new(RefCell::new(None));
@@ -459,11 +820,8 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
     preview.set_draw_background(initial_doc_pages_export_prefs.with_background);
     preview.set_draw_pattern(initial_doc_pages_export_prefs.with_pattern);
     preview.set_optimize_printing(initial_doc_pages_export_prefs.optimize_printing);
-    preview.set_contents(
-        canvas
-            .engine_ref()
-            .extract_pages_content(initial_doc_pages_export_prefs.page_order),
-    );
+    invert_colors_row.set_active(initial_doc_pages_export_prefs.invert_colors);
+    refresh_doc_pages_export_preview(appwindow, canvas, &preview);
     export_format_row.set_selected(
         initial_doc_pages_export_prefs
             .export_format
@@ -471,11 +829,35 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
             .unwrap(),
     );
     page_order_row.set_selected(initial_doc_pages_export_prefs.page_order.to_u32().unwrap());
-    bitmap_scalefactor_row.set_sensitive(
-        initial_doc_pages_export_prefs.export_format == DocPagesExportFormat::Png
-            || initial_doc_pages_export_prefs.export_format == DocPagesExportFormat::Jpeg,
+    page_selection_entryrow.set_text(&initial_doc_pages_export_prefs.page_selection.to_string());
+    page_files_naming_pattern_entryrow
+        .set_text(&initial_doc_pages_export_prefs.page_files_naming_pattern);
+    bitmap_scalefactor_row.set_sensitive(initial_doc_pages_export_prefs.export_format.is_bitmap());
+    bitmap_dpi_row.set_sensitive(initial_doc_pages_export_prefs.export_format.is_bitmap());
+    bitmap_max_dimension_row
+        .set_sensitive(initial_doc_pages_export_prefs.export_format.is_bitmap());
+    transparent_background_row.set_sensitive(
+        initial_doc_pages_export_prefs
+            .export_format
+            .supports_transparency(),
     );
-    bitmap_scalefactor_row.set_value(initial_doc_pages_export_prefs.bitmap_scalefactor);
+    tiff_multipage_row
+        .set_sensitive(initial_doc_pages_export_prefs.export_format == DocPagesExportFormat::Tiff);
+    tiff_multipage_row.set_active(initial_doc_pages_export_prefs.tiff_multipage);
+    bitmap_scalefactor_row.set_value(
+        initial_doc_pages_export_prefs
+            .bitmap_dpi
+            .map(|dpi| dpi / doc_dpi)
+            .unwrap_or(initial_doc_pages_export_prefs.bitmap_scalefactor),
+    );
+    bitmap_dpi_row.set_value(bitmap_scalefactor_row.value() * doc_dpi);
+    bitmap_max_dimension_row.set_value(initial_doc_pages_export_prefs.bitmap_max_dimension as f64);
+    bitmap_tiling_row.set_active(initial_doc_pages_export_prefs.bitmap_tiling);
+    bitmap_max_dimension_row
+        .bind_property("sensitive", &bitmap_tiling_row, "sensitive")
+        .sync_create()
+        .build();
+    transparent_background_row.set_active(initial_doc_pages_export_prefs.transparent_background);
     jpeg_quality_row
         .set_sensitive(initial_doc_pages_export_prefs.export_format == DocPagesExportFormat::Jpeg);
     jpeg_quality_row.set_value(initial_doc_pages_export_prefs.jpeg_quality as f64);
@@ -490,11 +872,11 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
         None,
     );
     export_files_stemname_entryrow.set_text(&default_stem_name);
-    page_files_naming_info_label.set_text(
-        &(rnote_engine::utils::doc_pages_files_names(default_stem_name, 1)
-            + "."
-            + &initial_doc_pages_export_prefs.export_format.file_ext()),
-    );
+    page_files_naming_info_label.set_text(&doc_pages_files_naming_preview(
+        &appwindow,
+        &canvas,
+        &default_stem_name,
+    ));
 
     // Update prefs
 
@@ -503,7 +885,7 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
             glib::spawn_future_local(clone!(#[strong] selected_file, #[weak] export_dir_label, #[weak] button_confirm, #[weak] dialog, #[weak] canvas, #[weak] appwindow ,async move {
                 dialog.set_sensitive(false);
 
-                let doc_pages_export_prefs = appwindow.engine_config().write().export_prefs.doc_pages_export_prefs;
+                let doc_pages_export_prefs = appwindow.engine_config().read().export_prefs.doc_pages_export_prefs.clone();
                 let filedialog = create_filedialog_export_doc_pages(
                     &appwindow,
                     &canvas,
@@ -574,96 +956,331 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
         }
     ));
 
-    optimize_printing_row.connect_active_notify(clone!(
+    optimize_printing_row.connect_active_notify(clone!(
+        #[weak]
+        preview,
+        #[weak]
+        appwindow,
+        move |optimize_printing_row| {
+            let active = optimize_printing_row.is_active();
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .doc_pages_export_prefs
+                .optimize_printing = active;
+            preview.set_optimize_printing(active);
+        }
+    ));
+
+    invert_colors_row.connect_active_notify(clone!(
+        #[weak]
+        preview,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        move |invert_colors_row| {
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .doc_pages_export_prefs
+                .invert_colors = invert_colors_row.is_active();
+            refresh_doc_pages_export_preview(&appwindow, &canvas, &preview);
+        }
+    ));
+
+    preview.connect_notify_local(
+        Some("current-page"),
+        clone!(
+            #[weak]
+            appwindow,
+            #[weak]
+            canvas,
+            move |preview, _| {
+                update_doc_pages_export_preview_info(&appwindow, &canvas, preview);
+            }
+        ),
+    );
+
+    export_format_row.connect_selected_notify(clone!(
+        #[weak]
+        preview,
+        #[weak]
+        page_files_naming_info_label,
+        #[weak]
+        export_files_stemname_entryrow,
+        #[weak]
+        bitmap_scalefactor_row,
+        #[weak]
+        bitmap_dpi_row,
+        #[weak]
+        bitmap_max_dimension_row,
+        #[weak]
+        transparent_background_row,
+        #[weak]
+        tiff_multipage_row,
+        #[weak]
+        jpeg_quality_row,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        move |row| {
+            let export_format = DocPagesExportFormat::try_from(row.selected()).unwrap();
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .doc_pages_export_prefs
+                .export_format = export_format;
+
+            // Set the bitmap scalefactor sensitive only when exporting to a bitmap image
+            bitmap_scalefactor_row.set_sensitive(export_format.is_bitmap());
+            bitmap_dpi_row.set_sensitive(export_format.is_bitmap());
+            bitmap_max_dimension_row.set_sensitive(export_format.is_bitmap());
+            transparent_background_row.set_sensitive(export_format.supports_transparency());
+            tiff_multipage_row.set_sensitive(export_format == DocPagesExportFormat::Tiff);
+            // Set the jpeg quality pref only sensitive when jpeg is actually selected
+            jpeg_quality_row.set_sensitive(export_format == DocPagesExportFormat::Jpeg);
+            // The transparent background and the output size depend on the format
+            refresh_doc_pages_export_preview(&appwindow, &canvas, &preview);
+            // update file naming preview
+            page_files_naming_info_label.set_text(&doc_pages_files_naming_preview(
+                &appwindow,
+                &canvas,
+                &export_files_stemname_entryrow.text(),
+            ));
+        }
+    ));
+
+    page_order_row.connect_selected_notify(clone!(
+        #[weak]
+        preview,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        move |row| {
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .doc_pages_export_prefs
+                .page_order = SplitOrder::try_from(row.selected()).unwrap();
+            refresh_doc_pages_export_preview(&appwindow, &canvas, &preview);
+        }
+    ));
+
+    page_selection_entryrow.connect_changed(clone!(
+        #[weak]
+        preview,
+        #[weak]
+        page_files_naming_info_label,
+        #[weak]
+        export_files_stemname_entryrow,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        move |entryrow| {
+            let Ok(page_selection) = PageSelection::from_str(&entryrow.text()) else {
+                entryrow.add_css_class("error");
+                return;
+            };
+            entryrow.remove_css_class("error");
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .doc_pages_export_prefs
+                .page_selection = page_selection;
+            refresh_doc_pages_export_preview(&appwindow, &canvas, &preview);
+            // update file naming preview
+            page_files_naming_info_label.set_text(&doc_pages_files_naming_preview(
+                &appwindow,
+                &canvas,
+                &export_files_stemname_entryrow.text(),
+            ));
+        }
+    ));
+
+    page_files_naming_pattern_entryrow.connect_changed(clone!(
+        #[weak]
+        page_files_naming_info_label,
+        #[weak]
+        export_files_stemname_entryrow,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        move |entryrow| {
+            let pattern = entryrow.text();
+            // a pattern without the page placeholder would make all page files overwrite each other
+            if !DocPagesExportPrefs::page_files_naming_pattern_valid(&pattern) {
+                entryrow.add_css_class("error");
+                return;
+            }
+            entryrow.remove_css_class("error");
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .doc_pages_export_prefs
+                .page_files_naming_pattern = pattern.to_string();
+            // update file naming preview
+            page_files_naming_info_label.set_text(&doc_pages_files_naming_preview(
+                &appwindow,
+                &canvas,
+                &export_files_stemname_entryrow.text(),
+            ));
+        }
+    ));
+
+    // Set while one of the bitmap scalefactor and dpi rows is updated to match the other
+    let bitmap_rows_syncing = Rc::new(Cell::new(false));
+    bitmap_scalefactor_row.connect_changed(clone!(
+        #[strong]
+        bitmap_rows_syncing,
+        #[weak]
+        bitmap_dpi_row,
+        #[weak]
+        preview,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        move |bitmap_scalefactor_row| {
+            let scalefactor = bitmap_scalefactor_row.value();
+            {
+                let mut config = appwindow.engine_config().write();
+                let prefs = &mut config.export_prefs.doc_pages_export_prefs;
+                prefs.bitmap_scalefactor = scalefactor;
+                // Only an edit by the user replaces a chosen dpi, not syncing from the dpi row
+                if !bitmap_rows_syncing.get() {
+                    prefs.bitmap_dpi = None;
+                }
+            }
+            // keep the dpi row in sync, guarding against endless update loops
+            if (bitmap_dpi_row.value() - scalefactor * doc_dpi).abs() > 0.5 {
+                bitmap_rows_syncing.set(true);
+                bitmap_dpi_row.set_value(scalefactor * doc_dpi);
+                bitmap_rows_syncing.set(false);
+            }
+            update_doc_pages_export_preview_info(&appwindow, &canvas, &preview);
+        }
+    ));
+
+    bitmap_dpi_row.connect_changed(clone!(
+        #[strong]
+        bitmap_rows_syncing,
+        #[weak]
+        bitmap_scalefactor_row,
+        #[weak]
+        preview,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        move |bitmap_dpi_row| {
+            let dpi = bitmap_dpi_row.value();
+            if !bitmap_rows_syncing.get() {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .export_prefs
+                    .doc_pages_export_prefs
+                    .bitmap_dpi = Some(dpi);
+            }
+            if (bitmap_scalefactor_row.value() - dpi / doc_dpi).abs() > 0.05 {
+                bitmap_rows_syncing.set(true);
+                bitmap_scalefactor_row.set_value(dpi / doc_dpi);
+                bitmap_rows_syncing.set(false);
+            }
+            update_doc_pages_export_preview_info(&appwindow, &canvas, &preview);
+        }
+    ));
+
+    bitmap_max_dimension_row.connect_changed(clone!(
         #[weak]
         preview,
         #[weak]
         appwindow,
-        move |optimize_printing_row| {
-            let active = optimize_printing_row.is_active();
+        #[weak]
+        canvas,
+        move |bitmap_max_dimension_row| {
             appwindow
                 .engine_config()
                 .write()
                 .export_prefs
                 .doc_pages_export_prefs
-                .optimize_printing = active;
-            preview.set_optimize_printing(active);
+                .bitmap_max_dimension = bitmap_max_dimension_row.value().max(1.0) as u32;
+            update_doc_pages_export_preview_info(&appwindow, &canvas, &preview);
         }
     ));
 
-    export_format_row.connect_selected_notify(clone!(
+    bitmap_tiling_row.connect_active_notify(clone!(
         #[weak]
-        page_files_naming_info_label,
-        #[weak]
-        export_files_stemname_entryrow,
-        #[weak]
-        bitmap_scalefactor_row,
-        #[weak]
-        jpeg_quality_row,
+        preview,
         #[weak]
         appwindow,
-        move |row| {
-            let export_format = DocPagesExportFormat::try_from(row.selected()).unwrap();
+        #[weak]
+        canvas,
+        move |bitmap_tiling_row| {
             appwindow
                 .engine_config()
                 .write()
                 .export_prefs
                 .doc_pages_export_prefs
-                .export_format = export_format;
-
-            // Set the bitmap scalefactor sensitive only when exporting to a bitmap image
-            bitmap_scalefactor_row.set_sensitive(
-                export_format == DocPagesExportFormat::Png
-                    || export_format == DocPagesExportFormat::Jpeg,
-            );
-            // Set the jpeg quality pref only sensitive when jpeg is actually selected
-            jpeg_quality_row.set_sensitive(export_format == DocPagesExportFormat::Jpeg);
-            // update file naming preview
-            page_files_naming_info_label.set_text(
-                &(rnote_engine::utils::doc_pages_files_names(
-                    export_files_stemname_entryrow.text().to_string(),
-                    1,
-                ) + "."
-                    + &appwindow
-                        .engine_config()
-                        .write()
-                        .export_prefs
-                        .doc_pages_export_prefs
-                        .export_format
-                        .file_ext()),
-            );
+                .bitmap_tiling = bitmap_tiling_row.is_active();
+            update_doc_pages_export_preview_info(&appwindow, &canvas, &preview);
         }
     ));
 
-    page_order_row.connect_selected_notify(clone!(
+    transparent_background_row.connect_active_notify(clone!(
         #[weak]
         preview,
         #[weak]
         appwindow,
         #[weak]
         canvas,
-        move |row| {
-            let page_order = SplitOrder::try_from(row.selected()).unwrap();
+        move |transparent_background_row| {
             appwindow
                 .engine_config()
                 .write()
                 .export_prefs
                 .doc_pages_export_prefs
-                .page_order = page_order;
-            preview.set_contents(canvas.engine_ref().extract_pages_content(page_order));
+                .transparent_background = transparent_background_row.is_active();
+            refresh_doc_pages_export_preview(&appwindow, &canvas, &preview);
         }
     ));
 
-    bitmap_scalefactor_row.connect_changed(clone!(
+    tiff_multipage_row.connect_active_notify(clone!(
+        #[weak]
+        preview,
+        #[weak]
+        page_files_naming_info_label,
+        #[weak]
+        export_files_stemname_entryrow,
         #[weak]
         appwindow,
-        move |bitmap_scalefactor_row| {
+        #[weak]
+        canvas,
+        move |tiff_multipage_row| {
             appwindow
                 .engine_config()
                 .write()
                 .export_prefs
                 .doc_pages_export_prefs
-                .bitmap_scalefactor = bitmap_scalefactor_row.value();
+                .tiff_multipage = tiff_multipage_row.is_active();
+            update_doc_pages_export_preview_info(&appwindow, &canvas, &preview);
+            // update file naming preview
+            page_files_naming_info_label.set_text(&doc_pages_files_naming_preview(
+                &appwindow,
+                &canvas,
+                &export_files_stemname_entryrow.text(),
+            ));
         }
     ));
 
@@ -687,24 +1304,84 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
         button_confirm,
         #[weak]
         appwindow,
+        #[weak]
+        canvas,
         move |entryrow| {
             button_confirm.set_sensitive(!entryrow.text().is_empty());
 
             // update file naming preview
-            page_files_naming_info_label.set_text(
-                &(rnote_engine::utils::doc_pages_files_names(entryrow.text().to_string(), 1)
-                    + "."
-                    + &appwindow
-                        .engine_config()
-                        .read()
-                        .export_prefs
-                        .doc_pages_export_prefs
-                        .export_format
-                        .file_ext()),
-            );
+            page_files_naming_info_label.set_text(&doc_pages_files_naming_preview(
+                &appwindow,
+                &canvas,
+                &entryrow.text(),
+            ));
         }
     ));
 
+    // Shows the preferences of an applied preset, the handlers above update the preview
+    let show_export_prefs = clone!(
+        #[weak]
+        with_background_row,
+        #[weak]
+        with_pattern_row,
+        #[weak]
+        optimize_printing_row,
+        #[weak]
+        invert_colors_row,
+        #[weak]
+        export_format_row,
+        #[weak]
+        page_order_row,
+        #[weak]
+        page_selection_entryrow,
+        #[weak]
+        page_files_naming_pattern_entryrow,
+        #[weak]
+        tiff_multipage_row,
+        #[weak]
+        bitmap_scalefactor_row,
+        #[weak]
+        bitmap_dpi_row,
+        #[weak]
+        bitmap_max_dimension_row,
+        #[weak]
+        bitmap_tiling_row,
+        #[weak]
+        transparent_background_row,
+        #[weak]
+        jpeg_quality_row,
+        #[weak]
+        appwindow,
+        move || {
+            let doc_pages_export_prefs = appwindow
+                .engine_config()
+                .read()
+                .export_prefs
+                .doc_pages_export_prefs
+                .clone();
+            export_format_row.set_selected(doc_pages_export_prefs.export_format.to_u32().unwrap());
+            with_background_row.set_active(doc_pages_export_prefs.with_background);
+            with_pattern_row.set_active(doc_pages_export_prefs.with_pattern);
+            optimize_printing_row.set_active(doc_pages_export_prefs.optimize_printing);
+            invert_colors_row.set_active(doc_pages_export_prefs.invert_colors);
+            page_order_row.set_selected(doc_pages_export_prefs.page_order.to_u32().unwrap());
+            page_selection_entryrow.set_text(&doc_pages_export_prefs.page_selection.to_string());
+            page_files_naming_pattern_entryrow
+                .set_text(&doc_pages_export_prefs.page_files_naming_pattern);
+            tiff_multipage_row.set_active(doc_pages_export_prefs.tiff_multipage);
+            // Setting the dpi row keeps the chosen dpi, setting the scalefactor row resets it
+            match doc_pages_export_prefs.bitmap_dpi {
+                Some(dpi) => bitmap_dpi_row.set_value(dpi),
+                None => bitmap_scalefactor_row.set_value(doc_pages_export_prefs.bitmap_scalefactor),
+            }
+            bitmap_max_dimension_row.set_value(doc_pages_export_prefs.bitmap_max_dimension as f64);
+            bitmap_tiling_row.set_active(doc_pages_export_prefs.bitmap_tiling);
+            transparent_background_row.set_active(doc_pages_export_prefs.transparent_background);
+            jpeg_quality_row.set_value(doc_pages_export_prefs.jpeg_quality as f64);
+        }
+    );
+    setup_export_presets_menubutton(&presets_menubutton, appwindow, canvas, show_export_prefs);
+
     // Listen to responses
 
     export_doc_pages_button_cancel.connect_clicked(clone!(
@@ -726,9 +1403,17 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
         };
 
         glib::spawn_future_local(clone!(#[weak] export_files_stemname_entryrow, #[weak] canvas, #[weak] appwindow,  async move {
-            appwindow.overlays().progressbar_start_pulsing();
-
             let file_stem_name = export_files_stemname_entryrow.text().to_string();
+            let doc_pages_export_prefs = appwindow.engine_config().read().export_prefs.doc_pages_export_prefs.clone();
+            // all pages go into a single file, which should not be replaced without asking
+            if doc_pages_export_prefs.exports_single_file() {
+                let file = dir.child(file_stem_name.clone() + "." + &doc_pages_export_prefs.export_format.file_ext());
+                if file.query_exists(gio::Cancellable::NONE) && !super::dialog_replace_file(&appwindow, &file).await {
+                    return;
+                }
+            }
+
+            appwindow.overlays().progressbar_start_pulsing();
 
             if let Err(e) = canvas.export_doc_pages(&appwindow, &dir, file_stem_name, None).await {
                 error!("Exporting document pages failed, Err: {e:?}");
@@ -736,6 +1421,7 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
                 appwindow.overlays().progressbar_abort();
                 return
             }
+            canvas.remember_document_export_prefs(&appwindow);
 
             appwindow.overlays().dispatch_toast_w_button(
                 &gettext("Exported document pages successfully"),
@@ -760,6 +1446,101 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
     dialog.present(appwindow.root().as_ref());
 }
 
+/// The file name of the first exported page file, displayed as naming preview.
+fn doc_pages_files_naming_preview(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    file_stem_name: &str,
+) -> String {
+    let doc_pages_export_prefs = appwindow
+        .engine_config()
+        .read()
+        .export_prefs
+        .doc_pages_export_prefs
+        .clone();
+    if doc_pages_export_prefs.exports_single_file() {
+        return String::from(file_stem_name)
+            + "."
+            + &doc_pages_export_prefs.export_format.file_ext();
+    }
+    let pages_amount = canvas
+        .engine_ref()
+        .pages_bounds_w_content(doc_pages_export_prefs.page_order)
+        .len();
+    let first_page_number = doc_pages_export_prefs
+        .page_selection
+        .selected_indices(pages_amount)
+        .first()
+        .map(|i| i + 1)
+        .unwrap_or(1);
+
+    rnote_engine::utils::doc_pages_files_names_w_pattern(
+        &doc_pages_export_prefs.page_files_naming_pattern,
+        file_stem_name,
+        first_page_number,
+        pages_amount.max(1),
+    ) + "."
+        + &doc_pages_export_prefs.export_format.file_ext()
+}
+
+/// Update the preview with the pages as they get exported with the current document pages export preferences.
+fn refresh_doc_pages_export_preview(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    preview: &RnStrokeContentPreview,
+) {
+    let doc_pages_export_prefs = appwindow
+        .engine_config()
+        .read()
+        .export_prefs
+        .doc_pages_export_prefs
+        .clone();
+    preview.set_contents(
+        canvas
+            .engine_ref()
+            .extract_doc_pages_export_content(&doc_pages_export_prefs),
+    );
+    update_doc_pages_export_preview_info(appwindow, canvas, preview);
+}
+
+/// Show the pixel size of the currently previewed page when it is exported as bitmap.
+fn update_doc_pages_export_preview_info(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    preview: &RnStrokeContentPreview,
+) {
+    let doc_pages_export_prefs = appwindow
+        .engine_config()
+        .read()
+        .export_prefs
+        .doc_pages_export_prefs
+        .clone();
+    let Some(size) = preview
+        .current_content_size()
+        .filter(|_| doc_pages_export_prefs.export_format.is_bitmap())
+    else {
+        preview.set_info_text(None);
+        return;
+    };
+    let doc_dpi = canvas.engine_ref().document.config.format.dpi();
+    let bounds = Aabb::new(na::point![0.0, 0.0], size.into());
+    let image_scale = if doc_pages_export_prefs.exports_tiles() {
+        // Tiled exports are not limited by the maximum dimension
+        bitmap_image_scale(
+            doc_pages_export_prefs.bitmap_scalefactor,
+            doc_pages_export_prefs.bitmap_dpi,
+            doc_dpi,
+            0,
+            bounds,
+        )
+    } else {
+        doc_pages_export_prefs.bitmap_image_scale(doc_dpi, bounds)
+    };
+    let width = (size[0] * image_scale).round() as u32;
+    let height = (size[1] * image_scale).round() as u32;
+    preview.set_info_text(Some(&format!("{width} × {height} px")));
+}
+
 fn create_filedialog_export_doc_pages(
     appwindow: &RnAppWindow,
     canvas: &RnCanvas,
@@ -812,6 +1593,30 @@ fn create_filedialog_export_doc_pages(
             }
             filter.set_name(Some(&gettext("Jpeg")));
         }
+        DocPagesExportFormat::Emf => {
+            if cfg!(target_os = "windows") {
+                filter.add_pattern("*.emf");
+            } else {
+                filter.add_mime_type("image/emf");
+            }
+            if cfg!(target_os = "macos") {
+                filter.add_suffix("emf");
+            }
+            filter.set_name(Some(&gettext("Emf")));
+        }
+        DocPagesExportFormat::Tiff => {
+            if cfg!(target_os = "windows") {
+                filter.add_pattern("*.tif");
+                filter.add_pattern("*.tiff");
+            } else {
+                filter.add_mime_type("image/tiff");
+            }
+            if cfg!(target_os = "macos") {
+                filter.add_suffix("tif");
+                filter.add_suffix("tiff");
+            }
+            filter.set_name(Some(&gettext("Tiff")));
+        }
     }
 
     filedialog.set_default_filter(Some(&filter));
@@ -845,6 +1650,16 @@ pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, can
     let bitmap_scalefactor_row: adw::SpinRow = builder
         .object("export_selection_bitmap_scalefactor_row")
         .unwrap();
+    let bitmap_dpi_row: adw::SpinRow = builder.object("export_selection_bitmap_dpi_row").unwrap();
+    let bitmap_max_dimension_row: adw::SpinRow = builder
+        .object("export_selection_bitmap_max_dimension_row")
+        .unwrap();
+    let bitmap_tiling_row: adw::SwitchRow = builder
+        .object("export_selection_bitmap_tiling_row")
+        .unwrap();
+    let transparent_background_row: adw::SwitchRow = builder
+        .object("export_selection_transparent_background_row")
+        .unwrap();
     let jpeg_quality_row: adw::SpinRow =
         builder.object("export_selection_jpeg_quality_row").unwrap();
     let margin_row: adw::SpinRow = builder.object("export_selection_margin_row").unwrap();
@@ -853,12 +1668,17 @@ pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, can
         builder.object("export_selection_button_cancel").unwrap();
     let export_selection_button_confirm: Button =
         builder.object("export_selection_button_confirm").unwrap();
+    let presets_menubutton: MenuButton = builder
+        .object("export_selection_presets_menubutton")
+        .unwrap();
 
+    canvas.apply_document_export_prefs();
     let initial_selection_export_prefs = appwindow
         .engine_config()
         .write()
         .export_prefs
         .selection_export_prefs;
+    let doc_dpi = canvas.engine_ref().document.config.format.dpi();
 
     // initial widget state with the preferences
     let selected_file: Rc<RefCell<Option<gio::File>>> = Rc::new(RefCell::new(None));
@@ -886,7 +1706,30 @@ pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, can
         initial_selection_export_prefs.export_format == SelectionExportFormat::Png
             || initial_selection_export_prefs.export_format == SelectionExportFormat::Jpeg,
     );
-    bitmap_scalefactor_row.set_value(initial_selection_export_prefs.bitmap_scalefactor);
+    bitmap_dpi_row.set_sensitive(
+        initial_selection_export_prefs.export_format == SelectionExportFormat::Png
+            || initial_selection_export_prefs.export_format == SelectionExportFormat::Jpeg,
+    );
+    bitmap_max_dimension_row.set_sensitive(
+        initial_selection_export_prefs.export_format == SelectionExportFormat::Png
+            || initial_selection_export_prefs.export_format == SelectionExportFormat::Jpeg,
+    );
+    transparent_background_row
+        .set_sensitive(initial_selection_export_prefs.export_format == SelectionExportFormat::Png);
+    bitmap_scalefactor_row.set_value(
+        initial_selection_export_prefs
+            .bitmap_dpi
+            .map(|dpi| dpi / doc_dpi)
+            .unwrap_or(initial_selection_export_prefs.bitmap_scalefactor),
+    );
+    bitmap_dpi_row.set_value(bitmap_scalefactor_row.value() * doc_dpi);
+    bitmap_max_dimension_row.set_value(initial_selection_export_prefs.bitmap_max_dimension as f64);
+    bitmap_tiling_row.set_active(initial_selection_export_prefs.bitmap_tiling);
+    bitmap_max_dimension_row
+        .bind_property("sensitive", &bitmap_tiling_row, "sensitive")
+        .sync_create()
+        .build();
+    transparent_background_row.set_active(initial_selection_export_prefs.transparent_background);
     jpeg_quality_row
         .set_sensitive(initial_selection_export_prefs.export_format == SelectionExportFormat::Jpeg);
     jpeg_quality_row.set_value(initial_selection_export_prefs.jpeg_quality as f64);
@@ -994,6 +1837,12 @@ pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, can
         #[weak]
         bitmap_scalefactor_row,
         #[weak]
+        bitmap_dpi_row,
+        #[weak]
+        bitmap_max_dimension_row,
+        #[weak]
+        transparent_background_row,
+        #[weak]
         jpeg_quality_row,
         #[weak]
         export_file_label,
@@ -1018,21 +1867,110 @@ pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, can
                 export_format == SelectionExportFormat::Png
                     || export_format == SelectionExportFormat::Jpeg,
             );
+            bitmap_dpi_row.set_sensitive(
+                export_format == SelectionExportFormat::Png
+                    || export_format == SelectionExportFormat::Jpeg,
+            );
+            bitmap_max_dimension_row.set_sensitive(
+                export_format == SelectionExportFormat::Png
+                    || export_format == SelectionExportFormat::Jpeg,
+            );
+            transparent_background_row.set_sensitive(export_format == SelectionExportFormat::Png);
             // Set the jpeg quality pref only sensitive when jpeg is actually selected
             jpeg_quality_row.set_sensitive(export_format == SelectionExportFormat::Jpeg);
         }
     ));
 
+    // Set while one of the bitmap scalefactor and dpi rows is updated to match the other
+    let bitmap_rows_syncing = Rc::new(Cell::new(false));
     bitmap_scalefactor_row.connect_changed(clone!(
+        #[strong]
+        bitmap_rows_syncing,
+        #[weak]
+        bitmap_dpi_row,
         #[weak]
         appwindow,
         move |bitmap_scalefactor_row| {
+            let scalefactor = bitmap_scalefactor_row.value();
+            {
+                let mut config = appwindow.engine_config().write();
+                let prefs = &mut config.export_prefs.selection_export_prefs;
+                prefs.bitmap_scalefactor = scalefactor;
+                // Only an edit by the user replaces a chosen dpi, not syncing from the dpi row
+                if !bitmap_rows_syncing.get() {
+                    prefs.bitmap_dpi = None;
+                }
+            }
+            // keep the dpi row in sync, guarding against endless update loops
+            if (bitmap_dpi_row.value() - scalefactor * doc_dpi).abs() > 0.5 {
+                bitmap_rows_syncing.set(true);
+                bitmap_dpi_row.set_value(scalefactor * doc_dpi);
+                bitmap_rows_syncing.set(false);
+            }
+        }
+    ));
+
+    bitmap_dpi_row.connect_changed(clone!(
+        #[strong]
+        bitmap_rows_syncing,
+        #[weak]
+        bitmap_scalefactor_row,
+        #[weak]
+        appwindow,
+        move |bitmap_dpi_row| {
+            let dpi = bitmap_dpi_row.value();
+            if !bitmap_rows_syncing.get() {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .export_prefs
+                    .selection_export_prefs
+                    .bitmap_dpi = Some(dpi);
+            }
+            if (bitmap_scalefactor_row.value() - dpi / doc_dpi).abs() > 0.05 {
+                bitmap_rows_syncing.set(true);
+                bitmap_scalefactor_row.set_value(dpi / doc_dpi);
+                bitmap_rows_syncing.set(false);
+            }
+        }
+    ));
+
+    bitmap_max_dimension_row.connect_changed(clone!(
+        #[weak]
+        appwindow,
+        move |bitmap_max_dimension_row| {
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .selection_export_prefs
+                .bitmap_max_dimension = bitmap_max_dimension_row.value().max(1.0) as u32;
+        }
+    ));
+
+    bitmap_tiling_row.connect_active_notify(clone!(
+        #[weak]
+        appwindow,
+        move |bitmap_tiling_row| {
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .selection_export_prefs
+                .bitmap_tiling = bitmap_tiling_row.is_active();
+        }
+    ));
+
+    transparent_background_row.connect_active_notify(clone!(
+        #[weak]
+        appwindow,
+        move |transparent_background_row| {
             appwindow
                 .engine_config()
                 .write()
                 .export_prefs
                 .selection_export_prefs
-                .bitmap_scalefactor = bitmap_scalefactor_row.value();
+                .transparent_background = transparent_background_row.is_active();
         }
     ));
 
@@ -1066,6 +2004,56 @@ pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, can
         }
     ));
 
+    // Shows the preferences of an applied preset, the handlers above update the preview
+    let show_export_prefs = clone!(
+        #[weak]
+        with_background_row,
+        #[weak]
+        with_pattern_row,
+        #[weak]
+        optimize_printing_row,
+        #[weak]
+        export_format_row,
+        #[weak]
+        bitmap_scalefactor_row,
+        #[weak]
+        bitmap_dpi_row,
+        #[weak]
+        bitmap_max_dimension_row,
+        #[weak]
+        bitmap_tiling_row,
+        #[weak]
+        transparent_background_row,
+        #[weak]
+        jpeg_quality_row,
+        #[weak]
+        margin_row,
+        #[weak]
+        appwindow,
+        move || {
+            let selection_export_prefs = appwindow
+                .engine_config()
+                .read()
+                .export_prefs
+                .selection_export_prefs;
+            export_format_row.set_selected(selection_export_prefs.export_format.to_u32().unwrap());
+            with_background_row.set_active(selection_export_prefs.with_background);
+            with_pattern_row.set_active(selection_export_prefs.with_pattern);
+            optimize_printing_row.set_active(selection_export_prefs.optimize_printing);
+            // Setting the dpi row keeps the chosen dpi, setting the scalefactor row resets it
+            match selection_export_prefs.bitmap_dpi {
+                Some(dpi) => bitmap_dpi_row.set_value(dpi),
+                None => bitmap_scalefactor_row.set_value(selection_export_prefs.bitmap_scalefactor),
+            }
+            bitmap_max_dimension_row.set_value(selection_export_prefs.bitmap_max_dimension as f64);
+            bitmap_tiling_row.set_active(selection_export_prefs.bitmap_tiling);
+            transparent_background_row.set_active(selection_export_prefs.transparent_background);
+            jpeg_quality_row.set_value(selection_export_prefs.jpeg_quality as f64);
+            margin_row.set_value(selection_export_prefs.margin);
+        }
+    );
+    setup_export_presets_menubutton(&presets_menubutton, appwindow, canvas, show_export_prefs);
+
     // Listen to responses
 
     export_selection_button_cancel.connect_clicked(clone!(
@@ -1089,7 +2077,7 @@ pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, can
         glib::spawn_future_local(clone!(#[weak] canvas, #[weak] appwindow , async move {
             appwindow.overlays().progressbar_start_pulsing();
 
-            if let Err(e) = canvas.export_selection(&file, None).await {
+            if let Err(e) = canvas.export_selection(&appwindow, &file, None).await {
                 error!("Exporting selection failed, Err: {e:?}");
                 appwindow
                     .overlays()
@@ -1097,6 +2085,7 @@ pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, can
                 appwindow.overlays().progressbar_abort();
                 return;
             }
+            canvas.remember_document_export_prefs(&appwindow);
 
             appwindow.overlays().dispatch_toast_w_button(
                 &gettext("Exported selection successfully"),
@@ -1204,6 +2193,17 @@ fn create_filedialog_export_selection(
             }
             filter.set_name(Some(&gettext("Jpeg")));
         }
+        SelectionExportFormat::Emf => {
+            if cfg!(target_os = "windows") {
+                filter.add_pattern("*.emf");
+            } else {
+                filter.add_mime_type("image/emf");
+            }
+            if cfg!(target_os = "macos") {
+                filter.add_suffix("emf");
+            }
+            filter.set_name(Some(&gettext("Emf")));
+        }
     }
     let file_ext = selection_export_prefs.export_format.file_ext();
     let file_name = crate::utils::default_file_title_for_export(
@@ -1342,3 +2342,130 @@ pub(crate) async fn filechooser_export_engine_config(appwindow: &RnAppWindow, ca
         }
     }
 }
+
+/// Fills the popover of the export presets menu button of an export dialog.
+///
+/// `show_export_prefs` is called after a preset was applied, to update the widgets of the dialog with its preferences.
+fn setup_export_presets_menubutton(
+    menubutton: &MenuButton,
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    show_export_prefs: impl Fn() + 'static,
+) {
+    let show_export_prefs = Rc::new(show_export_prefs);
+    let popover = Popover::new();
+    let popover_box = gtk4::Box::new(Orientation::Vertical, 6);
+    let presets_box = gtk4::Box::new(Orientation::Vertical, 0);
+    let save_box = gtk4::Box::new(Orientation::Horizontal, 6);
+    let name_entry = Entry::builder()
+        .placeholder_text(gettext("Preset Name"))
+        .hexpand(true)
+        .build();
+    let save_button = Button::builder()
+        .icon_name("document-save-symbolic")
+        .tooltip_text(gettext("Save Current Settings as Preset"))
+        .sensitive(false)
+        .build();
+    save_box.append(&name_entry);
+    save_box.append(&save_button);
+    popover_box.append(&presets_box);
+    popover_box.append(&save_box);
+    popover.set_child(Some(&popover_box));
+    menubutton.set_popover(Some(&popover));
+
+    // The presets are listed again every time the popover is shown, to include changes
+    popover.connect_show(clone!(
+        #[weak]
+        presets_box,
+        #[weak]
+        popover,
+        #[strong]
+        show_export_prefs,
+        #[weak]
+        canvas,
+        move |_| {
+            while let Some(child) = presets_box.first_child() {
+                presets_box.remove(&child);
+            }
+            for name in canvas.engine_ref().export_preset_names() {
+                let preset_box = gtk4::Box::new(Orientation::Horizontal, 6);
+                let apply_button = Button::builder()
+                    .label(&name)
+                    .hexpand(true)
+                    .css_classes(["flat"])
+                    .build();
+                let remove_button = Button::builder()
+                    .icon_name("user-trash-symbolic")
+                    .tooltip_text(gettext("Remove Preset"))
+                    .css_classes(["flat"])
+                    .build();
+                preset_box.append(&apply_button);
+                preset_box.append(&remove_button);
+                presets_box.append(&preset_box);
+
+                apply_button.connect_clicked(clone!(
+                    #[strong]
+                    name,
+                    #[weak]
+                    popover,
+                    #[strong]
+                    show_export_prefs,
+                    #[weak]
+                    canvas,
+                    move |_| {
+                        popover.popdown();
+                        if canvas.engine_ref().apply_export_preset(&name) {
+                            show_export_prefs();
+                        }
+                    }
+                ));
+                remove_button.connect_clicked(clone!(
+                    #[strong]
+                    name,
+                    #[weak]
+                    presets_box,
+                    #[weak]
+                    preset_box,
+                    #[weak]
+                    canvas,
+                    move |_| {
+                        canvas.engine_ref().remove_export_preset(&name);
+                        presets_box.remove(&preset_box);
+                    }
+                ));
+            }
+        }
+    ));
+
+    name_entry.connect_changed(clone!(
+        #[weak]
+        save_button,
+        move |entry| {
+            save_button.set_sensitive(!entry.text().trim().is_empty());
+        }
+    ));
+
+    save_button.connect_clicked(clone!(
+        #[weak]
+        name_entry,
+        #[weak]
+        popover,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        move |_| {
+            let name = name_entry.text().trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+            canvas.engine_ref().save_export_preset(name);
+            name_entry.set_text("");
+            popover.popdown();
+            appwindow.overlays().dispatch_toast_text(
+                &gettext("Saved export preset"),
+                crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+            );
+        }
+    ));
+}
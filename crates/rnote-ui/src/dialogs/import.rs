@@ -2,19 +2,29 @@
 
 // Imports
 use crate::canvas::RnCanvas;
-use crate::{RnAppWindow, config};
+use crate::colorpicker::ColorPalette;
+use crate::settingsprofiles::{self, SettingsProfile};
+use crate::{RnAppWindow, config, keyboardshortcuts};
 use adw::prelude::*;
 use anyhow::anyhow;
 use futures::StreamExt;
 use gettextrs::gettext;
-use gtk4::{Builder, Button, FileDialog, FileFilter, Label, ToggleButton, gio, glib, glib::clone};
+use gtk4::{
+    Builder, Button, FileDialog, FileFilter, FlowBox, Label, Orientation, Picture, ToggleButton,
+    gio, glib, glib::clone,
+};
 use gtk4::{graphene, gsk};
 use hayro::hayro_syntax;
 use num_traits::ToPrimitive;
 use rnote_engine::engine::import::{PdfImportPageSpacing, PdfImportPagesType};
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::Arc;
 use tracing::{debug, error};
 
+/// The width and height of the Pdf page thumbnails in the import dialog.
+const PDF_PAGE_THUMBNAIL_SIZE: i32 = 128;
+
 /// Opens a new rnote save file in a new tab
 pub(crate) async fn filedialog_open_doc(appwindow: &RnAppWindow) {
     let filter = FileFilter::new();
@@ -112,6 +122,329 @@ pub(crate) async fn filedialog_import_file(appwindow: &RnAppWindow) {
     }
 }
 
+/// Imports a GIMP palette (.gpl) file as a new color palette.
+pub(crate) async fn filedialog_import_color_palette(appwindow: &RnAppWindow) {
+    let filter = FileFilter::new();
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.gpl");
+    } else {
+        filter.add_mime_type("application/x-gimp-palette");
+    }
+    filter.add_suffix("gpl");
+    filter.set_name(Some(&gettext("GIMP Palette")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let dialog = FileDialog::builder()
+        .title(gettext("Import Palette"))
+        .modal(true)
+        .accept_label(gettext("Import"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .build();
+
+    let selected_file = match dialog.open_future(Some(appwindow)).await {
+        Ok(selected_file) => selected_file,
+        Err(e) => {
+            debug!("Did not import palette (Error or dialog dismissed by user), Err: {e:?}");
+            return;
+        }
+    };
+    let fallback_name = selected_file
+        .path()
+        .and_then(|path| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| gettext("Imported Palette"));
+
+    let palette = async {
+        let (bytes, _) = selected_file.load_bytes_future().await?;
+        ColorPalette::from_gpl(&String::from_utf8_lossy(&bytes), &fallback_name)
+    }
+    .await;
+    match palette {
+        Ok(palette) => {
+            appwindow.overlays().colorpicker().add_palette(palette);
+        }
+        Err(e) => {
+            error!("Importing color palette failed, Err: {e:?}");
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Importing palette failed"));
+        }
+    }
+}
+
+/// Imports a Svg file as a new custom stamp of the stamp brush.
+pub(crate) async fn filedialog_import_stamp(appwindow: &RnAppWindow) {
+    let filter = FileFilter::new();
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.svg");
+    } else {
+        filter.add_mime_type("image/svg+xml");
+    }
+    filter.add_suffix("svg");
+    filter.set_name(Some(&gettext("Svg")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let dialog = FileDialog::builder()
+        .title(gettext("Add Stamp"))
+        .modal(true)
+        .accept_label(gettext("Add"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .build();
+
+    let selected_file = match dialog.open_future(Some(appwindow)).await {
+        Ok(selected_file) => selected_file,
+        Err(e) => {
+            debug!("Did not add stamp (Error or dialog dismissed by user), Err: {e:?}");
+            return;
+        }
+    };
+    let name = selected_file
+        .path()
+        .and_then(|path| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| gettext("Custom Stamp"));
+
+    let stamp_index = async {
+        let (bytes, _) = selected_file.load_bytes_future().await?;
+        appwindow
+            .engine_config()
+            .write()
+            .stamp_library
+            .add_svg(name, &String::from_utf8_lossy(&bytes))
+    }
+    .await;
+    match stamp_index {
+        Ok(stamp_index) => {
+            appwindow
+                .engine_config()
+                .write()
+                .pens_config
+                .brush_config
+                .stamp_options
+                .stamp_index = stamp_index;
+            appwindow
+                .overlays()
+                .penssidebar()
+                .brush_page()
+                .refresh_stamps(appwindow);
+        }
+        Err(e) => {
+            error!("Adding stamp failed, Err: {e:?}");
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Adding stamp failed"));
+        }
+    }
+}
+
+pub(crate) async fn filedialog_import_keyboard_shortcuts(appwindow: &RnAppWindow) {
+    let filter = FileFilter::new();
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.json");
+    } else {
+        filter.add_mime_type("application/json");
+    }
+    filter.add_suffix("json");
+    filter.set_name(Some(&gettext("Shortcuts Profile")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let dialog = FileDialog::builder()
+        .title(gettext("Import Shortcuts Profile"))
+        .modal(true)
+        .accept_label(gettext("Import"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .build();
+
+    let selected_file = match dialog.open_future(Some(appwindow)).await {
+        Ok(selected_file) => selected_file,
+        Err(e) => {
+            debug!(
+                "Did not import shortcuts profile (Error or dialog dismissed by user), Err: {e:?}"
+            );
+            return;
+        }
+    };
+
+    let result = async {
+        let (bytes, _) = selected_file.load_bytes_future().await?;
+        keyboardshortcuts::import_profile(&appwindow.app(), &String::from_utf8_lossy(&bytes))
+    }
+    .await;
+    match result {
+        Ok(()) => {
+            appwindow.overlays().dispatch_toast_text(
+                &gettext("Imported shortcuts profile successfully"),
+                crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+            );
+        }
+        Err(e) => {
+            error!("Importing keyboard shortcuts profile failed, Err: {e:?}");
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Importing shortcuts profile failed"));
+        }
+    }
+}
+
+/// Import a settings profile, apply it and save it as named profile with the file name.
+pub(crate) async fn filedialog_import_settings_profile(appwindow: &RnAppWindow) {
+    let filter = FileFilter::new();
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.json");
+    } else {
+        filter.add_mime_type("application/json");
+    }
+    filter.add_suffix("json");
+    filter.set_name(Some(&gettext("Settings Profile")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let dialog = FileDialog::builder()
+        .title(gettext("Import Settings Profile"))
+        .modal(true)
+        .accept_label(gettext("Import"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .build();
+
+    let selected_file = match dialog.open_future(Some(appwindow)).await {
+        Ok(selected_file) => selected_file,
+        Err(e) => {
+            debug!(
+                "Did not import settings profile (Error or dialog dismissed by user), Err: {e:?}"
+            );
+            return;
+        }
+    };
+
+    let result = async {
+        let app_settings = appwindow
+            .app()
+            .app_settings()
+            .ok_or_else(|| anyhow!("Settings schema not found."))?;
+        let (bytes, _) = selected_file.load_bytes_future().await?;
+        let profile = SettingsProfile::from_json(&String::from_utf8_lossy(&bytes))?;
+        let name = selected_file
+            .path()
+            .and_then(|path| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+            })
+            .unwrap_or_else(|| gettext("Imported"));
+        settingsprofiles::save_profile(&app_settings, &name, &profile)?;
+        appwindow.switch_settings_profile(&name)
+    }
+    .await;
+    match result {
+        Ok(()) => {
+            appwindow.overlays().dispatch_toast_text(
+                &gettext("Imported settings profile successfully"),
+                crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+            );
+        }
+        Err(e) => {
+            error!("Importing settings profile failed, Err: {e:?}");
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Importing settings profile failed"));
+        }
+    }
+}
+
+/// The URL in the text, if the text consists of a single http or https URL.
+pub(crate) fn http_url_from_text(text: &str) -> Option<String> {
+    let url = url::Url::parse(text.trim()).ok()?;
+    matches!(url.scheme(), "http" | "https").then(|| url.to_string())
+}
+
+/// Asks for the URL of an image or Pdf and imports it into the active tab.
+///
+/// `pasted_text` is a pasted text containing an URL. It prefills the entry, and pasting it as text is offered instead.
+pub(crate) async fn dialog_import_url(
+    appwindow: &RnAppWindow,
+    pasted_text: Option<String>,
+    target_pos: Option<na::Vector2<f64>>,
+) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/import.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_import_url").unwrap();
+    let url_entryrow: adw::EntryRow = builder.object("import_url_entryrow").unwrap();
+
+    dialog.set_body(&format!(
+        "{} {}",
+        gettext("Images and Pdfs are downloaded from the URL and inserted. Only import from sources you trust. Maximum size:"),
+        glib::format_size(RnCanvas::URL_IMPORT_MAX_SIZE as u64)
+    ));
+    url_entryrow.connect_changed(clone!(
+        #[weak]
+        dialog,
+        move |entryrow| {
+            dialog.set_response_enabled("import", http_url_from_text(&entryrow.text()).is_some());
+        }
+    ));
+    if let Some(pasted_text) = pasted_text.as_ref() {
+        url_entryrow.set_text(pasted_text.trim());
+        dialog.add_response("text", &gettext("Paste as Text"));
+    }
+
+    let Some(canvas) = appwindow.active_tab_canvas() else {
+        return;
+    };
+    match dialog.choose_future(Some(appwindow)).await.as_str() {
+        "import" => {}
+        "text" => {
+            if let Some(pasted_text) = pasted_text
+                && let Err(e) = canvas.load_in_text(pasted_text, target_pos)
+            {
+                error!("Failed to paste clipboard text, Err: {e:?}");
+            }
+            return;
+        }
+        _ => return,
+    }
+    let Some(url) = http_url_from_text(&url_entryrow.text()) else {
+        return;
+    };
+
+    appwindow.overlays().progressbar_start_pulsing();
+    if let Err(e) = canvas.load_in_url(appwindow, &url, target_pos).await {
+        error!("Importing from URL '{url}' failed, Err: {e:?}");
+        appwindow
+            .overlays()
+            .dispatch_toast_error(&gettext("Importing from URL failed"));
+        appwindow.overlays().progressbar_abort();
+    } else {
+        appwindow.overlays().progressbar_finish();
+    }
+}
+
 /// Check for a pdf encryption and request a password if needed from the user
 ///
 /// Returns a password Option and a boolean weather the user canceled the file import or not
@@ -253,8 +586,11 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
         (String::from(config::APP_IDPATH) + "ui/dialogs/import.ui").as_str(),
     );
     let dialog: adw::Dialog = builder.object("dialog_import_pdf_w_prefs").unwrap();
-    let pdf_page_start_row: adw::SpinRow = builder.object("pdf_page_start_row").unwrap();
-    let pdf_page_end_row: adw::SpinRow = builder.object("pdf_page_end_row").unwrap();
+    let pdf_pages_flowbox: FlowBox = builder.object("pdf_pages_flowbox").unwrap();
+    let pdf_pages_select_all_button: Button =
+        builder.object("pdf_pages_select_all_button").unwrap();
+    let pdf_pages_select_none_button: Button =
+        builder.object("pdf_pages_select_none_button").unwrap();
     let pdf_info_label: Label = builder.object("pdf_info_label").unwrap();
     let pdf_import_width_row: adw::SpinRow = builder.object("pdf_import_width_row").unwrap();
     let pdf_import_page_spacing_row: adw::ComboRow =
@@ -267,6 +603,9 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
         builder.object("pdf_import_bitmap_scalefactor_row").unwrap();
     let pdf_import_adjust_document_row: adw::SwitchRow =
         builder.object("pdf_import_adjust_document_row").unwrap();
+    let pdf_import_ink_row: adw::SwitchRow = builder.object("pdf_import_ink_row").unwrap();
+    let pdf_import_keep_original_row: adw::SwitchRow =
+        builder.object("pdf_import_keep_original_row").unwrap();
     let import_pdf_button_cancel: Button = builder.object("import_pdf_button_cancel").unwrap();
     let import_pdf_button_confirm: Button = builder.object("import_pdf_button_confirm").unwrap();
 
@@ -293,30 +632,27 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
         PdfImportPagesType::Bitmap => {
             pdf_import_as_bitmap_toggle.set_active(true);
             pdf_import_bitmap_scalefactor_row.set_sensitive(true);
+            pdf_import_ink_row.set_sensitive(false);
         }
         PdfImportPagesType::Vector => {
             pdf_import_as_vector_toggle.set_active(true);
             pdf_import_bitmap_scalefactor_row.set_sensitive(false);
+            pdf_import_ink_row.set_sensitive(true);
         }
     }
     pdf_import_page_spacing_row.set_selected(pdf_import_prefs.page_spacing.to_u32().unwrap());
     pdf_import_bitmap_scalefactor_row.set_value(pdf_import_prefs.bitmap_scalefactor);
     pdf_import_adjust_document_row.set_active(pdf_import_prefs.adjust_document);
-
-    pdf_page_start_row
-        .bind_property("value", &pdf_page_end_row.adjustment(), "lower")
-        .sync_create()
-        .build();
-    pdf_page_end_row
-        .bind_property("value", &pdf_page_start_row.adjustment(), "upper")
-        .sync_create()
-        .build();
+    pdf_import_ink_row.set_active(pdf_import_prefs.import_ink);
+    pdf_import_keep_original_row.set_active(pdf_import_prefs.keep_original);
 
     // Update preferences
     pdf_import_as_vector_toggle.connect_toggled(clone!(
         #[weak]
         pdf_import_bitmap_scalefactor_row,
         #[weak]
+        pdf_import_ink_row,
+        #[weak]
         appwindow,
         move |toggle| {
             if !toggle.is_active() {
@@ -329,6 +665,7 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
                 .pdf_import_prefs
                 .pages_type = PdfImportPagesType::Vector;
             pdf_import_bitmap_scalefactor_row.set_sensitive(false);
+            pdf_import_ink_row.set_sensitive(true);
         }
     ));
 
@@ -336,6 +673,8 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
         #[weak]
         pdf_import_bitmap_scalefactor_row,
         #[weak]
+        pdf_import_ink_row,
+        #[weak]
         appwindow,
         move |toggle| {
             if !toggle.is_active() {
@@ -348,6 +687,7 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
                 .pdf_import_prefs
                 .pages_type = PdfImportPagesType::Bitmap;
             pdf_import_bitmap_scalefactor_row.set_sensitive(true);
+            pdf_import_ink_row.set_sensitive(false);
         }
     ));
 
@@ -404,12 +744,38 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
         }
     ));
 
+    pdf_import_ink_row.connect_active_notify(clone!(
+        #[weak]
+        appwindow,
+        move |row| {
+            appwindow
+                .engine_config()
+                .write()
+                .import_prefs
+                .pdf_import_prefs
+                .import_ink = row.is_active();
+        }
+    ));
+
+    pdf_import_keep_original_row.connect_active_notify(clone!(
+        #[weak]
+        appwindow,
+        move |row| {
+            appwindow
+                .engine_config()
+                .write()
+                .import_prefs
+                .pdf_import_prefs
+                .keep_original = row.is_active();
+        }
+    ));
+
     let pdf_data = Arc::new(input_file.load_bytes_future().await?.0.to_vec());
     let pdf = if let Some(password) = password.as_ref() {
-        hayro_syntax::Pdf::new_with_password(pdf_data, password)
+        hayro_syntax::Pdf::new_with_password(Arc::clone(&pdf_data), password)
             .map_err(|err| anyhow!("Creating Pdf instance failed, Err: {err:?}"))?
     } else {
-        hayro_syntax::Pdf::new(pdf_data)
+        hayro_syntax::Pdf::new(Arc::clone(&pdf_data))
             .map_err(|err| anyhow!("Creating Pdf instance failed, Err: {err:?}"))?
     };
     let pdf_metadata = pdf.metadata();
@@ -447,12 +813,109 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
                 &gettext("Pages:"))
         );
 
-    // Configure pages spinners
-    pdf_page_start_row.set_range(1.into(), n_pages as f64);
-    pdf_page_start_row.set_value(1.into());
+    // Page picker, the pages are imported in the order they were selected in
+    let page_order: Rc<RefCell<Vec<usize>>> = Rc::new(RefCell::new((0..n_pages).collect()));
+    let page_toggles = (0..n_pages)
+        .map(|_| {
+            let picture = Picture::builder()
+                .width_request(PDF_PAGE_THUMBNAIL_SIZE)
+                .height_request(PDF_PAGE_THUMBNAIL_SIZE)
+                .build();
+            let label = Label::new(None);
+            let content = gtk4::Box::new(Orientation::Vertical, 6);
+            content.append(&picture);
+            content.append(&label);
+            let toggle = ToggleButton::builder()
+                .child(&content)
+                .active(true)
+                .css_classes(["flat"])
+                .build();
+            pdf_pages_flowbox.append(&toggle);
+            (toggle, picture, label)
+        })
+        .collect::<Rc<[(ToggleButton, Picture, Label)]>>();
+    update_pdf_page_toggles(&page_toggles, &page_order.borrow());
+    import_pdf_button_confirm.set_sensitive(n_pages > 0);
+
+    for (page_i, (toggle, _, _)) in page_toggles.iter().enumerate() {
+        toggle.connect_toggled(clone!(
+            #[strong]
+            page_order,
+            #[strong]
+            page_toggles,
+            #[weak]
+            import_pdf_button_confirm,
+            move |toggle| {
+                {
+                    let mut page_order = page_order.borrow_mut();
+                    let position = page_order.iter().position(|i| *i == page_i);
+                    match (toggle.is_active(), position) {
+                        (true, None) => page_order.push(page_i),
+                        (false, Some(position)) => {
+                            page_order.remove(position);
+                        }
+                        _ => {}
+                    }
+                    import_pdf_button_confirm.set_sensitive(!page_order.is_empty());
+                }
+                update_pdf_page_toggles(&page_toggles, &page_order.borrow());
+            }
+        ));
+    }
+
+    pdf_pages_select_all_button.connect_clicked(clone!(
+        #[strong]
+        page_toggles,
+        move |_| {
+            for (toggle, _, _) in page_toggles.iter() {
+                toggle.set_active(true);
+            }
+        }
+    ));
 
-    pdf_page_end_row.set_range(1.into(), n_pages as f64);
-    pdf_page_end_row.set_value(n_pages as f64);
+    pdf_pages_select_none_button.connect_clicked(clone!(
+        #[strong]
+        page_toggles,
+        move |_| {
+            for (toggle, _, _) in page_toggles.iter() {
+                toggle.set_active(false);
+            }
+        }
+    ));
+
+    // The thumbnails are loaded in while the dialog is already presented
+    glib::spawn_future_local(clone!(
+        #[strong]
+        page_toggles,
+        #[strong]
+        password,
+        #[weak]
+        canvas,
+        async move {
+            let thumbnails_receiver = canvas.engine_ref().generate_pdf_page_thumbnails(
+                pdf_data.as_ref().clone(),
+                PDF_PAGE_THUMBNAIL_SIZE as u32,
+                password,
+            );
+            let thumbnails = match thumbnails_receiver.await {
+                Ok(Ok(thumbnails)) => thumbnails,
+                Ok(Err(e)) => {
+                    error!("Generating Pdf page thumbnails failed, Err: {e:?}");
+                    return;
+                }
+                Err(e) => {
+                    error!("Receiving Pdf page thumbnails failed, Err: {e:?}");
+                    return;
+                }
+            };
+            for ((_, picture, _), thumbnail) in page_toggles.iter().zip(thumbnails) {
+                match thumbnail.to_memtexture() {
+                    Ok(texture) => picture.set_paintable(Some(&texture)),
+                    Err(e) => error!("Converting Pdf page thumbnail to texture failed, Err: {e:?}"),
+                }
+            }
+        }
+    ));
 
     // Listen to responses
 
@@ -496,11 +959,8 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
                 let (tx_import, mut rx_import) =
                     futures::channel::mpsc::unbounded::<anyhow::Result<bool>>();
 
+                let page_indices = page_order.borrow().clone();
                 glib::spawn_future_local(clone!(
-                    #[weak]
-                    pdf_page_start_row,
-                    #[weak]
-                    pdf_page_end_row,
                     #[weak]
                     input_file,
                     #[weak]
@@ -508,8 +968,6 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
                     #[weak]
                     canvas,
                     async move {
-                        let page_range = (pdf_page_start_row.value() as usize).saturating_sub(1)
-                            ..pdf_page_end_row.value() as usize;
                         let (bytes, _) = match input_file.load_bytes_future().await {
                             Ok(res) => res,
                             Err(err) => {
@@ -527,7 +985,7 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
                                 &appwindow,
                                 bytes.to_vec(),
                                 target_pos,
-                                Some(page_range),
+                                Some(page_indices),
                                 password,
                             )
                             .await
@@ -563,6 +1021,21 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
     }
 }
 
+/// Sets the labels of the Pdf page toggles, showing the position in the import order for selected pages.
+fn update_pdf_page_toggles(page_toggles: &[(ToggleButton, Picture, Label)], page_order: &[usize]) {
+    for (page_i, (_, _, label)) in page_toggles.iter().enumerate() {
+        match page_order.iter().position(|i| *i == page_i) {
+            Some(position) => label.set_label(&format!(
+                "{} {}  ·  #{}",
+                gettext("Page"),
+                page_i + 1,
+                position + 1
+            )),
+            None => label.set_label(&format!("{} {}", gettext("Page"), page_i + 1)),
+        }
+    }
+}
+
 /// Imports the file as Xopp with an import dialog.
 ///
 /// Returns true when the file was imported, else false.
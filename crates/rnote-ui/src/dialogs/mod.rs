@@ -1,12 +1,17 @@
 // Modules
+pub(crate) mod commandpalette;
 pub(crate) mod export;
 pub(crate) mod import;
+pub(crate) mod print;
+pub(crate) mod stylus;
 
 // Imports
 use crate::appwindow::RnAppWindow;
 use crate::canvas::RnCanvas;
+use crate::canvas::recovery::RecoveryEntry;
 use crate::canvaswrapper::RnCanvasWrapper;
 use crate::config;
+use crate::keyboardshortcuts::{self, KeyboardShortcut, KeyboardShortcutEdit};
 use crate::workspacebrowser::workspacesbar::RnWorkspaceRow;
 use crate::{RnIconPicker, globals};
 use adw::prelude::*;
@@ -14,10 +19,12 @@ use gettextrs::{gettext, pgettext};
 #[allow(deprecated)]
 use gtk4::ShortcutsWindow;
 use gtk4::{
-    Builder, Button, CheckButton, ColorDialogButton, FileDialog, Label, MenuButton, StringList,
-    gio, glib, glib::clone,
+    Builder, Button, CheckButton, ColorDialogButton, EventControllerKey, FileDialog, Label,
+    MenuButton, PropagationPhase, ShortcutLabel, StringList, gdk, gio, glib, glib::clone,
 };
+use rnote_engine::fileformats::tableformat::TableText;
 use tracing::{debug, error, warn};
+use zeroize::Zeroizing;
 
 // About Dialog
 pub(crate) fn dialog_about(appwindow: &RnAppWindow) {
@@ -82,6 +89,212 @@ pub(crate) async fn dialog_clear_doc(appwindow: &RnAppWindow, canvas: &RnCanvas)
     }
 }
 
+/// Asks whether the pasted tabular text should be inserted as table or as plain text.
+pub(crate) async fn dialog_paste_table(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    text: String,
+    table: TableText,
+    target_pos: Option<na::Vector2<f64>>,
+) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_paste_table").unwrap();
+
+    match dialog.choose_future(Some(appwindow)).await.as_str() {
+        "table" => {
+            if let Err(e) = canvas.load_in_table_text(table, target_pos) {
+                error!("Failed to paste clipboard text as table, Err: {e:?}");
+            }
+        }
+        "text" => {
+            if let Err(e) = canvas.load_in_text(text, target_pos) {
+                error!("Failed to paste clipboard text, Err: {e:?}");
+            }
+        }
+        _ => {
+            // Cancel
+        }
+    }
+}
+
+/// Asks whether a pasted Svg is inserted as image or converted into editable strokes.
+pub(crate) async fn dialog_paste_svg(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    bytes: Vec<u8>,
+    target_pos: Option<na::Vector2<f64>>,
+) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_paste_svg").unwrap();
+
+    match dialog.choose_future(Some(appwindow)).await.as_str() {
+        "image" => {
+            if let Err(e) = canvas
+                .load_in_vectorimage_bytes(bytes, target_pos, appwindow.respect_borders())
+                .await
+            {
+                error!("Loading VectorImage bytes failed while pasting as Svg failed, Err: {e:?}");
+            }
+        }
+        "strokes" => {
+            if let Err(e) = canvas
+                .load_in_svg_strokes_bytes(bytes, target_pos, appwindow.respect_borders())
+                .await
+            {
+                error!("Converting Svg into strokes failed while pasting, Err: {e:?}");
+                appwindow
+                    .overlays()
+                    .dispatch_toast_error(&gettext("Converting Svg into strokes failed"));
+            }
+        }
+        _ => {
+            // Cancel
+        }
+    }
+}
+
+/// Offers to restore documents from recovery snapshots that were left over from a previous session.
+pub(crate) async fn dialog_restore_recovery(
+    appwindow: &RnAppWindow,
+    recovery_entries: Vec<RecoveryEntry>,
+) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_restore_recovery").unwrap();
+    let files_group: adw::PreferencesGroup =
+        builder.object("restore_recovery_files_group").unwrap();
+
+    for entry in recovery_entries.iter() {
+        let subtitle = entry
+            .metadata
+            .original_file
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| gettext("Draft"));
+        let row = adw::ActionRow::builder()
+            .title(entry.metadata.title.as_str())
+            .subtitle(subtitle)
+            .subtitle_lines(2)
+            .build();
+        files_group.add(&row);
+    }
+
+    match dialog.choose_future(Some(appwindow)).await.as_str() {
+        "restore" => {
+            for entry in recovery_entries {
+                let wrapper = appwindow.new_canvas_wrapper();
+                // Only track the original file when it still exists
+                let original_file = entry.metadata.original_file.clone().filter(|p| p.exists());
+                let result = async {
+                    let bytes = entry.load_bytes().await?;
+                    wrapper
+                        .canvas()
+                        .load_in_rnote_bytes(bytes, original_file)
+                        .await
+                }
+                .await;
+                match result {
+                    Ok(widget_flags) => {
+                        wrapper.canvas().set_unsaved_changes(true);
+                        appwindow.append_wrapper_new_tab(&wrapper);
+                        appwindow.handle_widget_flags(widget_flags, &wrapper.canvas());
+                        entry.remove();
+                    }
+                    Err(e) => {
+                        error!(
+                            "Restoring document from recovery file with path '{}' failed, Err: {e:?}",
+                            entry.rnote_path.display()
+                        );
+                        appwindow
+                            .overlays()
+                            .dispatch_toast_error(&gettext("Restoring document failed"));
+                    }
+                }
+            }
+        }
+        "discard" => {
+            for entry in recovery_entries {
+                entry.remove();
+            }
+        }
+        _ => {
+            // Keep the recovery files, the user is asked again on next start
+        }
+    }
+}
+
+/// Asks for the password of an encrypted document.
+///
+/// Returns `None` when cancelled.
+pub(crate) async fn dialog_unlock_document(appwindow: &RnAppWindow) -> Option<Zeroizing<String>> {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_unlock_document").unwrap();
+    let password_row: adw::PasswordEntryRow =
+        builder.object("unlock_document_password_row").unwrap();
+
+    match dialog.choose_future(Some(appwindow)).await.as_str() {
+        "unlock" => Some(Zeroizing::new(password_row.text().to_string())),
+        _ => None,
+    }
+}
+
+/// Sets, changes or removes the password the document is encrypted with.
+pub(crate) async fn dialog_document_password(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_document_password").unwrap();
+    let password_row: adw::PasswordEntryRow = builder.object("document_password_row").unwrap();
+    let password_confirm_row: adw::PasswordEntryRow =
+        builder.object("document_password_confirm_row").unwrap();
+
+    dialog.set_response_enabled("remove", canvas.encryption_password().is_some());
+    let update_set_response = clone!(
+        #[weak]
+        dialog,
+        #[weak]
+        password_row,
+        #[weak]
+        password_confirm_row,
+        move || {
+            let password = password_row.text();
+            dialog.set_response_enabled(
+                "set",
+                !password.is_empty() && password == password_confirm_row.text(),
+            );
+        }
+    );
+    password_row.connect_changed(clone!(
+        #[strong]
+        update_set_response,
+        move |_| update_set_response()
+    ));
+    password_confirm_row.connect_changed(move |_| update_set_response());
+
+    match dialog.choose_future(Some(appwindow)).await.as_str() {
+        "set" => {
+            canvas.set_encryption_password(Some(Zeroizing::new(password_row.text().to_string())));
+            canvas.set_unsaved_changes(true);
+            appwindow.refresh_ui();
+        }
+        "remove" => {
+            canvas.set_encryption_password(None);
+            canvas.set_unsaved_changes(true);
+            appwindow.refresh_ui();
+        }
+        _ => {
+            // Cancel
+        }
+    }
+}
+
 #[allow(unused)]
 pub(crate) async fn dialog_new_doc(appwindow: &RnAppWindow, canvas: &RnCanvas) {
     let builder = Builder::from_resource(
@@ -589,6 +802,23 @@ pub(crate) async fn dialog_edit_selected_workspace(appwindow: &RnAppWindow) {
     dialog.present(appwindow.root().as_ref());
 }
 
+/// Asks whether an existing file should be replaced. Returns true when the user confirmed.
+pub(crate) async fn dialog_replace_file(appwindow: &RnAppWindow, file: &gio::File) -> bool {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_replace_file").unwrap();
+    dialog.set_body(&format!(
+        "{} \"{}\"",
+        gettext("A file with this name already exists. Do you want to replace"),
+        file.basename()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    ));
+
+    dialog.choose_future(Some(appwindow)).await.as_str() == "replace"
+}
+
 pub(crate) async fn dialog_trash_file(appwindow: &RnAppWindow, current_file: &gio::File) {
     let builder = Builder::from_resource(
         (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
@@ -633,6 +863,113 @@ pub(crate) async fn dialog_trash_file(appwindow: &RnAppWindow, current_file: &gi
     }
 }
 
+/// Asks for the name of a color palette, prefilled with the initial name.
+///
+/// Returns `None` when cancelled or the name is empty.
+pub(crate) async fn dialog_color_palette_name(
+    appwindow: &RnAppWindow,
+    heading: &str,
+    initial_name: &str,
+) -> Option<String> {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_color_palette_name").unwrap();
+    let name_entryrow: adw::EntryRow = builder.object("color_palette_name_entryrow").unwrap();
+
+    dialog.set_heading(Some(heading));
+    name_entryrow.set_text(initial_name);
+
+    match dialog.choose_future(Some(appwindow)).await.as_str() {
+        "apply" => {
+            let name = name_entryrow.text().trim().to_string();
+            (!name.is_empty()).then_some(name)
+        }
+        _ => None,
+    }
+}
+
+/// Asks for a new keyboard shortcut for the action by capturing the next pressed key combination.
+///
+/// Conflicts with the shortcuts of other actions are shown before the new shortcut is applied.
+pub(crate) async fn dialog_edit_keyboard_shortcut(
+    appwindow: &RnAppWindow,
+    shortcut: &KeyboardShortcut,
+) -> Option<KeyboardShortcutEdit> {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_edit_keyboard_shortcut").unwrap();
+    let shortcut_label: ShortcutLabel = builder.object("edit_keyboard_shortcut_label").unwrap();
+    let conflict_label: Label = builder
+        .object("edit_keyboard_shortcut_conflict_label")
+        .unwrap();
+
+    dialog.set_body(&format!(
+        "{} \"{}\"",
+        gettext("Press the new shortcut for"),
+        shortcut.title
+    ));
+
+    let key_controller = EventControllerKey::builder()
+        .propagation_phase(PropagationPhase::Capture)
+        .build();
+    key_controller.connect_key_pressed(clone!(
+        #[weak]
+        appwindow,
+        #[weak]
+        dialog,
+        #[weak]
+        shortcut_label,
+        #[weak]
+        conflict_label,
+        #[strong(rename_to = detailed_action)]
+        shortcut.detailed_action,
+        #[upgrade_or]
+        glib::Propagation::Proceed,
+        move |_, key, _, modifier| {
+            let modifier = modifier & gtk4::accelerator_get_default_mod_mask();
+            // Escape without modifiers still closes the dialog
+            if key == gdk::Key::Escape && modifier.is_empty() {
+                return glib::Propagation::Proceed;
+            }
+            let key = key.to_lower();
+            // Pressing only modifiers is not a complete shortcut yet
+            if !gtk4::accelerator_valid(key, modifier) {
+                return glib::Propagation::Stop;
+            }
+            let accel = gtk4::accelerator_name(key, modifier);
+            let conflicts =
+                keyboardshortcuts::conflicting_actions(&appwindow.app(), &accel, &detailed_action);
+
+            shortcut_label.set_accelerator(&accel);
+            conflict_label.set_visible(!conflicts.is_empty());
+            conflict_label.set_label(&format!(
+                "{} {}",
+                gettext("Already used by, will be removed from:"),
+                conflicts
+                    .iter()
+                    .map(String::as_str)
+                    .map(keyboardshortcuts::action_title)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ));
+            dialog.set_response_enabled("apply", true);
+            glib::Propagation::Stop
+        }
+    ));
+    dialog.add_controller(key_controller);
+
+    match dialog.choose_future(Some(appwindow)).await.as_str() {
+        "apply" => Some(KeyboardShortcutEdit::Set(
+            shortcut_label.accelerator().to_string(),
+        )),
+        "disable" => Some(KeyboardShortcutEdit::Disable),
+        "reset" => Some(KeyboardShortcutEdit::Reset),
+        _ => None,
+    }
+}
+
 const WORKSPACELISTENTRY_ICONS_LIST: &[&str] = &[
     "workspacelistentryicon-bandaid-symbolic",
     "workspacelistentryicon-bank-symbolic",
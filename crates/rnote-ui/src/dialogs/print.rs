@@ -0,0 +1,322 @@
+// Imports
+use crate::canvas::RnCanvas;
+use crate::{RnAppWindow, config};
+use adw::prelude::*;
+use gettextrs::gettext;
+use gtk4::{Builder, PrintOperation, PrintOperationAction, ScrolledWindow, Unit, glib::clone};
+use num_traits::ToPrimitive;
+use p2d::bounding_volume::Aabb;
+use rnote_compose::SplitOrder;
+use rnote_engine::Engine;
+use rnote_engine::engine::StrokeContent;
+use rnote_engine::engine::export::{PrintPrefs, PrintScaling};
+use std::cell::RefCell;
+use std::rc::Rc;
+use tracing::{debug, error};
+
+/// Prints the document through the print dialog, which is extended with a tab for the print preferences.
+///
+/// The page range selection is offered by the print dialog itself.
+pub(crate) fn print_doc(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let print_op = PrintOperation::builder().unit(Unit::None).build();
+    let doc_dpi = canvas.engine_ref().document.config.format.dpi();
+    let pages_content: Rc<RefCell<Vec<StrokeContent>>> = Rc::default();
+
+    print_op.set_custom_tab_label(Some(&gettext("Document")));
+    print_op.connect_create_custom_widget(clone!(
+        #[strong]
+        appwindow,
+        move |_print_op| create_print_prefs_widget(&appwindow).upcast()
+    ));
+
+    // The pages are extracted after the dialog was confirmed, because the page order might have changed
+    print_op.connect_begin_print(clone!(
+        #[strong]
+        appwindow,
+        #[strong]
+        canvas,
+        #[strong]
+        pages_content,
+        move |print_op, _print_cx| {
+            let page_order = appwindow
+                .engine_config()
+                .read()
+                .export_prefs
+                .print_prefs
+                .page_order;
+            let content = canvas.engine_ref().extract_pages_content(page_order);
+            print_op.set_n_pages(content.len() as i32);
+            *pages_content.borrow_mut() = content;
+        }
+    ));
+
+    print_op.connect_draw_page(clone!(
+        #[strong]
+        appwindow,
+        #[strong]
+        pages_content,
+        move |_print_op, print_cx, page_no| {
+            let print_prefs = appwindow
+                .engine_config()
+                .read()
+                .export_prefs
+                .print_prefs
+                .clone();
+            let pages_content = pages_content.borrow();
+            let Some(page_content) = pages_content.get(page_no as usize) else {
+                return;
+            };
+            let Some(page_bounds) = page_content.bounds else {
+                return;
+            };
+            let printable_size = na::vector![print_cx.width(), print_cx.height()];
+            let print_scale = print_prefs.page_scale(
+                page_bounds.extents(),
+                printable_size,
+                print_cx.dpi_x(),
+                doc_dpi,
+            );
+            let offset =
+                print_prefs.page_offset(page_bounds.extents() * print_scale, printable_size);
+            let cairo_cx = print_cx.cairo_context();
+
+            cairo_cx.translate(offset[0], offset[1]);
+            cairo_cx.scale(print_scale, print_scale);
+            cairo_cx.translate(-page_bounds.mins[0], -page_bounds.mins[1]);
+            if let Err(e) = draw_page(
+                &cairo_cx,
+                page_content,
+                page_bounds,
+                &print_prefs,
+                // render images with the printer resolution
+                print_scale.max(Engine::STROKE_EXPORT_IMAGE_SCALE),
+            ) {
+                error!("Drawing page no: {page_no} while printing failed, Err: {e:?}");
+            }
+        }
+    ));
+
+    print_op.connect_status_changed(move |print_op| {
+        debug!(
+            "Print operation status has changed to: {:?}",
+            print_op.status()
+        );
+    });
+
+    appwindow.overlays().progressbar_start_pulsing();
+
+    // Run the print op
+    if let Err(e) = print_op.run(PrintOperationAction::PrintDialog, Some(appwindow)) {
+        error!("Running print operation failed , Err: {e:?}");
+        appwindow
+            .overlays()
+            .dispatch_toast_error(&gettext("Printing document failed"));
+        appwindow.overlays().progressbar_abort();
+    } else {
+        appwindow.overlays().progressbar_finish();
+    }
+}
+
+fn draw_page(
+    cairo_cx: &cairo::Context,
+    page_content: &StrokeContent,
+    page_bounds: Aabb,
+    print_prefs: &PrintPrefs,
+    image_scale: f64,
+) -> anyhow::Result<()> {
+    const MARGIN: f64 = 0.0;
+
+    if !print_prefs.grayscale {
+        return page_content.draw_to_cairo(
+            cairo_cx,
+            print_prefs.with_background,
+            print_prefs.with_pattern,
+            print_prefs.optimize_printing,
+            MARGIN,
+            image_scale,
+        );
+    }
+
+    cairo_cx.rectangle(
+        page_bounds.mins[0],
+        page_bounds.mins[1],
+        page_bounds.extents()[0],
+        page_bounds.extents()[1],
+    );
+    cairo_cx.clip();
+    cairo_cx.push_group();
+    page_content.draw_to_cairo(
+        cairo_cx,
+        print_prefs.with_background,
+        print_prefs.with_pattern,
+        print_prefs.optimize_printing,
+        MARGIN,
+        image_scale,
+    )?;
+    let content = cairo_cx.pop_group()?;
+
+    // The saturation blend mode keeps hue and luminosity of the content, but takes the saturation of the gray.
+    // It also covers transparent areas, so the result is masked with the content afterwards.
+    cairo_cx.push_group();
+    cairo_cx.set_source(&content)?;
+    cairo_cx.paint()?;
+    cairo_cx.set_operator(cairo::Operator::HslSaturation);
+    cairo_cx.set_source_rgb(0.5, 0.5, 0.5);
+    cairo_cx.paint()?;
+    let grayscale = cairo_cx.pop_group()?;
+
+    cairo_cx.set_source(&grayscale)?;
+    cairo_cx.mask(&content)?;
+    Ok(())
+}
+
+fn create_print_prefs_widget(appwindow: &RnAppWindow) -> ScrolledWindow {
+    let builder =
+        Builder::from_resource((String::from(config::APP_IDPATH) + "ui/dialogs/print.ui").as_str());
+    let print_options: ScrolledWindow = builder.object("print_options").unwrap();
+    let scaling_row: adw::ComboRow = builder.object("print_scaling_row").unwrap();
+    let custom_scale_row: adw::SpinRow = builder.object("print_custom_scale_row").unwrap();
+    let center_row: adw::SwitchRow = builder.object("print_center_row").unwrap();
+    let page_order_row: adw::ComboRow = builder.object("print_page_order_row").unwrap();
+    let with_background_row: adw::SwitchRow = builder.object("print_with_background_row").unwrap();
+    let with_pattern_row: adw::SwitchRow = builder.object("print_with_pattern_row").unwrap();
+    let optimize_printing_row: adw::SwitchRow =
+        builder.object("print_optimize_printing_row").unwrap();
+    let grayscale_row: adw::SwitchRow = builder.object("print_grayscale_row").unwrap();
+
+    let print_prefs = appwindow
+        .engine_config()
+        .read()
+        .export_prefs
+        .print_prefs
+        .clone();
+
+    // initial widget state with the preferences
+    scaling_row.set_selected(print_prefs.scaling.to_u32().unwrap());
+    custom_scale_row.set_value(print_prefs.custom_scale);
+    custom_scale_row.set_sensitive(print_prefs.scaling == PrintScaling::Custom);
+    center_row.set_active(print_prefs.center);
+    page_order_row.set_selected(print_prefs.page_order.to_u32().unwrap());
+    with_background_row.set_active(print_prefs.with_background);
+    with_pattern_row.set_active(print_prefs.with_pattern);
+    optimize_printing_row.set_active(print_prefs.optimize_printing);
+    grayscale_row.set_active(print_prefs.grayscale);
+
+    with_background_row
+        .bind_property("active", &with_pattern_row, "sensitive")
+        .sync_create()
+        .build();
+
+    // Update prefs
+
+    scaling_row.connect_selected_notify(clone!(
+        #[weak]
+        custom_scale_row,
+        #[weak]
+        appwindow,
+        move |row| {
+            let scaling = PrintScaling::try_from(row.selected()).unwrap();
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .print_prefs
+                .scaling = scaling;
+            custom_scale_row.set_sensitive(scaling == PrintScaling::Custom);
+        }
+    ));
+
+    custom_scale_row.connect_changed(clone!(
+        #[weak]
+        appwindow,
+        move |row| {
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .print_prefs
+                .custom_scale = row.value();
+        }
+    ));
+
+    center_row.connect_active_notify(clone!(
+        #[weak]
+        appwindow,
+        move |row| {
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .print_prefs
+                .center = row.is_active();
+        }
+    ));
+
+    page_order_row.connect_selected_notify(clone!(
+        #[weak]
+        appwindow,
+        move |row| {
+            let page_order = SplitOrder::try_from(row.selected()).unwrap();
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .print_prefs
+                .page_order = page_order;
+        }
+    ));
+
+    with_background_row.connect_active_notify(clone!(
+        #[weak]
+        appwindow,
+        move |row| {
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .print_prefs
+                .with_background = row.is_active();
+        }
+    ));
+
+    with_pattern_row.connect_active_notify(clone!(
+        #[weak]
+        appwindow,
+        move |row| {
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .print_prefs
+                .with_pattern = row.is_active();
+        }
+    ));
+
+    optimize_printing_row.connect_active_notify(clone!(
+        #[weak]
+        appwindow,
+        move |row| {
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .print_prefs
+                .optimize_printing = row.is_active();
+        }
+    ));
+
+    grayscale_row.connect_active_notify(clone!(
+        #[weak]
+        appwindow,
+        move |row| {
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .print_prefs
+                .grayscale = row.is_active();
+        }
+    ));
+
+    print_options
+}
@@ -0,0 +1,716 @@
+// Imports
+use crate::{RnAppWindow, canvas, config};
+use adw::prelude::*;
+use gettextrs::gettext;
+use gtk4::{
+    Builder, Button, DrawingArea, EventControllerLegacy, GestureDrag, Label, LevelBar, Native,
+    Widget, cairo, gdk, glib, glib::clone, graphene,
+};
+use num_traits::ToPrimitive;
+use rnote_compose::inputtransform::{InputTransform, TiltMapping};
+use rnote_compose::penpath::Element;
+use rnote_engine::pens::penholder::BacklogPolicy;
+use std::cell::RefCell;
+use std::rc::Rc;
+use tracing::error;
+
+/// The stroke width in the test area at full pressure.
+const TEST_STROKE_WIDTH_MAX: f64 = 16.0;
+/// The padding around the plot of the pressure curve.
+const CURVE_PADDING: f64 = 12.0;
+/// The minimum distance between the minimum and maximum pressure.
+const PRESSURE_RANGE_MIN: f64 = 0.1;
+
+#[derive(Debug, Default)]
+struct StylusTestState {
+    /// The drawn strokes in the test area, as positions in widget coordinates with the mapped pressure.
+    strokes: Vec<Vec<(na::Vector2<f64>, f64)>>,
+    pressed: bool,
+    hover_pos: Option<na::Vector2<f64>>,
+    /// The input pressure of the last stylus event.
+    input_pressure: Option<f64>,
+    device: Option<gdk::Device>,
+    device_tool: Option<gdk::DeviceTool>,
+}
+
+#[derive(Debug, Clone)]
+struct StylusDialogLabels {
+    input_pressure_levelbar: LevelBar,
+    input_pressure_label: Label,
+    mapped_pressure_levelbar: LevelBar,
+    mapped_pressure_label: Label,
+    tilt_label: Label,
+    hover_label: Label,
+    device_label: Label,
+    tool_label: Label,
+    axes_label: Label,
+    used_label: Label,
+}
+
+/// Shows the stylus calibration dialog.
+///
+/// It visualizes the live input of the stylus, edits the input transform of the engine config
+/// and reports the capabilities of the detected device.
+pub(crate) fn dialog_stylus_calibration(appwindow: &RnAppWindow) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/stylus.ui").as_str(),
+    );
+    let dialog: adw::Dialog = builder.object("dialog_stylus_calibration").unwrap();
+    let reset_button: Button = builder.object("stylus_reset_button").unwrap();
+    let test_area: DrawingArea = builder.object("stylus_test_area").unwrap();
+    let test_area_clear_button: Button = builder.object("stylus_test_area_clear_button").unwrap();
+    let curve_area: DrawingArea = builder.object("stylus_curve_area").unwrap();
+    let pressure_min_row: adw::SpinRow = builder.object("stylus_pressure_min_row").unwrap();
+    let pressure_max_row: adw::SpinRow = builder.object("stylus_pressure_max_row").unwrap();
+    let pressure_gamma_row: adw::SpinRow = builder.object("stylus_pressure_gamma_row").unwrap();
+    let tilt_mapping_row: adw::ComboRow = builder.object("stylus_tilt_mapping_row").unwrap();
+    let tilt_strength_row: adw::SpinRow = builder.object("stylus_tilt_strength_row").unwrap();
+    let jitter_threshold_row: adw::SpinRow = builder.object("stylus_jitter_threshold_row").unwrap();
+    let labels = StylusDialogLabels {
+        input_pressure_levelbar: builder.object("stylus_input_pressure_levelbar").unwrap(),
+        input_pressure_label: builder.object("stylus_input_pressure_label").unwrap(),
+        mapped_pressure_levelbar: builder.object("stylus_mapped_pressure_levelbar").unwrap(),
+        mapped_pressure_label: builder.object("stylus_mapped_pressure_label").unwrap(),
+        tilt_label: builder.object("stylus_tilt_label").unwrap(),
+        hover_label: builder.object("stylus_hover_label").unwrap(),
+        device_label: builder.object("stylus_device_label").unwrap(),
+        tool_label: builder.object("stylus_tool_label").unwrap(),
+        axes_label: builder.object("stylus_axes_label").unwrap(),
+        used_label: builder.object("stylus_used_label").unwrap(),
+    };
+    let state = Rc::new(RefCell::new(StylusTestState::default()));
+
+    let input_transform = appwindow.engine_config().read().input_transform;
+    set_input_transform_rows(
+        input_transform,
+        &pressure_min_row,
+        &pressure_max_row,
+        &pressure_gamma_row,
+        &tilt_mapping_row,
+        &tilt_strength_row,
+        &jitter_threshold_row,
+    );
+    update_live_input(&labels, None, None, None, None);
+    update_device_info(appwindow, &labels, &state.borrow());
+
+    test_area.set_draw_func(clone!(
+        #[strong]
+        state,
+        move |area, cx, _, _| {
+            draw_test_area(area, cx, &state.borrow());
+        }
+    ));
+    curve_area.set_draw_func(clone!(
+        #[weak]
+        appwindow,
+        #[strong]
+        state,
+        move |area, cx, _, _| {
+            let input_transform = appwindow.engine_config().read().input_transform;
+            draw_curve_area(area, cx, &input_transform, state.borrow().input_pressure);
+        }
+    ));
+
+    let pointer_controller = EventControllerLegacy::new();
+    pointer_controller.connect_event(clone!(
+        #[weak]
+        appwindow,
+        #[weak]
+        test_area,
+        #[weak]
+        curve_area,
+        #[strong]
+        state,
+        #[strong]
+        labels,
+        #[upgrade_or]
+        glib::Propagation::Proceed,
+        move |_, event| {
+            let propagation =
+                handle_test_area_event(&appwindow, &test_area, &labels, &state, event);
+            test_area.queue_draw();
+            curve_area.queue_draw();
+            propagation
+        }
+    ));
+    test_area.add_controller(pointer_controller);
+
+    test_area_clear_button.connect_clicked(clone!(
+        #[weak]
+        test_area,
+        #[strong]
+        state,
+        move |_| {
+            state.borrow_mut().strokes.clear();
+            test_area.queue_draw();
+        }
+    ));
+
+    // Dragging on the curve bends it through the pointer position
+    let curve_drag = GestureDrag::new();
+    let bend_curve = clone!(
+        #[weak]
+        appwindow,
+        #[weak]
+        curve_area,
+        #[weak]
+        pressure_gamma_row,
+        move |x: f64, y: f64| {
+            let input_transform = appwindow.engine_config().read().input_transform;
+            let (plot_width, plot_height) = curve_plot_size(&curve_area);
+            let input = ((x - CURVE_PADDING) / plot_width).clamp(0.0, 1.0);
+            let output = (1.0 - (y - CURVE_PADDING) / plot_height).clamp(0.0, 1.0);
+            let range = input_transform.pressure_max - input_transform.pressure_min;
+            let normalized = (input - input_transform.pressure_min) / range;
+            // the curve always passes through the corners, which can't be dragged
+            if !(0.01..=0.99).contains(&normalized) || !(0.01..=0.99).contains(&output) {
+                return;
+            }
+            pressure_gamma_row.set_value((output.ln() / normalized.ln()).clamp(
+                InputTransform::PRESSURE_GAMMA_MIN,
+                InputTransform::PRESSURE_GAMMA_MAX,
+            ));
+        }
+    );
+    curve_drag.connect_drag_begin(clone!(
+        #[strong]
+        bend_curve,
+        move |_, x, y| {
+            bend_curve(x, y);
+        }
+    ));
+    curve_drag.connect_drag_update(move |gesture, offset_x, offset_y| {
+        if let Some((start_x, start_y)) = gesture.start_point() {
+            bend_curve(start_x + offset_x, start_y + offset_y);
+        }
+    });
+    curve_area.add_controller(curve_drag);
+
+    pressure_min_row.connect_changed(clone!(
+        #[weak]
+        appwindow,
+        #[weak]
+        curve_area,
+        #[weak]
+        pressure_max_row,
+        move |row| {
+            let pressure_min = row.value();
+            appwindow
+                .engine_config()
+                .write()
+                .input_transform
+                .pressure_min = pressure_min;
+            if pressure_max_row.value() < pressure_min + PRESSURE_RANGE_MIN {
+                pressure_max_row.set_value(pressure_min + PRESSURE_RANGE_MIN);
+            }
+            curve_area.queue_draw();
+        }
+    ));
+
+    pressure_max_row.connect_changed(clone!(
+        #[weak]
+        appwindow,
+        #[weak]
+        curve_area,
+        #[weak]
+        pressure_min_row,
+        move |row| {
+            let pressure_max = row.value();
+            appwindow
+                .engine_config()
+                .write()
+                .input_transform
+                .pressure_max = pressure_max;
+            if pressure_min_row.value() > pressure_max - PRESSURE_RANGE_MIN {
+                pressure_min_row.set_value(pressure_max - PRESSURE_RANGE_MIN);
+            }
+            curve_area.queue_draw();
+        }
+    ));
+
+    pressure_gamma_row.connect_changed(clone!(
+        #[weak]
+        appwindow,
+        #[weak]
+        curve_area,
+        move |row| {
+            appwindow
+                .engine_config()
+                .write()
+                .input_transform
+                .pressure_gamma = row.value();
+            curve_area.queue_draw();
+        }
+    ));
+
+    tilt_mapping_row.connect_selected_notify(clone!(
+        #[weak]
+        appwindow,
+        #[weak]
+        tilt_strength_row,
+        #[strong]
+        state,
+        #[strong]
+        labels,
+        move |row| {
+            let tilt_mapping = TiltMapping::try_from(row.selected()).unwrap();
+            appwindow
+                .engine_config()
+                .write()
+                .input_transform
+                .tilt_mapping = tilt_mapping;
+            tilt_strength_row.set_sensitive(tilt_mapping != TiltMapping::None);
+            update_device_info(&appwindow, &labels, &state.borrow());
+        }
+    ));
+
+    tilt_strength_row.connect_changed(clone!(
+        #[weak]
+        appwindow,
+        move |row| {
+            appwindow
+                .engine_config()
+                .write()
+                .input_transform
+                .tilt_strength = row.value();
+        }
+    ));
+
+    jitter_threshold_row.connect_changed(clone!(
+        #[weak]
+        appwindow,
+        move |row| {
+            appwindow
+                .engine_config()
+                .write()
+                .input_transform
+                .jitter_threshold = row.value();
+        }
+    ));
+
+    reset_button.connect_clicked(clone!(
+        #[weak]
+        pressure_min_row,
+        #[weak]
+        pressure_max_row,
+        #[weak]
+        pressure_gamma_row,
+        #[weak]
+        tilt_mapping_row,
+        #[weak]
+        tilt_strength_row,
+        #[weak]
+        jitter_threshold_row,
+        move |_| {
+            set_input_transform_rows(
+                InputTransform::default(),
+                &pressure_min_row,
+                &pressure_max_row,
+                &pressure_gamma_row,
+                &tilt_mapping_row,
+                &tilt_strength_row,
+                &jitter_threshold_row,
+            );
+        }
+    ));
+
+    dialog.present(appwindow.root().as_ref());
+}
+
+fn set_input_transform_rows(
+    input_transform: InputTransform,
+    pressure_min_row: &adw::SpinRow,
+    pressure_max_row: &adw::SpinRow,
+    pressure_gamma_row: &adw::SpinRow,
+    tilt_mapping_row: &adw::ComboRow,
+    tilt_strength_row: &adw::SpinRow,
+    jitter_threshold_row: &adw::SpinRow,
+) {
+    // The handlers of the rows write into the config, so the maximum is set first to keep the range valid
+    pressure_max_row.set_value(input_transform.pressure_max);
+    pressure_min_row.set_value(input_transform.pressure_min);
+    pressure_gamma_row.set_value(input_transform.pressure_gamma);
+    tilt_mapping_row.set_selected(input_transform.tilt_mapping.to_u32().unwrap());
+    tilt_strength_row.set_value(input_transform.tilt_strength);
+    tilt_strength_row.set_sensitive(input_transform.tilt_mapping != TiltMapping::None);
+    jitter_threshold_row.set_value(input_transform.jitter_threshold);
+}
+
+fn handle_test_area_event(
+    appwindow: &RnAppWindow,
+    test_area: &DrawingArea,
+    labels: &StylusDialogLabels,
+    state: &RefCell<StylusTestState>,
+    event: &gdk::Event,
+) -> glib::Propagation {
+    let event_type = event.event_type();
+    if !matches!(
+        event_type,
+        gdk::EventType::ButtonPress
+            | gdk::EventType::ButtonRelease
+            | gdk::EventType::MotionNotify
+            | gdk::EventType::ProximityIn
+            | gdk::EventType::ProximityOut
+    ) || event.is_pointer_emulated()
+    {
+        return glib::Propagation::Proceed;
+    }
+
+    let device = event.device();
+    let device_tool = event.device_tool();
+    if state.borrow().device != device || state.borrow().device_tool != device_tool {
+        {
+            let mut state = state.borrow_mut();
+            state.device = device;
+            state.device_tool = device_tool;
+        }
+        update_device_info(appwindow, labels, &state.borrow());
+    }
+
+    if event_type == gdk::EventType::ProximityOut {
+        let mut state = state.borrow_mut();
+        state.pressed = false;
+        state.hover_pos = None;
+        state.input_pressure = None;
+        update_live_input(labels, None, None, None, None);
+        return glib::Propagation::Stop;
+    }
+
+    let Some(pos) = event_widget_pos(test_area, event) else {
+        return glib::Propagation::Proceed;
+    };
+    let input_transform = appwindow.engine_config().read().input_transform;
+    // Retrieving the axes only works when the event has a device tool, see the canvas input handling
+    let is_stylus = canvas::event_is_stylus(event);
+    let (input_pressure, tilt, distance) = if is_stylus {
+        (
+            event.axis(gdk::AxisUse::Pressure),
+            event
+                .axis(gdk::AxisUse::Xtilt)
+                .zip(event.axis(gdk::AxisUse::Ytilt))
+                .map(|(xtilt, ytilt)| na::vector![xtilt, ytilt]),
+            event.axis(gdk::AxisUse::Distance),
+        )
+    } else {
+        (None, None, None)
+    };
+    let mapped_pressure = match input_pressure {
+        Some(pressure) => {
+            input_transform
+                .transform_element(Element::new(pos, pressure), tilt)
+                .pressure
+        }
+        None => Element::PRESSURE_DEFAULT,
+    };
+
+    let mut state = state.borrow_mut();
+    match event_type {
+        gdk::EventType::ButtonPress => {
+            let primary = event
+                .downcast_ref::<gdk::ButtonEvent>()
+                .is_some_and(|event| event.button() == gdk::BUTTON_PRIMARY);
+            if primary {
+                state.pressed = true;
+                state.strokes.push(vec![(pos, mapped_pressure)]);
+            }
+        }
+        gdk::EventType::ButtonRelease => {
+            state.pressed = false;
+        }
+        gdk::EventType::MotionNotify if state.pressed => {
+            if let Some(stroke) = state.strokes.last_mut() {
+                stroke.push((pos, mapped_pressure));
+            }
+        }
+        _ => {}
+    }
+    state.hover_pos = (!state.pressed).then_some(pos);
+    let input_pressure = input_pressure.filter(|_| state.pressed);
+    let mapped_pressure = input_pressure.map(|_| mapped_pressure);
+    state.input_pressure = input_pressure;
+    let distance = distance.filter(|_| !state.pressed);
+    update_live_input(labels, input_pressure, mapped_pressure, tilt, distance);
+
+    glib::Propagation::Stop
+}
+
+/// The position of the event in the coordinate space of the widget.
+fn event_widget_pos(widget: &impl IsA<Widget>, event: &gdk::Event) -> Option<na::Vector2<f64>> {
+    let native = Native::for_surface(&event.surface()?)?;
+    let (surface_trans_x, surface_trans_y) = native.surface_transform();
+    let (x, y) = event.position()?;
+    let point = native.compute_point(
+        widget,
+        &graphene::Point::new((x - surface_trans_x) as f32, (y - surface_trans_y) as f32),
+    )?;
+    Some(na::vector![point.x() as f64, point.y() as f64])
+}
+
+fn update_live_input(
+    labels: &StylusDialogLabels,
+    input_pressure: Option<f64>,
+    mapped_pressure: Option<f64>,
+    tilt: Option<na::Vector2<f64>>,
+    distance: Option<f64>,
+) {
+    let not_available = gettext("Not available");
+
+    labels
+        .input_pressure_levelbar
+        .set_value(input_pressure.unwrap_or(0.0));
+    labels
+        .input_pressure_label
+        .set_label(&input_pressure.map_or_else(|| "-".to_string(), |p| format!("{p:.2}")));
+    labels
+        .mapped_pressure_levelbar
+        .set_value(mapped_pressure.unwrap_or(0.0));
+    labels
+        .mapped_pressure_label
+        .set_label(&mapped_pressure.map_or_else(|| "-".to_string(), |p| format!("{p:.2}")));
+    labels.tilt_label.set_label(&tilt.map_or_else(
+        || not_available.clone(),
+        |tilt| format!("x: {:.0}°, y: {:.0}°", tilt[0], tilt[1]),
+    ));
+    labels.hover_label.set_label(&distance.map_or_else(
+        || not_available.clone(),
+        |distance| format!("{distance:.2}"),
+    ));
+}
+
+fn update_device_info(
+    appwindow: &RnAppWindow,
+    labels: &StylusDialogLabels,
+    state: &StylusTestState,
+) {
+    let not_detected = gettext("Not detected");
+
+    labels
+        .device_label
+        .set_label(&state.device.as_ref().map_or_else(
+            || not_detected.clone(),
+            |device| {
+                let mut text =
+                    format!("{} ({})", device.name(), input_source_name(device.source()));
+                if let (Some(vendor_id), Some(product_id)) =
+                    (device.vendor_id(), device.product_id())
+                {
+                    text.push_str(&format!(", {vendor_id}:{product_id}"));
+                }
+                text
+            },
+        ));
+
+    let Some(device_tool) = state.device_tool.as_ref() else {
+        labels.tool_label.set_label(&not_detected);
+        labels.axes_label.set_label(&not_detected);
+        labels.used_label.set_label(&if state.device.is_some() {
+            gettext("Position only, without pressure")
+        } else {
+            not_detected
+        });
+        return;
+    };
+    let serial = device_tool.serial();
+    labels.tool_label.set_label(&if serial != 0 {
+        format!(
+            "{}, {} {serial:#x}",
+            tool_type_name(device_tool.tool_type()),
+            gettext("Serial")
+        )
+    } else {
+        tool_type_name(device_tool.tool_type())
+    });
+
+    let axes = device_tool.axes();
+    let axes_names = [
+        (gdk::AxisFlags::PRESSURE, gettext("Pressure")),
+        (
+            gdk::AxisFlags::XTILT | gdk::AxisFlags::YTILT,
+            gettext("Tilt"),
+        ),
+        (gdk::AxisFlags::DISTANCE, gettext("Hover Distance")),
+        (gdk::AxisFlags::ROTATION, gettext("Rotation")),
+        (gdk::AxisFlags::SLIDER, gettext("Slider")),
+        (gdk::AxisFlags::WHEEL, gettext("Wheel")),
+    ]
+    .into_iter()
+    .filter_map(|(flags, name)| axes.contains(flags).then_some(name))
+    .collect::<Vec<String>>();
+    labels.axes_label.set_label(&if axes_names.is_empty() {
+        gettext("None")
+    } else {
+        axes_names.join(", ")
+    });
+
+    // Mirrors how the canvas input handling retrieves the elements and the pen mode
+    let input_transform = appwindow.engine_config().read().input_transform;
+    let backlog_policy = appwindow
+        .active_tab_canvas()
+        .map(|canvas| canvas.engine_ref().penholder.backlog_policy());
+    let mut used = vec![gettext("Position")];
+    if axes.contains(gdk::AxisFlags::PRESSURE) {
+        used.push(gettext("Pressure"));
+    }
+    if axes.contains(gdk::AxisFlags::XTILT | gdk::AxisFlags::YTILT)
+        && input_transform.tilt_mapping != TiltMapping::None
+    {
+        used.push(gettext("Tilt"));
+    }
+    match device_tool.tool_type() {
+        gdk::DeviceToolType::Pen => used.push(gettext("Pen Mode")),
+        gdk::DeviceToolType::Eraser => used.push(gettext("Eraser Mode")),
+        _ => {}
+    }
+    if backlog_policy.is_some_and(|policy| policy != BacklogPolicy::Disable) {
+        used.push(gettext("Event History"));
+    }
+    labels.used_label.set_label(&used.join(", "));
+}
+
+fn input_source_name(source: gdk::InputSource) -> String {
+    match source {
+        gdk::InputSource::Mouse => gettext("Mouse"),
+        gdk::InputSource::Pen => gettext("Pen"),
+        gdk::InputSource::Keyboard => gettext("Keyboard"),
+        gdk::InputSource::Touchscreen => gettext("Touchscreen"),
+        gdk::InputSource::Touchpad => gettext("Touchpad"),
+        gdk::InputSource::Trackpoint => gettext("Trackpoint"),
+        gdk::InputSource::TabletPad => gettext("Drawing Pad"),
+        _ => gettext("Unknown"),
+    }
+}
+
+fn tool_type_name(tool_type: gdk::DeviceToolType) -> String {
+    match tool_type {
+        gdk::DeviceToolType::Pen => gettext("Pen"),
+        gdk::DeviceToolType::Eraser => gettext("Eraser"),
+        gdk::DeviceToolType::Brush => gettext("Brush"),
+        gdk::DeviceToolType::Pencil => gettext("Pencil"),
+        gdk::DeviceToolType::Airbrush => gettext("Airbrush"),
+        gdk::DeviceToolType::Mouse => gettext("Mouse"),
+        gdk::DeviceToolType::Lens => gettext("Lens"),
+        _ => gettext("Unknown"),
+    }
+}
+
+fn set_source_color(cx: &cairo::Context, color: &gdk::RGBA, alpha: f64) {
+    cx.set_source_rgba(
+        color.red() as f64,
+        color.green() as f64,
+        color.blue() as f64,
+        color.alpha() as f64 * alpha,
+    );
+}
+
+fn draw_test_area(area: &DrawingArea, cx: &cairo::Context, state: &StylusTestState) {
+    let accent_color = adw::StyleManager::default().accent_color_rgba();
+    set_source_color(cx, &accent_color, 1.0);
+    cx.set_line_cap(cairo::LineCap::Round);
+    for stroke in state.strokes.iter() {
+        for ((start, _), (end, pressure)) in stroke.iter().zip(stroke.iter().skip(1)) {
+            cx.set_line_width((TEST_STROKE_WIDTH_MAX * pressure).max(0.5));
+            cx.move_to(start[0], start[1]);
+            cx.line_to(end[0], end[1]);
+            if let Err(e) = cx.stroke() {
+                error!("Painting stylus test stroke failed, stroke returned Err: {e:?}");
+            }
+        }
+    }
+
+    if let Some(hover_pos) = state.hover_pos {
+        set_source_color(cx, &area.color(), 0.5);
+        cx.set_line_width(1.0);
+        cx.arc(
+            hover_pos[0],
+            hover_pos[1],
+            TEST_STROKE_WIDTH_MAX * 0.5,
+            0.0,
+            std::f64::consts::TAU,
+        );
+        if let Err(e) = cx.stroke() {
+            error!("Painting stylus hover indicator failed, stroke returned Err: {e:?}");
+        }
+    }
+}
+
+/// The size of the plot in the curve area, without the padding.
+fn curve_plot_size(area: &DrawingArea) -> (f64, f64) {
+    (
+        (f64::from(area.width()) - 2.0 * CURVE_PADDING).max(1.0),
+        (f64::from(area.height()) - 2.0 * CURVE_PADDING).max(1.0),
+    )
+}
+
+fn draw_curve_area(
+    area: &DrawingArea,
+    cx: &cairo::Context,
+    input_transform: &InputTransform,
+    input_pressure: Option<f64>,
+) {
+    let fg_color = area.color();
+    let accent_color = adw::StyleManager::default().accent_color_rgba();
+    let (plot_width, plot_height) = curve_plot_size(area);
+    let plot_point = |input: f64, output: f64| {
+        (
+            CURVE_PADDING + input * plot_width,
+            CURVE_PADDING + (1.0 - output) * plot_height,
+        )
+    };
+
+    // Dead zones
+    set_source_color(cx, &fg_color, 0.1);
+    cx.rectangle(
+        CURVE_PADDING,
+        CURVE_PADDING,
+        input_transform.pressure_min * plot_width,
+        plot_height,
+    );
+    cx.rectangle(
+        CURVE_PADDING + input_transform.pressure_max * plot_width,
+        CURVE_PADDING,
+        (1.0 - input_transform.pressure_max) * plot_width,
+        plot_height,
+    );
+    if let Err(e) = cx.fill() {
+        error!("Painting pressure curve dead zones failed, fill returned Err: {e:?}");
+    }
+
+    // Linear reference
+    set_source_color(cx, &fg_color, 0.3);
+    cx.set_line_width(1.0);
+    cx.set_dash(&[4.0, 4.0], 0.0);
+    let (x, y) = plot_point(0.0, 0.0);
+    cx.move_to(x, y);
+    let (x, y) = plot_point(1.0, 1.0);
+    cx.line_to(x, y);
+    if let Err(e) = cx.stroke() {
+        error!("Painting pressure curve reference failed, stroke returned Err: {e:?}");
+    }
+    cx.set_dash(&[], 0.0);
+
+    // Curve
+    const SAMPLES: usize = 100;
+    set_source_color(cx, &accent_color, 1.0);
+    cx.set_line_width(2.0);
+    for i in 0..=SAMPLES {
+        let input = i as f64 / SAMPLES as f64;
+        let (x, y) = plot_point(input, input_transform.map_pressure(input));
+        if i == 0 {
+            cx.move_to(x, y);
+        } else {
+            cx.line_to(x, y);
+        }
+    }
+    if let Err(e) = cx.stroke() {
+        error!("Painting pressure curve failed, stroke returned Err: {e:?}");
+    }
+
+    // Current input
+    if let Some(input_pressure) = input_pressure {
+        let (x, y) = plot_point(input_pressure, input_transform.map_pressure(input_pressure));
+        cx.arc(x, y, 5.0, 0.0, std::f64::consts::TAU);
+        if let Err(e) = cx.fill() {
+            error!("Painting pressure curve input indicator failed, fill returned Err: {e:?}");
+        }
+    }
+}
@@ -24,28 +24,11 @@ impl FileType {
         ) {
             match info.file_type() {
                 gio::FileType::Regular => {
-                    if let Some(content_type) = info.content_type() {
-                        match content_type.as_str() {
-                            "application/rnote" => {
-                                return Self::RnoteFile;
-                            }
-                            "image/svg+xml" => {
-                                return Self::VectorImageFile;
-                            }
-                            "image/png" | "image/jpeg" => {
-                                return Self::BitmapImageFile;
-                            }
-                            "application/x-xopp" => {
-                                return Self::XoppFile;
-                            }
-                            "application/pdf" => {
-                                return Self::PdfFile;
-                            }
-                            "text/plain" => {
-                                return Self::PlaintextFile;
-                            }
-                            _ => {}
-                        }
+                    if let Some(file_type) = info
+                        .content_type()
+                        .and_then(|content_type| Self::from_content_type(&content_type))
+                    {
+                        return file_type;
                     }
                 }
                 gio::FileType::Directory => {
@@ -91,4 +74,24 @@ impl FileType {
 
         Self::Unsupported
     }
+
+    /// Looks up the file type of content that is not backed by a file, by guessing it from the data.
+    pub(crate) fn lookup_bytes_type(bytes: &[u8]) -> Self {
+        let (content_type, _) = gio::content_type_guess(None::<&std::path::Path>, bytes);
+        gio::content_type_get_mime_type(&content_type)
+            .and_then(|mime_type| Self::from_content_type(&mime_type))
+            .unwrap_or(Self::Unsupported)
+    }
+
+    fn from_content_type(content_type: &str) -> Option<Self> {
+        match content_type {
+            "application/rnote" => Some(Self::RnoteFile),
+            "image/svg+xml" => Some(Self::VectorImageFile),
+            "image/png" | "image/jpeg" => Some(Self::BitmapImageFile),
+            "application/x-xopp" => Some(Self::XoppFile),
+            "application/pdf" => Some(Self::PdfFile),
+            "text/plain" => Some(Self::PlaintextFile),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,334 @@
+// Imports
+use crate::RnApp;
+use crate::RnPenPresetBar;
+use crate::dialogs::commandpalette;
+use gettextrs::gettext;
+use gtk4::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tracing::{error, warn};
+
+/// A window or app action that can be bound to keyboard shortcuts.
+#[derive(Debug, Clone)]
+pub(crate) struct KeyboardShortcut {
+    /// The detailed action name, including the action group prefix and the target, e.g. `win.pen-style::brush`.
+    pub(crate) detailed_action: String,
+    /// The translated title.
+    pub(crate) title: String,
+    /// The translated category the action belongs to.
+    pub(crate) category: String,
+}
+
+/// How the shortcuts of an action should be changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum KeyboardShortcutEdit {
+    /// Replace the shortcuts with the accelerator.
+    Set(String),
+    /// Remove all shortcuts.
+    Disable,
+    /// Restore the default shortcuts.
+    Reset,
+}
+
+/// The file contents of an exported keyboard shortcuts profile.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "keyboard_shortcuts_profile")]
+pub(crate) struct KeyboardShortcutsProfile {
+    /// The accelerators of every editable action, keyed by the detailed action name.
+    pub(crate) shortcuts: BTreeMap<String, Vec<String>>,
+}
+
+/// All actions that are listed in the keyboard shortcut editor.
+///
+/// Contains the commands of the command palette, and additionally actions that are only useful to trigger with
+/// keyboard shortcuts.
+pub(crate) fn keyboard_shortcuts() -> Vec<KeyboardShortcut> {
+    let general = gettext("General");
+    let edit = gettext("Edit");
+    let pens = gettext("Pens");
+
+    let mut shortcuts = commandpalette::commands()
+        .into_iter()
+        .map(|command| KeyboardShortcut {
+            detailed_action: command.detailed_action.to_string(),
+            title: command.title,
+            category: command.category,
+        })
+        .collect::<Vec<KeyboardShortcut>>();
+
+    shortcuts.extend(
+        [
+            (
+                "win.command-palette",
+                gettext("Show Command Palette"),
+                &general,
+            ),
+            ("win.open-canvasmenu", gettext("Open Canvas Menu"), &general),
+            ("win.open-appmenu", gettext("Open App Menu"), &general),
+            ("win.text-bold", gettext("Bold Text"), &edit),
+            ("win.text-italic", gettext("Italic Text"), &edit),
+            ("win.text-underline", gettext("Underline Text"), &edit),
+        ]
+        .into_iter()
+        .map(|(detailed_action, title, category)| KeyboardShortcut {
+            detailed_action: detailed_action.to_string(),
+            title,
+            category: category.clone(),
+        }),
+    );
+    shortcuts.extend(
+        (0..RnPenPresetBar::N_SHORTCUT_PRESETS).map(|i| KeyboardShortcut {
+            detailed_action: format!("win.pen-preset-apply(uint32 {i})"),
+            title: format!("{} {}", gettext("Apply Pinned Pen"), i + 1),
+            category: pens.clone(),
+        }),
+    );
+
+    shortcuts
+}
+
+/// The default accelerators, keyed by the detailed action name.
+pub(crate) fn default_accels() -> HashMap<String, Vec<String>> {
+    let mut accels = [
+        ("app.quit", &["<Ctrl>q"][..]),
+        ("app.new-window", &["<Ctrl>n"]),
+        ("win.active-tab-close", &["<Ctrl>w"]),
+        ("win.fullscreen", &["F11"]),
+        ("win.presentation-mode", &["F5"]),
+        ("win.keyboard-shortcuts", &["<Ctrl>question"]),
+        ("win.command-palette", &["<Ctrl><Shift>p"]),
+        ("win.toggle-overview", &["<Ctrl><Shift>o"]),
+        ("win.open-canvasmenu", &["F9"]),
+        ("win.open-appmenu", &["F10"]),
+        ("win.open-doc", &["<Ctrl>o"]),
+        ("win.save-doc", &["<Ctrl>s"]),
+        ("win.save-doc-as", &["<Ctrl><Shift>s"]),
+        ("win.new-tab", &["<Ctrl>t"]),
+        ("win.snap-positions", &["<Ctrl><Shift>g"]),
+        ("win.clear-doc", &["<Ctrl>l"]),
+        ("win.print-doc", &["<Ctrl>p"]),
+        ("win.add-page-to-doc", &["<Ctrl><Shift>a"]),
+        ("win.remove-page-from-doc", &["<Ctrl><Shift>r"]),
+        (
+            "win.zoom-in",
+            &["<Ctrl>plus", "<Ctrl>equal", "<Ctrl>KP_Add"],
+        ),
+        ("win.zoom-reset", &["<Ctrl>0", "<Ctrl>KP_0"]),
+        ("win.zoom-out", &["<Ctrl>minus", "<Ctrl>KP_Subtract"]),
+        ("win.import-file", &["<Ctrl><Shift>i"]),
+        ("win.undo", &["<Ctrl>z"]),
+        ("win.redo", &["<Ctrl><Shift>z"]),
+        ("win.clipboard-copy", &["<Ctrl>c"]),
+        ("win.clipboard-cut", &["<Ctrl>x"]),
+        ("win.clipboard-paste", &["<Ctrl>v"]),
+        ("win.selection-copy-style", &["<Ctrl><Alt>c"]),
+        ("win.selection-paste-style", &["<Ctrl><Alt>v"]),
+        ("win.selection-raise", &["<Ctrl>bracketright"]),
+        ("win.selection-lower", &["<Ctrl>bracketleft"]),
+        ("win.selection-group", &["<Ctrl>g"]),
+        ("win.selection-ungroup", &["<Ctrl><Alt>g"]),
+        ("win.text-bold", &["<Ctrl>b"]),
+        ("win.text-italic", &["<Ctrl>i"]),
+        ("win.text-underline", &["<Ctrl>u"]),
+        ("win.pen-style::brush", &["<Ctrl>1", "<Ctrl>KP_1"]),
+        ("win.pen-style::shaper", &["<Ctrl>2", "<Ctrl>KP_2"]),
+        ("win.pen-style::typewriter", &["<Ctrl>3", "<Ctrl>KP_3"]),
+        ("win.pen-style::eraser", &["<Ctrl>4", "<Ctrl>KP_4"]),
+        ("win.pen-style::selector", &["<Ctrl>5", "<Ctrl>KP_5"]),
+        ("win.pen-style::tools", &["<Ctrl>6", "<Ctrl>KP_6"]),
+    ]
+    .into_iter()
+    .map(|(detailed_action, accels)| {
+        (
+            detailed_action.to_string(),
+            accels.iter().map(|accel| accel.to_string()).collect(),
+        )
+    })
+    .collect::<HashMap<String, Vec<String>>>();
+
+    for i in 0..RnPenPresetBar::N_SHORTCUT_PRESETS {
+        let n = i + 1;
+        accels.insert(
+            format!("win.pen-preset-apply(uint32 {i})"),
+            vec![format!("<Alt>{n}"), format!("<Alt>KP_{n}")],
+        );
+    }
+
+    accels
+}
+
+/// The accelerators that differ from the defaults, read from the settings.
+pub(crate) fn accel_overrides(app: &RnApp) -> HashMap<String, Vec<String>> {
+    let Some(app_settings) = app.app_settings() else {
+        return HashMap::new();
+    };
+    app_settings
+        .value("keyboard-shortcuts")
+        .get::<HashMap<String, Vec<String>>>()
+        .unwrap_or_default()
+}
+
+fn save_accel_overrides(app: &RnApp, overrides: &HashMap<String, Vec<String>>) {
+    let Some(app_settings) = app.app_settings() else {
+        error!("Saving keyboard shortcuts failed, settings schema not found.");
+        return;
+    };
+    if let Err(e) = app_settings.set_value("keyboard-shortcuts", &overrides.to_variant()) {
+        error!("Saving `keyboard-shortcuts` to settings failed, Err: {e:?}");
+    }
+}
+
+/// Store the accelerators of the action as override, or remove the override when they match the defaults.
+fn set_accel_override(
+    overrides: &mut HashMap<String, Vec<String>>,
+    defaults: &HashMap<String, Vec<String>>,
+    detailed_action: &str,
+    accels: Vec<String>,
+) {
+    if defaults
+        .get(detailed_action)
+        .map(|default| *default == accels)
+        .unwrap_or(accels.is_empty())
+    {
+        overrides.remove(detailed_action);
+    } else {
+        overrides.insert(detailed_action.to_string(), accels);
+    }
+}
+
+/// Set the accelerators of all actions to the defaults, replaced by the user defined overrides.
+pub(crate) fn apply_accels(app: &RnApp) {
+    // Editable actions without defaults must be cleared, in case their overrides were removed
+    let mut accels = keyboard_shortcuts()
+        .into_iter()
+        .map(|shortcut| (shortcut.detailed_action, vec![]))
+        .collect::<HashMap<String, Vec<String>>>();
+    accels.extend(default_accels());
+    accels.extend(accel_overrides(app));
+
+    for (detailed_action, accels) in accels.iter() {
+        app.set_accels_for_action(
+            detailed_action,
+            &accels.iter().map(String::as_str).collect::<Vec<&str>>(),
+        );
+    }
+}
+
+/// Edit the accelerators of an action, and persist them in the settings.
+///
+/// When setting a new accelerator, it is removed from all other actions that were bound to it.
+pub(crate) fn edit_accels(app: &RnApp, detailed_action: &str, edit: KeyboardShortcutEdit) {
+    let defaults = default_accels();
+    let mut overrides = accel_overrides(app);
+
+    match edit {
+        KeyboardShortcutEdit::Set(accel) => {
+            for conflicting in conflicting_actions(app, &accel, detailed_action) {
+                let remaining = app
+                    .accels_for_action(&conflicting)
+                    .iter()
+                    .filter(|other| !accels_equal(other, &accel))
+                    .map(|other| other.to_string())
+                    .collect();
+                set_accel_override(&mut overrides, &defaults, &conflicting, remaining);
+            }
+            set_accel_override(&mut overrides, &defaults, detailed_action, vec![accel]);
+        }
+        KeyboardShortcutEdit::Disable => {
+            set_accel_override(&mut overrides, &defaults, detailed_action, vec![]);
+        }
+        KeyboardShortcutEdit::Reset => {
+            overrides.remove(detailed_action);
+        }
+    }
+
+    save_accel_overrides(app, &overrides);
+    apply_accels(app);
+}
+
+/// Restore the default accelerators of all actions.
+pub(crate) fn reset_all_accels(app: &RnApp) {
+    save_accel_overrides(app, &HashMap::new());
+    apply_accels(app);
+}
+
+/// The detailed names of the actions other than the given one that are bound to the accelerator.
+pub(crate) fn conflicting_actions(app: &RnApp, accel: &str, detailed_action: &str) -> Vec<String> {
+    app.actions_for_accel(accel)
+        .iter()
+        .map(|other| other.to_string())
+        .filter(|other| other != detailed_action)
+        .collect()
+}
+
+/// The title of the action in the shortcut editor, or the action name if it isn't listed there.
+pub(crate) fn action_title(detailed_action: &str) -> String {
+    keyboard_shortcuts()
+        .into_iter()
+        .find(|shortcut| shortcut.detailed_action == detailed_action)
+        .map(|shortcut| shortcut.title)
+        .unwrap_or_else(|| detailed_action.to_string())
+}
+
+fn accels_equal(first: &str, second: &str) -> bool {
+    gtk4::accelerator_parse(first)
+        .is_some_and(|first| Some(first) == gtk4::accelerator_parse(second))
+}
+
+/// Export the current accelerators of all editable actions as profile.
+pub(crate) fn export_profile(app: &RnApp) -> anyhow::Result<String> {
+    let profile = KeyboardShortcutsProfile {
+        shortcuts: keyboard_shortcuts()
+            .into_iter()
+            .map(|shortcut| {
+                let accels = app
+                    .accels_for_action(&shortcut.detailed_action)
+                    .iter()
+                    .map(|accel| accel.to_string())
+                    .collect();
+                (shortcut.detailed_action, accels)
+            })
+            .collect(),
+    };
+    Ok(serde_json::to_string_pretty(&profile)?)
+}
+
+/// Import a profile, replacing the accelerators of the actions contained in it.
+///
+/// Unknown actions and invalid accelerators are skipped.
+pub(crate) fn import_profile(app: &RnApp, profile: &str) -> anyhow::Result<()> {
+    let profile = serde_json::from_str::<KeyboardShortcutsProfile>(profile)?;
+    let editable = keyboard_shortcuts()
+        .into_iter()
+        .map(|shortcut| shortcut.detailed_action)
+        .collect::<Vec<String>>();
+    let defaults = default_accels();
+    let mut overrides = accel_overrides(app);
+
+    for (detailed_action, accels) in profile.shortcuts {
+        if !editable.contains(&detailed_action) {
+            warn!("Importing keyboard shortcut of unknown action `{detailed_action}` skipped.");
+            continue;
+        }
+        let (accels, invalid): (Vec<String>, Vec<String>) = accels
+            .into_iter()
+            .partition(|accel| gtk4::accelerator_parse(accel).is_some());
+        if !invalid.is_empty() {
+            warn!("Importing invalid keyboard shortcuts {invalid:?} skipped.");
+        }
+        set_accel_override(&mut overrides, &defaults, &detailed_action, accels);
+    }
+
+    save_accel_overrides(app, &overrides);
+    apply_accels(app);
+    Ok(())
+}
+
+/// The accelerators of the action in the form the `accelerator` property of `ShortcutLabel` expects.
+pub(crate) fn accels_label_string(app: &RnApp, detailed_action: &str) -> String {
+    app.accels_for_action(detailed_action)
+        .iter()
+        .map(|accel| accel.as_str())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
@@ -0,0 +1,251 @@
+// Imports
+use crate::RnAppWindow;
+use adw::prelude::*;
+use gettextrs::gettext;
+use gtk4::{
+    Button, CheckButton, CompositeTemplate, ToggleButton, Widget, glib, glib::clone,
+    subclass::prelude::*,
+};
+use rnote_engine::{Engine, WidgetFlags};
+use std::cell::RefCell;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, CompositeTemplate, Default)]
+    #[template(resource = "/com/github/flxzt/rnote/ui/layerspanel.ui")]
+    pub(crate) struct RnLayersPanel {
+        pub(crate) layer_rows: RefCell<Vec<adw::EntryRow>>,
+
+        #[template_child]
+        pub(crate) layers_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub(crate) selection_move_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) add_layer_button: TemplateChild<Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnLayersPanel {
+        const NAME: &'static str = "RnLayersPanel";
+        type Type = super::RnLayersPanel;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnLayersPanel {
+        fn constructed(&self) {
+            self.parent_constructed();
+        }
+
+        fn dispose(&self) {
+            self.dispose_template();
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for RnLayersPanel {}
+}
+
+glib::wrapper! {
+    pub(crate) struct RnLayersPanel(ObjectSubclass<imp::RnLayersPanel>)
+        @extends Widget,
+        @implements gtk4::Accessible, gtk4::Buildable, gtk4::ConstraintTarget;
+}
+
+impl Default for RnLayersPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RnLayersPanel {
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    pub(crate) fn init(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+
+        imp.add_layer_button.connect_clicked(clone!(
+            #[weak]
+            appwindow,
+            move |_| {
+                modify_engine(&appwindow, |engine| engine.add_layer());
+            }
+        ));
+        imp.selection_move_button.connect_clicked(clone!(
+            #[weak]
+            appwindow,
+            move |_| {
+                modify_engine(&appwindow, |engine| {
+                    engine.selection_move_to_layer(engine.active_layer())
+                });
+            }
+        ));
+    }
+
+    /// Rebuild the rows of the layers of the document in the active tab.
+    pub(crate) fn refresh_ui(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+
+        for row in imp.layer_rows.take() {
+            imp.layers_group.remove(&row);
+        }
+        let Some(canvas) = appwindow.active_tab_canvas() else {
+            return;
+        };
+        let (layers, active_layer) = {
+            let engine = canvas.engine_ref();
+            (engine.layers().to_vec(), engine.active_layer())
+        };
+        let n_layers = layers.len() as u32;
+        let mut active_group: Option<CheckButton> = None;
+
+        // The topmost layer is listed first
+        for (index, layer) in layers.into_iter().enumerate().rev() {
+            let index = index as u32;
+            let row = adw::EntryRow::builder()
+                .title(format!("{} {}", gettext("Layer"), index + 1))
+                .text(layer.name.as_str())
+                .show_apply_button(true)
+                .build();
+            let active_button = CheckButton::builder()
+                .tooltip_text(gettext("Active Layer"))
+                .valign(gtk4::Align::Center)
+                .build();
+            match &active_group {
+                Some(group) => active_button.set_group(Some(group)),
+                None => active_group = Some(active_button.clone()),
+            }
+            active_button.set_active(index == active_layer);
+            let visible_button = ToggleButton::builder()
+                .icon_name(if layer.visible {
+                    "layer-visible-symbolic"
+                } else {
+                    "layer-hidden-symbolic"
+                })
+                .active(layer.visible)
+                .tooltip_text(gettext("Show or Hide Layer"))
+                .valign(gtk4::Align::Center)
+                .css_classes(["flat"])
+                .build();
+            let locked_button = ToggleButton::builder()
+                .icon_name(if layer.locked {
+                    "layer-locked-symbolic"
+                } else {
+                    "layer-unlocked-symbolic"
+                })
+                .active(layer.locked)
+                .tooltip_text(gettext("Lock or Unlock Layer"))
+                .valign(gtk4::Align::Center)
+                .css_classes(["flat"])
+                .build();
+            let up_button = Button::builder()
+                .icon_name("dir-up-symbolic")
+                .tooltip_text(gettext("Move Layer Up"))
+                .sensitive(index + 1 < n_layers)
+                .valign(gtk4::Align::Center)
+                .css_classes(["flat"])
+                .build();
+            let down_button = Button::builder()
+                .icon_name("dir-down-symbolic")
+                .tooltip_text(gettext("Move Layer Down"))
+                .sensitive(index > 0)
+                .valign(gtk4::Align::Center)
+                .css_classes(["flat"])
+                .build();
+            let remove_button = Button::builder()
+                .icon_name("user-trash-symbolic")
+                .tooltip_text(gettext("Remove Layer and Its Strokes"))
+                .sensitive(n_layers > 1)
+                .valign(gtk4::Align::Center)
+                .css_classes(["flat"])
+                .build();
+            row.add_prefix(&active_button);
+            row.add_prefix(&visible_button);
+            row.add_prefix(&locked_button);
+            row.add_suffix(&up_button);
+            row.add_suffix(&down_button);
+            row.add_suffix(&remove_button);
+
+            active_button.connect_toggled(clone!(
+                #[weak]
+                appwindow,
+                move |active_button| {
+                    if active_button.is_active() {
+                        modify_engine(&appwindow, |engine| engine.set_active_layer(index));
+                    }
+                }
+            ));
+            visible_button.connect_toggled(clone!(
+                #[weak]
+                appwindow,
+                move |visible_button| {
+                    let visible = visible_button.is_active();
+                    modify_engine(&appwindow, |engine| {
+                        engine.set_layer_visible(index, visible)
+                    });
+                }
+            ));
+            locked_button.connect_toggled(clone!(
+                #[weak]
+                appwindow,
+                move |locked_button| {
+                    let locked = locked_button.is_active();
+                    modify_engine(&appwindow, |engine| engine.set_layer_locked(index, locked));
+                }
+            ));
+            row.connect_apply(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let name = row.text().trim().to_string();
+                    modify_engine(&appwindow, |engine| engine.rename_layer(index, name));
+                }
+            ));
+            up_button.connect_clicked(clone!(
+                #[weak]
+                appwindow,
+                move |_| {
+                    modify_engine(&appwindow, |engine| engine.move_layer(index, true));
+                }
+            ));
+            down_button.connect_clicked(clone!(
+                #[weak]
+                appwindow,
+                move |_| {
+                    modify_engine(&appwindow, |engine| engine.move_layer(index, false));
+                }
+            ));
+            remove_button.connect_clicked(clone!(
+                #[weak]
+                appwindow,
+                move |_| {
+                    modify_engine(&appwindow, |engine| engine.remove_layer(index));
+                }
+            ));
+
+            imp.layers_group.add(&row);
+            imp.layer_rows.borrow_mut().push(row);
+        }
+    }
+}
+
+/// Modify the engine of the active tab and handle the returned widget flags.
+fn modify_engine(appwindow: &RnAppWindow, modify: impl FnOnce(&mut Engine) -> WidgetFlags) {
+    let Some(canvas) = appwindow.active_tab_canvas() else {
+        return;
+    };
+    let widget_flags = modify(&mut canvas.engine_mut());
+    appwindow.handle_widget_flags(widget_flags, &canvas);
+}
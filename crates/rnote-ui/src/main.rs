@@ -23,11 +23,16 @@ pub(crate) mod filetype;
 pub(crate) mod globals;
 pub(crate) mod groupediconpicker;
 pub(crate) mod iconpicker;
+pub(crate) mod keyboardshortcuts;
+pub(crate) mod layerspanel;
 pub(crate) mod mainheader;
 pub(crate) mod overlays;
 pub(crate) mod penpicker;
+pub(crate) mod penpresetbar;
 pub(crate) mod penssidebar;
+pub(crate) mod radialmenu;
 pub(crate) mod settingspanel;
+pub(crate) mod settingsprofiles;
 pub(crate) mod sidebar;
 pub(crate) mod strokecontentpaintable;
 pub(crate) mod strokecontentpreview;
@@ -48,10 +53,13 @@ pub(crate) use contextmenu::RnContextMenu;
 pub(crate) use filetype::FileType;
 pub(crate) use groupediconpicker::RnGroupedIconPicker;
 pub(crate) use iconpicker::RnIconPicker;
+pub(crate) use layerspanel::RnLayersPanel;
 pub(crate) use mainheader::RnMainHeader;
 pub(crate) use overlays::RnOverlays;
 pub(crate) use penpicker::RnPenPicker;
+pub(crate) use penpresetbar::RnPenPresetBar;
 pub(crate) use penssidebar::RnPensSideBar;
+pub(crate) use radialmenu::RnRadialMenu;
 pub(crate) use settingspanel::RnSettingsPanel;
 pub(crate) use sidebar::RnSidebar;
 pub(crate) use strokecontentpaintable::StrokeContentPaintable;
@@ -1,11 +1,13 @@
 // Imports
 use crate::RnPensSideBar;
+use crate::boxed::StrokeContentBoxed;
 use crate::canvaswrapper::RnCanvasWrapper;
-use crate::{RnAppWindow, RnColorPicker, RnPenPicker, dialogs};
+use crate::{RnAppWindow, RnColorPicker, RnPenPicker, RnPenPresetBar, RnRadialMenu, dialogs};
 use core::time::Duration;
+use gettextrs::gettext;
 use gtk4::{
-    CompositeTemplate, Overlay, ProgressBar, ScrolledWindow, Widget, gio, glib, glib::clone,
-    prelude::*, subclass::prelude::*,
+    CompositeTemplate, DragSource, Image, Label, Overlay, ProgressBar, ScrolledWindow, Widget, gdk,
+    gio, glib, glib::clone, prelude::*, subclass::prelude::*,
 };
 use rnote_engine::ext::GdkRGBAExt;
 use rnote_engine::pens::PenStyle;
@@ -32,8 +34,28 @@ mod imp {
         #[template_child]
         pub(crate) colorpicker: TemplateChild<RnColorPicker>,
         #[template_child]
+        pub(crate) penpresetbar: TemplateChild<RnPenPresetBar>,
+        #[template_child]
+        pub(crate) radialmenu: TemplateChild<RnRadialMenu>,
+        #[template_child]
+        pub(crate) presentation_timer_label: TemplateChild<Label>,
+        #[template_child]
         pub(crate) tabview: TemplateChild<adw::TabView>,
         #[template_child]
+        pub(crate) main_pane_header: TemplateChild<gtk4::Box>,
+        #[template_child]
+        pub(crate) main_pane_drag_handle: TemplateChild<Image>,
+        #[template_child]
+        pub(crate) main_pane_title: TemplateChild<Label>,
+        #[template_child]
+        pub(crate) split_pane_box: TemplateChild<gtk4::Box>,
+        #[template_child]
+        pub(crate) split_pane_drag_handle: TemplateChild<Image>,
+        #[template_child]
+        pub(crate) split_pane_title: TemplateChild<Label>,
+        #[template_child]
+        pub(crate) split_tabview: TemplateChild<adw::TabView>,
+        #[template_child]
         pub(crate) sidebar_box: TemplateChild<gtk4::Box>,
         #[template_child]
         pub(crate) sidebar_scroller: TemplateChild<ScrolledWindow>,
@@ -80,6 +102,8 @@ mod imp {
                 .set_measure_overlay(&*self.penpicker, true);
             self.toolbar_overlay
                 .set_measure_overlay(&*self.sidebar_box, true);
+            self.toolbar_overlay
+                .set_measure_overlay(&*self.penpresetbar, true);
         }
     }
 }
@@ -112,6 +136,18 @@ impl RnOverlays {
         self.imp().colorpicker.get()
     }
 
+    pub(crate) fn penpresetbar(&self) -> RnPenPresetBar {
+        self.imp().penpresetbar.get()
+    }
+
+    pub(crate) fn radialmenu(&self) -> RnRadialMenu {
+        self.imp().radialmenu.get()
+    }
+
+    pub(crate) fn presentation_timer_label(&self) -> Label {
+        self.imp().presentation_timer_label.get()
+    }
+
     pub(crate) fn toast_overlay(&self) -> adw::ToastOverlay {
         self.imp().toast_overlay.get()
     }
@@ -124,6 +160,19 @@ impl RnOverlays {
         self.imp().tabview.get()
     }
 
+    /// The tab view of the split pane, holding at most one page.
+    pub(crate) fn split_tabview(&self) -> adw::TabView {
+        self.imp().split_tabview.get()
+    }
+
+    /// The canvas wrapper shown in the split pane, when the split view is open.
+    pub(crate) fn split_wrapper(&self) -> Option<RnCanvasWrapper> {
+        self.imp()
+            .split_tabview
+            .selected_page()
+            .map(|page| page.child().downcast::<RnCanvasWrapper>().unwrap())
+    }
+
     pub(crate) fn sidebar_box(&self) -> gtk4::Box {
         self.imp().sidebar_box.get()
     }
@@ -141,6 +190,7 @@ impl RnOverlays {
         imp.colorpicker.get().init(appwindow);
         imp.penssidebar.get().init(appwindow);
         imp.penpicker.get().init(appwindow);
+        imp.penpresetbar.get().init(appwindow);
         imp.penssidebar.get().brush_page().init(appwindow);
         imp.penssidebar.get().shaper_page().init(appwindow);
         imp.penssidebar.get().typewriter_page().init(appwindow);
@@ -150,6 +200,7 @@ impl RnOverlays {
 
         self.setup_colorpicker(appwindow);
         self.setup_tabview(appwindow);
+        self.setup_split_view(appwindow);
     }
 
     fn setup_colorpicker(&self, appwindow: &RnAppWindow) {
@@ -332,6 +383,141 @@ impl RnOverlays {
         ));
     }
 
+    fn setup_split_view(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+
+        imp.split_tabview.connect_page_attached(clone!(
+            #[weak(rename_to=overlays)]
+            self,
+            #[weak]
+            appwindow,
+            move |_, page, _| {
+                let canvaswrapper = page.child().downcast::<RnCanvasWrapper>().unwrap();
+                canvaswrapper.init_reconnect(&appwindow);
+                canvaswrapper.connect_to_tab_page(page);
+                let widget_flags = canvaswrapper.canvas().engine_mut().set_active(true);
+                appwindow.handle_widget_flags(widget_flags, &canvaswrapper.canvas());
+                overlays.update_split_view(&appwindow);
+            }
+        ));
+
+        imp.split_tabview.connect_page_detached(clone!(
+            #[weak(rename_to=overlays)]
+            self,
+            #[weak]
+            appwindow,
+            move |_, page, _| {
+                let canvaswrapper = page.child().downcast::<RnCanvasWrapper>().unwrap();
+                let _ = canvaswrapper.canvas().engine_mut().set_active(false);
+                canvaswrapper.disconnect_connections();
+                overlays.update_split_view(&appwindow);
+            }
+        ));
+
+        // The titles of the documents shown side by side
+        imp.tabview
+            .property_expression("selected-page")
+            .chain_property::<adw::TabPage>("title")
+            .bind(&*imp.main_pane_title, "label", None::<&glib::Object>);
+        imp.split_tabview
+            .property_expression("selected-page")
+            .chain_property::<adw::TabPage>("title")
+            .bind(&*imp.split_pane_title, "label", None::<&glib::Object>);
+
+        imp.main_pane_drag_handle
+            .add_controller(self.create_selection_drag_source(appwindow, false));
+        imp.split_pane_drag_handle
+            .add_controller(self.create_selection_drag_source(appwindow, true));
+    }
+
+    /// Creates a drag source that drags the selection of the canvas in the main pane or in the split pane.
+    fn create_selection_drag_source(
+        &self,
+        appwindow: &RnAppWindow,
+        split_pane: bool,
+    ) -> DragSource {
+        let drag_source = DragSource::builder().actions(gdk::DragAction::COPY).build();
+
+        drag_source.connect_prepare(clone!(
+            #[weak(rename_to=overlays)]
+            self,
+            #[weak]
+            appwindow,
+            #[upgrade_or]
+            None,
+            move |_, _, _| {
+                let canvas = if split_pane {
+                    overlays.split_wrapper()?.canvas()
+                } else {
+                    appwindow.active_tab_canvas()?
+                };
+                let Some(stroke_content) = canvas.engine_ref().selection_stroke_content() else {
+                    overlays.dispatch_toast_text(
+                        &gettext("Select strokes to drag them into the other document"),
+                        TEXT_TOAST_TIMEOUT_DEFAULT,
+                    );
+                    return None;
+                };
+                match serde_json::to_string(&stroke_content) {
+                    Ok(json_string) => Some(gdk::ContentProvider::for_value(
+                        &StrokeContentBoxed::new(json_string).to_value(),
+                    )),
+                    Err(e) => {
+                        error!("Serializing dragged selection failed, Err: {e:?}");
+                        None
+                    }
+                }
+            }
+        ));
+
+        drag_source
+    }
+
+    /// Shows the active tab in the split pane, next to the other tabs.
+    ///
+    /// A tab that is already shown in the split pane is moved back to the other tabs.
+    pub(crate) fn split_view_open(&self) {
+        let imp = self.imp();
+        let Some(page) = imp.tabview.selected_page() else {
+            return;
+        };
+        if imp.tabview.n_pages() <= 1 {
+            self.dispatch_toast_text(
+                &gettext("Open another tab to show two documents side by side"),
+                TEXT_TOAST_TIMEOUT_DEFAULT,
+            );
+            return;
+        }
+
+        self.split_view_close();
+        imp.tabview.transfer_page(&page, &imp.split_tabview, 0);
+    }
+
+    /// Moves the tab of the split pane back to the other tabs and selects it.
+    pub(crate) fn split_view_close(&self) {
+        let imp = self.imp();
+        let Some(page) = imp.split_tabview.selected_page() else {
+            return;
+        };
+        imp.split_tabview
+            .transfer_page(&page, &imp.tabview, imp.tabview.n_pages());
+        imp.tabview.set_selected_page(&page);
+    }
+
+    fn update_split_view(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        let split_view_open = imp.split_tabview.n_pages() > 0;
+
+        imp.split_pane_box.set_visible(split_view_open);
+        imp.main_pane_header.set_visible(split_view_open);
+        if let Some(action) = appwindow
+            .lookup_action("split-view-close")
+            .and_then(|action| action.downcast::<gio::SimpleAction>().ok())
+        {
+            action.set_enabled(split_view_open);
+        }
+    }
+
     pub(crate) fn progressbar_start_pulsing(&self) {
         const PULSE_INTERVAL: std::time::Duration = std::time::Duration::from_millis(300);
 
@@ -386,6 +572,33 @@ impl RnOverlays {
         }
     }
 
+    /// Show the fraction of a progress that is reported in steps, e.g. the rendering progress.
+    ///
+    /// Is ignored while the progressbar is pulsing for another operation.
+    pub(crate) fn progressbar_report(&self, fraction: f64) {
+        const FINISH_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(300);
+
+        if self.imp().progresspulses_active.get() > 0 {
+            return;
+        }
+        self.progressbar().set_fraction(fraction.clamp(0., 1.));
+
+        if fraction >= 1. {
+            glib::source::timeout_add_local_once(
+                FINISH_TIMEOUT,
+                clone!(
+                    #[weak(rename_to=appwindow)]
+                    self,
+                    move || {
+                        if appwindow.imp().progresspulses_active.get() == 0 {
+                            appwindow.progressbar().set_fraction(0.);
+                        }
+                    }
+                ),
+            );
+        }
+    }
+
     #[allow(unused)]
     pub(crate) fn progressbar_abort(&self) {
         self.imp()
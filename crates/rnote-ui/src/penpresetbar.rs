@@ -0,0 +1,178 @@
+// Imports
+use crate::RnAppWindow;
+use gettextrs::gettext;
+use gtk4::{
+    Align, Button, CompositeTemplate, DrawingArea, GestureClick, Image, PopoverMenu, TemplateChild,
+    Widget, gdk, gio, glib, glib::clone, prelude::*, subclass::prelude::*,
+};
+use rnote_engine::ext::GdkRGBAExt;
+use rnote_engine::pens::PenPreset;
+use tracing::error;
+
+mod imp {
+    use super::*;
+
+    #[derive(Default, Debug, CompositeTemplate)]
+    #[template(resource = "/com/github/flxzt/rnote/ui/penpresetbar.ui")]
+    pub(crate) struct RnPenPresetBar {
+        #[template_child]
+        pub(crate) presets_box: TemplateChild<gtk4::Box>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnPenPresetBar {
+        const NAME: &'static str = "RnPenPresetBar";
+        type Type = super::RnPenPresetBar;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnPenPresetBar {
+        fn dispose(&self) {
+            self.dispose_template();
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for RnPenPresetBar {}
+}
+
+glib::wrapper! {
+    pub(crate) struct RnPenPresetBar(ObjectSubclass<imp::RnPenPresetBar>)
+        @extends Widget,
+        @implements gtk4::Accessible, gtk4::Buildable, gtk4::ConstraintTarget;
+}
+
+impl Default for RnPenPresetBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RnPenPresetBar {
+    /// The number of presets that can be applied with the number key shortcuts.
+    pub(crate) const N_SHORTCUT_PRESETS: usize = 9;
+
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    pub(crate) fn init(&self, appwindow: &RnAppWindow) {
+        self.refresh_ui(appwindow);
+    }
+
+    /// Rebuild the preset buttons from the presets in the engine config.
+    pub(crate) fn refresh_ui(&self, appwindow: &RnAppWindow) {
+        let presets_box = self.imp().presets_box.get();
+        let pen_presets = appwindow.engine_config().read().pen_presets.clone();
+
+        while let Some(child) = presets_box.first_child() {
+            presets_box.remove(&child);
+        }
+        for (i, preset) in pen_presets.iter().enumerate() {
+            presets_box.append(&create_preset_button(i, preset));
+        }
+        presets_box.set_visible(!pen_presets.is_empty());
+    }
+}
+
+fn create_preset_button(index: usize, preset: &PenPreset) -> Button {
+    let content = gtk4::Box::builder()
+        .orientation(gtk4::Orientation::Vertical)
+        .spacing(3)
+        .build();
+    content.append(&Image::from_icon_name(&preset.pen_style.icon_name()));
+    content.append(&create_preset_indicator(preset));
+
+    let tooltip = if index < RnPenPresetBar::N_SHORTCUT_PRESETS {
+        let accel_label = gtk4::accelerator_get_label(
+            gdk::Key::from_name((index + 1).to_string()).unwrap_or(gdk::Key::VoidSymbol),
+            gdk::ModifierType::ALT_MASK,
+        );
+        format!("{} ({accel_label})", preset.name)
+    } else {
+        preset.name.clone()
+    };
+    let button = Button::builder()
+        .child(&content)
+        .tooltip_text(tooltip)
+        .action_name("win.pen-preset-apply")
+        .action_target(&(index as u32).to_variant())
+        .build();
+    button.add_css_class("flat");
+
+    // Remove the preset through a context menu
+    let menu = gio::Menu::new();
+    let remove_item = gio::MenuItem::new(Some(&gettext("Remove")), None);
+    remove_item.set_action_and_target_value(
+        Some("win.pen-preset-remove"),
+        Some(&(index as u32).to_variant()),
+    );
+    menu.append_item(&remove_item);
+    let popover = PopoverMenu::from_model(Some(&menu));
+    popover.set_parent(&button);
+    button.connect_destroy(clone!(
+        #[weak]
+        popover,
+        move |_| popover.unparent()
+    ));
+
+    let secondary_click = GestureClick::builder()
+        .button(gdk::BUTTON_SECONDARY)
+        .build();
+    secondary_click.connect_pressed(clone!(
+        #[weak]
+        popover,
+        move |_, _, _, _| popover.popup()
+    ));
+    button.add_controller(secondary_click);
+
+    button
+}
+
+/// A dot in the color of the preset, sized by its stroke width.
+fn create_preset_indicator(preset: &PenPreset) -> DrawingArea {
+    const SIZE: i32 = 12;
+    let stroke_width = preset.stroke_width;
+    let color = preset.stroke_color.map(gdk::RGBA::from_compose_color);
+
+    let indicator = DrawingArea::builder()
+        .content_width(SIZE)
+        .content_height(SIZE)
+        .halign(Align::Center)
+        .build();
+    indicator.set_draw_func(move |area, cx, width, height| {
+        let max_radius = f64::from(SIZE) * 0.5;
+        // asymptotic to the max radius, like the stroke width previews
+        let radius = (max_radius * stroke_width) / (max_radius + stroke_width);
+        let color = color.unwrap_or_else(|| area.color());
+
+        cx.set_source_rgba(
+            color.red() as f64,
+            color.green() as f64,
+            color.blue() as f64,
+            color.alpha() as f64,
+        );
+        cx.arc(
+            f64::from(width) * 0.5,
+            f64::from(height) * 0.5,
+            radius.max(1.0),
+            0.0,
+            std::f64::consts::TAU,
+        );
+        if let Err(e) = cx.fill() {
+            error!("Painting pen preset indicator failed, fill returned Err: {e:?}");
+        }
+    });
+
+    indicator
+}
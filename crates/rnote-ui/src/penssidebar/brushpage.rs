@@ -1,16 +1,26 @@
 // Imports
-use crate::{RnAppWindow, RnStrokeWidthPicker};
+use crate::colorpicker::RnColorSetter;
+use crate::{RnAppWindow, RnColorPicker, RnStrokeWidthPicker, dialogs};
 use adw::prelude::*;
+use gettextrs::gettext;
 use gtk4::{
-    Button, CompositeTemplate, ListBox, MenuButton, Popover, Widget, glib, glib::clone,
-    subclass::prelude::*,
+    Button, ColorDialogButton, CompositeTemplate, ListBox, MenuButton, Popover, Widget, gdk, gio,
+    glib, glib::clone, subclass::prelude::*,
 };
 use num_traits::cast::ToPrimitive;
+use rnote_compose::Color;
 use rnote_compose::builders::PenPathBuilderType;
 use rnote_compose::style::PressureCurve;
+use rnote_compose::style::smooth::{LineStyle, PenTip};
 use rnote_compose::style::textured::{TexturedDotsDistribution, TexturedOptions};
+use rnote_engine::ext::GdkRGBAExt;
+use rnote_engine::pens::PenStyle;
 use rnote_engine::pens::pensconfig::BrushConfig;
 use rnote_engine::pens::pensconfig::brushconfig::{BrushStyle, SolidOptions};
+use rnote_engine::strokes::stampstroke::Stamp;
+use rnote_engine::strokes::{BlendMode, SprayOptions};
+use std::cell::RefCell;
+use tracing::error;
 
 mod imp {
     use super::*;
@@ -33,6 +43,10 @@ mod imp {
         #[template_child]
         pub(crate) brushstyle_textured_row: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub(crate) brushstyle_airbrush_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(crate) brushstyle_stamp_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
         pub(crate) brushconfig_menubutton: TemplateChild<MenuButton>,
         #[template_child]
         pub(crate) brushconfig_popover: TemplateChild<Popover>,
@@ -49,11 +63,53 @@ mod imp {
         #[template_child]
         pub(crate) solidstyle_pressure_curves_row: TemplateChild<adw::ComboRow>,
         #[template_child]
+        pub(crate) solidstyle_line_style_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) marker_color_button: TemplateChild<ColorDialogButton>,
+        #[template_child]
+        pub(crate) marker_recent_colors_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(crate) marker_recent_colors_box: TemplateChild<gtk4::Box>,
+        #[template_child]
+        pub(crate) marker_tip_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) marker_blend_mode_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) marker_line_style_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) marker_pressure_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) marker_pressure_min_width_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) marker_hold_to_straighten_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) shape_recognition_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) gradient_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) gradient_end_color_button: TemplateChild<ColorDialogButton>,
+        #[template_child]
         pub(crate) texturedstyle_density_row: TemplateChild<adw::SpinRow>,
         #[template_child]
         pub(crate) texturedstyle_distribution_row: TemplateChild<adw::ComboRow>,
         #[template_child]
+        pub(crate) airbrush_rate_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) airbrush_density_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) airbrush_falloff_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) stamp_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) stamp_list: TemplateChild<gtk4::StringList>,
+        #[template_child]
+        pub(crate) stamp_add_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) stamp_remove_button: TemplateChild<Button>,
+        #[template_child]
         pub(crate) stroke_width_picker: TemplateChild<RnStrokeWidthPicker>,
+
+        pub(crate) marker_recent_colors: RefCell<Vec<Color>>,
     }
 
     #[glib::object_subclass]
@@ -130,6 +186,14 @@ impl RnBrushPage {
                 .imp()
                 .brushstyle_listbox
                 .select_row(Some(&*self.imp().brushstyle_textured_row)),
+            BrushStyle::Airbrush => self
+                .imp()
+                .brushstyle_listbox
+                .select_row(Some(&*self.imp().brushstyle_airbrush_row)),
+            BrushStyle::Stamp => self
+                .imp()
+                .brushstyle_listbox
+                .select_row(Some(&*self.imp().brushstyle_stamp_row)),
         }
     }
 
@@ -170,6 +234,94 @@ impl RnBrushPage {
             .set_selected(position);
     }
 
+    pub(crate) fn gradient_end_color(&self) -> Option<Color> {
+        let imp = self.imp();
+        imp.gradient_row
+            .is_active()
+            .then(|| imp.gradient_end_color_button.rgba().into_compose_color())
+    }
+
+    pub(crate) fn set_gradient_end_color(&self, end_color: Option<Color>) {
+        let imp = self.imp();
+        if let Some(end_color) = end_color {
+            imp.gradient_end_color_button
+                .set_rgba(&gdk::RGBA::from_compose_color(end_color));
+        }
+        imp.gradient_row.set_active(end_color.is_some());
+    }
+
+    pub(crate) fn set_marker_color(&self, color: Option<Color>) {
+        self.imp()
+            .marker_color_button
+            .set_rgba(&gdk::RGBA::from_compose_color(
+                color.unwrap_or(Color::TRANSPARENT),
+            ));
+    }
+
+    /// Add the color to the front of the recent marker colors, removing duplicates.
+    fn push_marker_recent_color(&self, color: Color) {
+        {
+            let mut recent_colors = self.imp().marker_recent_colors.borrow_mut();
+            recent_colors.retain(|c| !c.approx_eq_f32(color));
+            recent_colors.insert(0, color);
+            recent_colors.truncate(RnColorPicker::RECENT_COLORS_MAX);
+        }
+        self.refresh_marker_recent_colors();
+    }
+
+    /// Rebuild the setters of the recent marker colors.
+    fn refresh_marker_recent_colors(&self) {
+        let imp = self.imp();
+        let recent_box = imp.marker_recent_colors_box.get();
+
+        while let Some(child) = recent_box.first_child() {
+            recent_box.remove(&child);
+        }
+        let recent_colors = imp.marker_recent_colors.borrow().clone();
+        for color in recent_colors {
+            let setter = RnColorSetter::new();
+            setter.set_color(gdk::RGBA::from_compose_color(color));
+            setter.add_css_class("recent");
+            setter.set_size_request(22, 22);
+            setter.set_valign(gtk4::Align::Center);
+            setter.connect_clicked(clone!(
+                #[weak(rename_to=brushpage)]
+                self,
+                move |setter| {
+                    // The setters only apply the color, they don't stay selected
+                    setter.set_active(false);
+                    brushpage.set_marker_color(Some(color));
+                }
+            ));
+            recent_box.append(&setter);
+        }
+        imp.marker_recent_colors_row
+            .set_visible(!imp.marker_recent_colors.borrow().is_empty());
+    }
+
+    pub(crate) fn load_from_settings(&self, settings: &gio::Settings) {
+        let recent_colors = settings
+            .get::<Vec<(f64, f64, f64, f64)>>("brush-marker-recent-colors")
+            .into_iter()
+            .map(|(r, g, b, a)| Color::new(r, g, b, a))
+            .collect::<Vec<Color>>();
+        self.imp().marker_recent_colors.replace(recent_colors);
+        self.refresh_marker_recent_colors();
+    }
+
+    pub(crate) fn save_to_settings(&self, settings: &gio::Settings) {
+        let recent_colors = self
+            .imp()
+            .marker_recent_colors
+            .borrow()
+            .iter()
+            .map(|c| (c.r, c.g, c.b, c.a))
+            .collect::<Vec<(f64, f64, f64, f64)>>();
+        if let Err(e) = settings.set("brush-marker-recent-colors", recent_colors.to_variant()) {
+            error!("Saving `brush-marker-recent-colors` to settings failed, Err: {e:?}");
+        }
+    }
+
     pub(crate) fn texturedstyle_dots_distribution(&self) -> TexturedDotsDistribution {
         TexturedDotsDistribution::try_from(
             self.imp().texturedstyle_distribution_row.get().selected(),
@@ -193,6 +345,50 @@ impl RnBrushPage {
         self.imp().stroke_width_picker.get()
     }
 
+    /// Rebuild the stamp selection from the stamp library.
+    pub(crate) fn refresh_stamps(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        let (names, stamp_index) = {
+            let engine_config = appwindow.engine_config().read();
+            let names = engine_config
+                .stamp_library
+                .stamps()
+                .iter()
+                .map(stamp_display_name)
+                .collect::<Vec<String>>();
+            (
+                names,
+                engine_config
+                    .pens_config
+                    .brush_config
+                    .stamp_options
+                    .stamp_index,
+            )
+        };
+        let names = names.iter().map(String::as_str).collect::<Vec<&str>>();
+
+        imp.stamp_list.splice(0, imp.stamp_list.n_items(), &names);
+        // Changing the list might have changed the selection, so it is set after
+        imp.stamp_row.set_selected(stamp_index as u32);
+        self.update_stamp_remove_button(appwindow);
+    }
+
+    /// Only custom stamps can be removed.
+    fn update_stamp_remove_button(&self, appwindow: &RnAppWindow) {
+        let engine_config = appwindow.engine_config().read();
+        let removable = engine_config
+            .stamp_library
+            .get(
+                engine_config
+                    .pens_config
+                    .brush_config
+                    .stamp_options
+                    .stamp_index,
+            )
+            .is_some_and(|stamp| matches!(stamp, Stamp::Svg { .. }));
+        self.imp().stamp_remove_button.set_sensitive(removable);
+    }
+
     pub(crate) fn init(&self, appwindow: &RnAppWindow) {
         let imp = self.imp();
         let brushstyle_popover = imp.brushstyle_popover.get();
@@ -213,6 +409,23 @@ impl RnBrushPage {
                 brushconfig_popover.popdown();
             }
         ));
+        // The marker color might have been changed through the colorpicker in the meantime
+        brushconfig_popover.connect_show(clone!(
+            #[weak(rename_to=brushpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                let marker_color = appwindow
+                    .engine_config()
+                    .read()
+                    .pens_config
+                    .brush_config
+                    .marker_options
+                    .stroke_color;
+                brushpage.set_marker_color(marker_color);
+            }
+        ));
 
         // Stroke width
         imp.stroke_width_picker
@@ -264,6 +477,24 @@ impl RnBrushPage {
                                 .textured_options
                                 .stroke_width = stroke_width;
                         }
+                        BrushStyle::Airbrush => {
+                            appwindow
+                                .engine_config()
+                                .write()
+                                .pens_config
+                                .brush_config
+                                .airbrush_options
+                                .stroke_width = stroke_width;
+                        }
+                        BrushStyle::Stamp => {
+                            appwindow
+                                .engine_config()
+                                .write()
+                                .pens_config
+                                .brush_config
+                                .stamp_options
+                                .stroke_width = stroke_width;
+                        }
                     }
                 }
             ),
@@ -340,6 +571,40 @@ impl RnBrushPage {
                             .brushstyle_menubutton
                             .set_icon_name("pen-brush-style-textured-symbolic");
                     }
+                    BrushStyle::Airbrush => {
+                        let stroke_width = appwindow
+                            .engine_config()
+                            .read()
+                            .pens_config
+                            .brush_config
+                            .airbrush_options
+                            .stroke_width;
+                        brushpage
+                            .imp()
+                            .stroke_width_picker
+                            .set_stroke_width(stroke_width);
+                        brushpage
+                            .imp()
+                            .brushstyle_menubutton
+                            .set_icon_name("pen-brush-style-textured-symbolic");
+                    }
+                    BrushStyle::Stamp => {
+                        let stroke_width = appwindow
+                            .engine_config()
+                            .read()
+                            .pens_config
+                            .brush_config
+                            .stamp_options
+                            .stroke_width;
+                        brushpage
+                            .imp()
+                            .stroke_width_picker
+                            .set_stroke_width(stroke_width);
+                        brushpage
+                            .imp()
+                            .brushstyle_menubutton
+                            .set_icon_name("shapebuilder-star-symbolic");
+                    }
                 }
             }
         ));
@@ -382,6 +647,218 @@ impl RnBrushPage {
                         .pressure_curve = brushpage.solidstyle_pressure_curve();
                 }
             ));
+        // Line style
+        imp.solidstyle_line_style_row
+            .connect_selected_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Ok(line_style) = LineStyle::try_from(row.selected()) else {
+                        return;
+                    };
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .pens_config
+                        .brush_config
+                        .solid_options
+                        .update_line_style(line_style);
+                }
+            ));
+
+        // Marker color
+        self.refresh_marker_recent_colors();
+        imp.marker_color_button.connect_rgba_notify(clone!(
+            #[weak(rename_to=brushpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |button| {
+                let color = button.rgba().into_compose_color();
+                let marker_options_color = appwindow
+                    .engine_config()
+                    .read()
+                    .pens_config
+                    .brush_config
+                    .marker_options
+                    .stroke_color;
+                // Skip when only the button was updated to the current config
+                if marker_options_color.is_some_and(|c| c.approx_eq_f32(color)) {
+                    return;
+                }
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .brush_config
+                    .marker_options
+                    .stroke_color = Some(color);
+                brushpage.push_marker_recent_color(color);
+
+                // Preview the new color in the colorpicker, when the marker is the current pen
+                let marker_is_current = appwindow.active_tab_canvas().is_some_and(|canvas| {
+                    canvas.engine_ref().current_pen_style_w_override() == PenStyle::Brush
+                }) && appwindow
+                    .engine_config()
+                    .read()
+                    .pens_config
+                    .brush_config
+                    .style
+                    == BrushStyle::Marker;
+                if marker_is_current {
+                    appwindow
+                        .overlays()
+                        .colorpicker()
+                        .set_stroke_color(gdk::RGBA::from_compose_color(color));
+                }
+            }
+        ));
+
+        // Marker tip
+        imp.marker_tip_row.connect_selected_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Ok(pen_tip) = PenTip::try_from(row.selected()) else {
+                    return;
+                };
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .brush_config
+                    .marker_options
+                    .pen_tip = pen_tip;
+            }
+        ));
+
+        // Marker blend mode
+        imp.marker_blend_mode_row.connect_selected_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Ok(blend_mode) = BlendMode::try_from(row.selected()) else {
+                    return;
+                };
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .brush_config
+                    .marker_blend_mode = blend_mode;
+            }
+        ));
+
+        // Marker line style
+        imp.marker_line_style_row.connect_selected_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Ok(line_style) = LineStyle::try_from(row.selected()) else {
+                    return;
+                };
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .brush_config
+                    .marker_options
+                    .update_line_style(line_style);
+            }
+        ));
+
+        // Marker pressure
+        imp.marker_pressure_row.connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .brush_config
+                    .marker_options
+                    .pressure_curve = if row.is_active() {
+                    PressureCurve::Linear
+                } else {
+                    PressureCurve::Const
+                };
+            }
+        ));
+        imp.marker_pressure_min_width_row.connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .brush_config
+                    .marker_options
+                    .pressure_width_min_ratio = row.value();
+            }
+        ));
+        imp.marker_hold_to_straighten_row
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .pens_config
+                        .brush_config
+                        .marker_hold_to_straighten = row.is_active();
+                }
+            ));
+
+        // Shape recognition
+        imp.shape_recognition_row.connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .brush_config
+                    .shape_recognition = row.is_active();
+            }
+        ));
+
+        // Gradient
+        imp.gradient_end_color_button
+            .set_rgba(&gdk::RGBA::from_compose_color(Color::BLUE));
+
+        imp.gradient_row.connect_active_notify(clone!(
+            #[weak(rename_to=brushpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .brush_config
+                    .gradient_end_color = brushpage.gradient_end_color();
+            }
+        ));
+
+        imp.gradient_end_color_button.connect_rgba_notify(clone!(
+            #[weak(rename_to=brushpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .brush_config
+                    .gradient_end_color = brushpage.gradient_end_color();
+            }
+        ));
 
         // Textured style
         // Density
@@ -425,6 +902,120 @@ impl RnBrushPage {
                         .distribution = brushpage.texturedstyle_dots_distribution();
                 }
             ));
+
+        // Airbrush style
+        imp.airbrush_rate_row
+            .set_range(SprayOptions::RATE_MIN, SprayOptions::RATE_MAX);
+        imp.airbrush_density_row
+            .set_range(SprayOptions::DENSITY_MIN, SprayOptions::DENSITY_MAX);
+        imp.airbrush_falloff_row
+            .set_range(SprayOptions::FALLOFF_MIN, SprayOptions::FALLOFF_MAX);
+        // set values after the ranges!
+        imp.airbrush_rate_row
+            .set_value(SprayOptions::default().rate);
+        imp.airbrush_density_row
+            .set_value(SprayOptions::default().density);
+        imp.airbrush_falloff_row
+            .set_value(SprayOptions::default().falloff);
+
+        imp.airbrush_rate_row.connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .brush_config
+                    .airbrush_options
+                    .rate = row.value();
+            }
+        ));
+        imp.airbrush_density_row.connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .brush_config
+                    .airbrush_options
+                    .density = row.value();
+            }
+        ));
+        imp.airbrush_falloff_row.connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .brush_config
+                    .airbrush_options
+                    .falloff = row.value();
+            }
+        ));
+
+        // Stamp style
+        imp.stamp_row.connect_selected_notify(clone!(
+            #[weak(rename_to=brushpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |row| {
+                if row.selected() == gtk4::INVALID_LIST_POSITION {
+                    return;
+                }
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .brush_config
+                    .stamp_options
+                    .stamp_index = row.selected() as usize;
+                brushpage.update_stamp_remove_button(&appwindow);
+            }
+        ));
+        imp.stamp_add_button.connect_clicked(clone!(
+            #[weak]
+            appwindow,
+            move |_| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::import::filedialog_import_stamp(&appwindow).await;
+                    }
+                ));
+            }
+        ));
+        imp.stamp_remove_button.connect_clicked(clone!(
+            #[weak(rename_to=brushpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                {
+                    let mut engine_config = appwindow.engine_config().write();
+                    let stamp_index = engine_config
+                        .pens_config
+                        .brush_config
+                        .stamp_options
+                        .stamp_index;
+                    if engine_config.stamp_library.remove(stamp_index).is_none() {
+                        return;
+                    }
+                    engine_config
+                        .pens_config
+                        .brush_config
+                        .stamp_options
+                        .stamp_index = stamp_index.saturating_sub(1);
+                }
+                brushpage.refresh_stamps(&appwindow);
+            }
+        ));
     }
 
     pub(crate) fn refresh_ui(&self, appwindow: &RnAppWindow) {
@@ -436,10 +1027,35 @@ impl RnBrushPage {
             .brush_config
             .clone();
 
+        self.set_marker_color(brush_config.marker_options.stroke_color);
+        imp.marker_tip_row
+            .set_selected(brush_config.marker_options.pen_tip.to_u32().unwrap());
+        imp.marker_blend_mode_row
+            .set_selected(brush_config.marker_blend_mode.to_u32().unwrap());
+        imp.marker_line_style_row
+            .set_selected(brush_config.marker_options.line_style.to_u32().unwrap());
+        imp.marker_pressure_row
+            .set_active(brush_config.marker_options.pressure_curve != PressureCurve::Const);
+        imp.marker_pressure_min_width_row
+            .set_value(brush_config.marker_options.pressure_width_min_ratio);
+        imp.marker_hold_to_straighten_row
+            .set_active(brush_config.marker_hold_to_straighten);
+        imp.shape_recognition_row
+            .set_active(brush_config.shape_recognition);
         self.set_solidstyle_pressure_curve(brush_config.solid_options.pressure_curve);
+        imp.solidstyle_line_style_row
+            .set_selected(brush_config.solid_options.line_style.to_u32().unwrap());
+        self.set_gradient_end_color(brush_config.gradient_end_color);
         imp.texturedstyle_density_row
             .set_value(brush_config.textured_options.density);
         self.set_texturedstyle_distribution_variant(brush_config.textured_options.distribution);
+        imp.airbrush_rate_row
+            .set_value(brush_config.airbrush_options.rate);
+        imp.airbrush_density_row
+            .set_value(brush_config.airbrush_options.density);
+        imp.airbrush_falloff_row
+            .set_value(brush_config.airbrush_options.falloff);
+        self.refresh_stamps(appwindow);
 
         self.set_brush_style(brush_config.style);
         self.set_buildertype(brush_config.builder_type);
@@ -457,6 +1073,24 @@ impl RnBrushPage {
                 imp.stroke_width_picker
                     .set_stroke_width(brush_config.textured_options.stroke_width);
             }
+            BrushStyle::Airbrush => {
+                imp.stroke_width_picker
+                    .set_stroke_width(brush_config.airbrush_options.stroke_width);
+            }
+            BrushStyle::Stamp => {
+                imp.stroke_width_picker
+                    .set_stroke_width(brush_config.stamp_options.stroke_width);
+            }
         }
     }
 }
+
+/// The name of the stamp shown in the selection. The names of the built-in stamps are translated.
+fn stamp_display_name(stamp: &Stamp) -> String {
+    match stamp {
+        Stamp::Arrow => gettext("Arrow"),
+        Stamp::Star => gettext("Star"),
+        Stamp::Checkmark => gettext("Checkmark"),
+        Stamp::Svg { name, .. } => name.clone(),
+    }
+}
@@ -17,6 +17,8 @@ mod imp {
         #[template_child]
         pub(crate) eraserstyle_split_colliding_strokes_toggle: TemplateChild<ToggleButton>,
         #[template_child]
+        pub(crate) eraserstyle_erase_precisely_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
         pub(crate) stroke_width_picker: TemplateChild<RnStrokeWidthPicker>,
     }
 
@@ -82,6 +84,8 @@ impl RnEraserPage {
             .is_active()
         {
             Some(EraserStyle::SplitCollidingStrokes)
+        } else if self.imp().eraserstyle_erase_precisely_toggle.is_active() {
+            Some(EraserStyle::ErasePrecisely)
         } else {
             None
         }
@@ -98,6 +102,10 @@ impl RnEraserPage {
                 .imp()
                 .eraserstyle_split_colliding_strokes_toggle
                 .set_active(true),
+            EraserStyle::ErasePrecisely => self
+                .imp()
+                .eraserstyle_erase_precisely_toggle
+                .set_active(true),
         }
     }
 
@@ -142,6 +150,23 @@ impl RnEraserPage {
                 }
             ));
 
+        imp.eraserstyle_erase_precisely_toggle
+            .connect_toggled(clone!(
+                #[weak]
+                appwindow,
+                move |toggle| {
+                    if !toggle.is_active() {
+                        return;
+                    }
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .pens_config
+                        .eraser_config
+                        .style = EraserStyle::ErasePrecisely;
+                }
+            ));
+
         // width
         imp.stroke_width_picker.spinbutton().set_digits(0);
         imp.stroke_width_picker
@@ -1,9 +1,14 @@
 // Imports
 use crate::RnAppWindow;
+use adw::prelude::*;
 use gtk4::{
-    CompositeTemplate, ToggleButton, Widget, glib, glib::clone, prelude::*, subclass::prelude::*,
+    Button, CompositeTemplate, MenuButton, Popover, ToggleButton, Widget, glib, glib::clone,
+    subclass::prelude::*,
 };
+use num_traits::ToPrimitive;
+use rnote_engine::pens::pensconfig::SelectorConfig;
 use rnote_engine::pens::pensconfig::selectorconfig::SelectorStyle;
+use rnote_engine::store::stroke_comp::StrokeKindFilter;
 
 mod imp {
     use super::*;
@@ -20,7 +25,27 @@ mod imp {
         #[template_child]
         pub(crate) selectorstyle_intersectingpath_toggle: TemplateChild<ToggleButton>,
         #[template_child]
+        pub(crate) selectorstyle_color_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
         pub(crate) resize_lock_aspectratio_togglebutton: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) resize_warp_togglebutton: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) selectbycolor_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) selectbycolor_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) selectbycolor_popover_close_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) color_tolerance_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) strokefilter_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) strokefilter_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) strokefilter_popover_close_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) stroke_filter_row: TemplateChild<adw::ComboRow>,
     }
 
     #[glib::object_subclass]
@@ -81,6 +106,8 @@ impl RnSelectorPage {
             Some(SelectorStyle::Single)
         } else if self.imp().selectorstyle_intersectingpath_toggle.is_active() {
             Some(SelectorStyle::IntersectingPath)
+        } else if self.imp().selectorstyle_color_toggle.is_active() {
+            Some(SelectorStyle::Color)
         } else {
             None
         }
@@ -96,6 +123,7 @@ impl RnSelectorPage {
                 .imp()
                 .selectorstyle_intersectingpath_toggle
                 .set_active(true),
+            SelectorStyle::Color => self.imp().selectorstyle_color_toggle.set_active(true),
         }
     }
 
@@ -167,6 +195,22 @@ impl RnSelectorPage {
                 }
             ));
 
+        imp.selectorstyle_color_toggle.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |toggle| {
+                if !toggle.is_active() {
+                    return;
+                }
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .selector_config
+                    .style = SelectorStyle::Color;
+            }
+        ));
+
         imp.resize_lock_aspectratio_togglebutton
             .connect_toggled(clone!(
                 #[weak]
@@ -180,6 +224,73 @@ impl RnSelectorPage {
                         .resize_lock_aspectratio = toggle.is_active();
                 }
             ));
+
+        imp.resize_warp_togglebutton.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |toggle| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .selector_config
+                    .resize_warp = toggle.is_active();
+            }
+        ));
+
+        let selectbycolor_popover = imp.selectbycolor_popover.get();
+        imp.selectbycolor_popover_close_button
+            .connect_clicked(clone!(
+                #[weak]
+                selectbycolor_popover,
+                move |_| {
+                    selectbycolor_popover.popdown();
+                }
+            ));
+
+        // The tolerance is displayed in percent
+        imp.color_tolerance_row.set_range(
+            SelectorConfig::COLOR_TOLERANCE_MIN * 100.0,
+            SelectorConfig::COLOR_TOLERANCE_MAX * 100.0,
+        );
+        imp.color_tolerance_row.connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .selector_config
+                    .color_tolerance = row.value() / 100.0;
+            }
+        ));
+
+        let strokefilter_popover = imp.strokefilter_popover.get();
+        imp.strokefilter_popover_close_button
+            .connect_clicked(clone!(
+                #[weak]
+                strokefilter_popover,
+                move |_| {
+                    strokefilter_popover.popdown();
+                }
+            ));
+
+        imp.stroke_filter_row.connect_selected_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Ok(stroke_filter) = StrokeKindFilter::try_from(row.selected()) else {
+                    return;
+                };
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .selector_config
+                    .stroke_filter = stroke_filter;
+            }
+        ));
     }
 
     pub(crate) fn refresh_ui(&self, appwindow: &RnAppWindow) {
@@ -196,5 +307,11 @@ impl RnSelectorPage {
 
         imp.resize_lock_aspectratio_togglebutton
             .set_active(selector_config.resize_lock_aspectratio);
+        imp.resize_warp_togglebutton
+            .set_active(selector_config.resize_warp);
+        imp.color_tolerance_row
+            .set_value(selector_config.color_tolerance * 100.0);
+        imp.stroke_filter_row
+            .set_selected(selector_config.stroke_filter.to_u32().unwrap());
     }
 }
@@ -10,7 +10,9 @@ use gtk4::{
 };
 use num_traits::cast::ToPrimitive;
 use rnote_compose::builders::ShapeBuilderType;
-use rnote_compose::constraints::ConstraintRatio;
+use rnote_compose::constraints::{AngleSnap, ConstraintRatio};
+use rnote_compose::style::LinePattern;
+use rnote_compose::style::rough::RoughOptions;
 use rnote_compose::style::rough::roughoptions::FillStyle;
 use rnote_compose::style::smooth::{LineCap, LineStyle, SmoothOptions};
 use rnote_engine::pens::pensconfig::ShaperConfig;
@@ -54,12 +56,20 @@ mod imp {
         #[template_child]
         pub(crate) smoothstyle_line_style_row: TemplateChild<adw::ComboRow>,
         #[template_child]
+        pub(crate) smoothstyle_line_pattern_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
         pub(crate) roughstyle_group: TemplateChild<adw::PreferencesGroup>,
         #[template_child]
         pub(crate) roughstyle_fillstyle_row: TemplateChild<adw::ComboRow>,
         #[template_child]
         pub(crate) roughstyle_hachure_angle_row: TemplateChild<adw::SpinRow>,
         #[template_child]
+        pub(crate) roughstyle_roughness_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) roughstyle_bowing_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) roughstyle_multi_stroke_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub(crate) constraint_enabled_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub(crate) constraint_one_to_one_row: TemplateChild<adw::SwitchRow>,
@@ -67,6 +77,14 @@ mod imp {
         pub(crate) constraint_three_to_two_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub(crate) constraint_golden_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) constraint_angle_snap_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) constraint_angle_snap_increment_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) constraint_angle_snap_relative_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) constraint_object_snap_row: TemplateChild<adw::SwitchRow>,
 
         #[template_child]
         pub(crate) stroke_width_picker: TemplateChild<RnStrokeWidthPicker>,
@@ -164,6 +182,10 @@ impl RnShaperPage {
         LineStyle::try_from(self.imp().smoothstyle_line_style_row.get().selected()).unwrap()
     }
 
+    pub(crate) fn smoothstyle_line_pattern(&self) -> LinePattern {
+        LinePattern::try_from(self.imp().smoothstyle_line_pattern_row.get().selected()).unwrap()
+    }
+
     pub(crate) fn roughstyle_fillstyle(&self) -> FillStyle {
         FillStyle::try_from(self.imp().roughstyle_fillstyle_row.get().selected()).unwrap()
     }
@@ -387,6 +409,25 @@ impl RnShaperPage {
                 }
             ));
 
+        // Line pattern
+        imp.smoothstyle_line_pattern_row
+            .get()
+            .connect_selected_notify(clone!(
+                #[weak(rename_to=shaperpage)]
+                self,
+                #[weak]
+                appwindow,
+                move |_| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .pens_config
+                        .shaper_config
+                        .smooth_options
+                        .line_pattern = shaperpage.smoothstyle_line_pattern();
+                }
+            ));
+
         // Rough style
         // Fill style
         imp.roughstyle_fillstyle_row
@@ -428,6 +469,53 @@ impl RnShaperPage {
                 }
             ));
 
+        // Roughness
+        imp.roughstyle_roughness_row.get().connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .shaper_config
+                    .rough_options
+                    .roughness = row.value().clamp(0.0, RoughOptions::ROUGHNESS_MAX);
+            }
+        ));
+
+        // Bowing
+        imp.roughstyle_bowing_row.get().connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .shaper_config
+                    .rough_options
+                    .bowing = row.value().clamp(0.0, RoughOptions::BOWING_MAX);
+            }
+        ));
+
+        // Multiple strokes
+        imp.roughstyle_multi_stroke_row
+            .get()
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .pens_config
+                        .shaper_config
+                        .rough_options
+                        .multi_stroke = row.is_active();
+                }
+            ));
+
         // shape builder type
         imp.shapebuildertype_picker.set_groups(
             shape_builder_type_icons_get_groups(),
@@ -560,6 +648,76 @@ impl RnShaperPage {
                     }
                 }
             ));
+
+        imp.constraint_angle_snap_row
+            .get()
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .pens_config
+                        .shaper_config
+                        .constraints
+                        .angle_snap
+                        .enabled = row.is_active();
+                }
+            ));
+
+        imp.constraint_angle_snap_increment_row
+            .get()
+            .connect_changed(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .pens_config
+                        .shaper_config
+                        .constraints
+                        .angle_snap
+                        .increment = row
+                        .value()
+                        .to_radians()
+                        .clamp(AngleSnap::INCREMENT_MIN, AngleSnap::INCREMENT_MAX);
+                }
+            ));
+
+        imp.constraint_angle_snap_relative_row
+            .get()
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .pens_config
+                        .shaper_config
+                        .constraints
+                        .angle_snap
+                        .relative_to_last_segment = row.is_active();
+                }
+            ));
+
+        imp.constraint_object_snap_row
+            .get()
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .pens_config
+                        .shaper_config
+                        .constraints
+                        .object_snap = row.is_active();
+                }
+            ));
     }
 
     pub(crate) fn refresh_ui(&self, appwindow: &RnAppWindow) {
@@ -593,11 +751,19 @@ impl RnShaperPage {
             .set_selected(shaper_config.smooth_options.line_cap.to_u32().unwrap());
         imp.smoothstyle_line_style_row
             .set_selected(shaper_config.smooth_options.line_style.to_u32().unwrap());
+        imp.smoothstyle_line_pattern_row
+            .set_selected(shaper_config.smooth_options.line_pattern.to_u32().unwrap());
 
         // Rough style
         self.set_roughstyle_fillstyle(shaper_config.rough_options.fill_style);
         imp.roughstyle_hachure_angle_row
             .set_value(shaper_config.rough_options.hachure_angle.to_degrees());
+        imp.roughstyle_roughness_row
+            .set_value(shaper_config.rough_options.roughness);
+        imp.roughstyle_bowing_row
+            .set_value(shaper_config.rough_options.bowing);
+        imp.roughstyle_multi_stroke_row
+            .set_active(shaper_config.rough_options.multi_stroke);
 
         // Highlighter opacity
         imp.highlight_mode_row
@@ -626,6 +792,18 @@ impl RnShaperPage {
                 .ratios
                 .contains(&ConstraintRatio::Golden),
         );
+        imp.constraint_angle_snap_row
+            .set_active(shaper_config.constraints.angle_snap.enabled);
+        imp.constraint_angle_snap_increment_row
+            .set_value(shaper_config.constraints.angle_snap.increment.to_degrees());
+        imp.constraint_angle_snap_relative_row.set_active(
+            shaper_config
+                .constraints
+                .angle_snap
+                .relative_to_last_segment,
+        );
+        imp.constraint_object_snap_row
+            .set_active(shaper_config.constraints.object_snap);
     }
 }
 
@@ -640,6 +818,14 @@ fn shape_builder_type_icons_get_groups() -> Vec<GroupedIconPickerGroupData> {
                 "shapebuilder-grid-symbolic",
             ]),
         },
+        GroupedIconPickerGroupData {
+            name: gettext("Polygons"),
+            icons: StringList::new(&[
+                "shapebuilder-roundedrectangle-symbolic",
+                "shapebuilder-regularpolygon-symbolic",
+                "shapebuilder-star-symbolic",
+            ]),
+        },
         GroupedIconPickerGroupData {
             name: gettext("Coordinate Systems"),
             icons: StringList::new(&[
@@ -660,6 +846,9 @@ fn shape_builder_type_icons_get_groups() -> Vec<GroupedIconPickerGroupData> {
             icons: StringList::new(&[
                 "shapebuilder-quadbez-symbolic",
                 "shapebuilder-cubbez-symbolic",
+                "shapebuilder-arc-symbolic",
+                "shapebuilder-compass-symbolic",
+                "shapebuilder-spline-symbolic",
                 "shapebuilder-polyline-symbolic",
                 "shapebuilder-polygon-symbolic",
             ]),
@@ -684,5 +873,11 @@ fn shape_builder_type_icons_to_display_name(icon_name: &str) -> String {
         ShapeBuilderType::CubBez => gettext("Cubic bezier curve"),
         ShapeBuilderType::Polyline => gettext("Polyline"),
         ShapeBuilderType::Polygon => gettext("Polygon"),
+        ShapeBuilderType::RoundedRectangle => gettext("Rounded rectangle"),
+        ShapeBuilderType::RegularPolygon => gettext("Regular polygon"),
+        ShapeBuilderType::Star => gettext("Star"),
+        ShapeBuilderType::Arc => gettext("Arc through three points"),
+        ShapeBuilderType::Compass => gettext("Arc around a pinned center"),
+        ShapeBuilderType::Spline => gettext("Smooth curve through points"),
     }
 }
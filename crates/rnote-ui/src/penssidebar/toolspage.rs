@@ -1,10 +1,14 @@
 // Imports
 use crate::RnAppWindow;
 use gtk4::{
-    Button, CompositeTemplate, MenuButton, Popover, ToggleButton, Widget, glib, glib::clone,
-    prelude::*, subclass::prelude::*,
+    Button, ColorDialogButton, CompositeTemplate, MenuButton, Popover, ToggleButton, Widget, gdk,
+    glib, glib::clone, prelude::*, subclass::prelude::*,
 };
-use rnote_engine::pens::pensconfig::toolsconfig::ToolStyle;
+use num_traits::cast::ToPrimitive;
+use rnote_engine::ext::GdkRGBAExt;
+use rnote_engine::pens::pensconfig::toolsconfig::{LaserToolConfig, ToolStyle};
+use rnote_engine::strokes::smudgestroke::{SmudgeMode, SmudgeOptions};
+use rnote_engine::strokes::tapestroke::{TapeOptions, TapePattern};
 
 mod imp {
     use super::*;
@@ -21,6 +25,12 @@ mod imp {
         #[template_child]
         pub(crate) toolstyle_laser_toggle: TemplateChild<ToggleButton>,
         #[template_child]
+        pub(crate) toolstyle_fill_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) toolstyle_tape_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) toolstyle_smudge_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
         pub(crate) verticalspace_menubutton: TemplateChild<MenuButton>,
         #[template_child]
         pub(crate) verticalspace_popover: TemplateChild<Popover>,
@@ -31,6 +41,46 @@ mod imp {
         #[template_child]
         pub(crate) verticalspace_limit_movement_horizontal_bordersrow:
             TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) laser_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) laser_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) laser_popover_close_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) laser_fade_duration_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) fill_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) fill_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) fill_popover_close_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) fill_color_button: TemplateChild<ColorDialogButton>,
+        #[template_child]
+        pub(crate) tape_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) tape_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) tape_popover_close_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) tape_width_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) tape_color_button: TemplateChild<ColorDialogButton>,
+        #[template_child]
+        pub(crate) tape_pattern_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) smudge_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) smudge_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) smudge_popover_close_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) smudge_mode_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) smudge_radius_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) smudge_strength_row: TemplateChild<adw::SpinRow>,
     }
 
     #[glib::object_subclass]
@@ -93,6 +143,12 @@ impl RnToolsPage {
             Some(ToolStyle::Zoom)
         } else if imp.toolstyle_laser_toggle.is_active() {
             Some(ToolStyle::Laser)
+        } else if imp.toolstyle_fill_toggle.is_active() {
+            Some(ToolStyle::Fill)
+        } else if imp.toolstyle_tape_toggle.is_active() {
+            Some(ToolStyle::Tape)
+        } else if imp.toolstyle_smudge_toggle.is_active() {
+            Some(ToolStyle::Smudge)
         } else {
             None
         }
@@ -112,6 +168,9 @@ impl RnToolsPage {
             ToolStyle::OffsetCamera => imp.toolstyle_offsetcamera_toggle.set_active(true),
             ToolStyle::Zoom => imp.toolstyle_zoom_toggle.set_active(true),
             ToolStyle::Laser => imp.toolstyle_laser_toggle.set_active(true),
+            ToolStyle::Fill => imp.toolstyle_fill_toggle.set_active(true),
+            ToolStyle::Tape => imp.toolstyle_tape_toggle.set_active(true),
+            ToolStyle::Smudge => imp.toolstyle_smudge_toggle.set_active(true),
         }
     }
 
@@ -119,6 +178,21 @@ impl RnToolsPage {
         let imp = self.imp();
         // for now doesn't do anything but for the close button later
         let verticalspace_popover = imp.verticalspace_popover.get();
+        let laser_popover = imp.laser_popover.get();
+        let fill_popover = imp.fill_popover.get();
+        let tape_popover = imp.tape_popover.get();
+        let smudge_popover = imp.smudge_popover.get();
+
+        imp.laser_fade_duration_row.set_range(
+            LaserToolConfig::FADE_DURATION_MIN,
+            LaserToolConfig::FADE_DURATION_MAX,
+        );
+        imp.tape_width_row
+            .set_range(TapeOptions::WIDTH_MIN, TapeOptions::WIDTH_MAX);
+        imp.smudge_radius_row
+            .set_range(SmudgeOptions::RADIUS_MIN, SmudgeOptions::RADIUS_MAX);
+        imp.smudge_strength_row
+            .set_range(SmudgeOptions::STRENGTH_MIN, SmudgeOptions::STRENGTH_MAX);
 
         imp.toolstyle_verticalspace_toggle.connect_toggled(clone!(
             #[weak]
@@ -204,6 +278,69 @@ impl RnToolsPage {
             }
         ));
 
+        imp.toolstyle_fill_toggle.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |toggle| {
+                if !toggle.is_active() {
+                    return;
+                }
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .tools_config
+                    .style = ToolStyle::Fill;
+
+                if let Some(canvas) = appwindow.active_tab_canvas() {
+                    let widget_flags = canvas.engine_mut().reinstall_pen_current_style();
+                    canvas.emit_handle_widget_flags(widget_flags);
+                };
+            }
+        ));
+
+        imp.toolstyle_tape_toggle.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |toggle| {
+                if !toggle.is_active() {
+                    return;
+                }
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .tools_config
+                    .style = ToolStyle::Tape;
+
+                if let Some(canvas) = appwindow.active_tab_canvas() {
+                    let widget_flags = canvas.engine_mut().reinstall_pen_current_style();
+                    canvas.emit_handle_widget_flags(widget_flags);
+                };
+            }
+        ));
+
+        imp.toolstyle_smudge_toggle.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |toggle| {
+                if !toggle.is_active() {
+                    return;
+                }
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .tools_config
+                    .style = ToolStyle::Smudge;
+
+                if let Some(canvas) = appwindow.active_tab_canvas() {
+                    let widget_flags = canvas.engine_mut().reinstall_pen_current_style();
+                    canvas.emit_handle_widget_flags(widget_flags);
+                };
+            }
+        ));
+
         imp.verticalspace_menubutton.connect_active_notify(clone!(
             #[weak(rename_to=toolspage)]
             self,
@@ -253,6 +390,202 @@ impl RnToolsPage {
                         .limit_movement_horizontal_borders = row.is_active();
                 }
             ));
+
+        imp.laser_menubutton.connect_active_notify(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            move |menubutton| {
+                if menubutton.is_active() {
+                    toolspage.set_tool_style(ToolStyle::Laser);
+                }
+            }
+        ));
+
+        imp.laser_popover_close_button.connect_clicked(clone!(
+            #[weak]
+            laser_popover,
+            move |_| {
+                laser_popover.popdown();
+            }
+        ));
+
+        imp.laser_fade_duration_row.connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .tools_config
+                    .laser_tool_config
+                    .fade_duration = row.value();
+            }
+        ));
+
+        imp.fill_menubutton.connect_active_notify(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            move |menubutton| {
+                if menubutton.is_active() {
+                    toolspage.set_tool_style(ToolStyle::Fill);
+                }
+            }
+        ));
+
+        imp.fill_popover_close_button.connect_clicked(clone!(
+            #[weak]
+            fill_popover,
+            move |_| {
+                fill_popover.popdown();
+            }
+        ));
+
+        imp.fill_color_button.connect_rgba_notify(clone!(
+            #[weak]
+            appwindow,
+            move |button| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .tools_config
+                    .fill_tool_config
+                    .fill_color = button.rgba().into_compose_color();
+            }
+        ));
+
+        imp.tape_menubutton.connect_active_notify(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            move |menubutton| {
+                if menubutton.is_active() {
+                    toolspage.set_tool_style(ToolStyle::Tape);
+                }
+            }
+        ));
+
+        imp.tape_popover_close_button.connect_clicked(clone!(
+            #[weak]
+            tape_popover,
+            move |_| {
+                tape_popover.popdown();
+            }
+        ));
+
+        imp.tape_width_row.connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .tools_config
+                    .tape_tool_config
+                    .tape_options
+                    .width = row.value();
+            }
+        ));
+
+        imp.tape_color_button.connect_rgba_notify(clone!(
+            #[weak]
+            appwindow,
+            move |button| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .tools_config
+                    .tape_tool_config
+                    .tape_options
+                    .color = button.rgba().into_compose_color();
+            }
+        ));
+
+        imp.tape_pattern_row.connect_selected_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Ok(pattern) = TapePattern::try_from(row.selected()) else {
+                    return;
+                };
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .tools_config
+                    .tape_tool_config
+                    .tape_options
+                    .pattern = pattern;
+            }
+        ));
+
+        imp.smudge_menubutton.connect_active_notify(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            move |menubutton| {
+                if menubutton.is_active() {
+                    toolspage.set_tool_style(ToolStyle::Smudge);
+                }
+            }
+        ));
+
+        imp.smudge_popover_close_button.connect_clicked(clone!(
+            #[weak]
+            smudge_popover,
+            move |_| {
+                smudge_popover.popdown();
+            }
+        ));
+
+        imp.smudge_mode_row.connect_selected_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Ok(mode) = SmudgeMode::try_from(row.selected()) else {
+                    return;
+                };
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .tools_config
+                    .smudge_tool_config
+                    .smudge_options
+                    .mode = mode;
+            }
+        ));
+
+        imp.smudge_radius_row.connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .tools_config
+                    .smudge_tool_config
+                    .smudge_options
+                    .radius = row.value();
+            }
+        ));
+
+        imp.smudge_strength_row.connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .pens_config
+                    .tools_config
+                    .smudge_tool_config
+                    .smudge_options
+                    .strength = row.value();
+            }
+        ));
     }
 
     pub(crate) fn refresh_ui(&self, appwindow: &RnAppWindow) {
@@ -278,5 +611,22 @@ impl RnToolsPage {
                     .verticalspace_tool_config
                     .limit_movement_vertical_borders,
             );
+        imp.laser_fade_duration_row
+            .set_value(tools_config.laser_tool_config.fade_duration);
+        imp.fill_color_button
+            .set_rgba(&gdk::RGBA::from_compose_color(
+                tools_config.fill_tool_config.fill_color,
+            ));
+        let tape_options = &tools_config.tape_tool_config.tape_options;
+        imp.tape_width_row.set_value(tape_options.width);
+        imp.tape_color_button
+            .set_rgba(&gdk::RGBA::from_compose_color(tape_options.color));
+        imp.tape_pattern_row
+            .set_selected(tape_options.pattern.to_u32().unwrap());
+        let smudge_options = &tools_config.smudge_tool_config.smudge_options;
+        imp.smudge_mode_row
+            .set_selected(smudge_options.mode.to_u32().unwrap());
+        imp.smudge_radius_row.set_value(smudge_options.radius);
+        imp.smudge_strength_row.set_value(smudge_options.strength);
     }
 }
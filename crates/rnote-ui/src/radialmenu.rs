@@ -0,0 +1,364 @@
+// Imports
+use crate::RnAppWindow;
+use gettextrs::gettext;
+use gtk4::{
+    Align, Button, CompositeTemplate, DrawingArea, Fixed, Popover, ToggleButton, Widget, gdk, glib,
+    glib::clone, graphene, prelude::*, subclass::prelude::*,
+};
+use rnote_compose::Color;
+use rnote_engine::ext::GdkRGBAExt;
+use rnote_engine::pens::PenStyle;
+use std::str::FromStr;
+use tracing::error;
+
+mod imp {
+    use super::*;
+
+    #[derive(Default, Debug, CompositeTemplate)]
+    #[template(resource = "/com/github/flxzt/rnote/ui/radialmenu.ui")]
+    pub(crate) struct RnRadialMenu {
+        #[template_child]
+        pub(crate) popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) slots_fixed: TemplateChild<Fixed>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnRadialMenu {
+        const NAME: &'static str = "RnRadialMenu";
+        type Type = super::RnRadialMenu;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnRadialMenu {
+        fn dispose(&self) {
+            self.dispose_template();
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for RnRadialMenu {
+        fn size_allocate(&self, width: i32, height: i32, baseline: i32) {
+            self.parent_size_allocate(width, height, baseline);
+            self.popover.get().present();
+        }
+    }
+}
+
+glib::wrapper! {
+    pub(crate) struct RnRadialMenu(ObjectSubclass<imp::RnRadialMenu>)
+        @extends Widget,
+        @implements gtk4::Accessible, gtk4::Buildable, gtk4::ConstraintTarget;
+}
+
+impl Default for RnRadialMenu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A slot of the radial menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RadialMenuSlot {
+    Empty,
+    Pen(PenStyle),
+    /// The color at the index of the active palette.
+    PaletteColor(usize),
+    Undo,
+    Redo,
+    SnapPositions,
+    RespectBorders,
+}
+
+impl RadialMenuSlot {
+    /// The number of palette colors that can be put in slots.
+    const N_PALETTE_COLORS: usize = 9;
+
+    /// All slots that can be configured.
+    pub(crate) fn choices() -> Vec<Self> {
+        [
+            Self::Empty,
+            Self::Pen(PenStyle::Brush),
+            Self::Pen(PenStyle::Shaper),
+            Self::Pen(PenStyle::Typewriter),
+            Self::Pen(PenStyle::Eraser),
+            Self::Pen(PenStyle::Selector),
+            Self::Pen(PenStyle::Tools),
+            Self::Undo,
+            Self::Redo,
+            Self::SnapPositions,
+            Self::RespectBorders,
+        ]
+        .into_iter()
+        .chain((0..Self::N_PALETTE_COLORS).map(Self::PaletteColor))
+        .collect()
+    }
+
+    /// The key the slot is stored with in the settings.
+    pub(crate) fn key(&self) -> String {
+        match self {
+            Self::Empty => String::from("empty"),
+            Self::Pen(style) => format!("pen-{style}"),
+            Self::PaletteColor(i) => format!("palette-color-{}", i + 1),
+            Self::Undo => String::from("undo"),
+            Self::Redo => String::from("redo"),
+            Self::SnapPositions => String::from("snap-positions"),
+            Self::RespectBorders => String::from("respect-borders"),
+        }
+    }
+
+    pub(crate) fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "empty" => Some(Self::Empty),
+            "undo" => Some(Self::Undo),
+            "redo" => Some(Self::Redo),
+            "snap-positions" => Some(Self::SnapPositions),
+            "respect-borders" => Some(Self::RespectBorders),
+            _ => {
+                if let Some(style) = key.strip_prefix("pen-") {
+                    PenStyle::from_str(style).ok().map(Self::Pen)
+                } else if let Some(n) = key.strip_prefix("palette-color-") {
+                    n.parse::<usize>()
+                        .ok()
+                        .filter(|n| (1..=Self::N_PALETTE_COLORS).contains(n))
+                        .map(|n| Self::PaletteColor(n - 1))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    pub(crate) fn display_name(&self) -> String {
+        match self {
+            Self::Empty => gettext("Empty"),
+            Self::Pen(PenStyle::Brush) => gettext("Brush"),
+            Self::Pen(PenStyle::Shaper) => gettext("Shaper"),
+            Self::Pen(PenStyle::Typewriter) => gettext("Typewriter"),
+            Self::Pen(PenStyle::Eraser) => gettext("Eraser"),
+            Self::Pen(PenStyle::Selector) => gettext("Selector"),
+            Self::Pen(PenStyle::Tools) => gettext("Tools"),
+            Self::PaletteColor(i) => format!("{} {}", gettext("Palette Color"), i + 1),
+            Self::Undo => gettext("Undo"),
+            Self::Redo => gettext("Redo"),
+            Self::SnapPositions => gettext("Snap Positions"),
+            Self::RespectBorders => gettext("Respect Borders"),
+        }
+    }
+
+    fn icon_name(&self) -> Option<String> {
+        match self {
+            Self::Empty | Self::PaletteColor(_) => None,
+            Self::Pen(style) => Some(style.icon_name()),
+            Self::Undo => Some(String::from("edit-undo-symbolic")),
+            Self::Redo => Some(String::from("edit-redo-symbolic")),
+            Self::SnapPositions => Some(String::from("shapebuilder-grid-symbolic")),
+            Self::RespectBorders => Some(String::from("resize-to-fit-content-symbolic")),
+        }
+    }
+}
+
+impl RnRadialMenu {
+    /// The number of slots around the center.
+    pub(crate) const N_SLOTS: usize = 8;
+    /// The distance of the slot centers to the menu center.
+    const RADIUS: f64 = 64.0;
+    const SLOT_SIZE: i32 = 44;
+
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    /// The configured slots, read from the settings.
+    pub(crate) fn slots(appwindow: &RnAppWindow) -> Vec<RadialMenuSlot> {
+        let Some(app_settings) = appwindow.app().app_settings() else {
+            error!("Reading the radial menu slots failed, settings schema not found.");
+            return Vec::new();
+        };
+        app_settings
+            .strv("radial-menu-slots")
+            .iter()
+            .map(|key| RadialMenuSlot::from_key(key.as_str()).unwrap_or(RadialMenuSlot::Empty))
+            .collect()
+    }
+
+    /// Pops up the menu centered at the pointer position on the active canvas,
+    /// or centered on the canvas when the pointer is outside of it.
+    pub(crate) fn popup_at_pointer(&self, appwindow: &RnAppWindow) {
+        let Some(wrapper) = appwindow.active_tab_wrapper() else {
+            return;
+        };
+        let wrapper_pos = wrapper.pointer_pos().unwrap_or_else(|| {
+            na::vector![
+                f64::from(wrapper.width()) * 0.5,
+                f64::from(wrapper.height()) * 0.5
+            ]
+        });
+        let Some(pos) = wrapper.compute_point(
+            self,
+            &graphene::Point::new(wrapper_pos[0] as f32, wrapper_pos[1] as f32),
+        ) else {
+            return;
+        };
+
+        self.rebuild_slots(appwindow);
+        let popover = self.imp().popover.get();
+        popover.set_pointing_to(Some(&gdk::Rectangle::new(
+            pos.x() as i32,
+            pos.y() as i32,
+            1,
+            1,
+        )));
+        // center the menu on the position instead of placing it below
+        popover.set_offset(0, -Self::menu_size() / 2);
+        popover.popup();
+    }
+
+    fn menu_size() -> i32 {
+        (Self::RADIUS * 2.0) as i32 + Self::SLOT_SIZE
+    }
+
+    fn rebuild_slots(&self, appwindow: &RnAppWindow) {
+        let slots_fixed = self.imp().slots_fixed.get();
+        let menu_size = Self::menu_size();
+        let center = f64::from(menu_size) * 0.5;
+
+        while let Some(child) = slots_fixed.first_child() {
+            slots_fixed.remove(&child);
+        }
+        slots_fixed.set_size_request(menu_size, menu_size);
+
+        let slots = Self::slots(appwindow);
+        let n_slots = slots.len().max(1);
+        for (i, slot) in slots.into_iter().enumerate() {
+            let Some(slot_widget) = self.create_slot_widget(appwindow, slot) else {
+                continue;
+            };
+            // the first slot is at the top, the others follow clockwise
+            let angle =
+                -std::f64::consts::FRAC_PI_2 + std::f64::consts::TAU * i as f64 / n_slots as f64;
+            slots_fixed.put(
+                &slot_widget,
+                center + Self::RADIUS * angle.cos() - f64::from(Self::SLOT_SIZE) * 0.5,
+                center + Self::RADIUS * angle.sin() - f64::from(Self::SLOT_SIZE) * 0.5,
+            );
+        }
+
+        let close_button = Button::builder()
+            .icon_name("window-close-symbolic")
+            .tooltip_text(gettext("Close"))
+            .width_request(Self::SLOT_SIZE)
+            .height_request(Self::SLOT_SIZE)
+            .build();
+        close_button.add_css_class("circular");
+        close_button.add_css_class("flat");
+        close_button.connect_clicked(clone!(
+            #[weak(rename_to=radialmenu)]
+            self,
+            move |_| radialmenu.imp().popover.popdown()
+        ));
+        slots_fixed.put(
+            &close_button,
+            center - f64::from(Self::SLOT_SIZE) * 0.5,
+            center - f64::from(Self::SLOT_SIZE) * 0.5,
+        );
+    }
+
+    fn create_slot_widget(&self, appwindow: &RnAppWindow, slot: RadialMenuSlot) -> Option<Button> {
+        let button = match slot {
+            RadialMenuSlot::Empty => return None,
+            RadialMenuSlot::PaletteColor(i) => {
+                let color = appwindow.overlays().colorpicker().palette_color(i)?;
+                let button = Button::builder().child(&create_color_dot(color)).build();
+                button.connect_clicked(clone!(
+                    #[weak]
+                    appwindow,
+                    move |_| appwindow.overlays().colorpicker().select_palette_color(i)
+                ));
+                button
+            }
+            RadialMenuSlot::Pen(style) => {
+                // toggle buttons reflect the current state of stateful actions
+                let button = ToggleButton::builder()
+                    .action_name("win.pen-style")
+                    .action_target(&style.to_string().to_variant())
+                    .build();
+                button.upcast::<Button>()
+            }
+            RadialMenuSlot::Undo => Button::builder().action_name("win.undo").build(),
+            RadialMenuSlot::Redo => Button::builder().action_name("win.redo").build(),
+            RadialMenuSlot::SnapPositions => ToggleButton::builder()
+                .action_name("win.snap-positions")
+                .build()
+                .upcast::<Button>(),
+            RadialMenuSlot::RespectBorders => ToggleButton::builder()
+                .action_name("win.respect-borders")
+                .build()
+                .upcast::<Button>(),
+        };
+        if let Some(icon_name) = slot.icon_name() {
+            button.set_icon_name(&icon_name);
+        }
+        button.set_tooltip_text(Some(&slot.display_name()));
+        button.set_size_request(Self::SLOT_SIZE, Self::SLOT_SIZE);
+        button.add_css_class("circular");
+        button.connect_clicked(clone!(
+            #[weak(rename_to=radialmenu)]
+            self,
+            move |_| radialmenu.imp().popover.popdown()
+        ));
+
+        Some(button)
+    }
+}
+
+fn create_color_dot(color: Color) -> DrawingArea {
+    const SIZE: i32 = 20;
+    let color = gdk::RGBA::from_compose_color(color);
+
+    let dot = DrawingArea::builder()
+        .content_width(SIZE)
+        .content_height(SIZE)
+        .halign(Align::Center)
+        .valign(Align::Center)
+        .build();
+    dot.set_draw_func(move |area, cx, width, height| {
+        let radius = f64::from(SIZE) * 0.5;
+        let (center_x, center_y) = (f64::from(width) * 0.5, f64::from(height) * 0.5);
+
+        cx.set_source_rgba(
+            color.red() as f64,
+            color.green() as f64,
+            color.blue() as f64,
+            color.alpha() as f64,
+        );
+        cx.arc(center_x, center_y, radius - 1.0, 0.0, std::f64::consts::TAU);
+        if let Err(e) = cx.fill_preserve() {
+            error!("Painting radial menu color dot failed, fill returned Err: {e:?}");
+        }
+        // outline, so that colors close to the background stay visible
+        let fg_color = area.color();
+        cx.set_source_rgba(
+            fg_color.red() as f64,
+            fg_color.green() as f64,
+            fg_color.blue() as f64,
+            0.5,
+        );
+        cx.set_line_width(1.0);
+        if let Err(e) = cx.stroke() {
+            error!("Painting radial menu color dot failed, stroke returned Err: {e:?}");
+        }
+    });
+
+    dot
+}
@@ -7,12 +7,16 @@ pub(crate) use penshortcutrow::RnPenShortcutRow;
 use rnote_compose::ext::Vector2Ext;
 
 // Imports
-use crate::{RnAppWindow, RnIconPicker, RnUnitEntry};
+use crate::radialmenu::RadialMenuSlot;
+use crate::{
+    RnAppWindow, RnIconPicker, RnRadialMenu, RnUnitEntry, dialogs, keyboardshortcuts,
+    settingsprofiles,
+};
 use adw::prelude::*;
 use gettextrs::{gettext, pgettext};
 use gtk4::{
     Adjustment, Button, ColorDialogButton, CompositeTemplate, MenuButton, ScrolledWindow,
-    StringList, ToggleButton, Widget, gdk, glib, glib::clone, subclass::prelude::*,
+    ShortcutLabel, StringList, ToggleButton, Widget, gdk, glib, glib::clone, subclass::prelude::*,
 };
 use num_traits::ToPrimitive;
 use rnote_compose::penevent::ShortcutKey;
@@ -20,8 +24,11 @@ use rnote_engine::WidgetFlags;
 use rnote_engine::document::Layout;
 use rnote_engine::document::background::PatternStyle;
 use rnote_engine::document::format::{self, Format, PredefinedFormat};
+use rnote_engine::engine::rendering::{Antialias, ColorDepth, PrerenderMargin, RenderBackend};
 use rnote_engine::ext::GdkRGBAExt;
+use rnote_engine::fileformats::rnoteformat::{CompressionMethod, CompressionPrefs};
 use std::cell::RefCell;
+use tracing::error;
 
 mod imp {
     use super::*;
@@ -31,6 +38,10 @@ mod imp {
     pub(crate) struct RnSettingsPanel {
         pub(crate) temporary_format: RefCell<Format>,
         pub(crate) app_restart_toast_singleton: RefCell<Option<adw::Toast>>,
+        /// The shortcut labels of the keyboard shortcut editor, with the detailed action names they display.
+        pub(crate) keyboard_shortcut_labels: RefCell<Vec<(String, ShortcutLabel)>>,
+        pub(crate) radial_menu_slot_rows: RefCell<Vec<adw::ComboRow>>,
+        pub(crate) settings_profile_rows: RefCell<Vec<adw::ActionRow>>,
 
         #[template_child]
         pub(crate) settings_scroller: TemplateChild<ScrolledWindow>,
@@ -43,8 +54,42 @@ mod imp {
         #[template_child]
         pub(crate) general_optimize_epd_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub(crate) general_render_backend_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) general_prerender_margin_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) general_antialias_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) general_flattening_tolerance_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) general_reduce_quality_in_motion_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) general_color_depth_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) general_downscale_imported_images_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) general_imported_images_max_dimension_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) general_imported_images_max_megapixels_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) general_file_compression_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) general_file_compression_level_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) general_file_binary_pen_paths_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub(crate) general_inertial_scrolling_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub(crate) touch_gesture_two_finger_tap_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) touch_gesture_three_finger_tap_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) touch_gesture_three_finger_swipe_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) presentation_lock_editing_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) presentation_show_timer_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub(crate) general_regular_cursor_picker: TemplateChild<RnIconPicker>,
         #[template_child]
         pub(crate) general_regular_cursor_picker_menubutton: TemplateChild<MenuButton>,
@@ -109,6 +154,12 @@ mod imp {
         #[template_child]
         pub(crate) doc_show_origin_indicator_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub(crate) doc_password_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(crate) doc_password_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) doc_attachments_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
         pub(crate) background_pattern_invert_color_button: TemplateChild<Button>,
         #[template_child]
         pub(crate) penshortcut_stylus_button_primary_row: TemplateChild<RnPenShortcutRow>,
@@ -128,6 +179,24 @@ mod imp {
         pub(crate) penshortcut_drawing_pad_button_2: TemplateChild<RnPenShortcutRow>,
         #[template_child]
         pub(crate) penshortcut_drawing_pad_button_3: TemplateChild<RnPenShortcutRow>,
+        #[template_child]
+        pub(crate) radial_menu_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub(crate) keyboard_shortcuts_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub(crate) keyboard_shortcuts_import_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) keyboard_shortcuts_export_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) keyboard_shortcuts_reset_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) settings_profiles_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub(crate) settings_profiles_import_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) settings_profiles_export_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) settings_profiles_new_entryrow: TemplateChild<adw::EntryRow>,
     }
 
     #[glib::object_subclass]
@@ -398,6 +467,26 @@ impl RnSettingsPanel {
         self.imp().general_inertial_scrolling_row.clone()
     }
 
+    pub(crate) fn touch_gesture_two_finger_tap_row(&self) -> adw::SwitchRow {
+        self.imp().touch_gesture_two_finger_tap_row.clone()
+    }
+
+    pub(crate) fn touch_gesture_three_finger_tap_row(&self) -> adw::SwitchRow {
+        self.imp().touch_gesture_three_finger_tap_row.clone()
+    }
+
+    pub(crate) fn touch_gesture_three_finger_swipe_row(&self) -> adw::SwitchRow {
+        self.imp().touch_gesture_three_finger_swipe_row.clone()
+    }
+
+    pub(crate) fn presentation_lock_editing_row(&self) -> adw::SwitchRow {
+        self.imp().presentation_lock_editing_row.clone()
+    }
+
+    pub(crate) fn presentation_show_timer_row(&self) -> adw::SwitchRow {
+        self.imp().presentation_show_timer_row.clone()
+    }
+
     pub(crate) fn document_layout(&self) -> Layout {
         Layout::try_from(self.imp().doc_document_layout_row.get().selected()).unwrap()
     }
@@ -413,6 +502,16 @@ impl RnSettingsPanel {
         self.refresh_format_ui(appwindow);
         self.refresh_doc_ui(appwindow);
         self.refresh_shortcuts_ui(appwindow);
+        self.refresh_keyboard_shortcuts_ui(appwindow);
+        self.refresh_radial_menu_ui(appwindow);
+        self.refresh_settings_profiles_ui(appwindow);
+    }
+
+    fn refresh_file_compression_level_row(&self, compression_prefs: CompressionPrefs) {
+        let level_row = &self.imp().general_file_compression_level_row;
+        let level_range = compression_prefs.method.level_range();
+        level_row.set_range(*level_range.start() as f64, *level_range.end() as f64);
+        level_row.set_value(compression_prefs.level() as f64);
     }
 
     fn refresh_general_ui(&self, appwindow: &RnAppWindow) {
@@ -421,6 +520,38 @@ impl RnSettingsPanel {
 
         let optimize_epd = appwindow.engine_config().read().optimize_epd;
         imp.general_optimize_epd_row.set_active(optimize_epd);
+        let render_backend = appwindow.engine_config().read().render_backend;
+        imp.general_render_backend_row
+            .set_selected(render_backend.to_u32().unwrap());
+        let prerender_margin = appwindow.engine_config().read().prerender_margin;
+        imp.general_prerender_margin_row
+            .set_value(prerender_margin.factor() * 100.0);
+        let render_quality = appwindow.engine_config().read().render_quality;
+        imp.general_antialias_row
+            .set_selected(render_quality.antialias.to_u32().unwrap());
+        imp.general_flattening_tolerance_row
+            .set_value(render_quality.flattening_tolerance());
+        imp.general_reduce_quality_in_motion_row
+            .set_active(render_quality.reduce_in_motion);
+        imp.general_color_depth_row
+            .set_selected(render_quality.color_depth.to_u32().unwrap());
+        let bitmap_import_prefs = appwindow
+            .engine_config()
+            .read()
+            .import_prefs
+            .bitmap_import_prefs;
+        imp.general_downscale_imported_images_row
+            .set_active(bitmap_import_prefs.downscale);
+        imp.general_imported_images_max_dimension_row
+            .set_value(bitmap_import_prefs.max_dimension as f64);
+        imp.general_imported_images_max_megapixels_row
+            .set_value(bitmap_import_prefs.max_megapixels);
+        let compression_prefs = appwindow.engine_config().read().compression_prefs;
+        imp.general_file_compression_row
+            .set_selected(compression_prefs.method.to_u32().unwrap());
+        self.refresh_file_compression_level_row(compression_prefs);
+        imp.general_file_binary_pen_paths_row
+            .set_active(compression_prefs.binary_pen_paths);
 
         if let Some(canvas) = canvas {
             let format_border_color = canvas.engine_ref().document.config.format.border_color;
@@ -484,6 +615,20 @@ impl RnSettingsPanel {
             self.set_document_layout(&document_layout);
             imp.doc_show_origin_indicator_row
                 .set_active(show_origin_indicator);
+            imp.doc_password_row
+                .set_subtitle(&if canvas.encryption_password().is_some() {
+                    gettext("The document is encrypted when saved")
+                } else {
+                    gettext("Disabled")
+                });
+            let attachments_amount = canvas.engine_ref().document.attachments.len();
+            let attachments_size = canvas.engine_ref().document.attachments.total_size();
+            imp.doc_attachments_row.set_subtitle(&format!(
+                "{}: {attachments_amount}, {}: {}",
+                gettext("Files"),
+                gettext("Size"),
+                glib::format_size(attachments_size as u64)
+            ));
         }
     }
 
@@ -536,6 +681,9 @@ impl RnSettingsPanel {
         self.setup_format(appwindow);
         self.setup_doc(appwindow);
         self.setup_shortcuts(appwindow);
+        self.setup_radial_menu(appwindow);
+        self.setup_keyboard_shortcuts(appwindow);
+        self.setup_settings_profiles(appwindow);
     }
 
     fn setup_general(&self, appwindow: &RnAppWindow) {
@@ -606,6 +754,218 @@ impl RnSettingsPanel {
             }
         ));
 
+        imp.general_render_backend_row
+            .get()
+            .connect_selected_item_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Ok(render_backend) = RenderBackend::try_from(row.selected()) else {
+                        return;
+                    };
+                    appwindow.engine_config().write().render_backend = render_backend;
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+                    let widget_flags = canvas
+                        .engine_mut()
+                        .update_content_rendering_current_viewport();
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+            ));
+
+        imp.general_prerender_margin_row.connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow.engine_config().write().prerender_margin =
+                    PrerenderMargin::new(row.value() / 100.0);
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().update_rendering_current_viewport();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        imp.general_antialias_row
+            .get()
+            .connect_selected_item_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Ok(antialias) = Antialias::try_from(row.selected()) else {
+                        return;
+                    };
+                    appwindow.engine_config().write().render_quality.antialias = antialias;
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+                    let widget_flags = canvas
+                        .engine_mut()
+                        .update_content_rendering_current_viewport();
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+            ));
+
+        imp.general_flattening_tolerance_row.connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .engine_config()
+                    .write()
+                    .render_quality
+                    .flattening_tolerance = row.value();
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas
+                    .engine_mut()
+                    .update_content_rendering_current_viewport();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        imp.general_reduce_quality_in_motion_row
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .render_quality
+                        .reduce_in_motion = row.is_active();
+                }
+            ));
+
+        imp.general_color_depth_row
+            .get()
+            .connect_selected_item_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Ok(color_depth) = ColorDepth::try_from(row.selected()) else {
+                        return;
+                    };
+                    appwindow.engine_config().write().render_quality.color_depth = color_depth;
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+                    let widget_flags = canvas
+                        .engine_mut()
+                        .update_content_rendering_current_viewport();
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+            ));
+
+        imp.general_downscale_imported_images_row
+            .bind_property(
+                "active",
+                &*imp.general_imported_images_max_dimension_row,
+                "sensitive",
+            )
+            .sync_create()
+            .build();
+
+        imp.general_downscale_imported_images_row
+            .bind_property(
+                "active",
+                &*imp.general_imported_images_max_megapixels_row,
+                "sensitive",
+            )
+            .sync_create()
+            .build();
+
+        imp.general_downscale_imported_images_row
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .import_prefs
+                        .bitmap_import_prefs
+                        .downscale = row.is_active();
+                }
+            ));
+
+        imp.general_imported_images_max_dimension_row
+            .connect_changed(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .import_prefs
+                        .bitmap_import_prefs
+                        .max_dimension = row.value().max(1.0) as u32;
+                }
+            ));
+
+        imp.general_imported_images_max_megapixels_row
+            .connect_changed(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .import_prefs
+                        .bitmap_import_prefs
+                        .max_megapixels = row.value();
+                }
+            ));
+
+        imp.general_file_compression_row
+            .get()
+            .connect_selected_item_notify(clone!(
+                #[weak(rename_to=settings_panel)]
+                self,
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Ok(method) = CompressionMethod::try_from(row.selected()) else {
+                        return;
+                    };
+                    let compression_prefs = {
+                        let mut config = appwindow.engine_config().write();
+                        config.compression_prefs.method = method;
+                        config.compression_prefs
+                    };
+                    settings_panel.refresh_file_compression_level_row(compression_prefs);
+                }
+            ));
+
+        imp.general_file_compression_level_row
+            .connect_changed(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .compression_prefs
+                        .set_level(row.value().round() as i32);
+                }
+            ));
+
+        imp.general_file_binary_pen_paths_row
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .compression_prefs
+                        .binary_pen_paths = row.is_active();
+                }
+            ));
+
         // Regular cursor picker
         imp.general_regular_cursor_picker.set_list(
             StringList::new(CURSORS_LIST),
@@ -1180,6 +1540,25 @@ impl RnSettingsPanel {
                     appwindow.handle_widget_flags(widget_flags, &canvas);
                 }
             ));
+
+        imp.doc_password_button.get().connect_clicked(clone!(
+            #[weak]
+            appwindow,
+            move |_| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    #[weak]
+                    canvas,
+                    async move {
+                        dialogs::dialog_document_password(&appwindow, &canvas).await;
+                    }
+                ));
+            }
+        ));
     }
 
     fn setup_shortcuts(&self, appwindow: &RnAppWindow) {
@@ -1397,6 +1776,365 @@ impl RnSettingsPanel {
         );
     }
 
+    fn setup_radial_menu(&self, appwindow: &RnAppWindow) {
+        let Some(app_settings) = appwindow.app().app_settings() else {
+            error!("Setting up the radial menu settings failed, settings schema not found.");
+            return;
+        };
+        let choices = RadialMenuSlot::choices();
+        let choices_model = StringList::new(&[]);
+        for choice in choices.iter() {
+            choices_model.append(&choice.display_name());
+        }
+        let slots = Self::radial_menu_slots(appwindow);
+
+        let slot_rows = slots
+            .into_iter()
+            .enumerate()
+            .map(|(i, slot)| {
+                let row = adw::ComboRow::builder()
+                    .title(format!("{} {}", gettext("Slot"), i + 1))
+                    .model(&choices_model)
+                    .selected(
+                        choices
+                            .iter()
+                            .position(|choice| *choice == slot)
+                            .unwrap_or(0) as u32,
+                    )
+                    .build();
+                self.imp().radial_menu_group.add(&row);
+                row
+            })
+            .collect::<Vec<adw::ComboRow>>();
+
+        // connect after the initial selection, so the settings are not overwritten
+        for row in slot_rows.iter() {
+            row.connect_selected_notify(clone!(
+                #[strong]
+                slot_rows,
+                #[strong]
+                choices,
+                #[weak]
+                app_settings,
+                move |_| {
+                    let slot_keys = slot_rows
+                        .iter()
+                        .map(|row| {
+                            choices
+                                .get(row.selected() as usize)
+                                .copied()
+                                .unwrap_or(RadialMenuSlot::Empty)
+                                .key()
+                        })
+                        .collect::<Vec<String>>();
+                    if let Err(e) = app_settings.set_strv(
+                        "radial-menu-slots",
+                        slot_keys.iter().map(String::as_str).collect::<Vec<&str>>(),
+                    ) {
+                        error!("Saving `radial-menu-slots` to settings failed, Err: {e:?}");
+                    }
+                }
+            ));
+        }
+        self.imp().radial_menu_slot_rows.replace(slot_rows);
+    }
+
+    fn radial_menu_slots(appwindow: &RnAppWindow) -> Vec<RadialMenuSlot> {
+        let mut slots = RnRadialMenu::slots(appwindow);
+        slots.resize(RnRadialMenu::N_SLOTS, RadialMenuSlot::Empty);
+        slots
+    }
+
+    fn refresh_radial_menu_ui(&self, appwindow: &RnAppWindow) {
+        let choices = RadialMenuSlot::choices();
+        // the slots need to be read before updating the rows, because every row change saves all rows
+        let slots = Self::radial_menu_slots(appwindow);
+        for (row, slot) in self.imp().radial_menu_slot_rows.borrow().iter().zip(slots) {
+            let selected = choices
+                .iter()
+                .position(|choice| *choice == slot)
+                .unwrap_or(0) as u32;
+            if row.selected() != selected {
+                row.set_selected(selected);
+            }
+        }
+    }
+
+    fn setup_keyboard_shortcuts(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        let mut category_rows: Vec<(String, adw::ExpanderRow)> = Vec::new();
+
+        for shortcut in keyboardshortcuts::keyboard_shortcuts() {
+            let category_row = match category_rows
+                .iter()
+                .find(|(category, _)| *category == shortcut.category)
+            {
+                Some((_, category_row)) => category_row.clone(),
+                None => {
+                    let category_row = adw::ExpanderRow::builder()
+                        .title(shortcut.category.as_str())
+                        .build();
+                    imp.keyboard_shortcuts_group.add(&category_row);
+                    category_rows.push((shortcut.category.clone(), category_row.clone()));
+                    category_row
+                }
+            };
+
+            let shortcut_label = ShortcutLabel::builder()
+                .disabled_text(gettext("Disabled"))
+                .valign(gtk4::Align::Center)
+                .build();
+            let row = adw::ActionRow::builder()
+                .title(shortcut.title.as_str())
+                .activatable(true)
+                .build();
+            row.add_suffix(&shortcut_label);
+            imp.keyboard_shortcut_labels
+                .borrow_mut()
+                .push((shortcut.detailed_action.clone(), shortcut_label));
+
+            row.connect_activated(clone!(
+                #[weak(rename_to=settingspanel)]
+                self,
+                #[weak]
+                appwindow,
+                #[strong]
+                shortcut,
+                move |_| {
+                    glib::spawn_future_local(clone!(
+                        #[weak]
+                        settingspanel,
+                        #[weak]
+                        appwindow,
+                        #[strong]
+                        shortcut,
+                        async move {
+                            let Some(edit) =
+                                dialogs::dialog_edit_keyboard_shortcut(&appwindow, &shortcut).await
+                            else {
+                                return;
+                            };
+                            keyboardshortcuts::edit_accels(
+                                &appwindow.app(),
+                                &shortcut.detailed_action,
+                                edit,
+                            );
+                            settingspanel.refresh_keyboard_shortcuts_ui(&appwindow);
+                        }
+                    ));
+                }
+            ));
+            category_row.add_row(&row);
+        }
+
+        imp.keyboard_shortcuts_import_button.connect_clicked(clone!(
+            #[weak(rename_to=settingspanel)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    settingspanel,
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::import::filedialog_import_keyboard_shortcuts(&appwindow).await;
+                        settingspanel.refresh_keyboard_shortcuts_ui(&appwindow);
+                    }
+                ));
+            }
+        ));
+
+        imp.keyboard_shortcuts_export_button.connect_clicked(clone!(
+            #[weak]
+            appwindow,
+            move |_| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::export::filedialog_export_keyboard_shortcuts(&appwindow).await;
+                    }
+                ));
+            }
+        ));
+
+        imp.keyboard_shortcuts_reset_button.connect_clicked(clone!(
+            #[weak(rename_to=settingspanel)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                keyboardshortcuts::reset_all_accels(&appwindow.app());
+                settingspanel.refresh_keyboard_shortcuts_ui(&appwindow);
+                appwindow.overlays().dispatch_toast_text(
+                    &gettext("Keyboard shortcuts were reset to the defaults"),
+                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                );
+            }
+        ));
+
+        self.refresh_keyboard_shortcuts_ui(appwindow);
+    }
+
+    /// Refresh the shortcut labels, the shortcuts are shared between all windows and can change at any time.
+    fn refresh_keyboard_shortcuts_ui(&self, appwindow: &RnAppWindow) {
+        let app = appwindow.app();
+        for (detailed_action, shortcut_label) in self.imp().keyboard_shortcut_labels.borrow().iter()
+        {
+            shortcut_label.set_accelerator(&keyboardshortcuts::accels_label_string(
+                &app,
+                detailed_action,
+            ));
+        }
+    }
+
+    fn setup_settings_profiles(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+
+        imp.settings_profiles_new_entryrow.connect_apply(clone!(
+            #[weak(rename_to=settingspanel)]
+            self,
+            #[weak]
+            appwindow,
+            move |entryrow| {
+                let name = entryrow.text().trim().to_string();
+                if name.is_empty() {
+                    return;
+                }
+                let result = || -> anyhow::Result<()> {
+                    let app_settings = appwindow
+                        .app()
+                        .app_settings()
+                        .ok_or_else(|| anyhow::anyhow!("Settings schema not found."))?;
+                    let profile = appwindow.current_settings_profile()?;
+                    settingsprofiles::save_profile(&app_settings, &name, &profile)?;
+                    app_settings.set_string("active-settings-profile", &name)?;
+                    Ok(())
+                };
+                match result() {
+                    Ok(()) => {
+                        entryrow.set_text("");
+                        appwindow.overlays().dispatch_toast_text(
+                            &gettext("Saved settings profile"),
+                            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                        );
+                    }
+                    Err(e) => {
+                        error!("Saving settings profile `{name}` failed, Err: {e:?}");
+                        appwindow
+                            .overlays()
+                            .dispatch_toast_error(&gettext("Saving settings profile failed"));
+                    }
+                }
+                settingspanel.refresh_settings_profiles_ui(&appwindow);
+            }
+        ));
+
+        imp.settings_profiles_import_button.connect_clicked(clone!(
+            #[weak(rename_to=settingspanel)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    settingspanel,
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::import::filedialog_import_settings_profile(&appwindow).await;
+                        settingspanel.refresh_settings_profiles_ui(&appwindow);
+                    }
+                ));
+            }
+        ));
+
+        imp.settings_profiles_export_button.connect_clicked(clone!(
+            #[weak]
+            appwindow,
+            move |_| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::export::filedialog_export_settings_profile(&appwindow).await;
+                    }
+                ));
+            }
+        ));
+
+        self.refresh_settings_profiles_ui(appwindow);
+    }
+
+    /// Rebuild the rows of the saved settings profiles.
+    fn refresh_settings_profiles_ui(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        let Some(app_settings) = appwindow.app().app_settings() else {
+            return;
+        };
+        let active_name = settingsprofiles::active_profile_name(&app_settings);
+
+        for row in imp.settings_profile_rows.take() {
+            imp.settings_profiles_group.remove(&row);
+        }
+        for name in settingsprofiles::profile_names(&app_settings) {
+            let active = active_name.as_ref() == Some(&name);
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&name))
+                .subtitle(if active {
+                    gettext("Active")
+                } else {
+                    String::new()
+                })
+                .activatable(!active)
+                .build();
+            let remove_button = Button::builder()
+                .icon_name("user-trash-symbolic")
+                .tooltip_text(gettext("Remove Profile"))
+                .valign(gtk4::Align::Center)
+                .css_classes(["flat"])
+                .build();
+            row.add_suffix(&remove_button);
+
+            row.connect_activated(clone!(
+                #[weak(rename_to=settingspanel)]
+                self,
+                #[weak]
+                appwindow,
+                #[strong]
+                name,
+                move |_| {
+                    if let Err(e) = appwindow.switch_settings_profile(&name) {
+                        error!("Switching to settings profile `{name}` failed, Err: {e:?}");
+                        appwindow
+                            .overlays()
+                            .dispatch_toast_error(&gettext("Switching settings profile failed"));
+                    }
+                    settingspanel.refresh_settings_profiles_ui(&appwindow);
+                }
+            ));
+            remove_button.connect_clicked(clone!(
+                #[weak(rename_to=settingspanel)]
+                self,
+                #[weak]
+                appwindow,
+                #[weak]
+                app_settings,
+                #[strong]
+                name,
+                move |_| {
+                    settingsprofiles::remove_profile(&app_settings, &name);
+                    settingspanel.refresh_settings_profiles_ui(&appwindow);
+                }
+            ));
+
+            imp.settings_profiles_group.add(&row);
+            imp.settings_profile_rows.borrow_mut().push(row);
+        }
+    }
+
     fn revert_format(&self, appwindow: &RnAppWindow) {
         let imp = self.imp();
         let Some(canvas) = appwindow.active_tab_canvas() else {
@@ -73,6 +73,7 @@ mod imp {
                     ShortcutAction::ChangePenStyle { style, .. } => {
                         *style = new_pen_style;
                     }
+                    ShortcutAction::OpenRadialMenu => {}
                 }
                 row.emit_by_name::<()>("action-changed", &[]);
             });
@@ -80,12 +81,17 @@ mod imp {
             self.mode_dropdown.get().connect_selected_notify(clone!(
                 #[weak(rename_to=penshortcutrow)]
                 obj,
-                move |_| {
-                    match &mut *penshortcutrow.imp().action.borrow_mut() {
-                        ShortcutAction::ChangePenStyle { mode, .. } => {
-                            *mode = penshortcutrow.shortcut_mode();
-                        }
-                    }
+                move |mode_dropdown| {
+                    let new_action =
+                        if mode_dropdown.selected() == super::RnPenShortcutRow::MODE_RADIAL_MENU {
+                            ShortcutAction::OpenRadialMenu
+                        } else {
+                            ShortcutAction::ChangePenStyle {
+                                style: penshortcutrow.pen_style(),
+                                mode: penshortcutrow.shortcut_mode(),
+                            }
+                        };
+                    *penshortcutrow.imp().action.borrow_mut() = new_action;
                     penshortcutrow.emit_by_name::<()>("action-changed", &[]);
                 }
             ));
@@ -133,6 +139,9 @@ glib::wrapper! {
 }
 
 impl RnPenShortcutRow {
+    /// The position of the radial menu entry in the mode dropdown, after the shortcut modes.
+    pub(crate) const MODE_RADIAL_MENU: u32 = 4;
+
     #[allow(clippy::new_without_default)]
     #[allow(unused)]
     pub(crate) fn new() -> Self {
@@ -174,6 +183,11 @@ impl RnPenShortcutRow {
                 self.set_pen_style(style);
                 self.set_shortcut_mode(mode);
             }
+            ShortcutAction::OpenRadialMenu => {
+                self.imp()
+                    .mode_dropdown
+                    .set_selected(Self::MODE_RADIAL_MENU);
+            }
         }
     }
 }
@@ -0,0 +1,151 @@
+// Imports
+use gtk4::{gio, glib, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::{error, warn};
+
+/// Settings keys that are specific to the machine or the session, or deprecated, and are not part of profiles.
+const EXCLUDED_KEYS: &[&str] = &[
+    "window-width",
+    "window-height",
+    "is-maximized",
+    "selected-workspace-index",
+    "workspace-list",
+    "workspacebrowser-pinned-folders",
+    "workspacebrowser-recent-files",
+    "settings-profiles",
+    "active-settings-profile",
+    "colorpicker-color-1",
+    "colorpicker-color-2",
+    "colorpicker-color-3",
+    "colorpicker-color-4",
+    "colorpicker-color-5",
+    "colorpicker-color-6",
+    "colorpicker-color-7",
+    "colorpicker-color-8",
+    "colorpicker-color-9",
+];
+
+/// A snapshot of the app settings, including the engine configuration, pens, palettes, shortcuts and presets.
+///
+/// Used for the named profiles and the exported profile files.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "settings_profile")]
+pub(crate) struct SettingsProfile {
+    /// The settings values in the GVariant text format, keyed by the settings key.
+    pub(crate) settings: BTreeMap<String, String>,
+}
+
+impl SettingsProfile {
+    /// Capture the current values of all settings that are part of profiles.
+    pub(crate) fn from_settings(settings: &gio::Settings) -> Self {
+        let Some(schema) = settings.settings_schema() else {
+            error!("Capturing settings profile failed, settings have no schema.");
+            return Self::default();
+        };
+        let settings = schema
+            .list_keys()
+            .into_iter()
+            .filter(|key| !EXCLUDED_KEYS.contains(&key.as_str()))
+            .map(|key| {
+                let value = settings.value(&key).print(true).to_string();
+                (key.to_string(), value)
+            })
+            .collect();
+        Self { settings }
+    }
+
+    /// Write the values of the profile into the settings.
+    ///
+    /// Excluded keys, unknown keys and invalid values are skipped.
+    pub(crate) fn apply_to_settings(&self, settings: &gio::Settings) -> anyhow::Result<()> {
+        let schema = settings
+            .settings_schema()
+            .ok_or_else(|| anyhow::anyhow!("Settings have no schema."))?;
+
+        for (key, value) in self.settings.iter() {
+            if EXCLUDED_KEYS.contains(&key.as_str()) {
+                continue;
+            }
+            if !schema.has_key(key) {
+                warn!("Applying unknown settings key `{key}` from profile skipped.");
+                continue;
+            }
+            let value_type = schema.key(key).value_type();
+            let value = match glib::Variant::parse(Some(&*value_type), value) {
+                Ok(value) => value,
+                Err(e) => {
+                    warn!("Applying invalid value for settings key `{key}` skipped, Err: {e:?}");
+                    continue;
+                }
+            };
+            if let Err(e) = settings.set_value(key, &value) {
+                warn!("Applying value for settings key `{key}` failed, Err: {e:?}");
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub(crate) fn from_json(json: &str) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/// The names of the saved profiles, in alphabetical order.
+pub(crate) fn profile_names(settings: &gio::Settings) -> Vec<String> {
+    saved_profiles(settings).into_keys().collect()
+}
+
+/// The saved profile with the name.
+pub(crate) fn saved_profile(settings: &gio::Settings, name: &str) -> Option<SettingsProfile> {
+    let json = saved_profiles(settings).remove(name)?;
+    match SettingsProfile::from_json(&json) {
+        Ok(profile) => Some(profile),
+        Err(e) => {
+            error!("Loading settings profile `{name}` failed, Err: {e:?}");
+            None
+        }
+    }
+}
+
+/// Save the profile under the name, replacing an existing profile with the same name.
+pub(crate) fn save_profile(
+    settings: &gio::Settings,
+    name: &str,
+    profile: &SettingsProfile,
+) -> anyhow::Result<()> {
+    let mut profiles = saved_profiles(settings);
+    profiles.insert(name.to_string(), profile.to_json()?);
+    settings.set_value("settings-profiles", &profiles.to_variant())?;
+    Ok(())
+}
+
+pub(crate) fn remove_profile(settings: &gio::Settings, name: &str) {
+    let mut profiles = saved_profiles(settings);
+    profiles.remove(name);
+    if let Err(e) = settings.set_value("settings-profiles", &profiles.to_variant()) {
+        error!("Saving `settings-profiles` to settings failed, Err: {e:?}");
+    }
+    if settings.string("active-settings-profile") == name
+        && let Err(e) = settings.set_string("active-settings-profile", "")
+    {
+        error!("Saving `active-settings-profile` to settings failed, Err: {e:?}");
+    }
+}
+
+/// The name of the active profile, `None` if no profile was applied.
+pub(crate) fn active_profile_name(settings: &gio::Settings) -> Option<String> {
+    let name = settings.string("active-settings-profile");
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn saved_profiles(settings: &gio::Settings) -> BTreeMap<String, String> {
+    settings
+        .value("settings-profiles")
+        .get::<BTreeMap<String, String>>()
+        .unwrap_or_default()
+}
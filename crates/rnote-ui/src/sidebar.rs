@@ -1,5 +1,5 @@
 // Imports
-use crate::{RnAppMenu, RnAppWindow, RnSettingsPanel, RnWorkspaceBrowser};
+use crate::{RnAppMenu, RnAppWindow, RnLayersPanel, RnSettingsPanel, RnWorkspaceBrowser};
 use gtk4::{
     Button, CompositeTemplate, Widget, glib, glib::clone, prelude::*, subclass::prelude::*,
 };
@@ -23,6 +23,8 @@ mod imp {
         #[template_child]
         pub(crate) workspacebrowser: TemplateChild<RnWorkspaceBrowser>,
         #[template_child]
+        pub(crate) layers_panel: TemplateChild<RnLayersPanel>,
+        #[template_child]
         pub(crate) settings_panel: TemplateChild<RnSettingsPanel>,
     }
 
@@ -98,6 +100,10 @@ impl RnSidebar {
         self.imp().workspacebrowser.get()
     }
 
+    pub(crate) fn layers_panel(&self) -> RnLayersPanel {
+        self.imp().layers_panel.get()
+    }
+
     pub(crate) fn settings_panel(&self) -> RnSettingsPanel {
         self.imp().settings_panel.get()
     }
@@ -107,6 +113,7 @@ impl RnSidebar {
 
         imp.appmenu.get().init(appwindow);
         imp.workspacebrowser.get().init(appwindow);
+        imp.layers_panel.get().init(appwindow);
         imp.settings_panel.get().init(appwindow);
 
         imp.left_close_button.connect_clicked(clone!(
@@ -19,6 +19,7 @@ mod imp {
     pub(crate) struct StrokeContentPaintable {
         pub(super) paint_max_width: Cell<f64>,
         pub(super) paint_max_height: Cell<f64>,
+        pub(super) paint_scale: Cell<f64>,
         pub(super) paint_cache: RefCell<Option<Image>>,
         pub(super) paint_cache_texture: RefCell<Option<gdk::MemoryTexture>>,
         pub(super) draw_background: Cell<bool>,
@@ -53,6 +54,9 @@ mod imp {
                     glib::ParamSpecDouble::builder("paint-max-height")
                         .default_value(1000.)
                         .build(),
+                    glib::ParamSpecDouble::builder("paint-scale")
+                        .default_value(1.)
+                        .build(),
                     glib::ParamSpecBoolean::builder("draw-background")
                         .default_value(true)
                         .build(),
@@ -74,6 +78,7 @@ mod imp {
             match pspec.name() {
                 "paint-max-width" => self.paint_max_width.get().to_value(),
                 "paint-max-height" => self.paint_max_height.get().to_value(),
+                "paint-scale" => self.paint_scale.get().to_value(),
                 "draw-background" => self.draw_background.get().to_value(),
                 "draw-pattern" => self.draw_pattern.get().to_value(),
                 "optimize-printing" => self.optimize_printing.get().to_value(),
@@ -98,6 +103,13 @@ mod imp {
                     self.paint_max_height.replace(paint_max_height.max(0.0));
                     self.obj().repaint_cache_async();
                 }
+                "paint-scale" => {
+                    let paint_scale = value
+                        .get::<f64>()
+                        .expect("The value needs to be of type `f64`");
+                    self.paint_scale.replace(paint_scale.max(0.0));
+                    self.obj().repaint_cache_async();
+                }
                 "draw-background" => {
                     let draw_background = value
                         .get::<bool>()
@@ -139,6 +151,7 @@ mod imp {
             // TODO: fix it
             obj.set_paint_max_width(1000.);
             obj.set_paint_max_height(1000.);
+            obj.set_paint_scale(1.);
             let (tx, mut rx) = futures::channel::mpsc::unbounded::<anyhow::Result<Image>>();
             self.paint_task_tx.set(tx).unwrap();
 
@@ -354,6 +367,19 @@ impl StrokeContentPaintable {
         }
     }
 
+    #[allow(unused)]
+    pub(crate) fn paint_scale(&self) -> f64 {
+        self.property::<f64>("paint-scale")
+    }
+
+    /// The scale of the painted cache relative to the intrinsic size, still limited by the maximum paint size.
+    #[allow(unused)]
+    pub(crate) fn set_paint_scale(&self, paint_scale: f64) {
+        if self.imp().paint_scale.get() != paint_scale {
+            self.set_property("paint-scale", paint_scale.to_value());
+        }
+    }
+
     #[allow(unused)]
     pub(crate) fn draw_background(&self) -> bool {
         self.property::<bool>("draw-background")
@@ -412,9 +438,10 @@ impl StrokeContentPaintable {
     /// Regenerates the paint cache.
     #[allow(unused)]
     pub(crate) fn repaint_cache(&self) {
+        let paint_scale = self.imp().paint_scale.get();
         let (width, height) = (
-            (self.intrinsic_width() as f64).min(self.imp().paint_max_width.get()),
-            (self.intrinsic_height() as f64).min(self.imp().paint_max_height.get()),
+            (self.intrinsic_width() as f64 * paint_scale).min(self.imp().paint_max_width.get()),
+            (self.intrinsic_height() as f64 * paint_scale).min(self.imp().paint_max_height.get()),
         );
         if width <= 0. && height <= 0. {
             return;
@@ -456,9 +483,10 @@ impl StrokeContentPaintable {
     /// Regenerates the paint cache asynchronously.
     #[allow(unused)]
     pub(crate) fn repaint_cache_async(&self) {
+        let paint_scale = self.imp().paint_scale.get();
         let (width, height) = (
-            (self.intrinsic_width() as f64).min(self.imp().paint_max_width.get()),
-            (self.intrinsic_height() as f64).min(self.imp().paint_max_height.get()),
+            (self.intrinsic_width() as f64 * paint_scale).min(self.imp().paint_max_width.get()),
+            (self.intrinsic_height() as f64 * paint_scale).min(self.imp().paint_max_height.get()),
         );
         if width <= 0. && height <= 0. {
             return;
@@ -498,9 +526,10 @@ impl StrokeContentPaintable {
     #[allow(unused)]
     pub(crate) fn repaint_cache_w_timeout(&self) {
         const TIMEOUT: Duration = Duration::from_millis(500);
+        let paint_scale = self.imp().paint_scale.get();
         let (width, height) = (
-            (self.intrinsic_width() as f64).min(self.imp().paint_max_width.get()),
-            (self.intrinsic_height() as f64).min(self.imp().paint_max_height.get()),
+            (self.intrinsic_width() as f64 * paint_scale).min(self.imp().paint_max_width.get()),
+            (self.intrinsic_height() as f64 * paint_scale).min(self.imp().paint_max_height.get()),
         );
         if width <= 0. && height <= 0. {
             return;
@@ -1,7 +1,9 @@
 // Imports
 use crate::StrokeContentPaintable;
+use gettextrs::gettext;
 use gtk4::{
-    Button, CompositeTemplate, Entry, Overlay, Picture, ProgressBar, ScrolledWindow, Widget, glib,
+    Button, CompositeTemplate, Entry, EventControllerScroll, EventControllerScrollFlags, Label,
+    Overlay, Picture, ProgressBar, PropagationPhase, ScrolledWindow, Widget, gdk, glib,
     glib::clone, prelude::*, subclass::prelude::*,
 };
 use once_cell::sync::Lazy;
@@ -17,6 +19,8 @@ mod imp {
         pub(crate) contents: RefCell<Vec<StrokeContent>>,
         pub(crate) paintable: StrokeContentPaintable,
         pub(crate) current_page: Cell<usize>,
+        /// The zoom of the preview. Fits the content into the preview when None.
+        pub(crate) zoom: Cell<Option<f64>>,
         pub(crate) progresspulse_id: RefCell<Option<glib::SourceId>>,
 
         #[template_child]
@@ -37,6 +41,16 @@ mod imp {
         pub(crate) prev_page_button: TemplateChild<Button>,
         #[template_child]
         pub(crate) next_page_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) zoom_controls_box: TemplateChild<gtk4::Box>,
+        #[template_child]
+        pub(crate) info_label: TemplateChild<Label>,
+        #[template_child]
+        pub(crate) zoom_out_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) zoom_reset_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) zoom_in_button: TemplateChild<Button>,
     }
 
     #[glib::object_subclass]
@@ -62,6 +76,9 @@ mod imp {
             self.preview_picture.set_paintable(Some(&self.paintable));
             self.preview_overlay
                 .set_measure_overlay(&*self.pages_controls_box, true);
+            self.preview_overlay
+                .set_measure_overlay(&*self.zoom_controls_box, true);
+            self.apply_zoom();
 
             self.paintable.connect_local(
                 "repaint-in-progress",
@@ -138,6 +155,59 @@ mod imp {
                         .set_current_page(current_page.saturating_add(1).min(n_pages - 1));
                 }
             ));
+
+            self.zoom_out_button.connect_clicked(clone!(
+                #[weak(rename_to=strokecontentpreview)]
+                obj,
+                move |_| {
+                    strokecontentpreview.zoom_by(1. / super::RnStrokeContentPreview::ZOOM_STEP);
+                }
+            ));
+
+            self.zoom_reset_button.connect_clicked(clone!(
+                #[weak(rename_to=strokecontentpreview)]
+                obj,
+                move |_| {
+                    strokecontentpreview.set_zoom(None);
+                }
+            ));
+
+            self.zoom_in_button.connect_clicked(clone!(
+                #[weak(rename_to=strokecontentpreview)]
+                obj,
+                move |_| {
+                    strokecontentpreview.zoom_by(super::RnStrokeContentPreview::ZOOM_STEP);
+                }
+            ));
+
+            // Zoom with ctrl + scroll
+            let zoom_scroll_controller = EventControllerScroll::builder()
+                .name("zoom_scroll_controller")
+                .flags(EventControllerScrollFlags::VERTICAL)
+                .propagation_phase(PropagationPhase::Capture)
+                .build();
+            zoom_scroll_controller.connect_scroll(clone!(
+                #[weak(rename_to=strokecontentpreview)]
+                obj,
+                #[upgrade_or]
+                glib::Propagation::Proceed,
+                move |controller, _dx, dy| {
+                    if !controller
+                        .current_event_state()
+                        .contains(gdk::ModifierType::CONTROL_MASK)
+                    {
+                        return glib::Propagation::Proceed;
+                    }
+                    let factor = if dy < 0. {
+                        super::RnStrokeContentPreview::ZOOM_STEP
+                    } else {
+                        1. / super::RnStrokeContentPreview::ZOOM_STEP
+                    };
+                    strokecontentpreview.zoom_by(factor);
+                    glib::Propagation::Stop
+                }
+            ));
+            self.preview_scroller.add_controller(zoom_scroll_controller);
         }
 
         fn dispose(&self) {
@@ -194,6 +264,8 @@ mod imp {
             );
 
             self.pages_controls_box.set_visible(n_pages > 1);
+            // Pages might differ in size
+            self.apply_zoom();
             // the prev/next page buttons sensitivity get updated in the paintable `repaint-in-progress` signal handler.
             match parse_page_text(&self.page_entry.text(), n_pages) {
                 Ok(page) if page == current_page => {
@@ -208,6 +280,38 @@ mod imp {
         }
     }
 
+    impl RnStrokeContentPreview {
+        /// Apply the zoom to the size of the picture and the resolution the content is painted in.
+        pub(super) fn apply_zoom(&self) {
+            match self.zoom.get() {
+                Some(zoom) => {
+                    self.preview_picture.set_size_request(
+                        (self.paintable.intrinsic_width() as f64 * zoom).round() as i32,
+                        (self.paintable.intrinsic_height() as f64 * zoom).round() as i32,
+                    );
+                    self.paintable
+                        .set_paint_max_width(super::RnStrokeContentPreview::PAINT_MAX_SIZE_ZOOMED);
+                    self.paintable
+                        .set_paint_max_height(super::RnStrokeContentPreview::PAINT_MAX_SIZE_ZOOMED);
+                    self.paintable.set_paint_scale(zoom);
+                    self.zoom_reset_button
+                        .set_label(&format!("{:.0}%", (zoom * 100.).round()));
+                }
+                None => {
+                    self.preview_picture.set_size_request(-1, -1);
+                    self.paintable
+                        .set_paint_max_width(super::RnStrokeContentPreview::PAINT_MAX_SIZE);
+                    self.paintable
+                        .set_paint_max_height(super::RnStrokeContentPreview::PAINT_MAX_SIZE);
+                    self.paintable.set_paint_scale(1.);
+                    self.zoom_reset_button.set_label(&gettext("Fit"));
+                }
+            }
+            self.zoom_reset_button
+                .set_sensitive(self.zoom.get().is_some());
+        }
+    }
+
     fn parse_page_text(text: &str, n_pages: usize) -> anyhow::Result<usize> {
         // user facing page number is 1 indexed
         let page_range = 1..=n_pages;
@@ -236,10 +340,63 @@ impl Default for RnStrokeContentPreview {
 }
 
 impl RnStrokeContentPreview {
+    /// The maximum size of the painted content when it fits into the preview.
+    const PAINT_MAX_SIZE: f64 = 1000.;
+    /// The maximum size of the painted content when zoomed.
+    const PAINT_MAX_SIZE_ZOOMED: f64 = 4000.;
+    const ZOOM_MIN: f64 = 0.1;
+    const ZOOM_MAX: f64 = 4.0;
+    /// The factor the zoom is changed by when zooming in or out.
+    const ZOOM_STEP: f64 = 1.25;
+
     pub(crate) fn new() -> Self {
         glib::Object::new()
     }
 
+    /// The zoom of the preview. None when the content is fit into the preview.
+    #[allow(unused)]
+    pub(crate) fn zoom(&self) -> Option<f64> {
+        self.imp().zoom.get()
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_zoom(&self, zoom: Option<f64>) {
+        self.imp()
+            .zoom
+            .set(zoom.map(|z| z.clamp(Self::ZOOM_MIN, Self::ZOOM_MAX)));
+        self.imp().apply_zoom();
+    }
+
+    /// Change the zoom by the factor, starting from the current size of the content when it is fit into the preview.
+    pub(crate) fn zoom_by(&self, factor: f64) {
+        let imp = self.imp();
+        let current_zoom = imp.zoom.get().unwrap_or_else(|| {
+            let intrinsic_width = imp.paintable.intrinsic_width();
+            if intrinsic_width > 0 && imp.preview_picture.width() > 0 {
+                imp.preview_picture.width() as f64 / intrinsic_width as f64
+            } else {
+                1.
+            }
+        });
+        self.set_zoom(Some(current_zoom * factor));
+    }
+
+    /// The size of the content of the current page.
+    pub(crate) fn current_content_size(&self) -> Option<na::Vector2<f64>> {
+        self.imp()
+            .contents
+            .borrow()
+            .get(self.current_page())
+            .and_then(|content| content.size())
+    }
+
+    /// Show an info text over the preview, e.g. the size of the output.
+    pub(crate) fn set_info_text(&self, text: Option<&str>) {
+        let info_label = &self.imp().info_label;
+        info_label.set_label(text.unwrap_or_default());
+        info_label.set_visible(text.is_some());
+    }
+
     #[allow(unused)]
     pub(crate) fn current_page(&self) -> usize {
         self.property::<u32>("current-page") as usize
@@ -2,13 +2,14 @@
 mod actions;
 
 // Imports
-use crate::RnAppWindow;
+use crate::{RnAppWindow, RnWorkspaceBrowser};
 use gtk4::{
     CompositeTemplate, DragSource, GestureClick, GestureLongPress, Image, Label, MenuButton,
     PopoverMenu, Widget, gdk, gio, glib, glib::clone, prelude::*, subclass::prelude::*,
 };
 use once_cell::sync::Lazy;
 use std::cell::{Cell, RefCell};
+use tracing::debug;
 
 mod imp {
     use super::*;
@@ -178,7 +179,6 @@ impl RnFileRow {
         glib::Object::new()
     }
 
-    #[allow(unused)]
     pub(crate) fn current_file(&self) -> Option<gio::File> {
         self.property::<Option<gio::File>>("current-file")
     }
@@ -231,6 +231,61 @@ impl RnFileRow {
                 }
             }
         ));
+
+        self.connect_notify_local(
+            Some("current-file"),
+            clone!(
+                #[weak]
+                appwindow,
+                move |filerow, _| {
+                    filerow.update_thumbnail(&appwindow.sidebar().workspacebrowser());
+                }
+            ),
+        );
+    }
+
+    /// Replace the file icon with a thumbnail for `.rnote` files, when thumbnails are enabled.
+    fn update_thumbnail(&self, workspacebrowser: &RnWorkspaceBrowser) {
+        // Reset to the regular icon size, the icon itself is set through the expression binding.
+        self.imp().file_image.set_pixel_size(-1);
+
+        let Some(file) = self.current_file() else {
+            return;
+        };
+        if !workspacebrowser.show_thumbnails()
+            || !file
+                .path()
+                .is_some_and(|path| path.extension().is_some_and(|ext| ext == "rnote"))
+        {
+            return;
+        }
+
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to=filerow)]
+            self,
+            #[weak]
+            workspacebrowser,
+            async move {
+                let thumbnail = match workspacebrowser.rnote_file_thumbnail(&file).await {
+                    Ok(thumbnail) => thumbnail,
+                    Err(e) => {
+                        debug!(
+                            "Loading thumbnail for file {:?} failed, Err: {e:?}",
+                            file.path()
+                        );
+                        return;
+                    }
+                };
+                // The row might have been bound to a different file in the meantime.
+                if filerow.current_file().is_some_and(|f| f.equal(&file)) {
+                    filerow.imp().file_image.set_paintable(Some(&thumbnail));
+                    filerow
+                        .imp()
+                        .file_image
+                        .set_pixel_size(RnWorkspaceBrowser::THUMBNAIL_SIZE);
+                }
+            }
+        ));
     }
 
     fn setup_actions(&self, appwindow: &RnAppWindow) {
@@ -11,15 +11,23 @@ pub(crate) use workspacesbar::RnWorkspacesBar;
 
 // Imports
 use crate::appwindow::RnAppWindow;
+use adw::prelude::*;
+use anyhow::Context;
+use gettextrs::gettext;
 use gtk4::{
     Button, CompositeTemplate, ConstantExpression, CustomFilter, CustomSorter, DirectoryList,
-    FileFilter, FilterChange, FilterListModel, Grid, Label, ListItem, ListView, MultiSorter,
-    PropertyExpression, ScrolledWindow, Separator, SignalListItemFactory, SingleSelection,
-    SortListModel, SorterChange, Widget, gdk, gio, glib, glib::clone, glib::closure, prelude::*,
-    subclass::prelude::*,
+    FileFilter, FilterChange, FilterListModel, Grid, Label, ListBox, ListItem, ListView,
+    MultiSorter, Popover, PropertyExpression, ScrolledWindow, SearchEntry, Separator,
+    SignalListItemFactory, SingleSelection, SortListModel, SorterChange, Widget, gdk, gio, glib,
+    glib::clone, glib::closure, subclass::prelude::*,
 };
-use std::path::PathBuf;
-use tracing::warn;
+use rnote_engine::Engine;
+use rnote_engine::engine::EngineSnapshot;
+use rnote_engine::fileformats::rnoteformat::encryption;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::{error, warn};
 
 mod imp {
     use super::*;
@@ -30,6 +38,12 @@ mod imp {
         pub(crate) action_group: gio::SimpleActionGroup,
         pub(crate) dir_list: DirectoryList,
         pub(crate) list_selection_model: SingleSelection,
+        pub(crate) files_search_filter: CustomFilter,
+        pub(crate) pinned_folders: RefCell<Vec<PathBuf>>,
+        pub(crate) recent_files: RefCell<Vec<PathBuf>>,
+        pub(crate) show_thumbnails: Cell<bool>,
+        /// The cached thumbnails of `.rnote` files, together with the modification time of the file.
+        pub(crate) thumbnails: RefCell<HashMap<PathBuf, (Option<glib::DateTime>, gdk::Texture)>>,
 
         #[template_child]
         pub(crate) grid: TemplateChild<Grid>,
@@ -38,6 +52,8 @@ mod imp {
         #[template_child]
         pub(crate) corner_filler: TemplateChild<Separator>,
         #[template_child]
+        pub(crate) files_search_entry: TemplateChild<SearchEntry>,
+        #[template_child]
         pub(crate) files_scroller: TemplateChild<ScrolledWindow>,
         #[template_child]
         pub(crate) files_listview: TemplateChild<ListView>,
@@ -50,6 +66,12 @@ mod imp {
         #[template_child]
         pub(crate) dir_controls_actions_box: TemplateChild<gtk4::Box>,
         #[template_child]
+        pub(crate) places_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) pinned_folders_listbox: TemplateChild<ListBox>,
+        #[template_child]
+        pub(crate) recent_files_listbox: TemplateChild<ListBox>,
+        #[template_child]
         pub(crate) workspacesbar: TemplateChild<RnWorkspacesBar>,
     }
 
@@ -62,16 +84,25 @@ mod imp {
                 action_group: gio::SimpleActionGroup::new(),
                 dir_list,
                 list_selection_model: SingleSelection::default(),
+                files_search_filter: CustomFilter::default(),
+                pinned_folders: RefCell::new(Vec::new()),
+                recent_files: RefCell::new(Vec::new()),
+                show_thumbnails: Cell::new(true),
+                thumbnails: RefCell::new(HashMap::new()),
 
                 grid: TemplateChild::<Grid>::default(),
                 dir_box: TemplateChild::<gtk4::Box>::default(),
                 corner_filler: TemplateChild::<Separator>::default(),
+                files_search_entry: TemplateChild::<SearchEntry>::default(),
                 files_scroller: TemplateChild::<ScrolledWindow>::default(),
                 files_listview: TemplateChild::<ListView>::default(),
                 active_workspace_name_label: TemplateChild::<Label>::default(),
                 active_workspace_dir_label: TemplateChild::<Label>::default(),
                 dir_controls_dir_up_button: TemplateChild::<Button>::default(),
                 dir_controls_actions_box: TemplateChild::<gtk4::Box>::default(),
+                places_popover: TemplateChild::<Popover>::default(),
+                pinned_folders_listbox: TemplateChild::<ListBox>::default(),
+                recent_files_listbox: TemplateChild::<ListBox>::default(),
                 workspacesbar: TemplateChild::<RnWorkspacesBar>::default(),
             }
         }
@@ -124,6 +155,11 @@ impl Default for RnWorkspaceBrowser {
 }
 
 impl RnWorkspaceBrowser {
+    /// The maximum number of entries in the recent files list.
+    pub(crate) const RECENT_FILES_MAX: usize = 12;
+    /// The width and height of the `.rnote` file thumbnails in the files list.
+    pub(crate) const THUMBNAIL_SIZE: i32 = 48;
+
     pub(crate) fn new() -> Self {
         glib::Object::new()
     }
@@ -165,9 +201,159 @@ impl RnWorkspaceBrowser {
 
         self.setup_dir_controls(appwindow);
         self.setup_files_list(appwindow);
+        self.setup_places(appwindow);
         self.setup_actions(appwindow);
     }
 
+    pub(crate) fn load_from_settings(&self, settings: &gio::Settings) {
+        let imp = self.imp();
+        imp.pinned_folders.replace(
+            settings
+                .strv("workspacebrowser-pinned-folders")
+                .iter()
+                .map(|dir| PathBuf::from(dir.as_str()))
+                .collect(),
+        );
+        imp.recent_files.replace(
+            settings
+                .strv("workspacebrowser-recent-files")
+                .iter()
+                .map(|file| PathBuf::from(file.as_str()))
+                .collect(),
+        );
+        imp.action_group.change_action_state(
+            "show-thumbnails",
+            &settings
+                .boolean("workspacebrowser-show-thumbnails")
+                .to_variant(),
+        );
+    }
+
+    pub(crate) fn save_to_settings(&self, settings: &gio::Settings) {
+        let imp = self.imp();
+        let paths_to_strv = |paths: &[PathBuf]| {
+            paths
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect::<Vec<String>>()
+        };
+
+        if let Err(e) = settings.set_strv(
+            "workspacebrowser-pinned-folders",
+            paths_to_strv(&imp.pinned_folders.borrow()),
+        ) {
+            error!("Saving `workspacebrowser-pinned-folders` to settings failed, Err: {e:?}");
+        }
+        if let Err(e) = settings.set_strv(
+            "workspacebrowser-recent-files",
+            paths_to_strv(&imp.recent_files.borrow()),
+        ) {
+            error!("Saving `workspacebrowser-recent-files` to settings failed, Err: {e:?}");
+        }
+        if let Err(e) = settings.set_boolean(
+            "workspacebrowser-show-thumbnails",
+            imp.show_thumbnails.get(),
+        ) {
+            error!("Saving `workspacebrowser-show-thumbnails` to settings failed, Err: {e:?}");
+        }
+    }
+
+    /// Add the file to the front of the recent files, removing duplicates.
+    pub(crate) fn push_recent_file(&self, path: PathBuf) {
+        let mut recent_files = self.imp().recent_files.borrow_mut();
+        recent_files.retain(|file| !crate::utils::paths_abs_eq(file, &path).unwrap_or(false));
+        recent_files.insert(0, path);
+        recent_files.truncate(Self::RECENT_FILES_MAX);
+    }
+
+    pub(crate) fn clear_recent_files(&self) {
+        self.imp().recent_files.borrow_mut().clear();
+        self.refresh_places_ui();
+    }
+
+    /// Pin the folder. Returns false if it is already pinned.
+    pub(crate) fn pin_folder(&self, dir: PathBuf) -> bool {
+        let mut pinned_folders = self.imp().pinned_folders.borrow_mut();
+        if pinned_folders
+            .iter()
+            .any(|pinned| crate::utils::paths_abs_eq(pinned, &dir).unwrap_or(false))
+        {
+            return false;
+        }
+        pinned_folders.push(dir);
+        true
+    }
+
+    pub(crate) fn unpin_folder(&self, dir: &Path) {
+        self.imp()
+            .pinned_folders
+            .borrow_mut()
+            .retain(|pinned| pinned != dir);
+        self.refresh_places_ui();
+    }
+
+    pub(crate) fn show_thumbnails(&self) -> bool {
+        self.imp().show_thumbnails.get()
+    }
+
+    /// Set whether thumbnails are shown for `.rnote` files, rebinding the rows of the files list.
+    pub(crate) fn set_show_thumbnails(&self, show_thumbnails: bool) {
+        let imp = self.imp();
+        if imp.show_thumbnails.replace(show_thumbnails) == show_thumbnails {
+            return;
+        }
+        if !show_thumbnails {
+            imp.thumbnails.borrow_mut().clear();
+        }
+        imp.files_listview.set_model(None::<&SingleSelection>);
+        imp.files_listview
+            .set_model(Some(&imp.list_selection_model));
+    }
+
+    /// Load the thumbnail of the first page of a `.rnote` file.
+    ///
+    /// Thumbnails are cached until the file gets modified.
+    pub(crate) async fn rnote_file_thumbnail(
+        &self,
+        file: &gio::File,
+    ) -> anyhow::Result<gdk::Texture> {
+        let path = file.path().context("File has no path.")?;
+        let modified = file
+            .query_info_future(
+                "time::modified",
+                gio::FileQueryInfoFlags::NONE,
+                glib::Priority::LOW,
+            )
+            .await?
+            .modification_date_time();
+        if let Some((cached_modified, texture)) = self.imp().thumbnails.borrow().get(&path)
+            && *cached_modified == modified
+        {
+            return Ok(texture.clone());
+        }
+
+        let (bytes, _) = file.load_bytes_future().await?;
+        if encryption::is_encrypted(&bytes) {
+            return Err(anyhow::anyhow!(
+                "Thumbnails are not generated for encrypted files."
+            ));
+        }
+        let engine_snapshot = EngineSnapshot::load_from_rnote_bytes(bytes.to_vec()).await?;
+        let mut engine = Engine::default();
+        let _ = engine.load_snapshot(engine_snapshot);
+        // Rendered at twice the size to stay sharp on high-dpi displays
+        let thumbnail = engine
+            .generate_doc_thumbnail(2 * Self::THUMBNAIL_SIZE as u32)
+            .await??;
+        let texture = thumbnail.to_memtexture()?.upcast::<gdk::Texture>();
+
+        self.imp()
+            .thumbnails
+            .borrow_mut()
+            .insert(path, (modified, texture.clone()));
+        Ok(texture)
+    }
+
     pub(crate) fn dir_list_file(&self) -> Option<gio::File> {
         self.imp().dir_list.file()
     }
@@ -199,6 +385,15 @@ impl RnWorkspaceBrowser {
         self.imp()
             .action_group
             .add_action(&workspaceactions::open_folder(self, appwindow));
+        self.imp()
+            .action_group
+            .add_action(&workspaceactions::pin_folder(self, appwindow));
+        self.imp()
+            .action_group
+            .add_action(&workspaceactions::clear_recent_files(self));
+        self.imp()
+            .action_group
+            .add_action(&workspaceactions::show_thumbnails(self));
     }
 
     fn setup_dir_controls(&self, _appwindow: &RnAppWindow) {
@@ -233,6 +428,36 @@ impl RnWorkspaceBrowser {
         let notes_sorter = create_notes_sorter();
         let files_sorter = create_files_sorter();
 
+        let files_search_entry = imp.files_search_entry.get();
+        let files_search_filter = imp.files_search_filter.clone();
+        files_search_filter.set_filter_func(clone!(
+            #[weak]
+            files_search_entry,
+            #[upgrade_or]
+            true,
+            move |file| {
+                let query = files_search_entry.text().to_lowercase();
+                if query.is_empty() {
+                    return true;
+                }
+                let fileinfo = file.downcast_ref::<gio::FileInfo>().unwrap();
+                fileinfo
+                    .display_name()
+                    .to_lowercase()
+                    .contains(query.trim())
+            }
+        ));
+        folders_filter.append(files_search_filter.clone());
+        notes_filter.append(files_search_filter.clone());
+        files_filter.append(files_search_filter.clone());
+        files_search_entry.connect_search_changed(clone!(
+            #[weak]
+            files_search_filter,
+            move |_| {
+                files_search_filter.changed(FilterChange::Different);
+            }
+        ));
+
         let folders_list_model = SortListModel::new(
             Some(FilterListModel::new(
                 Some(imp.dir_list.clone()),
@@ -357,6 +582,102 @@ impl RnWorkspaceBrowser {
         ));
     }
 
+    fn setup_places(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+
+        imp.places_popover.connect_show(clone!(
+            #[weak(rename_to=workspacebrowser)]
+            self,
+            move |_| {
+                workspacebrowser.refresh_places_ui();
+            }
+        ));
+
+        imp.pinned_folders_listbox.connect_row_activated(clone!(
+            #[weak(rename_to=workspacebrowser)]
+            self,
+            move |_, row| {
+                let Some(dir) = workspacebrowser
+                    .imp()
+                    .pinned_folders
+                    .borrow()
+                    .get(row.index() as usize)
+                    .cloned()
+                else {
+                    return;
+                };
+                workspacebrowser.imp().places_popover.popdown();
+                workspacebrowser
+                    .workspacesbar()
+                    .set_selected_workspace_dir(dir);
+            }
+        ));
+
+        imp.recent_files_listbox.connect_row_activated(clone!(
+            #[weak(rename_to=workspacebrowser)]
+            self,
+            #[weak]
+            appwindow,
+            move |_, row| {
+                let Some(file) = workspacebrowser
+                    .imp()
+                    .recent_files
+                    .borrow()
+                    .get(row.index() as usize)
+                    .cloned()
+                else {
+                    return;
+                };
+                workspacebrowser.imp().places_popover.popdown();
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        appwindow
+                            .open_file_w_dialogs(gio::File::for_path(file), None, true)
+                            .await;
+                    }
+                ));
+            }
+        ));
+    }
+
+    /// Rebuild the rows of the pinned folders and recent files.
+    ///
+    /// Entries that do not exist anymore are removed.
+    fn refresh_places_ui(&self) {
+        let imp = self.imp();
+        imp.pinned_folders.borrow_mut().retain(|dir| dir.is_dir());
+        imp.recent_files.borrow_mut().retain(|file| file.is_file());
+
+        imp.pinned_folders_listbox.remove_all();
+        for dir in imp.pinned_folders.borrow().iter() {
+            let row = create_place_row(dir);
+            let unpin_button = Button::builder()
+                .icon_name("window-close-symbolic")
+                .tooltip_text(gettext("Unpin Folder"))
+                .valign(gtk4::Align::Center)
+                .build();
+            unpin_button.add_css_class("flat");
+            unpin_button.connect_clicked(clone!(
+                #[weak(rename_to=workspacebrowser)]
+                self,
+                #[strong]
+                dir,
+                move |_| {
+                    workspacebrowser.unpin_folder(&dir);
+                }
+            ));
+            row.add_suffix(&unpin_button);
+            imp.pinned_folders_listbox.append(&row);
+        }
+
+        imp.recent_files_listbox.remove_all();
+        for file in imp.recent_files.borrow().iter() {
+            imp.recent_files_listbox.append(&create_place_row(file));
+        }
+    }
+
     /// Set the selected file in the files list with its position.
     pub(crate) fn files_list_set_selected(&self, position: Option<u32>) {
         self.imp()
@@ -472,6 +793,25 @@ fn create_files_list_row_factory(appwindow: &RnAppWindow) -> SignalListItemFacto
     factory
 }
 
+/// A row for the pinned folders and recent files, showing the name and the parent directory.
+fn create_place_row(path: &Path) -> adw::ActionRow {
+    let title = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string());
+    let subtitle = path
+        .parent()
+        .map(|parent| parent.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    adw::ActionRow::builder()
+        .title(glib::markup_escape_text(&title))
+        .subtitle(glib::markup_escape_text(&subtitle))
+        .subtitle_lines(1)
+        .activatable(true)
+        .build()
+}
+
 fn create_files_list_header_factory(_appwindow: &RnAppWindow) -> SignalListItemFactory {
     let factory = SignalListItemFactory::new();
 
@@ -0,0 +1,18 @@
+// Imports
+use crate::RnWorkspaceBrowser;
+use gtk4::{gio, glib, glib::clone};
+
+/// Create a new `clear_recent_files` action.
+pub(crate) fn clear_recent_files(workspacebrowser: &RnWorkspaceBrowser) -> gio::SimpleAction {
+    let clear_recent_files_action = gio::SimpleAction::new("clear-recent-files", None);
+
+    clear_recent_files_action.connect_activate(clone!(
+        #[weak]
+        workspacebrowser,
+        move |_, _| {
+            workspacebrowser.clear_recent_files();
+        }
+    ));
+
+    clear_recent_files_action
+}
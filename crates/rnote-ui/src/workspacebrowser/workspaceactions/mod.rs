@@ -1,7 +1,13 @@
 // Modules
+mod clearrecentfiles;
 mod createfolder;
 mod openfolder;
+mod pinfolder;
+mod showthumbnails;
 
 // Re-exports
+pub(crate) use clearrecentfiles::clear_recent_files;
 pub(crate) use createfolder::create_folder;
 pub(crate) use openfolder::open_folder;
+pub(crate) use pinfolder::pin_folder;
+pub(crate) use showthumbnails::show_thumbnails;
@@ -0,0 +1,37 @@
+// Imports
+use crate::{RnAppWindow, RnWorkspaceBrowser};
+use gettextrs::gettext;
+use gtk4::{gio, glib, glib::clone};
+
+/// Create a new `pin_folder` action.
+pub(crate) fn pin_folder(
+    workspacebrowser: &RnWorkspaceBrowser,
+    appwindow: &RnAppWindow,
+) -> gio::SimpleAction {
+    let pin_folder_action = gio::SimpleAction::new("pin-folder", None);
+
+    pin_folder_action.connect_activate(clone!(
+        #[weak]
+        workspacebrowser,
+        #[weak]
+        appwindow,
+        move |_, _| {
+            let Some(dir) = workspacebrowser.dir_list_dir() else {
+                return;
+            };
+            if workspacebrowser.pin_folder(dir) {
+                appwindow.overlays().dispatch_toast_text(
+                    &gettext("Pinned folder"),
+                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                );
+            } else {
+                appwindow.overlays().dispatch_toast_text(
+                    &gettext("Folder is already pinned"),
+                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                );
+            }
+        }
+    ));
+
+    pin_folder_action
+}
@@ -0,0 +1,23 @@
+// Imports
+use crate::RnWorkspaceBrowser;
+use gtk4::{gio, glib, glib::clone, prelude::*};
+
+/// Create a new `show_thumbnails` action.
+pub(crate) fn show_thumbnails(workspacebrowser: &RnWorkspaceBrowser) -> gio::SimpleAction {
+    let show_thumbnails_action =
+        gio::SimpleAction::new_stateful("show-thumbnails", None, &true.to_variant());
+
+    show_thumbnails_action.connect_change_state(clone!(
+        #[weak]
+        workspacebrowser,
+        move |action, state_request| {
+            let Some(show_thumbnails) = state_request.and_then(|v| v.get::<bool>()) else {
+                return;
+            };
+            action.set_state(&show_thumbnails.to_variant());
+            workspacebrowser.set_show_thumbnails(show_thumbnails);
+        }
+    ));
+
+    show_thumbnails_action
+}